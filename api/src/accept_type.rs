@@ -1,13 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::bcs_payload;
+use crate::{bcs_payload, bcs_stream, json_stream};
 use poem::{web::Accept, FromRequest, Request, RequestBody, Result};
 
 #[derive(PartialEq)]
 pub enum AcceptType {
     Json,
     Bcs,
+    /// Newline delimited JSON, which endpoints that don't stream their
+    /// responses treat the same as JSON.
+    NdJson,
+    /// BCS with lists as length prefixed items, see `bcs_stream`. Anything
+    /// other than a list is returned as plain BCS.
+    BcsStream,
 }
 
 // This impl allows us to get the data straight from the arguments to the
@@ -24,8 +30,14 @@ impl<'a> FromRequest<'a> for AcceptType {
 /// overriding explicit accept type, default to JSON.
 fn parse_accept(accept: &Accept) -> Result<AcceptType> {
     for mime in &accept.0 {
-        if bcs_payload::CONTENT_TYPE == mime.as_ref() {
-            return Ok(AcceptType::Bcs);
+        if bcs_payload::CONTENT_TYPE == mime.essence_str() {
+            return Ok(match mime.get_param(bcs_stream::STREAM_PARAM) {
+                Some(framing) if framing == bcs_stream::LENGTH_PREFIXED => AcceptType::BcsStream,
+                _ => AcceptType::Bcs,
+            });
+        }
+        if json_stream::CONTENT_TYPE == mime.as_ref() {
+            return Ok(AcceptType::NdJson);
         }
     }
 
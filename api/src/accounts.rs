@@ -4,15 +4,18 @@
 use crate::accept_type::AcceptType;
 use crate::context::Context;
 use crate::failpoint::fail_point_poem;
+use crate::page::Page;
 use crate::response::{
     build_not_found, AptosErrorResponse, BadRequestError, BasicErrorWith404, BasicResponse,
-    BasicResponseStatus, BasicResultWith404, InternalError,
+    BasicResponseStatus, BasicResultWith404, InternalError, NotFoundError,
 };
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    AccountData, Address, AptosErrorCode, AsConverter, LedgerInfo, MoveModuleBytecode,
-    MoveResource, MoveStructTag, TransactionId, U64,
+    AccountBalances, AccountData, AccountDiff, AccountKeyHistory, AccountTransactionQueue, Address,
+    AptosErrorCode, AuthenticationKeyChange, CoinBalance, CoinHolder, CoinHolders, Cursor,
+    EventStream, LedgerInfo, ModifiedResource, MoveModuleBytecode, MoveResource, MoveStructTag,
+    TransactionId, U64,
 };
 use aptos_types::access_path::AccessPath;
 use aptos_types::account_config::AccountResource;
@@ -23,7 +26,7 @@ use aptos_types::state_store::state_key::StateKey;
 use move_deps::move_core_types::value::MoveValue;
 use move_deps::move_core_types::{
     identifier::Identifier,
-    language_storage::{ResourceKey, StructTag},
+    language_storage::{ResourceKey, StructTag, TypeTag, CORE_CODE_ADDRESS},
     move_resource::MoveStructType,
 };
 use poem_openapi::param::Query;
@@ -65,6 +68,10 @@ impl AccountsApi {
     ///
     /// The Aptos nodes prune account state history, via a configurable time window (link).
     /// If the requested data has been pruned, the server responds with a 404.
+    ///
+    /// Resources are listed in the order of their struct tags in storage. When
+    /// `limit` is given and more resources remain, the `X-Aptos-Cursor` header
+    /// holds the cursor from which to request the next page.
     #[oai(
         path = "/accounts/:address/resources",
         method = "get",
@@ -76,10 +83,15 @@ impl AccountsApi {
         accept_type: AcceptType,
         address: Path<Address>,
         ledger_version: Query<Option<U64>>,
+        /// Max number of resources to retrieve, all of them by default
+        limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page
+        cursor: Query<Option<Cursor>>,
     ) -> BasicResultWith404<Vec<MoveResource>> {
         fail_point_poem("endpoint_get_account_resources")?;
         let account = Account::new(self.context.clone(), address.0, ledger_version.0)?;
-        account.resources(&accept_type)
+        account.resources(&accept_type, limit.0, cursor.0)
     }
 
     /// Get account modules
@@ -106,6 +118,229 @@ impl AccountsApi {
         let account = Account::new(self.context.clone(), address.0, ledger_version.0)?;
         account.modules(&accept_type)
     }
+
+    /// Get account diff
+    ///
+    /// Get the net changes to the resources of an account between two
+    /// versions: the resources created, modified and deleted by the
+    /// transactions after `from_version` up to `to_version`, with their
+    /// decoded values. Changes undone before `to_version` are left out. The
+    /// latest ledger version is used if `to_version` is not given.
+    ///
+    /// The changes are found by scanning the write sets of the transactions
+    /// in between, so the two versions may only be as far apart as the node
+    /// allows. Modules are not included.
+    #[oai(
+        path = "/accounts/:address/diff",
+        method = "get",
+        operation_id = "get_account_diff",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_diff(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        /// Version to diff from
+        from_version: Query<U64>,
+        /// Version to diff to, the latest ledger version by default
+        to_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<AccountDiff> {
+        fail_point_poem("endpoint_get_account_diff")?;
+        let account = Account::new(self.context.clone(), address.0, to_version.0)?;
+        account.diff(&accept_type, from_version.0 .0).await
+    }
+
+    /// Get account transaction queue
+    ///
+    /// Compares the sequence number of the account on chain with its
+    /// transactions pending in the mempool of this node, reporting the
+    /// sequence number to give the next transaction, the sequence numbers
+    /// missing from mempool that keep later transactions from being committed,
+    /// and when the first pending transaction expires. Wallets can use this to
+    /// unblock an account whose transactions are stuck.
+    #[oai(
+        path = "/accounts/:address/transactions/next",
+        method = "get",
+        operation_id = "get_account_transaction_queue",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_transaction_queue(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+    ) -> BasicResultWith404<AccountTransactionQueue> {
+        fail_point_poem("endpoint_get_account_transaction_queue")?;
+        let account = Account::new(self.context.clone(), address.0, None)?;
+        account.transaction_queue(&accept_type).await
+    }
+
+    /// Get account key history
+    ///
+    /// Return the authentication keys the account has had up to the ledger
+    /// version, from the one it was created with on, with the versions and
+    /// timestamps of the transactions that set them. Custodians can use this to
+    /// audit the rotations of the key of an account.
+    ///
+    /// The timestamps of transactions the node has pruned are left out, and
+    /// so are the keys it replaced before then, except the one the account was
+    /// created with.
+    ///
+    /// This endpoint is only available on nodes with the internal indexer
+    /// enabled.
+    #[oai(
+        path = "/accounts/:address/key_history",
+        method = "get",
+        operation_id = "get_account_key_history",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_key_history(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<AccountKeyHistory> {
+        fail_point_poem("endpoint_get_account_key_history")?;
+        let account = Account::new(self.context.clone(), address.0, ledger_version.0)?;
+        account.key_history(&accept_type).await
+    }
+
+    /// Get account balances
+    ///
+    /// Get the latest balance of an account in each coin it has registered,
+    /// from the balance index of the node, without reading the resources of
+    /// the account. The response says which version the index is built up
+    /// to, which may trail the latest ledger version.
+    ///
+    /// This endpoint is only available on nodes with the balance index of
+    /// the internal indexer enabled.
+    #[oai(
+        path = "/accounts/:address/balances",
+        method = "get",
+        operation_id = "get_account_balances",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_balances(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+    ) -> BasicResultWith404<AccountBalances> {
+        fail_point_poem("endpoint_get_account_balances")?;
+        self.balances(&accept_type, address.0).await
+    }
+
+    /// Get top coin holders
+    ///
+    /// Get the accounts holding the most of a coin, from the largest balance
+    /// to the smallest, from the balance index of the node. The response says
+    /// which version the index is built up to, which may trail the latest
+    /// ledger version.
+    ///
+    /// This endpoint is only available on nodes with the balance index of
+    /// the internal indexer enabled.
+    #[oai(
+        path = "/coins/:coin_type/top_holders",
+        method = "get",
+        operation_id = "get_top_coin_holders",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_top_coin_holders(
+        &self,
+        accept_type: AcceptType,
+        coin_type: Path<MoveStructTag>,
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<CoinHolders> {
+        fail_point_poem("endpoint_get_top_coin_holders")?;
+        self.top_coin_holders(&accept_type, coin_type.0, limit.0)
+            .await
+    }
+}
+
+impl AccountsApi {
+    fn ensure_balance_index_enabled(&self) -> Result<(), BasicErrorWith404> {
+        if !self.context.balance_index_enabled() {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                "The balance index of the internal indexer is not enabled on this node",
+                AptosErrorCode::IndexerDisabled,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn balances(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+    ) -> BasicResultWith404<AccountBalances> {
+        self.ensure_balance_index_enabled()?;
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let (indexed_version, balances) = self
+            .context
+            .get_coin_balances(address.into())
+            .await
+            .context("Failed to read balances from the balance index")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+
+        BasicResponse::try_from_rust_value((
+            AccountBalances {
+                indexed_version: indexed_version.into(),
+                balances: balances
+                    .into_iter()
+                    .map(|(coin_type, amount)| CoinBalance {
+                        coin_type: coin_type.into(),
+                        amount: amount.into(),
+                    })
+                    .collect(),
+            },
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    async fn top_coin_holders(
+        &self,
+        accept_type: &AcceptType,
+        coin_type: MoveStructTag,
+        limit: Option<u16>,
+    ) -> BasicResultWith404<CoinHolders> {
+        self.ensure_balance_index_enabled()?;
+        let coin_type: StructTag = coin_type
+            .try_into()
+            .context("Failed to parse given coin type")
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
+        let coin_type = TypeTag::Struct(coin_type);
+        let limit = Page::new(None, limit, self.context.max_page_size()).limit()?;
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let (indexed_version, holders) = self
+            .context
+            .get_top_coin_holders(coin_type.clone(), limit)
+            .await
+            .context("Failed to read coin holders from the balance index")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+
+        BasicResponse::try_from_rust_value((
+            CoinHolders {
+                indexed_version: indexed_version.into(),
+                coin_type: coin_type.into(),
+                holders: holders
+                    .into_iter()
+                    .map(|(address, amount)| CoinHolder {
+                        address: address.into(),
+                        amount: amount.into(),
+                    })
+                    .collect(),
+            },
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
 }
 
 pub struct Account {
@@ -130,9 +365,19 @@ impl Account {
             return Err(build_not_found(
                 "ledger",
                 TransactionId::Version(U64::from(ledger_version)),
+                AptosErrorCode::VersionNotFound,
                 latest_ledger_info.version(),
             ));
         }
+        let oldest_version = latest_ledger_info.oldest_ledger_version.0;
+        if ledger_version < oldest_version {
+            return Err(BasicErrorWith404::not_found_with_code(
+                format!("Ledger version {} has been pruned", ledger_version),
+                AptosErrorCode::VersionPruned,
+            )
+            .aptos_ledger_version(latest_ledger_info.version())
+            .detail("oldest_ledger_version", oldest_version));
+        }
 
         Ok(Self {
             context,
@@ -145,24 +390,7 @@ impl Account {
     // These functions map directly to endpoint functions.
 
     pub fn account(self, accept_type: &AcceptType) -> BasicResultWith404<AccountData> {
-        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
-            self.address.into(),
-            AccountResource::struct_tag(),
-        )));
-
-        let state_value = self
-            .context
-            .get_state_value_poem(&state_key, self.ledger_version)?;
-
-        let state_value = match state_value {
-            Some(state_value) => state_value,
-            None => return Err(self.resource_not_found(&AccountResource::struct_tag())),
-        };
-
-        let account_resource: AccountResource = bcs::from_bytes(&state_value)
-            .context("Internal error deserializing response from DB")
-            .map_err(BasicErrorWith404::internal)?;
-        let account_data: AccountData = account_resource.into();
+        let account_data: AccountData = self.account_resource()?.into();
 
         BasicResponse::try_from_rust_value((
             account_data,
@@ -172,16 +400,52 @@ impl Account {
         ))
     }
 
-    pub fn resources(self, accept_type: &AcceptType) -> BasicResultWith404<Vec<MoveResource>> {
+    pub fn resources(
+        self,
+        accept_type: &AcceptType,
+        limit: Option<u16>,
+        cursor: Option<Cursor>,
+    ) -> BasicResultWith404<Vec<MoveResource>> {
+        let limit = limit
+            .map(|limit| Page::new(None, Some(limit), self.context.max_page_size()).limit())
+            .transpose()?;
+        let start_path = cursor
+            .map(Cursor::into_struct_tag)
+            .transpose()
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidCursorParam)
+            })?
+            .map(AccessPath::resource_access_vec);
+
+        // Resources come in the order of their access paths, so the page
+        // starts at the first one whose path isn't before the cursor's.
         let account_state = self.account_state()?;
-        let resources = account_state.get_resources();
+        let mut resources: Vec<_> = account_state
+            .get_resources()
+            .skip_while(|(struct_tag, _)| {
+                start_path.as_ref().map_or(false, |start_path| {
+                    AccessPath::resource_access_vec(struct_tag.clone()) < *start_path
+                })
+            })
+            .collect();
+        let cursor = match limit {
+            Some(limit) if resources.len() > limit as usize => {
+                let cursor = Cursor::struct_tag(resources[limit as usize].0.clone());
+                resources.truncate(limit as usize);
+                Some(cursor)
+            }
+            _ => None,
+        };
+
         let move_resolver = self.context.move_resolver_poem()?;
-        let converted_resources = move_resolver
-            .as_converter(self.context.db.clone())
-            .try_into_resources(resources)
+        let converted_resources = self
+            .context
+            .converter(&move_resolver)
+            .try_into_resources(resources.into_iter())
             .context("Failed to build move resource response from data in DB")
-            .map_err(BasicErrorWith404::internal)
-            .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
 
         BasicResponse::try_from_rust_value((
             converted_resources,
@@ -189,6 +453,7 @@ impl Account {
             BasicResponseStatus::Ok,
             accept_type,
         ))
+        .map(|response| response.with_cursor(cursor))
     }
 
     pub fn modules(self, accept_type: &AcceptType) -> BasicResultWith404<Vec<MoveModuleBytecode>> {
@@ -198,8 +463,12 @@ impl Account {
                 MoveModuleBytecode::new(module)
                     .try_parse_abi()
                     .context("Failed to parse move module ABI")
-                    .map_err(BasicErrorWith404::internal)
-                    .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?,
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?,
             );
         }
         BasicResponse::try_from_rust_value((
@@ -210,14 +479,237 @@ impl Account {
         ))
     }
 
+    pub async fn transaction_queue(
+        self,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<AccountTransactionQueue> {
+        let sequence_number = self.account_resource()?.sequence_number();
+        let pending_txns = self
+            .context
+            .get_pending_account_transactions(self.address.into())
+            .await
+            .context("Failed to get pending transactions from mempool")
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
+        let queue = AccountTransactionQueue::new(
+            sequence_number,
+            pending_txns
+                .iter()
+                .map(|txn| (txn.sequence_number(), txn.expiration_timestamp_secs())),
+        );
+
+        BasicResponse::try_from_rust_value((
+            queue,
+            &self.latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn diff(
+        self,
+        accept_type: &AcceptType,
+        from_version: u64,
+    ) -> BasicResultWith404<AccountDiff> {
+        let to_version = self.ledger_version;
+        if from_version > to_version {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "from_version {} is after to_version {}",
+                    from_version, to_version
+                ),
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+        let max_versions = self.context.max_account_diff_versions();
+        if to_version - from_version > max_versions {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "The versions are {} apart, more than the {} this node diffs across",
+                    to_version - from_version,
+                    max_versions
+                ),
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+        let oldest_version = self.latest_ledger_info.oldest_ledger_version.0;
+        if from_version < oldest_version {
+            return Err(BasicErrorWith404::not_found_with_code(
+                format!("from_version {} has been pruned", from_version),
+                AptosErrorCode::VersionPruned,
+            )
+            .aptos_ledger_version(self.latest_ledger_info.version())
+            .detail("oldest_ledger_version", oldest_version));
+        }
+
+        let changes = self
+            .context
+            .get_account_resource_changes(self.address.into(), from_version, to_version)
+            .await
+            .context("Failed to read the changes to the account from DB")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+
+        let move_resolver = self.context.move_resolver_poem()?;
+        let converter = self.context.converter(&move_resolver);
+        let resource = |struct_tag: &StructTag, bytes: &[u8]| {
+            converter
+                .try_into_resource(struct_tag, bytes)
+                .context("Failed to build move resource response from data in DB")
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(
+                        e,
+                        AptosErrorCode::InvalidBcsInStorageError,
+                    )
+                })
+        };
+        let mut diff = AccountDiff {
+            from_version: from_version.into(),
+            to_version: to_version.into(),
+            created: Vec::new(),
+            modified: Vec::new(),
+            deleted: Vec::new(),
+        };
+        for (struct_tag, before, after) in changes {
+            match (before, after) {
+                (None, Some(after)) => diff.created.push(resource(&struct_tag, &after)?),
+                (Some(before), Some(after)) if before != after => {
+                    diff.modified.push(ModifiedResource {
+                        before: resource(&struct_tag, &before)?,
+                        after: resource(&struct_tag, &after)?,
+                    })
+                }
+                (Some(before), None) => diff.deleted.push(resource(&struct_tag, &before)?),
+                // Created and deleted again, or changed back to its value.
+                _ => {}
+            }
+        }
+
+        BasicResponse::try_from_rust_value((
+            diff,
+            &self.latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn key_history(
+        self,
+        accept_type: &AcceptType,
+    ) -> BasicResultWith404<AccountKeyHistory> {
+        if !self.context.indexer_enabled() {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                "The internal indexer is not enabled on this node",
+                AptosErrorCode::IndexerDisabled,
+            ));
+        }
+        let authentication_keys: Vec<_> = self
+            .context
+            .get_auth_key_history(self.address.into(), self.ledger_version)
+            .await
+            .context("Failed to get authentication key history from DB")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .into_iter()
+            .map(
+                |(version, timestamp, authentication_key)| AuthenticationKeyChange {
+                    version: version.into(),
+                    timestamp: timestamp.map(U64::from),
+                    authentication_key: authentication_key.into(),
+                },
+            )
+            .collect();
+        let creation = authentication_keys
+            .first()
+            .ok_or_else(|| self.account_not_found())?;
+        let history = AccountKeyHistory {
+            creation_version: creation.version,
+            creation_timestamp: creation.timestamp,
+            authentication_keys,
+        };
+
+        BasicResponse::try_from_rust_value((
+            history,
+            &self.latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn event_streams(
+        self,
+        accept_type: &AcceptType,
+        page: Page,
+    ) -> BasicResultWith404<Vec<EventStream>> {
+        let limit = page.limit()?;
+        let num_guids = self.guid_creation_count()?;
+        let start = page.start_option().unwrap_or(0).min(num_guids);
+        let end = num_guids.min(start.saturating_add(limit as u64));
+
+        let mut streams = Vec::new();
+        for creation_number in start..end {
+            let key = EventKey::new(creation_number, self.address.into());
+            let latest_event = self
+                .context
+                .get_events(&key, None, 1, self.ledger_version)
+                .await
+                .context(format!("Failed to find the latest event by key {}", key))
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+                })?
+                .pop();
+            streams.push(EventStream {
+                creation_number: creation_number.into(),
+                key: key.into(),
+                counter: latest_event
+                    .as_ref()
+                    .map_or(0, |event| event.event.sequence_number() + 1)
+                    .into(),
+                latest_sequence_number: latest_event
+                    .as_ref()
+                    .map(|event| event.event.sequence_number().into()),
+                typ: latest_event.map(|event| event.event.type_tag().clone().into()),
+            });
+        }
+        let cursor = (end < num_guids).then(|| Cursor::creation_number(end));
+
+        BasicResponse::try_from_rust_value((
+            streams,
+            &self.latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+        .map(|response| response.with_cursor(cursor))
+    }
+
     // Helpers for processing account state.
 
+    fn account_resource(&self) -> Result<AccountResource, BasicErrorWith404> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            self.address.into(),
+            AccountResource::struct_tag(),
+        )));
+
+        let state_value = self
+            .context
+            .get_state_value_poem(&state_key, self.ledger_version)?
+            .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?;
+
+        bcs::from_bytes(&state_value)
+            .context("Internal error deserializing response from DB")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })
+    }
+
     fn account_state(&self) -> Result<AccountState, BasicErrorWith404> {
         let state = self
             .context
             .get_account_state(self.address.into(), self.ledger_version)
-            .map_err(BasicErrorWith404::internal)
-            .map_err(|e| e.error_code(AptosErrorCode::ReadFromStorageError))?
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
             .ok_or_else(|| self.account_not_found())?;
 
         Ok(state)
@@ -232,6 +724,7 @@ impl Account {
                 "address({}) and ledger version({})",
                 self.address, self.ledger_version
             ),
+            AptosErrorCode::AccountNotFound,
             self.latest_ledger_info.version(),
         )
     }
@@ -243,6 +736,7 @@ impl Account {
                 "address({}), struct tag({}) and ledger version({})",
                 self.address, struct_tag, self.ledger_version
             ),
+            AptosErrorCode::ResourceNotFound,
             self.latest_ledger_info.version(),
         )
     }
@@ -258,12 +752,45 @@ impl Account {
                 "address({}), struct tag({}), field name({}) and ledger version({})",
                 self.address, struct_tag, field_name, self.ledger_version
             ),
+            AptosErrorCode::StructFieldNotFound,
             self.latest_ledger_info.version(),
         )
     }
 
     // Events specific stuff.
 
+    /// The number of GUIDs the account created, as counted by its
+    /// `0x1::guid::Generator`. Event streams are numbered among them.
+    fn guid_creation_count(&self) -> Result<u64, BasicErrorWith404> {
+        // The account resource is checked first, for a missing account to be
+        // reported as such.
+        self.account_resource()?;
+        let struct_tag = StructTag {
+            address: CORE_CODE_ADDRESS,
+            module: Identifier::new("guid").expect("Valid identifier"),
+            name: Identifier::new("Generator").expect("Valid identifier"),
+            type_params: vec![],
+        };
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            self.address.into(),
+            struct_tag,
+        )));
+        match self
+            .context
+            .get_state_value_poem(&state_key, self.ledger_version)?
+        {
+            Some(bytes) => bcs::from_bytes(&bytes)
+                .context("Internal error deserializing the GUID generator from DB")
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(
+                        e,
+                        AptosErrorCode::InvalidBcsInStorageError,
+                    )
+                }),
+            None => Ok(0),
+        }
+    }
+
     pub fn find_event_key(
         &self,
         event_handle: MoveStructTag,
@@ -272,7 +799,9 @@ impl Account {
         let struct_tag: StructTag = event_handle
             .try_into()
             .context("Given event handle was invalid")
-            .map_err(BasicErrorWith404::bad_request)?;
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
 
         let resource = self.find_resource(&struct_tag)?;
 
@@ -284,14 +813,16 @@ impl Account {
         // Serialization should not fail, otherwise it's internal bug
         let event_handle_bytes = bcs::to_bytes(&value)
             .context("Failed to serialize event handle, this is an internal bug")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
         // Deserialization may fail because the bytes are not EventHandle struct type.
         let event_handle: EventHandle = bcs::from_bytes(&event_handle_bytes)
             .context(format!(
                 "Deserialization error, field({}) type is not EventHandle struct",
                 field_name
             ))
-            .map_err(BasicErrorWith404::bad_request)?;
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
         Ok(*event_handle.key())
     }
 
@@ -305,10 +836,10 @@ impl Account {
             .find(|(tag, _data)| tag == struct_tag)
             .ok_or_else(|| self.resource_not_found(struct_tag))?;
         let move_resolver = self.context.move_resolver_poem()?;
-        move_resolver
-            .as_converter(self.context.db.clone())
+        self.context
+            .converter(&move_resolver)
             .move_struct_fields(&typ, data)
             .context("Failed to convert move structs")
-            .map_err(BasicErrorWith404::internal)
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))
     }
 }
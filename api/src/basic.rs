@@ -11,6 +11,7 @@ use crate::context::Context;
 use crate::response::{BasicError, BasicResponse, BasicResponseStatus, BasicResult, InternalError};
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
+use aptos_api_types::AptosErrorCode;
 use poem_openapi::{param::Query, payload::Html, Object, OpenApi};
 use serde::{Deserialize, Serialize};
 use std::ops::Sub;
@@ -79,11 +80,12 @@ impl BasicApi {
                 .sub(Duration::from_secs(duration as u64))
                 .duration_since(UNIX_EPOCH)
                 .context("Failed to determine absolute unix time based on given duration")
-                .map_err(BasicError::internal)?;
+                .map_err(|e| BasicError::internal_with_code(e, AptosErrorCode::InternalError))?;
 
             if timestamp < expectation {
-                return Err(BasicError::internal_str(
+                return Err(BasicError::internal_with_code(
                     "The latest ledger info timestamp is less than the expected timestamp",
+                    AptosErrorCode::HealthCheckFailed,
                 ));
             }
         }
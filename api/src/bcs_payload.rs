@@ -9,7 +9,9 @@
 
 use std::ops::{Deref, DerefMut};
 
-use poem::{http::header, FromRequest, IntoResponse, Request, RequestBody, Response, Result};
+use crate::check_size::read_body_within_limit;
+use anyhow::anyhow;
+use poem::{http::header, IntoResponse, Request, RequestBody, Response, Result};
 use poem_openapi::{
     impl_apirequest_for_payload,
     payload::{ParsePayload, Payload},
@@ -17,12 +19,24 @@ use poem_openapi::{
     types::Type,
     ApiResponse,
 };
+use serde::de::DeserializeOwned;
 
 pub const CONTENT_TYPE: &str = "application/x-bcs";
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Bcs(pub Vec<u8>);
 
+impl Bcs {
+    /// Deserializes the payload as a single BCS value, rejecting any bytes
+    /// left over after it.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> anyhow::Result<T> {
+        bcs::from_bytes(&self.0).map_err(|err| match err {
+            bcs::Error::RemainingInput => anyhow!("Found trailing bytes after the BCS value"),
+            err => err.into(),
+        })
+    }
+}
+
 impl Deref for Bcs {
     type Target = Vec<u8>;
 
@@ -55,8 +69,7 @@ impl ParsePayload for Bcs {
     const IS_REQUIRED: bool = true;
 
     async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
-        let data: Vec<u8> = FromRequest::from_request(request, body).await?;
-        Ok(Self(data))
+        Ok(Self(read_body_within_limit(request, body).await?))
     }
 }
 
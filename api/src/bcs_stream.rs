@@ -0,0 +1,146 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines a Poem payload type for lists of items as a BCS
+//! stream: each item is serialized as BCS and prefixed with its length in
+//! bytes, as a ULEB128, with nothing before the first item or after the last.
+//!
+//! A plain BCS list starts with the number of items, so it can only be
+//! written once all of them have been read, and only parsed once all of it
+//! has been received. Length prefixed items can be written as they are
+//! produced and parsed one at a time, so neither end needs to hold the whole
+//! list in memory. Clients ask for this format with the `stream` parameter
+//! of the BCS content type, see `CONTENT_TYPE`.
+//!
+//! Like a JSON stream, a BCS stream that fails midway is aborted. Since the
+//! items are sent in whole chunks, the body received up to then ends on an
+//! item boundary, so clients must check that the chunked transfer encoding
+//! was properly terminated to tell a truncated list from a complete one.
+
+use crate::json_stream::chunked_body;
+use bytes::Bytes;
+use poem::{http::header, Body, IntoResponse, Response};
+use poem_openapi::{
+    payload::Payload,
+    registry::{MetaSchemaRef, Registry},
+    types::Type,
+};
+use serde::Serialize;
+use std::io;
+use tokio::sync::mpsc;
+
+pub const CONTENT_TYPE: &str = "application/x-bcs; stream=length-prefixed";
+
+/// The parameter of the BCS content type selecting how lists are framed.
+pub const STREAM_PARAM: &str = "stream";
+
+/// The value of `STREAM_PARAM` for length prefixed items.
+pub const LENGTH_PREFIXED: &str = "length-prefixed";
+
+/// A list of items as length prefixed BCS values.
+pub struct BcsStream(Body);
+
+impl BcsStream {
+    /// Serializes all the items up front, for endpoints that build their
+    /// responses in memory anyway.
+    pub fn from_items<T: Serialize>(items: &[T]) -> anyhow::Result<Self> {
+        let mut bytes = vec![];
+        for item in items {
+            write_item(&mut bytes, item)?;
+        }
+        Ok(Self(Body::from_vec(bytes)))
+    }
+
+    /// The size of an item in the stream, including its length prefix.
+    pub fn serialized_size<T: Serialize>(item: &T) -> anyhow::Result<u64> {
+        let size = bcs::serialized_size(item)? as u64;
+        Ok(uleb128_size(size) + size)
+    }
+}
+
+impl Payload for BcsStream {
+    const CONTENT_TYPE: &'static str = CONTENT_TYPE;
+
+    fn schema_ref() -> MetaSchemaRef {
+        Vec::<u8>::schema_ref()
+    }
+
+    #[allow(unused_variables)]
+    fn register(registry: &mut Registry) {
+        Vec::<u8>::register(registry);
+    }
+}
+
+impl IntoResponse for BcsStream {
+    fn into_response(self) -> Response {
+        Response::builder()
+            .header(header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(self.0)
+    }
+}
+
+/// Creates a stream of items, which are sent with the returned sender.
+pub fn bcs_stream() -> (BcsStreamSender, BcsStream) {
+    let (sender, body) = chunked_body();
+    (BcsStreamSender { sender }, BcsStream(body))
+}
+
+/// Sends items to a `BcsStream`, blocking while the client catches up. It is
+/// meant to be driven from a blocking task, e.g. one spawned with
+/// `tokio::task::spawn_blocking`.
+pub struct BcsStreamSender {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl BcsStreamSender {
+    /// Serializes the items and sends them as one chunk. Fails if they can't
+    /// be serialized, or if the client went away, in which case there is no
+    /// point producing more of them.
+    pub fn send<T: Serialize>(&mut self, items: &[T]) -> anyhow::Result<()> {
+        let mut chunk = vec![];
+        for item in items {
+            write_item(&mut chunk, item)?;
+        }
+        self.send_chunk(Ok(chunk.into()))
+    }
+
+    /// Ends the stream once all the items have been sent. There is no
+    /// trailer, the end of the body marks the end of the list.
+    pub fn finish(self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Aborts the stream, since the items could not all be produced.
+    pub fn abort(self, error: anyhow::Error) {
+        let _ = self.send_chunk(Err(io::Error::new(io::ErrorKind::Other, error)));
+    }
+
+    fn send_chunk(&self, chunk: io::Result<Bytes>) -> anyhow::Result<()> {
+        self.sender
+            .blocking_send(chunk)
+            .map_err(|_| anyhow::anyhow!("The client stopped reading the stream"))
+    }
+}
+
+fn write_item<T: Serialize>(out: &mut Vec<u8>, item: &T) -> anyhow::Result<()> {
+    let bytes = bcs::to_bytes(item)?;
+    write_uleb128(out, bytes.len() as u64);
+    out.extend(bytes);
+    Ok(())
+}
+
+/// Writes the value as a ULEB128, the same encoding BCS uses for the lengths
+/// of sequences.
+fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// The size of a ULEB128, as BCS writes lengths.
+pub(crate) fn uleb128_size(value: u64) -> u64 {
+    let bits = 64 - value.leading_zeros() as u64;
+    std::cmp::max(1, (bits + 6) / 7)
+}
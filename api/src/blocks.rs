@@ -9,7 +9,7 @@ use crate::response::{
 };
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
-use aptos_api_types::Block;
+use aptos_api_types::{AptosErrorCode, Block, BlockTransactions, Epoch};
 use poem_openapi::param::{Path, Query};
 use poem_openapi::OpenApi;
 use std::sync::Arc;
@@ -24,6 +24,19 @@ impl BlocksApi {
     ///
     /// This endpoint allows you to get the transactions in a block
     /// and the corresponding block information.
+    ///
+    /// With `with_transactions=summary`, the block includes a summary of each
+    /// of its transactions instead, which is much faster to compute than the
+    /// full transactions.
+    ///
+    /// With `with_fee_stats=true`, the block includes statistics of the fees
+    /// paid by its transactions: the gas used, the gas unit prices and the
+    /// fees burnt.
+    ///
+    /// With `with_proposal=true`, the block includes its proposal: the epoch,
+    /// the round, the proposer, the validators whose votes on the previous
+    /// block it includes and those that failed to propose in the rounds
+    /// before it, resolved against the validator set of its epoch.
     #[oai(
         path = "/blocks/by_height/:block_height",
         method = "get",
@@ -34,13 +47,17 @@ impl BlocksApi {
         &self,
         accept_type: AcceptType,
         block_height: Path<u64>,
-        with_transactions: Query<Option<bool>>,
+        with_transactions: Query<Option<BlockTransactions>>,
+        with_fee_stats: Query<Option<bool>>,
+        with_proposal: Query<Option<bool>>,
     ) -> BasicResultWith404<Block> {
         fail_point_poem("endpoint_get_block_by_height")?;
         self.get_by_height(
             accept_type,
             block_height.0,
-            with_transactions.0.unwrap_or_default(),
+            with_transactions.0.unwrap_or(BlockTransactions::False),
+            with_fee_stats.0.unwrap_or(false),
+            with_proposal.0.unwrap_or(false),
         )
     }
 
@@ -48,6 +65,19 @@ impl BlocksApi {
     ///
     /// This endpoint allows you to get the transactions in a block
     /// and the corresponding block information given a version in the block.
+    ///
+    /// With `with_transactions=summary`, the block includes a summary of each
+    /// of its transactions instead, which is much faster to compute than the
+    /// full transactions.
+    ///
+    /// With `with_fee_stats=true`, the block includes statistics of the fees
+    /// paid by its transactions: the gas used, the gas unit prices and the
+    /// fees burnt.
+    ///
+    /// With `with_proposal=true`, the block includes its proposal: the epoch,
+    /// the round, the proposer, the validators whose votes on the previous
+    /// block it includes and those that failed to propose in the rounds
+    /// before it, resolved against the validator set of its epoch.
     #[oai(
         path = "/blocks/by_version/:version",
         method = "get",
@@ -58,15 +88,39 @@ impl BlocksApi {
         &self,
         accept_type: AcceptType,
         version: Path<u64>,
-        with_transactions: Query<Option<bool>>,
+        with_transactions: Query<Option<BlockTransactions>>,
+        with_fee_stats: Query<Option<bool>>,
+        with_proposal: Query<Option<bool>>,
     ) -> BasicResultWith404<Block> {
         fail_point_poem("endpoint_get_block_by_version")?;
         self.get_by_version(
             accept_type,
             version.0,
-            with_transactions.0.unwrap_or_default(),
+            with_transactions.0.unwrap_or(BlockTransactions::False),
+            with_fee_stats.0.unwrap_or(false),
+            with_proposal.0.unwrap_or(false),
         )
     }
+
+    /// Get epoch
+    ///
+    /// This endpoint allows you to get the boundaries of an epoch: the first
+    /// version and the start timestamp, and once the epoch has ended, the last
+    /// version and the end timestamp.
+    #[oai(
+        path = "/epochs/:epoch",
+        method = "get",
+        operation_id = "get_epoch",
+        tag = "ApiTags::Blocks"
+    )]
+    async fn get_epoch(
+        &self,
+        accept_type: AcceptType,
+        epoch: Path<u64>,
+    ) -> BasicResultWith404<Epoch> {
+        fail_point_poem("endpoint_get_epoch")?;
+        self.get_epoch_by_number(accept_type, epoch.0)
+    }
 }
 
 impl BlocksApi {
@@ -74,15 +128,23 @@ impl BlocksApi {
         &self,
         accept_type: AcceptType,
         block_height: u64,
-        with_transactions: bool,
+        with_transactions: BlockTransactions,
+        with_fee_stats: bool,
+        with_proposal: bool,
     ) -> BasicResultWith404<Block> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
         let latest_version = latest_ledger_info.version();
         let block = self
             .context
-            .get_block_by_height(block_height, latest_version, with_transactions)
+            .get_block_by_height(
+                block_height,
+                latest_version,
+                with_transactions,
+                with_fee_stats,
+                with_proposal,
+            )
             .context("Failed to retrieve block by height")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
 
         BasicResponse::try_from_rust_value((
             block,
@@ -96,15 +158,23 @@ impl BlocksApi {
         &self,
         accept_type: AcceptType,
         version: u64,
-        with_transactions: bool,
+        with_transactions: BlockTransactions,
+        with_fee_stats: bool,
+        with_proposal: bool,
     ) -> BasicResultWith404<Block> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
         let latest_version = latest_ledger_info.version();
         let block = self
             .context
-            .get_block_by_version(version, latest_version, with_transactions)
+            .get_block_by_version(
+                version,
+                latest_version,
+                with_transactions,
+                with_fee_stats,
+                with_proposal,
+            )
             .context("Failed to retrieve block by height")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
 
         BasicResponse::try_from_rust_value((
             block,
@@ -113,4 +183,22 @@ impl BlocksApi {
             &accept_type,
         ))
     }
+
+    fn get_epoch_by_number(
+        &self,
+        accept_type: AcceptType,
+        epoch: u64,
+    ) -> BasicResultWith404<Epoch> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let epoch = self
+            .context
+            .get_epoch(epoch, latest_ledger_info.version())?;
+
+        BasicResponse::try_from_rust_value((
+            epoch,
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            &accept_type,
+        ))
+    }
 }
@@ -1,15 +1,23 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::generate_error_response;
+use crate::response::{AptosErrorResponse, BadRequestError, PayloadTooLargeError};
+use aptos_api_types::AptosErrorCode;
+use aptos_config::config::DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT;
 use hyper::Method;
 use poem::{
     error::SizedLimitError,
     web::headers::{self, HeaderMapExt},
-    Endpoint, Middleware, Request, Result,
+    Body, Endpoint, IntoResponse, Middleware, Request, RequestBody, Result,
 };
+use tokio::io::AsyncReadExt;
 
 /// This middleware confirms that the Content-Length header is set and the
-/// value is within the acceptable range. It only applies to POST requests.
+/// value is within the acceptable range, unless the body is chunked. It only
+/// applies to POST requests.
+/// The limit is also made available to payload extractors as a
+/// [`ContentLengthLimit`], for them to enforce while reading the body.
 pub struct PostSizeLimit {
     max_size: u64,
 }
@@ -31,6 +39,10 @@ impl<E: Endpoint> Middleware<E> for PostSizeLimit {
     }
 }
 
+/// The maximum size of a request body, set by the PostSizeLimit middleware.
+#[derive(Clone, Copy, Debug)]
+pub struct ContentLengthLimit(pub u64);
+
 /// Endpoint for PostSizeLimit middleware.
 pub struct PostSizeLimitEndpoint<E> {
     inner: E,
@@ -41,20 +53,73 @@ pub struct PostSizeLimitEndpoint<E> {
 impl<E: Endpoint> Endpoint for PostSizeLimitEndpoint<E> {
     type Output = E::Output;
 
-    async fn call(&self, req: Request) -> Result<Self::Output> {
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
         if req.method() != Method::POST {
             return self.inner.call(req).await;
         }
 
-        let content_length = req
-            .headers()
-            .typed_get::<headers::ContentLength>()
-            .ok_or(SizedLimitError::MissingContentLength)?;
-
-        if content_length.0 > self.max_size {
-            return Err(SizedLimitError::PayloadTooLarge.into());
+        match req.headers().typed_get::<headers::ContentLength>() {
+            Some(content_length) if content_length.0 > self.max_size => {
+                return Err(SizedLimitError::PayloadTooLarge.into());
+            }
+            Some(_) => {}
+            // A chunked body has no length up front. It is cut off just past the limit, so that
+            // no extractor buffers more than that, and those reading it within the limit reject
+            // it as too large.
+            None if is_chunked(&req) => {
+                let body = req.take_body().into_async_read();
+                req.set_body(Body::from_async_read(
+                    body.take(self.max_size.saturating_add(1)),
+                ));
+            }
+            None => return Err(SizedLimitError::MissingContentLength.into()),
         }
+        req.set_data(ContentLengthLimit(self.max_size));
 
         self.inner.call(req).await
     }
 }
+
+fn is_chunked(req: &Request) -> bool {
+    req.headers()
+        .typed_get::<headers::TransferEncoding>()
+        .map_or(false, |transfer_encoding| transfer_encoding.is_chunked())
+}
+
+// The errors of reading a payload, before the request reaches the endpoint.
+generate_error_response!(PayloadError, (400, BadRequest), (413, PayloadTooLarge));
+
+impl From<PayloadError> for poem::Error {
+    fn from(err: PayloadError) -> Self {
+        poem::Error::from_response(err.into_response())
+    }
+}
+
+/// Reads the body incrementally, rejecting it as soon as it is larger than
+/// the content length limit, rather than buffering all of it first.
+pub async fn read_body_within_limit(request: &Request, body: &mut RequestBody) -> Result<Vec<u8>> {
+    let max_size = request
+        .data::<ContentLengthLimit>()
+        .map_or(DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT, |limit| limit.0);
+    let mut data = Vec::new();
+    body.take()?
+        .into_async_read()
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut data)
+        .await
+        .map_err(|err| {
+            PayloadError::bad_request_with_code(
+                format!("Failed to read the request body: {}", err),
+                AptosErrorCode::InvalidInput,
+            )
+        })?;
+    if data.len() as u64 > max_size {
+        return Err(PayloadError::payload_too_large_with_code(
+            format!("The request body is larger than {} bytes", max_size),
+            AptosErrorCode::PayloadTooLarge,
+        )
+        .detail("max_size", max_size)
+        .into());
+    }
+    Ok(data)
+}
@@ -1,42 +1,155 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::response::{AptosErrorResponse, BasicErrorWith404, InternalError, NotFoundError};
+use crate::faucet::FaucetSequenceNumbers;
+use crate::fee_stats_cache::FeeStatsCache;
+use crate::multisig_sessions::MultisigSessionStore;
+use crate::response::{build_not_found, BasicErrorWith404, InternalError, NotFoundError};
+use crate::simulation_cache::SimulationCache;
 use anyhow::{anyhow, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, Block, BlockInfo, LedgerInfo, TransactionOnChainData,
+    AptosErrorCode, AsConverter, Block, BlockFeeStats, BlockInfo, BlockProposal, BlockTransactions,
+    Epoch, LedgerInfo, ModuleCache, MoveConverter, TransactionOnChainData,
+};
+use aptos_config::config::{
+    ApiConfig, NodeConfig, PersistableConfig, RoleType, SubmissionFilterConfig,
 };
-use aptos_config::config::{NodeConfig, RoleType};
 use aptos_crypto::HashValue;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_logger::{debug, info, warn};
+use aptos_mempool::{
+    EvictionReason, MempoolClientRequest, MempoolClientSender, MempoolSize, SubmissionStatus,
+};
 use aptos_state_view::StateView;
 use aptos_types::account_config::NewBlockEvent;
 use aptos_types::transaction::Transaction;
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
     account_state::AccountState,
     chain_id::ChainId,
-    contract_event::EventWithVersion,
+    contract_event::{EventWithTransactionContext, EventWithVersion},
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
-    transaction::{SignedTransaction, TransactionWithProof, Version},
+    on_chain_config::{access_path_for_config, OnChainConfig, ValidatorSet},
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_value::StateValue,
+        table::{TableHandle, TableInfo},
+    },
+    transaction::{
+        SignedTransaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionWithProof, Version,
+    },
+    validator_verifier::ValidatorVerifier,
+};
+use aptos_vm::{
+    data_cache::{IntoMoveResolver, RemoteStorageOwned},
+    move_vm_ext::MoveResolverExt,
+};
+use arc_swap::ArcSwap;
+use event_notifications::EventNotificationListener;
+use futures::{channel::oneshot, SinkExt, StreamExt};
+use move_deps::move_core_types::language_storage::{ResourceKey, StructTag, TypeTag};
+use std::{
+    cmp::min,
+    collections::{BTreeSet, HashMap},
+    ops::Deref,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
-use futures::{channel::oneshot, SinkExt};
-use std::{collections::HashMap, sync::Arc};
 use storage_interface::{
+    async_db_reader::AsyncDbReader,
     state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView},
-    DbReader, Order,
+    DbReader, Order, TransactionFilter,
 };
+use tokio::{runtime::Handle, sync::watch};
+
+/// The number of most recent versions the commit throughput is measured over.
+const COMMIT_THROUGHPUT_WINDOW: u64 = 10_000;
 
 // Context holds application scope context
 #[derive(Clone)]
 pub struct Context {
     chain_id: ChainId,
     pub db: Arc<dyn DbReader>,
+    // Reads the DB for requests on a dedicated thread pool, rather than on
+    // the threads of the runtime serving the requests.
+    async_db: AsyncDbReader,
     mp_sender: MempoolClientSender,
     node_config: NodeConfig,
+    // Shared by all clones of the Context, so that reloading the API config
+    // applies to every handler.
+    api_config: Arc<ArcSwap<ApiConfig>>,
+    ledger_info_cache: LedgerInfoCache,
+    module_cache: Arc<ModuleCache>,
+    simulation_cache: Arc<SimulationCache>,
+    fee_stats_cache: Arc<FeeStatsCache>,
+    multisig_sessions: Arc<MultisigSessionStore>,
+    faucet_sequence_numbers: Arc<FaucetSequenceNumbers>,
+}
+
+/// The latest LedgerInfo as computed from the DB, along with when that
+/// happened. Building a LedgerInfo takes several DB reads, so rather than
+/// doing that on every request we keep the latest one in a watch channel
+/// shared by all clones of the Context, refreshed whenever a commit
+/// notification arrives.
+#[derive(Clone, Debug)]
+struct CachedLedgerInfo {
+    ledger_info: LedgerInfo,
+    refreshed_at: Instant,
+}
+
+#[derive(Clone)]
+struct LedgerInfoCache {
+    sender: Arc<watch::Sender<Option<CachedLedgerInfo>>>,
+    receiver: watch::Receiver<Option<CachedLedgerInfo>>,
+    // How long a cached LedgerInfo may be served for before we go to the DB
+    // again. This bounds how stale responses can get if we miss (or never
+    // receive) commit notifications. Zero means the cache is never used.
+    max_staleness: Duration,
+}
+
+impl LedgerInfoCache {
+    fn new(max_staleness: Duration) -> Self {
+        let (sender, receiver) = watch::channel(None);
+        Self {
+            sender: Arc::new(sender),
+            receiver,
+            max_staleness,
+        }
+    }
+
+    fn get(&self) -> Option<LedgerInfo> {
+        match &*self.receiver.borrow() {
+            Some(cached) if cached.refreshed_at.elapsed() < self.max_staleness => {
+                Some(cached.ledger_info.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn set(&self, ledger_info: LedgerInfo) {
+        // Never move the cache backwards, e.g. if a request that computed its
+        // LedgerInfo before a refresh finishes after it. The check and the
+        // replacement happen under the channel's lock, so concurrent refreshes
+        // can't interleave between them. Reading the same version again only
+        // marks the cached LedgerInfo as fresh.
+        self.sender.send_if_modified(|cached| match cached {
+            Some(cached) if cached.ledger_info.version() > ledger_info.version() => false,
+            Some(cached) if cached.ledger_info.version() == ledger_info.version() => {
+                cached.refreshed_at = Instant::now();
+                true
+            }
+            _ => {
+                *cached = Some(CachedLedgerInfo {
+                    ledger_info,
+                    refreshed_at: Instant::now(),
+                });
+                true
+            }
+        });
+    }
 }
 
 impl Context {
@@ -48,12 +161,58 @@ impl Context {
     ) -> Self {
         Self {
             chain_id,
+            async_db: AsyncDbReader::new(db.clone(), node_config.api.storage_read_threads),
             db,
             mp_sender,
+            api_config: Arc::new(ArcSwap::from_pointee(node_config.api.clone())),
+            module_cache: Arc::new(ModuleCache::new(
+                node_config.api.module_cache_capacity,
+                node_config.api.module_cache_version_bucket_size,
+            )),
+            simulation_cache: Arc::new(SimulationCache::new(
+                node_config.api.simulation_cache_capacity,
+            )),
+            fee_stats_cache: Arc::new(FeeStatsCache::new(node_config.api.fee_stats_cache_capacity)),
+            multisig_sessions: Arc::new(MultisigSessionStore::new(
+                node_config.api.multisig_session_capacity,
+                node_config.api.multisig_session_ttl_secs,
+            )),
+            faucet_sequence_numbers: Arc::new(FaucetSequenceNumbers::default()),
             node_config,
+            // Without commit notifications we can't know when the cached
+            // LedgerInfo goes stale, so it is disabled until
+            // `with_commit_notifications` is called.
+            ledger_info_cache: LedgerInfoCache::new(Duration::ZERO),
         }
     }
 
+    /// Enables the LedgerInfo cache, refreshing it in the background on the
+    /// given runtime every time the listener reports a commit. The listener
+    /// should be subscribed to an event emitted in every block, e.g. the
+    /// NewBlockEvent.
+    pub fn with_commit_notifications(
+        mut self,
+        runtime_handle: &Handle,
+        mut commit_listener: EventNotificationListener,
+    ) -> Self {
+        self.ledger_info_cache = LedgerInfoCache::new(Duration::from_millis(
+            self.node_config.api.ledger_info_max_staleness_ms,
+        ));
+        let context = self.clone();
+        runtime_handle.spawn(async move {
+            while let Some(notification) = commit_listener.next().await {
+                debug!(
+                    "Refreshing cached ledger info after commit at version {}",
+                    notification.version
+                );
+                if let Err(e) = context.refresh_latest_ledger_info() {
+                    warn!("Failed to refresh cached ledger info: {:#}", e);
+                }
+            }
+        });
+        self
+    }
+
     pub fn move_resolver(&self) -> Result<RemoteStorageOwned<DbStateView>> {
         self.db
             .latest_state_checkpoint_view()
@@ -65,7 +224,31 @@ impl Context {
     ) -> Result<RemoteStorageOwned<DbStateView>, E> {
         self.move_resolver()
             .context("Failed to read latest state checkpoint from DB")
-            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))
+    }
+
+    /// Returns a converter reading state through the resolver, which resolves
+    /// modules through the module cache shared by all requests.
+    pub fn converter<'a, R>(&self, resolver: &'a R) -> MoveConverter<'a, R>
+    where
+        R: MoveResolverExt + Deref<Target = DbStateView>,
+    {
+        resolver
+            .as_converter(self.db.clone())
+            .with_module_cache(self.module_cache.clone(), resolver.version.unwrap_or(0))
+    }
+
+    /// The results of recent simulations, shared by all requests.
+    pub fn simulation_cache(&self) -> &SimulationCache {
+        &self.simulation_cache
+    }
+
+    pub fn multisig_sessions(&self) -> &MultisigSessionStore {
+        &self.multisig_sessions
+    }
+
+    pub fn faucet_sequence_numbers(&self) -> &FaucetSequenceNumbers {
+        &self.faucet_sequence_numbers
     }
 
     pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
@@ -80,12 +263,159 @@ impl Context {
         self.node_config.base.role
     }
 
+    /// The API configuration currently in effect, which changes when it is
+    /// reloaded.
+    pub fn api_config(&self) -> Arc<ApiConfig> {
+        self.api_config.load_full()
+    }
+
+    /// Reloads the API configuration and the mempool submission filter from
+    /// the node config file they were loaded from. Settings only applied when
+    /// the API starts keep their current values until the node restarts.
+    pub async fn reload_config(&self) -> Result<()> {
+        let path = self
+            .api_config()
+            .node_config_path
+            .clone()
+            .ok_or_else(|| format_err!("The node config was not loaded from a file"))?;
+        let node_config = NodeConfig::load_config(&path)
+            .with_context(|| format!("Failed to load node config from {:?}", path))?;
+        self.update_api_config(node_config.api);
+        self.update_submission_filter(node_config.mempool.submission_filter)
+            .await
+            .context("Failed to update the mempool submission filter")
+    }
+
+    /// Replaces the API configuration, apart from the settings only applied
+    /// when the API starts, e.g. its address.
+    pub fn update_api_config(&self, mut api_config: ApiConfig) {
+        fn keep<T: Clone + PartialEq>(
+            name: &'static str,
+            new: &mut T,
+            current: &T,
+            kept: &mut Vec<&'static str>,
+        ) {
+            if new != current {
+                kept.push(name);
+                *new = current.clone();
+            }
+        }
+
+        let current = self.api_config();
+        let mut kept = vec![];
+        keep(
+            "enabled",
+            &mut api_config.enabled,
+            &current.enabled,
+            &mut kept,
+        );
+        keep(
+            "address",
+            &mut api_config.address,
+            &current.address,
+            &mut kept,
+        );
+        keep(
+            "tls_cert_path",
+            &mut api_config.tls_cert_path,
+            &current.tls_cert_path,
+            &mut kept,
+        );
+        keep(
+            "tls_key_path",
+            &mut api_config.tls_key_path,
+            &current.tls_key_path,
+            &mut kept,
+        );
+        keep(
+            "content_length_limit",
+            &mut api_config.content_length_limit,
+            &current.content_length_limit,
+            &mut kept,
+        );
+        keep(
+            "ledger_info_max_staleness_ms",
+            &mut api_config.ledger_info_max_staleness_ms,
+            &current.ledger_info_max_staleness_ms,
+            &mut kept,
+        );
+        keep(
+            "module_cache_capacity",
+            &mut api_config.module_cache_capacity,
+            &current.module_cache_capacity,
+            &mut kept,
+        );
+        keep(
+            "module_cache_version_bucket_size",
+            &mut api_config.module_cache_version_bucket_size,
+            &current.module_cache_version_bucket_size,
+            &mut kept,
+        );
+        keep(
+            "storage_read_threads",
+            &mut api_config.storage_read_threads,
+            &current.storage_read_threads,
+            &mut kept,
+        );
+        api_config.node_config_path = current.node_config_path.clone();
+        if !kept.is_empty() {
+            warn!(
+                "Ignoring changes to API config settings {:?}, which require a restart",
+                kept
+            );
+        }
+        self.api_config.store(Arc::new(api_config));
+        info!("Reloaded API config");
+    }
+
     pub fn content_length_limit(&self) -> u64 {
-        self.node_config.api.content_length_limit()
+        self.api_config.load().content_length_limit()
+    }
+
+    pub fn max_page_size(&self) -> u16 {
+        self.api_config.load().max_page_size
+    }
+
+    /// The largest page of transactions that may be streamed, which is the
+    /// largest page of any kind if streaming large pages isn't enabled.
+    pub fn max_streamed_page_size(&self) -> u16 {
+        let api_config = self.api_config.load();
+        api_config
+            .max_streamed_page_size
+            .unwrap_or(api_config.max_page_size)
+    }
+
+    pub fn max_response_size(&self) -> u64 {
+        self.api_config.load().max_response_size_bytes
+    }
+
+    pub fn max_transaction_filter_scan(&self) -> u64 {
+        self.api_config.load().max_transaction_filter_scan
+    }
+
+    pub fn max_account_diff_versions(&self) -> u64 {
+        self.api_config.load().max_account_diff_versions
+    }
+
+    pub fn wait_by_hash_timeout(&self) -> Duration {
+        Duration::from_millis(self.api_config.load().wait_by_hash_timeout_ms)
+    }
+
+    pub fn wait_by_hash_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.api_config.load().wait_by_hash_poll_interval_ms)
     }
 
     pub fn failpoints_enabled(&self) -> bool {
-        self.node_config.api.failpoints_enabled
+        self.api_config.load().failpoints_enabled
+    }
+
+    pub fn developer_api_enabled(&self) -> bool {
+        self.api_config.load().developer_api_enabled
+    }
+
+    /// The number of transactions mempool holds per account.
+    pub fn mempool_capacity_per_user(&self) -> usize {
+        self.node_config.mempool.capacity_per_user
     }
 
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
@@ -98,6 +428,31 @@ impl Context {
         callback.await?
     }
 
+    /// Replaces the rules of the filter mempool rejects submitted transactions
+    /// by, keeping the current ones if the new ones are invalid.
+    pub async fn update_submission_filter(&self, config: SubmissionFilterConfig) -> Result<()> {
+        let (req_sender, callback) = oneshot::channel();
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::UpdateSubmissionFilter(
+                config, req_sender,
+            ))
+            .await?;
+
+        callback.await?
+    }
+
+    /// The number of transactions in mempool, out of the number it may hold.
+    pub async fn get_mempool_size(&self) -> Result<MempoolSize> {
+        let (req_sender, callback) = oneshot::channel();
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetSize(req_sender))
+            .await?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     // For use from external crates where they don't want to handle
     // the API response error types.
     pub fn get_latest_ledger_info_wrapped(&self) -> anyhow::Result<LedgerInfo> {
@@ -105,23 +460,41 @@ impl Context {
             .map_err(|e| e.into())
     }
 
-    // TODO: Add error codes to these errors.
+    /// Returns the latest LedgerInfo, from the cache if it is fresh enough,
+    /// otherwise from the DB.
     pub fn get_latest_ledger_info<E: InternalError>(&self) -> Result<LedgerInfo, E> {
+        if let Some(ledger_info) = self.ledger_info_cache.get() {
+            return Ok(ledger_info);
+        }
+        let ledger_info = self.get_latest_ledger_info_from_db()?;
+        self.ledger_info_cache.set(ledger_info.clone());
+        Ok(ledger_info)
+    }
+
+    fn refresh_latest_ledger_info(&self) -> anyhow::Result<()> {
+        let ledger_info = self
+            .get_latest_ledger_info_from_db::<crate::response::BasicError>()
+            .map_err(anyhow::Error::from)?;
+        self.ledger_info_cache.set(ledger_info);
+        Ok(())
+    }
+
+    fn get_latest_ledger_info_from_db<E: InternalError>(&self) -> Result<LedgerInfo, E> {
         let maybe_oldest_version = self
             .db
             .get_first_viable_txn_version()
-            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
         let ledger_info = self
             .get_latest_ledger_info_with_signatures()
-            .map_err(E::internal)?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
         let (oldest_version, oldest_block_event) = self
             .db
             .get_next_block_event(maybe_oldest_version)
-            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
         let (_, _, newest_block_event) = self
             .db
             .get_block_info_by_version(ledger_info.ledger_info().version())
-            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
 
         Ok(LedgerInfo::new(
             &self.chain_id(),
@@ -142,6 +515,35 @@ impl Context {
             .get_state_value(state_key)
     }
 
+    /// Reads the state value on the storage read pool, see `get_state_value`.
+    pub async fn get_state_value_async(
+        &self,
+        state_key: StateKey,
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .async_db
+            .get_state_value_by_version(state_key, version)
+            .await?
+            .map(|value| value.bytes))
+    }
+
+    /// Reads the state values of all the keys at once, in the order of the
+    /// keys.
+    pub async fn get_state_values_async(
+        &self,
+        state_keys: Vec<StateKey>,
+        version: u64,
+    ) -> Result<Vec<Option<Vec<u8>>>> {
+        Ok(self
+            .async_db
+            .get_state_values_by_version(state_keys, version)
+            .await?
+            .into_iter()
+            .map(|value| value.map(|value| value.bytes))
+            .collect())
+    }
+
     pub fn get_state_value_poem<E: InternalError>(
         &self,
         state_key: &StateKey,
@@ -149,7 +551,7 @@ impl Context {
     ) -> Result<Option<Vec<u8>>, E> {
         self.get_state_value(state_key, version)
             .context("Failed to retrieve state value")
-            .map_err(|e| E::internal(e).error_code(AptosErrorCode::ReadFromStorageError))
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))
     }
 
     pub fn get_state_values(
@@ -172,10 +574,45 @@ impl Context {
         )
     }
 
+    /// Reads the account state on the storage read pool, see `get_account_state`.
+    pub async fn get_account_state_async(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Option<AccountState>> {
+        self.async_db
+            .read("async_get_account_state", move |db| {
+                AccountState::from_access_paths_and_values(
+                    address,
+                    &db.get_state_values_by_key_prefix(&StateKeyPrefix::from(address), version)?,
+                )
+            })
+            .await
+    }
+
     pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         self.db.get_block_timestamp(version)
     }
 
+    /// The number of transactions committed per second over the last
+    /// `COMMIT_THROUGHPUT_WINDOW` versions, or None if no time passed over
+    /// the versions the node keeps, e.g. right after genesis.
+    pub fn get_commit_throughput(&self, ledger_info: &LedgerInfo) -> Result<Option<f64>> {
+        let end_version = ledger_info.version();
+        let start_version = end_version
+            .saturating_sub(COMMIT_THROUGHPUT_WINDOW)
+            .max(ledger_info.oldest_ledger_version.0);
+        let elapsed_usecs = ledger_info
+            .timestamp()
+            .saturating_sub(self.get_block_timestamp(start_version)?);
+        if elapsed_usecs == 0 {
+            return Ok(None);
+        }
+        Ok(Some(
+            (end_version - start_version) as f64 * 1_000_000.0 / elapsed_usecs as f64,
+        ))
+    }
+
     /// Retrieves information about a block
     pub fn get_block_info(&self, version: u64, ledger_version: u64) -> Result<BlockInfo> {
         let (first_version, last_version, new_block_event) =
@@ -206,21 +643,68 @@ impl Context {
         })
     }
 
+    /// Gets the boundaries of an epoch, from the ledger infos ending it and
+    /// the epoch before it.
+    pub fn get_epoch(&self, epoch: u64, ledger_version: u64) -> Result<Epoch, BasicErrorWith404> {
+        let latest_epoch = self
+            .get_latest_ledger_info_with_signatures()
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ledger_info()
+            .next_block_epoch();
+        if epoch > latest_epoch {
+            return Err(build_not_found(
+                "Epoch",
+                epoch,
+                AptosErrorCode::EpochNotFound,
+                ledger_version,
+            ));
+        }
+
+        // The latest epoch hasn't ended, so there is no ledger info ending it.
+        let ledger_infos = self
+            .db
+            .get_epoch_ending_ledger_infos(epoch.saturating_sub(1), min(epoch + 1, latest_epoch))
+            .context("Failed to retrieve epoch ending ledger infos")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ledger_info_with_sigs;
+        let mut ledger_infos = ledger_infos.iter().map(|li| li.ledger_info());
+        let previous_epoch_ending = if epoch == 0 {
+            None
+        } else {
+            ledger_infos.next()
+        };
+        Ok(Epoch::new(
+            epoch,
+            previous_epoch_ending,
+            ledger_infos.next(),
+        ))
+    }
+
     pub fn get_block_by_height(
         &self,
         height: u64,
         ledger_version: u64,
-        with_transactions: bool,
+        with_transactions: BlockTransactions,
+        with_fee_stats: bool,
+        with_proposal: bool,
     ) -> Result<Block, BasicErrorWith404> {
         let (first_version, last_version, new_block_event) = self
             .db
             .get_block_info_by_height(height)
             .context("Failed to find block")
-            .map_err(BasicErrorWith404::not_found)?;
+            .map_err(|e| {
+                BasicErrorWith404::not_found_with_code(e, AptosErrorCode::BlockNotFound)
+            })?;
 
         self.get_block(
             ledger_version,
             with_transactions,
+            with_fee_stats,
+            with_proposal,
             first_version,
             last_version,
             new_block_event,
@@ -231,17 +715,23 @@ impl Context {
         &self,
         version: u64,
         ledger_version: u64,
-        with_transactions: bool,
+        with_transactions: BlockTransactions,
+        with_fee_stats: bool,
+        with_proposal: bool,
     ) -> Result<Block, BasicErrorWith404> {
         let (first_version, last_version, new_block_event) = self
             .db
             .get_block_info_by_version(version)
             .context("Failed to find block")
-            .map_err(BasicErrorWith404::not_found)?;
+            .map_err(|e| {
+                BasicErrorWith404::not_found_with_code(e, AptosErrorCode::BlockNotFound)
+            })?;
 
         self.get_block(
             ledger_version,
             with_transactions,
+            with_fee_stats,
+            with_proposal,
             first_version,
             last_version,
             new_block_event,
@@ -251,46 +741,103 @@ impl Context {
     fn get_block(
         &self,
         ledger_version: Version,
-        with_transactions: bool,
+        with_transactions: BlockTransactions,
+        with_fee_stats: bool,
+        with_proposal: bool,
         first_version: Version,
         last_version: Version,
         new_block_event: NewBlockEvent,
     ) -> Result<Block, BasicErrorWith404> {
         if last_version > ledger_version {
-            return Err(BasicErrorWith404::not_found(anyhow!("Block not found")));
+            return Err(BasicErrorWith404::not_found_with_code(
+                "Block not found",
+                AptosErrorCode::BlockNotFound,
+            ));
         }
 
-        let (block_hash, timestamp, txns) = if with_transactions {
-            let txns = self
-                .get_transactions(
-                    first_version,
-                    (last_version - first_version + 1) as u16,
-                    ledger_version,
-                )
-                .context("Failed to read raw transactions from storage")
-                .map_err(BasicErrorWith404::internal)
-                .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?;
-
-            // TODO: embed block hash into the NewBlockEvent
-            let (block_hash, timestamp) = if let Some(txn) = txns.first() {
-                get_block_hash_and_timestamp(&txn.transaction, first_version)
-                    .map_err(BasicErrorWith404::internal)?
-            } else {
-                return Err(BasicErrorWith404::internal(anyhow!(
-                    "No transactions found for block"
-                )));
-            };
-            (block_hash, timestamp, Some(txns))
-        } else {
-            let txn = self
-                .get_transaction_by_version(first_version, ledger_version)
-                .context("Failed to read raw transactions from storage")
-                .map_err(BasicErrorWith404::internal)
-                .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?;
-            let (block_hash, timestamp) =
-                get_block_hash_and_timestamp(&txn.transaction, first_version)
-                    .map_err(BasicErrorWith404::internal)?;
-            (block_hash, timestamp, None)
+        let (block_hash, timestamp, txns, summaries) = match with_transactions {
+            BlockTransactions::True => {
+                let txns = self
+                    .get_transactions(
+                        first_version,
+                        (last_version - first_version + 1) as u16,
+                        ledger_version,
+                    )
+                    .context("Failed to read raw transactions from storage")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?;
+
+                // TODO: embed block hash into the NewBlockEvent
+                let (block_hash, timestamp) = if let Some(txn) = txns.first() {
+                    get_block_hash_and_timestamp(&txn.transaction, first_version).map_err(|e| {
+                        BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                    })?
+                } else {
+                    return Err(BasicErrorWith404::internal_with_code(
+                        "No transactions found for block",
+                        AptosErrorCode::InternalError,
+                    ));
+                };
+                (block_hash, timestamp, Some(txns), None)
+            }
+            BlockTransactions::Summary => {
+                // Summaries only need the transactions and their infos, so skip reading
+                // the outputs and decoding the transactions.
+                let txns = self
+                    .db
+                    .get_transactions(
+                        first_version,
+                        last_version - first_version + 1,
+                        ledger_version,
+                        false,
+                    )
+                    .context("Failed to read raw transactions from storage")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?;
+
+                let (block_hash, timestamp) = if let Some(txn) = txns.transactions.first() {
+                    get_block_hash_and_timestamp(txn, first_version).map_err(|e| {
+                        BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                    })?
+                } else {
+                    return Err(BasicErrorWith404::internal_with_code(
+                        "No transactions found for block",
+                        AptosErrorCode::InternalError,
+                    ));
+                };
+                let summaries = txns
+                    .transactions
+                    .iter()
+                    .zip(txns.proof.transaction_infos.iter())
+                    .enumerate()
+                    .map(|(i, (txn, info))| (first_version + i as u64, txn, info).into())
+                    .collect();
+                (block_hash, timestamp, None, Some(summaries))
+            }
+            BlockTransactions::False => {
+                let txn = self
+                    .get_transaction_by_version(first_version, ledger_version)
+                    .context("Failed to read raw transactions from storage")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?;
+                let (block_hash, timestamp) =
+                    get_block_hash_and_timestamp(&txn.transaction, first_version).map_err(|e| {
+                        BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                    })?;
+                (block_hash, timestamp, None, None)
+            }
         };
 
         let transactions = if let Some(inner) = txns {
@@ -299,6 +846,23 @@ impl Context {
             None
         };
 
+        let fee_stats = if with_fee_stats {
+            Some(self.get_block_fee_stats(
+                new_block_event.height(),
+                first_version,
+                last_version,
+                ledger_version,
+            )?)
+        } else {
+            None
+        };
+
+        let proposal = if with_proposal {
+            Some(self.get_block_proposal(&new_block_event, first_version)?)
+        } else {
+            None
+        };
+
         Ok(Block {
             block_height: new_block_event.height().into(),
             block_hash: block_hash.into(),
@@ -306,9 +870,83 @@ impl Context {
             first_version: first_version.into(),
             last_version: last_version.into(),
             transactions,
+            transaction_summaries: summaries,
+            fee_stats,
+            proposal,
         })
     }
 
+    /// Resolves the proposal of the block against the validator set the
+    /// block was proposed to, which is the one in effect right before its
+    /// first transaction.
+    fn get_block_proposal(
+        &self,
+        new_block_event: &NewBlockEvent,
+        first_version: Version,
+    ) -> Result<BlockProposal, BasicErrorWith404> {
+        let validators = if first_version == 0 {
+            // Genesis isn't proposed by any validator.
+            vec![]
+        } else {
+            let state_key = StateKey::AccessPath(access_path_for_config(ValidatorSet::CONFIG_ID));
+            let bytes = self
+                .get_state_value_poem::<BasicErrorWith404>(&state_key, first_version - 1)?
+                .ok_or_else(|| {
+                    BasicErrorWith404::internal_with_code(
+                        "Validator set not found before the block",
+                        AptosErrorCode::ReadFromStorageError,
+                    )
+                })?;
+            let validator_set = ValidatorSet::deserialize_into_config(&bytes)
+                .context("Failed to deserialize the validator set retrieved from DB")
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(
+                        e,
+                        AptosErrorCode::InvalidBcsInStorageError,
+                    )
+                })?;
+            ValidatorVerifier::from(&validator_set)
+                .get_ordered_account_addresses_iter()
+                .collect()
+        };
+        BlockProposal::new(new_block_event, &validators)
+            .context("Failed to resolve the proposal of the block")
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))
+    }
+
+    /// Computes the fee statistics of the block from its transactions and their infos, or takes
+    /// them from the cache.
+    fn get_block_fee_stats(
+        &self,
+        block_height: u64,
+        first_version: Version,
+        last_version: Version,
+        ledger_version: Version,
+    ) -> Result<BlockFeeStats, BasicErrorWith404> {
+        if let Some(stats) = self.fee_stats_cache.get(block_height) {
+            return Ok(stats);
+        }
+        let txns = self
+            .db
+            .get_transactions(
+                first_version,
+                last_version - first_version + 1,
+                ledger_version,
+                false,
+            )
+            .context("Failed to read raw transactions from storage")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
+        let stats = BlockFeeStats::new(
+            txns.transactions
+                .iter()
+                .zip(txns.proof.transaction_infos.iter()),
+        );
+        self.fee_stats_cache.insert(block_height, stats.clone());
+        Ok(stats)
+    }
+
     pub fn render_transactions<E: InternalError>(
         &self,
         data: Vec<TransactionOnChainData>,
@@ -319,7 +957,7 @@ impl Context {
         }
 
         let resolver = self.move_resolver_poem()?;
-        let converter = resolver.as_converter(self.db.clone());
+        let converter = self.converter(&resolver);
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
             .map(|t| {
@@ -328,7 +966,7 @@ impl Context {
             })
             .collect::<Result<_, anyhow::Error>>()
             .context("Failed to convert transaction data from storage")
-            .map_err(E::internal)?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::InternalError))?;
 
         Ok(txns)
     }
@@ -339,72 +977,165 @@ impl Context {
         limit: u16,
         ledger_version: u64,
     ) -> Result<Vec<TransactionOnChainData>> {
-        let data = self
-            .db
-            .get_transaction_outputs(start_version, limit as u64, ledger_version)?;
-
-        let txn_start_version = data
-            .first_transaction_output_version
-            .ok_or_else(|| format_err!("no start version from database"))?;
-        ensure!(
-            txn_start_version == start_version,
-            "invalid start version from database: {} != {}",
-            txn_start_version,
-            start_version
-        );
+        read_transactions(self.db.as_ref(), start_version, limit, ledger_version)
+    }
 
-        let infos = data.proof.transaction_infos;
-        let transactions_and_outputs = data.transactions_and_outputs;
+    /// Reads the transactions on the storage read pool, see `get_transactions`.
+    pub async fn get_transactions_async(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<TransactionOnChainData>> {
+        self.async_db
+            .read("async_get_transactions_on_chain_data", move |db| {
+                read_transactions(db, start_version, limit, ledger_version)
+            })
+            .await
+    }
 
-        ensure!(
-            transactions_and_outputs.len() == infos.len(),
-            "invalid data size from database: {}, {}",
-            transactions_and_outputs.len(),
-            infos.len(),
-        );
+    /// Returns the transactions in the given range along with their events and a proof of their
+    /// inclusion in the transaction accumulator at the ledger version, as served to state sync.
+    pub async fn get_transactions_with_proof(
+        &self,
+        start_version: u64,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionListWithProof> {
+        self.async_db
+            .get_transactions(start_version, limit, ledger_version, true)
+            .await
+    }
 
-        transactions_and_outputs
-            .into_iter()
-            .zip(infos.into_iter())
-            .enumerate()
-            .map(|(i, ((txn, txn_output), info))| {
-                let version = start_version + i as u64;
-                let (write_set, events, _, _) = txn_output.unpack();
-                self.get_accumulator_root_hash(version)
-                    .map(|h| (version, txn, info, events, h, write_set).into())
+    /// Returns the outputs of the transactions in the given range, as stored,
+    /// along with their versions and transaction infos. Unlike
+    /// `get_transactions`, this doesn't need the accumulator root hashes.
+    pub async fn get_transaction_outputs(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(Version, TransactionInfo, TransactionOutput)>> {
+        self.async_db
+            .read("async_get_transaction_outputs", move |db| {
+                read_transaction_outputs(db, start_version, limit, ledger_version)
             })
-            .collect()
+            .await
     }
 
-    pub fn get_account_transactions(
+    pub async fn get_account_transactions(
         &self,
         address: AccountAddress,
         start_seq_number: u64,
         limit: u16,
         ledger_version: u64,
     ) -> Result<Vec<TransactionOnChainData>> {
-        let txns = self.db.get_account_transactions(
-            address,
-            start_seq_number,
-            limit as u64,
-            true,
-            ledger_version,
-        )?;
-        txns.into_inner()
-            .into_iter()
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .collect::<Result<Vec<_>>>()
+        self.async_db
+            .read("async_get_account_transactions", move |db| {
+                db.get_account_transactions(
+                    address,
+                    start_seq_number,
+                    limit as u64,
+                    true,
+                    ledger_version,
+                )?
+                .into_inner()
+                .into_iter()
+                .map(|t| into_transaction_on_chain_data(db, t))
+                .collect::<Result<Vec<_>>>()
+            })
+            .await
+    }
+
+    /// Gets the authentication keys of the account up to the ledger version,
+    /// with the versions that set them and, unless they were pruned, the
+    /// timestamps of their blocks.
+    pub async fn get_auth_key_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: u64,
+    ) -> Result<Vec<(Version, Option<u64>, Vec<u8>)>> {
+        self.async_db
+            .read("async_get_auth_key_history", move |db| {
+                let first_viable_version = db.get_first_viable_txn_version()?;
+                db.get_auth_key_history(address, ledger_version)?
+                    .into_iter()
+                    .map(|(version, auth_key)| {
+                        let timestamp = if version >= first_viable_version {
+                            Some(db.get_block_timestamp(version)?)
+                        } else {
+                            None
+                        };
+                        Ok((version, timestamp, auth_key))
+                    })
+                    .collect()
+            })
+            .await
     }
 
-    pub fn get_transaction_by_hash(
+    /// Gets the resources of the account written by the transactions after
+    /// `from_version` up to `to_version`, found by scanning their write sets,
+    /// with their values at both versions.
+    pub async fn get_account_resource_changes(
+        &self,
+        address: AccountAddress,
+        from_version: u64,
+        to_version: u64,
+    ) -> Result<Vec<(StructTag, Option<Vec<u8>>, Option<Vec<u8>>)>> {
+        self.async_db
+            .read("async_get_account_resource_changes", move |db| {
+                let mut struct_tags = BTreeSet::new();
+                for write_set in db.get_write_sets(from_version + 1, to_version + 1)? {
+                    for (state_key, _) in write_set.iter() {
+                        if let StateKey::AccessPath(access_path) = state_key {
+                            if access_path.address == address {
+                                struct_tags.extend(access_path.get_struct_tag());
+                            }
+                        }
+                    }
+                }
+
+                struct_tags
+                    .into_iter()
+                    .map(|struct_tag| {
+                        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(
+                            ResourceKey::new(address, struct_tag.clone()),
+                        ));
+                        let value_at = |version| {
+                            db.get_state_value_by_version(&state_key, version)
+                                .map(|value| value.map(|value| value.bytes))
+                        };
+                        Ok((struct_tag, value_at(from_version)?, value_at(to_version)?))
+                    })
+                    .collect()
+            })
+            .await
+    }
+
+    pub async fn get_transaction_by_hash(
         &self,
         hash: HashValue,
         ledger_version: u64,
     ) -> Result<Option<TransactionOnChainData>> {
-        self.db
-            .get_transaction_by_hash(hash, ledger_version, true)?
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .transpose()
+        self.async_db
+            .read("async_get_transaction_on_chain_data_by_hash", move |db| {
+                db.get_transaction_by_hash(hash, ledger_version, true)?
+                    .map(|t| into_transaction_on_chain_data(db, t))
+                    .transpose()
+            })
+            .await
+    }
+
+    pub async fn get_transaction_version_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        self.async_db
+            .read("async_get_transaction_version_by_hash", move |db| {
+                db.get_transaction_version_by_hash(hash, ledger_version)
+            })
+            .await
     }
 
     pub async fn get_pending_transaction_by_hash(
@@ -422,36 +1153,92 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    /// Returns why mempool evicted the transaction with the hash before it was
+    /// committed, if it recently did.
+    pub async fn get_transaction_eviction_reason(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<EvictionReason>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetTransactionEvictionReason(
+                hash, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Returns the transactions of the account pending in mempool, ordered by
+    /// sequence number.
+    pub async fn get_pending_account_transactions(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Vec<SignedTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetAccountTransactions(
+                address, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
         ledger_version: u64,
     ) -> Result<TransactionOnChainData> {
-        self.convert_into_transaction_on_chain_data(self.db.get_transaction_by_version(
-            version,
-            ledger_version,
-            true,
-        )?)
+        read_transaction_by_version(self.db.as_ref(), version, ledger_version)
     }
 
-    pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
-        self.db.get_accumulator_root_hash(version)
+    /// Reads the transaction on the storage read pool, see `get_transaction_by_version`.
+    pub async fn get_transaction_by_version_async(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionOnChainData> {
+        self.async_db
+            .read(
+                "async_get_transaction_on_chain_data_by_version",
+                move |db| read_transaction_by_version(db, version, ledger_version),
+            )
+            .await
     }
 
-    fn convert_into_transaction_on_chain_data(
+    pub async fn get_filtered_transaction_versions(
         &self,
-        txn: TransactionWithProof,
-    ) -> Result<TransactionOnChainData> {
-        // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
-        let (_, txn_output) = &self
-            .db
-            .get_transaction_outputs(txn.version, 1, txn.version)?
-            .transactions_and_outputs[0];
-        self.get_accumulator_root_hash(txn.version)
-            .map(|h| (txn, h, txn_output).into())
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+        filter: TransactionFilter,
+    ) -> Result<(Vec<u64>, u64)> {
+        let max_scanned = self.max_transaction_filter_scan();
+        self.async_db
+            .read("async_get_filtered_transaction_versions", move |db| {
+                db.get_filtered_transaction_versions(
+                    start_version,
+                    limit as u64,
+                    max_scanned,
+                    ledger_version,
+                    &filter,
+                )
+            })
+            .await
+    }
+
+    pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
+        self.db.get_accumulator_root_hash(version)
     }
 
-    pub fn get_events(
+    pub async fn get_events(
         &self,
         event_key: &EventKey,
         start: Option<u64>,
@@ -459,28 +1246,183 @@ impl Context {
         ledger_version: u64,
     ) -> Result<Vec<EventWithVersion>> {
         if let Some(start) = start {
-            self.db.get_events(
-                event_key,
-                start,
-                Order::Ascending,
-                limit as u64,
-                ledger_version,
-            )
+            self.async_db
+                .get_events(
+                    *event_key,
+                    start,
+                    Order::Ascending,
+                    limit as u64,
+                    ledger_version,
+                )
+                .await
         } else {
-            self.db
+            self.async_db
                 .get_events(
-                    event_key,
+                    *event_key,
                     u64::MAX,
                     Order::Descending,
                     limit as u64,
                     ledger_version,
                 )
+                .await
                 .map(|mut result| {
                     result.reverse();
                     result
                 })
         }
     }
+
+    pub async fn get_events_with_transaction_context(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithTransactionContext>> {
+        self.async_db
+            .get_events_with_transaction_context(start_version, limit as u64, ledger_version)
+            .await
+    }
+
+    pub fn indexer_enabled(&self) -> bool {
+        self.db.indexer_enabled()
+    }
+
+    pub fn get_table_info(&self, handle: TableHandle) -> Result<TableInfo> {
+        self.db.get_table_info(handle)
+    }
+
+    pub fn balance_index_enabled(&self) -> bool {
+        self.db.balance_index_enabled()
+    }
+
+    pub async fn get_coin_balances(
+        &self,
+        address: AccountAddress,
+    ) -> Result<(Version, Vec<(TypeTag, u64)>)> {
+        self.async_db.get_coin_balances(address).await
+    }
+
+    pub async fn get_top_coin_holders(
+        &self,
+        coin_type: TypeTag,
+        limit: u16,
+    ) -> Result<(Version, Vec<(AccountAddress, u64)>)> {
+        self.async_db
+            .get_top_coin_holders(coin_type, limit as u64)
+            .await
+    }
+
+    pub async fn get_table_items(
+        &self,
+        handle: TableHandle,
+        cursor: Option<Vec<u8>>,
+        limit: u16,
+        version: u64,
+    ) -> Result<Vec<(Vec<u8>, StateValue)>> {
+        self.async_db
+            .get_table_items(handle, cursor, limit as u64, version)
+            .await
+    }
+}
+
+fn read_transactions(
+    db: &dyn DbReader,
+    start_version: u64,
+    limit: u16,
+    ledger_version: u64,
+) -> Result<Vec<TransactionOnChainData>> {
+    let data = db.get_transaction_outputs(start_version, limit as u64, ledger_version)?;
+
+    let txn_start_version = data
+        .first_transaction_output_version
+        .ok_or_else(|| format_err!("no start version from database"))?;
+    ensure!(
+        txn_start_version == start_version,
+        "invalid start version from database: {} != {}",
+        txn_start_version,
+        start_version
+    );
+
+    let infos = data.proof.transaction_infos;
+    let transactions_and_outputs = data.transactions_and_outputs;
+
+    ensure!(
+        transactions_and_outputs.len() == infos.len(),
+        "invalid data size from database: {}, {}",
+        transactions_and_outputs.len(),
+        infos.len(),
+    );
+
+    transactions_and_outputs
+        .into_iter()
+        .zip(infos.into_iter())
+        .enumerate()
+        .map(|(i, ((txn, txn_output), info))| {
+            let version = start_version + i as u64;
+            let (write_set, events, _, _) = txn_output.unpack();
+            db.get_accumulator_root_hash(version)
+                .map(|h| (version, txn, info, events, h, write_set).into())
+        })
+        .collect()
+}
+
+fn read_transaction_outputs(
+    db: &dyn DbReader,
+    start_version: u64,
+    limit: u16,
+    ledger_version: u64,
+) -> Result<Vec<(Version, TransactionInfo, TransactionOutput)>> {
+    let data = db.get_transaction_outputs(start_version, limit as u64, ledger_version)?;
+
+    let output_start_version = data
+        .first_transaction_output_version
+        .ok_or_else(|| format_err!("no start version from database"))?;
+    ensure!(
+        output_start_version == start_version,
+        "invalid start version from database: {} != {}",
+        output_start_version,
+        start_version
+    );
+
+    let infos = data.proof.transaction_infos;
+    let transactions_and_outputs = data.transactions_and_outputs;
+
+    ensure!(
+        transactions_and_outputs.len() == infos.len(),
+        "invalid data size from database: {}, {}",
+        transactions_and_outputs.len(),
+        infos.len(),
+    );
+
+    Ok(transactions_and_outputs
+        .into_iter()
+        .zip(infos.into_iter())
+        .enumerate()
+        .map(|(i, ((_, txn_output), info))| (start_version + i as u64, info, txn_output))
+        .collect())
+}
+
+fn read_transaction_by_version(
+    db: &dyn DbReader,
+    version: u64,
+    ledger_version: u64,
+) -> Result<TransactionOnChainData> {
+    into_transaction_on_chain_data(
+        db,
+        db.get_transaction_by_version(version, ledger_version, true)?,
+    )
+}
+
+fn into_transaction_on_chain_data(
+    db: &dyn DbReader,
+    txn: TransactionWithProof,
+) -> Result<TransactionOnChainData> {
+    // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
+    let (_, txn_output) = &db
+        .get_transaction_outputs(txn.version, 1, txn.version)?
+        .transactions_and_outputs[0];
+    db.get_accumulator_root_hash(txn.version)
+        .map(|h| (txn, h, txn_output).into())
 }
 
 pub fn get_block_hash_and_timestamp(txn: &Transaction, version: u64) -> Result<(HashValue, u64)> {
@@ -0,0 +1,163 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use aptos_api_types::X_APTOS_LEDGER_VERSION_PIN;
+use aptos_config::config::{ApiConfig, CorsConfig};
+use arc_swap::ArcSwap;
+use poem::{
+    endpoint::BoxEndpoint,
+    http::{header, Method},
+    middleware::Cors,
+    Endpoint, EndpointExt, Middleware, Request, Response, Result,
+};
+use std::sync::Arc;
+
+/// This middleware handles CORS, including preflight requests, with the CORS
+/// configuration of the route group a request is for: the override for the
+/// longest path prefix matching the request, or the default configuration.
+/// It follows reloads of the API config.
+pub struct RouteGroupCors {
+    context: Arc<Context>,
+}
+
+impl RouteGroupCors {
+    pub fn new(context: Arc<Context>) -> Self {
+        Self { context }
+    }
+}
+
+impl<E: Endpoint + 'static> Middleware<E> for RouteGroupCors {
+    type Output = RouteGroupCorsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        let inner = Arc::new(ep);
+        let route_groups = RouteGroups::new(&inner, self.context.api_config());
+        RouteGroupCorsEndpoint {
+            inner,
+            context: self.context.clone(),
+            route_groups: ArcSwap::from_pointee(route_groups),
+        }
+    }
+}
+
+/// Endpoint for RouteGroupCors middleware.
+pub struct RouteGroupCorsEndpoint<E> {
+    inner: Arc<E>,
+    context: Arc<Context>,
+    route_groups: ArcSwap<RouteGroups>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint + 'static> Endpoint for RouteGroupCorsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let api_config = self.context.api_config();
+        let mut route_groups = self.route_groups.load_full();
+        if !Arc::ptr_eq(&route_groups.api_config, &api_config) {
+            route_groups = Arc::new(RouteGroups::new(&self.inner, api_config));
+            self.route_groups.store(route_groups.clone());
+        }
+        route_groups.endpoint(req.uri().path()).call(req).await
+    }
+}
+
+/// The endpoint wrapped by the Cors middleware of each route group, for the
+/// API config they were built from.
+struct RouteGroups {
+    api_config: Arc<ApiConfig>,
+    overrides: Vec<(String, BoxEndpoint<'static, Response>)>,
+    default: BoxEndpoint<'static, Response>,
+}
+
+impl RouteGroups {
+    fn new<E: Endpoint + 'static>(inner: &Arc<E>, api_config: Arc<ApiConfig>) -> Self {
+        let mut overrides: Vec<_> = api_config
+            .cors_overrides
+            .iter()
+            .map(|(prefix, cors)| {
+                let ep = SharedEndpoint(inner.clone())
+                    .with(build_cors(cors))
+                    .map_to_response()
+                    .boxed();
+                (prefix.trim_end_matches('/').to_string(), ep)
+            })
+            .collect();
+        // Check the most specific route groups first.
+        overrides.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()));
+        let default = SharedEndpoint(inner.clone())
+            .with(build_cors(&api_config.cors))
+            .map_to_response()
+            .boxed();
+        Self {
+            api_config,
+            overrides,
+            default,
+        }
+    }
+
+    fn endpoint(&self, path: &str) -> &BoxEndpoint<'static, Response> {
+        self.overrides
+            .iter()
+            .find(|(prefix, _)| is_in_route_group(path, prefix))
+            .map_or(&self.default, |(_, ep)| ep)
+    }
+}
+
+/// Lets the endpoint wrapped by the middleware be wrapped again by the Cors
+/// middleware of every route group.
+struct SharedEndpoint<E>(Arc<E>);
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for SharedEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        self.0.call(req).await
+    }
+}
+
+fn is_in_route_group(path: &str, prefix: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+fn build_cors(config: &CorsConfig) -> Cors {
+    let mut cors = Cors::new()
+        .allow_methods(vec![Method::GET, Method::POST])
+        .allow_headers(vec![header::CONTENT_TYPE, header::ACCEPT])
+        .allow_header(X_APTOS_LEDGER_VERSION_PIN)
+        .allow_headers(config.allowed_headers.clone())
+        .max_age(i32::try_from(config.max_age_secs).unwrap_or(i32::MAX))
+        .allow_credentials(config.allow_credentials);
+    // Poem allows any origin when none are configured.
+    if !config.allowed_origins.is_empty() {
+        let allowed_origins = config.allowed_origins.clone();
+        cors = cors.allow_origins_fn(move |origin| {
+            allowed_origins
+                .iter()
+                .any(|pattern| origin_matches(pattern, origin))
+        });
+    }
+    cors
+}
+
+/// Whether the origin matches the pattern, in which the first `*` matches any
+/// characters.
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern.eq_ignore_ascii_case(origin),
+        Some((prefix, suffix)) => {
+            origin.len() >= prefix.len() + suffix.len()
+                && origin
+                    .get(..prefix.len())
+                    .map_or(false, |p| p.eq_ignore_ascii_case(prefix))
+                && origin
+                    .get(origin.len() - suffix.len()..)
+                    .map_or(false, |s| s.eq_ignore_ascii_case(suffix))
+        }
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::accept_type::AcceptType;
+use crate::context::Context;
+use crate::failpoint::fail_point_poem;
+use crate::move_unit_test::{run_move_unit_tests, MoveUnitTestError};
+use crate::response::{
+    build_not_found, AptosErrorResponse, BadRequestError, BasicError, BasicErrorWith404,
+    BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404, InternalError,
+};
+use crate::script_composer::{compose_script, ScriptCall, ScriptCompositionError};
+use crate::ApiTags;
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    AptosErrorCode, MoveScriptBytecode, MoveUnitTestRequest, MoveUnitTestResult,
+    ScriptCompositionRequest, ScriptExecutionRequest, ScriptExecutionResult, ScriptPayload,
+    TransactionId, TransactionPayload, U64,
+};
+use aptos_state_view::StateView;
+use aptos_types::access_path::AccessPath;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::{ExecutionStatus, TransactionStatus};
+use aptos_types::write_set::{WriteOp, WriteSet, WriteSetMut};
+use aptos_vm::data_cache::IntoMoveResolver;
+use aptos_vm::AptosVM;
+use move_deps::move_binary_format::CompiledModule;
+use move_deps::move_core_types::language_storage::{ModuleId, ResourceKey, StructTag, TypeTag};
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::OpenApi;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+pub struct DeveloperApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl DeveloperApi {
+    /// Run Move unit tests
+    ///
+    /// This endpoint compiles the Move package given in the request body in
+    /// test mode, against the Aptos framework of the node, and runs its unit
+    /// tests in a sandboxed VM that has no access to the node's state. It
+    /// returns the outcome of each test, including why failed tests failed,
+    /// and the instruction coverage of each function of the package if the
+    /// node traces VM execution.
+    ///
+    /// Packages that fail to compile are rejected with a 400 carrying the
+    /// compiler's diagnostics. Runs are executed one at a time.
+    ///
+    /// This endpoint is only available on nodes with the developer API
+    /// enabled, which is meant for hosted development environments rather
+    /// than production nodes.
+    #[oai(
+        path = "/developer/move/test",
+        method = "post",
+        operation_id = "run_move_unit_tests",
+        tag = "ApiTags::Developer"
+    )]
+    async fn run_move_unit_tests(
+        &self,
+        accept_type: AcceptType,
+        request: Json<MoveUnitTestRequest>,
+    ) -> BasicResult<MoveUnitTestResult> {
+        fail_point_poem("endpoint_run_move_unit_tests")?;
+        self.ensure_developer_api_enabled()?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+
+        let result = tokio::task::spawn_blocking(move || run_move_unit_tests(request.0))
+            .await
+            .map_err(|err| BasicError::internal_with_code(err, AptosErrorCode::InternalError))?
+            .map_err(|err| match err {
+                MoveUnitTestError::InvalidInput(message) => {
+                    BasicError::bad_request_with_code(message, AptosErrorCode::InvalidInput)
+                }
+                MoveUnitTestError::Compilation(diagnostics) => BasicError::bad_request_with_code(
+                    "The Move package failed to compile",
+                    AptosErrorCode::MoveCompilationFailed,
+                )
+                .detail("diagnostics", diagnostics),
+                MoveUnitTestError::Internal(err) => {
+                    BasicError::internal_with_code(err, AptosErrorCode::InternalError)
+                }
+            })?;
+
+        BasicResponse::try_from_rust_value((
+            result,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            &accept_type,
+        ))
+    }
+
+    /// Execute a script read-only
+    ///
+    /// Executes the script of the request against the state at a ledger
+    /// version, with the resources of the request replaced, and returns the
+    /// changes the script would have made to state and the events it
+    /// emitted. Nothing is committed. The signers of the script need not
+    /// have an account, and don't pay for the gas the script uses. This
+    /// allows "what if" analyses, e.g. of how a script behaves against a
+    /// manipulated price oracle. If the ledger version is not specified in
+    /// the request, the latest ledger version is used.
+    ///
+    /// This endpoint is only available on nodes with the developer API
+    /// enabled, which is meant for hosted development environments rather
+    /// than production nodes.
+    #[oai(
+        path = "/developer/move/execute_script",
+        method = "post",
+        operation_id = "execute_script_read_only",
+        tag = "ApiTags::Developer"
+    )]
+    async fn execute_script_read_only(
+        &self,
+        accept_type: AcceptType,
+        ledger_version: Query<Option<U64>>,
+        request: Json<ScriptExecutionRequest>,
+    ) -> BasicResultWith404<ScriptExecutionResult> {
+        fail_point_poem("endpoint_execute_script_read_only")?;
+        self.ensure_developer_api_enabled()?;
+        // Executing the script is CPU-bound, so keep it off the threads serving requests.
+        let api = DeveloperApi {
+            context: self.context.clone(),
+        };
+        tokio::task::spawn_blocking(move || {
+            api.execute_script(&accept_type, ledger_version.0, request.0)
+        })
+        .await
+        .map_err(|err| BasicErrorWith404::internal_with_code(err, AptosErrorCode::InternalError))?
+    }
+
+    /// Compose a script
+    ///
+    /// Generates a single script making the calls of the request one after
+    /// the other, so that they succeed or fail together, e.g. to batch swaps
+    /// without every dapp shipping a Move compiler. The called functions must
+    /// be public and return nothing. The script takes the signers of the
+    /// calls, which every call shares, then the arguments of each call in
+    /// order.
+    ///
+    /// Returns the script as a payload ready to be signed, with its bytecode,
+    /// its ABI and the arguments of the calls, e.g. to pass on to
+    /// `/transactions/encode_submission`.
+    ///
+    /// This endpoint is only available on nodes with the developer API
+    /// enabled, which is meant for hosted development environments rather
+    /// than production nodes.
+    #[oai(
+        path = "/developer/move/compose_script",
+        method = "post",
+        operation_id = "compose_script",
+        tag = "ApiTags::Developer"
+    )]
+    async fn compose_script(
+        &self,
+        accept_type: AcceptType,
+        request: Json<ScriptCompositionRequest>,
+    ) -> BasicResult<ScriptPayload> {
+        fail_point_poem("endpoint_compose_script")?;
+        self.ensure_developer_api_enabled()?;
+        self.composed_script(&accept_type, request.0)
+    }
+}
+
+impl DeveloperApi {
+    fn ensure_developer_api_enabled<E: BadRequestError>(&self) -> Result<(), E> {
+        if !self.context.developer_api_enabled() {
+            return Err(E::bad_request_with_code(
+                "The developer API is not enabled on this node",
+                AptosErrorCode::DeveloperApiDisabled,
+            ));
+        }
+        Ok(())
+    }
+
+    fn composed_script(
+        &self,
+        accept_type: &AcceptType,
+        request: ScriptCompositionRequest,
+    ) -> BasicResult<ScriptPayload> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let state_view = self
+            .context
+            .state_view_at_version(ledger_info.version())
+            .map_err(|e| BasicError::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
+
+        let mut calls = vec![];
+        let mut arguments = vec![];
+        for (i, call) in request.calls.into_iter().enumerate() {
+            let type_arguments = call
+                .type_arguments
+                .into_iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<_>>()
+                .context(format!("Invalid type arguments of calls[{}]", i))
+                .map_err(|e| BasicError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+            calls.push(ScriptCall {
+                module: call.function.module.into(),
+                function: call.function.name.0,
+                type_arguments,
+                num_arguments: call.arguments.len(),
+            });
+            arguments.extend(call.arguments);
+        }
+
+        let get_module = |module_id: &ModuleId| -> Result<CompiledModule, ScriptCompositionError> {
+            let bytes = state_view
+                .get_state_value(&StateKey::AccessPath(AccessPath::code_access_path(
+                    module_id.clone(),
+                )))
+                .context(format!("Failed to read module {}", module_id))?
+                .ok_or_else(|| {
+                    ScriptCompositionError::InvalidInput(format!(
+                        "The module {} can't be found",
+                        module_id
+                    ))
+                })?;
+            CompiledModule::deserialize(&bytes)
+                .context(format!("Failed to deserialize module {}", module_id))
+                .map_err(ScriptCompositionError::from)
+        };
+        let bytecode = compose_script(&calls, get_module).map_err(|err| match err {
+            ScriptCompositionError::InvalidInput(message) => {
+                BasicError::bad_request_with_code(message, AptosErrorCode::InvalidInput)
+            }
+            ScriptCompositionError::Internal(err) => {
+                BasicError::internal_with_code(err, AptosErrorCode::InternalError)
+            }
+        })?;
+        let payload = ScriptPayload {
+            code: MoveScriptBytecode::new(bytecode).try_parse_abi(),
+            type_arguments: vec![],
+            arguments,
+        };
+
+        // Check the arguments of the calls against the params of the script.
+        let resolver = state_view.into_move_resolver();
+        self.context
+            .converter(&resolver)
+            .try_into_aptos_core_transaction_payload(TransactionPayload::ScriptPayload(
+                payload.clone(),
+            ))
+            .context("Invalid arguments")
+            .map_err(|e| BasicError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+
+        BasicResponse::try_from_rust_value((
+            payload,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    fn execute_script(
+        &self,
+        accept_type: &AcceptType,
+        ledger_version: Option<U64>,
+        request: ScriptExecutionRequest,
+    ) -> BasicResultWith404<ScriptExecutionResult> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = ledger_version
+            .map(|v| v.0)
+            .unwrap_or_else(|| ledger_info.version());
+        if ledger_version > ledger_info.version() {
+            return Err(build_not_found(
+                "ledger",
+                TransactionId::Version(U64::from(ledger_version)),
+                AptosErrorCode::VersionNotFound,
+                ledger_info.version(),
+            ));
+        }
+
+        let resolver = self
+            .context
+            .state_view_at_version(ledger_version)
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .into_move_resolver();
+        let converter = self.context.converter(&resolver);
+
+        let script = match converter
+            .try_into_aptos_core_transaction_payload(TransactionPayload::ScriptPayload(
+                request.script,
+            ))
+            .context("Failed to parse the script")
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })? {
+            aptos_types::transaction::TransactionPayload::Script(script) => script,
+            _ => {
+                return Err(BasicErrorWith404::internal_with_code(
+                    "A script payload was unexpectedly converted into another payload",
+                    AptosErrorCode::InternalError,
+                ))
+            }
+        };
+
+        let mut overrides = WriteSetMut::default();
+        for resource_override in request.resource_overrides {
+            let struct_tag = StructTag::try_from(resource_override.resource_type)
+                .context("Failed to parse the type of a resource override")
+                .map_err(|e| {
+                    BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+                })?;
+            // Check the value up front, rather than have the script fail on it.
+            converter
+                .try_into_resource(&struct_tag, &resource_override.data.0)
+                .context(format!(
+                    "Failed to deserialize the override of resource {} of {}",
+                    struct_tag, resource_override.address
+                ))
+                .map_err(|e| {
+                    BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+                })?;
+            let resource_key = ResourceKey::new(resource_override.address.into(), struct_tag);
+            overrides.push((
+                StateKey::AccessPath(AccessPath::resource_access_path(resource_key)),
+                WriteOp::Modification(resource_override.data.0),
+            ));
+        }
+        let overrides: WriteSet = overrides
+            .freeze()
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
+
+        let signers = request.signers.into_iter().map(Into::into).collect();
+        let max_gas_amount = request.max_gas_amount.map(|amount| amount.0);
+        let result = match AptosVM::execute_script_read_only(
+            &script,
+            signers,
+            &overrides,
+            max_gas_amount,
+            &resolver,
+        ) {
+            Ok(output_ext) => {
+                let output = output_ext
+                    .into_transaction_output(&resolver)
+                    .context("Failed to apply the deltas of the script execution")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                    })?;
+                let gas_used = output.gas_used();
+                let (write_set, events) = output.into();
+                ScriptExecutionResult {
+                    success: true,
+                    vm_status: converter.explain_vm_status(&ExecutionStatus::Success),
+                    gas_used: gas_used.into(),
+                    changes: write_set
+                        .into_iter()
+                        .filter_map(|(state_key, op)| {
+                            converter.try_into_write_set_change(state_key, op).ok()
+                        })
+                        .collect(),
+                    events: converter
+                        .try_into_events(&events)
+                        .context("Failed to convert the events of the script execution")
+                        .map_err(|e| {
+                            BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                        })?,
+                }
+            }
+            Err((vm_status, gas_used)) => match TransactionStatus::from(vm_status) {
+                TransactionStatus::Keep(status) => ScriptExecutionResult {
+                    success: false,
+                    vm_status: converter.explain_vm_status(&status),
+                    gas_used: gas_used.into(),
+                    changes: vec![],
+                    events: vec![],
+                },
+                // The script can't be executed at all, e.g. it doesn't verify or its arguments
+                // don't match its parameters.
+                TransactionStatus::Discard(status_code) => {
+                    return Err(BasicErrorWith404::bad_request_with_code(
+                        format!("The script can't be executed: {:?}", status_code),
+                        AptosErrorCode::InvalidInput,
+                    ))
+                }
+                TransactionStatus::Retry => {
+                    return Err(BasicErrorWith404::internal_with_code(
+                        "The script execution unexpectedly asked to be retried",
+                        AptosErrorCode::InternalError,
+                    ))
+                }
+            },
+        };
+
+        BasicResponse::try_from_rust_value((
+            result,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+}
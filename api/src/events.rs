@@ -14,8 +14,11 @@ use crate::response::{
 };
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
-use aptos_api_types::{Address, EventKey, IdentifierWrapper, MoveStructTag, U64};
-use aptos_api_types::{AsConverter, VersionedEvent};
+use aptos_api_types::{
+    Address, Cursor, EventKey, EventStream, IdentifierWrapper, MoveStructTag, U64,
+};
+use aptos_api_types::{AptosErrorCode, VersionedEvent};
+use move_deps::move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::param::Query;
 use poem_openapi::{param::Path, OpenApi};
 
@@ -29,11 +32,15 @@ impl EventsApi {
     ///
     /// This endpoint allows you to get a list of events of a specific type
     /// as identified by its event key, which is a globally unique ID.
+    ///
+    /// Deprecated in favor of addressing the events by the account address
+    /// and creation number the event key is made of, which responses link to.
     #[oai(
         path = "/events/:event_key",
         method = "get",
         operation_id = "get_events_by_event_key",
-        tag = "ApiTags::Events"
+        tag = "ApiTags::Events",
+        deprecated
     )]
     // TODO: https://github.com/aptos-labs/aptos-core/issues/2284
     async fn get_events_by_event_key(
@@ -43,10 +50,91 @@ impl EventsApi {
         event_key: Path<EventKey>,
         start: Query<Option<U64>>,
         limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         fail_point_poem("endpoint_get_events_by_event_key")?;
-        let page = Page::new(start.0.map(|v| v.0), limit.0);
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_sequence_number)?;
+        let successor = format!(
+            "/v1/accounts/{}/events/{}",
+            event_key.account_address(),
+            event_key.creation_number().0
+        );
         self.list(accept_type, page, event_key.0)
+            .await
+            .map(|response| response.with_deprecation(&successor))
+    }
+
+    /// Get events by creation number
+    ///
+    /// Event streams are created by an account, which numbers them in order
+    /// of creation. This endpoint returns the events of the stream with the
+    /// given creation number, created by the given account.
+    #[oai(
+        path = "/accounts/:address/events/:creation_number",
+        method = "get",
+        operation_id = "get_events_by_creation_number",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_creation_number(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        creation_number: Path<U64>,
+        start: Query<Option<U64>>,
+        limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        fail_point_poem("endpoint_get_events_by_creation_number")?;
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_sequence_number)?;
+        self.list(
+            accept_type,
+            page,
+            EventKey::new(address.0, creation_number.0),
+        )
+        .await
+    }
+
+    /// Get event streams of an account
+    ///
+    /// This endpoint lists the event streams created by the given account,
+    /// by creation number, with the number of events emitted to each, the
+    /// sequence number of the latest one and their type. It lets clients
+    /// discover event streams without knowing which resource field holds
+    /// their event handle.
+    ///
+    /// Creation numbers are shared with the other GUIDs the account created,
+    /// which show up as streams without any event. When more streams remain,
+    /// the `X-Aptos-Cursor` header holds the cursor from which to request the
+    /// next page.
+    #[oai(
+        path = "/accounts/:address/events",
+        method = "get",
+        operation_id = "get_account_event_streams",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_account_event_streams(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        /// Creation number of the first stream to list
+        start: Query<Option<U64>>,
+        /// Max number of streams to list
+        limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
+    ) -> BasicResultWith404<Vec<EventStream>> {
+        fail_point_poem("endpoint_get_account_event_streams")?;
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_creation_number)?;
+        let account = Account::new(self.context.clone(), address.0, None)?;
+        account.event_streams(&accept_type, page).await
     }
 
     /// Get events by event handle
@@ -68,20 +156,81 @@ impl EventsApi {
         field_name: Path<IdentifierWrapper>,
         start: Query<Option<U64>>,
         limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         // TODO: Assert that Event represents u64s as strings.
         fail_point_poem("endpoint_get_events_by_event_handle")?;
-        let page = Page::new(start.0.map(|v| v.0), limit.0);
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_sequence_number)?;
         let account = Account::new(self.context.clone(), address.0, None)?;
         let key = account
             .find_event_key(event_handle.0, field_name.0.into())?
             .into();
-        self.list(accept_type, page, key)
+        self.list(accept_type, page, key).await
+    }
+
+    /// Get events by version range
+    ///
+    /// This endpoint returns the events emitted by a range of transactions,
+    /// in the order they were emitted, optionally only those of the given
+    /// type. It's meant for indexers that are only interested in events.
+    /// Each event comes with the hash, sender and status of the transaction
+    /// that emitted it, so they need not look the transaction up.
+    ///
+    /// Here `limit` is the number of transactions to scan rather than of
+    /// events to return, so a page may well be empty. The `X-Aptos-Cursor`
+    /// header holds the cursor to start the next page from, until the
+    /// latest version is reached. A page cut short for its size may end
+    /// within the events of a transaction, the next page then starts with
+    /// the rest of them.
+    #[oai(
+        path = "/events",
+        method = "get",
+        operation_id = "get_events_by_version_range",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_version_range(
+        &self,
+        accept_type: AcceptType,
+        /// Version of the first transaction to scan, the latest page by default
+        start: Query<Option<U64>>,
+        /// Number of transactions to scan
+        limit: Query<Option<u16>>,
+        /// Only return events of this type, e.g. `0x1::coin::DepositEvent`
+        event_type: Query<Option<MoveStructTag>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        fail_point_poem("endpoint_get_events_by_version_range")?;
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0.clone(), |cursor| {
+                cursor.into_version_and_index().map(|(version, _)| version)
+            })?;
+        // The index, among the events of the start version, of the first one
+        // to return.
+        let start_index = cursor.0.map_or(0, |cursor| {
+            cursor
+                .into_version_and_index()
+                .map_or(0, |(_, index)| index)
+        });
+        let event_type = event_type
+            .0
+            .map(|tag| StructTag::try_from(tag).map(TypeTag::Struct))
+            .transpose()
+            .context("Invalid event type")
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
+        self.list_by_version_range(accept_type, page, start_index, event_type)
+            .await
     }
 }
 
 impl EventsApi {
-    fn list(
+    async fn list(
         &self,
         accept_type: AcceptType,
         page: Page,
@@ -97,23 +246,102 @@ impl EventsApi {
                 page.limit()?,
                 ledger_version,
             )
+            .await
             // TODO: Previously this was a 500, but I'm making this a 400. I suspect
             // both could be true depending on the error. Make this more specific.
             .context(format!("Failed to find events by key {}", event_key))
-            .map_err(BasicErrorWith404::bad_request)?;
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
 
         let resolver = self.context.move_resolver_poem()?;
-        let events = resolver
-            .as_converter(self.context.db.clone())
+        let events = self
+            .context
+            .converter(&resolver)
             .try_into_versioned_events(&events)
             .context("Failed to convert events from storage into response {}")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
+
+        // Events of a key have consecutive sequence numbers, so the cursor can
+        // be derived from the first one.
+        let start = events.first().map(|e| e.sequence_number.0).unwrap_or(0);
+        BasicResponse::try_from_paged_rust_value((
+            events,
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            &accept_type,
+            start,
+            Cursor::sequence_number,
+            self.context.max_response_size(),
+        ))
+    }
+
+    async fn list_by_version_range(
+        &self,
+        accept_type: AcceptType,
+        page: Page,
+        start_index: u64,
+        event_type: Option<TypeTag>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+
+        let limit = page.limit()?;
+        let start_version = page.compute_start(limit, ledger_version)?;
+        let events = self
+            .context
+            .get_events_with_transaction_context(start_version, limit, ledger_version)
+            .await
+            .context("Failed to read events from storage")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+
+        // Number the events of each version, which positions them for the
+        // cursor, before leaving out those before the start and of other types.
+        let mut positions = vec![];
+        let mut kept_events = vec![];
+        let mut index = 0;
+        for (i, event) in events.iter().enumerate() {
+            if i > 0 && events[i - 1].transaction_version == event.transaction_version {
+                index += 1;
+            } else {
+                index = 0;
+            }
+            let after_start = event.transaction_version > start_version || index >= start_index;
+            let of_type = event_type
+                .as_ref()
+                .map_or(true, |event_type| event.event.type_tag() == event_type);
+            if after_start && of_type {
+                positions.push((event.transaction_version, index));
+                kept_events.push(event.clone());
+            }
+        }
+
+        let resolver = self.context.move_resolver_poem()?;
+        let events = self
+            .context
+            .converter(&resolver)
+            .try_into_versioned_events_with_context(&kept_events)
+            .context("Failed to convert events from storage into response")
+            .map_err(|e| BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError))?;
 
-        BasicResponse::try_from_rust_value((
+        BasicResponse::try_from_budgeted_rust_list((
             events,
             &latest_ledger_info,
             BasicResponseStatus::Ok,
             &accept_type,
+            self.context.max_response_size(),
+            |cut_at| match cut_at {
+                Some(count) => {
+                    let (version, index) = positions[count];
+                    Some(Cursor::version_and_index(version, index))
+                }
+                None => {
+                    let next_version = start_version + limit as u64;
+                    (next_version <= ledger_version).then(|| Cursor::version(next_version))
+                }
+            },
         ))
     }
 }
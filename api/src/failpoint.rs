@@ -5,16 +5,16 @@
 
 use crate::response::InternalError;
 use anyhow::{format_err, Result};
-use aptos_api_types::AptosError;
+use aptos_api_types::{AptosError, AptosErrorCode};
 use poem_openapi::payload::Json;
 
 #[allow(unused_variables)]
 #[inline]
 pub fn fail_point_poem<E: InternalError>(name: &str) -> Result<(), E> {
     Ok(fail::fail_point!(format!("api::{}", name).as_str(), |_| {
-        Err(E::internal_str(&format!(
-            "unexpected internal error for {}",
-            name
-        )))
+        Err(E::internal_with_code(
+            format!("unexpected internal error for {}", name),
+            AptosErrorCode::InternalError,
+        ))
     }))
 }
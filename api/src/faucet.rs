@@ -0,0 +1,290 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The faucet embedded in the API, which funds accounts on test networks the
+//! way the faucet service does: it creates the account if it doesn't exist
+//! and mints coins to it, submitting the transactions straight to the
+//! mempool of the node rather than through the API of another node.
+
+use crate::accept_type::AcceptType;
+use crate::context::Context;
+use crate::failpoint::fail_point_poem;
+use crate::response::{
+    BadRequestError, BasicError, BasicResponse, BasicResponseStatus, BasicResult, InternalError,
+};
+use crate::ApiTags;
+use anyhow::{format_err, Context as AnyhowContext};
+use aptos_api_types::{AptosErrorCode, FundAccountRequest, FundAccountResponse, U64};
+use aptos_config::config::FaucetConfig;
+use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_crypto::PrivateKey;
+use aptos_infallible::Mutex;
+use aptos_types::access_path::AccessPath;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::account_config::{aptos_test_root_address, AccountResource};
+use aptos_types::chain_id::NamedChain;
+use aptos_types::mempool_status::MempoolStatusCode;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::{RawTransaction, SignedTransaction, TransactionPayload};
+use framework::aptos_stdlib;
+use move_deps::move_core_types::language_storage::ResourceKey;
+use move_deps::move_core_types::move_resource::MoveStructType;
+use poem_openapi::payload::Json;
+use poem_openapi::OpenApi;
+use std::sync::Arc;
+
+/// The same parameters the faucet service builds its transactions with.
+const MAX_GAS_AMOUNT: u64 = 2_000;
+const GAS_UNIT_PRICE: u64 = 1;
+const TXN_EXPIRATION_SECS: u64 = 30;
+
+/// Past this many transactions ahead of the sequence number of the mint
+/// account on chain, the transactions in between are assumed to have been
+/// dropped, and sequence numbers are handed out from the one on chain again.
+const MAX_OUTSTANDING_TXNS: u64 = 50;
+
+/// The sequence numbers handed out to the transactions of the mint account,
+/// shared by all requests so that concurrent requests don't submit
+/// transactions with the same sequence number while the earlier ones are
+/// still in mempool.
+#[derive(Default)]
+pub struct FaucetSequenceNumbers {
+    /// The mint account and the sequence number of its next transaction.
+    next: Mutex<Option<(AccountAddress, u64)>>,
+}
+
+impl FaucetSequenceNumbers {
+    /// Hands out `count` sequence numbers for the account, returning the
+    /// first of them.
+    fn take(&self, address: AccountAddress, on_chain: u64, count: u64) -> u64 {
+        let mut next = self.next.lock();
+        let first = match *next {
+            Some((next_address, next_sequence_number))
+                if next_address == address
+                    && next_sequence_number > on_chain
+                    && next_sequence_number < on_chain + MAX_OUTSTANDING_TXNS =>
+            {
+                next_sequence_number
+            }
+            _ => on_chain,
+        };
+        *next = Some((address, first + count));
+        first
+    }
+
+    /// Forgets the sequence numbers handed out, after a transaction failed to
+    /// be submitted, so that the next request starts from the one on chain.
+    fn reset(&self) {
+        *self.next.lock() = None;
+    }
+}
+
+pub struct FaucetApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl FaucetApi {
+    /// Fund account
+    ///
+    /// Funds an account from the faucet of the node, creating the account if
+    /// it doesn't exist, and returns the hashes of the transactions it
+    /// submitted. The transactions are only submitted, so they may not have
+    /// been committed when the response is returned; wait for them with
+    /// their hashes before using the account.
+    ///
+    /// The amount is capped to the maximum amount the faucet funds per
+    /// request, if the node sets one.
+    ///
+    /// This endpoint is only available on nodes of test networks with the
+    /// faucet enabled, and never on mainnet.
+    #[oai(
+        path = "/faucet/fund",
+        method = "post",
+        operation_id = "fund_account",
+        tag = "ApiTags::Faucet"
+    )]
+    async fn fund_account(
+        &self,
+        accept_type: AcceptType,
+        request: Json<FundAccountRequest>,
+    ) -> BasicResult<FundAccountResponse> {
+        fail_point_poem("endpoint_fund_account")?;
+        let config = self.context.api_config();
+        self.ensure_faucet_enabled(&config.faucet)?;
+        let mint_key = mint_key(&config.faucet)
+            .map_err(|err| BasicError::internal_with_code(err, AptosErrorCode::InternalError))?;
+        let mint_address = config
+            .faucet
+            .mint_account_address
+            .unwrap_or_else(aptos_test_root_address);
+        let receiver = request.0.address.into();
+        let amount = match config.faucet.maximum_amount {
+            Some(maximum_amount) => request.0.amount.0.min(maximum_amount),
+            None => request.0.amount.0,
+        };
+
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let version = ledger_info.version();
+        let mint_sequence_number =
+            self.sequence_number(mint_address, version)?
+                .ok_or_else(|| {
+                    BasicError::internal_with_code(
+                        format!("The faucet's mint account {} doesn't exist", mint_address),
+                        AptosErrorCode::InternalError,
+                    )
+                })?;
+
+        let mut payloads = Vec::new();
+        if self.sequence_number(receiver, version)?.is_none() {
+            payloads.push(aptos_stdlib::account_create_account(receiver));
+        }
+        if amount > 0 {
+            payloads.push(aptos_stdlib::aptos_coin_mint(receiver, amount));
+        }
+
+        let sequence_numbers = self.context.faucet_sequence_numbers();
+        let first_sequence_number =
+            sequence_numbers.take(mint_address, mint_sequence_number, payloads.len() as u64);
+        let expiration_timestamp_secs =
+            aptos_infallible::duration_since_epoch().as_secs() + TXN_EXPIRATION_SECS;
+        let mut txn_hashes = Vec::new();
+        for (i, payload) in payloads.into_iter().enumerate() {
+            let txn = self
+                .sign(
+                    mint_address,
+                    &mint_key,
+                    first_sequence_number + i as u64,
+                    payload,
+                    expiration_timestamp_secs,
+                )
+                .map_err(|err| {
+                    BasicError::internal_with_code(err, AptosErrorCode::InternalError)
+                })?;
+            txn_hashes.push(txn.clone().committed_hash().into());
+            if let Err(err) = self.submit(txn).await {
+                sequence_numbers.reset();
+                return Err(err);
+            }
+        }
+
+        BasicResponse::try_from_rust_value((
+            FundAccountResponse {
+                amount: U64::from(amount),
+                txn_hashes,
+            },
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            &accept_type,
+        ))
+    }
+}
+
+impl FaucetApi {
+    fn ensure_faucet_enabled<E: BadRequestError>(&self, config: &FaucetConfig) -> Result<(), E> {
+        if !faucet_enabled(&self.context, config) {
+            return Err(E::bad_request_with_code(
+                "The faucet is not enabled on this node",
+                AptosErrorCode::FaucetDisabled,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The sequence number of the account at the version, or None if the
+    /// account doesn't exist.
+    fn sequence_number(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Option<u64>, BasicError> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            AccountResource::struct_tag(),
+        )));
+        self.context
+            .get_state_value_poem(&state_key, version)?
+            .map(|bytes| {
+                bcs::from_bytes::<AccountResource>(&bytes)
+                    .map(|account| account.sequence_number())
+                    .context("Failed to deserialize account resource")
+                    .map_err(|err| {
+                        BasicError::internal_with_code(err, AptosErrorCode::InternalError)
+                    })
+            })
+            .transpose()
+    }
+
+    fn sign(
+        &self,
+        sender: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+        sequence_number: u64,
+        payload: TransactionPayload,
+        expiration_timestamp_secs: u64,
+    ) -> anyhow::Result<SignedTransaction> {
+        Ok(RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            MAX_GAS_AMOUNT,
+            GAS_UNIT_PRICE,
+            expiration_timestamp_secs,
+            self.context.chain_id(),
+        )
+        .sign(private_key, private_key.public_key())?
+        .into_inner())
+    }
+
+    async fn submit(&self, txn: SignedTransaction) -> Result<(), BasicError> {
+        let (mempool_status, vm_status_opt) = self
+            .context
+            .submit_transaction(txn)
+            .await
+            .context("Mempool failed to initially evaluate submitted transaction")
+            .map_err(|err| BasicError::internal_with_code(err, AptosErrorCode::InternalError))?;
+        match mempool_status.code {
+            MempoolStatusCode::Accepted => Ok(()),
+            MempoolStatusCode::MempoolIsFull => Err(BasicError::internal_with_code(
+                &mempool_status.message,
+                AptosErrorCode::MempoolIsFull,
+            )),
+            _ => Err(BasicError::internal_with_code(
+                format!(
+                    "The faucet's transaction was rejected: {:?} {}{}",
+                    mempool_status.code,
+                    mempool_status.message,
+                    vm_status_opt
+                        .map(|status_code| format!(", VM status: {:?}", status_code))
+                        .unwrap_or_default(),
+                ),
+                AptosErrorCode::InternalError,
+            )),
+        }
+    }
+}
+
+/// Whether the faucet is enabled, which it never is on mainnet.
+pub fn faucet_enabled(context: &Context, config: &FaucetConfig) -> bool {
+    config.enabled && context.chain_id().id() != NamedChain::MAINNET.id()
+}
+
+/// The private key of the mint account, from the config or the key file it
+/// points to.
+fn mint_key(config: &FaucetConfig) -> anyhow::Result<Ed25519PrivateKey> {
+    if let Some(mint_key) = &config.mint_key {
+        return Ok(mint_key.private_key());
+    }
+    let path = config
+        .mint_key_file_path
+        .as_ref()
+        .ok_or_else(|| format_err!("The faucet has no mint key configured"))?;
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read the faucet's mint key from {:?}", path))?;
+    bcs::from_bytes(&bytes).with_context(|| {
+        format!(
+            "Failed to deserialize the faucet's mint key from {:?}",
+            path
+        )
+    })
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::BlockFeeStats;
+use aptos_infallible::Mutex;
+use lru::LruCache;
+
+/// A bounded cache of the fee statistics of blocks by height, so that
+/// requesting them again, e.g. as explorers poll recent blocks, skips reading
+/// the transactions of the block. Committed blocks are final, so the entries
+/// never go stale.
+///
+/// When the cache is full, the least recently used block is evicted.
+pub struct FeeStatsCache {
+    stats: Mutex<LruCache<u64, BlockFeeStats>>,
+}
+
+impl FeeStatsCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            stats: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, block_height: u64) -> Option<BlockFeeStats> {
+        self.stats.lock().get(&block_height).cloned()
+    }
+
+    pub fn insert(&self, block_height: u64, stats: BlockFeeStats) {
+        let mut cached = self.stats.lock();
+        if cached.cap() > 0 {
+            cached.put(block_height, stats);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FeeStatsCache;
+    use aptos_api_types::BlockFeeStats;
+
+    fn stats() -> BlockFeeStats {
+        BlockFeeStats::new(vec![])
+    }
+
+    #[test]
+    fn test_disabled_cache() {
+        let cache = FeeStatsCache::new(0);
+        cache.insert(1, stats());
+        assert!(cache.get(1).is_none());
+    }
+}
@@ -2,12 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::accept_type::AcceptType;
 use crate::context::Context;
-use crate::response::{BasicResponse, BasicResponseStatus, BasicResult};
+use crate::faucet::faucet_enabled;
+use crate::response::{BasicError, BasicResponse, BasicResponseStatus, BasicResult, InternalError};
+use crate::runtime::VERSION;
 use crate::ApiTags;
-use aptos_api_types::IndexResponse;
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{AptosErrorCode, IndexResponse, LedgerInfo, NodeHealth, U64};
+use poem_openapi::param::Query;
 use poem_openapi::OpenApi;
 
 pub struct IndexApi {
@@ -20,17 +25,31 @@ impl IndexApi {
     ///
     /// Get the latest ledger information, including data such as chain ID,
     /// role type, ledger versions, epoch, etc.
+    ///
+    /// With `with_health=true`, the response includes the health of the
+    /// node: the size and capacity of its mempool, how far it is behind the
+    /// chain, the version of the API it runs and the optional features of
+    /// the API it has enabled. Without it, this endpoint stays as cheap as it
+    /// is for use as a ping.
     #[oai(
         path = "/",
         method = "get",
         operation_id = "get_ledger_info",
         tag = "ApiTags::General"
     )]
-    async fn get_ledger_info(&self, accept_type: AcceptType) -> BasicResult<IndexResponse> {
+    async fn get_ledger_info(
+        &self,
+        accept_type: AcceptType,
+        /// If true, include the health of the node
+        with_health: Query<Option<bool>>,
+    ) -> BasicResult<IndexResponse> {
         let ledger_info = self.context.get_latest_ledger_info()?;
 
         let node_role = self.context.node_role();
-        let index_response = IndexResponse::new(ledger_info.clone(), node_role);
+        let mut index_response = IndexResponse::new(ledger_info.clone(), node_role);
+        if with_health.0.unwrap_or(false) {
+            index_response = index_response.with_health(self.node_health(&ledger_info).await?);
+        }
 
         BasicResponse::try_from_rust_value((
             index_response,
@@ -40,3 +59,41 @@ impl IndexApi {
         ))
     }
 }
+
+impl IndexApi {
+    async fn node_health(&self, ledger_info: &LedgerInfo) -> Result<NodeHealth, BasicError> {
+        let mempool_size = self
+            .context
+            .get_mempool_size()
+            .await
+            .context("Failed to get the size of mempool")
+            .map_err(|err| BasicError::internal_with_code(err, AptosErrorCode::InternalError))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let state_sync_lag = now.saturating_sub(Duration::from_micros(ledger_info.timestamp()));
+
+        let api_config = self.context.api_config();
+        let mut features = Vec::new();
+        if api_config.developer_api_enabled {
+            features.push("developer_api".to_string());
+        }
+        if faucet_enabled(&self.context, &api_config.faucet) {
+            features.push("faucet".to_string());
+        }
+        if api_config.config_reload_endpoint_enabled {
+            features.push("config_reload".to_string());
+        }
+        if api_config.failpoints_enabled {
+            features.push("failpoints".to_string());
+        }
+
+        Ok(NodeHealth {
+            mempool_size: U64::from(mempool_size.num_txns as u64),
+            mempool_capacity: U64::from(mempool_size.capacity as u64),
+            state_sync_lag_usecs: U64::from(state_sync_lag.as_micros() as u64),
+            api_version: VERSION.trim().to_string(),
+            features,
+        })
+    }
+}
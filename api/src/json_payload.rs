@@ -0,0 +1,86 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines a Poem payload type for JSON request bodies that, unlike
+//! the native Json payload, reports which field of the body failed to parse.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::check_size::{read_body_within_limit, PayloadError};
+use crate::response::{AptosErrorResponse, BadRequestError};
+use aptos_api_types::AptosErrorCode;
+use poem::{Request, RequestBody, Result};
+use poem_openapi::{
+    impl_apirequest_for_payload,
+    payload::{ParsePayload, Payload},
+    registry::{MetaSchemaRef, Registry},
+    types::Type,
+};
+use serde::de::DeserializeOwned;
+
+pub const CONTENT_TYPE: &str = "application/json";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct JsonWithPath<T>(pub T);
+
+impl<T> Deref for JsonWithPath<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for JsonWithPath<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: Type> Payload for JsonWithPath<T> {
+    const CONTENT_TYPE: &'static str = CONTENT_TYPE;
+
+    fn check_content_type(content_type: &str) -> bool {
+        matches!(content_type.parse::<mime::Mime>(), Ok(content_type)
+            if content_type.essence_str() == CONTENT_TYPE)
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        T::schema_ref()
+    }
+
+    fn register(registry: &mut Registry) {
+        T::register(registry);
+    }
+}
+
+#[poem::async_trait]
+impl<T: Type + DeserializeOwned> ParsePayload for JsonWithPath<T> {
+    const IS_REQUIRED: bool = true;
+
+    async fn from_request(request: &Request, body: &mut RequestBody) -> Result<Self> {
+        let data = read_body_within_limit(request, body).await?;
+        let mut deserializer = serde_json::Deserializer::from_slice(&data);
+        let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+            let field = err.path().to_string();
+            PayloadError::bad_request_with_code(
+                format!(
+                    "Failed to parse the request body at {}: {}",
+                    field,
+                    err.inner()
+                ),
+                AptosErrorCode::InvalidInput,
+            )
+            .detail("field", field)
+        })?;
+        deserializer.end().map_err(|err| {
+            PayloadError::bad_request_with_code(
+                format!("Failed to parse the request body: {}", err),
+                AptosErrorCode::InvalidInput,
+            )
+        })?;
+        Ok(Self(value))
+    }
+}
+
+impl_apirequest_for_payload!(JsonWithPath<T>, T: Type + DeserializeOwned);
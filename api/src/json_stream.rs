@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines a Poem payload type for lists of items streamed as
+//! JSON, either as a JSON array or as newline delimited JSON (NDJSON), with
+//! chunked transfer encoding.
+//!
+//! The items are serialized as they are produced, a chunk at a time, so the
+//! memory held for a response is bounded by the chunk rather than the list.
+//! Since the status and headers go out before the items are read, a failure
+//! midway can't be reported as an error response. The stream is aborted
+//! instead, so the client sees a truncated body rather than a valid one
+//! missing items.
+
+use bytes::Bytes;
+use futures::stream;
+use poem::{http::header, Body, IntoResponse, Response};
+use poem_openapi::{
+    payload::Payload,
+    registry::{MetaSchemaRef, Registry},
+    types::Type,
+};
+use serde::Serialize;
+use std::io;
+use tokio::sync::mpsc;
+
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// How many chunks of serialized items may wait to be sent to the client.
+const CHANNEL_CAPACITY: usize = 1;
+
+/// A streamed list of items. For the OpenAPI spec this is the NDJSON
+/// representation, the JSON array streamed to clients asking for JSON has
+/// the schema of the list itself.
+pub struct JsonStream {
+    body: Body,
+    ndjson: bool,
+}
+
+impl Payload for JsonStream {
+    const CONTENT_TYPE: &'static str = CONTENT_TYPE;
+
+    fn schema_ref() -> MetaSchemaRef {
+        String::schema_ref()
+    }
+
+    #[allow(unused_variables)]
+    fn register(registry: &mut Registry) {
+        String::register(registry);
+    }
+}
+
+impl IntoResponse for JsonStream {
+    fn into_response(self) -> Response {
+        let content_type = if self.ndjson {
+            CONTENT_TYPE
+        } else {
+            "application/json; charset=utf-8"
+        };
+        Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .body(self.body)
+    }
+}
+
+/// Creates a stream of items, which are sent with the returned sender. The
+/// items are serialized as NDJSON if `ndjson` is set, else as a JSON array.
+pub fn json_stream(ndjson: bool) -> (JsonStreamSender, JsonStream) {
+    let (sender, body) = chunked_body();
+    (
+        JsonStreamSender {
+            sender,
+            ndjson,
+            started: false,
+        },
+        JsonStream { body, ndjson },
+    )
+}
+
+/// Creates a body that is sent with chunked transfer encoding, a chunk for
+/// each one passed to the returned sender. An error ends the body early.
+pub(crate) fn chunked_body() -> (mpsc::Sender<io::Result<Bytes>>, Body) {
+    let (sender, receiver) = mpsc::channel::<io::Result<Bytes>>(CHANNEL_CAPACITY);
+    let body = Body::from_bytes_stream(stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    }));
+    (sender, body)
+}
+
+/// Sends items to a `JsonStream`, blocking while the client catches up. It is
+/// meant to be driven from a blocking task, e.g. one spawned with
+/// `tokio::task::spawn_blocking`.
+pub struct JsonStreamSender {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+    ndjson: bool,
+    /// Whether any items have been sent, i.e. whether a JSON array has been
+    /// opened and the next item needs a separating comma.
+    started: bool,
+}
+
+impl JsonStreamSender {
+    /// Serializes the items and sends them as one chunk. Fails if they can't
+    /// be serialized, or if the client went away, in which case there is no
+    /// point producing more of them.
+    pub fn send<T: Serialize>(&mut self, items: &[T]) -> anyhow::Result<()> {
+        let mut chunk = vec![];
+        for item in items {
+            if !self.ndjson {
+                chunk.push(if self.started { b',' } else { b'[' });
+            }
+            self.started = true;
+            serde_json::to_writer(&mut chunk, item)?;
+            if self.ndjson {
+                chunk.push(b'\n');
+            }
+        }
+        self.send_chunk(Ok(chunk.into()))
+    }
+
+    /// Ends the stream once all the items have been sent.
+    pub fn finish(self) -> anyhow::Result<()> {
+        if self.ndjson {
+            return Ok(());
+        }
+        let end: &'static [u8] = if self.started { b"]" } else { b"[]" };
+        self.send_chunk(Ok(Bytes::from_static(end)))
+    }
+
+    /// Aborts the stream, since the items could not all be produced.
+    pub fn abort(self, error: anyhow::Error) {
+        let _ = self.send_chunk(Err(io::Error::new(io::ErrorKind::Other, error)));
+    }
+
+    fn send_chunk(&self, chunk: io::Result<Bytes>) -> anyhow::Result<()> {
+        self.sender
+            .blocking_send(chunk)
+            .map_err(|_| anyhow::anyhow!("The client stopped reading the stream"))
+    }
+}
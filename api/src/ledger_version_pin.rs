@@ -0,0 +1,129 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use crate::generate_error_response;
+use crate::response::{AptosErrorResponse, BadRequestError, InternalError, NotFoundError};
+use aptos_api_types::{AptosErrorCode, X_APTOS_LEDGER_VERSION_PIN};
+use poem::{http::Uri, Endpoint, IntoResponse, Middleware, Request, Result};
+use std::sync::Arc;
+
+/// This middleware pins the requests with an `X-Aptos-Ledger-Version-Pin`
+/// header to the ledger version it holds, so that a client making several
+/// related reads gets them all at the same version. The version is passed to
+/// the endpoints as their `ledger_version` param, unless the request sets one
+/// itself, so endpoints without that param are not affected. Pins to versions
+/// ahead of the ledger or already pruned are rejected.
+pub struct LedgerVersionPin {
+    context: Arc<Context>,
+}
+
+impl LedgerVersionPin {
+    pub fn new(context: Arc<Context>) -> Self {
+        Self { context }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for LedgerVersionPin {
+    type Output = LedgerVersionPinEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        LedgerVersionPinEndpoint {
+            inner: ep,
+            context: self.context.clone(),
+        }
+    }
+}
+
+/// Endpoint for LedgerVersionPin middleware.
+pub struct LedgerVersionPinEndpoint<E> {
+    inner: E,
+    context: Arc<Context>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for LedgerVersionPinEndpoint<E> {
+    type Output = E::Output;
+
+    async fn call(&self, mut req: Request) -> Result<Self::Output> {
+        let pin = match req.headers().get(X_APTOS_LEDGER_VERSION_PIN) {
+            Some(pin) => pin,
+            None => return self.inner.call(req).await,
+        };
+        let version = pin
+            .to_str()
+            .ok()
+            .and_then(|pin| pin.parse::<u64>().ok())
+            .ok_or_else(|| {
+                LedgerVersionPinError::bad_request_with_code(
+                    format!(
+                        "The {} header must be a ledger version",
+                        X_APTOS_LEDGER_VERSION_PIN
+                    ),
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+
+        let ledger_info = self
+            .context
+            .get_latest_ledger_info::<LedgerVersionPinError>()?;
+        if version > ledger_info.version() {
+            return Err(LedgerVersionPinError::not_found_with_code(
+                format!("Pinned ledger version {} is ahead of the ledger", version),
+                AptosErrorCode::VersionNotFound,
+            )
+            .aptos_ledger_version(ledger_info.version())
+            .into());
+        }
+        if version < ledger_info.oldest_ledger_version.0 {
+            return Err(LedgerVersionPinError::not_found_with_code(
+                format!("Pinned ledger version {} has been pruned", version),
+                AptosErrorCode::VersionPruned,
+            )
+            .aptos_ledger_version(ledger_info.version())
+            .detail("oldest_ledger_version", ledger_info.oldest_ledger_version)
+            .into());
+        }
+
+        let query = req.uri().query().unwrap_or_default();
+        let sets_ledger_version = query
+            .split('&')
+            .any(|param| param.split('=').next() == Some("ledger_version"));
+        if !sets_ledger_version {
+            let query = if query.is_empty() {
+                format!("ledger_version={}", version)
+            } else {
+                format!("{}&ledger_version={}", query, version)
+            };
+            let internal = |e: &dyn std::fmt::Display| {
+                LedgerVersionPinError::internal_with_code(
+                    format!("Failed to pin the request: {}", e),
+                    AptosErrorCode::InternalError,
+                )
+            };
+            let mut parts = req.uri().clone().into_parts();
+            parts.path_and_query = Some(
+                format!("{}?{}", req.uri().path(), query)
+                    .parse()
+                    .map_err(|e| internal(&e))?,
+            );
+            *req.uri_mut() = Uri::from_parts(parts).map_err(|e| internal(&e))?;
+        }
+
+        self.inner.call(req).await
+    }
+}
+
+// The errors of pinning a request, before it reaches the endpoint.
+generate_error_response!(
+    LedgerVersionPinError,
+    (400, BadRequest),
+    (404, NotFound),
+    (500, Internal)
+);
+
+impl From<LedgerVersionPinError> for poem::Error {
+    fn from(err: LedgerVersionPinError) -> Self {
+        poem::Error::from_response(err.into_response())
+    }
+}
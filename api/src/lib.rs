@@ -7,23 +7,41 @@ mod accept_type;
 mod accounts;
 mod basic;
 mod bcs_payload;
+mod bcs_stream;
 mod blocks;
 mod check_size;
 pub mod context;
+mod cors;
+mod developer;
 mod error_converter;
 mod events;
 mod failpoint;
+mod faucet;
+mod fee_stats_cache;
 mod index;
+mod json_payload;
+mod json_stream;
+mod ledger_version_pin;
 mod log;
 pub mod metrics;
+mod move_unit_test;
+mod multisig;
+mod multisig_sessions;
 mod page;
+mod reload_config;
+mod request_trace;
 mod response;
 mod runtime;
+mod script_composer;
 mod set_failpoints;
+mod signature;
+mod simulation_cache;
+mod staking;
 mod state;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
+mod vm_status;
 
 #[derive(Tags)]
 pub enum ApiTags {
@@ -32,12 +50,24 @@ pub enum ApiTags {
     /// Access to blocks
     Blocks,
 
+    /// Tools for developing Move packages
+    Developer,
+
     /// Access to events
     Events,
 
+    /// Funding of accounts on test networks
+    Faucet,
+
     /// General information
     General,
 
+    /// Signing of transactions of MultiEd25519 accounts
+    Multisig,
+
+    /// Access to stake pools
+    Staking,
+
     /// Access to tables
     Tables,
 
@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 
-use crate::metrics::{HISTOGRAM, RESPONSE_STATUS};
+use crate::metrics::{label_value, Label, ERROR_CODES, HISTOGRAM, RESPONSE_SIZE, RESPONSE_STATUS};
 use aptos_logger::{
     debug, error,
     prelude::{sample, SampleRate},
@@ -39,7 +39,7 @@ pub async fn middleware_log<E: Endpoint>(next: E, request: Request) -> Result<Re
             .and_then(|v| v.to_str().ok().map(|v| v.to_string())),
     };
 
-    let response = next.get_response(request).await;
+    let mut response = next.get_response(request).await;
 
     let elapsed = start.elapsed();
 
@@ -52,26 +52,58 @@ pub async fn middleware_log<E: Endpoint>(next: E, request: Request) -> Result<Re
         sample!(SampleRate::Duration(Duration::from_secs(1)), debug!(log));
     }
 
+    let status = log.status.to_string();
+    let status = label_value(Label::Status, &status);
+    let operation_id = label_value(
+        Label::OperationId,
+        response
+            .data::<OperationId>()
+            .map(|operation_id| operation_id.0)
+            .unwrap_or("operation_id_not_set"),
+    );
+
     // Log response statuses generally.
     RESPONSE_STATUS
-        .with_label_values(&[log.status.to_string().as_str()])
+        .with_label_values(&[status])
         .observe(elapsed.as_secs_f64());
 
     // Log response status per-endpoint + method.
     HISTOGRAM
-        .with_label_values(&[
-            log.method.as_str(),
-            response
-                .data::<OperationId>()
-                .map(|operation_id| operation_id.0)
-                .unwrap_or("operation_id_not_set"),
-            log.status.to_string().as_str(),
-        ])
+        .with_label_values(&[log.method.as_str(), operation_id, status])
         .observe(elapsed.as_secs_f64());
 
+    // The body is buffered to measure it, which is fine since none of the endpoints stream.
+    let body = response.take_body().into_bytes().await?;
+    let format = response_format(&response);
+    RESPONSE_SIZE
+        .with_label_values(&[operation_id, format])
+        .observe(body.len() as f64);
+    if log.status >= 400 && format == "json" {
+        if let Some(error_code) = serde_json::from_slice::<serde_json::Value>(&body)
+            .ok()
+            .as_ref()
+            .and_then(|error| error.get("error_code"))
+            .and_then(|error_code| error_code.as_str())
+        {
+            ERROR_CODES
+                .with_label_values(&[operation_id, label_value(Label::ErrorCode, error_code)])
+                .inc();
+        }
+    }
+    response.set_body(body);
+
     Ok(response)
 }
 
+/// The format of the response body, going by its content type: `bcs`, `json` or `other`.
+fn response_format(response: &Response) -> &'static str {
+    match response.content_type() {
+        Some(content_type) if content_type.ends_with("bcs") => "bcs",
+        Some(content_type) if content_type.contains("json") => "json",
+        _ => "other",
+    }
+}
+
 // TODO: Figure out how to have certain fields be borrowed, like in the
 // original implementation.
 #[derive(Schema)]
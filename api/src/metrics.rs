@@ -1,8 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_histogram_vec, HistogramVec};
+use aptos_infallible::Mutex;
+use aptos_metrics_core::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
+    IntCounterVec,
+};
 use once_cell::sync::Lazy;
+use std::collections::HashSet;
+
+/// The most values a label of the API metrics takes. Further values are recorded as
+/// `OTHER_LABEL_VALUE`, so that unexpected values, e.g. the paths of requests no endpoint
+/// matches, can't blow up the number of series the node exports.
+const MAX_LABEL_VALUES: usize = 256;
+
+pub const OTHER_LABEL_VALUE: &str = "other";
 
 pub static HISTOGRAM: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -21,3 +33,95 @@ pub static RESPONSE_STATUS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static RESPONSE_SIZE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_api_response_size_bytes",
+        "Size of API response bodies grouped by operation_id and format",
+        &["operation_id", "format"],
+        exponential_buckets(/*start=*/ 64.0, /*factor=*/ 4.0, /*count=*/ 12).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static ERROR_CODES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_error_codes",
+        "Number of API errors grouped by operation_id and error_code",
+        &["operation_id", "error_code"]
+    )
+    .unwrap()
+});
+
+pub static SIMULATION_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_simulation_cache",
+        "Number of simulations grouped by whether their result was cached",
+        &["result"]
+    )
+    .unwrap()
+});
+
+/// The label values recorded so far per label, shared by all the API metrics.
+static LABEL_GUARDS: Lazy<[LabelGuard; 3]> = Lazy::new(|| {
+    [
+        LabelGuard::new(MAX_LABEL_VALUES),
+        LabelGuard::new(MAX_LABEL_VALUES),
+        LabelGuard::new(MAX_LABEL_VALUES),
+    ]
+});
+
+/// The labels whose values come from requests or responses, rather than from a fixed set.
+#[derive(Clone, Copy)]
+pub enum Label {
+    OperationId = 0,
+    Status = 1,
+    ErrorCode = 2,
+}
+
+/// Returns the value to record for the label, `OTHER_LABEL_VALUE` once the label took too many
+/// values.
+pub fn label_value(label: Label, value: &str) -> &str {
+    LABEL_GUARDS[label as usize].value(value)
+}
+
+struct LabelGuard {
+    max_values: usize,
+    values: Mutex<HashSet<String>>,
+}
+
+impl LabelGuard {
+    fn new(max_values: usize) -> Self {
+        Self {
+            max_values,
+            values: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn value<'a>(&self, value: &'a str) -> &'a str {
+        let mut values = self.values.lock();
+        if values.contains(value) {
+            value
+        } else if values.len() < self.max_values {
+            values.insert(value.to_string());
+            value
+        } else {
+            OTHER_LABEL_VALUE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LabelGuard, OTHER_LABEL_VALUE};
+
+    #[test]
+    fn test_label_guard() {
+        let guard = LabelGuard::new(2);
+        assert_eq!(guard.value("a"), "a");
+        assert_eq!(guard.value("b"), "b");
+        assert_eq!(guard.value("c"), OTHER_LABEL_VALUE);
+        // Values recorded before the limit was reached are still recorded.
+        assert_eq!(guard.value("a"), "a");
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compiles a Move package from its sources and runs its unit tests, for the
+//! developer API.
+//!
+//! The package is compiled against the sources of the framework the node was
+//! built with, which the head release bundle carries, so the node doesn't
+//! need a checkout of the framework. Unlike the Move CLI, nothing here exits
+//! the process on a compilation error: diagnostics are returned instead.
+//!
+//! Coverage comes from the Move VM's execution trace. The VM only writes it
+//! when the `MOVE_VM_TRACE` environment variable is set at startup, and the
+//! trace file is shared by every execution in the process, so runs are
+//! serialized and only the part of the trace a run appended is read back.
+
+use anyhow::{format_err, Context as AnyhowContext, Result};
+use aptos_api_types::{
+    MoveFunctionCoverage, MoveUnitTestOutcome, MoveUnitTestRequest, MoveUnitTestResult,
+    MoveUnitTestStatus,
+};
+use aptos_gas::NativeGasParameters;
+use aptos_vm::natives;
+use move_deps::move_binary_format::access::ModuleAccess;
+use move_deps::move_binary_format::CompiledModule;
+use move_deps::move_command_line_common::address::{NumberFormat, NumericalAddress};
+use move_deps::move_compiler::compiled_unit::AnnotatedCompiledUnit;
+use move_deps::move_compiler::diagnostics::{report_diagnostics_to_buffer, FilesSourceText};
+use move_deps::move_compiler::shared::Flags;
+use move_deps::move_compiler::unit_test::plan_builder::construct_test_plan;
+use move_deps::move_compiler::{Compiler, PASS_CFGIR};
+use move_deps::move_core_types::account_address::AccountAddress;
+use move_deps::move_core_types::identifier::Identifier;
+use move_deps::move_core_types::language_storage::ModuleId;
+use move_deps::move_unit_test::{TestPlan, UnitTestingConfig};
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The maximum number of instructions a single test may execute.
+const INSTRUCTION_LIMIT: u64 = 100_000;
+
+/// The environment variable holding the path of the Move VM trace.
+const MOVE_VM_TRACE_ENV_VAR: &str = "MOVE_VM_TRACE";
+
+/// Serializes runs, as they share the VM trace and the native extension hook.
+static RUN_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug)]
+pub enum MoveUnitTestError {
+    /// The request can't describe a package, e.g. a file name is not valid.
+    InvalidInput(String),
+    /// The package failed to compile, with the compiler's diagnostics.
+    Compilation(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for MoveUnitTestError {
+    fn from(err: anyhow::Error) -> Self {
+        MoveUnitTestError::Internal(err)
+    }
+}
+
+/// Compiles the package of the request in test mode and runs its unit tests.
+/// This blocks for as long as the tests run, and while any other run is in
+/// progress.
+pub fn run_move_unit_tests(
+    request: MoveUnitTestRequest,
+) -> Result<MoveUnitTestResult, MoveUnitTestError> {
+    let _guard = RUN_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let dir = tempfile::tempdir().context("Failed to create package directory")?;
+    let sources = write_sources(&request, dir.path())?;
+    let deps = write_framework_sources(&dir.path().join("framework"))?;
+
+    let mut named_addresses = framework::named_addresses().clone();
+    for named_address in &request.named_addresses {
+        named_addresses.insert(
+            named_address.name.clone(),
+            NumericalAddress::new(
+                named_address.address.inner().into_bytes(),
+                NumberFormat::Hex,
+            ),
+        );
+    }
+
+    let (test_plan, functions) = build_test_plan(sources, deps, named_addresses)?;
+
+    let trace = match std::env::var(MOVE_VM_TRACE_ENV_VAR) {
+        Ok(path) => Some(TraceWindow::start(PathBuf::from(path))?),
+        Err(_) => None,
+    };
+
+    let config = UnitTestingConfig {
+        filter: request.filter,
+        ..UnitTestingConfig::default_with_bound(Some(INSTRUCTION_LIMIT))
+    };
+    let native_function_table = natives::aptos_natives(NativeGasParameters::zeros());
+    natives::configure_for_unit_test();
+    let (output, success) = config
+        .run_and_report_unit_tests(test_plan, Some(native_function_table), Vec::new())
+        .map_err(|err| format_err!("Failed to run unit tests: {}", err))?;
+
+    let coverage = match trace {
+        Some(trace) => Some(function_coverage(&functions, &trace.executed_pcs()?)),
+        None => None,
+    };
+
+    Ok(MoveUnitTestResult {
+        success,
+        tests: parse_test_outcomes(&String::from_utf8_lossy(&output)),
+        coverage,
+    })
+}
+
+/// Writes the sources of the request into `dir`, returning their paths.
+fn write_sources(
+    request: &MoveUnitTestRequest,
+    dir: &Path,
+) -> Result<Vec<String>, MoveUnitTestError> {
+    if request.sources.is_empty() {
+        return Err(MoveUnitTestError::InvalidInput(
+            "The package has no sources".to_string(),
+        ));
+    }
+    let mut paths = vec![];
+    for file in &request.sources {
+        // File names become paths, so only allow plain names.
+        if file.name.is_empty()
+            || !file
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(MoveUnitTestError::InvalidInput(format!(
+                "Invalid source file name '{}', expected only letters, digits, '_' and '-'",
+                file.name
+            )));
+        }
+        let path = dir.join(format!("{}.move", file.name));
+        if path.exists() {
+            return Err(MoveUnitTestError::InvalidInput(format!(
+                "Duplicate source file name '{}'",
+                file.name
+            )));
+        }
+        std::fs::write(&path, &file.source)
+            .with_context(|| format!("Failed to write source file {}", file.name))?;
+        paths.push(path.display().to_string());
+    }
+    Ok(paths)
+}
+
+/// Writes the sources of every package of the head release bundle into
+/// `dir`, returning their paths.
+fn write_framework_sources(dir: &Path) -> Result<Vec<String>> {
+    let mut paths = vec![];
+    for package in &framework::head_release_bundle().packages {
+        let metadata = package.package_metadata();
+        let package_dir = dir.join(&metadata.name);
+        std::fs::create_dir_all(&package_dir)?;
+        for module in &metadata.modules {
+            let source = framework::unzip_metadata(&module.source)
+                .with_context(|| format!("Failed to unzip the source of {}", module.name))?;
+            let path = package_dir.join(format!("{}.move", module.name));
+            std::fs::write(&path, source)?;
+            paths.push(path.display().to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// The functions of the compiled package, with the number of instructions in
+/// each, for computing coverage.
+type FunctionSizes = BTreeMap<(ModuleId, Identifier), u64>;
+
+/// Compiles the package in test mode, the way the Move CLI does, but returns
+/// any diagnostics rather than reporting them and exiting.
+fn build_test_plan(
+    sources: Vec<String>,
+    deps: Vec<String>,
+    named_addresses: BTreeMap<String, NumericalAddress>,
+) -> Result<(TestPlan, FunctionSizes), MoveUnitTestError> {
+    let (files, comments_and_compiler_res) = Compiler::from_files(sources, deps, named_addresses)
+        .set_flags(Flags::testing())
+        .run::<PASS_CFGIR>()
+        .map_err(|err| format_err!("Failed to compile the package: {}", err))?;
+    let (_, compiler) =
+        comments_and_compiler_res.map_err(|diags| compilation_error(&files, diags))?;
+
+    let (mut compiler, cfgir) = compiler.into_ast();
+    let compilation_env = compiler.compilation_env();
+    let tests = construct_test_plan(compilation_env, None, &cfgir);
+    let (units, _warnings) = compiler
+        .at_cfgir(cfgir)
+        .build()
+        .map_err(|diags| compilation_error(&files, diags))?;
+
+    let functions = units
+        .iter()
+        .filter_map(|unit| match unit {
+            AnnotatedCompiledUnit::Module(module) => Some(&module.named_module.module),
+            AnnotatedCompiledUnit::Script(_) => None,
+        })
+        .flat_map(function_sizes)
+        .collect();
+    Ok((
+        TestPlan::new(tests.unwrap_or_default(), files, units),
+        functions,
+    ))
+}
+
+fn compilation_error(
+    files: &FilesSourceText,
+    diags: move_deps::move_compiler::diagnostics::Diagnostics,
+) -> MoveUnitTestError {
+    MoveUnitTestError::Compilation(
+        String::from_utf8_lossy(&report_diagnostics_to_buffer(files, diags)).to_string(),
+    )
+}
+
+fn function_sizes(module: &CompiledModule) -> Vec<((ModuleId, Identifier), u64)> {
+    module
+        .function_defs()
+        .iter()
+        .filter_map(|function| {
+            let code = function.code.as_ref()?;
+            let handle = module.function_handle_at(function.function);
+            Some((
+                (
+                    module.self_id(),
+                    module.identifier_at(handle.name).to_owned(),
+                ),
+                code.code.len() as u64,
+            ))
+        })
+        .collect()
+}
+
+fn function_coverage(
+    functions: &FunctionSizes,
+    executed_pcs: &BTreeMap<(ModuleId, Identifier), BTreeSet<u64>>,
+) -> Vec<MoveFunctionCoverage> {
+    functions
+        .iter()
+        .map(|((module_id, function), total)| MoveFunctionCoverage {
+            module: module_id.clone().into(),
+            function: function.clone().into(),
+            covered_instructions: executed_pcs
+                .get(&(module_id.clone(), function.clone()))
+                .map_or(0, |pcs| pcs.len() as u64)
+                .into(),
+            total_instructions: (*total).into(),
+        })
+        .collect()
+}
+
+/// The part of the VM trace written after it was started.
+struct TraceWindow {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl TraceWindow {
+    fn start(path: PathBuf) -> Result<Self> {
+        let offset = std::fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        Ok(Self { path, offset })
+    }
+
+    /// The instructions executed in every function since the window started.
+    /// Each line of the trace is `<address>::<module>::<function>,<pc>,...`.
+    fn executed_pcs(&self) -> Result<BTreeMap<(ModuleId, Identifier), BTreeSet<u64>>> {
+        let mut file = std::fs::File::open(&self.path)
+            .with_context(|| format!("Failed to open the VM trace {}", self.path.display()))?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut trace = String::new();
+        file.read_to_string(&mut trace)?;
+
+        let mut executed_pcs: BTreeMap<_, BTreeSet<u64>> = BTreeMap::new();
+        for line in trace.lines() {
+            let mut fields = line.split(',');
+            let (context, pc) = match (fields.next(), fields.next()) {
+                (Some(context), Some(pc)) => (context, pc),
+                _ => continue,
+            };
+            let segments: Vec<&str> = context.split("::").collect();
+            // Scripts have no module, and aren't part of a package.
+            if let [address, module, function] = segments[..] {
+                let address = AccountAddress::from_hex_literal(address)
+                    .or_else(|_| AccountAddress::from_hex(address));
+                if let (Ok(address), Ok(module), Ok(function), Ok(pc)) = (
+                    address,
+                    Identifier::new(module),
+                    Identifier::new(function),
+                    pc.parse::<u64>(),
+                ) {
+                    executed_pcs
+                        .entry((ModuleId::new(address, module), function))
+                        .or_default()
+                        .insert(pc);
+                }
+            }
+        }
+        Ok(executed_pcs)
+    }
+}
+
+/// Parses the outcome of each test from the report of the unit test runner,
+/// which lists every test as `[ PASS    ] <name>` and then, for each failed
+/// test, a box with the reason it failed:
+///
+/// ```text
+/// Failures in 0x1::coin:
+///
+/// ┌── test_transfer ──────
+/// │ error[E11001]: test failure
+/// │ ...
+/// └──────────────────
+/// ```
+fn parse_test_outcomes(report: &str) -> Vec<MoveUnitTestOutcome> {
+    let report = strip_ansi_codes(report);
+    let mut outcomes = vec![];
+    let mut failures = BTreeMap::new();
+    let mut module = None;
+    let mut failure: Option<(String, Vec<&str>)> = None;
+
+    for line in report.lines() {
+        if let Some(rest) = line.strip_prefix('[') {
+            if let Some((status, name)) = rest.split_once(']') {
+                let status = match status.trim() {
+                    "PASS" => MoveUnitTestStatus::Pass,
+                    "FAIL" => MoveUnitTestStatus::Fail,
+                    "TIMEOUT" => MoveUnitTestStatus::Timeout,
+                    _ => continue,
+                };
+                outcomes.push(MoveUnitTestOutcome {
+                    name: name.trim().to_string(),
+                    status,
+                    failure: None,
+                });
+            }
+        } else if let Some(name) = line
+            .strip_prefix("Failures in ")
+            .and_then(|rest| rest.strip_suffix(':'))
+        {
+            module = Some(name.to_string());
+        } else if let Some(rest) = line.strip_prefix("┌──") {
+            let test = rest.trim().trim_end_matches('─').trim();
+            let name = match &module {
+                Some(module) => format!("{}::{}", module, test),
+                None => test.to_string(),
+            };
+            failure = Some((name, vec![]));
+        } else if line.starts_with('└') {
+            if let Some((name, lines)) = failure.take() {
+                failures.insert(name, lines.join("\n").trim().to_string());
+            }
+        } else if let Some((_, lines)) = &mut failure {
+            lines.push(
+                line.strip_prefix('│')
+                    .map_or(line, |line| line.strip_prefix(' ').unwrap_or(line)),
+            );
+        }
+    }
+
+    for outcome in &mut outcomes {
+        outcome.failure = failures.remove(&outcome.name);
+    }
+    outcomes
+}
+
+/// Removes the color codes the runner may add to its report.
+fn strip_ansi_codes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip the control sequence up to and including its final byte.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_outcomes() {
+        let report = "\
+Running Move unit tests
+[ PASS    ] 0xcafe::counter::test_increment
+[ FAIL    ] 0xcafe::counter::test_overflow
+\u{1b}[31m[ TIMEOUT ]\u{1b}[0m 0xcafe::counter::test_loop
+Test failures:
+
+Failures in 0xcafe::counter:
+
+┌── test_overflow ──────
+│ error[E11001]: test failure
+│    ┌─ counter.move:12:9
+│    │
+│ 12 │         assert!(value < 10, 1);
+│    │         ^^^^^^^^^^^^^^^^^^^^^^ Test was not expected to abort but it aborted with 1 here
+│
+│
+└──────────────────
+
+Test result: FAILED. Total tests: 3; passed: 1; failed: 2
+";
+        let outcomes = parse_test_outcomes(report);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[0].name, "0xcafe::counter::test_increment");
+        assert_eq!(outcomes[0].status, MoveUnitTestStatus::Pass);
+        assert_eq!(outcomes[0].failure, None);
+        assert_eq!(outcomes[1].status, MoveUnitTestStatus::Fail);
+        let failure = outcomes[1].failure.as_ref().unwrap();
+        assert!(failure.starts_with("error[E11001]: test failure"));
+        assert!(failure.contains("aborted with 1 here"));
+        assert_eq!(outcomes[2].name, "0xcafe::counter::test_loop");
+        assert_eq!(outcomes[2].status, MoveUnitTestStatus::Timeout);
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::accept_type::AcceptType;
+use crate::context::Context;
+use crate::failpoint::fail_point_poem;
+use crate::generate_error_response;
+use crate::json_payload::JsonWithPath;
+use crate::multisig_sessions::{self, MultisigSessionError};
+use crate::response::{
+    BadRequestError, BasicResponse, BasicResponseStatus, InsufficientStorageError, InternalError,
+    NotFoundError,
+};
+use crate::ApiTags;
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    AddMultisigSignatureRequest, AptosErrorCode, CreateMultisigSessionRequest, HashValue,
+    HexEncodedBytes, MultisigSession,
+};
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_crypto::multi_ed25519::MultiEd25519PublicKey;
+use aptos_types::transaction::RawTransaction;
+use poem_openapi::{param::Path, OpenApi};
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+generate_error_response!(
+    MultisigError,
+    (400, BadRequest),
+    (404, NotFound),
+    (500, Internal),
+    (507, InsufficientStorage)
+);
+
+type MultisigResult<T> = poem::Result<BasicResponse<T>, MultisigError>;
+
+pub struct MultisigApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl MultisigApi {
+    /// Create multisig session
+    ///
+    /// Start signing a transaction sent by a MultiEd25519 account. The node
+    /// holds the transaction while the holders of the keys of the account
+    /// add their signatures, each signing the `signing_message` of the
+    /// session. Creating a session for the same transaction and keys again
+    /// returns the existing session, so every signer may start with this.
+    ///
+    /// Sessions are held in the memory of the node until they expire, and
+    /// are lost if the node restarts. A node holds a limited number of
+    /// sessions, and returns 507 when it holds as many as it may.
+    #[oai(
+        path = "/multisig/sessions",
+        method = "post",
+        operation_id = "create_multisig_session",
+        tag = "ApiTags::Multisig"
+    )]
+    async fn create_multisig_session(
+        &self,
+        accept_type: AcceptType,
+        data: JsonWithPath<CreateMultisigSessionRequest>,
+    ) -> MultisigResult<MultisigSession> {
+        fail_point_poem("endpoint_create_multisig_session")?;
+        self.create_session(&accept_type, data.0)
+    }
+
+    /// Get multisig session
+    ///
+    /// Get a multisig session, with the keys that signed so far.
+    #[oai(
+        path = "/multisig/sessions/:session_id",
+        method = "get",
+        operation_id = "get_multisig_session",
+        tag = "ApiTags::Multisig"
+    )]
+    async fn get_multisig_session(
+        &self,
+        accept_type: AcceptType,
+        session_id: Path<HashValue>,
+    ) -> MultisigResult<MultisigSession> {
+        fail_point_poem("endpoint_get_multisig_session")?;
+        let session = self
+            .context
+            .multisig_sessions()
+            .get(&session_id.0.into(), now_secs())
+            .map_err(|e| session_error(e, session_id.0))?;
+        self.render_session(&accept_type, &session)
+    }
+
+    /// Add multisig signature
+    ///
+    /// Add the signature of one of the keys of a multisig session. The
+    /// signature must be the Ed25519 signature of the `signing_message` of
+    /// the session by the key. Adding a signature of a key that already
+    /// signed replaces the previous one.
+    #[oai(
+        path = "/multisig/sessions/:session_id/signatures",
+        method = "post",
+        operation_id = "add_multisig_signature",
+        tag = "ApiTags::Multisig"
+    )]
+    async fn add_multisig_signature(
+        &self,
+        accept_type: AcceptType,
+        session_id: Path<HashValue>,
+        data: JsonWithPath<AddMultisigSignatureRequest>,
+    ) -> MultisigResult<MultisigSession> {
+        fail_point_poem("endpoint_add_multisig_signature")?;
+        let public_key = Ed25519PublicKey::try_from(data.0.public_key.inner())
+            .context("The given public key is invalid")
+            .map_err(|e| MultisigError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+        let signature = Ed25519Signature::try_from(data.0.signature.inner())
+            .context("The given signature is invalid")
+            .map_err(|e| {
+                MultisigError::bad_request_with_code(e, AptosErrorCode::InvalidSignature)
+            })?;
+        let session = self
+            .context
+            .multisig_sessions()
+            .add_signature(&session_id.0.into(), &public_key, signature, now_secs())
+            .map_err(|e| session_error(e, session_id.0))?;
+        self.render_session(&accept_type, &session)
+    }
+
+    /// Get multisig transaction
+    ///
+    /// Get the transaction of a complete multisig session, signed with the
+    /// signatures added to it, as BCS encoded bytes. These can be submitted
+    /// as is to /transactions with the BCS content type.
+    #[oai(
+        path = "/multisig/sessions/:session_id/transaction",
+        method = "get",
+        operation_id = "get_multisig_transaction",
+        tag = "ApiTags::Multisig"
+    )]
+    async fn get_multisig_transaction(
+        &self,
+        accept_type: AcceptType,
+        session_id: Path<HashValue>,
+    ) -> MultisigResult<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_multisig_transaction")?;
+        let session = self
+            .context
+            .multisig_sessions()
+            .get(&session_id.0.into(), now_secs())
+            .map_err(|e| session_error(e, session_id.0))?;
+        let signed_txn = session.signed_transaction().ok_or_else(|| {
+            MultisigError::bad_request_with_code(
+                format!(
+                    "Multisig session {} has {} of the {} signatures it needs",
+                    session_id.0,
+                    session.signatures.len(),
+                    session.public_key.threshold()
+                ),
+                AptosErrorCode::InvalidInput,
+            )
+        })?;
+        let bytes = bcs::to_bytes(&signed_txn)
+            .context("Failed to serialize the signed transaction")
+            .map_err(|e| MultisigError::internal_with_code(e, AptosErrorCode::InternalError))?;
+
+        BasicResponse::try_from_rust_value((
+            HexEncodedBytes::from(bytes),
+            &self.context.get_latest_ledger_info()?,
+            BasicResponseStatus::Ok,
+            &accept_type,
+        ))
+    }
+}
+
+impl MultisigApi {
+    fn create_session(
+        &self,
+        accept_type: &AcceptType,
+        request: CreateMultisigSessionRequest,
+    ) -> MultisigResult<MultisigSession> {
+        let public_keys = request
+            .public_keys
+            .iter()
+            .map(|key| Ed25519PublicKey::try_from(key.inner()))
+            .collect::<Result<Vec<_>, _>>()
+            .context("The given public keys are invalid")
+            .map_err(|e| MultisigError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+        let public_key = MultiEd25519PublicKey::new(public_keys, request.threshold)
+            .context("The given public keys and threshold are invalid")
+            .map_err(|e| MultisigError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+
+        let resolver = self.context.move_resolver_poem()?;
+        let raw_txn: RawTransaction = self
+            .context
+            .converter(&resolver)
+            .try_into_raw_transaction_poem(request.transaction, self.context.chain_id())
+            .context("The given transaction is invalid")
+            .map_err(|e| MultisigError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
+
+        let session = self
+            .context
+            .multisig_sessions()
+            .create(raw_txn, public_key, now_secs())
+            .map_err(|_| limit_reached())?;
+        self.render_session(accept_type, &session)
+    }
+
+    fn render_session(
+        &self,
+        accept_type: &AcceptType,
+        session: &multisig_sessions::MultisigSession,
+    ) -> MultisigResult<MultisigSession> {
+        let session = MultisigSession {
+            id: session.id.into(),
+            sender: session.raw_txn.sender().into(),
+            signing_message: session.signing_message().into(),
+            public_keys: session
+                .public_key
+                .public_keys()
+                .iter()
+                .map(|key| key.to_bytes().to_vec().into())
+                .collect(),
+            threshold: *session.public_key.threshold(),
+            signed_key_indices: session.signatures.keys().copied().collect(),
+            complete: session.is_complete(),
+            expiration_timestamp_secs: session.expiration_timestamp_secs.into(),
+        };
+        BasicResponse::try_from_rust_value((
+            session,
+            &self.context.get_latest_ledger_info()?,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+}
+
+fn now_secs() -> u64 {
+    aptos_infallible::duration_since_epoch().as_secs()
+}
+
+fn limit_reached() -> MultisigError {
+    MultisigError::insufficient_storage_with_code(
+        "The node holds as many multisig sessions as it may",
+        AptosErrorCode::MultisigSessionLimitReached,
+    )
+}
+
+fn session_error(error: MultisigSessionError, session_id: HashValue) -> MultisigError {
+    match error {
+        MultisigSessionError::NotFound => MultisigError::not_found_with_code(
+            format!("Multisig session not found by {}", session_id),
+            AptosErrorCode::MultisigSessionNotFound,
+        ),
+        MultisigSessionError::UnknownPublicKey => MultisigError::bad_request_with_code(
+            format!(
+                "The given public key is not one of the keys of multisig session {}",
+                session_id
+            ),
+            AptosErrorCode::InvalidInput,
+        ),
+        MultisigSessionError::InvalidSignature => MultisigError::bad_request_with_code(
+            format!(
+                "The given signature is not a signature of the signing message of multisig session {}",
+                session_id
+            ),
+            AptosErrorCode::InvalidSignature,
+        ),
+        MultisigSessionError::LimitReached => limit_reached(),
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signing sessions for transactions sent by MultiEd25519 accounts, so that
+//! the holders of the keys of an account can sign a transaction separately,
+//! through the node, without a coordination server of their own.
+//!
+//! A session holds the transaction, the public key of the account and the
+//! signatures collected so far, until it expires. Sessions are only held in
+//! memory, so they don't survive a restart of the node, and they are not
+//! shared between nodes.
+
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use aptos_crypto::hash::HashValue;
+use aptos_crypto::multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature};
+use aptos_crypto::traits::Signature;
+use aptos_infallible::Mutex;
+use aptos_types::transaction::{RawTransaction, SignedTransaction};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, PartialEq)]
+pub enum MultisigSessionError {
+    /// There is no session with the ID, or it expired.
+    NotFound,
+    /// The store holds as many sessions as it may.
+    LimitReached,
+    /// The key of the signature is not one of the keys of the session.
+    UnknownPublicKey,
+    /// The signature doesn't verify against the signing message.
+    InvalidSignature,
+}
+
+#[derive(Clone, Debug)]
+pub struct MultisigSession {
+    pub id: HashValue,
+    pub raw_txn: RawTransaction,
+    pub public_key: MultiEd25519PublicKey,
+    /// The signatures collected so far, by the index of their key.
+    pub signatures: BTreeMap<u8, Ed25519Signature>,
+    pub expiration_timestamp_secs: u64,
+}
+
+impl MultisigSession {
+    fn new(
+        raw_txn: RawTransaction,
+        public_key: MultiEd25519PublicKey,
+        expiration_timestamp_secs: u64,
+    ) -> Self {
+        // Derived from what is signed, so that every signer creating the
+        // session for the same transaction joins the same session.
+        let mut bytes = raw_txn.signing_message();
+        bytes.extend(public_key.to_bytes());
+        Self {
+            id: HashValue::sha3_256_of(&bytes),
+            raw_txn,
+            public_key,
+            signatures: BTreeMap::new(),
+            expiration_timestamp_secs,
+        }
+    }
+
+    pub fn signing_message(&self) -> Vec<u8> {
+        self.raw_txn.signing_message()
+    }
+
+    /// Whether enough keys signed for the transaction to be submitted.
+    pub fn is_complete(&self) -> bool {
+        self.signatures.len() >= *self.public_key.threshold() as usize
+    }
+
+    /// The transaction signed with the collected signatures, once complete.
+    pub fn signed_transaction(&self) -> Option<SignedTransaction> {
+        if !self.is_complete() {
+            return None;
+        }
+        let signatures = self
+            .signatures
+            .iter()
+            .map(|(index, signature)| (signature.clone(), *index))
+            .collect();
+        let signature = MultiEd25519Signature::new(signatures).ok()?;
+        Some(SignedTransaction::new_multisig(
+            self.raw_txn.clone(),
+            self.public_key.clone(),
+            signature,
+        ))
+    }
+}
+
+/// The signing sessions in progress, up to a maximum number. Sessions expire
+/// a fixed time after they are created, and expired sessions are dropped
+/// whenever the store is accessed.
+pub struct MultisigSessionStore {
+    capacity: usize,
+    ttl_secs: u64,
+    sessions: Mutex<HashMap<HashValue, MultisigSession>>,
+}
+
+impl MultisigSessionStore {
+    pub fn new(capacity: usize, ttl_secs: u64) -> Self {
+        Self {
+            capacity,
+            ttl_secs,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Creates a session for the transaction, or returns the existing one if
+    /// a session was already created for the same transaction and key. Only
+    /// fails with `LimitReached`.
+    pub fn create(
+        &self,
+        raw_txn: RawTransaction,
+        public_key: MultiEd25519PublicKey,
+        now_secs: u64,
+    ) -> Result<MultisigSession, MultisigSessionError> {
+        let session = MultisigSession::new(raw_txn, public_key, now_secs + self.ttl_secs);
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|_, session| session.expiration_timestamp_secs > now_secs);
+        if let Some(existing) = sessions.get(&session.id) {
+            return Ok(existing.clone());
+        }
+        if sessions.len() >= self.capacity {
+            return Err(MultisigSessionError::LimitReached);
+        }
+        sessions.insert(session.id, session.clone());
+        Ok(session)
+    }
+
+    pub fn get(
+        &self,
+        id: &HashValue,
+        now_secs: u64,
+    ) -> Result<MultisigSession, MultisigSessionError> {
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|_, session| session.expiration_timestamp_secs > now_secs);
+        sessions
+            .get(id)
+            .cloned()
+            .ok_or(MultisigSessionError::NotFound)
+    }
+
+    /// Adds the signature of one of the keys of the session, replacing any
+    /// signature of that key added before.
+    pub fn add_signature(
+        &self,
+        id: &HashValue,
+        public_key: &Ed25519PublicKey,
+        signature: Ed25519Signature,
+        now_secs: u64,
+    ) -> Result<MultisigSession, MultisigSessionError> {
+        let mut sessions = self.sessions.lock();
+        sessions.retain(|_, session| session.expiration_timestamp_secs > now_secs);
+        let session = sessions.get_mut(id).ok_or(MultisigSessionError::NotFound)?;
+        let index = session
+            .public_key
+            .public_keys()
+            .iter()
+            .position(|key| key == public_key)
+            .ok_or(MultisigSessionError::UnknownPublicKey)?;
+        signature
+            .verify_arbitrary_msg(&session.signing_message(), public_key)
+            .map_err(|_| MultisigSessionError::InvalidSignature)?;
+        session.signatures.insert(index as u8, signature);
+        Ok(session.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultisigSessionError, MultisigSessionStore};
+    use aptos_crypto::ed25519::Ed25519PrivateKey;
+    use aptos_crypto::multi_ed25519::MultiEd25519PublicKey;
+    use aptos_crypto::traits::{SigningKey, Uniform};
+    use aptos_types::account_address::AccountAddress;
+    use aptos_types::chain_id::ChainId;
+    use aptos_types::transaction::{RawTransaction, Script, TransactionPayload};
+
+    fn raw_txn(sequence_number: u64) -> RawTransaction {
+        RawTransaction::new(
+            AccountAddress::random(),
+            sequence_number,
+            TransactionPayload::Script(Script::new(vec![], vec![], vec![])),
+            1000,
+            1,
+            u64::MAX,
+            ChainId::test(),
+        )
+    }
+
+    fn keys() -> (Vec<Ed25519PrivateKey>, MultiEd25519PublicKey) {
+        let mut rng = rand::thread_rng();
+        let private_keys: Vec<_> = (0..3)
+            .map(|_| Ed25519PrivateKey::generate(&mut rng))
+            .collect();
+        let public_keys = private_keys.iter().map(|key| key.into()).collect();
+        (
+            private_keys,
+            MultiEd25519PublicKey::new(public_keys, 2).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_collect_signatures() {
+        let store = MultisigSessionStore::new(10, 60);
+        let (private_keys, public_key) = keys();
+        let session = store.create(raw_txn(0), public_key.clone(), 0).unwrap();
+        assert!(session.signed_transaction().is_none());
+
+        let message = session.signing_message();
+        for index in [2, 0] {
+            let private_key = &private_keys[index];
+            store
+                .add_signature(
+                    &session.id,
+                    &private_key.into(),
+                    private_key.sign_arbitrary_message(&message),
+                    1,
+                )
+                .unwrap();
+        }
+
+        let session = store.get(&session.id, 2).unwrap();
+        assert!(session.is_complete());
+        let signed_txn = session.signed_transaction().unwrap();
+        assert!(signed_txn.check_signature().is_ok());
+
+        // Creating the session again joins it.
+        let same_session = store.create(raw_txn(0), public_key, 3);
+        assert_eq!(same_session.unwrap().id, session.id);
+    }
+
+    #[test]
+    fn test_rejects_invalid_signatures() {
+        let store = MultisigSessionStore::new(10, 60);
+        let (private_keys, public_key) = keys();
+        let session = store.create(raw_txn(0), public_key, 0).unwrap();
+
+        let (other_keys, _) = keys();
+        let other_key = &other_keys[0];
+        assert_eq!(
+            store
+                .add_signature(
+                    &session.id,
+                    &other_key.into(),
+                    other_key.sign_arbitrary_message(&session.signing_message()),
+                    1,
+                )
+                .unwrap_err(),
+            MultisigSessionError::UnknownPublicKey
+        );
+        assert_eq!(
+            store
+                .add_signature(
+                    &session.id,
+                    &(&private_keys[0]).into(),
+                    private_keys[0].sign_arbitrary_message(b"something else"),
+                    1,
+                )
+                .unwrap_err(),
+            MultisigSessionError::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn test_expiration_and_capacity() {
+        let store = MultisigSessionStore::new(1, 60);
+        let (_, public_key) = keys();
+        let session = store.create(raw_txn(0), public_key.clone(), 0).unwrap();
+        assert_eq!(
+            store
+                .create(raw_txn(1), public_key.clone(), 59)
+                .unwrap_err(),
+            MultisigSessionError::LimitReached
+        );
+
+        // The expired session makes room for a new one.
+        assert_eq!(
+            store.get(&session.id, 60).unwrap_err(),
+            MultisigSessionError::NotFound
+        );
+        assert!(store.create(raw_txn(1), public_key, 60).is_ok());
+    }
+}
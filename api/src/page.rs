@@ -1,22 +1,49 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::response::BadRequestError;
-use aptos_api_types::AptosErrorCode;
+use crate::response::{AptosErrorResponse, BadRequestError};
+use aptos_api_types::{AptosErrorCode, Cursor};
 use serde::Deserialize;
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
-const MAX_PAGE_SIZE: u16 = 1000;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
     start: Option<u64>,
     limit: Option<u16>,
+    max_page_size: u16,
 }
 
 impl Page {
-    pub fn new(start: Option<u64>, limit: Option<u16>) -> Self {
-        Self { start, limit }
+    pub fn new(start: Option<u64>, limit: Option<u16>, max_page_size: u16) -> Self {
+        Self {
+            start,
+            limit,
+            max_page_size,
+        }
+    }
+
+    /// Starts the page at the position of the given cursor, decoded with
+    /// `decode` (e.g. `Cursor::into_version`), if there is one. A cursor
+    /// can't be given along with a start value.
+    pub fn with_cursor<E: BadRequestError>(
+        mut self,
+        cursor: Option<Cursor>,
+        decode: fn(Cursor) -> anyhow::Result<u64>,
+    ) -> Result<Self, E> {
+        if let Some(cursor) = cursor {
+            if self.start.is_some() {
+                return Err(E::bad_request_with_code(
+                    "Only one of start and cursor may be given",
+                    AptosErrorCode::InvalidCursorParam,
+                ));
+            }
+            self.start =
+                Some(decode(cursor).map_err(|e| {
+                    E::bad_request_with_code(e, AptosErrorCode::InvalidCursorParam)
+                })?);
+        }
+        Ok(self)
     }
 
     pub fn compute_start<E: BadRequestError>(&self, limit: u16, max: u64) -> Result<u64, E> {
@@ -27,11 +54,15 @@ impl Page {
     pub fn start<E: BadRequestError>(&self, default: u64, max: u64) -> Result<u64, E> {
         let start = self.start.unwrap_or(default);
         if start > max {
-            return Err(E::bad_request_str(&format!(
+            return Err(E::bad_request_with_code(
+                format!(
                 "Given start value ({}) is higher than the current ledger version, it must be < {}",
                 start, max
-            ))
-            .error_code(AptosErrorCode::InvalidStartParam));
+            ),
+                AptosErrorCode::InvalidStartParam,
+            )
+            .detail("start", start)
+            .detail("max", max));
         }
         Ok(start)
     }
@@ -41,20 +72,26 @@ impl Page {
     }
 
     pub fn limit<E: BadRequestError>(&self) -> Result<u16, E> {
-        let limit = self.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        let limit = self
+            .limit
+            .unwrap_or_else(|| DEFAULT_PAGE_SIZE.min(self.max_page_size));
         if limit == 0 {
-            return Err(E::bad_request_str(&format!(
-                "Given limit value ({}) must not be zero",
-                limit
-            ))
-            .error_code(AptosErrorCode::InvalidLimitParam));
+            return Err(E::bad_request_with_code(
+                format!("Given limit value ({}) must not be zero", limit),
+                AptosErrorCode::InvalidLimitParam,
+            )
+            .detail("limit", limit));
         }
-        if limit > MAX_PAGE_SIZE {
-            return Err(E::bad_request_str(&format!(
-                "Given limit value ({}) is too large, it must be < {}",
-                limit, MAX_PAGE_SIZE
-            ))
-            .error_code(AptosErrorCode::InvalidLimitParam));
+        if limit > self.max_page_size {
+            return Err(E::bad_request_with_code(
+                format!(
+                    "Given limit value ({}) is too large, it must be < {}",
+                    limit, self.max_page_size
+                ),
+                AptosErrorCode::InvalidLimitParam,
+            )
+            .detail("limit", limit)
+            .detail("max_page_size", self.max_page_size));
         }
         Ok(limit)
     }
@@ -0,0 +1,49 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use anyhow::anyhow;
+use aptos_logger::prelude::*;
+use poem::{handler, web::Data};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+
+#[handler]
+pub async fn reload_config_poem(context: Data<&Arc<Context>>) -> poem::Result<String> {
+    if context.api_config().config_reload_endpoint_enabled {
+        context.reload_config().await.map_err(poem::Error::from)?;
+        Ok("Reloaded API config".to_string())
+    } else {
+        Err(poem::Error::from(anyhow!(
+            "Reloading the API config is not enabled at a config level"
+        )))
+    }
+}
+
+/// Reloads the API config and the mempool submission filter from the node
+/// config file every time the node receives SIGHUP.
+#[cfg(unix)]
+pub fn reload_config_on_sighup(runtime_handle: &Handle, context: Arc<Context>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    runtime_handle.spawn(async move {
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(e) => {
+                error!(
+                    "Failed to listen for SIGHUP to reload the API config: {}",
+                    e
+                );
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            if let Err(e) = context.reload_config().await {
+                error!("Failed to reload the API config: {:#}", e);
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn reload_config_on_sighup(_runtime_handle: &Handle, _context: Arc<Context>) {}
@@ -0,0 +1,51 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use opentelemetry::{global, propagation::Extractor};
+use poem::{http::HeaderMap, Endpoint, Request, Response, Result};
+use poem_openapi::OperationId;
+use tracing::{field::Empty, info_span, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Traces each request in a span, the parent of the spans of the storage
+/// reads, conversions and VM sessions done to serve it. The span continues
+/// the trace of the caller if the request carries W3C Trace Context headers.
+/// Spans are only exported if the node is configured to export traces.
+pub async fn middleware_trace<E: Endpoint>(next: E, request: Request) -> Result<Response> {
+    let parent_context = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    let span = info_span!(
+        "api_request",
+        otel.name = Empty,
+        otel.kind = "server",
+        http.method = %request.method(),
+        http.target = %request.uri().path(),
+        http.status_code = Empty,
+    );
+    span.set_parent(parent_context);
+
+    let response = next.get_response(request).instrument(span.clone()).await;
+
+    span.record(
+        "otel.name",
+        &response
+            .data::<OperationId>()
+            .map(|operation_id| operation_id.0)
+            .unwrap_or("operation_id_not_set"),
+    );
+    span.record("http.status_code", &response.status().as_u16());
+    Ok(response)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
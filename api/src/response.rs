@@ -32,10 +32,12 @@
 use std::fmt::Display;
 
 use super::accept_type::AcceptType;
-use aptos_api_types::{AptosError, AptosErrorCode};
+use aptos_api_types::{AptosError, AptosErrorCode, LedgerInfo};
 use poem_openapi::{payload::Json, types::ToJSON, ResponseContent};
 
 use super::bcs_payload::Bcs;
+use super::bcs_stream::{uleb128_size, BcsStream};
+use super::json_stream::JsonStream;
 
 #[derive(ResponseContent)]
 pub enum AptosResponseContent<T: ToJSON + Send + Sync> {
@@ -47,6 +49,15 @@ pub enum AptosResponseContent<T: ToJSON + Send + Sync> {
     // from either an internal Rust type being serialized into bytes, or just
     // the bytes directly from storage.
     Bcs(Bcs),
+
+    // Return a list of items as they are serialized, a chunk at a time, rather
+    // than all at once. Only list endpoints that stream their responses use
+    // this, for large pages.
+    JsonStream(JsonStream),
+
+    // Return a list of items as BCS values each prefixed with its length,
+    // for clients that parse lists an item at a time.
+    BcsStream(BcsStream),
 }
 
 /// This trait defines common functions that all error responses should impl.
@@ -56,11 +67,13 @@ pub enum AptosResponseContent<T: ToJSON + Send + Sync> {
 pub trait AptosErrorResponse {
     fn inner_mut(&mut self) -> &mut AptosError;
 
-    fn error_code(mut self, error_code: AptosErrorCode) -> Self
+    fn detail<V: Display>(mut self, key: &str, value: V) -> Self
     where
         Self: Sized,
     {
-        self.inner_mut().error_code = Some(error_code);
+        self.inner_mut()
+            .details
+            .insert(key.to_string(), value.to_string());
         self
     }
 
@@ -83,14 +96,19 @@ pub trait AptosErrorResponse {
 /// throughout the entire API. Every one of these traits requires that the
 /// implementor also implements AptosErrorResponse, which saves functions from
 /// having to add that bound to errors themselves.
+///
+/// The only way to build an error is with a code from the AptosErrorCode
+/// registry, which is what clients use to decide how to handle the error.
 #[macro_export]
 macro_rules! generate_error_traits {
     ($($trait_name:ident),*) => {
         paste::paste! {
         $(
         pub trait [<$trait_name Error>]: AptosErrorResponse {
-            fn [<$trait_name:snake>](error: anyhow::Error) -> Self where Self: Sized;
-            fn [<$trait_name:snake _str>](error_str: &str) -> Self where Self: Sized;
+            fn [<$trait_name:snake _with_code>]<Err: std::fmt::Display>(
+                error: Err,
+                error_code: aptos_api_types::AptosErrorCode,
+            ) -> Self where Self: Sized;
         }
         )*
         }
@@ -123,18 +141,15 @@ macro_rules! generate_error_response {
 
         // For each status, implement the relevant error trait. This means if
         // the macro invocation specifies Internal and BadRequest, the
-        // functions internal(anyhow::Error) and bad_request(anyhow::Error)
-        // will be generated. There are also variants for taking in strs.
+        // functions internal_with_code and bad_request_with_code will be
+        // generated. These take anything Display, e.g. anyhow::Error or &str.
         $(
         impl $crate::response::[<$name Error>] for $enum_name {
-            fn [<$name:snake>](error: anyhow::Error) -> Self where Self: Sized {
-                let error = aptos_api_types::AptosError::from(error);
-                let payload = poem_openapi::payload::Json(error);
-                Self::from($enum_name::$name(payload))
-            }
-
-            fn [<$name:snake _str>](error_str: &str) -> Self where Self: Sized {
-                let error = aptos_api_types::AptosError::new(error_str.to_string());
+            fn [<$name:snake _with_code>]<Err: std::fmt::Display>(
+                error: Err,
+                error_code: aptos_api_types::AptosErrorCode,
+            ) -> Self where Self: Sized {
+                let error = aptos_api_types::AptosError::new(error, error_code);
                 let payload = poem_openapi::payload::Json(error);
                 Self::from($enum_name::$name(payload))
             }
@@ -192,6 +207,14 @@ macro_rules! generate_success_response {
                 #[oai(header = "X-Aptos-Epoch")] u64,
                 #[oai(header = "X-Aptos-Block-Height")] u64,
                 #[oai(header = "X-Aptos-Oldest-Block-Height")] u64,
+                // Only set when a page of items stops short of the items
+                // requested, in which case this is the cursor from which to
+                // request the rest.
+                #[oai(header = "X-Aptos-Cursor")] Option<String>,
+                // Only set by deprecated endpoints, along with a link to the
+                // endpoint replacing them.
+                #[oai(header = "Deprecation")] Option<String>,
+                #[oai(header = "Link")] Option<String>,
             ),
             )*
         }
@@ -231,6 +254,9 @@ macro_rules! generate_success_response {
                             ledger_info.epoch.into(),
                             ledger_info.block_height.into(),
                             ledger_info.oldest_block_height.into(),
+                            None,
+                            None,
+                            None,
                         )
                     },
                     )*
@@ -266,6 +292,38 @@ macro_rules! generate_success_response {
             }
         }
 
+        // Generate a From impl that builds a response from a JsonStream and friends.
+        impl<T: poem_openapi::types::ToJSON + Send + Sync> From<($crate::json_stream::JsonStream, &aptos_api_types::LedgerInfo, [<$enum_name Status>])>
+            for $enum_name<T>
+        {
+            fn from(
+                (value, ledger_info, status): (
+                    $crate::json_stream::JsonStream,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>]
+                ),
+            ) -> Self {
+                let content = $crate::response::AptosResponseContent::JsonStream(value);
+                Self::from((content, ledger_info, status))
+            }
+        }
+
+        // Generate a From impl that builds a response from a BcsStream and friends.
+        impl<T: poem_openapi::types::ToJSON + Send + Sync> From<($crate::bcs_stream::BcsStream, &aptos_api_types::LedgerInfo, [<$enum_name Status>])>
+            for $enum_name<T>
+        {
+            fn from(
+                (value, ledger_info, status): (
+                    $crate::bcs_stream::BcsStream,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>]
+                ),
+            ) -> Self {
+                let content = $crate::response::AptosResponseContent::BcsStream(value);
+                Self::from((content, ledger_info, status))
+            }
+        }
+
         // Generate a TryFrom impl that builds a response from a T, an AcceptType,
         // and all the other usual suspects. It expects to be called with a generic
         // parameter E: InternalError, with which we can build an internal error
@@ -280,15 +338,15 @@ macro_rules! generate_success_response {
                 ),
             ) -> Result<Self, E> {
                 match accept_type {
-                    AcceptType::Bcs => Ok(Self::from((
+                    AcceptType::Bcs | AcceptType::BcsStream => Ok(Self::from((
                         $crate::bcs_payload::Bcs(
                             bcs::to_bytes(&value)
-                                .map_err(|e| E::internal(e.into()).error_code(aptos_api_types::AptosErrorCode::BcsSerializationError))?
+                                .map_err(|e| E::internal_with_code(e, aptos_api_types::AptosErrorCode::BcsSerializationError))?
                         ),
                         ledger_info,
                         status
                     ))),
-                    AcceptType::Json => Ok(Self::from((
+                    AcceptType::Json | AcceptType::NdJson => Ok(Self::from((
                         poem_openapi::payload::Json(value),
                         ledger_info,
                         status
@@ -296,19 +354,324 @@ macro_rules! generate_success_response {
                 }
             }
         }
+
+        impl<T: poem_openapi::types::ToJSON + Send + Sync> $enum_name<T> {
+            /// Sets the cursor from which the client can fetch the items
+            /// that didn't fit in this response.
+            pub fn with_cursor(self, cursor: Option<aptos_api_types::Cursor>) -> Self {
+                let cursor = cursor.map(|cursor| cursor.to_string());
+                match self {
+                    $(
+                    $enum_name::$name(
+                        value,
+                        chain_id,
+                        ledger_version,
+                        oldest_ledger_version,
+                        ledger_timestamp,
+                        epoch,
+                        block_height,
+                        oldest_block_height,
+                        _,
+                        deprecation,
+                        link,
+                    ) => $enum_name::$name(
+                        value,
+                        chain_id,
+                        ledger_version,
+                        oldest_ledger_version,
+                        ledger_timestamp,
+                        epoch,
+                        block_height,
+                        oldest_block_height,
+                        cursor,
+                        deprecation,
+                        link,
+                    ),
+                    )*
+                }
+            }
+
+            /// Marks the response as coming from a deprecated endpoint, linking
+            /// to the path of the endpoint replacing it.
+            pub fn with_deprecation(self, successor: &str) -> Self {
+                match self {
+                    $(
+                    $enum_name::$name(
+                        value,
+                        chain_id,
+                        ledger_version,
+                        oldest_ledger_version,
+                        ledger_timestamp,
+                        epoch,
+                        block_height,
+                        oldest_block_height,
+                        cursor,
+                        _,
+                        _,
+                    ) => $enum_name::$name(
+                        value,
+                        chain_id,
+                        ledger_version,
+                        oldest_ledger_version,
+                        ledger_timestamp,
+                        epoch,
+                        block_height,
+                        oldest_block_height,
+                        cursor,
+                        Some("true".to_string()),
+                        Some(format!("<{}>; rel=\"successor-version\"", successor)),
+                    ),
+                    )*
+                }
+            }
+        }
+
+        // Generate a TryFrom-like function for lists of items. This works like
+        // try_from_rust_value, except that clients asking for a BCS stream get
+        // the items length prefixed rather than as a BCS sequence.
+        impl<T: poem_openapi::types::ToJSON + Send + Sync + serde::Serialize> $enum_name<Vec<T>> {
+            pub fn try_from_rust_list<E: InternalError>(
+                (items, ledger_info, status, accept_type): (
+                    Vec<T>,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>],
+                    &$crate::accept_type::AcceptType
+                ),
+            ) -> Result<Self, E> {
+                match accept_type {
+                    AcceptType::BcsStream => Ok(Self::from((
+                        $crate::bcs_stream::BcsStream::from_items(&items)
+                            .map_err(|e| E::internal_with_code(e, aptos_api_types::AptosErrorCode::BcsSerializationError))?,
+                        ledger_info,
+                        status
+                    ))),
+                    _ => Self::try_from_rust_value::<E>((items, ledger_info, status, accept_type)),
+                }
+            }
+        }
+
+        // Generate a TryFrom-like function for pages of items. This works like
+        // try_from_rust_list, except that the page is cut short at an item
+        // boundary if writing all of it would exceed max_response_size bytes.
+        // next_cursor builds the cursor returned to the client from the number
+        // of items kept if the page was cut short, or None if it wasn't, e.g.
+        // to point at the next page anyway.
+        impl<T: poem_openapi::types::ToJSON + Send + Sync + serde::Serialize> $enum_name<Vec<T>> {
+            pub fn try_from_budgeted_rust_list<E: InternalError, C: FnOnce(Option<usize>) -> Option<aptos_api_types::Cursor>>(
+                (mut items, ledger_info, status, accept_type, max_response_size, next_cursor): (
+                    Vec<T>,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>],
+                    &$crate::accept_type::AcceptType,
+                    u64,
+                    C
+                ),
+            ) -> Result<Self, E> {
+                match accept_type {
+                    AcceptType::Bcs | AcceptType::BcsStream => Self::try_from_budgeted_bcs_list::<T, E, C>((
+                        items,
+                        ledger_info,
+                        status,
+                        accept_type,
+                        max_response_size,
+                        next_cursor,
+                    )),
+                    AcceptType::Json | AcceptType::NdJson => {
+                        let count = $crate::response::json_items_within_size_budget(&items, max_response_size)
+                            .map_err(|e| E::internal_with_code(e, aptos_api_types::AptosErrorCode::InternalError))?;
+                        let cut_at = if count < items.len() { Some(count) } else { None };
+                        items.truncate(count);
+                        Ok(Self::from((poem_openapi::payload::Json(items), ledger_info, status))
+                            .with_cursor(next_cursor(cut_at)))
+                    }
+                }
+            }
+
+            // Like try_from_budgeted_rust_list, for BCS accept types, with the
+            // items written being of another type than those of the response,
+            // e.g. the raw outputs of transactions rather than their JSON view.
+            pub fn try_from_budgeted_bcs_list<U: serde::Serialize, E: InternalError, C: FnOnce(Option<usize>) -> Option<aptos_api_types::Cursor>>(
+                (mut items, ledger_info, status, accept_type, max_response_size, next_cursor): (
+                    Vec<U>,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>],
+                    &$crate::accept_type::AcceptType,
+                    u64,
+                    C
+                ),
+            ) -> Result<Self, E> {
+                let map_err = |e| E::internal_with_code(e, aptos_api_types::AptosErrorCode::BcsSerializationError);
+                let length_prefixed = accept_type == &AcceptType::BcsStream;
+                let count = $crate::response::bcs_items_within_size_budget(&items, length_prefixed, max_response_size)
+                    .map_err(map_err)?;
+                let cut_at = if count < items.len() { Some(count) } else { None };
+                items.truncate(count);
+                let response = if length_prefixed {
+                    Self::from((
+                        $crate::bcs_stream::BcsStream::from_items(&items).map_err(map_err)?,
+                        ledger_info,
+                        status
+                    ))
+                } else {
+                    Self::from((
+                        $crate::bcs_payload::Bcs(bcs::to_bytes(&items).map_err(|e| map_err(e.into()))?),
+                        ledger_info,
+                        status
+                    ))
+                };
+                Ok(response.with_cursor(next_cursor(cut_at)))
+            }
+
+            // Like try_from_budgeted_rust_list, for items numbered consecutively
+            // from start (e.g. by version or sequence number), so the number of
+            // the first item left out is returned to the client as a cursor,
+            // built with to_cursor (e.g. Cursor::version).
+            pub fn try_from_paged_rust_value<E: InternalError>(
+                (items, ledger_info, status, accept_type, start, to_cursor, max_response_size): (
+                    Vec<T>,
+                    &aptos_api_types::LedgerInfo,
+                    [<$enum_name Status>],
+                    &$crate::accept_type::AcceptType,
+                    u64,
+                    fn(u64) -> aptos_api_types::Cursor,
+                    u64
+                ),
+            ) -> Result<Self, E> {
+                Self::try_from_budgeted_rust_list::<E, _>((
+                    items,
+                    ledger_info,
+                    status,
+                    accept_type,
+                    max_response_size,
+                    |cut_at| cut_at.map(|count| to_cursor(start + count as u64)),
+                ))
+            }
+        }
         }
     };
 }
 
+/// Returns how many of the leading items fit in a response of at most
+/// max_response_size bytes, sized as they are written for the given accept
+/// type. At least one item is always included, otherwise a client could never
+/// get past an item that is larger than the limit by itself.
+pub fn items_within_size_budget<T: ToJSON + serde::Serialize>(
+    items: &[T],
+    accept_type: &AcceptType,
+    max_response_size: u64,
+) -> anyhow::Result<usize> {
+    match accept_type {
+        AcceptType::Json | AcceptType::NdJson => {
+            json_items_within_size_budget(items, max_response_size)
+        }
+        AcceptType::Bcs => bcs_items_within_size_budget(items, false, max_response_size),
+        AcceptType::BcsStream => bcs_items_within_size_budget(items, true, max_response_size),
+    }
+}
+
+/// Like items_within_size_budget, for a JSON array of the items, sized as poem
+/// writes it, from the JSON values of the items rather than their serde
+/// serialization.
+pub fn json_items_within_size_budget<T: ToJSON>(
+    items: &[T],
+    max_response_size: u64,
+) -> anyhow::Result<usize> {
+    // Start with the brackets of the array.
+    let mut size = 2u64;
+    for (index, item) in items.iter().enumerate() {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, &item.to_json())?;
+        // Include the separating comma.
+        size = size.saturating_add(counter.0 + (index > 0) as u64);
+        if index > 0 && size > max_response_size {
+            return Ok(index);
+        }
+    }
+    Ok(items.len())
+}
+
+/// Like items_within_size_budget, for the items as a BCS sequence, or as
+/// length prefixed BCS values.
+pub fn bcs_items_within_size_budget<T: serde::Serialize>(
+    items: &[T],
+    length_prefixed: bool,
+    max_response_size: u64,
+) -> anyhow::Result<usize> {
+    let mut size = 0u64;
+    for (index, item) in items.iter().enumerate() {
+        let item_size = if length_prefixed {
+            BcsStream::serialized_size(item)?
+        } else {
+            bcs::serialized_size(item)? as u64
+        };
+        size = size.saturating_add(item_size);
+        // A BCS sequence starts with its number of items.
+        let framing = if length_prefixed {
+            0
+        } else {
+            uleb128_size(index as u64 + 1)
+        };
+        if index > 0 && size.saturating_add(framing) > max_response_size {
+            return Ok(index);
+        }
+    }
+    Ok(items.len())
+}
+
+/// A writer that discards what is written to it, only counting the bytes.
+struct ByteCounter(u64);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 = self.0.saturating_add(buf.len() as u64);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 // Generate a success response that only has an option for 200.
 generate_success_response!(BasicResponse, (200, Ok));
 
+/// The response to a HEAD request, which has the status and ledger headers
+/// of the response to the matching GET request, but no body.
+#[derive(poem_openapi::ApiResponse)]
+pub enum HeadResponse {
+    #[oai(status = 200)]
+    Ok(
+        #[oai(header = "X-Aptos-Chain-Id")] u16,
+        #[oai(header = "X-Aptos-Ledger-Version")] u64,
+        #[oai(header = "X-Aptos-Ledger-Oldest-Version")] u64,
+        #[oai(header = "X-Aptos-Ledger-TimestampUsec")] u64,
+        #[oai(header = "X-Aptos-Epoch")] u64,
+        #[oai(header = "X-Aptos-Block-Height")] u64,
+        #[oai(header = "X-Aptos-Oldest-Block-Height")] u64,
+    ),
+}
+
+impl From<&LedgerInfo> for HeadResponse {
+    fn from(ledger_info: &LedgerInfo) -> Self {
+        HeadResponse::Ok(
+            ledger_info.chain_id as u16,
+            ledger_info.ledger_version.into(),
+            ledger_info.oldest_ledger_version.into(),
+            ledger_info.ledger_timestamp.into(),
+            ledger_info.epoch.into(),
+            ledger_info.block_height.into(),
+            ledger_info.oldest_block_height.into(),
+        )
+    }
+}
+
 // Generate traits defining a "from" function for each of these status types.
 // The error response then impls these traits for each status type they mention.
 generate_error_traits!(
     BadRequest,
     NotFound,
     PayloadTooLarge,
+    TooManyRequests,
     Internal,
     InsufficientStorage
 );
@@ -327,13 +690,18 @@ generate_error_response!(
     (500, Internal)
 );
 pub type BasicResultWith404<T> = poem::Result<BasicResponse<T>, BasicErrorWith404>;
+pub type HeadResultWith404 = poem::Result<HeadResponse, BasicErrorWith404>;
 
 // Just this one helper for a specific kind of 404.
 pub fn build_not_found<S: Display, E: NotFoundError>(
     resource: &str,
     identifier: S,
+    error_code: AptosErrorCode,
     ledger_version: u64,
 ) -> E {
-    E::not_found_str(&format!("{} not found by {}", resource, identifier))
-        .aptos_ledger_version(ledger_version)
+    E::not_found_with_code(
+        format!("{} not found by {}", resource, identifier),
+        error_code,
+    )
+    .aptos_ledger_version(ledger_version)
 }
@@ -5,11 +5,14 @@ use std::{net::SocketAddr, sync::Arc};
 
 use crate::blocks::BlocksApi;
 use crate::log::middleware_log;
+use crate::reload_config::{reload_config_on_sighup, reload_config_poem};
+use crate::request_trace::middleware_trace;
 use crate::set_failpoints;
 use crate::{
     accounts::AccountsApi, basic::BasicApi, check_size::PostSizeLimit, context::Context,
-    error_converter::convert_error, events::EventsApi, index::IndexApi, state::StateApi,
-    transactions::TransactionsApi,
+    cors::RouteGroupCors, developer::DeveloperApi, error_converter::convert_error,
+    events::EventsApi, faucet::FaucetApi, index::IndexApi, ledger_version_pin::LedgerVersionPin,
+    multisig::MultisigApi, staking::StakingApi, state::StateApi, transactions::TransactionsApi,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_config::config::NodeConfig;
@@ -17,24 +20,25 @@ use aptos_logger::info;
 use aptos_mempool::MempoolClientSender;
 use aptos_runtime::instrumented_runtime::instrument_tokio_runtime;
 use aptos_types::chain_id::ChainId;
+use event_notifications::EventNotificationListener;
 use poem::{
-    http::{header, Method},
     listener::{Listener, RustlsCertificate, RustlsConfig, TcpListener},
-    middleware::Cors,
     EndpointExt, Route, Server,
 };
 use poem_openapi::{ContactObject, LicenseObject, OpenApiService};
 use storage_interface::DbReader;
 use tokio::runtime::{Builder, Handle, Runtime};
 
-const VERSION: &str = include_str!("../doc/.version");
+pub(crate) const VERSION: &str = include_str!("../doc/.version");
 
-/// Create a runtime and attach the Poem webserver to it.
+/// Create a runtime and attach the Poem webserver to it. If a commit listener
+/// is given, the latest ledger info is cached and refreshed on every commit.
 pub fn bootstrap(
     config: &NodeConfig,
     chain_id: ChainId,
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
+    commit_listener: Option<EventNotificationListener>,
 ) -> anyhow::Result<Runtime> {
     let runtime = Builder::new_multi_thread()
         .thread_name("api")
@@ -43,7 +47,13 @@ pub fn bootstrap(
         .context("[api] failed to create runtime")?;
 
     instrument_tokio_runtime(&runtime, "api");
-    let context = Context::new(chain_id, db, mp_sender, config.clone());
+    let mut context = Context::new(chain_id, db, mp_sender, config.clone());
+    if let Some(commit_listener) = commit_listener {
+        context = context.with_commit_notifications(runtime.handle(), commit_listener);
+    }
+    if config.api.node_config_path.is_some() {
+        reload_config_on_sighup(runtime.handle(), Arc::new(context.clone()));
+    }
 
     attach_poem_to_runtime(runtime.handle(), context, config, false)
         .context("Failed to attach poem to runtime")?;
@@ -64,8 +74,12 @@ pub fn get_api_service(
         AccountsApi,
         BasicApi,
         BlocksApi,
+        DeveloperApi,
         EventsApi,
+        FaucetApi,
         IndexApi,
+        MultisigApi,
+        StakingApi,
         StateApi,
         TransactionsApi,
     ),
@@ -82,12 +96,24 @@ pub fn get_api_service(
         BlocksApi {
             context: context.clone(),
         },
+        DeveloperApi {
+            context: context.clone(),
+        },
         EventsApi {
             context: context.clone(),
         },
+        FaucetApi {
+            context: context.clone(),
+        },
         IndexApi {
             context: context.clone(),
         },
+        MultisigApi {
+            context: context.clone(),
+        },
+        StakingApi {
+            context: context.clone(),
+        },
         StateApi {
             context: context.clone(),
         },
@@ -163,10 +189,8 @@ pub fn attach_poem_to_runtime(
     let actual_address = *actual_address
         .as_socket_addr()
         .context("Failed to get socket addr from local addr for Poem webserver")?;
+    let cors = RouteGroupCors::new(context.clone());
     runtime_handle.spawn(async move {
-        let cors = Cors::new()
-            .allow_methods(vec![Method::GET, Method::POST])
-            .allow_headers(vec![header::CONTENT_TYPE, header::ACCEPT]);
         let route = Route::new()
             .nest(
                 "/v1",
@@ -179,13 +203,19 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+                    )
+                    .at(
+                        "/reload_config",
+                        poem::post(reload_config_poem).data(context.clone()),
                     ),
             )
             .with(cors)
             .with(PostSizeLimit::new(size_limit))
+            .with(LedgerVersionPin::new(context.clone()))
             // NOTE: Make sure to keep this after all the `with` middleware.
             .catch_all_error(convert_error)
-            .around(middleware_log);
+            .around(middleware_log)
+            .around(middleware_trace);
         Server::new_with_acceptor(acceptor)
             .run(route)
             .await
@@ -231,6 +261,7 @@ mod tests {
             ChainId::test(),
             context.db.clone(),
             context.mempool.ac_client.clone(),
+            None,
         );
         assert!(ret.is_ok());
 
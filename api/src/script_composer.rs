@@ -0,0 +1,461 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Composes calls of public functions into a single script, for the
+//! developer API.
+//!
+//! The script is generated as bytecode rather than compiled from source, so
+//! any published module can be called without its sources: the handles the
+//! script needs are copied from the bytecode of the modules it calls. The
+//! `main` function of the script takes the signers first, which every call
+//! shares, then the arguments of each call in order, which it passes on as
+//! they are. Type arguments are fixed in the script.
+
+use anyhow::Context as AnyhowContext;
+use move_deps::move_binary_format::access::{ModuleAccess, ScriptAccess};
+use move_deps::move_binary_format::file_format::{
+    empty_script, AbilitySet, AddressIdentifierIndex, Bytecode, CompiledModule, CompiledScript,
+    FunctionHandle, FunctionHandleIndex, FunctionInstantiation, FunctionInstantiationIndex,
+    IdentifierIndex, ModuleHandle, ModuleHandleIndex, Signature, SignatureIndex, SignatureToken,
+    StructHandle, StructHandleIndex, StructTypeParameter, TableIndex, Visibility,
+};
+use move_deps::move_bytecode_verifier::verify_script;
+use move_deps::move_core_types::account_address::AccountAddress;
+use move_deps::move_core_types::identifier::{IdentStr, Identifier};
+use move_deps::move_core_types::language_storage::{ModuleId, TypeTag, CORE_CODE_ADDRESS};
+
+#[derive(Debug)]
+pub enum ScriptCompositionError {
+    /// The calls can't be composed, e.g. a function doesn't exist or isn't
+    /// public, or takes params a script can't be given.
+    InvalidInput(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for ScriptCompositionError {
+    fn from(err: anyhow::Error) -> Self {
+        ScriptCompositionError::Internal(err)
+    }
+}
+
+/// A call of a public function, by the script.
+pub struct ScriptCall {
+    pub module: ModuleId,
+    pub function: Identifier,
+    pub type_arguments: Vec<TypeTag>,
+    /// The number of arguments given for the call, which must be the number
+    /// of non-signer params of the function.
+    pub num_arguments: usize,
+}
+
+/// How the script passes on one of its params to a call.
+enum CallArgument {
+    /// The signer with this index among the signers of the script.
+    Signer(usize),
+    /// An argument of the type of the param of the script.
+    Value(SignatureToken),
+}
+
+/// Returns the bytecode of a script making the calls in order. `get_module`
+/// reads the modules holding the called functions and the structs of the
+/// type arguments.
+pub fn compose_script(
+    calls: &[ScriptCall],
+    get_module: impl Fn(&ModuleId) -> Result<CompiledModule, ScriptCompositionError>,
+) -> Result<Vec<u8>, ScriptCompositionError> {
+    if calls.is_empty() {
+        return Err(invalid_input("At least one call must be given".to_string()));
+    }
+
+    let mut builder = ScriptBuilder {
+        script: empty_script(),
+    };
+    let mut num_signers = 0;
+    let mut planned_calls = vec![];
+    for (i, call) in calls.iter().enumerate() {
+        let (instruction, arguments) =
+            builder
+                .add_call(call, &get_module)
+                .map_err(|err| match err {
+                    ScriptCompositionError::InvalidInput(message) => call_error(i, call, message),
+                    err => err,
+                })?;
+        let call_signers = arguments
+            .iter()
+            .filter(|argument| matches!(argument, CallArgument::Signer(_)))
+            .count();
+        if arguments.len() - call_signers != call.num_arguments {
+            return Err(call_error(
+                i,
+                call,
+                format!(
+                    "expected {} arguments, but got {}",
+                    arguments.len() - call_signers,
+                    call.num_arguments
+                ),
+            ));
+        }
+        num_signers = num_signers.max(call_signers);
+        planned_calls.push((instruction, arguments));
+    }
+
+    // The signers come first, then the arguments of every call, each of which
+    // is moved into its call.
+    let signer = SignatureToken::Reference(Box::new(SignatureToken::Signer));
+    let mut params = vec![signer; num_signers];
+    let mut code = vec![];
+    for (instruction, arguments) in planned_calls {
+        for argument in arguments {
+            match argument {
+                CallArgument::Signer(index) => code.push(Bytecode::CopyLoc(index as u8)),
+                CallArgument::Value(token) => {
+                    if params.len() > u8::MAX as usize {
+                        return Err(invalid_input(format!(
+                            "The calls take more than {} params in total",
+                            u8::MAX
+                        )));
+                    }
+                    code.push(Bytecode::MoveLoc(params.len() as u8));
+                    params.push(token);
+                }
+            }
+        }
+        code.push(instruction);
+    }
+    code.push(Bytecode::Ret);
+
+    builder.script.parameters = builder.signature(params);
+    builder.script.code.code = code;
+    let script = builder.script;
+    verify_script(&script).map_err(|err| {
+        invalid_input(format!(
+            "The composed script doesn't verify, e.g. a type argument lacks an ability the \
+             function requires: {:?}",
+            err.major_status()
+        ))
+    })?;
+
+    let mut bytes = vec![];
+    script
+        .serialize(&mut bytes)
+        .context("Failed to serialize the composed script")?;
+    Ok(bytes)
+}
+
+struct ScriptBuilder {
+    script: CompiledScript,
+}
+
+impl ScriptBuilder {
+    /// Adds the handles of the call to the script, returning the instruction
+    /// making it and how its params are given.
+    fn add_call(
+        &mut self,
+        call: &ScriptCall,
+        get_module: &impl Fn(&ModuleId) -> Result<CompiledModule, ScriptCompositionError>,
+    ) -> Result<(Bytecode, Vec<CallArgument>), ScriptCompositionError> {
+        let module = get_module(&call.module)?;
+        let function_def = module
+            .function_defs()
+            .iter()
+            .find(|def| {
+                module.identifier_at(module.function_handle_at(def.function).name)
+                    == call.function.as_ident_str()
+            })
+            .ok_or_else(|| invalid_input("The function can't be found".to_string()))?;
+        if function_def.visibility != Visibility::Public {
+            return Err(invalid_input(
+                "The function isn't public, so a script can't call it".to_string(),
+            ));
+        }
+        let function_handle = module.function_handle_at(function_def.function);
+        if !module.signature_at(function_handle.return_).is_empty() {
+            return Err(invalid_input(
+                "The function returns values, which a script would have to drop".to_string(),
+            ));
+        }
+        if function_handle.type_parameters.len() != call.type_arguments.len() {
+            return Err(invalid_input(format!(
+                "expected {} type arguments, but got {}",
+                function_handle.type_parameters.len(),
+                call.type_arguments.len()
+            )));
+        }
+
+        let type_arguments = call
+            .type_arguments
+            .iter()
+            .map(|type_tag| self.type_tag_token(type_tag, get_module))
+            .collect::<Result<Vec<_>, _>>()?;
+        let params: Vec<_> = module
+            .signature_at(function_handle.parameters)
+            .0
+            .iter()
+            .map(|token| self.import_token(&module, token))
+            .collect();
+
+        let mut num_signers = 0;
+        let mut arguments = vec![];
+        for param in &params {
+            let argument = match substitute(param, &type_arguments) {
+                SignatureToken::Reference(token) if *token == SignatureToken::Signer => {
+                    num_signers += 1;
+                    CallArgument::Signer(num_signers - 1)
+                }
+                SignatureToken::Signer => {
+                    return Err(invalid_input(
+                        "The function takes a signer by value, which a script can't share \
+                         between calls"
+                            .to_string(),
+                    ))
+                }
+                token if self.is_valid_argument(&token) => CallArgument::Value(token),
+                _ => {
+                    return Err(invalid_input(
+                        "The function takes a param a script can't be given, only primitives, \
+                         vectors and strings can be"
+                            .to_string(),
+                    ))
+                }
+            };
+            arguments.push(argument);
+        }
+
+        let handle = FunctionHandle {
+            module: self.module_handle(&call.module),
+            name: self.identifier(call.function.as_ident_str()),
+            parameters: self.signature(params),
+            return_: self.signature(vec![]),
+            type_parameters: function_handle.type_parameters.clone(),
+        };
+        let handle_index = self.function_handle(handle);
+        let instruction = if type_arguments.is_empty() {
+            Bytecode::Call(handle_index)
+        } else {
+            let instantiation = FunctionInstantiation {
+                handle: handle_index,
+                type_parameters: self.signature(type_arguments),
+            };
+            Bytecode::CallGeneric(self.function_instantiation(instantiation))
+        };
+        Ok((instruction, arguments))
+    }
+
+    /// Transaction arguments may only be primitives, vectors of them and
+    /// `0x1::string::String`.
+    fn is_valid_argument(&self, token: &SignatureToken) -> bool {
+        match token {
+            SignatureToken::Bool
+            | SignatureToken::U8
+            | SignatureToken::U64
+            | SignatureToken::U128
+            | SignatureToken::Address => true,
+            SignatureToken::Vector(token) => self.is_valid_argument(token),
+            SignatureToken::Struct(index) => {
+                let handle = self.script.struct_handle_at(*index);
+                let module = self.script.module_handle_at(handle.module);
+                *self.script.address_identifier_at(module.address) == CORE_CODE_ADDRESS
+                    && self.script.identifier_at(module.name).as_str() == "string"
+                    && self.script.identifier_at(handle.name).as_str() == "String"
+            }
+            _ => false,
+        }
+    }
+
+    /// Translates a token of `module` into one of the script.
+    fn import_token(&mut self, module: &CompiledModule, token: &SignatureToken) -> SignatureToken {
+        match token {
+            SignatureToken::Struct(index) => {
+                SignatureToken::Struct(self.import_struct_handle(module, *index))
+            }
+            SignatureToken::StructInstantiation(index, type_params) => {
+                SignatureToken::StructInstantiation(
+                    self.import_struct_handle(module, *index),
+                    type_params
+                        .iter()
+                        .map(|token| self.import_token(module, token))
+                        .collect(),
+                )
+            }
+            SignatureToken::Vector(token) => {
+                SignatureToken::Vector(Box::new(self.import_token(module, token)))
+            }
+            SignatureToken::Reference(token) => {
+                SignatureToken::Reference(Box::new(self.import_token(module, token)))
+            }
+            SignatureToken::MutableReference(token) => {
+                SignatureToken::MutableReference(Box::new(self.import_token(module, token)))
+            }
+            token => token.clone(),
+        }
+    }
+
+    fn import_struct_handle(
+        &mut self,
+        module: &CompiledModule,
+        index: StructHandleIndex,
+    ) -> StructHandleIndex {
+        let handle = module.struct_handle_at(index);
+        let module_id = module.module_id_for_handle(module.module_handle_at(handle.module));
+        self.struct_handle(
+            &module_id,
+            module.identifier_at(handle.name),
+            handle.abilities,
+            handle.type_parameters.clone(),
+        )
+    }
+
+    fn type_tag_token(
+        &mut self,
+        type_tag: &TypeTag,
+        get_module: &impl Fn(&ModuleId) -> Result<CompiledModule, ScriptCompositionError>,
+    ) -> Result<SignatureToken, ScriptCompositionError> {
+        Ok(match type_tag {
+            TypeTag::Bool => SignatureToken::Bool,
+            TypeTag::U8 => SignatureToken::U8,
+            TypeTag::U64 => SignatureToken::U64,
+            TypeTag::U128 => SignatureToken::U128,
+            TypeTag::Address => SignatureToken::Address,
+            TypeTag::Signer => SignatureToken::Signer,
+            TypeTag::Vector(type_tag) => {
+                SignatureToken::Vector(Box::new(self.type_tag_token(type_tag, get_module)?))
+            }
+            TypeTag::Struct(struct_tag) => {
+                let module_id = ModuleId::new(struct_tag.address, struct_tag.module.clone());
+                let module = get_module(&module_id)?;
+                let handle = module
+                    .struct_defs()
+                    .iter()
+                    .map(|def| module.struct_handle_at(def.struct_handle))
+                    .find(|handle| {
+                        module.identifier_at(handle.name) == struct_tag.name.as_ident_str()
+                    })
+                    .ok_or_else(|| {
+                        invalid_input(format!("The struct {} can't be found", struct_tag))
+                    })?;
+                let index = self.struct_handle(
+                    &module_id,
+                    &struct_tag.name,
+                    handle.abilities,
+                    handle.type_parameters.clone(),
+                );
+                if struct_tag.type_params.is_empty() {
+                    SignatureToken::Struct(index)
+                } else {
+                    SignatureToken::StructInstantiation(
+                        index,
+                        struct_tag
+                            .type_params
+                            .iter()
+                            .map(|type_tag| self.type_tag_token(type_tag, get_module))
+                            .collect::<Result<_, _>>()?,
+                    )
+                }
+            }
+        })
+    }
+
+    // The pools of a script may not hold duplicates, so each of these returns
+    // the index of an equal entry if there is one.
+
+    fn identifier(&mut self, identifier: &IdentStr) -> IdentifierIndex {
+        IdentifierIndex(find_or_push(
+            &mut self.script.identifiers,
+            identifier.to_owned(),
+        ))
+    }
+
+    fn address_identifier(&mut self, address: AccountAddress) -> AddressIdentifierIndex {
+        AddressIdentifierIndex(find_or_push(&mut self.script.address_identifiers, address))
+    }
+
+    fn signature(&mut self, tokens: Vec<SignatureToken>) -> SignatureIndex {
+        SignatureIndex(find_or_push(&mut self.script.signatures, Signature(tokens)))
+    }
+
+    fn module_handle(&mut self, module_id: &ModuleId) -> ModuleHandleIndex {
+        let handle = ModuleHandle {
+            address: self.address_identifier(*module_id.address()),
+            name: self.identifier(module_id.name()),
+        };
+        ModuleHandleIndex(find_or_push(&mut self.script.module_handles, handle))
+    }
+
+    fn struct_handle(
+        &mut self,
+        module_id: &ModuleId,
+        name: &IdentStr,
+        abilities: AbilitySet,
+        type_parameters: Vec<StructTypeParameter>,
+    ) -> StructHandleIndex {
+        let handle = StructHandle {
+            module: self.module_handle(module_id),
+            name: self.identifier(name),
+            abilities,
+            type_parameters,
+        };
+        StructHandleIndex(find_or_push(&mut self.script.struct_handles, handle))
+    }
+
+    fn function_handle(&mut self, handle: FunctionHandle) -> FunctionHandleIndex {
+        FunctionHandleIndex(find_or_push(&mut self.script.function_handles, handle))
+    }
+
+    fn function_instantiation(
+        &mut self,
+        instantiation: FunctionInstantiation,
+    ) -> FunctionInstantiationIndex {
+        FunctionInstantiationIndex(find_or_push(
+            &mut self.script.function_instantiations,
+            instantiation,
+        ))
+    }
+}
+
+fn find_or_push<T: PartialEq>(pool: &mut Vec<T>, item: T) -> TableIndex {
+    match pool.iter().position(|existing| *existing == item) {
+        Some(index) => index as TableIndex,
+        None => {
+            pool.push(item);
+            (pool.len() - 1) as TableIndex
+        }
+    }
+}
+
+/// Replaces the type params of a token by the given type arguments.
+fn substitute(token: &SignatureToken, type_arguments: &[SignatureToken]) -> SignatureToken {
+    match token {
+        SignatureToken::TypeParameter(index) => type_arguments[*index as usize].clone(),
+        SignatureToken::StructInstantiation(index, type_params) => {
+            SignatureToken::StructInstantiation(
+                *index,
+                type_params
+                    .iter()
+                    .map(|token| substitute(token, type_arguments))
+                    .collect(),
+            )
+        }
+        SignatureToken::Vector(token) => {
+            SignatureToken::Vector(Box::new(substitute(token, type_arguments)))
+        }
+        SignatureToken::Reference(token) => {
+            SignatureToken::Reference(Box::new(substitute(token, type_arguments)))
+        }
+        SignatureToken::MutableReference(token) => {
+            SignatureToken::MutableReference(Box::new(substitute(token, type_arguments)))
+        }
+        token => token.clone(),
+    }
+}
+
+/// An error of the call at `index` of the request.
+fn call_error(index: usize, call: &ScriptCall, message: String) -> ScriptCompositionError {
+    invalid_input(format!(
+        "calls[{}] {}::{}: {}",
+        index, call.module, call.function, message
+    ))
+}
+
+fn invalid_input(message: String) -> ScriptCompositionError {
+    ScriptCompositionError::InvalidInput(message)
+}
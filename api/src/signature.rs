@@ -0,0 +1,172 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validates the signatures of a submitted transaction, and the chain ID they
+//! sign over, before it goes to mempool.
+//!
+//! Mempool only tells us that a transaction has an INVALID_SIGNATURE, which
+//! is little help when a transaction carries signatures from several signers,
+//! or a MultiEd25519 signature made up of several keys. Here we check every
+//! signer separately and, within a MultiEd25519 signature, every key selected
+//! by its bitmap, so the error can say exactly which public keys failed.
+//!
+//! The checks of each scheme are done by its validator in the
+//! `AUTHENTICATOR_REGISTRY`, so new schemes need no changes here.
+
+use crate::response::{AptosErrorResponse, BadRequestError};
+use aptos_api_types::{AptosErrorCode, TransactionSignerRole};
+use aptos_crypto::signing_message;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::chain_id::ChainId;
+use aptos_types::transaction::authenticator::{Scheme, TransactionAuthenticator};
+use aptos_types::transaction::authenticator_registry::AUTHENTICATOR_REGISTRY;
+use aptos_types::transaction::{RawTransactionWithData, SignedTransaction};
+
+/// Why the signature of one signer of a transaction is invalid.
+#[derive(Debug, PartialEq)]
+pub struct SignerFailure {
+    /// The position of the signer in the transaction: the sender is 0 and the
+    /// secondary signers follow in order.
+    pub signer_index: usize,
+    pub address: AccountAddress,
+    pub role: TransactionSignerRole,
+    pub scheme: Scheme,
+    /// Indices of the public keys whose signatures did not verify. For
+    /// Ed25519 this is just the one key.
+    pub failed_public_keys: Vec<usize>,
+    pub reason: String,
+}
+
+/// Checks the signature of every signer of the transaction, returning the
+/// ones that are invalid.
+pub fn check_signatures(txn: &SignedTransaction) -> Vec<SignerFailure> {
+    let raw_txn = txn.clone().into_raw_transaction();
+    let authenticator = txn.authenticator();
+    let message = match &authenticator {
+        TransactionAuthenticator::Ed25519 { .. }
+        | TransactionAuthenticator::MultiEd25519 { .. } => signing_message(&raw_txn),
+        TransactionAuthenticator::MultiAgent {
+            secondary_signer_addresses,
+            ..
+        } => signing_message(&RawTransactionWithData::new_multi_agent(
+            raw_txn,
+            secondary_signer_addresses.clone(),
+        )),
+    };
+
+    let mut signers = vec![(
+        txn.sender(),
+        TransactionSignerRole::Sender,
+        authenticator.sender(),
+    )];
+    signers.extend(
+        authenticator
+            .secondary_signer_addreses()
+            .into_iter()
+            .zip(authenticator.secondary_signers())
+            .map(|(address, signer)| (address, TransactionSignerRole::SecondarySigner, signer)),
+    );
+
+    signers
+        .into_iter()
+        .enumerate()
+        .filter_map(|(signer_index, (address, role, signer))| {
+            let failure = AUTHENTICATOR_REGISTRY.diagnose(&signer, &message)?;
+            Some(SignerFailure {
+                signer_index,
+                address,
+                role,
+                scheme: signer.scheme(),
+                failed_public_keys: failure.failed_public_keys,
+                reason: failure.reason,
+            })
+        })
+        .collect()
+}
+
+/// Builds the error returned for a transaction with invalid signatures. Each
+/// failed signer gets its own set of details, keyed by its index among the
+/// signers, i.e. `signer.0.*` is the sender.
+pub fn signature_error<E: BadRequestError>(failures: &[SignerFailure]) -> E {
+    let addresses: Vec<String> = failures
+        .iter()
+        .map(|failure| failure.address.to_hex_literal())
+        .collect();
+    let mut error = E::bad_request_with_code(
+        format!(
+            "invalid transaction: invalid signatures from {}",
+            addresses.join(", ")
+        ),
+        AptosErrorCode::InvalidSignature,
+    )
+    .detail(
+        "remediation",
+        "Sign the BCS encoded signing message of the transaction, which can be built with the encode_submission endpoint",
+    );
+    for failure in failures {
+        let index = failure.signer_index;
+        let failed_public_keys: Vec<String> = failure
+            .failed_public_keys
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        error = error
+            .detail(
+                &format!("signer.{}.address", index),
+                failure.address.to_hex_literal(),
+            )
+            .detail(&format!("signer.{}.role", index), role_name(failure.role))
+            .detail(&format!("signer.{}.scheme", index), failure.scheme.name())
+            .detail(
+                &format!("signer.{}.failed_public_keys", index),
+                failed_public_keys.join(","),
+            )
+            .detail(&format!("signer.{}.reason", index), &failure.reason);
+    }
+    error
+}
+
+/// Returns an error describing every invalid signature of the transaction, if
+/// there are any.
+pub fn validate_signatures<E: BadRequestError>(txn: &SignedTransaction) -> Result<(), E> {
+    let failures = check_signatures(txn);
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(signature_error(&failures))
+    }
+}
+
+fn role_name(role: TransactionSignerRole) -> &'static str {
+    match role {
+        TransactionSignerRole::Sender => "sender",
+        TransactionSignerRole::SecondarySigner => "secondary_signer",
+    }
+}
+
+/// Returns an error if the transaction is for another chain than the node's.
+/// Mempool would only discard it with BAD_CHAIN_ID, after checking the
+/// signatures, which are valid as they sign over the transaction's chain ID.
+pub fn validate_chain_id<E: BadRequestError>(
+    txn: &SignedTransaction,
+    chain_id: ChainId,
+) -> Result<(), E> {
+    if txn.chain_id() == chain_id {
+        return Ok(());
+    }
+    Err(E::bad_request_with_code(
+        format!(
+            "invalid transaction: the transaction is for chain {} but the node is on chain {}",
+            txn.chain_id(),
+            chain_id
+        ),
+        AptosErrorCode::ChainIdMismatch,
+    )
+    .detail("transaction_chain_id", txn.chain_id().id())
+    .detail("node_chain_id", chain_id.id())
+    .detail("node_chain_name", chain_id)
+    .detail(
+        "remediation",
+        "Sign the transaction again with the chain ID of the node, which the index endpoint returns",
+    ))
+}
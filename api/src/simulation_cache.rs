@@ -0,0 +1,81 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::UserTransaction;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::transaction::Version;
+use lru::LruCache;
+
+/// What a simulation depends on: the transaction, the state it runs against
+/// and whether its state diff was requested.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SimulationKey {
+    pub txn_hash: HashValue,
+    pub state_version: Version,
+    pub include_state_diff: bool,
+}
+
+/// A bounded cache of the results of simulations, so that simulating the same
+/// transaction against the same state again, e.g. as wallets re-render, skips
+/// running the VM. Only simulations the VM keeps are cached: those it would
+/// discard are reported as errors.
+///
+/// When the cache is full, the least recently used entry is evicted.
+pub struct SimulationCache {
+    results: Mutex<LruCache<SimulationKey, Vec<UserTransaction>>>,
+}
+
+impl SimulationCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, key: &SimulationKey) -> Option<Vec<UserTransaction>> {
+        self.results.lock().get(key).cloned()
+    }
+
+    pub fn insert(&self, key: SimulationKey, result: Vec<UserTransaction>) {
+        let mut results = self.results.lock();
+        if results.cap() > 0 {
+            results.put(key, result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimulationCache, SimulationKey};
+    use aptos_crypto::HashValue;
+
+    fn key(state_version: u64) -> SimulationKey {
+        SimulationKey {
+            txn_hash: HashValue::zero(),
+            state_version,
+            include_state_diff: false,
+        }
+    }
+
+    #[test]
+    fn test_keyed_by_state_version_and_state_diff() {
+        let cache = SimulationCache::new(10);
+        cache.insert(key(1), vec![]);
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache
+            .get(&SimulationKey {
+                include_state_diff: true,
+                ..key(1)
+            })
+            .is_none());
+    }
+
+    #[test]
+    fn test_disabled_cache() {
+        let cache = SimulationCache::new(0);
+        cache.insert(key(1), vec![]);
+        assert!(cache.get(&key(1)).is_none());
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::accept_type::AcceptType;
+use crate::context::Context;
+use crate::failpoint::fail_point_poem;
+use crate::response::{
+    build_not_found, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+    BasicResultWith404, InternalError,
+};
+use crate::ApiTags;
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, StakePoolSummary, StakeRewards, TransactionId, U64,
+};
+use aptos_types::access_path::AccessPath;
+use aptos_types::stake_pool::{DistributeRewardsEvent, StakePool};
+use aptos_types::state_store::state_key::StateKey;
+use move_deps::move_core_types::language_storage::ResourceKey;
+use move_deps::move_core_types::move_resource::MoveStructType;
+use poem_openapi::param::Query;
+use poem_openapi::{param::Path, OpenApi};
+use std::sync::Arc;
+
+pub struct StakingApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl StakingApi {
+    /// Get stake pool
+    ///
+    /// Get the stake of the stake pool at a given address, at a specified
+    /// ledger version (AKA transaction version): its active, inactive and
+    /// pending stake, and when its lockup expires relative to the timestamp
+    /// of the ledger version. If the ledger version is not specified in the
+    /// request, the latest ledger version is used.
+    ///
+    /// If `rewards_since_version` is given, the response also sums up the
+    /// rewards distributed to the pool from that version on, which requires
+    /// the events of those versions not to be pruned.
+    #[oai(
+        path = "/accounts/:address/stake_pool",
+        method = "get",
+        operation_id = "get_stake_pool",
+        tag = "ApiTags::Staking"
+    )]
+    async fn get_stake_pool(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        ledger_version: Query<Option<U64>>,
+        rewards_since_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<StakePoolSummary> {
+        fail_point_poem("endpoint_get_stake_pool")?;
+        self.stake_pool(
+            &accept_type,
+            address.0,
+            ledger_version.0,
+            rewards_since_version.0,
+        )
+        .await
+    }
+}
+
+impl StakingApi {
+    async fn stake_pool(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        ledger_version: Option<U64>,
+        rewards_since_version: Option<U64>,
+    ) -> BasicResultWith404<StakePoolSummary> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = ledger_version
+            .map(|v| v.0)
+            .unwrap_or_else(|| ledger_info.version());
+        if ledger_version > ledger_info.version() {
+            return Err(build_not_found(
+                "ledger",
+                TransactionId::Version(U64::from(ledger_version)),
+                AptosErrorCode::VersionNotFound,
+                ledger_info.version(),
+            ));
+        }
+        if let Some(since_version) = rewards_since_version {
+            if since_version.0 > ledger_version {
+                return Err(BasicErrorWith404::bad_request_with_code(
+                    format!(
+                        "Rewards since version {} requested, past the ledger version {}",
+                        since_version, ledger_version
+                    ),
+                    AptosErrorCode::InvalidInput,
+                ));
+            }
+        }
+
+        let resource_key = ResourceKey::new(address.into(), StakePool::struct_tag());
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(resource_key));
+        let bytes = self
+            .context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ok_or_else(|| {
+                build_not_found(
+                    "Stake pool",
+                    address,
+                    AptosErrorCode::ResourceNotFound,
+                    ledger_version,
+                )
+            })?;
+        let stake_pool: StakePool = bcs::from_bytes(&bytes)
+            .context("Failed to deserialize stake pool retrieved from DB")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
+        let timestamp_usecs = self
+            .context
+            .get_block_timestamp(ledger_version)
+            .context(format!(
+                "Failed to read the timestamp of version {}",
+                ledger_version
+            ))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+
+        let rewards = match rewards_since_version {
+            Some(since_version) => Some(
+                self.rewards_since(&stake_pool, since_version.0, ledger_version)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        BasicResponse::try_from_rust_value((
+            StakePoolSummary::new(address, &stake_pool, timestamp_usecs, rewards),
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    /// Sums up the rewards distributed to the pool in `[since_version, ledger_version]`, walking
+    /// its rewards events back from the latest one, a page at a time.
+    async fn rewards_since(
+        &self,
+        stake_pool: &StakePool,
+        since_version: u64,
+        ledger_version: u64,
+    ) -> Result<StakeRewards, BasicErrorWith404> {
+        let event_handle = stake_pool.distribute_rewards_events();
+        let page_size = self.context.max_page_size() as u64;
+        let mut amount: u64 = 0;
+        let mut num_distributions: u64 = 0;
+        let mut end = event_handle.count();
+        'pages: while end > 0 {
+            let start = end.saturating_sub(page_size);
+            let events = self
+                .context
+                .get_events(
+                    event_handle.key(),
+                    Some(start),
+                    (end - start) as u16,
+                    ledger_version,
+                )
+                .await
+                .context("Failed to read the rewards events of the stake pool")
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+                })?;
+            for event in events.iter().rev() {
+                if event.transaction_version < since_version {
+                    break 'pages;
+                }
+                let rewards = DistributeRewardsEvent::try_from_bytes(event.event.event_data())
+                    .context("Failed to deserialize rewards event retrieved from DB")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?;
+                amount = amount.saturating_add(rewards.rewards_amount());
+                num_distributions += 1;
+            }
+            end = start;
+        }
+
+        Ok(StakeRewards {
+            since_version: since_version.into(),
+            amount: amount.into(),
+            num_distributions: num_distributions.into(),
+        })
+    }
+}
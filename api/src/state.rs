@@ -2,25 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::accept_type::AcceptType;
+use crate::bcs_payload::Bcs;
 use crate::context::Context;
 use crate::failpoint::fail_point_poem;
+use crate::page::Page;
 use crate::response::{
-    build_not_found, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
-    BasicResultWith404, InternalError, NotFoundError,
+    build_not_found, items_within_size_budget, BadRequestError, BasicErrorWith404, BasicResponse,
+    BasicResponseStatus, BasicResultWith404, HeadResponse, HeadResultWith404, InternalError,
+    NotFoundError,
 };
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    Address, AsConverter, IdentifierWrapper, MoveModuleBytecode, MoveStructTag, MoveValue,
-    TableItemRequest, TransactionId, U128, U64,
+    AccountResources, AccountResourcesRequest, Address, AptosErrorCode, Cursor, DecodedTableData,
+    GasSchedule, HashValue, HexEncodedBytes, IdentifierWrapper, MoveModuleBytecode,
+    MoveModuleDisassembly, MoveModuleVerification, MoveStructTag, MoveValue, OnChainConfigs,
+    RawStateValueRequest, TableItemRequest, TableItems, TransactionId, ValidatorSet, U128, U64,
 };
 use aptos_api_types::{LedgerInfo, MoveResource};
-use aptos_state_view::StateView;
 use aptos_types::access_path::AccessPath;
+use aptos_types::account_state::AccountState;
+use aptos_types::on_chain_config::{
+    access_path_for_config, BlockGasLimit, GasSchedule as OnChainGasSchedule, OnChainConfig,
+    OnChainConsensusConfig, ParallelExecutionConfig, ValidatorSet as OnChainValidatorSet, Version,
+};
 use aptos_types::state_store::state_key::StateKey;
 use aptos_types::state_store::table::TableHandle;
 use aptos_vm::data_cache::AsMoveResolver;
+use framework::natives::code::{PackageMetadata, PackageRegistry};
+use framework::source_attestation::SourceAttestation;
+use move_deps::move_bytecode_source_map::source_map::SourceMap;
 use move_deps::move_core_types::language_storage::{ModuleId, ResourceKey, StructTag};
+use move_deps::move_core_types::move_resource::MoveStructType;
 use poem_openapi::param::Query;
 use poem_openapi::payload::Json;
 use poem_openapi::{param::Path, OpenApi};
@@ -28,10 +41,19 @@ use std::convert::TryInto;
 use std::sync::Arc;
 use storage_interface::state_view::DbStateView;
 
+/// The maximum number of entries accepted by the batch account resources endpoint.
+const MAX_BATCH_ACCOUNT_RESOURCES_REQUESTS: usize = 100;
+
 pub struct StateApi {
     pub context: Arc<Context>,
 }
 
+/// What was read from storage for an entry of a batch account resources request.
+enum BatchEntry {
+    Resource(StructTag, Option<Vec<u8>>),
+    Account(Option<AccountState>),
+}
+
 #[OpenApi]
 impl StateApi {
     /// Get specific account resource
@@ -58,6 +80,62 @@ impl StateApi {
     ) -> BasicResultWith404<MoveResource> {
         fail_point_poem("endpoint_get_account_resource")?;
         self.resource(&accept_type, address.0, resource_type.0, ledger_version.0)
+            .await
+    }
+
+    /// Check specific account resource exists
+    ///
+    /// This endpoint responds to a HEAD request for the resource of a specific
+    /// type residing at a given account with the status and headers the GET
+    /// request would respond with, but without a body. The resource is not
+    /// deserialized, so this is a cheap way to check whether it exists at a
+    /// ledger version before requesting it.
+    #[oai(
+        path = "/accounts/:address/resource/:resource_type",
+        method = "head",
+        operation_id = "head_account_resource",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn head_account_resource(
+        &self,
+        address: Path<Address>,
+        resource_type: Path<MoveStructTag>,
+        ledger_version: Query<Option<U64>>,
+    ) -> HeadResultWith404 {
+        fail_point_poem("endpoint_head_account_resource")?;
+        self.resource_exists(address.0, resource_type.0, ledger_version.0)
+            .await
+    }
+
+    /// Get resources of multiple accounts
+    ///
+    /// This endpoint returns the resources of every account given in the
+    /// request body, all read at the same ledger version (AKA transaction
+    /// version). If the ledger version is not specified in the request, the
+    /// latest ledger version is used.
+    ///
+    /// Each entry may optionally specify a resource type, in which case only
+    /// the resource of that type is returned for that account. Accounts and
+    /// resources that don't exist at the ledger version are returned with an
+    /// empty list of resources rather than failing the whole request.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window (link).
+    /// If the requested data has been pruned, the server responds with a 404.
+    #[oai(
+        path = "/accounts/resources/batch",
+        method = "post",
+        operation_id = "get_batch_account_resources",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_batch_account_resources(
+        &self,
+        accept_type: AcceptType,
+        requests: Json<Vec<AccountResourcesRequest>>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<AccountResources>> {
+        fail_point_poem("endpoint_get_batch_account_resources")?;
+        self.batch_resources(&accept_type, requests.0, ledger_version.0)
+            .await
     }
 
     /// Get specific account module
@@ -84,6 +162,104 @@ impl StateApi {
     ) -> BasicResultWith404<MoveModuleBytecode> {
         fail_point_poem("endpoint_get_account_module")?;
         self.module(&accept_type, address.0, module_name.0, ledger_version.0)
+            .await
+    }
+
+    /// Check specific account module exists
+    ///
+    /// This endpoint responds to a HEAD request for the module with a specific
+    /// name residing at a given account with the status and headers the GET
+    /// request would respond with, but without a body. The module is not
+    /// parsed, so this is a cheap way to check whether it exists at a ledger
+    /// version before requesting it.
+    #[oai(
+        path = "/accounts/:address/module/:module_name",
+        method = "head",
+        operation_id = "head_account_module",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn head_account_module(
+        &self,
+        address: Path<Address>,
+        module_name: Path<IdentifierWrapper>,
+        ledger_version: Query<Option<U64>>,
+    ) -> HeadResultWith404 {
+        fail_point_poem("endpoint_head_account_module")?;
+        self.module_exists(address.0, module_name.0, ledger_version.0)
+            .await
+    }
+
+    /// Get specific account module disassembly
+    ///
+    /// This endpoint returns the textual disassembly of the bytecode of the
+    /// module with a specific name residing at a given account, at a specified
+    /// ledger version (AKA transaction version). If the ledger version is not
+    /// specified in the request, the latest ledger version is used.
+    ///
+    /// When the package of the module was published with its source maps, the
+    /// disassembly names locals and parameters as they are named in the source.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window (link).
+    /// If the requested data has been pruned, the server responds with a 404.
+    #[oai(
+        path = "/accounts/:address/module/:module_name/disassembly",
+        method = "get",
+        operation_id = "get_account_module_disassembly",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_module_disassembly(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        module_name: Path<IdentifierWrapper>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<MoveModuleDisassembly> {
+        fail_point_poem("endpoint_get_account_module_disassembly")?;
+        self.module_disassembly(&accept_type, address.0, module_name.0, ledger_version.0)
+            .await
+    }
+
+    /// Verify specific account module
+    ///
+    /// This endpoint reports whether the bytecode of the module with a
+    /// specific name residing at a given account is the one its publisher
+    /// attests to have compiled from a source, at a specified ledger version
+    /// (AKA transaction version). If the ledger version is not specified in
+    /// the request, the latest ledger version is used.
+    ///
+    /// Publishers attest the hash of the source of each module and the
+    /// version of the toolchain that compiled it in the build info of the
+    /// package, which is signed along with the transaction publishing it.
+    /// When a source hash or a toolchain version is given, the module is only
+    /// reported as verified if the attestation is for those.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window (link).
+    /// If the requested data has been pruned, the server responds with a 404.
+    #[oai(
+        path = "/accounts/:address/module/:module_name/verification",
+        method = "get",
+        operation_id = "get_account_module_verification",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_module_verification(
+        &self,
+        accept_type: AcceptType,
+        address: Path<Address>,
+        module_name: Path<IdentifierWrapper>,
+        source_hash: Query<Option<HashValue>>,
+        toolchain_version: Query<Option<String>>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<MoveModuleVerification> {
+        fail_point_poem("endpoint_get_account_module_verification")?;
+        self.module_verification(
+            &accept_type,
+            address.0,
+            module_name.0,
+            source_hash.0,
+            toolchain_version.0,
+            ledger_version.0,
+        )
+        .await
     }
 
     /// Get table item
@@ -115,6 +291,147 @@ impl StateApi {
             table_item_request.0,
             ledger_version.0,
         )
+        .await
+    }
+
+    /// Get table items
+    ///
+    /// Get the items of the table identified by {table_handle} in the path at
+    /// a specified ledger version (AKA transaction version), decoded with the
+    /// key and value types of the table. If the ledger version is not
+    /// specified in the request, the latest ledger version is used.
+    ///
+    /// Items are listed in the order of the BCS encoding of their keys. The
+    /// `cursor` of a page is passed back as `cursor` to request the next one.
+    ///
+    /// This endpoint is only available on nodes with the internal indexer
+    /// enabled, which also tracks the keys written to each table.
+    #[oai(
+        path = "/tables/:table_handle/items",
+        method = "get",
+        operation_id = "get_table_items",
+        tag = "ApiTags::Tables"
+    )]
+    async fn get_table_items(
+        &self,
+        accept_type: AcceptType,
+        table_handle: Path<U128>,
+        /// Max number of items to retrieve
+        limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the previous page
+        cursor: Query<Option<Cursor>>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<TableItems> {
+        fail_point_poem("endpoint_get_table_items")?;
+        self.table_items(
+            &accept_type,
+            table_handle.0,
+            limit.0,
+            cursor.0,
+            ledger_version.0,
+        )
+        .await
+    }
+
+    /// Get gas schedule
+    ///
+    /// Get the gas schedule in effect at a specified ledger version (AKA
+    /// transaction version): the value of every gas parameter, by name, along
+    /// with the feature version the schedule applies to. If the ledger version
+    /// is not specified in the request, the latest ledger version is used.
+    ///
+    /// The schedule covers every parameter transactions are charged by,
+    /// including the size of transactions, so SDKs can use it to estimate
+    /// costs offline.
+    #[oai(
+        path = "/gas_schedule",
+        method = "get",
+        operation_id = "get_gas_schedule",
+        tag = "ApiTags::General"
+    )]
+    async fn get_gas_schedule(
+        &self,
+        accept_type: AcceptType,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<GasSchedule> {
+        fail_point_poem("endpoint_get_gas_schedule")?;
+        self.gas_schedule(&accept_type, ledger_version.0).await
+    }
+
+    /// Get validator set
+    ///
+    /// Get the validator set in effect at a specified ledger version (AKA
+    /// transaction version): the active validators, the validators joining
+    /// and leaving the set at the next epoch, with their voting power and
+    /// consensus public keys. If the ledger version is not specified in the
+    /// request, the latest ledger version is used.
+    #[oai(
+        path = "/validators",
+        method = "get",
+        operation_id = "get_validator_set",
+        tag = "ApiTags::General"
+    )]
+    async fn get_validator_set(
+        &self,
+        accept_type: AcceptType,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<ValidatorSet> {
+        fail_point_poem("endpoint_get_validator_set")?;
+        self.validator_set(&accept_type, ledger_version.0).await
+    }
+
+    /// Get on-chain configs
+    ///
+    /// Get the on-chain configs in effect at a specified ledger version (AKA
+    /// transaction version), decoded: the version of the Aptos software the
+    /// chain runs, which gates the features of the VM and the gas schedule,
+    /// and the configs of consensus and execution, including the block gas
+    /// limit if there is one. If the ledger version is not specified in the
+    /// request, the latest ledger version is used.
+    ///
+    /// This lets operators and SDKs tell what the network supports without
+    /// decoding the config resources themselves.
+    #[oai(
+        path = "/on_chain_configs",
+        method = "get",
+        operation_id = "get_on_chain_configs",
+        tag = "ApiTags::General"
+    )]
+    async fn get_on_chain_configs(
+        &self,
+        accept_type: AcceptType,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<OnChainConfigs> {
+        fail_point_poem("endpoint_get_on_chain_configs")?;
+        self.on_chain_configs(&accept_type, ledger_version.0).await
+    }
+
+    /// Get raw state value
+    ///
+    /// Get the value stored under a state key at a specified ledger version
+    /// (AKA transaction version), exactly as it is stored. The key is given as
+    /// a BCS encoded StateKey in the request body. If the ledger version is not
+    /// specified in the request, the latest ledger version is used.
+    ///
+    /// This is meant for tools that execute transactions locally against the
+    /// state of a node, which need state as the VM reads it rather than
+    /// decoded into JSON. With a BCS Accept header the response body is the
+    /// raw value itself.
+    #[oai(
+        path = "/state_values/raw",
+        method = "post",
+        operation_id = "get_raw_state_value",
+        tag = "ApiTags::General"
+    )]
+    async fn get_raw_state_value(
+        &self,
+        accept_type: AcceptType,
+        request: Json<RawStateValueRequest>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_raw_state_value")?;
+        self.raw_state_value(&accept_type, request.0, ledger_version.0)
+            .await
     }
 }
 
@@ -132,44 +449,40 @@ impl StateApi {
             return Err(build_not_found(
                 "ledger",
                 TransactionId::Version(U64::from(ledger_version)),
+                AptosErrorCode::VersionNotFound,
                 latest_ledger_info.version(),
             ));
         }
 
         let state_view = self.context.state_view_at_version(ledger_version)
             .context(format!("Failed to get state view at version {} even after confirming the ledger has advanced past that version to {}", ledger_version, latest_ledger_info.version()))
-            .map_err(E::internal)?;
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))?;
 
         Ok((latest_ledger_info, ledger_version, state_view))
     }
 
-    fn resource(
+    async fn resource(
         &self,
         accept_type: &AcceptType,
         address: Address,
         resource_type: MoveStructTag,
         ledger_version: Option<U64>,
     ) -> BasicResultWith404<MoveResource> {
-        let resource_type: StructTag = resource_type
-            .try_into()
-            .context("Failed to parse given resource type")
-            .map_err(BasicErrorWith404::bad_request)?;
-        let resource_key = ResourceKey::new(address.into(), resource_type.clone());
-        let access_path = AccessPath::resource_access_path(resource_key.clone());
-        let state_key = StateKey::AccessPath(access_path);
+        let resource_type = parse_resource_type(resource_type)?;
         let (ledger_info, ledger_version, state_view) = self.preprocess_request(ledger_version)?;
-        let bytes = state_view
-            .get_state_value(&state_key)
-            .context(format!("Failed to query DB to check for {:?}", state_key))
-            .map_err(BasicErrorWith404::internal)?
-            .ok_or_else(|| build_not_found("Resource", resource_key, ledger_version))?;
+        let bytes = self
+            .resource_bytes(address, &resource_type, ledger_version)
+            .await?;
 
-        let resource = state_view
-            .as_move_resolver()
-            .as_converter(self.context.db.clone())
+        let resolver = state_view.as_move_resolver();
+        let resource = self
+            .context
+            .converter(&resolver)
             .try_into_resource(&resource_type, &bytes)
             .context("Failed to deserialize resource data retrieved from DB")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
 
         BasicResponse::try_from_rust_value((
             resource,
@@ -179,7 +492,156 @@ impl StateApi {
         ))
     }
 
-    pub fn module(
+    async fn resource_exists(
+        &self,
+        address: Address,
+        resource_type: MoveStructTag,
+        ledger_version: Option<U64>,
+    ) -> HeadResultWith404 {
+        let resource_type = parse_resource_type(resource_type)?;
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        self.resource_bytes(address, &resource_type, ledger_version)
+            .await?;
+        Ok(HeadResponse::from(&ledger_info))
+    }
+
+    async fn resource_bytes(
+        &self,
+        address: Address,
+        resource_type: &StructTag,
+        ledger_version: u64,
+    ) -> Result<Vec<u8>, BasicErrorWith404> {
+        let resource_key = ResourceKey::new(address.into(), resource_type.clone());
+        let access_path = AccessPath::resource_access_path(resource_key.clone());
+        let state_key = StateKey::AccessPath(access_path);
+        self.context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ok_or_else(|| {
+                build_not_found(
+                    "Resource",
+                    resource_key,
+                    AptosErrorCode::ResourceNotFound,
+                    ledger_version,
+                )
+            })
+    }
+
+    async fn batch_resources(
+        &self,
+        accept_type: &AcceptType,
+        requests: Vec<AccountResourcesRequest>,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<Vec<AccountResources>> {
+        if requests.len() > MAX_BATCH_ACCOUNT_RESOURCES_REQUESTS {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Too many accounts requested: {}, the maximum is {}",
+                    requests.len(),
+                    MAX_BATCH_ACCOUNT_RESOURCES_REQUESTS
+                ),
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+
+        // Every entry is read from the same state view, so the response
+        // reflects a single consistent ledger version.
+        let (ledger_info, ledger_version, state_view) = self.preprocess_request(ledger_version)?;
+
+        // The resources of the entries with a resource type are read all at
+        // once, rather than one at a time.
+        let mut parsed_requests = Vec::with_capacity(requests.len());
+        let mut state_keys = Vec::new();
+        for request in requests {
+            let resource_type = match request.resource_type {
+                Some(resource_type) => {
+                    let resource_type = parse_resource_type(resource_type)?;
+                    state_keys.push(StateKey::AccessPath(AccessPath::resource_access_path(
+                        ResourceKey::new(request.address.into(), resource_type.clone()),
+                    )));
+                    Some(resource_type)
+                }
+                None => None,
+            };
+            parsed_requests.push((request.address, resource_type));
+        }
+        let mut resources = self
+            .context
+            .get_state_values_async(state_keys, ledger_version)
+            .await
+            .context("Failed to query DB for the requested resources")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .into_iter();
+
+        // Every entry is read before any is converted, since the converter
+        // can't be held across the reads.
+        let mut entries = Vec::with_capacity(parsed_requests.len());
+        for (address, resource_type) in parsed_requests {
+            let entry = match resource_type {
+                Some(resource_type) => {
+                    BatchEntry::Resource(resource_type, resources.next().flatten())
+                }
+                None => {
+                    let account_state = self
+                        .context
+                        .get_account_state_async(address.into(), ledger_version)
+                        .await
+                        .context(format!("Failed to read account state of {}", address))
+                        .map_err(|e| {
+                            BasicErrorWith404::internal_with_code(
+                                e,
+                                AptosErrorCode::ReadFromStorageError,
+                            )
+                        })?;
+                    BatchEntry::Account(account_state)
+                }
+            };
+            entries.push((address, entry));
+        }
+
+        let resolver = state_view.as_move_resolver();
+        let converter = self.context.converter(&resolver);
+        let mut batch = Vec::with_capacity(entries.len());
+        for (address, entry) in entries {
+            let resources = match entry {
+                BatchEntry::Resource(resource_type, Some(bytes)) => vec![converter
+                    .try_into_resource(&resource_type, &bytes)
+                    .context("Failed to deserialize resource data retrieved from DB")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?],
+                BatchEntry::Account(Some(account_state)) => converter
+                    .try_into_resources(account_state.get_resources())
+                    .context("Failed to build move resource response from data in DB")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })?,
+                BatchEntry::Resource(_, None) | BatchEntry::Account(None) => vec![],
+            };
+            batch.push(AccountResources { address, resources });
+        }
+
+        BasicResponse::try_from_rust_value((
+            batch,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn module(
         &self,
         accept_type: &AcceptType,
         address: Address,
@@ -187,19 +649,15 @@ impl StateApi {
         ledger_version: Option<U64>,
     ) -> BasicResultWith404<MoveModuleBytecode> {
         let module_id = ModuleId::new(address.into(), name.into());
-        let access_path = AccessPath::code_access_path(module_id.clone());
-        let state_key = StateKey::AccessPath(access_path);
-        let (ledger_info, ledger_version, state_view) = self.preprocess_request(ledger_version)?;
-        let bytes = state_view
-            .get_state_value(&state_key)
-            .context(format!("Failed to query DB to check for {:?}", state_key))
-            .map_err(BasicErrorWith404::internal)?
-            .ok_or_else(|| build_not_found("Module", module_id, ledger_version))?;
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let bytes = self.module_bytes(module_id, ledger_version).await?;
 
         let module = MoveModuleBytecode::new(bytes)
             .try_parse_abi()
             .context("Failed to parse move module ABI from bytes retrieved from storage")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
 
         BasicResponse::try_from_rust_value((
             module,
@@ -209,7 +667,208 @@ impl StateApi {
         ))
     }
 
-    pub fn table_item(
+    async fn module_exists(
+        &self,
+        address: Address,
+        name: IdentifierWrapper,
+        ledger_version: Option<U64>,
+    ) -> HeadResultWith404 {
+        let module_id = ModuleId::new(address.into(), name.into());
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        self.module_bytes(module_id, ledger_version).await?;
+        Ok(HeadResponse::from(&ledger_info))
+    }
+
+    pub async fn module_disassembly(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        name: IdentifierWrapper,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<MoveModuleDisassembly> {
+        let module_id = ModuleId::new(address.into(), name.into());
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let bytes = self.module_bytes(module_id.clone(), ledger_version).await?;
+        let source_map = self
+            .published_source_map(&module_id, ledger_version)
+            .await?;
+
+        let disassembly = MoveModuleBytecode::new(bytes)
+            .disassemble(source_map)
+            .context("Failed to disassemble move module retrieved from storage")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
+
+        BasicResponse::try_from_rust_value((
+            disassembly,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn module_verification(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        name: IdentifierWrapper,
+        source_hash: Option<HashValue>,
+        toolchain_version: Option<String>,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<MoveModuleVerification> {
+        let module_id = ModuleId::new(address.into(), name.into());
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let bytes = self.module_bytes(module_id.clone(), ledger_version).await?;
+        let package = self.published_package(&module_id, ledger_version).await?;
+
+        let module_name = module_id.name().as_str();
+        let module = package.as_ref().and_then(|package| {
+            package
+                .modules
+                .iter()
+                .find(|module| module.name == module_name)
+        });
+        // Build info that can't be parsed is taken as not attesting anything.
+        let attestation = package
+            .as_ref()
+            .and_then(|package| SourceAttestation::from_build_info(&package.build_info).ok())
+            .flatten();
+        let module_attestation = attestation
+            .as_ref()
+            .and_then(|attestation| attestation.modules.get(module_name));
+
+        let verification = match (&attestation, module_attestation) {
+            (Some(attestation), Some(module_attestation)) => {
+                let published_source_matches = module
+                    .filter(|module| !module.source.is_empty())
+                    .map(|module| {
+                        framework::unzip_metadata(&module.source).map_or(false, |source| {
+                            aptos_crypto::HashValue::sha3_256_of(&source)
+                                == module_attestation.source_hash
+                        })
+                    });
+                let mut verification = MoveModuleVerification {
+                    attested: true,
+                    toolchain_version: Some(attestation.toolchain_version.clone()),
+                    source_hash: Some(module_attestation.source_hash.into()),
+                    bytecode_matches: aptos_crypto::HashValue::sha3_256_of(&bytes)
+                        == module_attestation.bytecode_hash,
+                    published_source_matches,
+                    source_hash_matches: source_hash
+                        .map(|hash| hash.0 == module_attestation.source_hash),
+                    toolchain_version_matches: toolchain_version
+                        .as_ref()
+                        .map(|version| *version == attestation.toolchain_version),
+                    verified: false,
+                };
+                verification.verified = verification.bytecode_matches
+                    && verification.published_source_matches != Some(false)
+                    && verification.source_hash_matches != Some(false)
+                    && verification.toolchain_version_matches != Some(false);
+                verification
+            }
+            _ => MoveModuleVerification {
+                attested: false,
+                toolchain_version: None,
+                source_hash: None,
+                bytecode_matches: false,
+                published_source_matches: None,
+                source_hash_matches: None,
+                toolchain_version_matches: None,
+                verified: false,
+            },
+        };
+
+        BasicResponse::try_from_rust_value((
+            verification,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    async fn module_bytes(
+        &self,
+        module_id: ModuleId,
+        ledger_version: u64,
+    ) -> Result<Vec<u8>, BasicErrorWith404> {
+        let access_path = AccessPath::code_access_path(module_id.clone());
+        let state_key = StateKey::AccessPath(access_path);
+        self.context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ok_or_else(|| {
+                build_not_found(
+                    "Module",
+                    module_id,
+                    AptosErrorCode::ModuleNotFound,
+                    ledger_version,
+                )
+            })
+    }
+
+    /// Returns the metadata of the package the module was published in, from
+    /// the package registry of its account, if the module was published as
+    /// part of a package. A registry that can't be decoded is ignored, as the
+    /// metadata is only informative.
+    async fn published_package(
+        &self,
+        module_id: &ModuleId,
+        ledger_version: u64,
+    ) -> Result<Option<PackageMetadata>, BasicErrorWith404> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            *module_id.address(),
+            PackageRegistry::struct_tag(),
+        )));
+        let bytes = match self
+            .context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        let registry: PackageRegistry = match bcs::from_bytes(&bytes) {
+            Ok(registry) => registry,
+            Err(_) => return Ok(None),
+        };
+        Ok(registry.packages.into_iter().find(|package| {
+            package
+                .modules
+                .iter()
+                .any(|module| module.name == module_id.name().as_str())
+        }))
+    }
+
+    /// Returns the source map of the module, if the module was published as
+    /// part of a package along with its source maps. Source maps that can't
+    /// be decoded are ignored, since the module can be disassembled without
+    /// them.
+    async fn published_source_map(
+        &self,
+        module_id: &ModuleId,
+        ledger_version: u64,
+    ) -> Result<Option<SourceMap>, BasicErrorWith404> {
+        let package = self.published_package(module_id, ledger_version).await?;
+        Ok(package
+            .iter()
+            .flat_map(|package| package.modules.iter())
+            .find(|module| module.name == module_id.name().as_str())
+            .filter(|module| !module.source_map.is_empty())
+            .and_then(|module| framework::unzip_metadata(&module.source_map).ok())
+            .and_then(|source_map| bcs::from_bytes(&source_map).ok()))
+    }
+
+    pub async fn table_item(
         &self,
         accept_type: &AcceptType,
         table_handle: U128,
@@ -220,41 +879,69 @@ impl StateApi {
             .key_type
             .try_into()
             .context("Failed to parse key_type")
-            .map_err(BasicErrorWith404::bad_request)?;
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
         let value_type = table_item_request
             .value_type
             .try_into()
             .context("Failed to parse value_type")
-            .map_err(BasicErrorWith404::bad_request)?;
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
         let key = table_item_request.key;
 
         let (ledger_info, ledger_version, state_view) = self.preprocess_request(ledger_version)?;
 
         let resolver = state_view.as_move_resolver();
-        let converter = resolver.as_converter(self.context.db.clone());
 
-        let vm_key = converter
-            .try_into_vm_value(&key_type, key.clone())
-            .map_err(BasicErrorWith404::bad_request)?;
-        let raw_key = vm_key
-            .undecorate()
-            .simple_serialize()
-            .ok_or_else(|| BasicErrorWith404::internal_str("Failed to serialize table key"))?;
+        // The converter can't be held across the read, so it is built again
+        // to convert the value.
+        let raw_key = {
+            let vm_key = self
+                .context
+                .converter(&resolver)
+                .try_into_vm_value(&key_type, key.clone())
+                .map_err(|e| {
+                    BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+                })?;
+            vm_key.undecorate().simple_serialize().ok_or_else(|| {
+                BasicErrorWith404::internal_with_code(
+                    "Failed to serialize table key",
+                    AptosErrorCode::InternalError,
+                )
+            })?
+        };
 
         let state_key = StateKey::table_item(TableHandle(table_handle.0), raw_key);
-        let bytes = state_view
-            .get_state_value(&state_key)
+        let bytes = self
+            .context
+            .get_state_value_async(state_key, ledger_version)
+            .await
             .context(format!(
                 "Failed when trying to retrieve table item from the DB with key: {}",
                 key
             ))
-            .map_err(BasicErrorWith404::internal)?
-            .ok_or_else(|| build_not_found("table handle or item", key, ledger_version))?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ok_or_else(|| {
+                build_not_found(
+                    "table handle or item",
+                    key,
+                    AptosErrorCode::TableItemNotFound,
+                    ledger_version,
+                )
+            })?;
 
-        let move_value = converter
+        let move_value = self
+            .context
+            .converter(&resolver)
             .try_into_move_value(&value_type, &bytes)
             .context("Failed to deserialize table item retrieved from DB")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
 
         BasicResponse::try_from_rust_value((
             move_value,
@@ -263,4 +950,260 @@ impl StateApi {
             accept_type,
         ))
     }
+
+    pub async fn table_items(
+        &self,
+        accept_type: &AcceptType,
+        table_handle: U128,
+        limit: Option<u16>,
+        cursor: Option<Cursor>,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<TableItems> {
+        if !self.context.indexer_enabled() {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                "The internal indexer is not enabled on this node",
+                AptosErrorCode::IndexerDisabled,
+            ));
+        }
+        let limit = Page::new(None, limit, self.context.max_page_size()).limit()?;
+        let start_key = cursor
+            .map(Cursor::into_table_key)
+            .transpose()
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidCursorParam)
+            })?;
+        let (ledger_info, ledger_version, state_view) = self.preprocess_request(ledger_version)?;
+
+        // The table info is only known once the table has been written to.
+        let handle = TableHandle(table_handle.0);
+        let table_info = self.context.get_table_info(handle).map_err(|_| {
+            build_not_found(
+                "table handle",
+                table_handle.0,
+                AptosErrorCode::TableItemNotFound,
+                ledger_version,
+            )
+        })?;
+
+        let raw_items = self
+            .context
+            .get_table_items(handle, start_key, limit, ledger_version)
+            .await
+            .context(format!(
+                "Failed to read the items of table {} from the DB",
+                table_handle.0
+            ))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+        let has_more = raw_items.len() == limit as usize;
+
+        let resolver = state_view.as_move_resolver();
+        let converter = self.context.converter(&resolver);
+        let mut items = raw_items
+            .iter()
+            .map(|(key, value)| {
+                Ok(DecodedTableData {
+                    key: converter
+                        .try_into_move_value(&table_info.key_type, key)?
+                        .json()?,
+                    key_type: table_info.key_type.to_string(),
+                    value: converter
+                        .try_into_move_value(&table_info.value_type, &value.bytes)?
+                        .json()?,
+                    value_type: table_info.value_type.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("Failed to deserialize table items retrieved from DB")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
+
+        let count = items_within_size_budget(&items, accept_type, self.context.max_response_size())
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::BcsSerializationError)
+            })?;
+        let cursor = if count < items.len() || has_more {
+            items.truncate(count);
+            raw_items
+                .into_iter()
+                .nth(count - 1)
+                .map(|(key, _)| Cursor::table_key(key))
+        } else {
+            None
+        };
+
+        BasicResponse::try_from_rust_value((
+            TableItems { items, cursor },
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn gas_schedule(
+        &self,
+        accept_type: &AcceptType,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<GasSchedule> {
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let gas_schedule = self
+            .on_chain_config::<OnChainGasSchedule>(ledger_version)
+            .await?;
+        let version = self.on_chain_config::<Version>(ledger_version).await?;
+
+        BasicResponse::try_from_rust_value((
+            GasSchedule::new(gas_schedule, version),
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn validator_set(
+        &self,
+        accept_type: &AcceptType,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<ValidatorSet> {
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let validator_set = self
+            .on_chain_config::<OnChainValidatorSet>(ledger_version)
+            .await?;
+
+        BasicResponse::try_from_rust_value((
+            ValidatorSet::from(validator_set),
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub async fn on_chain_configs(
+        &self,
+        accept_type: &AcceptType,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<OnChainConfigs> {
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let version = self.on_chain_config::<Version>(ledger_version).await?;
+        // Validators fall back to the defaults of the consensus and execution
+        // configs until they are set on chain, and there is no block gas
+        // limit until one is set.
+        let consensus = self
+            .optional_on_chain_config::<OnChainConsensusConfig>(ledger_version)
+            .await?
+            .unwrap_or_default();
+        let execution = self
+            .optional_on_chain_config::<ParallelExecutionConfig>(ledger_version)
+            .await?
+            .unwrap_or_default();
+        let block_gas_limit = self
+            .optional_on_chain_config::<BlockGasLimit>(ledger_version)
+            .await?;
+
+        BasicResponse::try_from_rust_value((
+            OnChainConfigs::new(version, consensus, execution, block_gas_limit),
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    async fn on_chain_config<T: OnChainConfig>(
+        &self,
+        ledger_version: u64,
+    ) -> Result<T, BasicErrorWith404> {
+        self.optional_on_chain_config(ledger_version)
+            .await?
+            .ok_or_else(|| {
+                build_not_found(
+                    "On-chain config",
+                    T::CONFIG_ID.name(),
+                    AptosErrorCode::ResourceNotFound,
+                    ledger_version,
+                )
+            })
+    }
+
+    async fn optional_on_chain_config<T: OnChainConfig>(
+        &self,
+        ledger_version: u64,
+    ) -> Result<Option<T>, BasicErrorWith404> {
+        let state_key = StateKey::AccessPath(access_path_for_config(T::CONFIG_ID));
+        let bytes = self
+            .context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+        bytes
+            .map(|bytes| {
+                T::deserialize_into_config(&bytes)
+                    .context(format!(
+                        "Failed to deserialize on-chain config {} retrieved from DB",
+                        T::CONFIG_ID.name()
+                    ))
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::InvalidBcsInStorageError,
+                        )
+                    })
+            })
+            .transpose()
+    }
+
+    pub async fn raw_state_value(
+        &self,
+        accept_type: &AcceptType,
+        request: RawStateValueRequest,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        let state_key: StateKey = bcs::from_bytes(request.key.inner())
+            .context("Failed to deserialize given key as a StateKey")
+            .map_err(|e| {
+                BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+            })?;
+        let (ledger_info, ledger_version, _) = self.preprocess_request(ledger_version)?;
+        let bytes = self
+            .context
+            .get_state_value_async(state_key.clone(), ledger_version)
+            .await
+            .context(format!("Failed to query DB to check for {:?}", state_key))
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
+            .ok_or_else(|| {
+                build_not_found(
+                    "State value",
+                    request.key,
+                    AptosErrorCode::StateValueNotFound,
+                    ledger_version,
+                )
+            })?;
+
+        match accept_type {
+            // Return the value as is, rather than as BCS encoded bytes.
+            AcceptType::Bcs | AcceptType::BcsStream => Ok(BasicResponse::from((
+                Bcs(bytes),
+                &ledger_info,
+                BasicResponseStatus::Ok,
+            ))),
+            AcceptType::Json | AcceptType::NdJson => BasicResponse::try_from_rust_value((
+                HexEncodedBytes::from(bytes),
+                &ledger_info,
+                BasicResponseStatus::Ok,
+                accept_type,
+            )),
+        }
+    }
+}
+
+fn parse_resource_type(resource_type: MoveStructTag) -> Result<StructTag, BasicErrorWith404> {
+    resource_type
+        .try_into()
+        .context("Failed to parse given resource type")
+        .map_err(|e| BasicErrorWith404::bad_request_with_code(e, AptosErrorCode::InvalidInput))
 }
@@ -1,8 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_indexer};
 use aptos_api_test_context::{current_function_name, find_value};
+use aptos_api_types::Cursor;
+use aptos_sdk::{transaction_builder::aptos_stdlib, types::AccountKey};
 use serde_json::json;
 
 /* TODO: reactivate once cause of failure for `"8"` vs `8` in the JSON output is known.
@@ -50,6 +52,62 @@ async fn test_get_account_resources_by_valid_account_address() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_by_page() {
+    let mut context = new_test_context(current_function_name!());
+    let all_resources = context.get(&account_resources("0x1")).await;
+    let resource_types: Vec<_> = all_resources
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|resource| resource["type"].clone())
+        .collect();
+    assert!(resource_types.len() > 2);
+
+    // Following the cursors lists every resource once, in the same order.
+    let mut paged_types = vec![];
+    let mut path = format!("{}?limit=2", account_resources("0x1"));
+    loop {
+        let resp = context
+            .reply(
+                warp::test::request()
+                    .method("GET")
+                    .path(&context.prepend_path(&path)),
+            )
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+        assert!(page.as_array().unwrap().len() <= 2);
+        paged_types.extend(
+            page.as_array()
+                .unwrap()
+                .iter()
+                .map(|resource| resource["type"].clone()),
+        );
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(cursor) => {
+                path = format!(
+                    "{}?limit=2&cursor={}",
+                    account_resources("0x1"),
+                    cursor.to_str().unwrap()
+                )
+            }
+            None => break,
+        }
+    }
+    assert_eq!(paged_types, resource_types);
+
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "{}?cursor={}",
+            account_resources("0x1"),
+            Cursor::version(0)
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_cursor_param");
+}
+
 // Unstable due to framework changes
 #[ignore]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -204,6 +262,298 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_batch_account_resources() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let root_address = context.root_account().address().to_hex_literal();
+    let new_address = account.address().to_hex_literal();
+    let resp = context
+        .post(
+            "/accounts/resources/batch?ledger_version=0",
+            json!([
+                {"address": root_address, "resource_type": "0x1::account::Account"},
+                {"address": new_address},
+                {"address": "0x1"},
+            ]),
+        )
+        .await;
+
+    let batch = resp.as_array().unwrap();
+    assert_eq!(batch.len(), 3);
+
+    // The root account resource is read at version 0, before it sent any transactions.
+    let root_resources = batch[0]["resources"].as_array().unwrap();
+    assert_eq!(root_resources.len(), 1);
+    assert_eq!(root_resources[0]["type"], "0x1::account::Account");
+    assert_eq!(root_resources[0]["data"]["sequence_number"], "0");
+
+    // The new account didn't exist yet at version 0.
+    assert_eq!(batch[1]["address"], new_address);
+    assert!(batch[1]["resources"].as_array().unwrap().is_empty());
+
+    assert!(!batch[2]["resources"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_batch_account_resources_too_many_accounts() {
+    let mut context = new_test_context(current_function_name!());
+    let requests: Vec<_> = (0..101).map(|_| json!({"address": "0x1"})).collect();
+    let resp = context
+        .expect_status_code(400)
+        .post("/accounts/resources/batch", json!(requests))
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("Too many accounts requested"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_transaction_queue() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let path = format!(
+        "/accounts/{}/transactions/next",
+        root_account.address().to_hex_literal()
+    );
+
+    let resp = context.get(&path).await;
+    assert_eq!(resp["sequence_number"], "0");
+    assert_eq!(resp["next_sequence_number"], "0");
+    assert_eq!(resp["lowest_pending_sequence_number"], json!(null));
+    assert_eq!(resp["sequence_number_gaps"], json!([]));
+
+    // Leave out the transaction with sequence number 1.
+    let account1 = context.gen_account();
+    let txn0 = context.create_user_account_by(&mut root_account, &account1);
+    *root_account.sequence_number_mut() += 1;
+    let account2 = context.gen_account();
+    let txn2 = context.create_user_account_by(&mut root_account, &account2);
+    context.mempool.add_txns(vec![txn0.clone(), txn2]).unwrap();
+
+    let resp = context.get(&path).await;
+    assert_eq!(resp["sequence_number"], "0");
+    assert_eq!(resp["next_sequence_number"], "1");
+    assert_eq!(resp["lowest_pending_sequence_number"], "0");
+    assert_eq!(resp["highest_pending_sequence_number"], "2");
+    assert_eq!(
+        resp["sequence_number_gaps"],
+        json!([{"start": "1", "end": "1"}])
+    );
+    assert_eq!(
+        resp["earliest_expiration_timestamp_secs"],
+        txn0.expiration_timestamp_secs().to_string()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_transaction_queue_account_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0xf/transactions/next")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_key_history() {
+    let mut context = new_test_context_with_indexer(current_function_name!());
+    let mut root_account = context.root_account();
+    let path = format!(
+        "/accounts/{}/key_history",
+        root_account.address().to_hex_literal()
+    );
+    let original_key = format!("0x{}", hex::encode(root_account.authentication_key()));
+
+    // The root account is created at genesis.
+    let resp = context.get(&path).await;
+    assert_eq!(resp["creation_version"], "0");
+    assert_eq!(
+        resp["authentication_keys"],
+        json!([{
+            "version": "0",
+            "timestamp": resp["creation_timestamp"],
+            "authentication_key": original_key,
+        }])
+    );
+
+    let new_key = AccountKey::generate(context.rng());
+    let txn = root_account.sign_with_transaction_builder(context.transaction_factory().payload(
+        aptos_stdlib::account_rotate_authentication_key(new_key.authentication_key().to_vec()),
+    ));
+    context.commit_block(&vec![txn.clone()]).await;
+    let rotation_version = context.get_latest_ledger_info().version() - 1;
+
+    let resp = context.get(&path).await;
+    assert_eq!(resp["creation_version"], "0");
+    let keys = resp["authentication_keys"].as_array().unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0]["authentication_key"], original_key);
+    assert_eq!(keys[1]["version"], rotation_version.to_string());
+    assert_eq!(
+        keys[1]["authentication_key"],
+        format!("0x{}", hex::encode(new_key.authentication_key()))
+    );
+
+    // Rotations after the ledger version are left out.
+    let resp = context
+        .get(&format!("{}?ledger_version={}", path, rotation_version - 1))
+        .await;
+    assert_eq!(resp["authentication_keys"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_key_history_account_not_found() {
+    let mut context = new_test_context_with_indexer(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0xf/key_history")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_key_history_indexer_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0x1/key_history")
+        .await;
+    assert_eq!(resp["error_code"], "indexer_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_balances() {
+    let mut context = new_test_context_with_indexer(current_function_name!());
+    let address = context.root_account().address().to_hex_literal();
+    let coin_store = context
+        .get(&format!(
+            "/accounts/{}/resource/0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            address
+        ))
+        .await;
+
+    let resp = context
+        .get(&format!("/accounts/{}/balances", address))
+        .await;
+    let balances = resp["balances"].as_array().unwrap();
+    let balance = balances
+        .iter()
+        .find(|b| b["coin_type"] == "0x1::aptos_coin::AptosCoin")
+        .unwrap();
+    assert_eq!(balance["amount"], coin_store["data"]["coin"]["value"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_top_coin_holders() {
+    let mut context = new_test_context_with_indexer(current_function_name!());
+    let resp = context
+        .get("/coins/0x1::aptos_coin::AptosCoin/top_holders?limit=10")
+        .await;
+    assert_eq!(resp["coin_type"], "0x1::aptos_coin::AptosCoin");
+
+    let amounts: Vec<u64> = resp["holders"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|h| h["amount"].as_str().unwrap().parse().unwrap())
+        .collect();
+    assert!(!amounts.is_empty());
+    assert!(amounts.len() <= 10);
+    assert!(amounts.windows(2).all(|w| w[0] >= w[1]));
+    assert!(amounts.iter().all(|amount| *amount > 0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_balances_balance_index_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0x1/balances")
+        .await;
+    assert_eq!(resp["error_code"], "indexer_disabled");
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/coins/0x1::aptos_coin::AptosCoin/top_holders")
+        .await;
+    assert_eq!(resp["error_code"], "indexer_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_diff() {
+    let mut context = new_test_context(current_function_name!());
+    let from_version = context.get_latest_ledger_info().version();
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let root = context.root_account().address().to_hex_literal();
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/diff?from_version={}",
+            root, from_version
+        ))
+        .await;
+    assert_eq!(resp["from_version"], from_version.to_string());
+    assert_eq!(resp["created"], json!([]));
+    assert_eq!(resp["deleted"], json!([]));
+    let modified = find_value(&resp["modified"], |f| {
+        f["before"]["type"] == "0x1::account::Account"
+    });
+    assert_eq!(modified["before"]["data"]["sequence_number"], "0");
+    assert_eq!(modified["after"]["data"]["sequence_number"], "1");
+
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/diff?from_version={}",
+            account.address().to_hex_literal(),
+            from_version
+        ))
+        .await;
+    assert_eq!(resp["modified"], json!([]));
+    let created = find_value(&resp["created"], |f| f["type"] == "0x1::account::Account");
+    assert_eq!(created["data"]["sequence_number"], "0");
+
+    // Nothing changes from a version to itself.
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/diff?from_version={}&to_version={}",
+            root, from_version, from_version
+        ))
+        .await;
+    assert_eq!(resp["created"], json!([]));
+    assert_eq!(resp["modified"], json!([]));
+    assert_eq!(resp["deleted"], json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_diff_invalid_versions() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "/accounts/0x1/diff?from_version={}&to_version=0",
+            version
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "/accounts/0x1/diff?from_version=0&to_version={}",
+            version + 1
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "version_not_found");
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -3,6 +3,7 @@
 
 use super::new_test_context;
 use aptos_api_test_context::current_function_name;
+use serde_json::Value;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_genesis_block_by_height() {
@@ -23,6 +24,156 @@ async fn test_get_unknown_block_by_height() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_block_with_transaction_summaries() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let block = context
+        .get(&format!("{}?with_transactions=true", blocks_by_height(1)))
+        .await;
+    let resp = context
+        .get(&format!(
+            "{}?with_transactions=summary",
+            blocks_by_height(1)
+        ))
+        .await;
+    assert!(resp.get("transactions").is_none());
+    assert_eq!(resp["block_hash"], block["block_hash"]);
+
+    let transactions = block["transactions"].as_array().unwrap();
+    let summaries = resp["transaction_summaries"].as_array().unwrap();
+    assert_eq!(summaries.len(), transactions.len());
+    for (summary, txn) in summaries.iter().zip(transactions) {
+        assert_eq!(summary["version"], txn["version"]);
+        assert_eq!(summary["hash"], txn["hash"]);
+        assert_eq!(summary["type"], txn["type"]);
+        assert_eq!(summary["success"], txn["success"]);
+        assert_eq!(summary["gas_used"], txn["gas_used"]);
+    }
+    assert_eq!(summaries[1]["type"], "user_transaction");
+    assert_eq!(
+        summaries[1]["sender"],
+        context.root_account().address().to_hex_literal()
+    );
+    assert!(summaries[0].get("sender").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_block_with_fee_stats() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let resp = context
+        .get(&format!(
+            "{}?with_transactions=true&with_fee_stats=true",
+            blocks_by_height(1)
+        ))
+        .await;
+    let transactions = resp["transactions"].as_array().unwrap();
+    let total_gas_used: u64 = transactions
+        .iter()
+        .map(|txn| parse_u64(&txn["gas_used"]))
+        .sum();
+    let user_txn = &transactions[1];
+    assert_eq!(user_txn["type"], "user_transaction");
+    let gas_unit_price = user_txn["gas_unit_price"].clone();
+    let fees = parse_u64(&user_txn["gas_used"]) * parse_u64(&gas_unit_price);
+
+    let fee_stats = &resp["fee_stats"];
+    assert_eq!(fee_stats["num_user_transactions"], "1");
+    assert_eq!(parse_u64(&fee_stats["total_gas_used"]), total_gas_used);
+    assert_eq!(fee_stats["min_gas_unit_price"], gas_unit_price);
+    assert_eq!(fee_stats["max_gas_unit_price"], gas_unit_price);
+    assert_eq!(fee_stats["average_gas_unit_price"], gas_unit_price);
+    assert_eq!(fee_stats["median_gas_unit_price"], gas_unit_price);
+    assert_eq!(parse_u64(&fee_stats["total_fees"]), fees);
+    assert_eq!(parse_u64(&fee_stats["burnt_fees"]), fees);
+
+    // Served from the cache the second time around.
+    let resp = context
+        .get(&format!(
+            "/blocks/by_version/{}?with_fee_stats=true",
+            user_txn["version"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(&resp["fee_stats"], fee_stats);
+
+    let resp = context.get(&blocks_by_height(1)).await;
+    assert!(resp.get("fee_stats").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_block_with_proposal() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let resp = context
+        .get(&format!("{}?with_proposal=true", blocks_by_height(1)))
+        .await;
+    let proposal = &resp["proposal"];
+    assert_eq!(proposal["epoch"], "0");
+    assert_eq!(proposal["round"], "1");
+    assert_eq!(
+        proposal["proposer"],
+        context.validator_owner.to_hex_literal()
+    );
+    assert_eq!(proposal["previous_block_voters"], serde_json::json!([]));
+    assert_eq!(proposal["failed_proposers"], serde_json::json!([]));
+
+    let resp = context.get(&blocks_by_height(1)).await;
+    assert!(resp.get("proposal").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_block_with_transactions_flag() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get(&format!("{}?with_transactions=false", blocks_by_height(0)))
+        .await;
+    assert!(resp.get("transactions").is_none());
+    assert!(resp.get("transaction_summaries").is_none());
+
+    let resp = context
+        .get(&format!("{}?with_transactions=true", blocks_by_height(0)))
+        .await;
+    assert_eq!(resp["transactions"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_epoch() {
+    let mut context = new_test_context(current_function_name!());
+
+    // Genesis ends epoch 0.
+    let genesis_epoch = context.get("/epochs/0").await;
+    assert_eq!(genesis_epoch["epoch"], "0");
+    assert_eq!(genesis_epoch["first_version"], "0");
+    assert_eq!(genesis_epoch["start_timestamp"], "0");
+    assert_eq!(genesis_epoch["last_version"], "0");
+    assert!(genesis_epoch["end_timestamp"].is_string());
+
+    let resp = context.get("/epochs/1").await;
+    assert_eq!(resp["epoch"], "1");
+    assert_eq!(resp["first_version"], "1");
+    assert_eq!(resp["start_timestamp"], genesis_epoch["end_timestamp"]);
+    assert!(resp.get("last_version").is_none());
+    assert!(resp.get("end_timestamp").is_none());
+
+    let resp = context.expect_status_code(404).get("/epochs/2").await;
+    assert_eq!(resp["error_code"], "epoch_not_found");
+}
+
 fn blocks_by_height(height: u64) -> String {
     format!("/blocks/by_height/{}", height)
 }
+
+fn parse_u64(value: &Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
@@ -0,0 +1,215 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Contract tests of the wire format of the API: the requests recorded in
+//! contract_fixtures.json are replayed against every endpoint, asking for both
+//! JSON and BCS, and the responses are compared to the golden output, so that
+//! any change of the wire format shows up in the diff of the golden file.
+//!
+//! To record the responses after an intended change, run the test with
+//! UPDATE_GOLDENFILES=1.
+
+use super::new_test_context;
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_api_types::{
+    mime_types, HexEncodedBytes, X_APTOS_BLOCK_HEIGHT, X_APTOS_CHAIN_ID, X_APTOS_EPOCH,
+    X_APTOS_LEDGER_OLDEST_VERSION, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    X_APTOS_OLDEST_BLOCK_HEIGHT,
+};
+use aptos_types::{
+    access_path::AccessPath, account_config::AccountResource, event::EventKey,
+    state_store::state_key::StateKey,
+};
+use bytes::Bytes;
+use hyper::Response;
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use warp::http::header::{ACCEPT, CONTENT_TYPE};
+
+/// The accept types every fixture is replayed with.
+const ACCEPT_TYPES: [&str; 2] = [mime_types::JSON, mime_types::BCS];
+
+/// The response headers that are part of the contract, in the order they are recorded.
+const CONTRACT_HEADERS: [&str; 11] = [
+    "content-type",
+    X_APTOS_CHAIN_ID,
+    X_APTOS_EPOCH,
+    X_APTOS_LEDGER_VERSION,
+    X_APTOS_LEDGER_OLDEST_VERSION,
+    X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_BLOCK_HEIGHT,
+    X_APTOS_OLDEST_BLOCK_HEIGHT,
+    "x-aptos-cursor",
+    "deprecation",
+    "link",
+];
+
+/// A recorded request. Its path and bodies may refer to values that depend on
+/// the state the test builds, such as `{account}`, which are substituted
+/// before the request is sent.
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    name: String,
+    method: String,
+    path: String,
+    /// The JSON body of the request.
+    #[serde(default)]
+    body: Option<Value>,
+    /// The hex encoded BCS body of the request, sent as a signed transaction.
+    #[serde(default)]
+    bcs_body: Option<String>,
+    /// Whether only the length of BCS encoded response bodies, hex encoded or
+    /// not, is recorded, for responses carrying hashes that change whenever
+    /// the genesis does. Hashes in JSON bodies are pruned from the golden
+    /// output anyway.
+    #[serde(default)]
+    opaque: bool,
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_api_contract() {
+    let mut context = new_test_context(current_function_name!());
+    let substitutions = build_state(&mut context).await;
+
+    let fixtures: Vec<Fixture> =
+        serde_json::from_str(include_str!("contract_fixtures.json")).unwrap();
+    for fixture in &fixtures {
+        for accept in ACCEPT_TYPES {
+            let resp = context.reply(fixture.request(&substitutions, accept)).await;
+            let record = fixture.record(accept, &resp);
+            context.check_golden_output(record);
+        }
+    }
+}
+
+/// Commits a transaction creating an account and builds the transactions the
+/// fixtures submit, returning the values the fixtures refer to.
+async fn build_state(context: &mut TestContext) -> BTreeMap<&'static str, String> {
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&[txn.clone()]).await;
+
+    let new_account = context.gen_account();
+    let pending_txn = context.create_user_account_by(&mut root_account, &new_account);
+    let unsigned_txn = context.create_invalid_signature_transaction();
+
+    let account_resource_state_key = StateKey::AccessPath(AccessPath::resource_access_path(
+        ResourceKey::new(account.address(), AccountResource::struct_tag()),
+    ));
+    let account_event_key = EventKey::new(0, account.address());
+
+    let mut substitutions = BTreeMap::new();
+    substitutions.insert("root", root_account.address().to_hex_literal());
+    substitutions.insert("account", account.address().to_hex_literal());
+    substitutions.insert("txn_hash", txn.committed_hash().to_hex_literal());
+    // The transaction is committed after the block metadata transaction of its block.
+    substitutions.insert("txn_version", "2".to_string());
+    substitutions.insert(
+        "account_resource_state_key",
+        hex_bcs(&account_resource_state_key),
+    );
+    substitutions.insert("account_event_key", hex_bcs(&account_event_key));
+    substitutions.insert("pending_txn", hex_bcs(&pending_txn));
+    substitutions.insert("unsigned_txn", hex_bcs(&unsigned_txn));
+    substitutions
+}
+
+impl Fixture {
+    fn request(
+        &self,
+        substitutions: &BTreeMap<&'static str, String>,
+        accept: &str,
+    ) -> warp::test::RequestBuilder {
+        let path = substitute(&self.path, substitutions);
+        let mut req = warp::test::request()
+            .method(self.method.as_str())
+            .path(&format!("/v1{}", path))
+            .header(ACCEPT, accept);
+        if let Some(body) = &self.body {
+            req = req.json(&substitute_json(body, substitutions));
+        }
+        if let Some(body) = &self.bcs_body {
+            let body: HexEncodedBytes = substitute(body, substitutions).parse().unwrap();
+            req = req
+                .header(CONTENT_TYPE, mime_types::BCS_SIGNED_TRANSACTION)
+                .body(body.inner().to_vec());
+        }
+        req
+    }
+
+    /// Records the response to the fixture, as it is compared to the golden
+    /// output. The request is recorded before substitutions, so the golden
+    /// output doesn't depend on hashes.
+    fn record(&self, accept: &str, resp: &Response<Bytes>) -> Value {
+        let mut headers = Map::new();
+        for name in CONTRACT_HEADERS {
+            if let Some(value) = resp.headers().get(name) {
+                headers.insert(
+                    name.to_lowercase(),
+                    Value::String(value.to_str().unwrap().to_string()),
+                );
+            }
+        }
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .map(|value| value.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        let body = if content_type.starts_with(mime_types::JSON) {
+            match serde_json::from_slice(resp.body()).expect("response body is JSON") {
+                Value::String(hex) if self.opaque => json!({ "length": hex.len() }),
+                body => body,
+            }
+        } else if content_type.starts_with(mime_types::BCS) {
+            if self.opaque {
+                json!({ "length": resp.body().len() })
+            } else {
+                Value::String(HexEncodedBytes::from(resp.body().to_vec()).to_string())
+            }
+        } else {
+            Value::String(String::from_utf8_lossy(resp.body()).to_string())
+        };
+        json!({
+            "fixture": self.name,
+            "request": format!("{} {}", self.method, self.path),
+            "accept": accept,
+            "status": resp.status().as_u16(),
+            "headers": headers,
+            "body": body,
+        })
+    }
+}
+
+fn substitute(template: &str, substitutions: &BTreeMap<&'static str, String>) -> String {
+    substitutions
+        .iter()
+        .fold(template.to_string(), |s, (name, value)| {
+            s.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+fn substitute_json(template: &Value, substitutions: &BTreeMap<&'static str, String>) -> Value {
+    match template {
+        Value::String(s) => Value::String(substitute(s, substitutions)),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|v| substitute_json(v, substitutions))
+                .collect(),
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), substitute_json(v, substitutions)))
+                .collect(),
+        ),
+        _ => template.clone(),
+    }
+}
+
+fn hex_bcs<T: serde::Serialize>(value: &T) -> String {
+    HexEncodedBytes::from(bcs::to_bytes(value).unwrap()).to_string()
+}
@@ -3,7 +3,9 @@
 
 use super::new_test_context;
 use aptos_api_test_context::current_function_name;
-use aptos_api_types::{new_vm_utf8_string, AsConverter, MoveConverter, MoveType};
+use aptos_api_types::{
+    new_vm_utf8_string, AsConverter, ModuleCache, MoveConverter, MoveType, TransactionPayload,
+};
 use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::MoveResolverExt};
 use move_deps::move_core_types::{
     account_address::AccountAddress,
@@ -11,7 +13,7 @@ use move_deps::move_core_types::{
 };
 use serde::Serialize;
 use serde_json::json;
-use std::convert::TryInto;
+use std::{convert::TryInto, sync::Arc};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_value_conversion() {
@@ -56,6 +58,39 @@ async fn test_value_conversion() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_script_function_payload_with_module_cache() {
+    let context = new_test_context(current_function_name!());
+    let payload: TransactionPayload = serde_json::from_value(json!({
+        "type": "script_function_payload",
+        "function": "0x1::account::create_account",
+        "type_arguments": [],
+        "arguments": ["0x1234"]
+    }))
+    .unwrap();
+
+    let state_view = context.latest_state_view();
+    let resolver = state_view.as_move_resolver();
+    let expected = resolver
+        .as_converter(context.db.clone())
+        .try_into_aptos_core_transaction_payload(payload.clone())
+        .unwrap();
+
+    let module_cache = Arc::new(ModuleCache::new(10, 100));
+    for _ in 0..2 {
+        let converter = resolver
+            .as_converter(context.db.clone())
+            .with_module_cache(module_cache.clone(), 0);
+        assert_eq!(
+            converter
+                .try_into_aptos_core_transaction_payload(payload.clone())
+                .unwrap(),
+            expected
+        );
+    }
+    assert_eq!(module_cache.len(), 1);
+}
+
 fn assert_value_conversion<'r, R: MoveResolverExt, V: Serialize>(
     converter: &MoveConverter<'r, R>,
     json_move_type: &str,
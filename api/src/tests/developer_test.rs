@@ -0,0 +1,335 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_config::config::NodeConfig;
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::AccessPath, account_address::AccountAddress, account_config::CoinStoreResource,
+    state_store::state_key::StateKey,
+};
+use move_deps::{
+    move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType},
+    move_ir_compiler::Compiler,
+};
+use serde_json::{json, Value};
+
+const COUNTER_MODULE: &str = r#"
+module counter::counter {
+    public fun increment(value: u64): u64 {
+        assert!(value < 10, 1);
+        value + 1
+    }
+
+    #[test]
+    fun test_increment() {
+        assert!(increment(1) == 2, 0);
+    }
+
+    #[test]
+    fun test_overflow() {
+        increment(10);
+    }
+}
+"#;
+
+const TRANSFER_SCRIPT: &str = r#"
+import 0x1.aptos_coin;
+import 0x1.coin;
+
+main(account: &signer, to: address, amount: u64) {
+label b0:
+    coin.transfer<aptos_coin.AptosCoin>(move(account), move(to), move(amount));
+    return;
+}
+"#;
+
+fn developer_test_context(test_name: String) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.developer_api_enabled = true;
+    new_test_context_with_config(test_name, node_config)
+}
+
+fn test_request(source: &str) -> Value {
+    json!({
+        "sources": [{"name": "counter", "source": source}],
+        "named_addresses": [{"name": "counter", "address": "0xcafe"}],
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_move_unit_tests() {
+    let context = developer_test_context(current_function_name!());
+    let resp = context
+        .post("/developer/move/test", test_request(COUNTER_MODULE))
+        .await;
+
+    assert_eq!(resp["success"], false);
+    let tests = resp["tests"].as_array().unwrap();
+    assert_eq!(tests.len(), 2);
+    assert_eq!(tests[0]["name"], "0xcafe::counter::test_increment");
+    assert_eq!(tests[0]["status"], "pass");
+    assert_eq!(tests[1]["name"], "0xcafe::counter::test_overflow");
+    assert_eq!(tests[1]["status"], "fail");
+    assert!(tests[1]["failure"]
+        .as_str()
+        .unwrap()
+        .contains("aborted with"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_move_unit_tests_with_filter() {
+    let context = developer_test_context(current_function_name!());
+    let mut request = test_request(COUNTER_MODULE);
+    request["filter"] = json!("test_increment");
+    let resp = context.post("/developer/move/test", request).await;
+
+    assert_eq!(resp["success"], true);
+    assert_eq!(resp["tests"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_move_unit_tests_compilation_error() {
+    let context = developer_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/developer/move/test",
+            test_request("module counter::counter { fun broken(): u64 { true } }"),
+        )
+        .await;
+
+    assert_eq!(resp["error_code"], "move_compilation_failed");
+    assert!(resp["details"]["diagnostics"]
+        .as_str()
+        .unwrap()
+        .contains("error"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_run_move_unit_tests_requires_developer_api() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/test", test_request(COUNTER_MODULE))
+        .await;
+
+    assert_eq!(resp["error_code"], "developer_api_disabled");
+    assert_eq!(resp["code"], 23);
+}
+
+/// A request transferring `amount` from the root account to the validator owner, with the coin
+/// balance of the root account replaced by `balance`.
+fn transfer_script_request(context: &TestContext, balance: u64, amount: u64) -> Value {
+    let framework_modules = framework::head_release_bundle().compiled_modules();
+    let script = Compiler {
+        deps: framework_modules.iter().collect(),
+    }
+    .into_script_blob(TRANSFER_SCRIPT)
+    .unwrap();
+
+    let root = context.root_account().address();
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        root,
+        CoinStoreResource::struct_tag(),
+    )));
+    let bytes = context
+        .latest_state_view()
+        .get_state_value(&state_key)
+        .unwrap()
+        .unwrap();
+    let coin_store: CoinStoreResource = bcs::from_bytes(&bytes).unwrap();
+    let coin_store = CoinStoreResource::new(
+        balance,
+        coin_store.frozen(),
+        coin_store.deposit_events().clone(),
+        coin_store.withdraw_events().clone(),
+    );
+
+    json!({
+        "script": {
+            "code": {"bytecode": format!("0x{}", hex::encode(script))},
+            "type_arguments": [],
+            "arguments": [context.validator_owner.to_hex_literal(), amount.to_string()],
+        },
+        "signers": [root.to_hex_literal()],
+        "resource_overrides": [{
+            "address": root.to_hex_literal(),
+            "resource_type": "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            "data": format!("0x{}", hex::encode(bcs::to_bytes(&coin_store).unwrap())),
+        }],
+    })
+}
+
+fn coin_store_change(resp: &Value, address: AccountAddress) -> &Value {
+    resp["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|change| {
+            change["type"] == "write_resource"
+                && change["address"] == address.to_hex_literal()
+                && change["data"]["type"] == "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+        })
+        .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_execute_script_read_only() {
+    let context = developer_test_context(current_function_name!());
+    let request = transfer_script_request(&context, 100, 40);
+    let resp = context
+        .post("/developer/move/execute_script", request)
+        .await;
+
+    assert_eq!(resp["success"], true, "{}", resp);
+    // The transfer is made from the overridden balance.
+    let root = context.root_account().address();
+    assert_eq!(
+        coin_store_change(&resp, root)["data"]["data"]["coin"]["value"],
+        "60"
+    );
+    assert_eq!(resp["events"].as_array().unwrap().len(), 2);
+
+    // Nothing was committed.
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/resource/0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            root.to_hex_literal()
+        ))
+        .await;
+    assert_ne!(resp["data"]["coin"]["value"], "60");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_execute_script_read_only_abort() {
+    let context = developer_test_context(current_function_name!());
+    let request = transfer_script_request(&context, 100, 140);
+    let resp = context
+        .post("/developer/move/execute_script", request)
+        .await;
+
+    assert_eq!(resp["success"], false);
+    assert!(resp["vm_status"].as_str().unwrap().contains("abort"));
+    // The gas used up to the abort is reported.
+    assert_ne!(resp["gas_used"], "0");
+    assert_eq!(resp["changes"], json!([]));
+    assert_eq!(resp["events"], json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_execute_script_read_only_invalid_override() {
+    let context = developer_test_context(current_function_name!());
+    let mut request = transfer_script_request(&context, 100, 40);
+    request["resource_overrides"][0]["data"] = json!("0x00");
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/execute_script", request)
+        .await;
+
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_execute_script_read_only_requires_developer_api() {
+    let context = new_test_context(current_function_name!());
+    let request = transfer_script_request(&context, 100, 40);
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/execute_script", request)
+        .await;
+
+    assert_eq!(resp["error_code"], "developer_api_disabled");
+}
+
+fn compose_script_request(context: &TestContext, amounts: &[u64]) -> Value {
+    let calls: Vec<_> = amounts
+        .iter()
+        .map(|amount| {
+            json!({
+                "function": "0x1::coin::transfer",
+                "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+                "arguments": [context.validator_owner.to_hex_literal(), amount.to_string()],
+            })
+        })
+        .collect();
+    json!({ "calls": calls })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_compose_script() {
+    let context = developer_test_context(current_function_name!());
+    let script = context
+        .post(
+            "/developer/move/compose_script",
+            compose_script_request(&context, &[10, 20]),
+        )
+        .await;
+
+    // The script takes the signer once, then the arguments of both calls.
+    assert_eq!(
+        script["code"]["abi"]["params"],
+        json!(["&signer", "address", "u64", "address", "u64"])
+    );
+    assert_eq!(script["type_arguments"], json!([]));
+    assert_eq!(script["arguments"].as_array().unwrap().len(), 4);
+
+    // Executing it makes both transfers.
+    let mut request = transfer_script_request(&context, 100, 0);
+    request["script"] = script;
+    let resp = context
+        .post("/developer/move/execute_script", request)
+        .await;
+    assert_eq!(resp["success"], true, "{}", resp);
+    let root = context.root_account().address();
+    assert_eq!(
+        coin_store_change(&resp, root)["data"]["data"]["coin"]["value"],
+        "70"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_compose_script_invalid_calls() {
+    let context = developer_test_context(current_function_name!());
+
+    let mut request = compose_script_request(&context, &[10]);
+    request["calls"][0]["arguments"] = json!([context.validator_owner.to_hex_literal()]);
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/compose_script", request)
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+
+    // Private functions can't be called from a script.
+    let request = json!({
+        "calls": [{
+            "function": "0x1::coin::coin_address",
+            "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+            "arguments": [],
+        }],
+    });
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/compose_script", request)
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/compose_script", json!({"calls": []}))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_compose_script_requires_developer_api() {
+    let context = new_test_context(current_function_name!());
+    let request = compose_script_request(&context, &[10]);
+    let resp = context
+        .expect_status_code(400)
+        .post("/developer/move/compose_script", request)
+        .await;
+
+    assert_eq!(resp["error_code"], "developer_api_disabled");
+}
@@ -3,6 +3,7 @@
 
 use super::new_test_context;
 use aptos_api_test_context::current_function_name;
+use aptos_api_types::Cursor;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
 static EVENT_KEY: &str =
@@ -52,6 +53,97 @@ async fn test_get_events_by_invalid_key() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_creation_number() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context.get("/accounts/0xa550c18/events/5?start=1").await;
+    let expected = context
+        .get(format!("/events/{}?start=1", EVENT_KEY).as_str())
+        .await;
+    assert_eq!(resp, expected);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_event_key_is_deprecated() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("/v1/events/{}", EVENT_KEY)),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["deprecation"], "true");
+    assert_eq!(
+        resp.headers()["link"],
+        "</v1/accounts/0xa550c18/events/5>; rel=\"successor-version\""
+    );
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/v1/accounts/0xa550c18/events/5"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.headers().contains_key("deprecation"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_version_range() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.get("/events?start=1&limit=2").await;
+    let versions: Vec<u64> = resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["version"].as_str().unwrap().parse().unwrap())
+        .collect();
+    assert!(!versions.is_empty());
+    assert!(versions.windows(2).all(|w| w[0] <= w[1]));
+    assert!(versions.iter().all(|v| (1..=2).contains(v)));
+
+    let resp = context
+        .get("/events?start=0&limit=10&event_type=0x1::block::NewBlockEvent")
+        .await;
+    let events = resp.as_array().unwrap();
+    assert!(events
+        .iter()
+        .all(|e| e["type"] == "0x1::block::NewBlockEvent"));
+    assert!(events.iter().any(|e| e["version"] == "1"));
+    // Block metadata transactions have no sender.
+    assert!(events.iter().all(|e| e["transaction_hash"].is_string()
+        && e["success"] == true
+        && e.get("sender").is_none()));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_version_range_cursor() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/v1/events?start=0&limit=1"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(1).to_string().as_str()
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_events_by_account_event_handle() {
     let mut context = new_test_context(current_function_name!());
@@ -131,3 +223,79 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_event_streams() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context.get("/accounts/0xa550c18/events").await;
+    let streams = resp.as_array().unwrap();
+    assert!(streams.len() > 5);
+    for (creation_number, stream) in streams.iter().enumerate() {
+        assert_eq!(stream["creation_number"], creation_number.to_string());
+    }
+    let stream = &streams[5];
+    assert_eq!(stream["key"], EVENT_KEY);
+
+    let events = context.get("/accounts/0xa550c18/events/5").await;
+    let latest_event = events.as_array().unwrap().last().unwrap();
+    assert_eq!(
+        stream["latest_sequence_number"],
+        latest_event["sequence_number"]
+    );
+    assert_eq!(stream["type"], latest_event["type"]);
+    let counter: u64 = stream["counter"].as_str().unwrap().parse().unwrap();
+    let latest_sequence_number: u64 = latest_event["sequence_number"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(counter, latest_sequence_number + 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_event_streams_paged() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/v1/accounts/0xa550c18/events?start=2&limit=2"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::creation_number(4).to_string().as_str()
+    );
+
+    let resp = context
+        .get(&format!(
+            "/accounts/0xa550c18/events?limit=1&cursor={}",
+            Cursor::creation_number(5)
+        ))
+        .await;
+    let streams = resp.as_array().unwrap();
+    assert_eq!(streams.len(), 1);
+    assert_eq!(streams[0]["key"], EVENT_KEY);
+
+    // A cursor of another list is rejected.
+    context
+        .expect_status_code(400)
+        .get(&format!(
+            "/accounts/0xa550c18/events?cursor={}",
+            Cursor::sequence_number(5)
+        ))
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_event_streams_of_missing_account() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(404)
+        .get("/accounts/0x12345/events")
+        .await;
+}
@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_config::config::NodeConfig;
+use aptos_temppath::TempPath;
+use serde_json::json;
+
+// The mint key is written to the key file once the context, and with it the
+// key of the root account, exists.
+fn faucet_test_context(test_name: String, key_path: &TempPath) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.faucet.enabled = true;
+    node_config.api.faucet.mint_key_file_path = Some(key_path.path().to_path_buf());
+    node_config.api.faucet.maximum_amount = Some(1_000);
+    let context = new_test_context_with_config(test_name, node_config);
+    std::fs::write(
+        key_path.path(),
+        bcs::to_bytes(context.root_account().private_key()).unwrap(),
+    )
+    .unwrap();
+    context
+}
+
+async fn coin_balance(context: &TestContext, address: &str) -> String {
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/resource/0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>",
+            address
+        ))
+        .await;
+    resp["data"]["coin"]["value"].as_str().unwrap().to_owned()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_fund_account() {
+    let key_path = TempPath::new();
+    let mut context = faucet_test_context(current_function_name!(), &key_path);
+    let address = context.gen_account().address().to_hex_literal();
+
+    let resp = context
+        .post("/faucet/fund", json!({"address": address, "amount": "100"}))
+        .await;
+    assert_eq!(resp["amount"], "100");
+    // The account is created, then funded.
+    let txn_hashes = resp["txn_hashes"].as_array().unwrap();
+    assert_eq!(txn_hashes.len(), 2);
+
+    context.commit_mempool_txns(2).await;
+    for txn_hash in txn_hashes {
+        let txn = context
+            .get(&format!(
+                "/transactions/by_hash/{}",
+                txn_hash.as_str().unwrap()
+            ))
+            .await;
+        assert_eq!(txn["success"], true);
+    }
+    assert_eq!(coin_balance(&context, &address).await, "100");
+
+    // An account that exists is only funded, up to the maximum amount.
+    let resp = context
+        .post(
+            "/faucet/fund",
+            json!({"address": address, "amount": "5000"}),
+        )
+        .await;
+    assert_eq!(resp["amount"], "1000");
+    assert_eq!(resp["txn_hashes"].as_array().unwrap().len(), 1);
+
+    context.commit_mempool_txns(1).await;
+    assert_eq!(coin_balance(&context, &address).await, "1100");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_fund_account_faucet_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let address = context.gen_account().address().to_hex_literal();
+
+    let resp = context
+        .expect_status_code(400)
+        .post("/faucet/fund", json!({"address": address, "amount": "100"}))
+        .await;
+    assert_eq!(resp["error_code"], "faucet_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_fund_account_without_mint_key() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.faucet.enabled = true;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let address = context.gen_account().address().to_hex_literal();
+
+    let resp = context
+        .expect_status_code(500)
+        .post("/faucet/fund", json!({"address": address, "amount": "100"}))
+        .await;
+    assert_eq!(resp["error_code"], "internal_error");
+}
@@ -1,9 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
-use aptos_api_test_context::current_function_name;
+use super::{new_test_context, new_test_context_with_config};
+use crate::response::BasicError;
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_config::config::{CorsConfig, NodeConfig};
+use channel::{aptos_channel, message_queues::QueueStyle};
+use event_notifications::{EventNotification, EventNotificationListener};
 use serde_json::json;
+use std::time::Duration;
+use tokio::runtime::Handle;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_index() {
@@ -122,3 +128,170 @@ async fn test_cors_on_non_200_responses() {
     let cors_header = resp.headers().get("access-control-allow-origin").unwrap();
     assert_eq!(cors_header, "test");
 }
+
+async fn cors_preflight(
+    context: &TestContext,
+    origin: &str,
+    path: &str,
+) -> warp::http::Response<bytes::Bytes> {
+    let req = warp::test::request()
+        .header("origin", origin)
+        .header("Access-Control-Request-Headers", "Content-Type")
+        .header("Access-Control-Request-Method", "POST")
+        .method("OPTIONS")
+        .path(&format!("/v1{}", path));
+    context.reply(req).await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cors_allowed_origins() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.cors.allowed_origins = vec![
+        "https://dapp.io".to_string(),
+        "https://*.example.com".to_string(),
+    ];
+    node_config.api.cors.max_age_secs = 600;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    for origin in ["https://dapp.io", "https://app.example.com"] {
+        let resp = cors_preflight(&context, origin, "/transactions").await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers()["access-control-allow-origin"], origin);
+        assert_eq!(resp.headers()["access-control-max-age"], "600");
+    }
+    for origin in ["https://example.com", "https://app.example.com.evil.io"] {
+        let resp = cors_preflight(&context, origin, "/transactions").await;
+        assert_ne!(resp.status(), 200);
+        assert!(resp.headers().get("access-control-allow-origin").is_none());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cors_route_group_overrides() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.cors_overrides.insert(
+        "/v1/transactions".to_string(),
+        CorsConfig {
+            allowed_origins: vec!["https://wallet.io".to_string()],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        },
+    );
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    // Other route groups keep the default configuration.
+    let resp = cors_preflight(&context, "https://dapp.io", "/accounts/0x1/resources").await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["access-control-allow-origin"],
+        "https://dapp.io"
+    );
+    assert!(resp
+        .headers()
+        .get("access-control-allow-credentials")
+        .is_none());
+
+    for path in ["/transactions", "/transactions/by_version/0"] {
+        let resp = cors_preflight(&context, "https://dapp.io", path).await;
+        assert_ne!(resp.status(), 200);
+        assert!(resp.headers().get("access-control-allow-origin").is_none());
+
+        let resp = cors_preflight(&context, "https://wallet.io", path).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            resp.headers()["access-control-allow-origin"],
+            "https://wallet.io"
+        );
+        assert_eq!(resp.headers()["access-control-allow-credentials"], "true");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cors_follows_config_reload() {
+    let context = new_test_context(current_function_name!());
+    let resp = cors_preflight(&context, "https://dapp.io", "/transactions").await;
+    assert_eq!(resp.status(), 200);
+
+    let mut api_config = (*context.context.api_config()).clone();
+    api_config.cors.allowed_origins = vec!["https://wallet.io".to_string()];
+    context.context.update_api_config(api_config);
+
+    let resp = cors_preflight(&context, "https://dapp.io", "/transactions").await;
+    assert_ne!(resp.status(), 200);
+    let resp = cors_preflight(&context, "https://wallet.io", "/transactions").await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_index_with_health() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.developer_api_enabled = true;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let resp = context.get("/").await;
+    assert_eq!(resp.get("health"), None);
+
+    let resp = context.get("/?with_health=true").await;
+    let health = &resp["health"];
+    assert_eq!(health["mempool_size"], "0");
+    assert_ne!(health["mempool_capacity"], "0");
+    assert!(health["state_sync_lag_usecs"].is_string());
+    assert_eq!(
+        health["api_version"],
+        include_str!("../../doc/.version").trim()
+    );
+    assert_eq!(health["features"], json!(["developer_api"]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ledger_info_cache_refreshed_on_commit_notification() {
+    let mut node_config = NodeConfig::default();
+    // Long enough that only commit notifications refresh the cached ledger info.
+    node_config.api.ledger_info_max_staleness_ms = 3_600_000;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let (sender, notification_receiver) = aptos_channel::new(QueueStyle::KLAST, 1, None);
+    let api_context = context.context.clone().with_commit_notifications(
+        &Handle::current(),
+        EventNotificationListener {
+            notification_receiver,
+        },
+    );
+    let cached_version = api_context
+        .get_latest_ledger_info::<BasicError>()
+        .unwrap()
+        .version();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+    let latest_version = context.get_latest_ledger_info().version();
+    assert!(latest_version > cached_version);
+    assert_eq!(
+        api_context
+            .get_latest_ledger_info::<BasicError>()
+            .unwrap()
+            .version(),
+        cached_version
+    );
+
+    sender
+        .push(
+            (),
+            EventNotification {
+                version: latest_version,
+                subscribed_events: vec![],
+            },
+        )
+        .unwrap();
+    let mut remaining_attempts = 50;
+    while api_context
+        .get_latest_ledger_info::<BasicError>()
+        .unwrap()
+        .version()
+        != latest_version
+    {
+        assert!(remaining_attempts > 0, "cached ledger info not refreshed");
+        remaining_attempts -= 1;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
@@ -48,7 +48,7 @@ async fn test_invalid_script_function_argument_address_string() {
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_missing_script_function_arguments() {
     let mut req = signing_message_request();
-    req["payload"]["arguments"] = json!(["0"]);
+    req["payload"]["arguments"] = json!([]);
 
     response_error_msg(req, current_function_name!()).await;
 }
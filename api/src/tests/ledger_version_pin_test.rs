@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_api_types::X_APTOS_LEDGER_VERSION_PIN;
+use serde_json::Value;
+
+async fn get_pinned(context: &TestContext, path: &str, pin: &str) -> Value {
+    context
+        .execute(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path(path))
+                .header(X_APTOS_LEDGER_VERSION_PIN, pin),
+        )
+        .await
+}
+
+fn root_account_resource(context: &TestContext) -> String {
+    format!(
+        "/accounts/{}/resource/0x1::account::Account",
+        context.root_account().address().to_hex_literal()
+    )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ledger_version_pin() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+    let path = root_account_resource(&context);
+
+    let resp = context.get(&path).await;
+    assert_eq!(resp["data"]["sequence_number"], "1");
+    let resp = get_pinned(&context, &path, "0").await;
+    assert_eq!(resp["data"]["sequence_number"], "0");
+
+    // A ledger version requested explicitly takes precedence over the pin.
+    let latest_version = context.get_latest_ledger_info().version();
+    let resp = get_pinned(
+        &context,
+        &format!("{}?ledger_version={}", path, latest_version),
+        "0",
+    )
+    .await;
+    assert_eq!(resp["data"]["sequence_number"], "1");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ledger_version_pin_out_of_range() {
+    let context = new_test_context(current_function_name!());
+    let path = root_account_resource(&context);
+
+    let resp = get_pinned(&context.expect_status_code(404), &path, "1000000").await;
+    assert_eq!(resp["error_code"], "version_not_found");
+
+    let resp = get_pinned(&context.expect_status_code(400), &path, "latest").await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
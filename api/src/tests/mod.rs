@@ -3,17 +3,37 @@
 
 mod accounts_test;
 mod blocks_test;
+mod contract_test;
 mod converter_test;
+mod developer_test;
 mod events_test;
+mod faucet_test;
 mod index_test;
 mod invalid_post_request_test;
+mod ledger_version_pin_test;
+mod multisig_test;
+mod reload_config_test;
+mod rest_client_test;
+mod staking_test;
 mod state_test;
 mod string_resource_test;
 mod transaction_vector_test;
 mod transactions_test;
 
-use aptos_api_test_context::{new_test_context as super_new_test_context, TestContext};
+use aptos_api_test_context::{
+    new_test_context as super_new_test_context,
+    new_test_context_with_config as super_new_test_context_with_config, TestContext,
+};
+use aptos_config::config::NodeConfig;
 
 fn new_test_context(test_name: String) -> TestContext {
     super_new_test_context(test_name, false)
 }
+
+fn new_test_context_with_config(test_name: String, node_config: NodeConfig) -> TestContext {
+    super_new_test_context_with_config(test_name, node_config, false)
+}
+
+fn new_test_context_with_indexer(test_name: String) -> TestContext {
+    super_new_test_context(test_name, true)
+}
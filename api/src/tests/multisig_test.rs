@@ -0,0 +1,164 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::current_function_name;
+use aptos_api_types::HexEncodedBytes;
+use aptos_config::config::NodeConfig;
+use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use aptos_crypto::traits::{SigningKey, Uniform};
+use aptos_types::transaction::SignedTransaction;
+use serde_json::{json, Value};
+
+fn generate_keys(count: usize) -> Vec<Ed25519PrivateKey> {
+    let mut rng = rand::thread_rng();
+    (0..count)
+        .map(|_| Ed25519PrivateKey::generate(&mut rng))
+        .collect()
+}
+
+fn public_key_hex(private_key: &Ed25519PrivateKey) -> String {
+    HexEncodedBytes::from(Ed25519PublicKey::from(private_key).to_bytes().to_vec()).to_string()
+}
+
+fn create_session_request(private_keys: &[Ed25519PrivateKey], threshold: u8) -> Value {
+    json!({
+        "sender": "0xdd",
+        "sequence_number": "0",
+        "gas_unit_price": "1",
+        "max_gas_amount": "1000000",
+        "expiration_timestamp_secs": "9991638487317",
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::account::create_account",
+            "type_arguments": [],
+            "arguments": ["0x1234567"]
+        },
+        "public_keys": private_keys.iter().map(public_key_hex).collect::<Vec<_>>(),
+        "threshold": threshold,
+    })
+}
+
+fn sign(private_key: &Ed25519PrivateKey, session: &Value) -> Value {
+    let message: HexEncodedBytes = session["signing_message"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let signature = private_key.sign_arbitrary_message(message.inner());
+    json!({
+        "public_key": public_key_hex(private_key),
+        "signature": HexEncodedBytes::from(signature.to_bytes().to_vec()).to_string(),
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_multisig_session() {
+    let context = new_test_context(current_function_name!());
+    let private_keys = generate_keys(3);
+    let session = context
+        .post(
+            "/multisig/sessions",
+            create_session_request(&private_keys, 2),
+        )
+        .await;
+    assert_eq!(session["sender"], "0xdd");
+    assert_eq!(session["threshold"], 2);
+    assert_eq!(session["complete"], false);
+    let session_id = session["id"].as_str().unwrap().to_owned();
+
+    // Creating the session again joins it.
+    let same_session = context
+        .post(
+            "/multisig/sessions",
+            create_session_request(&private_keys, 2),
+        )
+        .await;
+    assert_eq!(same_session["id"], session_id);
+
+    let path = format!("/multisig/sessions/{}", session_id);
+    context
+        .expect_status_code(400)
+        .get(&format!("{}/transaction", path))
+        .await;
+
+    let resp = context
+        .post(
+            &format!("{}/signatures", path),
+            sign(&private_keys[2], &session),
+        )
+        .await;
+    assert_eq!(resp["signed_key_indices"], json!([2]));
+    assert_eq!(resp["complete"], false);
+    context
+        .post(
+            &format!("{}/signatures", path),
+            sign(&private_keys[0], &session),
+        )
+        .await;
+
+    let resp = context.get(&path).await;
+    assert_eq!(resp["signed_key_indices"], json!([0, 2]));
+    assert_eq!(resp["complete"], true);
+
+    let resp = context.get(&format!("{}/transaction", path)).await;
+    let bytes: HexEncodedBytes = resp.as_str().unwrap().parse().unwrap();
+    let signed_txn: SignedTransaction = bcs::from_bytes(bytes.inner()).unwrap();
+    assert!(signed_txn.check_signature().is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_add_multisig_signature_rejects_invalid_signatures() {
+    let context = new_test_context(current_function_name!());
+    let private_keys = generate_keys(2);
+    let session = context
+        .post(
+            "/multisig/sessions",
+            create_session_request(&private_keys, 1),
+        )
+        .await;
+    let path = format!(
+        "/multisig/sessions/{}/signatures",
+        session["id"].as_str().unwrap()
+    );
+
+    let other_key = &generate_keys(1)[0];
+    let resp = context
+        .expect_status_code(400)
+        .post(&path, sign(other_key, &session))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+
+    let mut request = sign(&private_keys[0], &session);
+    request["public_key"] = json!(public_key_hex(&private_keys[1]));
+    let resp = context.expect_status_code(400).post(&path, request).await;
+    assert_eq!(resp["error_code"], "invalid_signature");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_multisig_session_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "/multisig/sessions/{}",
+            aptos_crypto::HashValue::zero().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "multisig_session_not_found");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_create_multisig_session_limit_reached() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.multisig_session_capacity = 0;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp = context
+        .expect_status_code(507)
+        .post(
+            "/multisig/sessions",
+            create_session_request(&generate_keys(2), 1),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "multisig_session_limit_reached");
+}
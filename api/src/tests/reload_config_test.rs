@@ -0,0 +1,78 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context_with_config;
+use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_config::config::{NodeConfig, PersistableConfig};
+use aptos_temppath::TempPath;
+
+fn reloadable_test_context(test_name: String, config_path: &TempPath) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.config_reload_endpoint_enabled = true;
+    node_config.api.node_config_path = Some(config_path.path().to_path_buf());
+    new_test_context_with_config(test_name, node_config)
+}
+
+async fn reload_config(context: &TestContext) -> u16 {
+    let req = warp::test::request()
+        .method("POST")
+        .path("/v1/reload_config")
+        .body("");
+    context.reply(req).await.status().as_u16()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reload_config() {
+    let config_path = TempPath::new();
+    let context = reloadable_test_context(current_function_name!(), &config_path);
+    context.get("/transactions?limit=20").await;
+
+    let mut file_config = NodeConfig::default();
+    file_config.api.config_reload_endpoint_enabled = true;
+    file_config.api.max_page_size = 10;
+    file_config.api.address.set_port(1);
+    file_config.save_config(config_path.path()).unwrap();
+    assert_eq!(reload_config(&context).await, 200);
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?limit=20")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_limit_param");
+    // Settings only applied when the API starts are not reloaded.
+    let api_config = context.context.api_config();
+    assert_eq!(api_config.max_page_size, 10);
+    assert_eq!(api_config.address, NodeConfig::default().api.address);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reload_config_keeps_config_on_failure() {
+    let config_path = TempPath::new();
+    let context = reloadable_test_context(current_function_name!(), &config_path);
+
+    std::fs::write(config_path.path(), "api: [").unwrap();
+    assert_eq!(reload_config(&context).await, 500);
+    assert_eq!(*context.context.api_config(), {
+        let mut api_config = NodeConfig::default().api;
+        api_config.config_reload_endpoint_enabled = true;
+        api_config.node_config_path = Some(config_path.path().to_path_buf());
+        api_config
+    });
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_reload_config_endpoint_disabled() {
+    let config_path = TempPath::new();
+    let mut node_config = NodeConfig::default();
+    node_config.api.node_config_path = Some(config_path.path().to_path_buf());
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let mut file_config = NodeConfig::default();
+    file_config.api.max_page_size = 10;
+    file_config.save_config(config_path.path()).unwrap();
+    assert_ne!(reload_config(&context).await, 200);
+    assert_eq!(
+        context.context.api_config().max_page_size,
+        NodeConfig::default().api.max_page_size
+    );
+}
@@ -0,0 +1,132 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_rest_client::{Backoff, Client, RestError};
+use aptos_types::{
+    account_config::AccountResource,
+    transaction::{ExecutionStatus, TransactionStatus},
+};
+use reqwest::StatusCode;
+use std::time::{Duration, Instant};
+use url::Url;
+
+fn rest_client(context: &TestContext) -> Client {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    Client::new(Url::parse(&format!("http://{}", address)).unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_json_and_bcs_reads_agree() {
+    let context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let address = context.root_account().address();
+
+    let account = client.get_account(address).await.unwrap();
+    let account_bcs = client.get_account_bcs(address).await.unwrap();
+    assert_eq!(
+        account.inner().sequence_number,
+        account_bcs.inner().sequence_number()
+    );
+    assert_eq!(
+        account.inner().authentication_key.to_vec(),
+        account_bcs.inner().authentication_key()
+    );
+    assert_eq!(account.state().version, account_bcs.state().version);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_pinned_reads() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let root = context.root_account().address();
+    let pinned = client.pinned_to_latest_version().await.unwrap();
+    let pinned_version = pinned.ledger_version_pin().unwrap();
+
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let resp = client.get_account_bcs(root).await.unwrap();
+    assert_eq!(resp.inner().sequence_number(), 1);
+    let resp = pinned.get_account_bcs(root).await.unwrap();
+    assert_eq!(resp.inner().sequence_number(), 0);
+    let resp = pinned.get_account(root).await.unwrap();
+    assert_eq!(resp.inner().sequence_number, 0);
+
+    // The account created after the pinned version doesn't exist at it.
+    let error = pinned
+        .get_account_bcs(account.address())
+        .await
+        .unwrap_err()
+        .downcast::<RestError>()
+        .unwrap();
+    assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+
+    // A version set on the request takes precedence over the pin.
+    let latest_version = context.get_latest_ledger_info().version();
+    let resp = pinned
+        .get_account_resource_at_version_bcs::<AccountResource>(root, latest_version)
+        .await
+        .unwrap();
+    assert_eq!(resp.inner().sequence_number(), 1);
+
+    let unpinned = pinned.unpinned();
+    assert_eq!(unpinned.ledger_version_pin(), None);
+    let resp = unpinned.get_account_bcs(account.address()).await.unwrap();
+    assert_eq!(resp.inner().sequence_number(), 0);
+    assert!(resp.state().version > pinned_version);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_submit_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let client = rest_client(&context);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    client.submit(&txn).await.unwrap();
+    context.commit_mempool_txns(1).await;
+
+    let resp = client.get_account_bcs(account.address()).await.unwrap();
+    assert_eq!(resp.inner().sequence_number(), 0);
+
+    let transaction = client
+        .get_transaction_by_hash(txn.committed_hash())
+        .await
+        .unwrap();
+    assert!(transaction.inner().success());
+    let version = transaction.inner().version().unwrap();
+    let output = client
+        .get_transaction_output_by_version_bcs(version)
+        .await
+        .unwrap();
+    assert_eq!(
+        output.inner().status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    // The block metadata transaction precedes it in the block.
+    let outputs = client
+        .get_transaction_outputs_bcs(Some(version - 1), Some(2))
+        .await
+        .unwrap();
+    assert_eq!(outputs.inner().len(), 2);
+    assert_eq!(outputs.inner()[1], *output.inner());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_backoff_gives_up_after_timeout() {
+    // Nothing listens on the port, so every attempt fails to connect.
+    let backoff = Backoff::new(
+        Duration::from_millis(10),
+        Duration::from_millis(50),
+        Duration::from_millis(300),
+    );
+    let client = Client::new(Url::parse("http://127.0.0.1:1").unwrap()).with_backoff(backoff);
+
+    let start = Instant::now();
+    assert!(client.get_index().await.is_err());
+    // The delays before the backoff reaches its limit add up to 130ms.
+    assert!(start.elapsed() >= Duration::from_millis(130));
+}
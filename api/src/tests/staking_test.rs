@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::current_function_name;
+use serde_json::{json, Value};
+
+fn parse_u64(value: &Value) -> u64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_stake_pool() {
+    let mut context = new_test_context(current_function_name!());
+    let pool_address = context.validator_owner.to_hex_literal();
+    let resp = context
+        .get(&format!("/accounts/{}/stake_pool", pool_address))
+        .await;
+    assert_eq!(resp["pool_address"], pool_address);
+    assert!(parse_u64(&resp["active"]) > 0);
+    assert_eq!(
+        parse_u64(&resp["total_stake"]),
+        parse_u64(&resp["active"])
+            + parse_u64(&resp["inactive"])
+            + parse_u64(&resp["pending_active"])
+            + parse_u64(&resp["pending_inactive"])
+    );
+    if resp["lockup_expired"].as_bool().unwrap() {
+        assert_eq!(parse_u64(&resp["remaining_lockup_secs"]), 0);
+    } else {
+        assert!(parse_u64(&resp["remaining_lockup_secs"]) > 0);
+    }
+    assert_eq!(resp.get("rewards"), None);
+
+    // No epoch has ended since genesis, so no rewards have been distributed.
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/stake_pool?rewards_since_version=0",
+            pool_address
+        ))
+        .await;
+    assert_eq!(
+        resp["rewards"],
+        json!({"since_version": "0", "amount": "0", "num_distributions": "0"})
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_stake_pool_errors() {
+    let mut context = new_test_context(current_function_name!());
+    let pool_address = context.validator_owner.to_hex_literal();
+    context
+        .expect_status_code(404)
+        .get("/accounts/0x1/stake_pool")
+        .await;
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "/accounts/{}/stake_pool?ledger_version=1000000",
+            pool_address
+        ))
+        .await;
+    context
+        .expect_status_code(400)
+        .get(&format!(
+            "/accounts/{}/stake_pool?rewards_since_version=1000000",
+            pool_address
+        ))
+        .await;
+}
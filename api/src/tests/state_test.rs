@@ -1,10 +1,27 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_config, new_test_context_with_indexer};
 use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_api_types::{Cursor, HexEncodedBytes};
+use aptos_config::config::NodeConfig;
+use aptos_crypto::HashValue;
 use aptos_sdk::types::LocalAccount;
-use move_deps::{move_core_types::account_address::AccountAddress, move_package::BuildConfig};
+use aptos_types::{
+    access_path::AccessPath, account_config::AccountResource, state_store::state_key::StateKey,
+};
+use framework::{
+    aptos_stdlib,
+    source_attestation::{SourceAttestation, TOOLCHAIN_VERSION},
+    BuildOptions, BuiltPackage,
+};
+use move_deps::{
+    move_core_types::{
+        account_address::AccountAddress, language_storage::ResourceKey,
+        move_resource::MoveStructType,
+    },
+    move_package::BuildConfig,
+};
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::{convert::TryInto, path::PathBuf};
@@ -64,6 +81,93 @@ async fn test_get_account_resource_struct_tag_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_head_account_resource_and_module() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    for (path, status) in [
+        (
+            get_account_resource("0xA550C18", "0x1::guid::Generator"),
+            200,
+        ),
+        (
+            get_account_resource("0xA550C19", "0x1::guid::Generator"),
+            404,
+        ),
+        (
+            get_account_resource("0xA550C18", "0x1::GUID_Generator"),
+            400,
+        ),
+        (get_account_module("0x1", "guid"), 200),
+        (get_account_module("0x1", "NoNoNo"), 404),
+    ] {
+        let resp = context
+            .reply(
+                warp::test::request()
+                    .method("HEAD")
+                    .path(&context.prepend_path(&path)),
+            )
+            .await;
+        assert_eq!(resp.status(), status, "{}", path);
+        assert!(resp.body().is_empty(), "{}", path);
+        if status == 200 {
+            assert_eq!(
+                resp.headers()["X-Aptos-Ledger-Version"],
+                ledger_version.to_string().as_str()
+            );
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_raw_state_value() {
+    let context = new_test_context(current_function_name!());
+    let root_address = context.root_account().address();
+    let resp = context
+        .post(
+            "/state_values/raw",
+            json!({ "key": raw_state_key(account_resource_state_key(root_address)) }),
+        )
+        .await;
+    let bytes: HexEncodedBytes = resp.as_str().unwrap().parse().unwrap();
+    let account: AccountResource = bcs::from_bytes(bytes.inner()).unwrap();
+    assert_eq!(account.sequence_number(), 0);
+
+    let missing_address = AccountAddress::from_hex_literal("0xa550c19").unwrap();
+    let resp = context
+        .expect_status_code(404)
+        .post(
+            "/state_values/raw",
+            json!({ "key": raw_state_key(account_resource_state_key(missing_address)) }),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "state_value_not_found");
+
+    context
+        .expect_status_code(400)
+        .post("/state_values/raw", json!({ "key": "0x00" }))
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resources_with_one_storage_read_thread() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.storage_read_threads = 1;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    // Concurrent reads wait for each other, as only one storage read runs at once.
+    let paths: Vec<_> = ["0x1::guid::Generator", "0x1::account::Account"]
+        .iter()
+        .cycle()
+        .take(10)
+        .map(|resource| get_account_resource("0xA550C18", resource))
+        .collect();
+    let resps = futures::future::join_all(paths.iter().map(|path| context.get(path))).await;
+    for (path, resp) in paths.iter().zip(resps) {
+        assert_eq!(resp, context.get(path).await);
+    }
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_module() {
     let mut context = new_test_context(current_function_name!());
@@ -91,6 +195,194 @@ async fn test_get_account_module_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_module_disassembly() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .get(&format!(
+            "{}/disassembly",
+            get_account_module("0x1", "guid")
+        ))
+        .await;
+    let disassembly = resp["disassembly"].as_str().unwrap();
+    assert!(disassembly.contains("module 1.guid {"), "{}", disassembly);
+    assert!(disassembly.contains("public create_id("), "{}", disassembly);
+    // The framework is released without source maps.
+    assert_eq!(resp["with_source_map"], false);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_module_disassembly_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "{}/disassembly",
+            get_account_module("0x1", "NoNoNo")
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "module_not_found");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_module_verification() {
+    let mut context = new_test_context(current_function_name!());
+    let mut account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let package = BuiltPackage::build(
+        test_package_dir(),
+        BuildOptions {
+            with_source_attestation: true,
+            named_addresses: [("TestAccount".to_string(), account.address())].into(),
+            ..BuildOptions::default()
+        },
+    )
+    .unwrap();
+    let metadata = package.extract_metadata().unwrap();
+    let source_hash = SourceAttestation::from_build_info(&metadata.build_info)
+        .unwrap()
+        .unwrap()
+        .modules["TableTestData"]
+        .source_hash;
+    let txn = account.sign_with_transaction_builder(context.transaction_factory().payload(
+        aptos_stdlib::code_publish_package_txn(
+            bcs::to_bytes(&metadata).unwrap(),
+            package.extract_code(),
+        ),
+    ));
+    context.commit_block(&vec![txn]).await;
+
+    let path = format!(
+        "{}/verification",
+        get_account_module(&account.address().to_hex_literal(), "TableTestData")
+    );
+    let resp = context
+        .get(&format!(
+            "{}?source_hash={}&toolchain_version={}",
+            path,
+            source_hash.to_hex_literal(),
+            TOOLCHAIN_VERSION
+        ))
+        .await;
+    assert_eq!(resp["attested"], true);
+    assert_eq!(resp["bytecode_matches"], true);
+    assert_eq!(resp["published_source_matches"], true);
+    assert_eq!(resp["source_hash_matches"], true);
+    assert_eq!(resp["toolchain_version_matches"], true);
+    assert_eq!(resp["verified"], true);
+
+    let resp = context
+        .get(&format!(
+            "{}?source_hash={}",
+            path,
+            HashValue::zero().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["source_hash_matches"], false);
+    assert_eq!(resp["verified"], false);
+
+    // The framework is released without attestations.
+    let resp = context
+        .get(&format!(
+            "{}/verification",
+            get_account_module("0x1", "guid")
+        ))
+        .await;
+    assert_eq!(resp["attested"], false);
+    assert_eq!(resp["verified"], false);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_gas_schedule() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context.get("/gas_schedule").await;
+    let entries = resp["entries"].as_array().unwrap();
+    let min_transaction_gas_units = entries
+        .iter()
+        .find(|entry| entry["name"] == "txn.min_transaction_gas_units")
+        .unwrap_or_else(|| panic!("no txn.min_transaction_gas_units in {}", resp));
+    assert!(min_transaction_gas_units["value"]
+        .as_str()
+        .unwrap()
+        .parse::<u64>()
+        .is_ok());
+    assert!(resp["feature_version"]
+        .as_str()
+        .unwrap()
+        .parse::<u64>()
+        .is_ok());
+
+    context
+        .expect_status_code(404)
+        .get("/gas_schedule?ledger_version=1000000")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_validator_set() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context.get("/validators").await;
+    let validators = resp["active_validators"].as_array().unwrap();
+    assert_eq!(validators.len(), 1);
+    assert_eq!(
+        validators[0]["address"],
+        context.validator_owner.to_hex_literal()
+    );
+    assert!(validators[0]["voting_power"]
+        .as_str()
+        .unwrap()
+        .parse::<u64>()
+        .is_ok());
+    // A BLS12-381 public key is 48 bytes.
+    assert_eq!(
+        validators[0]["consensus_public_key"]
+            .as_str()
+            .unwrap()
+            .len(),
+        2 + 48 * 2
+    );
+    assert_eq!(resp["pending_active"], json!([]));
+    assert_eq!(resp["pending_inactive"], json!([]));
+
+    context
+        .expect_status_code(404)
+        .get("/validators?ledger_version=1000000")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_on_chain_configs() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context.get("/on_chain_configs").await;
+    let gas_schedule = context.get("/gas_schedule").await;
+    assert_eq!(resp["version"], gas_schedule["feature_version"]);
+    assert!(resp["consensus"]["decoupled_execution"].is_boolean());
+    assert!([
+        "fixed_proposer",
+        "rotating_proposer",
+        "leader_reputation",
+        "round_proposer"
+    ]
+    .contains(
+        &resp["consensus"]["proposer_election_type"]
+            .as_str()
+            .unwrap()
+    ));
+    assert!(resp["execution"]["sequential_fallback_enabled"].is_boolean());
+    assert!(resp["execution"]["max_aborts_per_hundred_txns"]
+        .as_str()
+        .unwrap()
+        .parse::<u64>()
+        .is_ok());
+
+    context
+        .expect_status_code(404)
+        .get("/on_chain_configs?ledger_version=1000000")
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_table_item() {
     let mut context = new_test_context(current_function_name!());
@@ -173,6 +465,61 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_table_items() {
+    let mut context = new_test_context_with_indexer(current_function_name!());
+    let ctx = &mut context;
+    let mut account = ctx.gen_account();
+    let acc = &mut account;
+    let txn = ctx.create_user_account(acc);
+    ctx.commit_block(&vec![txn.clone()]).await;
+    make_test_tables(ctx, acc).await;
+
+    let tt = ctx
+        .api_get_account_resource(
+            acc,
+            &acc.address().to_hex_literal(),
+            "TableTestData",
+            "TestTables",
+        )
+        .await["data"]
+        .to_owned();
+    let handle: u128 = tt["u8_table"]["handle"].as_str().unwrap().parse().unwrap();
+
+    let resp = ctx.get(&get_table_items(handle)).await;
+    assert_eq!(
+        resp,
+        json!({
+            "items": [{"key": 1, "key_type": "u8", "value": 1, "value_type": "u8"}],
+            "cursor": null,
+        })
+    );
+
+    // A full page may be followed by more items, so it comes with a cursor.
+    let resp = ctx
+        .get(&format!("{}?limit=1", get_table_items(handle)))
+        .await;
+    assert_eq!(resp["items"].as_array().unwrap().len(), 1);
+    let cursor = resp["cursor"].as_str().unwrap().to_owned();
+    let resp = ctx
+        .get(&format!("{}?cursor={}", get_table_items(handle), cursor))
+        .await;
+    assert_eq!(resp, json!({"items": [], "cursor": null}));
+
+    // Cursors of other lists are rejected.
+    ctx.expect_status_code(400)
+        .get(&format!(
+            "{}?cursor={}",
+            get_table_items(handle),
+            Cursor::version(1)
+        ))
+        .await;
+
+    ctx.expect_status_code(404)
+        .get(&get_table_items(handle + 1_000_000))
+        .await;
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -185,6 +532,10 @@ fn get_table_item(handle: u128) -> String {
     format!("/tables/{}/item", handle)
 }
 
+fn get_table_items(handle: u128) -> String {
+    format!("/tables/{}/items", handle)
+}
+
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     let module = build_test_module(account.address()).await;
 
@@ -200,11 +551,15 @@ async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     .await
 }
 
-async fn build_test_module(account: AccountAddress) -> Vec<u8> {
-    let package_dir = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"))
+fn test_package_dir() -> PathBuf {
+    PathBuf::from(std::env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
-        .join("api/move-test-package");
+        .join("api/move-test-package")
+}
+
+async fn build_test_module(account: AccountAddress) -> Vec<u8> {
+    let package_dir = test_package_dir();
     let build_config = BuildConfig {
         generate_docs: false,
         install_dir: Some(package_dir.clone()),
@@ -256,3 +611,14 @@ async fn assert_table_item<T: Serialize, U: Serialize>(
     let response = api_get_table_item(ctx, table, key_type, value_type, key).await;
     assert_eq!(response, json!(value));
 }
+
+fn account_resource_state_key(address: AccountAddress) -> StateKey {
+    StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        address,
+        AccountResource::struct_tag(),
+    )))
+}
+
+fn raw_state_key(state_key: StateKey) -> String {
+    HexEncodedBytes::from(bcs::to_bytes(&state_key).unwrap()).to_string()
+}
@@ -1,25 +1,31 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
-use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{
+    assert_json, current_function_name, pretty, ApiSpecificConfig, TestContext,
+};
+use aptos_api_types::{Cursor, HexEncodedBytes};
 
 use aptos_crypto::{
-    multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
-    PrivateKey, SigningKey, Uniform,
+    multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey, MultiEd25519Signature},
+    signing_message, PrivateKey, SigningKey, Uniform,
 };
-use aptos_sdk::types::LocalAccount;
+use aptos_sdk::{transaction_builder::TransactionBuilder, types::LocalAccount};
 use aptos_types::{
     access_path::{AccessPath, Path},
     account_address::AccountAddress,
+    chain_id::ChainId,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
-        ChangeSet, Script, ScriptFunction, SignedTransaction,
+        ChangeSet, RawTransactionWithData, Script, ScriptFunction, SignedTransaction,
+        TransactionListWithProof, TransactionOutput,
     },
     utility_coin::APTOS_COIN_TYPE,
     write_set::{WriteOp, WriteSetMut},
 };
 
+use aptos_config::config::{NodeConfig, SubmissionFilterConfig};
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_types::state_store::state_key::StateKey;
 use move_deps::move_core_types::{
@@ -29,6 +35,10 @@ use move_deps::move_core_types::{
 use poem_openapi::types::ParseFromJSON;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_deserialize_genesis_transaction() {
@@ -113,6 +123,371 @@ async fn test_get_transactions_param_limit_exceeds_limit() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_param_limit_exceeds_configured_max_page_size() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_page_size = 10;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?limit=11")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_limit_param");
+    assert_eq!(resp["code"], 5);
+    assert_eq!(resp["retriable"], false);
+    assert_eq!(resp["details"]["max_page_size"], "10");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_streamed() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_page_size = 2;
+    node_config.api.max_streamed_page_size = Some(10);
+    // Streamed pages aren't cut short.
+    node_config.api.max_response_size_bytes = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=0&limit=10")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("X-Aptos-Cursor").is_none());
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    let versions: Vec<_> = txns
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|txn| txn["version"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(versions, ["0", "1", "2", "3"]);
+
+    // The same transactions come one per line as NDJSON, for any limit.
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .header("Accept", "application/x-ndjson")
+                .path(&context.prepend_path("/transactions?start=1&limit=2")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["Content-Type"], "application/x-ndjson");
+    let body = std::str::from_utf8(resp.body()).unwrap();
+    let lines: Vec<serde_json::Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2, "{}", body);
+    assert_eq!(lines[0], txns[1]);
+    assert_eq!(lines[1], txns[2]);
+
+    // And as length prefixed BCS values, each of which is what a plain BCS
+    // list holds for the transaction.
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .header("Accept", "application/x-bcs; stream=length-prefixed")
+                .path(&context.prepend_path("/transactions?start=1&limit=2")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["Content-Type"],
+        "application/x-bcs; stream=length-prefixed"
+    );
+    let items = split_length_prefixed(resp.body());
+    assert_eq!(items.len(), 2);
+    for (version, item) in (1..).zip(items) {
+        let resp = context
+            .reply(
+                warp::test::request()
+                    .method("GET")
+                    .header("Accept", "application/x-bcs")
+                    .path(
+                        &context.prepend_path(&format!("/transactions?start={}&limit=1", version)),
+                    ),
+            )
+            .await;
+        assert_eq!(resp.status(), 200);
+        // The list is prefixed with its single item count.
+        assert_eq!(resp.body()[0], 1);
+        assert_eq!(&resp.body()[1..], item);
+    }
+
+    // Limits are still bounded.
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?limit=11")
+        .await;
+    assert_eq!(resp["details"]["max_page_size"], "10");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_truncated_by_max_response_size() {
+    let mut node_config = NodeConfig::default();
+    // Far smaller than any transaction, so only a single one fits.
+    node_config.api.max_response_size_bytes = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=1&limit=3")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(2).to_string().as_str()
+    );
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(txns[0]["version"], "1");
+
+    // Resuming from the cursor continues where the previous page stopped.
+    let cursor = resp.headers()["X-Aptos-Cursor"]
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path(&format!("/transactions?cursor={}&limit=3", cursor))),
+        )
+        .await;
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(3).to_string().as_str()
+    );
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(txns[0]["version"], "2");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_with_invalid_cursor() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?cursor=not-a-cursor")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_cursor_param");
+
+    // A cursor of another kind of list.
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "/transactions?cursor={}",
+            Cursor::sequence_number(1)
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_cursor_param");
+
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "/transactions?start=1&cursor={}",
+            Cursor::version(1)
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "invalid_cursor_param");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_within_max_response_size_has_no_cursor() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=0&limit=1")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get("X-Aptos-Cursor").is_none());
+}
+
+/// Commits two blocks of one user transaction each, so the ledger is: genesis at
+/// 0, block metadata at 1 and 4, user transactions at 2 and 5 and state
+/// checkpoints at 3 and 6.
+async fn commit_two_user_transactions(context: &mut TestContext) {
+    let mut root_account = context.root_account();
+    for _i in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+}
+
+fn versions_of(txns: &serde_json::Value) -> Vec<&str> {
+    txns.as_array()
+        .unwrap()
+        .iter()
+        .map(|txn| txn["version"].as_str().unwrap())
+        .collect()
+}
+
+/// Splits a BCS stream into the BCS values of its items.
+fn split_length_prefixed(mut bytes: &[u8]) -> Vec<&[u8]> {
+    let mut items = vec![];
+    while !bytes.is_empty() {
+        let mut len = 0usize;
+        let mut shift = 0;
+        loop {
+            let byte = bytes[0];
+            bytes = &bytes[1..];
+            len |= ((byte & 0x7f) as usize) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let (item, rest) = bytes.split_at(len);
+        items.push(item);
+        bytes = rest;
+    }
+    items
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_filtered_by_type() {
+    let mut context = new_test_context(current_function_name!());
+    commit_two_user_transactions(&mut context).await;
+
+    let txns = context.get("/transactions?start=0&type=user").await;
+    assert_eq!(versions_of(&txns), vec!["2", "5"]);
+    assert_eq!(txns[0]["type"], "user_transaction");
+
+    let txns = context
+        .get("/transactions?start=0&type=block_metadata")
+        .await;
+    assert_eq!(versions_of(&txns), vec!["1", "4"]);
+
+    let txns = context
+        .get("/transactions?start=4&type=state_checkpoint")
+        .await;
+    assert_eq!(versions_of(&txns), vec!["6"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_filtered_by_success() {
+    let mut context = new_test_context(current_function_name!());
+    commit_two_user_transactions(&mut context).await;
+
+    let txns = context
+        .get("/transactions?start=0&type=user&success=true")
+        .await;
+    assert_eq!(versions_of(&txns), vec!["2", "5"]);
+
+    let txns = context.get("/transactions?start=0&success=false").await;
+    assert!(versions_of(&txns).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_filtered_by_sender() {
+    let mut context = new_test_context(current_function_name!());
+    commit_two_user_transactions(&mut context).await;
+    let root_address = context.root_account().address().to_hex_literal();
+
+    let txns = context
+        .get(&format!("/transactions?start=0&sender={}", root_address))
+        .await;
+    assert_eq!(versions_of(&txns), vec!["2", "5"]);
+
+    let txns = context
+        .get(&format!("/transactions?start=3&sender={}", root_address))
+        .await;
+    assert_eq!(versions_of(&txns), vec!["5"]);
+
+    let txns = context
+        .get(&format!(
+            "/transactions?start=0&sender={}&limit=1",
+            root_address
+        ))
+        .await;
+    assert_eq!(versions_of(&txns), vec!["2"]);
+
+    // Only user transactions have a sender.
+    let txns = context
+        .get(&format!(
+            "/transactions?start=0&sender={}&type=block_metadata",
+            root_address
+        ))
+        .await;
+    assert!(versions_of(&txns).is_empty());
+
+    let account = context.gen_account();
+    let txns = context
+        .get(&format!(
+            "/transactions?start=0&sender={}",
+            account.address().to_hex_literal()
+        ))
+        .await;
+    assert!(versions_of(&txns).is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_filtered_returns_cursor_when_scan_limit_reached() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_transaction_filter_scan = 2;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    commit_two_user_transactions(&mut context).await;
+
+    // Versions 0 and 1 are scanned and neither matches.
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=0&type=user")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(2).to_string().as_str()
+    );
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(versions_of(&txns).is_empty());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=2&type=user")),
+        )
+        .await;
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(4).to_string().as_str()
+    );
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(versions_of(&txns), vec!["2"]);
+
+    // Once the scan reaches the latest version there is nothing to continue from.
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&context.prepend_path("/transactions?start=5&type=user")),
+        )
+        .await;
+    assert!(resp.headers().get("X-Aptos-Cursor").is_none());
+    let txns: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(versions_of(&txns), vec!["5"]);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_transactions_output_user_transaction_with_script_function_payload() {
     let mut context = new_test_context(current_function_name!());
@@ -213,31 +588,507 @@ async fn test_get_transactions_output_user_transaction_with_write_set_payload()
             vec![],
         )),
     );
-    context.commit_block(&vec![txn.clone()]).await;
-
-    let txns = context.get("/transactions?start=2").await;
-    assert_eq!(1, txns.as_array().unwrap().len());
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txns = context.get("/transactions?start=2").await;
+    assert_eq!(1, txns.as_array().unwrap().len());
+
+    context.check_golden_output(txns);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_bcs_format_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let body = bcs::to_bytes(&txn).unwrap();
+    let resp = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", body)
+        .await;
+    context.check_golden_output(resp.clone());
+
+    // ensure ed25519 sig txn can be submitted into mempool by JSON format
+    context
+        .expect_status_code(202)
+        .post("/transactions", resp)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_bcs_format_transaction_with_trailing_bytes() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let mut body = bcs::to_bytes(&txn).unwrap();
+    body.push(0);
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", body)
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+    assert!(resp["message"].as_str().unwrap().contains("trailing bytes"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_bcs_format_transaction_for_another_chain() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = root_account.sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .with_chain_id(ChainId::new(99))
+            .create_user_account(account.public_key()),
+    );
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "chain_id_mismatch");
+    assert_eq!(resp["details"]["transaction_chain_id"], "99");
+    assert_eq!(
+        resp["details"]["node_chain_id"],
+        context.context.chain_id().id().to_string()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_transaction_rejected_by_submission_filter() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    for submission_filter in [
+        SubmissionFilterConfig {
+            denied_senders: vec![txn.sender()],
+            ..SubmissionFilterConfig::default()
+        },
+        SubmissionFilterConfig {
+            denied_functions: vec!["0x1::account".to_string()],
+            ..SubmissionFilterConfig::default()
+        },
+        SubmissionFilterConfig {
+            denied_functions: vec!["0x1::account::create_account".to_string()],
+            ..SubmissionFilterConfig::default()
+        },
+        SubmissionFilterConfig {
+            max_payload_size_bytes: Some(1),
+            ..SubmissionFilterConfig::default()
+        },
+    ] {
+        context
+            .context
+            .update_submission_filter(submission_filter)
+            .await
+            .unwrap();
+        let resp = context
+            .expect_status_code(400)
+            .post_bcs_txn("/transactions", &body)
+            .await;
+        assert_eq!(resp["error_code"], "transaction_rejected_by_filter");
+    }
+
+    // Invalid rules are rejected, keeping the current ones.
+    assert!(context
+        .context
+        .update_submission_filter(SubmissionFilterConfig {
+            denied_functions: vec!["account::create_account".to_string()],
+            ..SubmissionFilterConfig::default()
+        })
+        .await
+        .is_err());
+    context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &body)
+        .await;
+
+    context
+        .context
+        .update_submission_filter(SubmissionFilterConfig::default())
+        .await
+        .unwrap();
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", &body)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_transaction_too_many_pending_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root = context.root_account();
+    let capacity_per_user = context.context.mempool_capacity_per_user();
+    for _ in 0..capacity_per_user {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root, &account);
+        context
+            .expect_status_code(202)
+            .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+            .await;
+    }
+
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root, &account);
+    let resp = context
+        .expect_status_code(429)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "too_many_pending_transactions");
+    assert_eq!(resp["retriable"], true);
+    let details = &resp["details"];
+    assert_eq!(details["pending_txns"], capacity_per_user.to_string());
+    assert_eq!(details["max_pending_txns"], capacity_per_user.to_string());
+    assert_eq!(details["mempool_size"], capacity_per_user.to_string());
+    assert!(details["mempool_capacity"].is_string());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_state_diff() {
+    let mut context = new_test_context(current_function_name!());
+    let root = context.root_account();
+    let txn = context.create_invalid_signature_transaction();
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", body.clone())
+        .await;
+    assert!(resp[0]["state_diff"].is_null());
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate?include_state_diff=true", body)
+        .await;
+    let account_path = format!(
+        "{}/resource/0x1::account::Account",
+        root.address().to_hex_literal()
+    );
+    let account_diff = resp[0]["state_diff"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|diff| diff["path"] == account_path.as_str())
+        .unwrap_or_else(|| panic!("no diff of {} in {}", account_path, pretty(&resp)));
+    assert_eq!(account_diff["change"], "modified");
+    assert_eq!(
+        account_diff["before"]["sequence_number"],
+        root.sequence_number().to_string()
+    );
+    assert_eq!(
+        account_diff["after"]["sequence_number"],
+        (root.sequence_number() + 1).to_string()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_storage_fee() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    // Storage isn't charged for until the network upgrades to version 5.
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", body.clone())
+        .await;
+    assert!(resp[0]["storage_fee"].is_null(), "{}", pretty(&resp));
+
+    let resp = context
+        .post_bcs_txn(
+            "/transactions/simulate?gas_feature_version=5&gas_schedule_overrides=\
+             storage.per_item_create=0,storage.per_byte_create=0,\
+             storage.per_item_write=0,storage.per_byte_write=0,\
+             storage.per_item_refund=0,storage.per_byte_refund=0",
+            body,
+        )
+        .await;
+    let storage_fee = &resp[0]["storage_fee"];
+    // The transfer only overwrites the coin store of the sender.
+    assert_eq!(storage_fee["new_items"], "0", "{}", pretty(&resp));
+    assert_ne!(storage_fee["modified_items"], "0");
+    assert_ne!(storage_fee["modified_bytes"], "0");
+    assert_eq!(storage_fee["deleted_items"], "0");
+    assert_eq!(storage_fee["freed_bytes"], "0");
+    assert_eq!(storage_fee["charge"], "0");
+    assert_eq!(storage_fee["refund"], "0");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_cached() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", body.clone())
+        .await;
+    let cached = context
+        .post_bcs_txn("/transactions/simulate", body.clone())
+        .await;
+    assert_eq!(resp, cached);
+
+    let bypassed = context
+        .post_bcs_txn("/transactions/simulate?bypass_cache=true", body)
+        .await;
+    assert_eq!(resp, bypassed);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_gas_overrides() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+    let body = bcs::to_bytes(&txn).unwrap();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", body.clone())
+        .await;
+    let gas_used: u64 = resp[0]["gas_used"].as_str().unwrap().parse().unwrap();
+    assert_eq!(resp[0]["success"], true);
+
+    // The gas is scaled by 1000, so this charges 1000 more gas units.
+    let resp = context
+        .post_bcs_txn(
+            "/transactions/simulate?gas_schedule_overrides=txn.min_transaction_gas_units=1000000",
+            body.clone(),
+        )
+        .await;
+    assert_eq!(resp[0]["success"], true);
+    let overridden_gas_used: u64 = resp[0]["gas_used"].as_str().unwrap().parse().unwrap();
+    assert!(
+        overridden_gas_used > gas_used,
+        "{} <= {}",
+        overridden_gas_used,
+        gas_used
+    );
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate?gas_feature_version=4", body.clone())
+        .await;
+    assert_eq!(resp[0]["success"], true);
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn(
+            "/transactions/simulate?gas_schedule_overrides=txn.unknown=1",
+            body.clone(),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/simulate?gas_feature_version=100", body)
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_precheck_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let root = context.root_account();
+    let factory = context.transaction_factory();
+    let sign = |builder: TransactionBuilder, private_key: &Ed25519PrivateKey| {
+        let txn = builder
+            .build()
+            .sign(private_key, private_key.public_key())
+            .unwrap()
+            .into_inner();
+        bcs::to_bytes(&txn).unwrap()
+    };
+    let root_key = root.private_key().clone();
+
+    // Signed or not, a transaction passing the prologue passes, and so does
+    // one whose sequence number is ahead of the sender's.
+    for sequence_number in [root.sequence_number(), root.sequence_number() + 5] {
+        let body = sign(
+            factory
+                .transfer(root.address(), 1)
+                .sender(root.address())
+                .sequence_number(sequence_number),
+            &root_key,
+        );
+        let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+        assert_eq!(resp["success"], true, "{}", pretty(&resp));
+        assert_eq!(resp["vm_status"], "Executed");
+        assert_eq!(resp["sequence_number"]["passed"], true);
+    }
+    let body = bcs::to_bytes(&context.create_invalid_signature_transaction()).unwrap();
+    let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+    assert_eq!(resp["success"], true, "{}", pretty(&resp));
+
+    // A key that isn't the sender's fails the auth key check.
+    let other_key = Ed25519PrivateKey::generate_for_testing();
+    let body = sign(
+        factory
+            .transfer(root.address(), 1)
+            .sender(root.address())
+            .sequence_number(root.sequence_number()),
+        &other_key,
+    );
+    let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+    assert_eq!(resp["success"], false);
+    assert_eq!(resp["vm_status"], "INVALID_AUTH_KEY");
+    assert_eq!(resp["auth_key"]["passed"], false, "{}", pretty(&resp));
+    assert!(resp["auth_key"]["reason"].is_string());
+    assert_eq!(resp["sequence_number"]["passed"], true);
+    assert_eq!(resp["gas_balance"]["passed"], true);
+
+    // Each check is reported even though the prologue stops at the first
+    // failure.
+    let body = sign(
+        factory
+            .transfer(root.address(), 1)
+            .sender(root.address())
+            .sequence_number(root.sequence_number())
+            .gas_unit_price(u64::MAX),
+        &other_key,
+    );
+    let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+    assert_eq!(resp["success"], false);
+    assert_eq!(resp["auth_key"]["passed"], false, "{}", pretty(&resp));
+    assert_eq!(resp["gas_balance"]["passed"], false);
+    assert!(resp["gas_balance"]["reason"].is_string());
+
+    // A sender without an account fails every check.
+    let account = context.gen_account();
+    let body = sign(
+        factory
+            .transfer(root.address(), 1)
+            .sender(account.address())
+            .sequence_number(0),
+        account.private_key(),
+    );
+    let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+    assert_eq!(resp["vm_status"], "SENDING_ACCOUNT_DOES_NOT_EXIST");
+    for check in ["auth_key", "sequence_number", "gas_balance"] {
+        assert_eq!(resp[check]["passed"], false, "{}", pretty(&resp));
+    }
+
+    // Once a transaction of the sender is committed, its sequence number is
+    // too old.
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+    let body = sign(
+        factory
+            .transfer(root.address(), 1)
+            .sender(root.address())
+            .sequence_number(root.sequence_number()),
+        &root_key,
+    );
+    let resp = context.post_bcs_txn("/transactions/precheck", body).await;
+    assert_eq!(resp["vm_status"], "SEQUENCE_NUMBER_TOO_OLD");
+    assert_eq!(
+        resp["sequence_number"]["passed"],
+        false,
+        "{}",
+        pretty(&resp)
+    );
+    assert_eq!(resp["sequence_number"]["expected"], ">= 1");
+    assert_eq!(resp["sequence_number"]["actual"], "0");
+}
 
-    context.check_golden_output(txns);
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_bcs_format_transaction_too_large() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.content_length_limit = Some(64);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let req = warp::test::request()
+        .method("POST")
+        .path(&build_path(""))
+        .header("content-type", "application/x.aptos.signed_transaction+bcs")
+        .body(bcs::to_bytes(&txn).unwrap());
+    let resp = context.reply(req).await;
+    assert_eq!(resp.status(), 413);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn test_post_bcs_format_transaction() {
+async fn test_post_chunked_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
     let account = context.gen_account();
     let txn = context.create_user_account(&account);
-    let body = bcs::to_bytes(&txn).unwrap();
-    let resp = context
-        .expect_status_code(202)
-        .post_bcs_txn("/transactions", body)
-        .await;
-    context.check_golden_output(resp.clone());
+    let status = post_chunked(
+        &context,
+        &build_path(""),
+        "application/x.aptos.signed_transaction+bcs",
+        &bcs::to_bytes(&txn).unwrap(),
+    )
+    .await;
+    assert_eq!(status, 202);
+}
 
-    // ensure ed25519 sig txn can be submitted into mempool by JSON format
-    context
-        .expect_status_code(202)
-        .post("/transactions", resp)
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_chunked_bcs_format_transaction_too_large() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.content_length_limit = Some(64);
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    // Without a Content-Length, the limit is only enforced while reading the body.
+    let status = post_chunked(
+        &context,
+        &build_path(""),
+        "application/x.aptos.signed_transaction+bcs",
+        &bcs::to_bytes(&txn).unwrap(),
+    )
+    .await;
+    assert_eq!(status, 413);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_encode_submission_accepts_numbers_and_unprefixed_addresses() {
+    let mut context = new_test_context(current_function_name!());
+    let payload = json!({
+        "type": "script_function_payload",
+        "function": "0x1::account::create_account",
+        "type_arguments": [],
+        "arguments": ["0x1234567"]
+    });
+    let body = json!({
+        "sender": "0xdd",
+        "sequence_number": "0",
+        "gas_unit_price": "1",
+        "max_gas_amount": "1000000",
+        "expiration_timestamp_secs": "9991638487317",
+        "payload": payload,
+    });
+    let expected = context.post("/transactions/encode_submission", body).await;
+
+    let mut payload = payload;
+    payload["arguments"] = json!(["1234567"]);
+    let body = json!({
+        "sender": "dd",
+        "sequence_number": 0,
+        "gas_unit_price": 1,
+        "max_gas_amount": 1000000,
+        "expiration_timestamp_secs": 9991638487317u64,
+        "payload": payload,
+    });
+    let resp = context.post("/transactions/encode_submission", body).await;
+    assert_eq!(resp, expected);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_encode_submission_reports_invalid_field() {
+    let mut context = new_test_context(current_function_name!());
+    let body = json!({
+        "sender": "0xdd",
+        "sequence_number": "0",
+        "gas_unit_price": "1",
+        "max_gas_amount": "abc",
+        "expiration_timestamp_secs": "9991638487317",
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::account::create_account",
+            "type_arguments": [],
+            "arguments": ["0x1234567"]
+        },
+    });
+    let resp = context
+        .expect_status_code(400)
+        .post("/transactions/encode_submission", body)
         .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+    assert_eq!(resp["details"]["field"], "max_gas_amount");
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -263,6 +1114,78 @@ async fn test_post_invalid_signature_transaction() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_invalid_multi_ed25519_signature_transaction() {
+    let context = new_test_context(current_function_name!());
+    let private_keys: Vec<Ed25519PrivateKey> = (0..3)
+        .map(|_| Ed25519PrivateKey::generate_for_testing())
+        .collect();
+    let public_key =
+        MultiEd25519PublicKey::new(private_keys.iter().map(|key| key.public_key()).collect(), 2)
+            .unwrap();
+    let auth_key = AuthenticationKey::multi_ed25519(&public_key);
+
+    let raw_txn = context
+        .transaction_factory()
+        .transfer(context.root_account().address(), 1)
+        .sender(auth_key.derived_address())
+        .sequence_number(0)
+        .build();
+    // Key 2's signature is made with key 1, so only key 2 should be reported.
+    let signature = MultiEd25519Signature::new(vec![
+        (private_keys[0].sign(&raw_txn), 0),
+        (private_keys[1].sign(&raw_txn), 2),
+    ])
+    .unwrap();
+    let txn = SignedTransaction::new_multisig(raw_txn, public_key, signature);
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "invalid_signature");
+    assert_json(
+        resp["details"].clone(),
+        json!({
+            "remediation": "Sign the BCS encoded signing message of the transaction, which can be built with the encode_submission endpoint",
+            "signer.0.address": auth_key.derived_address().to_hex_literal(),
+            "signer.0.failed_public_keys": "2",
+            "signer.0.reason": "signatures of these public keys do not verify against the signing message",
+            "signer.0.role": "sender",
+            "signer.0.scheme": "multi_ed25519",
+        }),
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_post_transaction_with_old_sequence_number() {
+    let mut context = new_test_context(current_function_name!());
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let txn1 = context.create_user_account(&account1);
+    context.commit_block(&vec![txn1]).await;
+
+    // The root account has already used sequence number 0.
+    let txn2 = context.create_user_account(&account2);
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn2).unwrap())
+        .await;
+    assert_eq!(resp["error_code"], "invalid_transaction");
+    assert_json(
+        resp["details"].clone(),
+        json!({
+            "actual": "0",
+            "expected": ">= 1",
+            "field": "sequence_number",
+            "remediation": "Use the current sequence number of the sender account and sign the transaction again",
+            "vm_status": "SEQUENCE_NUMBER_TOO_OLD",
+            "vm_status_category": "validation",
+            "vm_status_code": "3",
+        }),
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_post_transaction_rejected_by_mempool() {
     let mut context = new_test_context(current_function_name!());
@@ -476,6 +1399,107 @@ async fn test_get_transaction_by_version() {
     assert_json(resp, txns[0].clone())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_with_epoch_change() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    // Genesis starts epoch 1 with the whole validator set.
+    let resp = context.get("/transactions/by_version/0").await;
+    let validator_set = context.get("/validators?ledger_version=0").await;
+    let epoch_change = &resp["epoch_change"];
+    assert_eq!(epoch_change["new_epoch"], "1", "{}", pretty(&resp));
+    assert_eq!(
+        epoch_change["validators_added"],
+        validator_set["active_validators"]
+    );
+    assert_eq!(epoch_change["validators_removed"], json!([]));
+    assert_eq!(epoch_change["voting_power_changed"], json!([]));
+    assert_eq!(
+        epoch_change["total_voting_power"],
+        validator_set["total_voting_power"]
+    );
+
+    let resp = context.get("/transactions/by_version/2").await;
+    assert!(resp.get("epoch_change").is_none(), "{}", pretty(&resp));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_output_by_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txn = context.get("/transactions/by_version/2").await;
+    let output = context.get("/transactions/2/outputs").await;
+    assert_eq!(output["version"], txn["version"]);
+    assert_eq!(output["hash"], txn["hash"]);
+    assert_eq!(output["gas_used"], txn["gas_used"]);
+    assert_eq!(output["success"], txn["success"]);
+    assert_eq!(output["vm_status"], txn["vm_status"]);
+    assert_eq!(output["changes"], txn["changes"]);
+    assert_eq!(output["events"], txn["events"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_output_by_version_not_found() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(404)
+        .get("/transactions/10000/outputs")
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_outputs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txns = context.get("/transactions?start=1&limit=3").await;
+    let outputs = context.get("/transactions/outputs?start=1&limit=3").await;
+    let txns = txns.as_array().unwrap();
+    let outputs = outputs.as_array().unwrap();
+    assert_eq!(txns.len(), outputs.len());
+    for (txn, output) in txns.iter().zip(outputs.iter()) {
+        assert_eq!(output["version"], txn["version"]);
+        assert_eq!(output["hash"], txn["hash"]);
+        assert_eq!(output["changes"], txn["changes"]);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_outputs_as_bcs_truncated_by_max_response_size() {
+    let mut node_config = NodeConfig::default();
+    // Far smaller than any transaction output, so only a single one fits.
+    node_config.api.max_response_size_bytes = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .header("Accept", "application/x-bcs")
+                .path(&context.prepend_path("/transactions/outputs?start=1&limit=3")),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["X-Aptos-Cursor"],
+        Cursor::version(2).to_string().as_str()
+    );
+    let outputs: Vec<TransactionOutput> = bcs::from_bytes(resp.body()).unwrap();
+    assert_eq!(outputs.len(), 1);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_pending_transaction_by_hash() {
     let mut context = new_test_context(current_function_name!());
@@ -511,6 +1535,94 @@ async fn test_get_pending_transaction_by_hash() {
     context.check_golden_output(not_found);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_evicted_transaction_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let sign = |gas_unit_price| {
+        context.root_account().sign_with_transaction_builder(
+            context
+                .transaction_factory()
+                .create_user_account(account.public_key())
+                .expiration_timestamp_secs(u64::MAX)
+                .gas_unit_price(gas_unit_price),
+        )
+    };
+    let txn = sign(1);
+    let replacement = sign(2);
+    for txn in [&txn, &replacement] {
+        context
+            .expect_status_code(202)
+            .post_bcs_txn("/transactions", bcs::to_bytes(txn).unwrap())
+            .await;
+    }
+
+    // Mempool replaced the transaction with the one paying more for gas.
+    let resp = context
+        .expect_status_code(404)
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            txn.committed_hash().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["error_code"], "transaction_evicted");
+    assert_eq!(
+        resp["details"]["eviction_reason"],
+        "replaced_by_higher_gas_price"
+    );
+    let resp = context
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            replacement.committed_hash().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["type"], "pending_transaction");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_transaction_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txns = context.get("/transactions?start=2&limit=1").await;
+    let resp = context
+        .get(&format!(
+            "/transactions/wait_by_hash/{}",
+            txns[0]["hash"].as_str().unwrap()
+        ))
+        .await;
+    assert_json(resp, txns[0].clone());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_wait_transaction_by_hash_returns_pending_transaction_on_timeout() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.wait_by_hash_timeout_ms = 100;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let txn = context
+        .get(&format!(
+            "/transactions/wait_by_hash/{}",
+            pending_txn["hash"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(txn["type"], "pending_transaction");
+    assert_eq!(txn["hash"], pending_txn["hash"]);
+
+    context
+        .expect_status_code(404)
+        .get("/transactions/wait_by_hash/0xdadfeddcca7cb6396c735e9094c76c6e4e9cb3e3ef814730693aed59bd87b31d")
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_signing_message_with_script_function_payload() {
     let mut context = new_test_context(current_function_name!());
@@ -527,6 +1639,65 @@ async fn test_signing_message_with_script_function_payload() {
     test_signing_message_with_payload(context, txn, payload).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_signing_messages_with_secondary_signers() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let secondary = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let sender = context.root_account();
+    let body = json!({
+        "sender": sender.address().to_hex_literal(),
+        "sequence_number": sender.sequence_number().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::account::create_account",
+            "type_arguments": [],
+            "arguments": [
+                account.address().to_hex_literal(),
+            ]
+        },
+        "secondary_signers": [secondary.address().to_hex_literal()],
+    });
+
+    let resp = context
+        .post("/transactions/encode_submission", body.clone())
+        .await;
+    let signing_msg = context
+        .api_specific_config
+        .unwrap_signing_message_response(resp);
+    let expected_msg = format!(
+        "0x{}",
+        hex::encode(signing_message(&RawTransactionWithData::new_multi_agent(
+            txn.into_raw_transaction(),
+            vec![secondary.address()],
+        )))
+    );
+    assert_eq!(signing_msg.to_string(), expected_msg);
+
+    let resp = context
+        .post("/transactions/encode_submission/signers", body)
+        .await;
+    assert_json(
+        resp,
+        json!([
+            {
+                "signer": sender.address().to_hex_literal(),
+                "role": "sender",
+                "message": expected_msg,
+            },
+            {
+                "signer": secondary.address().to_hex_literal(),
+                "role": "secondary_signer",
+                "message": expected_msg,
+            },
+        ]),
+    );
+}
+
 // need a correct module payload
 #[ignore]
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -1198,7 +2369,82 @@ fn gen_string(len: u64) -> String {
         .collect()
 }
 
+/// Posts the body straight to the Poem backend in a single chunk, without a
+/// Content-Length, which the warp test requests always set, and returns the
+/// status code of the response.
+async fn post_chunked(context: &TestContext, path: &str, content_type: &str, body: &[u8]) -> u16 {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: {}\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:x}\r\n",
+        path,
+        address,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    request.extend_from_slice(body);
+    request.extend_from_slice(b"\r\n0\r\n\r\n");
+
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    stream.write_all(&request).await.unwrap();
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    // The status line is e.g. "HTTP/1.1 202 Accepted".
+    String::from_utf8_lossy(&response)
+        .split_whitespace()
+        .nth(1)
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
 // For use when not using the methods on `TestContext` directly.
 fn build_path(path: &str) -> String {
     format!("/v1/transactions{}", path)
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_with_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let resp = context
+        .get("/transactions/proof?start_version=1&limit=2")
+        .await;
+    let bytes: HexEncodedBytes = serde_json::from_value(resp).unwrap();
+    let txns: TransactionListWithProof = bcs::from_bytes(bytes.inner()).unwrap();
+    assert_eq!(txns.transactions.len(), 2);
+    assert_eq!(txns.first_transaction_version, Some(1));
+    assert!(txns.events.is_some());
+
+    let ledger_info = context.context.db.get_latest_ledger_info().unwrap();
+    txns.verify(ledger_info.ledger_info(), Some(1)).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_with_proof_clips_to_ledger_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn]).await;
+
+    let resp = context
+        .get("/transactions/proof?start_version=0&limit=25&ledger_version=1")
+        .await;
+    let bytes: HexEncodedBytes = serde_json::from_value(resp).unwrap();
+    let txns: TransactionListWithProof = bcs::from_bytes(bytes.inner()).unwrap();
+    assert_eq!(txns.transactions.len(), 2);
+    assert_eq!(txns.first_transaction_version, Some(0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_with_proof_ledger_version_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/transactions/proof?start_version=0&ledger_version=1000000")
+        .await;
+    assert_eq!(resp["error_code"], "version_not_found");
+}
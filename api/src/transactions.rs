@@ -5,40 +5,67 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::accept_type::AcceptType;
 use crate::bcs_payload::Bcs;
+use crate::bcs_stream::{bcs_stream, BcsStreamSender};
 use crate::context::Context;
 use crate::failpoint::fail_point_poem;
+use crate::json_payload::JsonWithPath;
+use crate::json_stream::{json_stream, JsonStreamSender};
+use crate::metrics::SIMULATION_CACHE;
 use crate::page::Page;
 use crate::response::{
-    AptosErrorResponse, BadRequestError, BasicError, BasicErrorWith404, BasicResponse,
-    BasicResponseStatus, BasicResult, BasicResultWith404, InsufficientStorageError, InternalError,
-    NotFoundError,
+    build_not_found, AptosErrorResponse, BadRequestError, BasicError, BasicErrorWith404,
+    BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404, InsufficientStorageError,
+    InternalError, NotFoundError, TooManyRequestsError,
 };
+use crate::signature::{validate_chain_id, validate_signatures};
+use crate::simulation_cache::SimulationKey;
+use crate::vm_status::vm_status_error;
 use crate::ApiTags;
 use crate::{generate_error_response, generate_success_response};
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    Address, AptosErrorCode, AsConverter, EncodeSubmissionRequest, HashValue, HexEncodedBytes,
-    LedgerInfo, PendingTransaction, SubmitTransactionRequest, Transaction, TransactionData,
-    TransactionOnChainData, UserTransaction, U64,
+    Address, AptosErrorCode, Cursor, EncodeSubmissionRequest, GasScheduleOverrides, HashValue,
+    HexEncodedBytes, LedgerInfo, MoveConverter, OnChainTransactionOutput, PendingTransaction,
+    PrecheckResult, SignerSigningMessage, StorageFee, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionId, TransactionOnChainData, TransactionPrecheck,
+    TransactionSignerRole, TransactionTypeFilter, UserTransaction, U64,
 };
 use aptos_crypto::signing_message;
+use aptos_gas::{AptosGasParameters, ToOnChainGasSchedule};
+use aptos_logger::warn;
+use aptos_state_view::StateView;
+use aptos_types::access_path::AccessPath;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::account_config::{AccountResource, CoinStoreResource};
 use aptos_types::mempool_status::MempoolStatusCode;
+use aptos_types::on_chain_config::{Version as FeatureVersion, APTOS_MAX_KNOWN_VERSION};
+use aptos_types::state_store::state_key::StateKey;
 use aptos_types::transaction::{
-    ExecutionStatus, RawTransaction, RawTransactionWithData, SignedTransaction, TransactionStatus,
+    ExecutionStatus, RawTransaction, RawTransactionWithData, SignedTransaction,
+    TransactionInfo as RawTransactionInfo, TransactionOutput, TransactionStatus, Version,
 };
+use aptos_vm::move_vm_ext::MoveResolverExt;
 use aptos_vm::AptosVM;
+use futures::future::try_join_all;
+use move_deps::move_core_types::language_storage::ResourceKey;
+use move_deps::move_core_types::move_resource::MoveResource;
 use poem_openapi::param::{Path, Query};
-use poem_openapi::payload::Json;
 use poem_openapi::{ApiRequest, OpenApi};
+use std::cmp::min;
+use std::collections::BTreeSet;
+use storage_interface::{TransactionFilter, TransactionType};
+use tracing::info_span;
 
 generate_success_response!(SubmitTransactionResponse, (202, Accepted));
 generate_error_response!(
     SubmitTransactionError,
     (400, BadRequest),
     (413, PayloadTooLarge),
+    (429, TooManyRequests),
     (500, Internal),
     (507, InsufficientStorage)
 );
@@ -46,6 +73,46 @@ generate_error_response!(
 type SubmitTransactionResult<T> =
     poem::Result<SubmitTransactionResponse<T>, SubmitTransactionError>;
 
+/// How many transactions a streamed page of transactions is read and sent in
+/// at a time.
+const STREAM_CHUNK_SIZE: u16 = 100;
+
+/// The streams a page of transactions can be sent to, one for each format
+/// streamed pages come in.
+trait TransactionSender: Send + Sized + 'static {
+    fn send(&mut self, txns: &[Transaction]) -> anyhow::Result<()>;
+    fn finish(self) -> anyhow::Result<()>;
+    fn abort(self, error: anyhow::Error);
+}
+
+impl TransactionSender for JsonStreamSender {
+    fn send(&mut self, txns: &[Transaction]) -> anyhow::Result<()> {
+        JsonStreamSender::send(self, txns)
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        JsonStreamSender::finish(self)
+    }
+
+    fn abort(self, error: anyhow::Error) {
+        JsonStreamSender::abort(self, error)
+    }
+}
+
+impl TransactionSender for BcsStreamSender {
+    fn send(&mut self, txns: &[Transaction]) -> anyhow::Result<()> {
+        BcsStreamSender::send(self, txns)
+    }
+
+    fn finish(self) -> anyhow::Result<()> {
+        BcsStreamSender::finish(self)
+    }
+
+    fn abort(self, error: anyhow::Error) {
+        BcsStreamSender::abort(self, error)
+    }
+}
+
 type SimulateTransactionResult<T> = poem::Result<BasicResponse<T>, SubmitTransactionError>;
 
 // TODO: Consider making both content types accept either
@@ -57,7 +124,7 @@ type SimulateTransactionResult<T> = poem::Result<BasicResponse<T>, SubmitTransac
 #[derive(ApiRequest, Debug)]
 pub enum SubmitTransactionPost {
     #[oai(content_type = "application/json")]
-    Json(Json<SubmitTransactionRequest>),
+    Json(JsonWithPath<SubmitTransactionRequest>),
 
     // TODO: Since I don't want to impl all the Poem derives on SignedTransaction,
     // find a way to at least indicate in the spec that it expects a SignedTransaction.
@@ -76,6 +143,23 @@ impl TransactionsApi {
     ///
     /// Get on-chain (meaning, committed) transactions. You may specify from
     /// when you want the transactions and how to include in the response.
+    ///
+    /// The transactions can be filtered by type, by whether they succeeded and
+    /// by sender, which only user transactions have. With any filter set, at
+    /// most a configured number of transactions from `start` are examined for
+    /// matches, so a page may hold fewer than `limit` transactions even though
+    /// more matches follow. The X-Aptos-Cursor header is then set to the
+    /// cursor from which to continue, which is passed back as `cursor`.
+    ///
+    /// Without filters, a `limit` above the maximum page size, up to the
+    /// maximum streamed page size, has the transactions streamed as a JSON
+    /// array with chunked transfer encoding. They are streamed as newline
+    /// delimited JSON, one transaction per line, for any limit if the Accept
+    /// header is application/x-ndjson, and as BCS values each prefixed with
+    /// its length as a ULEB128 if it is
+    /// `application/x-bcs; stream=length-prefixed`. Should reading the
+    /// transactions fail midway, the stream is aborted, leaving the body
+    /// truncated.
     #[oai(
         path = "/transactions",
         method = "get",
@@ -87,10 +171,50 @@ impl TransactionsApi {
         accept_type: AcceptType,
         start: Query<Option<U64>>,
         limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
+        #[oai(name = "type")] transaction_type: Query<Option<TransactionTypeFilter>>,
+        success: Query<Option<bool>>,
+        sender: Query<Option<Address>>,
     ) -> BasicResultWith404<Vec<Transaction>> {
         fail_point_poem("endppoint_get_transactions")?;
-        let page = Page::new(start.0.map(|v| v.0), limit.0);
-        self.list(&accept_type, page)
+        // Pages too large to build in memory are streamed, as are those asked
+        // for as NDJSON or a BCS stream. Only unfiltered pages are streamed,
+        // since filtered ones are bounded by the filter scan anyway.
+        let streamed = match accept_type {
+            AcceptType::NdJson | AcceptType::BcsStream => true,
+            AcceptType::Json => limit.0.unwrap_or_default() > self.context.max_page_size(),
+            AcceptType::Bcs => false,
+        } && transaction_type.0.is_none()
+            && success.0.is_none()
+            && sender.0.is_none();
+        let max_page_size = if streamed {
+            self.context.max_streamed_page_size()
+        } else {
+            self.context.max_page_size()
+        };
+        let page = Page::new(start.0.map(|v| v.0), limit.0, max_page_size)
+            .with_cursor(cursor.0, Cursor::into_version)?;
+        if streamed {
+            return self.stream(&accept_type, page);
+        }
+        let filter = TransactionFilter {
+            transaction_type: transaction_type
+                .0
+                .map(|transaction_type| match transaction_type {
+                    TransactionTypeFilter::User => TransactionType::User,
+                    TransactionTypeFilter::BlockMetadata => TransactionType::BlockMetadata,
+                    TransactionTypeFilter::StateCheckpoint => TransactionType::StateCheckpoint,
+                }),
+            success: success.0,
+            sender: sender.0.map(|address| address.into()),
+        };
+        if filter.is_empty() {
+            self.list(&accept_type, page).await
+        } else {
+            self.list_filtered(&accept_type, page, filter).await
+        }
     }
 
     /// Get transaction by hash
@@ -124,6 +248,32 @@ impl TransactionsApi {
             .await
     }
 
+    /// Wait for transaction by hash
+    ///
+    /// Same as /transactions/by_hash, but if the transaction has not been
+    /// committed yet, the server waits for it to be before responding. If the
+    /// transaction is still not committed when the wait times out, this returns
+    /// the same response /transactions/by_hash would, i.e. the pending
+    /// transaction if it is in mempool or a 404 otherwise.
+    ///
+    /// Use this instead of polling /transactions/by_hash, it saves round trips
+    /// and returns as soon as the transaction is committed.
+    #[oai(
+        path = "/transactions/wait_by_hash/:txn_hash",
+        method = "get",
+        operation_id = "wait_transaction_by_hash",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn wait_transaction_by_hash(
+        &self,
+        accept_type: AcceptType,
+        txn_hash: Path<HashValue>,
+    ) -> BasicResultWith404<Transaction> {
+        fail_point_poem("endpoint_wait_transaction_by_hash")?;
+        self.wait_transaction_by_hash_inner(&accept_type, txn_hash.0)
+            .await
+    }
+
     /// Get transaction by version
     ///
     /// todo
@@ -143,6 +293,95 @@ impl TransactionsApi {
             .await
     }
 
+    /// Get transaction outputs
+    ///
+    /// Get the outputs of on-chain (meaning, committed) transactions: the
+    /// changes each one made to state, the events it emitted and the gas it
+    /// used. As with /transactions, you may specify the start version and how
+    /// many outputs to include in the response.
+    ///
+    /// When requesting BCS, the response is the list of TransactionOutputs
+    /// exactly as they are stored, see TransactionOutput in
+    /// types/src/transaction/mod.rs.
+    #[oai(
+        path = "/transactions/outputs",
+        method = "get",
+        operation_id = "get_transaction_outputs",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transaction_outputs(
+        &self,
+        accept_type: AcceptType,
+        start: Query<Option<U64>>,
+        limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
+    ) -> BasicResultWith404<Vec<OnChainTransactionOutput>> {
+        fail_point_poem("endpoint_get_transaction_outputs")?;
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_version)?;
+        self.list_outputs(&accept_type, page).await
+    }
+
+    /// Get transaction output by version
+    ///
+    /// Get the output of the on-chain transaction at the given version: the
+    /// changes it made to state, the events it emitted and the gas it used.
+    ///
+    /// When requesting BCS, the response is the TransactionOutput exactly as
+    /// it is stored, see TransactionOutput in types/src/transaction/mod.rs.
+    #[oai(
+        path = "/transactions/:txn_version/outputs",
+        method = "get",
+        operation_id = "get_transaction_output_by_version",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transaction_output_by_version(
+        &self,
+        accept_type: AcceptType,
+        txn_version: Path<U64>,
+    ) -> BasicResultWith404<OnChainTransactionOutput> {
+        fail_point_poem("endpoint_get_transaction_output_by_version")?;
+        self.get_output_by_version(&accept_type, txn_version.0)
+            .await
+    }
+
+    /// Get transactions with proof
+    ///
+    /// Get a range of on-chain transactions, along with their events, their
+    /// transaction infos and a proof that they are included in the transaction
+    /// accumulator at the given ledger version, as a BCS encoded
+    /// TransactionListWithProof, see types/src/transaction/mod.rs. If the
+    /// ledger version is not specified, the latest ledger version is used.
+    ///
+    /// This lets auditors verify the inclusion of transactions in bulk against
+    /// the accumulator root hash of a ledger info they trust, the same way
+    /// state sync does. With a BCS Accept header the response body is the
+    /// TransactionListWithProof itself, otherwise its BCS bytes are hex encoded.
+    #[oai(
+        path = "/transactions/proof",
+        method = "get",
+        operation_id = "get_transactions_with_proof",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transactions_with_proof(
+        &self,
+        accept_type: AcceptType,
+        start_version: Query<U64>,
+        limit: Query<Option<u16>>,
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        fail_point_poem("endpoint_get_transactions_with_proof")?;
+        let page = Page::new(
+            Some(start_version.0 .0),
+            limit.0,
+            self.context.max_page_size(),
+        );
+        self.list_with_proof(&accept_type, page, ledger_version.0)
+            .await
+    }
+
     /// Get account transactions
     ///
     /// todo
@@ -159,10 +398,14 @@ impl TransactionsApi {
         address: Path<Address>,
         start: Query<Option<U64>>,
         limit: Query<Option<u16>>,
+        /// Cursor from which to continue, as returned in the X-Aptos-Cursor
+        /// header of the previous page, instead of `start`
+        cursor: Query<Option<Cursor>>,
     ) -> BasicResultWith404<Vec<Transaction>> {
         fail_point_poem("endpoint_get_accounts_transactions")?;
-        let page = Page::new(start.0.map(|v| v.0), limit.0);
-        self.list_by_account(&accept_type, page, address.0)
+        let page = Page::new(start.0.map(|v| v.0), limit.0, self.context.max_page_size())
+            .with_cursor(cursor.0, Cursor::into_sequence_number)?;
+        self.list_by_account(&accept_type, page, address.0).await
     }
 
     /// Submit transaction
@@ -182,6 +425,13 @@ impl TransactionsApi {
     ///
     /// To submit a transaction as BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    ///
+    /// If any signature of the transaction does not verify, this returns a 400
+    /// before the transaction reaches mempool, with details of which signers
+    /// and which of their public keys failed.
+    ///
+    /// If the transaction fails validation, the details of the error include
+    /// the VM status, its category, the offending field and a suggested fix.
     // TODO: Point to examples of both of these flows, in multiple languages.
     #[oai(
         path = "/transactions",
@@ -196,6 +446,7 @@ impl TransactionsApi {
     ) -> SubmitTransactionResult<PendingTransaction> {
         fail_point_poem("endpoint_submit_transaction")?;
         let signed_transaction = self.get_signed_transaction(data)?;
+        validate_signatures::<SubmitTransactionError>(&signed_transaction)?;
         self.create(&accept_type, signed_transaction).await
     }
 
@@ -207,6 +458,26 @@ impl TransactionsApi {
     ///
     /// To use this endpoint with BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    ///
+    /// If the VM would discard the transaction, e.g. because its sequence
+    /// number is too old, this returns a 400 with the same details as
+    /// submitting it would.
+    ///
+    /// If `include_state_diff` is set, the simulated transaction also
+    /// contains every change it would make to state, keyed by the path of
+    /// the changed state and with the decoded values before and after the
+    /// transaction, so that wallets can show what a transaction changes
+    /// before it is signed.
+    ///
+    /// Results are cached by transaction and state version, so simulating
+    /// the same transaction again before the state changes doesn't run the
+    /// VM again. Set `bypass_cache` to run it regardless.
+    ///
+    /// To estimate the costs of a transaction under upcoming gas changes,
+    /// `gas_schedule_overrides` gives values of gas parameters to charge it
+    /// by instead of those of the on-chain gas schedule, and
+    /// `gas_feature_version` a feature version to run it under instead of
+    /// the on-chain one. Simulations with either are never cached.
     #[oai(
         path = "/transactions/simulate",
         method = "post",
@@ -217,18 +488,59 @@ impl TransactionsApi {
         &self,
         accept_type: AcceptType,
         data: SubmitTransactionPost,
+        include_state_diff: Query<Option<bool>>,
+        bypass_cache: Query<Option<bool>>,
+        gas_schedule_overrides: Query<Option<GasScheduleOverrides>>,
+        gas_feature_version: Query<Option<U64>>,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         fail_point_poem("endpoint_simulate_transaction")?;
         let signed_transaction = self.get_signed_transaction(data)?;
-        self.simulate(&accept_type, signed_transaction).await
+        self.simulate(
+            &accept_type,
+            signed_transaction,
+            include_state_diff.0.unwrap_or_default(),
+            bypass_cache.0.unwrap_or_default(),
+            gas_schedule_overrides.0.unwrap_or_default(),
+            gas_feature_version.0.map(|version| version.0),
+        )
+        .await
+    }
+
+    /// Precheck transaction
+    ///
+    /// Runs a transaction through the checks it goes through on submission,
+    /// without executing it: those of its format and gas parameters, and the
+    /// prologue, which checks that the public key of the signature matches the
+    /// authentication key of the sender account, that the sequence number
+    /// isn't older than that of the account and that the account can pay for
+    /// the maximum gas of the transaction. The signature is not verified, so
+    /// the transaction may be signed or not.
+    ///
+    /// This is much cheaper than simulating the transaction, so wallets can use
+    /// it to check a transaction while it is being built. Failed checks are
+    /// reported in the response with why they failed, rather than as an error.
+    #[oai(
+        path = "/transactions/precheck",
+        method = "post",
+        operation_id = "precheck_transaction",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn precheck_transaction(
+        &self,
+        accept_type: AcceptType,
+        data: SubmitTransactionPost,
+    ) -> SimulateTransactionResult<TransactionPrecheck> {
+        fail_point_poem("endpoint_precheck_transaction")?;
+        let signed_transaction = self.get_signed_transaction(data)?;
+        self.precheck(&accept_type, signed_transaction)
     }
 
     /// Encode submission
     ///
     /// This endpoint accepts an EncodeSubmissionRequest, which internally is a
-    /// UserTransactionRequestInner (and optionally secondary signers) encoded
-    /// as JSON, validates the request format, and then returns that request
-    /// encoded in BCS. The client can then use this to create a transaction
+    /// UserTransactionRequestInner (and optionally secondary signers and a fee
+    /// payer) encoded as JSON, validates the request format, and then returns
+    /// that request encoded in BCS. The client can then use this to create a transaction
     /// signature to be used in a SubmitTransactionRequest, which it then
     /// passes to the /transactions POST endpoint.
     ///
@@ -241,6 +553,8 @@ impl TransactionsApi {
     /// - Decode the hex encoded string in the response to bytes.
     /// - Sign the bytes to create the signature.
     /// - Use that as the signature field in something like Ed25519Signature, which you then use to build a TransactionSignature.
+    ///
+    /// For multi-agent transactions, every signer signs the returned message.
     //
     // TODO: Link an example of how to do this. Use externalDoc.
     #[oai(
@@ -252,16 +566,43 @@ impl TransactionsApi {
     async fn encode_submission(
         &self,
         accept_type: AcceptType,
-        data: Json<EncodeSubmissionRequest>,
+        data: JsonWithPath<EncodeSubmissionRequest>,
         // TODO: Use a new request type that can't return 507 but still returns all the other necessary errors.
     ) -> BasicResult<HexEncodedBytes> {
         fail_point_poem("endpoint_encode_submission")?;
         self.get_signing_message(&accept_type, data.0)
     }
+
+    /// Encode submission for all signers
+    ///
+    /// This endpoint accepts the same EncodeSubmissionRequest as
+    /// /transactions/encode_submission, but returns the signing message for
+    /// every signer of the transaction: the sender, then each secondary signer
+    /// in order. Each entry carries the signer's address and role, so clients
+    /// building multi-agent transactions know which account must produce which
+    /// signature.
+    #[oai(
+        path = "/transactions/encode_submission/signers",
+        method = "post",
+        operation_id = "encode_submission_for_signers",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn encode_submission_for_signers(
+        &self,
+        accept_type: AcceptType,
+        data: JsonWithPath<EncodeSubmissionRequest>,
+    ) -> BasicResult<Vec<SignerSigningMessage>> {
+        fail_point_poem("endpoint_encode_submission_for_signers")?;
+        self.get_signer_signing_messages(&accept_type, data.0)
+    }
 }
 
 impl TransactionsApi {
-    fn list(&self, accept_type: &AcceptType, page: Page) -> BasicResultWith404<Vec<Transaction>> {
+    async fn list(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+    ) -> BasicResultWith404<Vec<Transaction>> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
         let ledger_version = latest_ledger_info.version();
 
@@ -270,19 +611,334 @@ impl TransactionsApi {
         let start_version = page.compute_start(limit, ledger_version)?;
         let data = self
             .context
-            .get_transactions(start_version, limit, ledger_version)
+            .get_transactions_async(start_version, limit, ledger_version)
+            .await
             .context("Failed to read raw transactions from storage")
-            .map_err(BasicErrorWith404::internal)
-            .map_err(|e| e.error_code(AptosErrorCode::InvalidBcsInStorageError))?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+            })?;
 
-        BasicResponse::try_from_rust_value((
+        BasicResponse::try_from_paged_rust_value((
+            self.render_transactions(data)?,
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+            start_version,
+            Cursor::version,
+            self.context.max_response_size(),
+        ))
+    }
+
+    /// Streams the transactions of the page, reading, converting and sending
+    /// them a chunk at a time on a blocking task, so that the memory held for
+    /// the response doesn't grow with the page.
+    fn stream(&self, accept_type: &AcceptType, page: Page) -> BasicResultWith404<Vec<Transaction>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+
+        let limit = page.limit()?;
+        let start_version = page.compute_start(limit, ledger_version)?;
+        let end_version = min(start_version + limit as u64, ledger_version + 1);
+
+        if accept_type == &AcceptType::BcsStream {
+            let (sender, stream) = bcs_stream();
+            self.spawn_send_transactions(sender, start_version, end_version, ledger_version);
+            return Ok(BasicResponse::from((
+                stream,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )));
+        }
+        let (sender, stream) = json_stream(accept_type == &AcceptType::NdJson);
+        self.spawn_send_transactions(sender, start_version, end_version, ledger_version);
+        Ok(BasicResponse::from((
+            stream,
+            &latest_ledger_info,
+            BasicResponseStatus::Ok,
+        )))
+    }
+
+    /// Sends the transactions from `start_version` up to `end_version` on a
+    /// blocking task, then ends the stream, or aborts it if that fails.
+    fn spawn_send_transactions<S: TransactionSender>(
+        &self,
+        mut sender: S,
+        start_version: u64,
+        end_version: u64,
+        ledger_version: u64,
+    ) {
+        let context = self.context.clone();
+        tokio::task::spawn_blocking(move || {
+            let api = TransactionsApi { context };
+            match api.send_transactions(&mut sender, start_version, end_version, ledger_version) {
+                Ok(()) => {
+                    let _ = sender.finish();
+                }
+                Err(err) => {
+                    warn!(
+                        "Aborted streaming transactions from version {}: {:#}",
+                        start_version, err
+                    );
+                    sender.abort(err);
+                }
+            }
+        });
+    }
+
+    /// Reads the transactions from `start_version` up to `end_version` and
+    /// sends them to the stream, a chunk at a time.
+    fn send_transactions(
+        &self,
+        sender: &mut impl TransactionSender,
+        start_version: u64,
+        end_version: u64,
+        ledger_version: u64,
+    ) -> anyhow::Result<()> {
+        // The whole page is converted against the same state.
+        let resolver = self.context.move_resolver()?;
+        let converter = self.context.converter(&resolver);
+        for chunk_start in (start_version..end_version).step_by(STREAM_CHUNK_SIZE as usize) {
+            let chunk_size = min(STREAM_CHUNK_SIZE as u64, end_version - chunk_start) as u16;
+            let data = self
+                .context
+                .get_transactions(chunk_start, chunk_size, ledger_version)
+                .context("Failed to read raw transactions from storage")?;
+            sender.send(&self.convert_transactions(&converter, data)?)?;
+        }
+        Ok(())
+    }
+
+    async fn list_filtered(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        filter: TransactionFilter,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+
+        let limit = page.limit()?;
+        let start_version = page.compute_start(limit, ledger_version)?;
+        let (versions, next_version) = self
+            .context
+            .get_filtered_transaction_versions(start_version, limit, ledger_version, filter)
+            .await
+            .context("Failed to read filtered transactions from storage")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+        let data = try_join_all(versions.iter().map(|version| {
+            self.context
+                .get_transaction_by_version_async(*version, ledger_version)
+        }))
+        .await
+        .context("Failed to read raw transactions from storage")
+        .map_err(|e| {
+            BasicErrorWith404::internal_with_code(e, AptosErrorCode::InvalidBcsInStorageError)
+        })?;
+
+        // The matching versions are not consecutive, so the cursor is worked
+        // out here rather than by try_from_paged_rust_value.
+        BasicResponse::try_from_budgeted_rust_list((
             self.render_transactions(data)?,
             &latest_ledger_info,
             BasicResponseStatus::Ok,
             accept_type,
+            self.context.max_response_size(),
+            |cut_at| match cut_at {
+                Some(count) => Some(Cursor::version(versions[count])),
+                None => (next_version <= ledger_version).then(|| Cursor::version(next_version)),
+            },
         ))
     }
 
+    async fn list_with_proof(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        ledger_version: Option<U64>,
+    ) -> BasicResultWith404<HexEncodedBytes> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = ledger_version
+            .map(|v| v.0)
+            .unwrap_or_else(|| latest_ledger_info.version());
+        if ledger_version > latest_ledger_info.version() {
+            return Err(build_not_found(
+                "ledger",
+                TransactionId::Version(U64::from(ledger_version)),
+                AptosErrorCode::VersionNotFound,
+                latest_ledger_info.version(),
+            ));
+        }
+
+        let limit = page.limit()?;
+        let start_version = page.start(0, ledger_version)?;
+        // The range can't extend past the ledger version the proof is for.
+        let limit = (limit as u64).min(ledger_version - start_version + 1);
+        let txns = self
+            .context
+            .get_transactions_with_proof(start_version, limit, ledger_version)
+            .await
+            .context("Failed to read transactions with proof from storage")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+        let bytes = bcs::to_bytes(&txns)
+            .context("Failed to serialize transactions with proof")
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::BcsSerializationError)
+            })?;
+
+        match accept_type {
+            // Return the list as is, rather than as BCS encoded bytes.
+            AcceptType::Bcs | AcceptType::BcsStream => Ok(BasicResponse::from((
+                Bcs(bytes),
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            ))),
+            AcceptType::Json | AcceptType::NdJson => BasicResponse::try_from_rust_value((
+                HexEncodedBytes::from(bytes),
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+                accept_type,
+            )),
+        }
+    }
+
+    async fn list_outputs(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+    ) -> BasicResultWith404<Vec<OnChainTransactionOutput>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+
+        let limit = page.limit()?;
+        let start_version = page.compute_start(limit, ledger_version)?;
+        let outputs = self
+            .read_outputs(start_version, limit, ledger_version)
+            .await?;
+
+        match accept_type {
+            // The raw outputs are returned as they are, rather than their JSON view.
+            AcceptType::Bcs | AcceptType::BcsStream => BasicResponse::try_from_budgeted_bcs_list((
+                outputs
+                    .into_iter()
+                    .map(|(_, _, output)| output)
+                    .collect::<Vec<TransactionOutput>>(),
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+                accept_type,
+                self.context.max_response_size(),
+                |cut_at| cut_at.map(|count| Cursor::version(start_version + count as u64)),
+            )),
+            AcceptType::Json | AcceptType::NdJson => BasicResponse::try_from_paged_rust_value((
+                self.render_outputs(outputs)?,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+                accept_type,
+                start_version,
+                Cursor::version,
+                self.context.max_response_size(),
+            )),
+        }
+    }
+
+    async fn get_output_by_version(
+        &self,
+        accept_type: &AcceptType,
+        version: U64,
+    ) -> BasicResultWith404<OnChainTransactionOutput> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+        if version.0 > ledger_version {
+            return Err(build_not_found(
+                "Transaction output",
+                TransactionId::Version(version),
+                AptosErrorCode::VersionNotFound,
+                ledger_version,
+            ));
+        }
+
+        let output = self
+            .read_outputs(version.0, 1, ledger_version)
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                build_not_found(
+                    "Transaction output",
+                    TransactionId::Version(version),
+                    AptosErrorCode::TransactionNotFound,
+                    ledger_version,
+                )
+            })?;
+
+        match accept_type {
+            AcceptType::Bcs | AcceptType::BcsStream => {
+                let bytes = bcs::to_bytes(&output.2)
+                    .context("Failed to serialize transaction output")
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::BcsSerializationError,
+                        )
+                    })?;
+                Ok(BasicResponse::from((
+                    Bcs(bytes),
+                    &latest_ledger_info,
+                    BasicResponseStatus::Ok,
+                )))
+            }
+            AcceptType::Json | AcceptType::NdJson => {
+                let output = self.render_outputs(vec![output])?.pop().ok_or_else(|| {
+                    BasicErrorWith404::internal_with_code(
+                        "Failed to render transaction output",
+                        AptosErrorCode::InternalError,
+                    )
+                })?;
+                BasicResponse::try_from_rust_value((
+                    output,
+                    &latest_ledger_info,
+                    BasicResponseStatus::Ok,
+                    accept_type,
+                ))
+            }
+        }
+    }
+
+    async fn read_outputs<E: InternalError>(
+        &self,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(Version, RawTransactionInfo, TransactionOutput)>, E> {
+        self.context
+            .get_transaction_outputs(start_version, limit, ledger_version)
+            .await
+            .context("Failed to read transaction outputs from storage")
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::ReadFromStorageError))
+    }
+
+    fn render_outputs<E: InternalError>(
+        &self,
+        outputs: Vec<(Version, RawTransactionInfo, TransactionOutput)>,
+    ) -> Result<Vec<OnChainTransactionOutput>, E> {
+        if outputs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let resolver = self.context.move_resolver_poem()?;
+        let converter = self.context.converter(&resolver);
+        outputs
+            .into_iter()
+            .map(|(version, info, output)| {
+                converter.try_into_transaction_output(version, &info, output)
+            })
+            .collect::<Result<_, anyhow::Error>>()
+            .context("Failed to convert transaction outputs from storage")
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::InternalError))
+    }
+
     fn render_transactions<E: InternalError>(
         &self,
         data: Vec<TransactionOnChainData>,
@@ -292,9 +948,20 @@ impl TransactionsApi {
         }
 
         let resolver = self.context.move_resolver_poem()?;
-        let converter = resolver.as_converter(self.context.db.clone());
-        let txns: Vec<Transaction> = data
-            .into_iter()
+        let converter = self.context.converter(&resolver);
+        let txns = self
+            .convert_transactions(&converter, data)
+            .map_err(|e| E::internal_with_code(e, AptosErrorCode::InternalError))?;
+
+        Ok(txns)
+    }
+
+    fn convert_transactions<R: MoveResolverExt>(
+        &self,
+        converter: &MoveConverter<R>,
+        data: Vec<TransactionOnChainData>,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        data.into_iter()
             .map(|t| {
                 let version = t.version;
                 let timestamp = self.context.get_block_timestamp(version)?;
@@ -303,9 +970,6 @@ impl TransactionsApi {
             })
             .collect::<Result<_, anyhow::Error>>()
             .context("Failed to convert transaction data from storage")
-            .map_err(E::internal)?;
-
-        Ok(txns)
     }
 
     async fn get_transaction_by_hash_inner(
@@ -318,29 +982,72 @@ impl TransactionsApi {
             .get_by_hash(hash.into(), &ledger_info)
             .await
             .context(format!("Failed to get transaction by hash {}", hash))
-            .map_err(BasicErrorWith404::not_found)?
-            .context(format!("Failed to find transaction with hash: {}", hash))
-            .map_err(BasicErrorWith404::not_found)?;
+            .map_err(|e| {
+                BasicErrorWith404::not_found_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
+        let txn_data = match txn_data {
+            Some(txn_data) => txn_data,
+            None => return Err(self.transaction_not_found_by_hash(hash).await),
+        };
 
         self.get_transaction_inner(accept_type, txn_data, &ledger_info)
             .await
     }
 
+    /// The error for a transaction neither committed nor pending, which says
+    /// so if mempool recently evicted it, as it then won't be committed unless
+    /// it is submitted again.
+    async fn transaction_not_found_by_hash(&self, hash: HashValue) -> BasicErrorWith404 {
+        // Failing to ask mempool doesn't change that the transaction is not found.
+        match self
+            .context
+            .get_transaction_eviction_reason(hash.into())
+            .await
+        {
+            Ok(Some(reason)) => BasicErrorWith404::not_found_with_code(
+                format!(
+                    "Transaction with hash {} was evicted from mempool because {}",
+                    hash, reason
+                ),
+                AptosErrorCode::TransactionEvicted,
+            )
+            .detail("eviction_reason", reason.name()),
+            _ => BasicErrorWith404::not_found_with_code(
+                format!("Failed to find transaction with hash: {}", hash),
+                AptosErrorCode::TransactionNotFound,
+            ),
+        }
+    }
+
     async fn get_transaction_by_version_inner(
         &self,
         accept_type: &AcceptType,
         version: U64,
     ) -> BasicResultWith404<Transaction> {
         let ledger_info = self.context.get_latest_ledger_info()?;
+        let oldest_version = ledger_info.oldest_ledger_version.0;
+        if version.0 < oldest_version {
+            return Err(BasicErrorWith404::not_found_with_code(
+                format!("Transaction at version {} has been pruned", version),
+                AptosErrorCode::VersionPruned,
+            )
+            .aptos_ledger_version(ledger_info.version())
+            .detail("oldest_ledger_version", oldest_version));
+        }
         let txn_data = self
             .get_by_version(version.0, &ledger_info)
+            .await
             .context(format!("Failed to get transaction by version {}", version))
-            .map_err(BasicErrorWith404::not_found)?
+            .map_err(|e| {
+                BasicErrorWith404::not_found_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?
             .context(format!(
                 "Failed to find transaction at version: {}",
                 version
             ))
-            .map_err(BasicErrorWith404::not_found)?;
+            .map_err(|e| {
+                BasicErrorWith404::not_found_with_code(e, AptosErrorCode::TransactionNotFound)
+            })?;
 
         self.get_transaction_inner(accept_type, txn_data, &ledger_info)
             .await
@@ -359,18 +1066,28 @@ impl TransactionsApi {
                     .context
                     .get_block_timestamp(txn.version)
                     .context("Failed to get block timestamp from DB")
-                    .map_err(BasicErrorWith404::internal)?;
-                resolver
-                    .as_converter(self.context.db.clone())
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(
+                            e,
+                            AptosErrorCode::ReadFromStorageError,
+                        )
+                    })?;
+                self.context
+                    .converter(&resolver)
                     .try_into_onchain_transaction(timestamp, txn)
                     .context("Failed to convert on chain transaction to Transaction")
-                    .map_err(BasicErrorWith404::internal)?
+                    .map_err(|e| {
+                        BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                    })?
             }
-            TransactionData::Pending(txn) => resolver
-                .as_converter(self.context.db.clone())
+            TransactionData::Pending(txn) => self
+                .context
+                .converter(&resolver)
                 .try_into_pending_transaction(*txn)
                 .context("Failed to convert on pending transaction to Transaction")
-                .map_err(BasicErrorWith404::internal)?,
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(e, AptosErrorCode::InternalError)
+                })?,
         };
 
         BasicResponse::try_from_rust_value((
@@ -381,7 +1098,7 @@ impl TransactionsApi {
         ))
     }
 
-    fn get_by_version(
+    async fn get_by_version(
         &self,
         version: u64,
         ledger_info: &LedgerInfo,
@@ -391,15 +1108,40 @@ impl TransactionsApi {
         }
         Ok(Some(
             self.context
-                .get_transaction_by_version(version, ledger_info.version())?
+                .get_transaction_by_version_async(version, ledger_info.version())
+                .await?
                 .into(),
         ))
     }
 
+    async fn wait_transaction_by_hash_inner(
+        &self,
+        accept_type: &AcceptType,
+        hash: HashValue,
+    ) -> BasicResultWith404<Transaction> {
+        let deadline = Instant::now() + self.context.wait_by_hash_timeout();
+        // Only the hash index is checked while waiting, the transaction itself
+        // is read once it is committed or we run out of time.
+        loop {
+            let ledger_info = self.context.get_latest_ledger_info()?;
+            let version = self
+                .context
+                .get_transaction_version_by_hash(hash.into(), ledger_info.version())
+                .await
+                .context(format!("Failed to look up transaction by hash {}", hash))
+                .map_err(|e| {
+                    BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+                })?;
+            if version.is_some() || Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(self.context.wait_by_hash_poll_interval()).await;
+        }
+        self.get_transaction_by_hash_inner(accept_type, hash).await
+    }
+
     // This function looks for the transaction by hash in database and then mempool,
     // because the period a transaction stay in the mempool is likely short.
-    // Although the mempool get transation is async, but looking up txn in database is a sync call,
-    // thus we keep it simple and call them in sequence.
     async fn get_by_hash(
         &self,
         hash: aptos_crypto::HashValue,
@@ -407,7 +1149,8 @@ impl TransactionsApi {
     ) -> anyhow::Result<Option<TransactionData>> {
         let from_db = self
             .context
-            .get_transaction_by_hash(hash, ledger_info.version())?;
+            .get_transaction_by_hash(hash, ledger_info.version())
+            .await?;
         Ok(match from_db {
             None => self
                 .context
@@ -418,30 +1161,37 @@ impl TransactionsApi {
         })
     }
 
-    fn list_by_account(
+    async fn list_by_account(
         &self,
         accept_type: &AcceptType,
         page: Page,
         address: Address,
     ) -> BasicResultWith404<Vec<Transaction>> {
         let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let start_sequence_number = page.start(0, u64::MAX)?;
         // TODO: Return more specific errors from within this function.
         let data = self
             .context
             .get_account_transactions(
                 address.into(),
-                page.start(0, u64::MAX)?,
+                start_sequence_number,
                 page.limit()?,
                 latest_ledger_info.version(),
             )
+            .await
             .context("Failed to get account transactions for the given account")
-            .map_err(BasicErrorWith404::internal)?;
+            .map_err(|e| {
+                BasicErrorWith404::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+            })?;
 
-        BasicResponse::try_from_rust_value((
+        BasicResponse::try_from_paged_rust_value((
             self.render_transactions(data)?,
             &latest_ledger_info,
             BasicResponseStatus::Ok,
             accept_type,
+            start_sequence_number,
+            Cursor::sequence_number,
+            self.context.max_response_size(),
         ))
     }
 
@@ -449,21 +1199,29 @@ impl TransactionsApi {
         &self,
         data: SubmitTransactionPost,
     ) -> Result<SignedTransaction, SubmitTransactionError> {
-        match data {
-            SubmitTransactionPost::Bcs(data) => {
-                let signed_transaction = bcs::from_bytes(&data.0)
-                    .context("Failed to deserialize input into SignedTransaction")
-                    .map_err(SubmitTransactionError::bad_request)?;
-                Ok(signed_transaction)
+        let signed_transaction = match data {
+            SubmitTransactionPost::Bcs(data) => data
+                .deserialize()
+                .context("Failed to deserialize input into SignedTransaction")
+                .map_err(|e| {
+                    SubmitTransactionError::bad_request_with_code(e, AptosErrorCode::InvalidInput)
+                })?,
+            SubmitTransactionPost::Json(data) => {
+                let resolver = self.context.move_resolver_poem()?;
+                self.context
+                    .converter(&resolver)
+                    .try_into_signed_transaction_poem(data.0, self.context.chain_id())
+                    .context("Failed to create SignedTransaction from SubmitTransactionRequest")
+                    .map_err(|e| {
+                        SubmitTransactionError::bad_request_with_code(
+                            e,
+                            AptosErrorCode::InvalidInput,
+                        )
+                    })?
             }
-            SubmitTransactionPost::Json(data) => self
-                .context
-                .move_resolver_poem()?
-                .as_converter(self.context.db.clone())
-                .try_into_signed_transaction_poem(data.0, self.context.chain_id())
-                .context("Failed to create SignedTransaction from SubmitTransactionRequest")
-                .map_err(SubmitTransactionError::bad_request),
-        }
+        };
+        validate_chain_id(&signed_transaction, self.context.chain_id())?;
+        Ok(signed_transaction)
     }
 
     async fn create(
@@ -477,15 +1235,17 @@ impl TransactionsApi {
             .submit_transaction(txn.clone())
             .await
             .context("Mempool failed to initially evaluate submitted transaction")
-            .map_err(SubmitTransactionError::internal)?;
+            .map_err(|e| {
+                SubmitTransactionError::internal_with_code(e, AptosErrorCode::InternalError)
+            })?;
         match mempool_status.code {
             MempoolStatusCode::Accepted => {
                 let resolver = self.context.move_resolver_poem()?;
-                let pending_txn = resolver
-                    .as_converter(self.context.db.clone())
+                let pending_txn = self.context
+                    .converter(&resolver)
                     .try_into_pending_transaction_poem(txn)
                     .context("Failed to build PendingTransaction from mempool response, even though it said the request was accepted")
-                    .map_err(SubmitTransactionError::internal)?;
+                    .map_err(|e| SubmitTransactionError::internal_with_code(e, AptosErrorCode::InternalError))?;
                 SubmitTransactionResponse::try_from_rust_value((
                     pending_txn,
                     &ledger_info,
@@ -493,22 +1253,88 @@ impl TransactionsApi {
                     accept_type,
                 ))
             }
-            MempoolStatusCode::MempoolIsFull => Err(
-                SubmitTransactionError::insufficient_storage_str(&mempool_status.message),
-            ),
-            MempoolStatusCode::VmError => Err(SubmitTransactionError::bad_request_str(&format!(
-                "invalid transaction: {}",
-                vm_status_opt
-                    .map(|s| format!("{:?}", s))
-                    .unwrap_or_else(|| "UNKNOWN".to_owned())
-            ))),
-            _ => Err(SubmitTransactionError::bad_request_str(&format!(
-                "transaction is rejected: {}",
-                mempool_status,
-            ))),
+            MempoolStatusCode::MempoolIsFull => {
+                let error = SubmitTransactionError::insufficient_storage_with_code(
+                    "Mempool is full, the transaction may be submitted again once it has room",
+                    AptosErrorCode::MempoolIsFull,
+                );
+                Err(self.with_mempool_details(error, &ledger_info, None).await)
+            }
+            MempoolStatusCode::TooManyTransactions => {
+                let error = SubmitTransactionError::too_many_requests_with_code(
+                    format!(
+                        "Account {} has as many transactions pending in mempool as it may, the \
+                        transaction may be submitted again once some of them are committed",
+                        txn.sender()
+                    ),
+                    AptosErrorCode::TooManyPendingTransactions,
+                );
+                Err(self
+                    .with_mempool_details(error, &ledger_info, Some(txn.sender()))
+                    .await)
+            }
+            MempoolStatusCode::VmError => Err(match vm_status_opt {
+                Some(status_code) => vm_status_error(&self.context, status_code, &txn),
+                None => SubmitTransactionError::bad_request_with_code(
+                    "invalid transaction: UNKNOWN",
+                    AptosErrorCode::InvalidTransaction,
+                ),
+            }),
+            MempoolStatusCode::RejectedByFilter => {
+                Err(SubmitTransactionError::bad_request_with_code(
+                    format!(
+                        "transaction is rejected by the node's submission filter: {}",
+                        mempool_status.message
+                    ),
+                    AptosErrorCode::TransactionRejectedByFilter,
+                ))
+            }
+            _ => Err(SubmitTransactionError::bad_request_with_code(
+                format!("transaction is rejected: {}", mempool_status),
+                AptosErrorCode::TransactionRejected,
+            )),
         }
     }
 
+    /// Adds to an error rejecting a transaction for lack of room in mempool
+    /// how full mempool is and, if the sender ran out of room, how many
+    /// transactions it has pending out of how many it may. It also adds in
+    /// how many seconds to submit the transaction again, estimated from how
+    /// fast the chain has recently been committing transactions. Details that
+    /// fail to be read are left out, so the rejection is still reported.
+    async fn with_mempool_details(
+        &self,
+        mut error: SubmitTransactionError,
+        ledger_info: &LedgerInfo,
+        sender: Option<AccountAddress>,
+    ) -> SubmitTransactionError {
+        // The number of transactions that have to leave mempool before the
+        // transaction fits.
+        let mut num_txns_over_limit = 1;
+        if let Ok(mempool_size) = self.context.get_mempool_size().await {
+            error = error
+                .detail("mempool_size", mempool_size.num_txns)
+                .detail("mempool_capacity", mempool_size.capacity);
+            if sender.is_none() {
+                num_txns_over_limit =
+                    (mempool_size.num_txns + 1).saturating_sub(mempool_size.capacity);
+            }
+        }
+        if let Some(sender) = sender {
+            let max_pending_txns = self.context.mempool_capacity_per_user();
+            error = error.detail("max_pending_txns", max_pending_txns);
+            if let Ok(txns) = self.context.get_pending_account_transactions(sender).await {
+                error = error.detail("pending_txns", txns.len());
+                num_txns_over_limit = (txns.len() + 1).saturating_sub(max_pending_txns);
+            }
+        }
+        if let Ok(Some(throughput)) = self.context.get_commit_throughput(ledger_info) {
+            let retry_after_secs = (num_txns_over_limit.max(1) as f64 / throughput).ceil() as u64;
+            error = error.detail("retry_after_secs", retry_after_secs.max(1));
+        }
+        error
+    }
+
     // TODO: This returns a Vec<Transaction>, but is it possible for a single
     // transaction request to result in multiple executed transactions?
     // TODO: This function leverages a lot of types from aptos_types, use the
@@ -518,16 +1344,62 @@ impl TransactionsApi {
         &self,
         accept_type: &AcceptType,
         txn: SignedTransaction,
+        include_state_diff: bool,
+        bypass_cache: bool,
+        gas_schedule_overrides: GasScheduleOverrides,
+        gas_feature_version: Option<u64>,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         if txn.clone().check_signature().is_ok() {
-            return Err(SubmitTransactionError::bad_request_str(
+            return Err(SubmitTransactionError::bad_request_with_code(
                 "Transaction simulation request has a valid signature, this is not allowed",
+                AptosErrorCode::InvalidInput,
             ));
         }
+        validate_gas_overrides(&gas_schedule_overrides, gas_feature_version)?;
         let ledger_info = self.context.get_latest_ledger_info()?;
         let move_resolver = self.context.move_resolver_poem()?;
-        let (status, output_ext) = AptosVM::simulate_signed_transaction(&txn, &move_resolver);
+
+        // The result only depends on the transaction and the state it runs
+        // against, so it can be served from the cache until the state changes.
+        // Simulations under other gas parameters are one-offs, so they aren't
+        // worth caching.
+        let has_gas_overrides =
+            !gas_schedule_overrides.0.is_empty() || gas_feature_version.is_some();
+        let cache_key = move_resolver
+            .version
+            .filter(|_| !has_gas_overrides)
+            .map(|state_version| SimulationKey {
+                txn_hash: txn.clone().committed_hash(),
+                state_version,
+                include_state_diff,
+            });
+        if bypass_cache {
+            SIMULATION_CACHE.with_label_values(&["bypass"]).inc();
+        } else if let Some(user_transactions) = cache_key
+            .as_ref()
+            .and_then(|cache_key| self.context.simulation_cache().get(cache_key))
+        {
+            SIMULATION_CACHE.with_label_values(&["hit"]).inc();
+            return BasicResponse::try_from_rust_value((
+                user_transactions,
+                &ledger_info,
+                BasicResponseStatus::Ok,
+                accept_type,
+            ));
+        } else {
+            SIMULATION_CACHE.with_label_values(&["miss"]).inc();
+        }
+
+        let (status, output_ext) = info_span!("vm_simulation").in_scope(|| {
+            AptosVM::simulate_signed_transaction_with_overrides(
+                &txn,
+                &move_resolver,
+                &gas_schedule_overrides.0,
+                gas_feature_version.map(|major| FeatureVersion { major }),
+            )
+        });
         let version = ledger_info.version();
+        let storage_fee = output_ext.storage_fee().map(render_storage_fee);
 
         // Apply deltas.
         // TODO: while `into_transaction_output_with_status()` should never fail
@@ -542,6 +1414,11 @@ impl TransactionsApi {
 
         let exe_status = match status.into() {
             TransactionStatus::Keep(exec_status) => exec_status,
+            // The transaction would not make it into a block, so report why
+            // the same way submission does.
+            TransactionStatus::Discard(status_code) => {
+                return Err(vm_status_error(&self.context, status_code, &txn))
+            }
             _ => ExecutionStatus::MiscellaneousError(None),
         };
 
@@ -554,6 +1431,24 @@ impl TransactionsApi {
             output.gas_used(),
             exe_status,
         );
+        let state_diff = if include_state_diff {
+            // The simulation ran against the same state the resolver reads, so
+            // it reads the values from before the transaction.
+            let converter = self.context.converter(&move_resolver);
+            let state_diff = output
+                .write_set()
+                .iter()
+                .map(|(state_key, op)| converter.try_into_state_diff(state_key, op))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .context("Failed to convert the state diff of the simulated transaction")
+                .map_err(|e| {
+                    SubmitTransactionError::internal_with_code(e, AptosErrorCode::InternalError)
+                })?;
+            Some(state_diff)
+        } else {
+            None
+        };
+
         let simulated_txn = TransactionOnChainData {
             version,
             transaction: aptos_types::transaction::Transaction::UserTransaction(txn),
@@ -570,12 +1465,22 @@ impl TransactionsApi {
         let mut user_transactions = Vec::new();
         for transaction in transactions.into_iter() {
             match transaction {
-                Transaction::UserTransaction(user_txn) => user_transactions.push(*user_txn),
-                _ => return Err(SubmitTransactionError::internal_str(
+                Transaction::UserTransaction(mut user_txn) => {
+                    user_txn.state_diff = state_diff.clone();
+                    user_txn.storage_fee = storage_fee.clone();
+                    user_transactions.push(*user_txn)
+                }
+                _ => return Err(SubmitTransactionError::internal_with_code(
                     "Simulation unexpectedly resulted in something other than a UserTransaction",
+                    AptosErrorCode::InternalError,
                 )),
             }
         }
+        if let Some(cache_key) = cache_key {
+            self.context
+                .simulation_cache()
+                .insert(cache_key, user_transactions.clone());
+        }
         BasicResponse::try_from_rust_value((
             user_transactions,
             &ledger_info,
@@ -584,37 +1489,264 @@ impl TransactionsApi {
         ))
     }
 
+    fn precheck(
+        &self,
+        accept_type: &AcceptType,
+        txn: SignedTransaction,
+    ) -> SimulateTransactionResult<TransactionPrecheck> {
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let move_resolver = self.context.move_resolver_poem()?;
+        let vm_result = info_span!("vm_precheck")
+            .in_scope(|| AptosVM::precheck_transaction(&txn, &move_resolver));
+
+        // The checks of the sender account are read from the same state the VM
+        // ran against.
+        let account: Option<AccountResource> = read_resource(&move_resolver, txn.sender())?;
+        let coin_store: Option<CoinStoreResource> = read_resource(&move_resolver, txn.sender())?;
+        let (auth_key, sequence_number, gas_balance) = match account {
+            Some(account) => (
+                precheck_auth_key(&txn, &account),
+                precheck_sequence_number(&txn, &account),
+                precheck_gas_balance(&txn, coin_store.as_ref()),
+            ),
+            None => {
+                let missing = PrecheckResult {
+                    passed: false,
+                    expected: None,
+                    actual: None,
+                    reason: Some(format!("Account {} does not exist", txn.sender())),
+                };
+                (missing.clone(), missing.clone(), missing)
+            }
+        };
+
+        let precheck = TransactionPrecheck {
+            success: vm_result.is_ok()
+                && auth_key.passed
+                && sequence_number.passed
+                && gas_balance.passed,
+            vm_status: match vm_result {
+                Ok(()) => "Executed".to_string(),
+                Err(status) => format!("{:?}", status.status_code()),
+            },
+            auth_key,
+            sequence_number,
+            gas_balance,
+        };
+        BasicResponse::try_from_rust_value((
+            precheck,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
     pub fn get_signing_message(
         &self,
         accept_type: &AcceptType,
         request: EncodeSubmissionRequest,
     ) -> BasicResult<HexEncodedBytes> {
+        let raw_message = self.build_signing_message(&request)?;
+
+        BasicResponse::try_from_rust_value((
+            HexEncodedBytes::from(raw_message),
+            // TODO: Make a variant that doesn't require ledger info.
+            &self.context.get_latest_ledger_info()?,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    pub fn get_signer_signing_messages(
+        &self,
+        accept_type: &AcceptType,
+        request: EncodeSubmissionRequest,
+    ) -> BasicResult<Vec<SignerSigningMessage>> {
+        let message = HexEncodedBytes::from(self.build_signing_message(&request)?);
+
+        let mut signers = vec![(request.transaction.sender, TransactionSignerRole::Sender)];
+        signers.extend(
+            request
+                .secondary_signers
+                .iter()
+                .flatten()
+                .map(|address| (*address, TransactionSignerRole::SecondarySigner)),
+        );
+
+        let messages = signers
+            .into_iter()
+            .map(|(signer, role)| SignerSigningMessage {
+                signer,
+                role,
+                message: message.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        BasicResponse::try_from_rust_value((
+            messages,
+            // TODO: Make a variant that doesn't require ledger info.
+            &self.context.get_latest_ledger_info()?,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
+    /// Builds the BCS signing message shared by all signers of the requested
+    /// transaction
+    fn build_signing_message(
+        &self,
+        request: &EncodeSubmissionRequest,
+    ) -> Result<Vec<u8>, BasicError> {
         let resolver = self.context.move_resolver_poem()?;
-        let raw_txn: RawTransaction = resolver
-            .as_converter(self.context.db.clone())
-            .try_into_raw_transaction_poem(request.transaction, self.context.chain_id())
+        let raw_txn: RawTransaction = self
+            .context
+            .converter(&resolver)
+            .try_into_raw_transaction_poem(request.transaction.clone(), self.context.chain_id())
             .context("The given transaction is invalid")
-            .map_err(BasicError::bad_request)?;
+            .map_err(|e| BasicError::bad_request_with_code(e, AptosErrorCode::InvalidInput))?;
 
-        let raw_message = match request.secondary_signers {
+        let raw_message = match &request.secondary_signers {
             Some(secondary_signer_addresses) => {
                 signing_message(&RawTransactionWithData::new_multi_agent(
                     raw_txn,
                     secondary_signer_addresses
-                        .into_iter()
-                        .map(|v| v.into())
+                        .iter()
+                        .map(|v| (*v).into())
                         .collect(),
                 ))
             }
             None => raw_txn.signing_message(),
         };
+        Ok(raw_message)
+    }
+}
 
-        BasicResponse::try_from_rust_value((
-            HexEncodedBytes::from(raw_message),
-            // TODO: Make a variant that doesn't require ledger info.
-            &self.context.get_latest_ledger_info()?,
-            BasicResponseStatus::Ok,
-            accept_type,
-        ))
+/// Checks that the gas parameters overridden exist, and that the feature
+/// version is one this node knows how to run transactions under.
+fn validate_gas_overrides(
+    gas_schedule_overrides: &GasScheduleOverrides,
+    gas_feature_version: Option<u64>,
+) -> Result<(), SubmitTransactionError> {
+    // The storage gas parameters may be overridden too, to estimate the
+    // costs of a transaction once storage is charged for.
+    let gas_parameters: BTreeSet<String> = AptosGasParameters::initial_with_storage()
+        .to_on_chain_gas_schedule()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    if let Some(name) = gas_schedule_overrides
+        .0
+        .keys()
+        .find(|name| !gas_parameters.contains(*name))
+    {
+        return Err(SubmitTransactionError::bad_request_with_code(
+            format!("Unknown gas parameter {} in gas_schedule_overrides", name),
+            AptosErrorCode::InvalidInput,
+        ));
+    }
+    if let Some(major) = gas_feature_version {
+        if major == 0 || major > APTOS_MAX_KNOWN_VERSION.major {
+            return Err(SubmitTransactionError::bad_request_with_code(
+                format!(
+                    "Unknown gas_feature_version {}, expected at most {}",
+                    major, APTOS_MAX_KNOWN_VERSION.major
+                ),
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reads a resource of an account, if the account has it.
+fn read_resource<T: MoveResource>(
+    state_view: &impl StateView,
+    address: AccountAddress,
+) -> Result<Option<T>, SubmitTransactionError> {
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        address,
+        T::struct_tag(),
+    )));
+    state_view
+        .get_state_value(&state_key)
+        .and_then(|bytes| {
+            bytes
+                .map(|bytes| bcs::from_bytes(&bytes))
+                .transpose()
+                .map_err(Into::into)
+        })
+        .context("Failed to read a resource of the sender account")
+        .map_err(|e| {
+            SubmitTransactionError::internal_with_code(e, AptosErrorCode::ReadFromStorageError)
+        })
+}
+
+fn precheck_auth_key(txn: &SignedTransaction, account: &AccountResource) -> PrecheckResult {
+    let auth_key = txn.authenticator().sender().authentication_key();
+    let passed = auth_key.as_ref() == account.authentication_key();
+    PrecheckResult {
+        passed,
+        expected: Some(HexEncodedBytes::from(account.authentication_key().to_vec()).to_string()),
+        actual: Some(auth_key.to_string()),
+        reason: (!passed).then(|| {
+            "The public key of the signature doesn't match the authentication key of the \
+            sender account, which may have rotated its key"
+                .to_string()
+        }),
+    }
+}
+
+fn precheck_sequence_number(txn: &SignedTransaction, account: &AccountResource) -> PrecheckResult {
+    let passed = txn.sequence_number() >= account.sequence_number();
+    PrecheckResult {
+        passed,
+        expected: Some(format!(">= {}", account.sequence_number())),
+        actual: Some(txn.sequence_number().to_string()),
+        reason: (!passed).then(|| {
+            "The sequence number was already used by a committed transaction of the sender \
+            account"
+                .to_string()
+        }),
+    }
+}
+
+fn precheck_gas_balance(
+    txn: &SignedTransaction,
+    coin_store: Option<&CoinStoreResource>,
+) -> PrecheckResult {
+    let max_fee = txn.max_gas_amount() as u128 * txn.gas_unit_price() as u128;
+    match coin_store {
+        Some(coin_store) => {
+            let passed = coin_store.coin() as u128 >= max_fee;
+            PrecheckResult {
+                passed,
+                expected: Some(format!(">= {}", max_fee)),
+                actual: Some(coin_store.coin().to_string()),
+                reason: (!passed).then(|| {
+                    "The balance of the sender account can't pay for max_gas_amount at \
+                    gas_unit_price"
+                        .to_string()
+                }),
+            }
+        }
+        None => PrecheckResult {
+            passed: false,
+            expected: Some(format!(">= {}", max_fee)),
+            actual: None,
+            reason: Some("The sender account has no coin store to pay for gas from".to_string()),
+        },
+    }
+}
+
+fn render_storage_fee(fee: &aptos_gas::StorageFee) -> StorageFee {
+    StorageFee {
+        new_items: fee.stats.num_new_items.into(),
+        new_bytes: fee.stats.new_bytes.into(),
+        modified_items: fee.stats.num_modified_items.into(),
+        modified_bytes: fee.stats.modified_bytes.into(),
+        deleted_items: fee.stats.num_deleted_items.into(),
+        freed_bytes: fee.stats.freed_bytes.into(),
+        charge: u64::from(fee.charge).into(),
+        refund: u64::from(fee.refund).into(),
     }
 }
@@ -0,0 +1,195 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Maps the VM status of a discarded transaction to an API error.
+//!
+//! When the VM discards a transaction, either while validating it on
+//! submission or while simulating it, a StatusCode is all we get back. On its
+//! own that rarely tells the user what to do about it, so this module adds the
+//! category of the status, the field of the transaction that caused it (along
+//! with the expected and actual values where we can tell) and a suggested
+//! remediation. These are returned in the details of the error.
+
+use crate::context::Context;
+use crate::response::{AptosErrorResponse, BadRequestError};
+use aptos_api_types::AptosErrorCode;
+use aptos_types::access_path::AccessPath;
+use aptos_types::account_address::AccountAddress;
+use aptos_types::account_config::AccountResource;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::transaction::SignedTransaction;
+use aptos_types::vm_status::{StatusCode, StatusType};
+use move_deps::move_core_types::language_storage::ResourceKey;
+use move_deps::move_core_types::move_resource::MoveStructType;
+
+/// What we know about why the VM discarded a transaction.
+#[derive(Debug, Default, PartialEq)]
+pub struct VmStatusDiagnostic {
+    /// The transaction field that caused the status, if it is a single field.
+    pub field: Option<&'static str>,
+    /// The value the VM expected for the field, if known.
+    pub expected: Option<String>,
+    /// The value the transaction had for the field.
+    pub actual: Option<String>,
+    pub remediation: &'static str,
+}
+
+/// Returns the category of the status, e.g. whether the transaction failed
+/// validation or its code failed bytecode verification.
+pub fn vm_status_category(status_code: StatusCode) -> &'static str {
+    match status_code.status_type() {
+        StatusType::Validation => "validation",
+        StatusType::Verification => "verification",
+        StatusType::InvariantViolation => "invariant_violation",
+        StatusType::Deserialization => "deserialization",
+        StatusType::Execution => "execution",
+        StatusType::Unknown => "unknown",
+    }
+}
+
+/// Works out which field of the transaction caused the status and how to fix
+/// it. Expected values are read from the latest ledger state where needed,
+/// failures to do so just leave them out.
+pub fn diagnose(
+    context: &Context,
+    status_code: StatusCode,
+    txn: &SignedTransaction,
+) -> VmStatusDiagnostic {
+    match status_code {
+        StatusCode::SEQUENCE_NUMBER_TOO_OLD => VmStatusDiagnostic {
+            field: Some("sequence_number"),
+            expected: account_resource(context, txn.sender())
+                .map(|account| format!(">= {}", account.sequence_number())),
+            actual: Some(txn.sequence_number().to_string()),
+            remediation: "Use the current sequence number of the sender account and sign the transaction again",
+        },
+        StatusCode::SEQUENCE_NUMBER_TOO_NEW => VmStatusDiagnostic {
+            field: Some("sequence_number"),
+            expected: account_resource(context, txn.sender())
+                .map(|account| account.sequence_number().to_string()),
+            actual: Some(txn.sequence_number().to_string()),
+            remediation: "Wait for the earlier transactions of the sender account to be committed, or use its current sequence number",
+        },
+        StatusCode::SEQUENCE_NUMBER_TOO_BIG => VmStatusDiagnostic {
+            field: Some("sequence_number"),
+            expected: Some(format!("< {}", u64::MAX)),
+            actual: Some(txn.sequence_number().to_string()),
+            remediation: "Use the current sequence number of the sender account",
+        },
+        StatusCode::SENDING_ACCOUNT_DOES_NOT_EXIST => VmStatusDiagnostic {
+            field: Some("sender"),
+            actual: Some(txn.sender().to_hex_literal()),
+            remediation: "Create the sender account before sending transactions from it",
+            ..Default::default()
+        },
+        StatusCode::INVALID_AUTH_KEY => VmStatusDiagnostic {
+            field: Some("signature"),
+            expected: account_resource(context, txn.sender())
+                .map(|account| hex::encode(account.authentication_key())),
+            remediation: "Sign the transaction with the key matching the authentication key of the sender account",
+            ..Default::default()
+        },
+        StatusCode::INVALID_SIGNATURE => VmStatusDiagnostic {
+            field: Some("signature"),
+            remediation: "Sign the BCS encoded signing message of the transaction, which can be built with the encode_submission endpoint",
+            ..Default::default()
+        },
+        StatusCode::BAD_CHAIN_ID => VmStatusDiagnostic {
+            field: Some("chain_id"),
+            expected: Some(context.chain_id().id().to_string()),
+            actual: Some(txn.chain_id().id().to_string()),
+            remediation: "Use the chain ID of the network the node belongs to",
+        },
+        StatusCode::TRANSACTION_EXPIRED => VmStatusDiagnostic {
+            field: Some("expiration_timestamp_secs"),
+            expected: context
+                .get_latest_ledger_info_wrapped()
+                .ok()
+                .map(|info| format!("> {}", info.timestamp() / 1_000_000)),
+            actual: Some(txn.expiration_timestamp_secs().to_string()),
+            remediation: "Sign the transaction again with an expiration time in the future",
+        },
+        StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE => VmStatusDiagnostic {
+            field: Some("max_gas_amount"),
+            actual: Some(txn.max_gas_amount().to_string()),
+            remediation: "Fund the sender account, or lower max_gas_amount or gas_unit_price",
+            ..Default::default()
+        },
+        StatusCode::MAX_GAS_UNITS_EXCEEDS_MAX_GAS_UNITS_BOUND => VmStatusDiagnostic {
+            field: Some("max_gas_amount"),
+            actual: Some(txn.max_gas_amount().to_string()),
+            remediation: "Lower max_gas_amount to the maximum allowed by the gas schedule",
+            ..Default::default()
+        },
+        StatusCode::MAX_GAS_UNITS_BELOW_MIN_TRANSACTION_GAS_UNITS => VmStatusDiagnostic {
+            field: Some("max_gas_amount"),
+            actual: Some(txn.max_gas_amount().to_string()),
+            remediation: "Raise max_gas_amount to at least the minimum transaction gas units of the gas schedule",
+            ..Default::default()
+        },
+        StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND => VmStatusDiagnostic {
+            field: Some("gas_unit_price"),
+            actual: Some(txn.gas_unit_price().to_string()),
+            remediation: "Raise gas_unit_price to at least the minimum price of the gas schedule",
+            ..Default::default()
+        },
+        StatusCode::GAS_UNIT_PRICE_ABOVE_MAX_BOUND => VmStatusDiagnostic {
+            field: Some("gas_unit_price"),
+            actual: Some(txn.gas_unit_price().to_string()),
+            remediation: "Lower gas_unit_price to at most the maximum price of the gas schedule",
+            ..Default::default()
+        },
+        StatusCode::EXCEEDED_MAX_TRANSACTION_SIZE => VmStatusDiagnostic {
+            field: Some("payload"),
+            actual: Some(txn.raw_txn_bytes_len().to_string()),
+            remediation: "Reduce the size of the transaction, e.g. by splitting it into several",
+            ..Default::default()
+        },
+        _ => VmStatusDiagnostic {
+            remediation: match status_code.status_type() {
+                StatusType::Verification => "The code in the transaction failed bytecode verification, fix it and compile it again",
+                StatusType::Deserialization => "The transaction could not be deserialized, check that it was encoded correctly",
+                StatusType::InvariantViolation => "The VM hit an internal error, which is a bug that should be reported",
+                _ => "Fix the transaction according to the VM status and submit it again",
+            },
+            ..Default::default()
+        },
+    }
+}
+
+/// Builds the error returned for a transaction that the VM discarded.
+pub fn vm_status_error<E: BadRequestError>(
+    context: &Context,
+    status_code: StatusCode,
+    txn: &SignedTransaction,
+) -> E {
+    let diagnostic = diagnose(context, status_code, txn);
+    let mut error = E::bad_request_with_code(
+        format!("invalid transaction: {:?}", status_code),
+        AptosErrorCode::InvalidTransaction,
+    )
+    .detail("vm_status", format!("{:?}", status_code))
+    .detail("vm_status_code", status_code as u64)
+    .detail("vm_status_category", vm_status_category(status_code))
+    .detail("remediation", diagnostic.remediation);
+    if let Some(field) = diagnostic.field {
+        error = error.detail("field", field);
+    }
+    if let Some(expected) = diagnostic.expected {
+        error = error.detail("expected", expected);
+    }
+    if let Some(actual) = diagnostic.actual {
+        error = error.detail("actual", actual);
+    }
+    error
+}
+
+fn account_resource(context: &Context, address: AccountAddress) -> Option<AccountResource> {
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        address,
+        AccountResource::struct_tag(),
+    )));
+    let version = context.get_latest_ledger_info_wrapped().ok()?.version();
+    let bytes = context.get_state_value(&state_key, version).ok()??;
+    bcs::from_bytes(&bytes).ok()
+}
@@ -8,7 +8,8 @@ use aptos_api_types::{
     X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
 };
 use aptos_config::config::{
-    NodeConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE,
+    NodeConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+    TARGET_SNAPSHOT_SIZE,
 };
 use aptos_crypto::{hash::HashValue, SigningKey};
 use aptos_mempool::mocks::MockSharedMempool;
@@ -84,6 +85,14 @@ impl ApiSpecificConfig {
 }
 
 pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestContext {
+    new_test_context_with_config(test_name, NodeConfig::default(), use_db_with_indexer)
+}
+
+pub fn new_test_context_with_config(
+    test_name: String,
+    node_config: NodeConfig,
+    use_db_with_indexer: bool,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -113,6 +122,7 @@ pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestCon
                 RocksdbConfigs::default(),
                 false, /* indexer */
                 TARGET_SNAPSHOT_SIZE,
+                TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
             )
             .unwrap(),
         )
@@ -123,8 +133,6 @@ pub fn new_test_context(test_name: String, use_db_with_indexer: bool) -> TestCon
 
     let mempool = MockSharedMempool::new_in_runtime(&db_rw, VMValidator::new(db.clone()));
 
-    let node_config = NodeConfig::default();
-
     let context = Context::new(
         ChainId::test(),
         db.clone(),
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HexEncodedBytes, U64};
+use crate::{Address, HexEncodedBytes, MoveResource, MoveStructTag, U64};
 
 use aptos_types::account_config::AccountResource;
 use poem_openapi::Object;
@@ -22,3 +22,190 @@ impl From<AccountResource> for AccountData {
         }
     }
 }
+
+/// The authentication keys an account has had, from its creation on, with
+/// which custodians can audit the rotations of its key
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountKeyHistory {
+    /// The version of the transaction that created the account
+    pub creation_version: U64,
+    /// The timestamp in microseconds of the block that created the account,
+    /// if the node hasn't pruned it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_timestamp: Option<U64>,
+    /// The keys of the account, oldest first. The first one is the key the
+    /// account was created with, each later one was set by a rotation.
+    pub authentication_keys: Vec<AuthenticationKeyChange>,
+}
+
+/// An authentication key of an account, with the transaction that set it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AuthenticationKeyChange {
+    pub version: U64,
+    /// The timestamp in microseconds of the block of the transaction, if the
+    /// node hasn't pruned it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<U64>,
+    pub authentication_key: HexEncodedBytes,
+}
+
+/// The net changes to the resources of an account between two versions,
+/// which leave out the changes undone before the later version
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountDiff {
+    pub from_version: U64,
+    pub to_version: U64,
+    /// The resources the account has at `to_version` and didn't have at
+    /// `from_version`
+    pub created: Vec<MoveResource>,
+    /// The resources whose values differ between the two versions
+    pub modified: Vec<ModifiedResource>,
+    /// The resources the account had at `from_version` and doesn't have at
+    /// `to_version`, with their values at `from_version`
+    pub deleted: Vec<MoveResource>,
+}
+
+/// A resource with its values at both versions of an account diff
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ModifiedResource {
+    pub before: MoveResource,
+    pub after: MoveResource,
+}
+
+/// A single entry of a batch account resources request
+///
+/// If `resource_type` is given, only the resource of that type is returned
+/// for the account, otherwise all of its resources are returned.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountResourcesRequest {
+    pub address: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<MoveStructTag>,
+}
+
+/// The resources found for a single entry of a batch account resources request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountResources {
+    pub address: Address,
+    pub resources: Vec<MoveResource>,
+}
+
+/// The transactions of an account waiting in mempool, compared with the
+/// sequence number of the account on chain, from which wallets can find out
+/// why their transactions are stuck
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountTransactionQueue {
+    /// The sequence number of the account on chain, i.e. of the next
+    /// transaction of the account to be committed
+    pub sequence_number: U64,
+    /// The sequence number to give a new transaction: the lowest one that is
+    /// neither committed nor pending
+    pub next_sequence_number: U64,
+    pub lowest_pending_sequence_number: Option<U64>,
+    pub highest_pending_sequence_number: Option<U64>,
+    /// The sequence numbers missing from mempool below the highest pending
+    /// one. Pending transactions after a gap can't be committed until
+    /// transactions with the missing sequence numbers are submitted.
+    pub sequence_number_gaps: Vec<SequenceNumberRange>,
+    /// The earliest expiration time of the pending transactions, after which
+    /// that transaction, and those after it, can't be committed
+    pub earliest_expiration_timestamp_secs: Option<U64>,
+}
+
+impl AccountTransactionQueue {
+    /// Builds the queue of an account from the sequence numbers and expiration
+    /// times of its transactions in mempool, ordered by sequence number.
+    /// Transactions with sequence numbers that were already committed are
+    /// ignored.
+    pub fn new(sequence_number: u64, pending: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        let mut expected = sequence_number;
+        let mut lowest = None;
+        let mut highest = None;
+        let mut gaps = vec![];
+        let mut earliest_expiration: Option<u64> = None;
+        for (pending_sequence_number, expiration_timestamp_secs) in pending {
+            if pending_sequence_number < expected {
+                continue;
+            }
+            if pending_sequence_number > expected {
+                gaps.push(SequenceNumberRange {
+                    start: expected.into(),
+                    end: (pending_sequence_number - 1).into(),
+                });
+            }
+            lowest.get_or_insert(pending_sequence_number);
+            highest = Some(pending_sequence_number);
+            earliest_expiration = Some(
+                earliest_expiration.map_or(expiration_timestamp_secs, |earliest| {
+                    earliest.min(expiration_timestamp_secs)
+                }),
+            );
+            expected = pending_sequence_number + 1;
+        }
+        let next_sequence_number = gaps.first().map_or(expected, |gap| gap.start.0);
+        Self {
+            sequence_number: sequence_number.into(),
+            next_sequence_number: next_sequence_number.into(),
+            lowest_pending_sequence_number: lowest.map(U64::from),
+            highest_pending_sequence_number: highest.map(U64::from),
+            sequence_number_gaps: gaps,
+            earliest_expiration_timestamp_secs: earliest_expiration.map(U64::from),
+        }
+    }
+}
+
+/// An inclusive range of sequence numbers
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct SequenceNumberRange {
+    pub start: U64,
+    pub end: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AccountTransactionQueue, SequenceNumberRange};
+
+    #[test]
+    fn test_transaction_queue_without_pending_transactions() {
+        let queue = AccountTransactionQueue::new(5, vec![]);
+        assert_eq!(queue.next_sequence_number, 5u64.into());
+        assert_eq!(queue.lowest_pending_sequence_number, None);
+        assert_eq!(queue.highest_pending_sequence_number, None);
+        assert!(queue.sequence_number_gaps.is_empty());
+        assert_eq!(queue.earliest_expiration_timestamp_secs, None);
+    }
+
+    #[test]
+    fn test_transaction_queue_with_gaps() {
+        let queue = AccountTransactionQueue::new(5, vec![(3, 10), (6, 300), (7, 200), (10, 100)]);
+        assert_eq!(queue.sequence_number, 5u64.into());
+        assert_eq!(queue.next_sequence_number, 5u64.into());
+        assert_eq!(queue.lowest_pending_sequence_number, Some(6u64.into()));
+        assert_eq!(queue.highest_pending_sequence_number, Some(10u64.into()));
+        assert_eq!(
+            queue.sequence_number_gaps,
+            vec![
+                SequenceNumberRange {
+                    start: 5u64.into(),
+                    end: 5u64.into(),
+                },
+                SequenceNumberRange {
+                    start: 8u64.into(),
+                    end: 9u64.into(),
+                },
+            ]
+        );
+        // The transaction that was already committed is ignored.
+        assert_eq!(
+            queue.earliest_expiration_timestamp_secs,
+            Some(100u64.into())
+        );
+    }
+
+    #[test]
+    fn test_transaction_queue_without_gaps() {
+        let queue = AccountTransactionQueue::new(5, vec![(5, 100), (6, 100)]);
+        assert_eq!(queue.next_sequence_number, 7u64.into());
+        assert!(queue.sequence_number_gaps.is_empty());
+    }
+}
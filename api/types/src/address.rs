@@ -1,6 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::hex_parser::{HexDigits, HexParseError};
+use aptos_crypto::HashValue;
 use aptos_types::account_address::AccountAddress;
 use move_deps::move_core_types;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
@@ -24,14 +26,27 @@ impl fmt::Display for Address {
 impl FromStr for Address {
     type Err = anyhow::Error;
 
+    /// Parses an address, which needs the `0x` prefix to be given in its short
+    /// form, e.g. `0x1`. Letters are validated against the checksum of the
+    /// address if they are in mixed case, see `to_checksummed_string`.
     fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
-        let mut ret = AccountAddress::from_hex_literal(s);
-        if ret.is_err() {
-            ret = AccountAddress::from_hex(s)
-        }
-        Ok(Self(ret.map_err(|_| {
-            anyhow::format_err!("invalid account address {:?}", s)
-        })?))
+        let digits = HexDigits::new(s);
+        let min_digits = if digits.has_prefix() {
+            1
+        } else {
+            2 * AccountAddress::LENGTH
+        };
+        Self::parse(&digits, min_digits).map_err(|err| {
+            let hint = match err {
+                HexParseError::WrongNumberOfDigits { num_digits, .. }
+                    if !digits.has_prefix() && num_digits < min_digits =>
+                {
+                    ", short addresses need the 0x prefix"
+                }
+                _ => "",
+            };
+            anyhow::format_err!("invalid account address {:?}: {}{}", s, err, hint)
+        })
     }
 }
 
@@ -53,6 +68,75 @@ impl From<&Address> for AccountAddress {
     }
 }
 
+impl Address {
+    /// Parses an address in a JSON value, which may also omit the `0x` prefix of
+    /// a short address, e.g. `1` for `0x1`.
+    fn from_json_str(s: &str) -> anyhow::Result<Self> {
+        Self::parse(&HexDigits::new(s), 1)
+            .map_err(|err| anyhow::format_err!("invalid account address {:?}: {}", s, err))
+    }
+
+    fn parse(digits: &HexDigits, min_digits: usize) -> Result<Self, HexParseError> {
+        let address = Self(AccountAddress::new(digits.parse(min_digits)?));
+        if digits.is_mixed_case() {
+            address.verify_checksum(digits)?;
+        }
+        Ok(address)
+    }
+
+    /// The address in its long form, with the case of its letters encoding
+    /// a checksum, the way EIP-55 does for Ethereum addresses: a letter is
+    /// uppercase if the nibble at its position in the SHA3-256 of the
+    /// lowercase address, without the `0x` prefix, is 8 or more. Addresses
+    /// given in mixed case are validated against it, which catches most typos.
+    pub fn to_checksummed_string(&self) -> String {
+        let lowercase = self.0.to_hex();
+        let checksum = HashValue::sha3_256_of(lowercase.as_bytes());
+        let digits: String = lowercase
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if checksum_nibble(&checksum, i) >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        format!("0x{}", digits)
+    }
+
+    fn verify_checksum(&self, digits: &HexDigits) -> Result<(), HexParseError> {
+        let checksummed = self.to_checksummed_string();
+        // Short forms are aligned with the end of the long form.
+        let padding = 2 * AccountAddress::LENGTH - digits.digits.len();
+        for (i, (character, expected)) in digits
+            .digits
+            .chars()
+            .zip(checksummed[2 + padding..].chars())
+            .enumerate()
+        {
+            if character != expected {
+                return Err(HexParseError::InvalidChecksum {
+                    character,
+                    position: digits.offset + i,
+                    expected,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn checksum_nibble(checksum: &HashValue, i: usize) -> u8 {
+    let byte = checksum[i / 2];
+    if i % 2 == 0 {
+        byte >> 4
+    } else {
+        byte & 0x0f
+    }
+}
+
 impl From<Address> for move_core_types::value::MoveValue {
     fn from(d: Address) -> Self {
         move_core_types::value::MoveValue::Address(d.0)
@@ -71,7 +155,7 @@ impl<'de> Deserialize<'de> for Address {
         D: Deserializer<'de>,
     {
         let address = <String>::deserialize(deserializer)?;
-        address.parse().map_err(D::Error::custom)
+        Address::from_json_str(&address).map_err(D::Error::custom)
     }
 }
 
@@ -90,13 +174,69 @@ mod tests {
             assert_eq!(address.parse::<Address>().unwrap().to_string(), "0x1");
         }
 
-        let invalid_addresses = vec!["invalid", "00x1", "x1", "01", "1"];
-        for address in invalid_addresses {
+        let invalid_addresses = vec![
+            (
+                "invalid",
+                "invalid character 'i' at position 0, expected a hex digit",
+            ),
+            (
+                "00x1",
+                "invalid character 'x' at position 2, expected a hex digit",
+            ),
+            (
+                "x1",
+                "invalid character 'x' at position 0, expected a hex digit",
+            ),
+            (
+                "01",
+                "expected 64 hex digits, found 2, short addresses need the 0x prefix",
+            ),
+            (
+                "1",
+                "expected 64 hex digits, found 1, short addresses need the 0x prefix",
+            ),
+            ("0x", "expected between 1 and 64 hex digits, found 0"),
+        ];
+        for (address, error) in invalid_addresses {
             assert_eq!(
-                format!("invalid account address {:?}", address),
+                format!("invalid account address {:?}: {}", address, error),
                 address.parse::<Address>().unwrap_err().to_string()
             );
         }
+
+        let too_long = format!("0x{}", "1".repeat(65));
+        assert_eq!(
+            format!(
+                "invalid account address {:?}: expected between 1 and 64 hex digits, found 65",
+                too_long
+            ),
+            too_long.parse::<Address>().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_checksum() {
+        let address: Address = "0xa550c18".parse().unwrap();
+        let checksummed = address.to_checksummed_string();
+        assert_eq!(
+            checksummed,
+            "0x000000000000000000000000000000000000000000000000000000000a550C18"
+        );
+        assert_eq!(checksummed.parse::<Address>().unwrap(), address);
+        // Short forms in mixed case are validated too, and addresses in a
+        // single case aren't.
+        for valid in ["0xa550C18", "0xA550C18", "a550c18"] {
+            assert_eq!(
+                serde_json::from_value::<Address>(json!(valid)).unwrap(),
+                address
+            );
+        }
+
+        assert_eq!(
+            "0xA550c18".parse::<Address>().unwrap_err().to_string(),
+            "invalid account address \"0xA550c18\": the case of 'A' at position 2 doesn't \
+             match the checksum, expected 'a'"
+        );
     }
 
     #[test]
@@ -108,6 +248,37 @@ mod tests {
         assert_eq!(val, json!("0x1"));
     }
 
+    #[test]
+    fn test_from_json_without_prefix() {
+        for address in ["1", "01", "00000000000000000000000000000001"] {
+            let address: Address = serde_json::from_value(json!(address)).unwrap();
+            assert_eq!(address, "0x1".parse().unwrap());
+        }
+
+        for (address, error) in [
+            ("", "expected between 1 and 64 hex digits, found 0"),
+            (
+                "invalid",
+                "invalid character 'i' at position 0, expected a hex digit",
+            ),
+            (
+                "x1",
+                "invalid character 'x' at position 0, expected a hex digit",
+            ),
+            (
+                "00x1",
+                "invalid character 'x' at position 2, expected a hex digit",
+            ),
+        ] {
+            assert_eq!(
+                format!("invalid account address {:?}: {}", address, error),
+                serde_json::from_value::<Address>(json!(address))
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+    }
+
     #[test]
     fn test_from_and_to_account_address() {
         let address: Address = serde_json::from_value(json!("0x1")).unwrap();
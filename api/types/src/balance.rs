@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, MoveType, U64};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// The latest coin balances of an account, from the balance index of the node
+///
+/// The index is built up to `indexed_version`, which may trail the latest
+/// ledger version by the few transactions committed since it was updated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AccountBalances {
+    pub indexed_version: U64,
+    /// A balance for each coin the account has registered, zero balances
+    /// included
+    pub balances: Vec<CoinBalance>,
+}
+
+/// The balance of an account in a coin
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct CoinBalance {
+    pub coin_type: MoveType,
+    pub amount: U64,
+}
+
+/// The accounts holding the most of a coin, from the balance index of the
+/// node
+///
+/// The index is built up to `indexed_version`, which may trail the latest
+/// ledger version by the few transactions committed since it was updated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct CoinHolders {
+    pub indexed_version: U64,
+    pub coin_type: MoveType,
+    /// The holders from the largest balance to the smallest, accounts with a
+    /// zero balance left out
+    pub holders: Vec<CoinHolder>,
+}
+
+/// An account holding a coin, and how much of it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct CoinHolder {
+    pub address: Address,
+    pub amount: U64,
+}
@@ -1,8 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HashValue, Transaction, U64};
-use poem_openapi::Object;
+use crate::{Address, HashValue, Transaction, U64};
+use anyhow::format_err;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::NewBlockEvent,
+    transaction::{Transaction as RawTransaction, TransactionInfo},
+};
+use poem_openapi::{Enum, Object};
 use serde::{Deserialize, Serialize};
 
 // TODO: Consider including this in the API.
@@ -28,4 +34,251 @@ pub struct Block {
     pub last_version: U64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub transactions: Option<Vec<Transaction>>,
+    /// Summaries of the transactions of the block, returned instead of the
+    /// transactions when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_summaries: Option<Vec<TransactionSummary>>,
+    /// Statistics of the fees paid by the transactions of the block,
+    /// returned when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_stats: Option<BlockFeeStats>,
+    /// The proposal of the block, with the validators it refers to resolved,
+    /// returned when requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proposal: Option<BlockProposal>,
+}
+
+/// How to return the transactions of a block
+///
+/// `true` returns the full transactions, while `summary` returns a lightweight
+/// summary of each transaction, which is much cheaper to compute.
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum BlockTransactions {
+    True,
+    False,
+    Summary,
+}
+
+/// A summary of a transaction of a block
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct TransactionSummary {
+    pub version: U64,
+    pub hash: HashValue,
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: TransactionSummaryType,
+    /// The sender of a user transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
+    pub success: bool,
+    pub gas_used: U64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum TransactionSummaryType {
+    UserTransaction,
+    GenesisTransaction,
+    BlockMetadataTransaction,
+    StateCheckpointTransaction,
+}
+
+impl From<(u64, &RawTransaction, &TransactionInfo)> for TransactionSummary {
+    fn from((version, txn, info): (u64, &RawTransaction, &TransactionInfo)) -> Self {
+        let (typ, sender) = match txn {
+            RawTransaction::UserTransaction(txn) => (
+                TransactionSummaryType::UserTransaction,
+                Some(txn.sender().into()),
+            ),
+            RawTransaction::GenesisTransaction(_) => {
+                (TransactionSummaryType::GenesisTransaction, None)
+            }
+            RawTransaction::BlockMetadata(_) => {
+                (TransactionSummaryType::BlockMetadataTransaction, None)
+            }
+            RawTransaction::StateCheckpoint(_) => {
+                (TransactionSummaryType::StateCheckpointTransaction, None)
+            }
+        };
+        Self {
+            version: version.into(),
+            hash: info.transaction_hash().into(),
+            typ,
+            sender,
+            success: info.status().is_success(),
+            gas_used: info.gas_used().into(),
+        }
+    }
+}
+
+/// Statistics of the fees paid by the user transactions of a block
+///
+/// Only user transactions pay for gas, at the gas unit price they set. The
+/// gas prices are 0 in a block without user transactions.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct BlockFeeStats {
+    pub num_user_transactions: U64,
+    /// The gas used by all the transactions of the block
+    pub total_gas_used: U64,
+    pub min_gas_unit_price: U64,
+    pub max_gas_unit_price: U64,
+    pub average_gas_unit_price: U64,
+    pub median_gas_unit_price: U64,
+    /// The sum of the gas used by each user transaction times its gas unit
+    /// price
+    pub total_fees: U64,
+    /// The fees burnt rather than paid to validators, which are all of them
+    /// for now
+    pub burnt_fees: U64,
+}
+
+impl BlockFeeStats {
+    /// Computes the statistics from the transactions of a block along with
+    /// their transaction infos.
+    pub fn new<'a>(
+        txns: impl IntoIterator<Item = (&'a RawTransaction, &'a TransactionInfo)>,
+    ) -> Self {
+        let mut total_gas_used: u64 = 0;
+        let mut total_fees: u64 = 0;
+        let mut gas_unit_prices = Vec::new();
+        for (txn, info) in txns {
+            total_gas_used = total_gas_used.saturating_add(info.gas_used());
+            if let RawTransaction::UserTransaction(txn) = txn {
+                gas_unit_prices.push(txn.gas_unit_price());
+                total_fees =
+                    total_fees.saturating_add(info.gas_used().saturating_mul(txn.gas_unit_price()));
+            }
+        }
+        gas_unit_prices.sort_unstable();
+
+        let num_user_transactions = gas_unit_prices.len() as u64;
+        let average_gas_unit_price = if gas_unit_prices.is_empty() {
+            0
+        } else {
+            (gas_unit_prices
+                .iter()
+                .map(|price| *price as u128)
+                .sum::<u128>()
+                / num_user_transactions as u128) as u64
+        };
+        let mid = gas_unit_prices.len() / 2;
+        let median_gas_unit_price = match gas_unit_prices.len() {
+            0 => 0,
+            len if len % 2 == 1 => gas_unit_prices[mid],
+            _ => ((gas_unit_prices[mid - 1] as u128 + gas_unit_prices[mid] as u128) / 2) as u64,
+        };
+        Self {
+            num_user_transactions: num_user_transactions.into(),
+            total_gas_used: total_gas_used.into(),
+            min_gas_unit_price: gas_unit_prices.first().copied().unwrap_or(0).into(),
+            max_gas_unit_price: gas_unit_prices.last().copied().unwrap_or(0).into(),
+            average_gas_unit_price: average_gas_unit_price.into(),
+            median_gas_unit_price: median_gas_unit_price.into(),
+            total_fees: total_fees.into(),
+            burnt_fees: total_fees.into(),
+        }
+    }
+}
+
+/// The proposal of a block, with the validators it refers to by index
+/// resolved against the validator set of its epoch
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct BlockProposal {
+    pub epoch: U64,
+    pub round: U64,
+    /// The proposer of the block, or `0x0` if the block was not proposed by a
+    /// validator, e.g. because the round timed out
+    pub proposer: Address,
+    /// The validators whose votes certified the previous block, in the order
+    /// of the validator set
+    pub previous_block_voters: Vec<Address>,
+    /// The validators that failed to propose a block in the rounds right
+    /// before this block's, in order of round
+    pub failed_proposers: Vec<Address>,
+}
+
+impl BlockProposal {
+    /// Resolves the indices in the `NewBlockEvent` of a block against the
+    /// addresses of the validators of its epoch, in the order of the
+    /// validator set.
+    pub fn new(event: &NewBlockEvent, validators: &[AccountAddress]) -> anyhow::Result<Self> {
+        let resolve = |index: usize| -> anyhow::Result<Address> {
+            validators
+                .get(index)
+                .map(|address| (*address).into())
+                .ok_or_else(|| {
+                    format_err!(
+                        "Validator index {} is out of range of the {} validators of epoch {}",
+                        index,
+                        validators.len(),
+                        event.epoch()
+                    )
+                })
+        };
+        // The bits of the bitvec are ordered from the most significant one of
+        // each byte, as in `aptos_bitvec::BitVec`.
+        let previous_block_voters = event
+            .previous_block_votes_bitvec()
+            .iter()
+            .enumerate()
+            .flat_map(|(i, byte)| {
+                (0..8)
+                    .filter(move |bit| byte & (0b1000_0000 >> bit) != 0)
+                    .map(move |bit| i * 8 + bit)
+            })
+            .map(resolve)
+            .collect::<anyhow::Result<_>>()?;
+        let failed_proposers = event
+            .failed_proposer_indices()
+            .iter()
+            .map(|index| resolve(*index as usize))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            epoch: event.epoch().into(),
+            round: event.round().into(),
+            proposer: event.proposer().into(),
+            previous_block_voters,
+            failed_proposers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BlockProposal;
+    use aptos_types::{account_address::AccountAddress, account_config::NewBlockEvent};
+
+    #[test]
+    fn test_resolve_block_proposal() {
+        let validators: Vec<_> = (0..10).map(|_| AccountAddress::random()).collect();
+        let event = NewBlockEvent::new(
+            2,
+            5,
+            100,
+            vec![0b1010_0000, 0b0100_0000],
+            validators[3],
+            vec![1, 9],
+            1000,
+        );
+        let proposal = BlockProposal::new(&event, &validators).unwrap();
+        assert_eq!(proposal.proposer, validators[3].into());
+        assert_eq!(
+            proposal.previous_block_voters,
+            vec![
+                validators[0].into(),
+                validators[2].into(),
+                validators[9].into()
+            ]
+        );
+        assert_eq!(
+            proposal.failed_proposers,
+            vec![validators[1].into(), validators[9].into()]
+        );
+
+        // Indices beyond the validator set are rejected.
+        assert!(BlockProposal::new(&event, &validators[..9]).is_err());
+    }
 }
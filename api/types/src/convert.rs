@@ -2,14 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    payload_builder::build_script_function_payload,
     transaction::{
         DecodedTableData, DeleteModule, DeleteResource, DeleteTableItem, DeletedTableData,
         ModuleBundlePayload, StateCheckpointTransaction, UserTransactionRequestInner, WriteModule,
         WriteResource, WriteTableItem,
     },
-    Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveValue, PendingTransaction, ScriptFunctionId,
-    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, SubmitTransactionRequest, Transaction,
+    Address, Bytecode, DirectWriteSet, EpochChange, Event, HexEncodedBytes, ModuleCache,
+    MoveFunction, MoveModuleBytecode, MoveResource, MoveScriptBytecode, MoveStructTag, MoveValue,
+    OnChainTransactionOutput, PendingTransaction, ScriptFunctionId, ScriptFunctionPayload,
+    ScriptPayload, ScriptWriteSet, StateChange, StateDiff, SubmitTransactionRequest, Transaction,
     TransactionInfo, TransactionOnChainData, TransactionPayload, UserTransactionRequest,
     VersionedEvent, WriteSet, WriteSetChange, WriteSetPayload,
 };
@@ -19,11 +21,13 @@ use aptos_transaction_builder::error_explain;
 use aptos_types::state_store::table::TableHandle;
 use aptos_types::{
     access_path::{AccessPath, Path},
+    account_config::NewEpochEvent,
     chain_id::ChainId,
-    contract_event::{ContractEvent, EventWithVersion},
+    contract_event::{ContractEvent, EventWithTransactionContext, EventWithVersion},
+    on_chain_config::{access_path_for_config, OnChainConfig, ValidatorSet},
     state_store::state_key::StateKey,
     transaction::{
-        ExecutionStatus, ModuleBundle, RawTransaction, Script, ScriptFunction, SignedTransaction,
+        ExecutionStatus, ModuleBundle, RawTransaction, Script, SignedTransaction, Version,
     },
     vm_status::AbortLocation,
     write_set::WriteOp,
@@ -38,29 +42,62 @@ use move_deps::{
         value::{MoveStructLayout, MoveTypeLayout},
     },
     move_resource_viewer::MoveValueAnnotator,
+    move_table_extension,
 };
 use serde_json::Value;
 use std::sync::Arc;
 use std::{
     convert::{TryFrom, TryInto},
     iter::IntoIterator,
-    rc::Rc,
 };
 use storage_interface::DbReader;
+use tracing::instrument;
 
 pub struct MoveConverter<'a, R: ?Sized> {
     inner: MoveValueAnnotator<'a, R>,
+    resolver: &'a R,
     db: Arc<dyn DbReader>,
+    module_cache: Option<(Arc<ModuleCache>, Version)>,
 }
 
 impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     pub fn new(inner: &'a R, db: Arc<dyn DbReader>) -> Self {
         Self {
             inner: MoveValueAnnotator::new(inner),
+            resolver: inner,
             db,
+            module_cache: None,
         }
     }
 
+    /// Resolves modules through the given cache shared across requests, the resolver reading
+    /// state at the given version.
+    pub fn with_module_cache(mut self, module_cache: Arc<ModuleCache>, version: Version) -> Self {
+        self.module_cache = Some((module_cache, version));
+        self
+    }
+
+    /// Calls `f` with the module, from the shared module cache if the converter has one.
+    fn with_module<T>(
+        &self,
+        module_id: &ModuleId,
+        f: impl FnOnce(&dyn Bytecode) -> T,
+    ) -> Result<T> {
+        match &self.module_cache {
+            Some((module_cache, version)) => {
+                let bytes = self
+                    .resolver
+                    .get_module(module_id)
+                    .map_err(|e| format_err!("Failed to read module {}: {:?}", module_id, e))?
+                    .ok_or_else(|| format_err!("Module {} can't be found", module_id))?;
+                let module = module_cache.get_or_deserialize(module_id, *version, bytes)?;
+                Ok(f(&*module))
+            }
+            None => Ok(f(&*self.inner.get_module(module_id)?)),
+        }
+    }
+
+    #[instrument(skip_all)]
     pub fn try_into_resources<'b>(
         &self,
         data: impl Iterator<Item = (StructTag, &'b [u8])>,
@@ -81,6 +118,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         self.inner.move_struct_fields(typ, bytes)
     }
 
+    #[instrument(skip_all)]
     pub fn try_into_pending_transaction(&self, txn: SignedTransaction) -> Result<Transaction> {
         let payload = self.try_into_transaction_payload(txn.payload().clone())?;
         Ok((txn, payload).into())
@@ -94,18 +132,20 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         Ok((txn, payload).into())
     }
 
+    #[instrument(skip_all)]
     pub fn try_into_onchain_transaction(
         &self,
         timestamp: u64,
         data: TransactionOnChainData,
     ) -> Result<Transaction> {
         use aptos_types::transaction::Transaction::*;
-        let info = self.into_transaction_info(
+        let mut info = self.into_transaction_info(
             data.version,
             &data.info,
             data.accumulator_root_hash,
             data.changes,
         );
+        info.epoch_change = self.try_into_epoch_change(data.version, &data.events)?;
         let events = self.try_into_events(&data.events)?;
         Ok(match data.transaction {
             UserTransaction(txn) => {
@@ -116,7 +156,10 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                 let payload = self.try_into_write_set_payload(write_set)?;
                 (info, payload, events).into()
             }
-            BlockMetadata(txn) => (&txn, info, events).into(),
+            BlockMetadata(txn) => {
+                let block_gas_limit_reached = self.db.get_block_gas_limit_reached(data.version)?;
+                (&txn, info, events, block_gas_limit_reached).into()
+            }
             StateCheckpoint(_) => {
                 Transaction::StateCheckpointTransaction(StateCheckpointTransaction {
                     info,
@@ -126,6 +169,45 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         })
     }
 
+    /// The new epoch the transaction at the given version started, if it emitted a
+    /// `NewEpochEvent`, with how the validator set changed.
+    fn try_into_epoch_change(
+        &self,
+        version: u64,
+        events: &[ContractEvent],
+    ) -> Result<Option<EpochChange>> {
+        let event = match events
+            .iter()
+            .find(|event| *event.key() == NewEpochEvent::event_key())
+        {
+            Some(event) => NewEpochEvent::try_from_bytes(event.event_data())?,
+            None => return Ok(None),
+        };
+        let previous = if version == 0 {
+            // There is no validator set before genesis.
+            ValidatorSet::empty()
+        } else {
+            self.validator_set(version - 1)?
+        };
+        let validator_set = self.validator_set(version)?;
+        Ok(Some(EpochChange::new(
+            event.epoch(),
+            previous,
+            validator_set,
+        )))
+    }
+
+    fn validator_set(&self, version: u64) -> Result<ValidatorSet> {
+        let value = self
+            .db
+            .get_state_value_by_version(
+                &StateKey::AccessPath(access_path_for_config(ValidatorSet::CONFIG_ID)),
+                version,
+            )?
+            .ok_or_else(|| format_err!("Validator set not found at version {}", version))?;
+        ValidatorSet::deserialize_into_config(&value.bytes)
+    }
+
     pub fn into_transaction_info(
         &self,
         version: u64,
@@ -147,9 +229,34 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                 .into_iter()
                 .filter_map(|(sk, wo)| self.try_into_write_set_change(sk, wo).ok())
                 .collect(),
+            epoch_change: None,
         }
     }
 
+    #[instrument(skip_all)]
+    pub fn try_into_transaction_output(
+        &self,
+        version: u64,
+        info: &aptos_types::transaction::TransactionInfo,
+        output: aptos_types::transaction::TransactionOutput,
+    ) -> Result<OnChainTransactionOutput> {
+        let gas_used = output.gas_used();
+        let (write_set, events) = output.into();
+        Ok(OnChainTransactionOutput {
+            version: version.into(),
+            hash: info.transaction_hash().into(),
+            gas_used: gas_used.into(),
+            success: info.status().is_success(),
+            vm_status: self.explain_vm_status(info.status()),
+            // TODO: the resource value is interpreted by the type definition at the version of the converter, not the version of the tx: must be fixed before we allow module updates
+            changes: write_set
+                .into_iter()
+                .filter_map(|(sk, wo)| self.try_into_write_set_change(sk, wo).ok())
+                .collect(),
+            events: self.try_into_events(&events)?,
+        })
+    }
+
     pub fn try_into_transaction_payload(
         &self,
         payload: aptos_types::transaction::TransactionPayload,
@@ -352,6 +459,94 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         }))
     }
 
+    /// Converts a write of a simulated transaction into the difference it makes to state, reading
+    /// the value before the transaction through the resolver of the converter.
+    pub fn try_into_state_diff(&self, state_key: &StateKey, op: &WriteOp) -> Result<StateDiff> {
+        let after_bytes = match op {
+            WriteOp::Creation(value) | WriteOp::Modification(value) => Some(value.as_slice()),
+            WriteOp::Deletion => None,
+        };
+        let (path, existed, before, after) = match state_key {
+            StateKey::AccessPath(access_path) => {
+                let address = Address::from(access_path.address);
+                match access_path.get_path() {
+                    Path::Code(module_id) => {
+                        let before_bytes = self.resolver.get_module(&module_id).map_err(|e| {
+                            format_err!("Failed to read module {}: {:?}", module_id, e)
+                        })?;
+                        // Modules aren't decoded.
+                        (
+                            format!("{}/module/{}", address, module_id.name()),
+                            before_bytes.is_some(),
+                            None,
+                            None,
+                        )
+                    }
+                    Path::Resource(typ) => {
+                        let before_bytes = self
+                            .resolver
+                            .get_resource(&access_path.address, &typ)
+                            .map_err(|e| {
+                            format_err!("Failed to read resource {}: {:?}", typ, e)
+                        })?;
+                        let decode = |bytes: &[u8]| -> Result<Value> {
+                            Ok(serde_json::to_value(
+                                self.try_into_resource(&typ, bytes)?.data,
+                            )?)
+                        };
+                        (
+                            format!("{}/resource/{}", address, MoveStructTag::from(typ.clone())),
+                            before_bytes.is_some(),
+                            before_bytes.as_deref().map(decode).transpose()?,
+                            after_bytes.map(decode).transpose()?,
+                        )
+                    }
+                }
+            }
+            StateKey::TableItem { handle, key } => {
+                let before_bytes = self
+                    .resolver
+                    .resolve_table_entry(&move_table_extension::TableHandle(handle.0), key)
+                    .map_err(|e| format_err!("Failed to read table item: {:?}", e))?;
+                let value_type = if self.db.indexer_enabled() {
+                    Some(self.db.get_table_info(*handle)?.value_type)
+                } else {
+                    None
+                };
+                // Values of tables are only decoded if the types of the table are known.
+                let decode = |bytes: &[u8]| -> Result<Value> {
+                    match &value_type {
+                        Some(value_type) => self.try_into_move_value(value_type, bytes)?.json(),
+                        None => Ok(Value::String(
+                            HexEncodedBytes::from(bytes.to_vec()).to_string(),
+                        )),
+                    }
+                };
+                (
+                    format!("table/{}/{}", handle.0, HexEncodedBytes::from(key.clone())),
+                    before_bytes.is_some(),
+                    before_bytes.as_deref().map(decode).transpose()?,
+                    after_bytes.map(decode).transpose()?,
+                )
+            }
+            StateKey::Raw(_) => bail!("Can't convert account raw key {:?} to StateDiff", state_key),
+        };
+
+        // Resources written by the VM are always modifications, so whether the state is created
+        // depends on whether it existed before the transaction.
+        let change = match (existed, after_bytes) {
+            (_, None) => StateChange::Deleted,
+            (false, Some(_)) => StateChange::Created,
+            (true, Some(_)) => StateChange::Modified,
+        };
+        Ok(StateDiff {
+            path,
+            change,
+            before,
+            after,
+        })
+    }
+
     pub fn try_into_events(&self, events: &[ContractEvent]) -> Result<Vec<Event>> {
         let mut ret = vec![];
         for event in events {
@@ -377,6 +572,21 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         Ok(ret)
     }
 
+    pub fn try_into_versioned_events_with_context(
+        &self,
+        events: &[EventWithTransactionContext],
+    ) -> Result<Vec<VersionedEvent>> {
+        let mut ret = vec![];
+        for event in events {
+            let data = self
+                .inner
+                .view_value(event.event.type_tag(), event.event.event_data())?;
+            ret.push((event, MoveValue::try_from(data)?.json()?).into());
+        }
+        Ok(ret)
+    }
+
+    #[instrument(skip_all)]
     pub fn try_into_signed_transaction(
         &self,
         txn: UserTransactionRequest,
@@ -470,33 +680,12 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                     arguments,
                 } = script_func_payload;
 
-                let module = function.module.clone();
-                let code = self.inner.get_module(&module.clone().into())? as Rc<dyn Bytecode>;
-                let func = code
-                    .find_script_function(function.name.0.as_ident_str())
+                let func = self
+                    .with_module(&function.module.clone().into(), |code| {
+                        code.find_script_function(function.name.0.as_ident_str())
+                    })?
                     .ok_or_else(|| format_err!("could not find script function by {}", function))?;
-                ensure!(
-                    func.generic_type_params.len() == type_arguments.len(),
-                    "expect {} type arguments for script function {}, but got {}",
-                    func.generic_type_params.len(),
-                    function,
-                    type_arguments.len()
-                );
-                let args = self
-                    .try_into_vm_values(func, arguments)?
-                    .iter()
-                    .map(bcs::to_bytes)
-                    .collect::<Result<_, bcs::Error>>()?;
-
-                Target::ScriptFunction(ScriptFunction::new(
-                    module.into(),
-                    function.name.into(),
-                    type_arguments
-                        .into_iter()
-                        .map(|v| v.try_into())
-                        .collect::<Result<_>>()?,
-                    args,
-                ))
+                build_script_function_payload(function, &func, type_arguments, arguments)?
             }
             TransactionPayload::ModuleBundlePayload(payload) => {
                 Target::ModuleBundle(ModuleBundle::new(
@@ -624,7 +813,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         layout: &MoveTypeLayout,
         val: Value,
     ) -> Result<move_core_types::value::MoveValue> {
-        if matches!(layout, MoveTypeLayout::U8) {
+        if matches!(layout, MoveTypeLayout::U8) && val.is_string() {
             Ok(serde_json::from_value::<HexEncodedBytes>(val)?.into())
         } else if let Value::Array(list) = val {
             let vals = list
@@ -681,7 +870,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         ))
     }
 
-    fn explain_vm_status(&self, status: &ExecutionStatus) -> String {
+    pub fn explain_vm_status(&self, status: &ExecutionStatus) -> String {
         match status {
             ExecutionStatus::MoveAbort { location, code} => match &location {
                 AbortLocation::Module(module_id) => {
@@ -738,10 +927,10 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     }
 
     fn explain_function_index(&self, module_id: &ModuleId, function: &u16) -> Result<String> {
-        let code = self.inner.get_module(&module_id.clone())? as Rc<dyn Bytecode>;
-        let func = code.function_handle_at(FunctionHandleIndex::new(*function));
-        let id = code.identifier_at(func.name);
-        Ok(format!("{}", id))
+        self.with_module(module_id, |code| {
+            let func = code.function_handle_at(FunctionHandleIndex::new(*function));
+            format!("{}", code.identifier_at(func.name))
+        })
     }
 }
 
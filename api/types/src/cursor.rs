@@ -0,0 +1,241 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err};
+use move_deps::move_core_types::language_storage::StructTag;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// The version of the encoding of cursors, which is the first byte of every
+/// cursor. It changes whenever positions are encoded differently, so that
+/// cursors handed out by older nodes are rejected rather than misread.
+const CURSOR_FORMAT_VERSION: u8 = 1;
+
+/// The position in a list at which a page of it starts.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+enum Position {
+    /// The item at `index` among the items of the transaction at `version`,
+    /// e.g. an event. Lists of transactions use the index 0.
+    VersionAndIndex { version: u64, index: u64 },
+    /// The item with a sequence number, e.g. an event of an event stream or a
+    /// transaction of an account.
+    SequenceNumber(u64),
+    /// The resource of an account with a struct tag, resources being ordered
+    /// by the BCS encoding of their access paths.
+    StructTag(StructTag),
+    /// The raw key of the table item after which the page starts.
+    TableKey(Vec<u8>),
+    /// The event stream an account created with a creation number.
+    CreationNumber(u64),
+}
+
+impl Position {
+    fn kind(&self) -> &'static str {
+        match self {
+            Position::VersionAndIndex { .. } => "version",
+            Position::SequenceNumber(_) => "sequence number",
+            Position::StructTag(_) => "struct tag",
+            Position::TableKey(_) => "table key",
+            Position::CreationNumber(_) => "creation number",
+        }
+    }
+}
+
+/// An opaque position in a list, from which to request the next page of it
+///
+/// Paginated endpoints return a cursor when there are more items to list,
+/// which is passed back as is in the `cursor` param of the next request.
+/// Cursors are base64 strings that only the node knows how to decode, and
+/// that are only valid for the kind of list they were returned for.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Cursor(Position);
+
+impl Cursor {
+    pub fn version(version: u64) -> Self {
+        Self::version_and_index(version, 0)
+    }
+
+    pub fn version_and_index(version: u64, index: u64) -> Self {
+        Self(Position::VersionAndIndex { version, index })
+    }
+
+    pub fn sequence_number(sequence_number: u64) -> Self {
+        Self(Position::SequenceNumber(sequence_number))
+    }
+
+    pub fn struct_tag(struct_tag: StructTag) -> Self {
+        Self(Position::StructTag(struct_tag))
+    }
+
+    pub fn table_key(key: Vec<u8>) -> Self {
+        Self(Position::TableKey(key))
+    }
+
+    pub fn creation_number(creation_number: u64) -> Self {
+        Self(Position::CreationNumber(creation_number))
+    }
+
+    /// The version of a cursor into a list of transactions.
+    pub fn into_version(self) -> anyhow::Result<u64> {
+        let (version, index) = self.into_version_and_index()?;
+        ensure!(
+            index == 0,
+            "The cursor is not a position in a list of transactions"
+        );
+        Ok(version)
+    }
+
+    pub fn into_version_and_index(self) -> anyhow::Result<(u64, u64)> {
+        match self.0 {
+            Position::VersionAndIndex { version, index } => Ok((version, index)),
+            position => Self::wrong_kind(position, "version"),
+        }
+    }
+
+    pub fn into_sequence_number(self) -> anyhow::Result<u64> {
+        match self.0 {
+            Position::SequenceNumber(sequence_number) => Ok(sequence_number),
+            position => Self::wrong_kind(position, "sequence number"),
+        }
+    }
+
+    pub fn into_struct_tag(self) -> anyhow::Result<StructTag> {
+        match self.0 {
+            Position::StructTag(struct_tag) => Ok(struct_tag),
+            position => Self::wrong_kind(position, "struct tag"),
+        }
+    }
+
+    pub fn into_table_key(self) -> anyhow::Result<Vec<u8>> {
+        match self.0 {
+            Position::TableKey(key) => Ok(key),
+            position => Self::wrong_kind(position, "table key"),
+        }
+    }
+
+    pub fn into_creation_number(self) -> anyhow::Result<u64> {
+        match self.0 {
+            Position::CreationNumber(creation_number) => Ok(creation_number),
+            position => Self::wrong_kind(position, "creation number"),
+        }
+    }
+
+    fn wrong_kind<T>(position: Position, expected: &str) -> anyhow::Result<T> {
+        bail!(
+            "The cursor is a {} position, while this list is paginated by {}, it must have been returned for another list",
+            position.kind(),
+            expected
+        )
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bytes = vec![CURSOR_FORMAT_VERSION];
+        bytes.extend(bcs::to_bytes(&self.0).map_err(|_| fmt::Error)?);
+        write!(
+            f,
+            "{}",
+            base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+        )
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let bytes = base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+            .map_err(|_| format_err!("Invalid cursor, it must be passed as returned"))?;
+        match bytes.split_first() {
+            Some((&CURSOR_FORMAT_VERSION, position)) => {
+                Ok(Self(bcs::from_bytes(position).map_err(|_| {
+                    format_err!("Invalid cursor, it must be passed as returned")
+                })?))
+            }
+            _ => bail!(
+                "Unsupported cursor, it may have been returned by another version of the node"
+            ),
+        }
+    }
+}
+
+impl Serialize for Cursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let cursor = <String>::deserialize(deserializer)?;
+        cursor.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cursor;
+    use move_deps::move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+    };
+
+    #[test]
+    fn test_from_and_to_string() {
+        let struct_tag = StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new("coin").unwrap(),
+            name: Identifier::new("CoinStore").unwrap(),
+            type_params: vec![],
+        };
+        for cursor in [
+            Cursor::version(10),
+            Cursor::version_and_index(10, 3),
+            Cursor::sequence_number(u64::MAX),
+            Cursor::struct_tag(struct_tag),
+            Cursor::table_key(vec![1, 2, 3]),
+            Cursor::creation_number(5),
+        ] {
+            let encoded = cursor.to_string();
+            assert!(encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+            assert_eq!(encoded.parse::<Cursor>().unwrap(), cursor);
+        }
+    }
+
+    #[test]
+    fn test_decode_position() {
+        assert_eq!(Cursor::version(10).into_version().unwrap(), 10);
+        assert_eq!(
+            Cursor::version_and_index(10, 3)
+                .into_version_and_index()
+                .unwrap(),
+            (10, 3)
+        );
+        assert!(Cursor::version_and_index(10, 3).into_version().is_err());
+        assert!(Cursor::sequence_number(10).into_version().is_err());
+        assert!(Cursor::version(10).into_sequence_number().is_err());
+        assert!(Cursor::version(10).into_table_key().is_err());
+        assert_eq!(
+            Cursor::creation_number(5).into_creation_number().unwrap(),
+            5
+        );
+        assert!(Cursor::sequence_number(5).into_creation_number().is_err());
+    }
+
+    #[test]
+    fn test_invalid_cursors() {
+        assert!("not base64!".parse::<Cursor>().is_err());
+        assert!("".parse::<Cursor>().is_err());
+        // A cursor of another version of the encoding.
+        let cursor = base64::encode_config([2, 0, 10], base64::URL_SAFE_NO_PAD);
+        assert!(cursor.parse::<Cursor>().is_err());
+        // A truncated cursor.
+        let cursor = base64::encode_config([1, 1, 10, 0], base64::URL_SAFE_NO_PAD);
+        assert!(cursor.parse::<Cursor>().is_err());
+    }
+}
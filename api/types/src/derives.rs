@@ -23,8 +23,8 @@ use serde_json::json;
 
 use crate::{
     move_types::{MoveAbility, MoveStructValue},
-    Address, EventKey, HashValue, HexEncodedBytes, IdentifierWrapper, MoveModuleId, MoveStructTag,
-    MoveType, ScriptFunctionId, U128, U64,
+    Address, Cursor, EventKey, GasScheduleOverrides, HashValue, HexEncodedBytes, IdentifierWrapper,
+    MoveModuleId, MoveStructTag, MoveType, ScriptFunctionId, U128, U64,
 };
 use indoc::indoc;
 
@@ -63,6 +63,35 @@ impl_poem_type!(
     )
 );
 
+impl_poem_type!(
+    Cursor,
+    "string",
+    (
+        example = Some(serde_json::Value::String("AQEKAAAAAAAAAA".to_string())),
+        description = Some(indoc! {"
+            An opaque position in a paginated list, from which to request the next page.
+
+            Cursors are only valid for the kind of list they were returned for, and must be
+            passed as returned.
+        "})
+    )
+);
+
+impl_poem_type!(
+    GasScheduleOverrides,
+    "string",
+    (
+        example = Some(serde_json::Value::String(
+            "txn.min_transaction_gas_units=1000,txn.gas_unit_scaling_factor=1000".to_string()
+        )),
+        description = Some(indoc! {"
+            Values of gas parameters, by name, as comma separated `name=value` pairs.
+
+            The names are those of the entries of the gas schedule, e.g. `txn.min_transaction_gas_units`.
+        "})
+    )
+);
+
 impl_poem_type!(HashValue, "string", ());
 
 impl_poem_type!(
@@ -279,7 +308,9 @@ impl_poem_type!(
 
 impl_poem_parameter!(
     Address,
+    Cursor,
     EventKey,
+    GasScheduleOverrides,
     HashValue,
     IdentifierWrapper,
     HexEncodedBytes,
@@ -0,0 +1,45 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use aptos_types::ledger_info::LedgerInfo;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// The boundaries of an epoch
+///
+/// An epoch starts right after the reconfiguration ending the previous epoch,
+/// at the timestamp of that reconfiguration. The last version and the end
+/// timestamp are only returned once the epoch has ended.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct Epoch {
+    pub epoch: U64,
+    pub first_version: U64,
+    pub start_timestamp: U64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_version: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_timestamp: Option<U64>,
+}
+
+impl Epoch {
+    /// Builds the epoch from the ledger infos ending the previous epoch, if
+    /// any, and ending the epoch itself, if it has ended.
+    pub fn new(
+        epoch: u64,
+        previous_epoch_ending: Option<&LedgerInfo>,
+        epoch_ending: Option<&LedgerInfo>,
+    ) -> Self {
+        Self {
+            epoch: epoch.into(),
+            first_version: previous_epoch_ending
+                .map_or(0, |ledger_info| ledger_info.version() + 1)
+                .into(),
+            start_timestamp: previous_epoch_ending
+                .map_or(0, LedgerInfo::timestamp_usecs)
+                .into(),
+            last_version: epoch_ending.map(|ledger_info| ledger_info.version().into()),
+            end_timestamp: epoch_ending.map(|ledger_info| ledger_info.timestamp_usecs().into()),
+        }
+    }
+}
@@ -2,31 +2,48 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use poem_openapi::{Enum, Object};
-use serde::Deserialize;
-use std::convert::From;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
 
 use crate::move_types::U64;
 
 /// This is the generic struct we use for all API errors, it contains a string
-/// message and an Aptos API specific error code.
+/// message and an Aptos API specific error code from the registry defined by
+/// AptosErrorCode.
 #[derive(Debug, Deserialize, Object)]
 pub struct AptosError {
+    /// A message describing the error
     pub message: String,
-    pub error_code: Option<AptosErrorCode>,
+    /// The registry code for this error
+    pub error_code: AptosErrorCode,
+    /// The stable numeric value of `error_code`
+    pub code: u32,
+    /// Whether the same request may succeed if it is retried later
+    pub retriable: bool,
+    /// Structured information about the error, e.g. the offending parameter
+    pub details: BTreeMap<String, String>,
+    /// A link to the documentation of `error_code`
+    pub docs_url: String,
     pub aptos_ledger_version: Option<U64>,
 }
 
 impl AptosError {
-    pub fn new(message: String) -> Self {
+    pub fn new<Err: Display>(error: Err, error_code: AptosErrorCode) -> Self {
         Self {
-            message,
-            error_code: None,
+            // Use the alternate format so anyhow errors include their context.
+            message: format!("{:#}", error),
+            error_code,
+            code: error_code.code(),
+            retriable: error_code.is_retriable(),
+            details: BTreeMap::new(),
+            docs_url: error_code.docs_url(),
             aptos_ledger_version: None,
         }
     }
 
-    pub fn error_code(mut self, error_code: AptosErrorCode) -> Self {
-        self.error_code = Some(error_code);
+    pub fn detail<V: Display>(mut self, key: &str, value: V) -> Self {
+        self.details.insert(key.to_string(), value.to_string());
         self
     }
 
@@ -36,16 +53,13 @@ impl AptosError {
     }
 }
 
-impl From<anyhow::Error> for AptosError {
-    fn from(error: anyhow::Error) -> Self {
-        AptosError::new(format!("{:#}", error))
-    }
-}
-
-/// These codes provide more granular error information beyond just the HTTP
-/// status code of the response.
-// Make sure the integer codes increment one by one.
-#[derive(Debug, Deserialize, Enum)]
+/// The registry of codes that provide more granular error information beyond
+/// just the HTTP status code of the response. Every error the API returns
+/// uses one of these codes.
+///
+/// The numeric values are part of the API: clients may match on them, so
+/// never renumber or reuse a code. New codes must be added at the end.
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
 #[oai(rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum AptosErrorCode {
@@ -64,4 +78,177 @@ pub enum AptosErrorCode {
 
     /// The limit param given for paging is invalid.
     InvalidLimitParam = 5,
+
+    /// An unexpected error occurred while serving the request.
+    InternalError = 6,
+
+    /// The request contained a value that could not be understood, e.g. a
+    /// malformed type or an argument that doesn't match its type.
+    InvalidInput = 7,
+
+    /// The web framework rejected the request before it reached the API, e.g.
+    /// because a path or query param or the payload failed to parse.
+    WebFrameworkError = 8,
+
+    /// The requested account does not exist.
+    AccountNotFound = 9,
+
+    /// The requested resource does not exist at the account.
+    ResourceNotFound = 10,
+
+    /// The requested module does not exist at the account.
+    ModuleNotFound = 11,
+
+    /// The requested field does not exist in the struct.
+    StructFieldNotFound = 12,
+
+    /// The requested table or table item does not exist.
+    TableItemNotFound = 13,
+
+    /// The requested ledger version is ahead of the ledger.
+    VersionNotFound = 14,
+
+    /// The requested transaction is neither committed nor pending.
+    TransactionNotFound = 15,
+
+    /// The requested block has not been committed.
+    BlockNotFound = 16,
+
+    /// Mempool has no room for the submitted transaction.
+    MempoolIsFull = 17,
+
+    /// The submitted transaction failed validation.
+    InvalidTransaction = 18,
+
+    /// Mempool did not accept the submitted transaction, e.g. because another
+    /// transaction with the same sequence number is already in mempool.
+    TransactionRejected = 19,
+
+    /// The node is not healthy according to the health check parameters.
+    HealthCheckFailed = 20,
+
+    /// One or more signatures of the submitted transaction do not verify.
+    InvalidSignature = 21,
+
+    /// There is no state value stored under the requested state key.
+    StateValueNotFound = 22,
+
+    /// The developer API is not enabled on this node.
+    DeveloperApiDisabled = 23,
+
+    /// The submitted Move package failed to compile.
+    MoveCompilationFailed = 24,
+
+    /// The request body is larger than the node accepts.
+    PayloadTooLarge = 25,
+
+    /// The submitted transaction is for another chain than the node's.
+    ChainIdMismatch = 26,
+
+    /// The internal indexer, or the index of it the request relies on, is not
+    /// enabled on this node.
+    IndexerDisabled = 27,
+
+    /// The requested epoch has not started.
+    EpochNotFound = 28,
+
+    /// There is no multisig signing session with the requested ID, or it
+    /// expired.
+    MultisigSessionNotFound = 29,
+
+    /// The node holds as many multisig signing sessions as it may.
+    MultisigSessionLimitReached = 30,
+
+    /// The cursor param given for paging is invalid, e.g. because it was
+    /// returned for another list.
+    InvalidCursorParam = 31,
+
+    /// The submitted transaction matches a rule of the submission filter of
+    /// the node, e.g. because its sender is denied.
+    TransactionRejectedByFilter = 32,
+
+    /// The requested ledger version is older than the oldest version the node
+    /// keeps, which has been pruned.
+    VersionPruned = 33,
+
+    /// The faucet is not enabled on this node, which it never is on mainnet.
+    FaucetDisabled = 34,
+
+    /// The sender of the submitted transaction has as many transactions
+    /// pending in mempool as the node holds per account.
+    TooManyPendingTransactions = 35,
+
+    /// The requested transaction was evicted from mempool before it was
+    /// committed, e.g. because mempool was full, so it won't be committed
+    /// unless it is submitted again.
+    TransactionEvicted = 36,
+}
+
+/// The documentation of every error code, with a section per code.
+const ERROR_CODES_DOCS_URL: &str =
+    "https://github.com/aptos-labs/aptos-core/blob/main/api/doc/error_codes.md";
+
+impl AptosErrorCode {
+    /// The stable numeric value of the code.
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// The link to the section documenting the code, which is named after it.
+    pub fn docs_url(self) -> String {
+        let name = serde_json::to_value(self).expect("Error codes serialize to their names");
+        format!(
+            "{}#{}",
+            ERROR_CODES_DOCS_URL,
+            name.as_str().expect("Error codes serialize to strings")
+        )
+    }
+
+    /// Whether a request that failed with this code may succeed if sent again
+    /// later without changes, e.g. because the node caught up in the meantime.
+    pub fn is_retriable(self) -> bool {
+        matches!(
+            self,
+            AptosErrorCode::ReadFromStorageError
+                | AptosErrorCode::VersionNotFound
+                | AptosErrorCode::TransactionNotFound
+                | AptosErrorCode::BlockNotFound
+                | AptosErrorCode::MempoolIsFull
+                | AptosErrorCode::HealthCheckFailed
+                | AptosErrorCode::MultisigSessionLimitReached
+                | AptosErrorCode::TooManyPendingTransactions
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::{AptosError, AptosErrorCode};
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        // Clients rely on these values, so changing any of them is a breaking change.
+        assert_eq!(AptosErrorCode::ReadFromStorageError.code(), 1);
+        assert_eq!(AptosErrorCode::InvalidLimitParam.code(), 5);
+        assert_eq!(AptosErrorCode::WebFrameworkError.code(), 8);
+        assert_eq!(AptosErrorCode::HealthCheckFailed.code(), 20);
+        assert_eq!(AptosErrorCode::InvalidSignature.code(), 21);
+    }
+
+    #[test]
+    fn test_new_error() {
+        let error = AptosError::new(
+            anyhow::anyhow!("inner").context("outer"),
+            AptosErrorCode::MempoolIsFull,
+        )
+        .detail("capacity", 100);
+        assert_eq!(error.message, "outer: inner");
+        assert_eq!(error.code, 17);
+        assert!(error.retriable);
+        assert_eq!(error.details["capacity"], "100");
+        assert_eq!(
+            error.docs_url,
+            "https://github.com/aptos-labs/aptos-core/blob/main/api/doc/error_codes.md#mempool_is_full"
+        );
+    }
 }
@@ -1,12 +1,72 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{Address, MoveType, U64};
+use poem_openapi::Object;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::{fmt, str::FromStr};
 
 #[derive(Clone, Debug, PartialEq, Copy)]
 pub struct EventKey(pub aptos_types::event::EventKey);
 
+impl EventKey {
+    /// The key of the event stream the account created with the given creation number.
+    pub fn new(account_address: Address, creation_number: U64) -> Self {
+        Self(aptos_types::event::EventKey::new(
+            creation_number.0,
+            account_address.into(),
+        ))
+    }
+
+    pub fn account_address(&self) -> Address {
+        self.0.get_creator_address().into()
+    }
+
+    pub fn creation_number(&self) -> U64 {
+        self.0.get_creation_number().into()
+    }
+}
+
+/// The account address and creation number identifying an event stream, which
+/// replace the event key.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct EventGuid {
+    pub creation_number: U64,
+    pub account_address: Address,
+}
+
+impl From<EventKey> for EventGuid {
+    fn from(key: EventKey) -> Self {
+        Self {
+            creation_number: key.creation_number(),
+            account_address: key.account_address(),
+        }
+    }
+}
+
+impl From<EventGuid> for EventKey {
+    fn from(guid: EventGuid) -> Self {
+        Self::new(guid.account_address, guid.creation_number)
+    }
+}
+
+/// An event stream created by an account, which can be listed without knowing
+/// the resource and field its event handle is in
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct EventStream {
+    pub creation_number: U64,
+    pub key: EventKey,
+    /// The number of events emitted to the stream so far
+    pub counter: U64,
+    /// The sequence number of the latest event, if any was emitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_sequence_number: Option<U64>,
+    /// The type of the events, known once any was emitted
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    #[oai(rename = "type")]
+    pub typ: Option<MoveType>,
+}
+
 impl From<aptos_types::event::EventKey> for EventKey {
     fn from(val: aptos_types::event::EventKey) -> Self {
         Self(val)
@@ -53,7 +113,7 @@ impl fmt::Display for EventKey {
 
 #[cfg(test)]
 mod tests {
-    use crate::event_key::EventKey;
+    use crate::event_key::{EventGuid, EventKey};
 
     use serde_json::{json, Value};
 
@@ -74,4 +134,16 @@ mod tests {
         let val: Value = serde_json::to_value(hash).unwrap();
         assert_eq!(val, json!(hex));
     }
+
+    #[test]
+    fn test_from_and_to_guid() {
+        let key: EventKey =
+            "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18"
+                .parse()
+                .unwrap();
+        let guid = EventGuid::from(key);
+        assert_eq!(guid.account_address, "0xa550c18".parse().unwrap());
+        assert_eq!(guid.creation_number, 5u64.into());
+        assert_eq!(EventKey::from(guid), key);
+    }
 }
@@ -0,0 +1,26 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HashValue, U64};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Request to fund an account from the faucet of the node
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct FundAccountRequest {
+    /// The account to fund, which is created if it doesn't exist
+    pub address: Address,
+    /// The amount of coins to mint to the account, capped to the maximum
+    /// amount the faucet funds per request
+    pub amount: U64,
+}
+
+/// The transactions the faucet submitted to fund an account
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct FundAccountResponse {
+    /// The amount of coins minted to the account
+    pub amount: U64,
+    /// The hashes of the transactions creating the account, if it didn't
+    /// exist, and minting the coins, in the order they were submitted
+    pub txn_hashes: Vec<HashValue>,
+}
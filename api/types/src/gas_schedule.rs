@@ -0,0 +1,137 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use anyhow::{bail, format_err};
+use aptos_types::on_chain_config::{GasSchedule as OnChainGasSchedule, Version};
+use poem_openapi::Object;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// The gas schedule in effect on chain
+///
+/// Entries are named after the gas parameters they set, e.g.
+/// `txn.min_transaction_gas_units`, and listed in the order they are stored
+/// on chain. The feature version is the version of the Aptos software the
+/// schedule applies to, which decides how the parameters are charged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct GasSchedule {
+    pub feature_version: U64,
+    pub entries: Vec<GasScheduleEntry>,
+}
+
+/// A gas parameter and its value
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct GasScheduleEntry {
+    pub name: String,
+    pub value: U64,
+}
+
+impl GasSchedule {
+    pub fn new(gas_schedule: OnChainGasSchedule, version: Version) -> Self {
+        Self {
+            feature_version: version.major.into(),
+            entries: gas_schedule
+                .entries
+                .into_iter()
+                .map(|(name, value)| GasScheduleEntry {
+                    name,
+                    value: value.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Values to charge gas parameters by in place of those of the on-chain gas
+/// schedule, keyed by the names of the parameters, e.g.
+/// `txn.min_transaction_gas_units`
+///
+/// In query params, they are given as comma separated `name=value` pairs.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct GasScheduleOverrides(pub BTreeMap<String, u64>);
+
+impl fmt::Display for GasScheduleOverrides {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let overrides = self
+            .0
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>();
+        write!(f, "{}", overrides.join(","))
+    }
+}
+
+impl FromStr for GasScheduleOverrides {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut overrides = BTreeMap::new();
+        for pair in s.split(',').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').ok_or_else(|| {
+                format_err!(
+                    "invalid gas schedule override {:?}, expected `name=value`",
+                    pair
+                )
+            })?;
+            let value = value.parse::<u64>().map_err(|_| {
+                format_err!(
+                    "invalid value {:?} of gas parameter {}, expected a u64",
+                    value,
+                    name
+                )
+            })?;
+            if overrides.insert(name.to_string(), value).is_some() {
+                bail!("gas parameter {} is overridden more than once", name);
+            }
+        }
+        Ok(Self(overrides))
+    }
+}
+
+impl Serialize for GasScheduleOverrides {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GasScheduleOverrides {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let overrides = <String>::deserialize(deserializer)?;
+        overrides.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::GasScheduleOverrides;
+
+    #[test]
+    fn test_parse_gas_schedule_overrides() {
+        let overrides: GasScheduleOverrides = "txn.min_transaction_gas_units=10,instr.nop=2"
+            .parse()
+            .unwrap();
+        assert_eq!(overrides.0.len(), 2);
+        assert_eq!(overrides.0["txn.min_transaction_gas_units"], 10);
+        assert_eq!(
+            overrides.to_string(),
+            "instr.nop=2,txn.min_transaction_gas_units=10"
+        );
+        assert_eq!(
+            overrides
+                .to_string()
+                .parse::<GasScheduleOverrides>()
+                .unwrap(),
+            overrides
+        );
+
+        assert!("instr.nop".parse::<GasScheduleOverrides>().is_err());
+        assert!("instr.nop=-1".parse::<GasScheduleOverrides>().is_err());
+        assert!("instr.nop=1,instr.nop=2"
+            .parse::<GasScheduleOverrides>()
+            .is_err());
+    }
+}
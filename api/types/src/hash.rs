@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::hex_parser::HexDigits;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     fmt,
@@ -26,12 +27,12 @@ impl From<HashValue> for aptos_crypto::hash::HashValue {
 impl FromStr for HashValue {
     type Err = anyhow::Error;
 
+    /// Parses a hash in its long form, with or without the `0x` prefix.
     fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
-        if let Some(hex) = s.strip_prefix("0x") {
-            Ok(hex.parse::<aptos_crypto::hash::HashValue>()?.into())
-        } else {
-            Ok(s.parse::<aptos_crypto::hash::HashValue>()?.into())
-        }
+        let bytes = HexDigits::new(s)
+            .parse(2 * aptos_crypto::hash::HashValue::LENGTH)
+            .map_err(|err| anyhow::format_err!("invalid hash {:?}: {}", s, err))?;
+        Ok(aptos_crypto::hash::HashValue::new(bytes).into())
     }
 }
 
@@ -100,4 +101,20 @@ mod tests {
         let val: Value = serde_json::to_value(hash).unwrap();
         assert_eq!(val, json!(hex));
     }
+
+    #[test]
+    fn test_from_invalid_string() {
+        assert_eq!(
+            "0x1".parse::<HashValue>().unwrap_err().to_string(),
+            "invalid hash \"0x1\": expected 64 hex digits, found 1"
+        );
+        let hash = "0xb78e1ba6fa7f7b3a3f3ac2a31e6675d84f2261c711c3b438a252f648b26df3eg";
+        assert_eq!(
+            hash.parse::<HashValue>().unwrap_err().to_string(),
+            format!(
+                "invalid hash {:?}: invalid character 'g' at position 65, expected a hex digit",
+                hash
+            )
+        );
+    }
 }
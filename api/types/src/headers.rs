@@ -5,6 +5,8 @@ pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_OLDEST_VERSION: &str = "X-Aptos-Ledger-Oldest-Version";
+/// Request header pinning the reads of a request to a ledger version.
+pub const X_APTOS_LEDGER_VERSION_PIN: &str = "X-Aptos-Ledger-Version-Pin";
 pub const X_APTOS_BLOCK_HEIGHT: &str = "X-Aptos-Block-Height";
 pub const X_APTOS_OLDEST_BLOCK_HEIGHT: &str = "X-Aptos-Oldest-Block-Height";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
@@ -0,0 +1,188 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The parser of the hex strings addresses and hashes are given as, in paths,
+//! query params and JSON alike, which points at what is wrong with a string
+//! that doesn't parse, so the 400 returned for it says how to fix it.
+
+use std::fmt;
+
+/// Why a string didn't parse as hex. Positions are of characters, counted
+/// from 0 at the start of the string, including its `0x` prefix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HexParseError {
+    /// A character is not a hex digit.
+    InvalidCharacter { character: char, position: usize },
+    /// There are too few or too many digits.
+    WrongNumberOfDigits {
+        num_digits: usize,
+        min_digits: usize,
+        max_digits: usize,
+    },
+    /// The case of a letter doesn't match the checksum of the value.
+    InvalidChecksum {
+        character: char,
+        position: usize,
+        expected: char,
+    },
+}
+
+impl fmt::Display for HexParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexParseError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid character {:?} at position {}, expected a hex digit",
+                character, position
+            ),
+            HexParseError::WrongNumberOfDigits {
+                num_digits,
+                min_digits,
+                max_digits,
+            } if min_digits == max_digits => write!(
+                f,
+                "expected {} hex digits, found {}",
+                max_digits, num_digits
+            ),
+            HexParseError::WrongNumberOfDigits {
+                num_digits,
+                min_digits,
+                max_digits,
+            } => write!(
+                f,
+                "expected between {} and {} hex digits, found {}",
+                min_digits, max_digits, num_digits
+            ),
+            HexParseError::InvalidChecksum {
+                character,
+                position,
+                expected,
+            } => write!(
+                f,
+                "the case of {:?} at position {} doesn't match the checksum, expected {:?}",
+                character, position, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HexParseError {}
+
+/// The hex digits of a string, after its `0x` prefix if it has one.
+pub(crate) struct HexDigits<'a> {
+    pub digits: &'a str,
+    /// The position of the first digit in the string.
+    pub offset: usize,
+}
+
+impl<'a> HexDigits<'a> {
+    pub fn new(s: &'a str) -> Self {
+        match s.strip_prefix("0x") {
+            Some(digits) => Self { digits, offset: 2 },
+            None => Self {
+                digits: s,
+                offset: 0,
+            },
+        }
+    }
+
+    pub fn has_prefix(&self) -> bool {
+        self.offset != 0
+    }
+
+    /// Whether the digits include both lowercase and uppercase letters, which
+    /// is what opts a string into having its checksum validated.
+    pub fn is_mixed_case(&self) -> bool {
+        self.digits.bytes().any(|b| b.is_ascii_lowercase())
+            && self.digits.bytes().any(|b| b.is_ascii_uppercase())
+    }
+
+    /// Parses the digits into `N` bytes in a single pass, right-aligned, so
+    /// that fewer digits than `2 * N` are zero-padded on the left. Fewer than
+    /// `min_digits` digits are rejected.
+    pub fn parse<const N: usize>(&self, min_digits: usize) -> Result<[u8; N], HexParseError> {
+        let max_digits = 2 * N;
+        let mut num_digits = 0;
+        for (i, character) in self.digits.chars().enumerate() {
+            if !character.is_ascii_hexdigit() {
+                return Err(HexParseError::InvalidCharacter {
+                    character,
+                    position: self.offset + i,
+                });
+            }
+            num_digits += 1;
+        }
+        if num_digits < min_digits || num_digits > max_digits {
+            return Err(HexParseError::WrongNumberOfDigits {
+                num_digits,
+                min_digits,
+                max_digits,
+            });
+        }
+
+        let mut bytes = [0u8; N];
+        let padding = max_digits - num_digits;
+        for (i, digit) in self.digits.bytes().enumerate() {
+            let nibble = (digit as char).to_digit(16).expect("Checked above") as u8;
+            let index = padding + i;
+            if index % 2 == 0 {
+                bytes[index / 2] |= nibble << 4;
+            } else {
+                bytes[index / 2] |= nibble;
+            }
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HexDigits, HexParseError};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(HexDigits::new("0x1").parse::<2>(1), Ok([0, 1]));
+        assert_eq!(HexDigits::new("abc").parse::<2>(1), Ok([0x0a, 0xbc]));
+        assert_eq!(HexDigits::new("0xABcd").parse::<2>(4), Ok([0xab, 0xcd]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            HexDigits::new("0x1g").parse::<2>(1),
+            Err(HexParseError::InvalidCharacter {
+                character: 'g',
+                position: 3
+            })
+        );
+        assert_eq!(
+            HexDigits::new("1é").parse::<2>(1),
+            Err(HexParseError::InvalidCharacter {
+                character: 'é',
+                position: 1
+            })
+        );
+        assert_eq!(
+            HexDigits::new("0x").parse::<2>(1),
+            Err(HexParseError::WrongNumberOfDigits {
+                num_digits: 0,
+                min_digits: 1,
+                max_digits: 4
+            })
+        );
+        assert_eq!(
+            HexDigits::new("12345")
+                .parse::<2>(4)
+                .unwrap_err()
+                .to_string(),
+            "expected 4 hex digits, found 5"
+        );
+        assert_eq!(
+            HexDigits::new("0x").parse::<2>(1).unwrap_err().to_string(),
+            "expected between 1 and 4 hex digits, found 0"
+        );
+    }
+}
@@ -1,8 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::LedgerInfo;
+use crate::{LedgerInfo, U64};
 use aptos_config::config::RoleType;
+use aptos_types::{
+    chain_id::{ChainId, NamedChain},
+    transaction::authenticator_registry::AUTHENTICATOR_REGISTRY,
+};
 use poem_openapi::Object as PoemObject;
 use serde::{Deserialize, Serialize};
 
@@ -16,14 +20,55 @@ pub struct IndexResponse {
     #[oai(flatten)]
     #[serde(flatten)]
     pub ledger_info: LedgerInfo,
+    /// The name of the chain, e.g. `testnet`, if its chain ID is a known one
+    pub chain_name: Option<String>,
     pub node_role: RoleType,
+    /// The signature schemes transactions may be signed with, e.g. `ed25519`
+    #[serde(default)]
+    pub signature_schemes: Vec<String>,
+    /// The health of the node, if requested with `with_health`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<NodeHealth>,
+}
+
+/// Details of the health of the node, which take more work to gather than
+/// the rest of the index, so they are only returned when requested
+#[derive(Clone, Debug, Deserialize, PartialEq, PoemObject, Serialize)]
+pub struct NodeHealth {
+    /// The number of transactions in the mempool of the node
+    pub mempool_size: U64,
+    /// The number of transactions the mempool of the node may hold
+    pub mempool_capacity: U64,
+    /// How far behind the clock of the node the timestamp of its latest
+    /// ledger info is, in microseconds, which is how far behind the chain the
+    /// node is while it is syncing
+    pub state_sync_lag_usecs: U64,
+    /// The version of the API the node runs
+    pub api_version: String,
+    /// The optional features of the API enabled on the node, e.g.
+    /// `developer_api`
+    pub features: Vec<String>,
 }
 
 impl IndexResponse {
     pub fn new(ledger_info: LedgerInfo, node_role: RoleType) -> IndexResponse {
+        let chain_name = NamedChain::from_chain_id(&ChainId::new(ledger_info.chain_id))
+            .ok()
+            .map(|chain| chain.to_string());
         Self {
             ledger_info,
+            chain_name,
             node_role,
+            signature_schemes: AUTHENTICATOR_REGISTRY
+                .schemes()
+                .map(|scheme| scheme.name().to_string())
+                .collect(),
+            health: None,
         }
     }
+
+    pub fn with_health(mut self, health: NodeHealth) -> Self {
+        self.health = Some(health);
+        self
+    }
 }
@@ -3,49 +3,92 @@
 
 mod account;
 mod address;
+mod balance;
 mod block;
 mod bytecode;
 mod convert;
+mod cursor;
 mod derives;
+mod epoch;
 mod error;
 mod event_key;
+mod faucet;
+mod gas_schedule;
 mod hash;
 mod headers;
+mod hex_parser;
 mod index;
 mod ledger_info;
 pub mod mime_types;
+mod module_cache;
 mod move_types;
+mod move_unit_test;
+mod multisig;
+mod on_chain_config;
+mod payload_builder;
+mod script_composition;
+mod script_execution;
+mod stake;
 mod table;
 mod transaction;
+mod type_tag_parser;
+mod validator;
 mod wrappers;
 
-pub use account::AccountData;
+pub use account::{
+    AccountData, AccountDiff, AccountKeyHistory, AccountResources, AccountResourcesRequest,
+    AccountTransactionQueue, AuthenticationKeyChange, ModifiedResource, SequenceNumberRange,
+};
 pub use address::Address;
-pub use block::Block;
-pub use block::BlockInfo;
+pub use balance::{AccountBalances, CoinBalance, CoinHolder, CoinHolders};
+pub use block::{
+    Block, BlockFeeStats, BlockInfo, BlockProposal, BlockTransactions, TransactionSummary,
+    TransactionSummaryType,
+};
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_utf8_string, AsConverter, MoveConverter};
+pub use cursor::Cursor;
+pub use epoch::Epoch;
 pub use error::{AptosError, AptosErrorCode};
-pub use event_key::EventKey;
+pub use event_key::{EventGuid, EventKey, EventStream};
+pub use faucet::{FundAccountRequest, FundAccountResponse};
+pub use gas_schedule::{GasSchedule, GasScheduleEntry, GasScheduleOverrides};
 pub use hash::HashValue;
 pub use headers::*;
-pub use index::IndexResponse;
+pub use index::{IndexResponse, NodeHealth};
 pub use ledger_info::LedgerInfo;
+pub use module_cache::ModuleCache;
 pub use move_types::{
     HexEncodedBytes, MoveAbility, MoveFunction, MoveFunctionGenericTypeParam,
-    MoveFunctionVisibility, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType, MoveValue,
-    ScriptFunctionId, U128, U64,
+    MoveFunctionVisibility, MoveModule, MoveModuleBytecode, MoveModuleDisassembly, MoveModuleId,
+    MoveModuleVerification, MoveResource, MoveScriptBytecode, MoveStruct, MoveStructField,
+    MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U128, U64,
+};
+pub use move_unit_test::{
+    MoveFunctionCoverage, MoveNamedAddress, MoveSourceFile, MoveUnitTestOutcome,
+    MoveUnitTestRequest, MoveUnitTestResult, MoveUnitTestStatus,
+};
+pub use multisig::{AddMultisigSignatureRequest, CreateMultisigSessionRequest, MultisigSession};
+pub use on_chain_config::{ConsensusConfig, ExecutionConfig, OnChainConfigs};
+pub use payload_builder::{
+    build_script_function_payload, encode_argument, encode_script_function_arguments,
+    validate_type_arguments,
 };
-pub use table::TableItemRequest;
+pub use script_composition::ScriptCompositionRequest;
+pub use script_execution::{ResourceOverride, ScriptExecutionRequest, ScriptExecutionResult};
+pub use stake::{StakePoolSummary, StakeRewards};
+pub use table::{RawStateValueRequest, TableItemRequest, TableItems};
 pub use transaction::{
     AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource, DeleteTableItem,
     DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest, Event, GenesisPayload,
-    GenesisTransaction, MultiEd25519Signature, PendingTransaction, ScriptFunctionPayload,
-    ScriptPayload, ScriptWriteSet, SubmitTransactionRequest, Transaction, TransactionData,
+    GenesisTransaction, MultiEd25519Signature, OnChainTransactionOutput, PendingTransaction,
+    PrecheckResult, ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, SignerSigningMessage,
+    StateChange, StateDiff, StorageFee, SubmitTransactionRequest, Transaction, TransactionData,
     TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSignature, TransactionSigningMessage, UserCreateSigningMessageRequest,
-    UserTransaction, UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet,
-    WriteSetChange, WriteSetPayload, WriteTableItem,
+    TransactionPrecheck, TransactionSignature, TransactionSignerRole, TransactionSigningMessage,
+    TransactionTypeFilter, UserCreateSigningMessageRequest, UserTransaction,
+    UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
+    WriteSetPayload, WriteTableItem,
 };
+pub use validator::{EpochChange, Validator, ValidatorSet};
 pub use wrappers::IdentifierWrapper;
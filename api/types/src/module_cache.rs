@@ -0,0 +1,154 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use aptos_infallible::Mutex;
+use aptos_types::transaction::Version;
+use lru::LruCache;
+use move_deps::{move_binary_format::CompiledModule, move_core_types::language_storage::ModuleId};
+use std::sync::Arc;
+
+/// Deserialized modules shared by the converters of all requests, so that resolving the types
+/// of hot modules doesn't deserialize them again for every request.
+///
+/// Modules are cached per bucket of ledger versions, so requests reading old versions of an
+/// upgraded module don't keep replacing the entry of requests reading the latest ones. A cached
+/// module is only used if its bytes are the ones read at the version of the request, so a module
+/// upgraded within a bucket replaces its stale entry instead of being served from it. When the
+/// cache is full, the least recently used module is evicted.
+pub struct ModuleCache {
+    version_bucket_size: u64,
+    modules: Mutex<LruCache<(ModuleId, u64), CachedModule>>,
+}
+
+struct CachedModule {
+    bytes: Vec<u8>,
+    module: Arc<CompiledModule>,
+}
+
+impl ModuleCache {
+    pub fn new(capacity: usize, version_bucket_size: u64) -> Self {
+        Self {
+            version_bucket_size: version_bucket_size.max(1),
+            modules: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the module with the given bytes, read at the given version, deserializing it
+    /// unless it is cached.
+    pub fn get_or_deserialize(
+        &self,
+        module_id: &ModuleId,
+        version: Version,
+        bytes: Vec<u8>,
+    ) -> Result<Arc<CompiledModule>> {
+        let key = (module_id.clone(), version / self.version_bucket_size);
+        if let Some(cached) = self.modules.lock().get(&key) {
+            if cached.bytes == bytes {
+                return Ok(cached.module.clone());
+            }
+        }
+
+        let module = Arc::new(
+            CompiledModule::deserialize(&bytes)
+                .map_err(|e| format_err!("Failed to deserialize module {}: {:?}", module_id, e))?,
+        );
+        let mut modules = self.modules.lock();
+        if modules.cap() > 0 {
+            modules.put(
+                key,
+                CachedModule {
+                    bytes,
+                    module: module.clone(),
+                },
+            );
+        }
+        Ok(module)
+    }
+
+    pub fn len(&self) -> usize {
+        self.modules.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModuleCache;
+    use move_deps::{
+        move_binary_format::{file_format::empty_module, CompiledModule},
+        move_core_types::identifier::Identifier,
+    };
+    use std::sync::Arc;
+
+    fn serialize(module: &CompiledModule) -> Vec<u8> {
+        let mut bytes = vec![];
+        module.serialize(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_cached_module_is_shared() {
+        let cache = ModuleCache::new(10, 100);
+        let module = empty_module();
+        let bytes = serialize(&module);
+
+        let first = cache
+            .get_or_deserialize(&module.self_id(), 1, bytes.clone())
+            .unwrap();
+        let second = cache
+            .get_or_deserialize(&module.self_id(), 99, bytes.clone())
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Another version bucket has its own entry.
+        let third = cache
+            .get_or_deserialize(&module.self_id(), 100, bytes)
+            .unwrap();
+        assert!(!Arc::ptr_eq(&first, &third));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_upgraded_module_replaces_cached_module() {
+        let cache = ModuleCache::new(10, 100);
+        let module = empty_module();
+        let old = cache
+            .get_or_deserialize(&module.self_id(), 1, serialize(&module))
+            .unwrap();
+
+        let mut upgraded = module.clone();
+        upgraded
+            .identifiers
+            .push(Identifier::new("upgraded").unwrap());
+        let new = cache
+            .get_or_deserialize(&module.self_id(), 2, serialize(&upgraded))
+            .unwrap();
+        assert_eq!(new.as_ref(), &upgraded);
+        assert_ne!(old, new);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_disabled_cache() {
+        let cache = ModuleCache::new(0, 100);
+        let module = empty_module();
+        cache
+            .get_or_deserialize(&module.self_id(), 1, serialize(&module))
+            .unwrap();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_module() {
+        let cache = ModuleCache::new(10, 100);
+        let module = empty_module();
+        assert!(cache
+            .get_or_deserialize(&module.self_id(), 1, vec![1, 2, 3])
+            .is_err());
+        assert!(cache.is_empty());
+    }
+}
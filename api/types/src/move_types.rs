@@ -1,33 +1,41 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Address, Bytecode, IdentifierWrapper};
+use crate::{type_tag_parser, Address, Bytecode, HashValue, IdentifierWrapper};
 use anyhow::{bail, format_err};
 use aptos_types::{account_config::CORE_CODE_ADDRESS, event::EventKey, transaction::Module};
 use move_deps::{
     move_binary_format::{
         access::ModuleAccess,
+        binary_views::BinaryIndexedView,
         file_format::{
             Ability, AbilitySet, CompiledModule, CompiledScript, StructTypeParameter, Visibility,
         },
     },
+    move_bytecode_source_map::{mapping::SourceMapping, source_map::SourceMap},
     move_core_types,
     move_core_types::{
         account_address::AccountAddress,
         identifier::Identifier,
         language_storage::{ModuleId, StructTag, TypeTag},
-        parser::{parse_struct_tag, parse_type_tag},
+        parser::parse_type_tag,
         transaction_argument::TransactionArgument,
     },
+    move_disassembler::disassembler::{Disassembler, DisassemblerOptions},
+    move_ir_types::location::Spanned,
     move_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue},
 };
 
 use poem_openapi::{Enum, Object, Union};
-use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serde::{
+    de::{Error as _, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 use std::{
     collections::BTreeMap,
     convert::{From, Into, TryFrom, TryInto},
     fmt,
+    marker::PhantomData,
     result::Result,
     str::FromStr,
 };
@@ -95,8 +103,35 @@ impl<'de> Deserialize<'de> for U64 {
     where
         D: Deserializer<'de>,
     {
-        let s = <String>::deserialize(deserializer)?;
-        s.parse().map_err(D::Error::custom)
+        deserializer
+            .deserialize_any(IntegerVisitor::<u64>(PhantomData))
+            .map(U64)
+    }
+}
+
+/// Integers are serialized as strings, as JSON numbers can't represent all
+/// u64 values in JavaScript, but numbers are accepted as input as well.
+struct IntegerVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for IntegerVisitor<T>
+where
+    T: FromStr + TryFrom<u64>,
+    <T as FromStr>::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an unsigned integer, as a string or a number")
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<T, E> {
+        T::try_from(value).map_err(|_| E::custom(format!("{} is out of range", value)))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .parse()
+            .map_err(|e| E::custom(format!("invalid integer {:?}: {}", value, e)))
     }
 }
 
@@ -150,10 +185,9 @@ impl<'de> Deserialize<'de> for U128 {
     where
         D: Deserializer<'de>,
     {
-        let s = <String>::deserialize(deserializer)?;
-        let data = s.parse::<u128>().map_err(D::Error::custom)?;
-
-        Ok(U128(data))
+        deserializer
+            .deserialize_any(IntegerVisitor::<u128>(PhantomData))
+            .map(U128)
     }
 }
 
@@ -386,7 +420,9 @@ impl FromStr for MoveStructTag {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
-        Ok(parse_struct_tag(s)?.into())
+        let tag = type_tag_parser::parse_struct_tag(s)
+            .map_err(|err| format_err!("invalid struct tag {:?}: {}", s, err))?;
+        Ok(tag.into())
     }
 }
 
@@ -920,6 +956,71 @@ impl MoveModuleBytecode {
         }
         Ok(self)
     }
+
+    /// Disassembles the module, naming its locals and parameters as in the
+    /// given source map if there is one and it matches the module.
+    pub fn disassemble(
+        &self,
+        source_map: Option<SourceMap>,
+    ) -> anyhow::Result<MoveModuleDisassembly> {
+        let module = CompiledModule::deserialize(self.bytecode.inner())?;
+        let view = BinaryIndexedView::Module(&module);
+        if let Some(source_map) = source_map {
+            // A source map of another build of the module fails to disassemble
+            // the module, in which case it is disassembled without it.
+            if let Ok(disassembly) = disassemble(SourceMapping::new(source_map, view)) {
+                return Ok(MoveModuleDisassembly {
+                    disassembly,
+                    with_source_map: true,
+                });
+            }
+        }
+        let source_mapping = SourceMapping::new_from_view(view, Spanned::unsafe_no_loc(()).loc)?;
+        Ok(MoveModuleDisassembly {
+            disassembly: disassemble(source_mapping)?,
+            with_source_map: false,
+        })
+    }
+}
+
+fn disassemble(source_mapping: SourceMapping) -> anyhow::Result<String> {
+    let mut options = DisassemblerOptions::new();
+    options.print_code = true;
+    Disassembler::new(source_mapping, options).disassemble()
+}
+
+/// The textual disassembly of the bytecode of a module
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveModuleDisassembly {
+    pub disassembly: String,
+    /// Whether the disassembly uses the source map published with the module
+    pub with_source_map: bool,
+}
+
+/// Whether the bytecode of a module is the one its publisher attests to have
+/// compiled from a source
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveModuleVerification {
+    /// Whether the module was published with an attestation of its source
+    pub attested: bool,
+    /// The version of the toolchain the publisher attests compiled the module
+    pub toolchain_version: Option<String>,
+    /// The SHA3-256 hash of the source the publisher attests the module was
+    /// compiled from
+    pub source_hash: Option<HashValue>,
+    /// Whether the bytecode of the module hashes to the attested hash. This
+    /// is false if the module was upgraded without attesting its new source.
+    pub bytecode_matches: bool,
+    /// Whether the source published along with the module hashes to the
+    /// attested hash, if the source was published
+    pub published_source_matches: Option<bool>,
+    /// Whether the attested source hash is the one requested, if one was
+    pub source_hash_matches: Option<bool>,
+    /// Whether the attested toolchain version is the one requested, if one was
+    pub toolchain_version_matches: Option<bool>,
+    /// Whether the module is attested, its bytecode matches the attestation
+    /// and nothing requested or published contradicts the attestation
+    pub verified: bool,
 }
 
 impl From<Module> for MoveModuleBytecode {
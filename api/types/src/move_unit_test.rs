@@ -0,0 +1,86 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, IdentifierWrapper, MoveModuleId, U64};
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+/// Request to run the Move unit tests of a package
+///
+/// The package is compiled in test mode against the Aptos framework the node
+/// was built with, so its sources may use the framework's named addresses,
+/// e.g. `aptos_framework` and `std`, without declaring them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveUnitTestRequest {
+    /// The Move source files of the package
+    pub sources: Vec<MoveSourceFile>,
+    /// Values of the named addresses the sources use, other than those of the
+    /// framework
+    #[serde(default)]
+    #[oai(default)]
+    pub named_addresses: Vec<MoveNamedAddress>,
+    /// Only run the tests whose fully qualified name contains this string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+/// A Move source file of a package
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveSourceFile {
+    /// File name, without the `.move` extension
+    pub name: String,
+    pub source: String,
+}
+
+/// A named address and its value
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveNamedAddress {
+    pub name: String,
+    pub address: Address,
+}
+
+/// The outcome of running the Move unit tests of a package
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveUnitTestResult {
+    /// Whether every test that was run passed
+    pub success: bool,
+    pub tests: Vec<MoveUnitTestOutcome>,
+    /// Instruction coverage of every function of the package
+    ///
+    /// Coverage is computed from the Move VM's execution trace, so it is only
+    /// returned by nodes started with the `MOVE_VM_TRACE` environment variable
+    /// set to the path of the trace file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<Vec<MoveFunctionCoverage>>,
+}
+
+/// The outcome of a single Move unit test
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveUnitTestOutcome {
+    /// Fully qualified name of the test, e.g. `0x1::coin::test_transfer`
+    pub name: String,
+    pub status: MoveUnitTestStatus,
+    /// Why the test failed, e.g. the abort code and location of a failed
+    /// assertion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MoveUnitTestStatus {
+    Pass,
+    Fail,
+    /// The test ran out of its instruction budget
+    Timeout,
+}
+
+/// How many of the instructions of a function the tests executed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MoveFunctionCoverage {
+    pub module: MoveModuleId,
+    pub function: IdentifierWrapper,
+    pub covered_instructions: U64,
+    pub total_instructions: U64,
+}
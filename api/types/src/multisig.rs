@@ -0,0 +1,44 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HashValue, HexEncodedBytes, UserTransactionRequestInner, U64};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Request to start signing a transaction of a MultiEd25519 account
+///
+/// The public keys and threshold are those of the MultiEd25519 key of the
+/// sender.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct CreateMultisigSessionRequest {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub transaction: UserTransactionRequestInner,
+    pub public_keys: Vec<HexEncodedBytes>,
+    pub threshold: u8,
+}
+
+/// A transaction of a MultiEd25519 account being signed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct MultisigSession {
+    pub id: HashValue,
+    pub sender: Address,
+    /// The message each key signs
+    pub signing_message: HexEncodedBytes,
+    pub public_keys: Vec<HexEncodedBytes>,
+    pub threshold: u8,
+    /// The indices, in `public_keys`, of the keys that signed so far
+    pub signed_key_indices: Vec<u8>,
+    /// Whether enough keys signed for the transaction to be submitted
+    pub complete: bool,
+    /// When the session expires, in seconds since the Unix epoch
+    pub expiration_timestamp_secs: U64,
+}
+
+/// Request to add the signature of one of the keys of a multisig session
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct AddMultisigSignatureRequest {
+    pub public_key: HexEncodedBytes,
+    /// The Ed25519 signature of the signing message of the session
+    pub signature: HexEncodedBytes,
+}
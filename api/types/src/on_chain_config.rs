@@ -0,0 +1,82 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use aptos_types::on_chain_config::{
+    BlockGasLimit, OnChainConsensusConfig, ParallelExecutionConfig, ProposerElectionType, Version,
+};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// The on-chain configs in effect, decoded
+///
+/// The version is the version of the Aptos software the chain runs, which
+/// gates the features of the VM and is the feature version of the gas
+/// schedule. Consensus and execution configs that were never set on chain
+/// are reported with the values validators fall back to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct OnChainConfigs {
+    pub version: U64,
+    pub consensus: ConsensusConfig,
+    pub execution: ExecutionConfig,
+}
+
+/// The config of consensus in effect on chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ConsensusConfig {
+    pub decoupled_execution: bool,
+    /// The number of rounds ordered blocks may be ahead of committed ones
+    pub back_pressure_limit: U64,
+    /// The number of recent rounds that don't count into reputations
+    pub leader_reputation_exclude_round: U64,
+    pub max_failed_authors_to_store: U64,
+    /// How proposers are elected: `fixed_proposer`, `rotating_proposer`,
+    /// `leader_reputation` or `round_proposer`
+    pub proposer_election_type: String,
+}
+
+/// The config of execution in effect on chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ExecutionConfig {
+    /// Whether blocks whose transactions conflict too much are executed
+    /// sequentially
+    pub sequential_fallback_enabled: bool,
+    pub min_block_size_for_fallback: U64,
+    pub max_aborts_per_hundred_txns: U64,
+    /// The gas the transactions of a block may use, if it is limited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_gas_limit: Option<U64>,
+}
+
+impl OnChainConfigs {
+    pub fn new(
+        version: Version,
+        consensus: OnChainConsensusConfig,
+        execution: ParallelExecutionConfig,
+        block_gas_limit: Option<BlockGasLimit>,
+    ) -> Self {
+        let proposer_election_type = match consensus.proposer_election_type() {
+            ProposerElectionType::FixedProposer(_) => "fixed_proposer",
+            ProposerElectionType::RotatingProposer(_) => "rotating_proposer",
+            ProposerElectionType::LeaderReputation(_) => "leader_reputation",
+            ProposerElectionType::RoundProposer(_) => "round_proposer",
+        };
+        Self {
+            version: version.major.into(),
+            consensus: ConsensusConfig {
+                decoupled_execution: consensus.decoupled_execution(),
+                back_pressure_limit: consensus.back_pressure_limit().into(),
+                leader_reputation_exclude_round: consensus.leader_reputation_exclude_round().into(),
+                max_failed_authors_to_store: (consensus.max_failed_authors_to_store() as u64)
+                    .into(),
+                proposer_election_type: proposer_election_type.to_string(),
+            },
+            execution: ExecutionConfig {
+                sequential_fallback_enabled: execution.sequential_fallback_enabled(),
+                min_block_size_for_fallback: execution.min_block_size_for_fallback().into(),
+                max_aborts_per_hundred_txns: execution.max_aborts_per_hundred_txns().into(),
+                block_gas_limit: block_gas_limit.map(|limit| limit.gas_limit.into()),
+            },
+        }
+    }
+}
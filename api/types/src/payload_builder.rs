@@ -0,0 +1,305 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builds the BCS encoded `TransactionPayload` of a script function call from
+//! the function's ABI and JSON arguments.
+//!
+//! Script functions only accept primitives, vectors and `0x1::string::String`
+//! as arguments, so unlike the `MoveConverter` this doesn't need to resolve
+//! struct layouts from storage: the ABI is enough. That makes it usable both
+//! by the API, which reads the ABI from the DB, and by offline tools such as
+//! the CLI, which may have it from a local package or a previous request.
+
+use crate::{Address, HexEncodedBytes, MoveValue};
+use crate::{MoveFunction, MoveType, ScriptFunctionId, U128, U64};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_types::transaction::{ScriptFunction, TransactionPayload};
+use move_deps::{
+    move_binary_format::file_format::Ability,
+    move_core_types::{
+        language_storage::{StructTag, TypeTag},
+        value,
+    },
+};
+use serde_json::Value;
+use std::convert::TryInto;
+
+/// Builds the payload calling `function` with the given type arguments and
+/// JSON arguments, checking both against the function's ABI.
+pub fn build_script_function_payload(
+    function: ScriptFunctionId,
+    abi: &MoveFunction,
+    type_arguments: Vec<MoveType>,
+    arguments: Vec<Value>,
+) -> Result<TransactionPayload> {
+    let type_arguments = validate_type_arguments(&function, abi, type_arguments)?;
+    let arguments = encode_script_function_arguments(abi, arguments)?;
+    Ok(TransactionPayload::ScriptFunction(ScriptFunction::new(
+        function.module.into(),
+        function.name.into(),
+        type_arguments,
+        arguments,
+    )))
+}
+
+/// Checks that there is a type argument for every generic type param of the
+/// function, that each of them can be a type argument at all, and that it has
+/// the abilities the param requires where we can tell without storage, i.e.
+/// for primitives and vectors of them.
+pub fn validate_type_arguments(
+    function: &ScriptFunctionId,
+    abi: &MoveFunction,
+    type_arguments: Vec<MoveType>,
+) -> Result<Vec<TypeTag>> {
+    ensure!(
+        abi.generic_type_params.len() == type_arguments.len(),
+        "expect {} type arguments for script function {}, but got {}",
+        abi.generic_type_params.len(),
+        function,
+        type_arguments.len()
+    );
+    abi.generic_type_params
+        .iter()
+        .zip(type_arguments.into_iter())
+        .enumerate()
+        .map(|(i, (param, type_argument))| {
+            let name = type_argument.to_string();
+            let type_tag = to_type_argument(type_argument)
+                .map_err(|e| format_err!("invalid type_arguments[{}] {}: {}", i, name, e))?;
+            if let Some(abilities) = builtin_abilities(&type_tag) {
+                if let Some(missing) = param
+                    .constraints
+                    .iter()
+                    .find(|constraint| !abilities.contains(&constraint.0))
+                {
+                    bail!(
+                        "invalid type_arguments[{}] {}: expected a type with the {} ability",
+                        i,
+                        name,
+                        missing
+                    );
+                }
+            }
+            Ok(type_tag)
+        })
+        .collect()
+}
+
+/// Encodes the JSON arguments of a call as BCS, according to the types of the
+/// non-signer params of the function.
+pub fn encode_script_function_arguments(
+    abi: &MoveFunction,
+    arguments: Vec<Value>,
+) -> Result<Vec<Vec<u8>>> {
+    let arg_types = abi
+        .params
+        .iter()
+        .filter(|p| !p.is_signer())
+        .collect::<Vec<_>>();
+    ensure!(
+        arg_types.len() == arguments.len(),
+        "expected {} arguments [{}], but got {} ({:?})",
+        arg_types.len(),
+        arg_types
+            .iter()
+            .map(|t| t.json_type_name())
+            .collect::<Vec<String>>()
+            .join(", "),
+        arguments.len(),
+        arguments,
+    );
+    arg_types
+        .into_iter()
+        .zip(arguments.into_iter())
+        .enumerate()
+        .map(|(i, (arg_type, arg))| {
+            let value = encode_argument(arg_type, arg).map_err(|e| {
+                format_err!(
+                    "parse arguments[{}] failed, expect {}, caused by error: {}",
+                    i,
+                    arg_type.json_type_name(),
+                    e,
+                )
+            })?;
+            Ok(bcs::to_bytes(&value)?)
+        })
+        .collect()
+}
+
+/// Converts a JSON argument to the `MoveValue` of the given type. Only types a
+/// script function can take as an argument are supported.
+pub fn encode_argument(typ: &MoveType, val: Value) -> Result<value::MoveValue> {
+    Ok(match typ {
+        MoveType::Bool => value::MoveValue::Bool(serde_json::from_value::<bool>(val)?),
+        MoveType::U8 => value::MoveValue::U8(serde_json::from_value::<u8>(val)?),
+        MoveType::U64 => serde_json::from_value::<U64>(val)?.into(),
+        MoveType::U128 => serde_json::from_value::<U128>(val)?.into(),
+        MoveType::Address => serde_json::from_value::<Address>(val)?.into(),
+        MoveType::Vector { items } => {
+            if matches!(**items, MoveType::U8) {
+                serde_json::from_value::<HexEncodedBytes>(val)?.into()
+            } else if let Value::Array(list) = val {
+                value::MoveValue::Vector(
+                    list.into_iter()
+                        .map(|v| encode_argument(items, v))
+                        .collect::<Result<_>>()?,
+                )
+            } else {
+                bail!("expected vector<{}>, but got: {:?}", items, val)
+            }
+        }
+        MoveType::Struct(struct_tag) => {
+            let struct_tag: StructTag = struct_tag.clone().try_into()?;
+            ensure!(
+                MoveValue::is_utf8_string(&struct_tag),
+                "struct {} can't be a script function argument",
+                struct_tag
+            );
+            let string = val
+                .as_str()
+                .ok_or_else(|| format_err!("failed to parse string::String."))?;
+            crate::new_vm_utf8_string(string)
+        }
+        MoveType::Signer
+        | MoveType::GenericTypeParam { .. }
+        | MoveType::Reference { .. }
+        | MoveType::Unparsable(_) => {
+            bail!("unexpected move type {} for value {:?}", typ, val)
+        }
+    })
+}
+
+/// Converts a type argument to a `TypeTag`, rejecting types that can't be
+/// used to instantiate a function.
+fn to_type_argument(typ: MoveType) -> Result<TypeTag> {
+    match &typ {
+        MoveType::Signer => bail!("signer can't be a type argument"),
+        MoveType::Reference { .. } => bail!("references can't be type arguments"),
+        MoveType::GenericTypeParam { .. } => {
+            bail!("generic type params can't be type arguments of a transaction")
+        }
+        MoveType::Vector { items } => {
+            to_type_argument((**items).clone())?;
+        }
+        MoveType::Struct(struct_tag) => {
+            for generic_type_param in &struct_tag.generic_type_params {
+                to_type_argument(generic_type_param.clone())?;
+            }
+        }
+        _ => (),
+    }
+    typ.try_into()
+}
+
+/// The abilities of a type that doesn't involve any structs, whose abilities
+/// are only known from their modules.
+fn builtin_abilities(type_tag: &TypeTag) -> Option<Vec<Ability>> {
+    match type_tag {
+        TypeTag::Bool | TypeTag::U8 | TypeTag::U64 | TypeTag::U128 | TypeTag::Address => {
+            Some(vec![Ability::Copy, Ability::Drop, Ability::Store])
+        }
+        TypeTag::Vector(items) => builtin_abilities(items),
+        TypeTag::Signer => Some(vec![Ability::Drop]),
+        TypeTag::Struct(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MoveAbility, MoveFunctionGenericTypeParam, MoveFunctionVisibility};
+    use aptos_types::account_address::AccountAddress;
+    use move_deps::move_core_types::identifier::Identifier;
+    use serde_json::json;
+
+    fn abi(constraints: Vec<Ability>, params: Vec<MoveType>) -> MoveFunction {
+        MoveFunction {
+            name: Identifier::new("transfer").unwrap().into(),
+            visibility: MoveFunctionVisibility::Public,
+            is_entry: true,
+            generic_type_params: vec![MoveFunctionGenericTypeParam {
+                constraints: constraints.into_iter().map(MoveAbility::from).collect(),
+            }],
+            params,
+            return_: vec![],
+        }
+    }
+
+    fn function() -> ScriptFunctionId {
+        "0x1::coin::transfer".parse().unwrap()
+    }
+
+    #[test]
+    fn test_build_script_function_payload() {
+        let abi = abi(
+            vec![],
+            vec![
+                MoveType::Reference {
+                    mutable: false,
+                    to: Box::new(MoveType::Signer),
+                },
+                MoveType::Address,
+                MoveType::U64,
+                "0x1::string::String".parse().unwrap(),
+            ],
+        );
+        let payload = build_script_function_payload(
+            function(),
+            &abi,
+            vec![MoveType::U8],
+            vec![json!("0x1"), json!("100"), json!("hello")],
+        )
+        .unwrap();
+
+        match payload {
+            TransactionPayload::ScriptFunction(script_function) => {
+                assert_eq!(script_function.ty_args(), &[TypeTag::U8]);
+                assert_eq!(
+                    script_function.args(),
+                    &[
+                        bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+                        bcs::to_bytes(&100u64).unwrap(),
+                        bcs::to_bytes("hello").unwrap(),
+                    ]
+                );
+            }
+            _ => panic!("expected a script function payload"),
+        }
+    }
+
+    #[test]
+    fn test_arguments_must_match_abi() {
+        let abi = abi(vec![], vec![MoveType::U64]);
+        let err = encode_script_function_arguments(&abi, vec![]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected 1 arguments [string<u64>], but got 0 ([])"
+        );
+
+        let err = encode_script_function_arguments(&abi, vec![json!(true)]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("parse arguments[0] failed, expect string<u64>"));
+    }
+
+    #[test]
+    fn test_validate_type_arguments() {
+        let abi = abi(vec![Ability::Key], vec![]);
+        assert!(validate_type_arguments(&function(), &abi, vec![]).is_err());
+        assert_eq!(
+            validate_type_arguments(&function(), &abi, vec![MoveType::U64])
+                .unwrap_err()
+                .to_string(),
+            "invalid type_arguments[0] u64: expected a type with the key ability"
+        );
+        assert!(validate_type_arguments(&function(), &abi, vec![MoveType::Signer]).is_err());
+        assert!(validate_type_arguments(
+            &function(),
+            &abi,
+            vec!["0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"
+                .parse()
+                .unwrap()]
+        )
+        .is_ok());
+    }
+}
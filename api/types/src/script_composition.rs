@@ -0,0 +1,16 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ScriptFunctionPayload;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Request to compose several calls into a single script
+///
+/// The calls are made in order by the script, and succeed or fail together.
+/// Each call gives the arguments of the called function, leaving out its
+/// signers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ScriptCompositionRequest {
+    pub calls: Vec<ScriptFunctionPayload>,
+}
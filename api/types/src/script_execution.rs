@@ -0,0 +1,54 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, Event, HexEncodedBytes, MoveStructTag, ScriptPayload, WriteSetChange, U64};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// Request to execute a script read-only
+///
+/// The script runs against the state at the ledger version of the request,
+/// with the given resources replaced. Nothing it does is committed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ScriptExecutionRequest {
+    pub script: ScriptPayload,
+    /// The addresses the `signer` arguments of the script are given for, in
+    /// order. They need not have an account.
+    #[serde(default)]
+    #[oai(default)]
+    pub signers: Vec<Address>,
+    /// Resources to replace before executing the script
+    #[serde(default)]
+    #[oai(default)]
+    pub resource_overrides: Vec<ResourceOverride>,
+    /// The maximum amount of gas the script may use, by default the maximum
+    /// of the gas schedule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_gas_amount: Option<U64>,
+}
+
+/// A resource replaced for the execution of a script
+///
+/// The resource is created if the account doesn't hold one of the type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    pub resource_type: MoveStructTag,
+    /// The BCS encoded value of the resource
+    pub data: HexEncodedBytes,
+}
+
+/// The outcome of a read-only script execution
+///
+/// Scripts don't return values, so what the script did is given by the
+/// changes it would have made to state and the events it emitted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ScriptExecutionResult {
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: U64,
+    /// The changes the script would have made, empty if it failed
+    pub changes: Vec<WriteSetChange>,
+    /// The events the script emitted, empty if it failed
+    pub events: Vec<Event>,
+}
@@ -0,0 +1,81 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, U64};
+use aptos_types::stake_pool::StakePool;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+/// The stake of a stake pool, derived from its on-chain state
+///
+/// The active stake and the pending inactive stake count towards the voting
+/// power of the pool. At the end of each epoch, the pending active stake
+/// becomes active, and the pending inactive stake becomes inactive, and so
+/// withdrawable, if the lockup has expired.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct StakePoolSummary {
+    pub pool_address: Address,
+    pub operator_address: Address,
+    pub delegated_voter: Address,
+    pub active: U64,
+    pub inactive: U64,
+    pub pending_active: U64,
+    pub pending_inactive: U64,
+    /// The sum of the active, inactive and pending stake
+    pub total_stake: U64,
+    pub locked_until_secs: U64,
+    /// Whether the lockup had expired by the timestamp of the ledger version
+    pub lockup_expired: bool,
+    /// The seconds left until the lockup expires, from the timestamp of the
+    /// ledger version
+    pub remaining_lockup_secs: U64,
+    /// The rewards distributed to the pool since the version given in the
+    /// request, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewards: Option<StakeRewards>,
+}
+
+/// The rewards distributed to a stake pool over a range of versions
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct StakeRewards {
+    /// The first version of the range, the last one being the ledger version
+    pub since_version: U64,
+    pub amount: U64,
+    /// The number of epochs the rewards were distributed at
+    pub num_distributions: U64,
+}
+
+impl StakePoolSummary {
+    /// Builds the summary of the stake pool as of `timestamp_usecs`, the
+    /// timestamp of the ledger version the stake pool was read at.
+    pub fn new(
+        pool_address: Address,
+        stake_pool: &StakePool,
+        timestamp_usecs: u64,
+        rewards: Option<StakeRewards>,
+    ) -> Self {
+        let timestamp_secs = timestamp_usecs / 1_000_000;
+        let total_stake = stake_pool
+            .active
+            .saturating_add(stake_pool.inactive)
+            .saturating_add(stake_pool.pending_active)
+            .saturating_add(stake_pool.pending_inactive);
+        Self {
+            pool_address,
+            operator_address: stake_pool.operator_address.into(),
+            delegated_voter: stake_pool.delegated_voter.into(),
+            active: stake_pool.active.into(),
+            inactive: stake_pool.inactive.into(),
+            pending_active: stake_pool.pending_active.into(),
+            pending_inactive: stake_pool.pending_inactive.into(),
+            total_stake: total_stake.into(),
+            locked_until_secs: stake_pool.locked_until_secs.into(),
+            lockup_expired: stake_pool.locked_until_secs <= timestamp_secs,
+            remaining_lockup_secs: stake_pool
+                .locked_until_secs
+                .saturating_sub(timestamp_secs)
+                .into(),
+            rewards,
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::MoveType;
+use crate::{Cursor, DecodedTableData, HexEncodedBytes, MoveType};
 use poem_openapi::Object;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -12,3 +12,23 @@ pub struct TableItemRequest {
     pub value_type: MoveType,
     pub key: Value,
 }
+
+/// Request for the raw value stored under a state key
+///
+/// `key` is the BCS encoded `StateKey`, e.g. the access path of a resource or
+/// module, or the handle and key of a table item.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct RawStateValueRequest {
+    pub key: HexEncodedBytes,
+}
+
+/// A page of the items of a table
+///
+/// The items are decoded with the key and value types of the table. `cursor`
+/// is only set when there may be more items, in which case it is passed back
+/// as `cursor` to request the rest.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct TableItems {
+    pub items: Vec<DecodedTableData>,
+    pub cursor: Option<Cursor>,
+}
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    Address, EventKey, HashValue, HexEncodedBytes, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U64,
+    Address, EpochChange, EventKey, HashValue, HexEncodedBytes, MoveModuleBytecode, MoveModuleId,
+    MoveResource, MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U64,
 };
 
 use anyhow::{bail, Context as AnyhowContext};
@@ -14,14 +14,14 @@ use aptos_crypto::{
 use aptos_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
-    contract_event::{ContractEvent, EventWithVersion},
+    contract_event::{ContractEvent, EventWithTransactionContext, EventWithVersion},
     transaction::{
         authenticator::{AccountAuthenticator, TransactionAuthenticator},
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
     },
 };
 
-use poem_openapi::{Object, Union};
+use poem_openapi::{Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::{
     boxed::Box,
@@ -210,6 +210,17 @@ impl Transaction {
     }
 }
 
+/// The types of committed transactions that lists of transactions can be
+/// filtered by.
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum TransactionTypeFilter {
+    User,
+    BlockMetadata,
+    StateCheckpoint,
+}
+
 // TODO: Remove this when we cut over to the new API fully.
 impl From<(SignedTransaction, TransactionPayload)> for Transaction {
     fn from((txn, payload): (SignedTransaction, TransactionPayload)) -> Self {
@@ -243,6 +254,8 @@ impl
             request: (txn, payload).into(),
             events,
             timestamp: timestamp.into(),
+            state_diff: None,
+            storage_fee: None,
         }))
     }
 }
@@ -257,8 +270,15 @@ impl From<(TransactionInfo, WriteSetPayload, Vec<Event>)> for Transaction {
     }
 }
 
-impl From<(&BlockMetadata, TransactionInfo, Vec<Event>)> for Transaction {
-    fn from((txn, info, events): (&BlockMetadata, TransactionInfo, Vec<Event>)) -> Self {
+impl From<(&BlockMetadata, TransactionInfo, Vec<Event>, bool)> for Transaction {
+    fn from(
+        (txn, info, events, block_gas_limit_reached): (
+            &BlockMetadata,
+            TransactionInfo,
+            Vec<Event>,
+            bool,
+        ),
+    ) -> Self {
         Transaction::BlockMetadataTransaction(BlockMetadataTransaction {
             info,
             id: txn.id().into(),
@@ -269,6 +289,7 @@ impl From<(&BlockMetadata, TransactionInfo, Vec<Event>)> for Transaction {
             proposer: txn.proposer().into(),
             failed_proposer_indices: txn.failed_proposer_indices().clone(),
             timestamp: txn.timestamp_usecs().into(),
+            block_gas_limit_reached,
         })
     }
 }
@@ -298,6 +319,24 @@ pub struct TransactionInfo {
     pub vm_status: String,
     pub accumulator_root_hash: HashValue,
     pub changes: Vec<WriteSetChange>,
+    /// The new epoch the transaction started, if it triggered a reconfiguration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub epoch_change: Option<EpochChange>,
+}
+
+/// The output of an on-chain transaction: every change it made to state, the
+/// events it emitted and the gas it used. This is everything an indexer needs
+/// to follow state without re-executing the transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct OnChainTransactionOutput {
+    pub version: U64,
+    pub hash: HashValue,
+    pub gas_used: U64,
+    pub success: bool,
+    pub vm_status: String,
+    pub changes: Vec<WriteSetChange>,
+    pub events: Vec<Event>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
@@ -327,6 +366,16 @@ pub struct UserTransaction {
     pub request: UserTransactionRequest,
     pub events: Vec<Event>,
     pub timestamp: U64,
+    /// The changes the transaction makes to state, decoded. Only returned by simulation, when
+    /// requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub state_diff: Option<Vec<StateDiff>>,
+    /// The part of the gas used that pays for the storage the transaction changes. Only returned
+    /// by simulation, for transactions that succeed under a feature version charging for storage.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub storage_fee: Option<StorageFee>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
@@ -410,6 +459,13 @@ pub struct BlockMetadataTransaction {
     pub proposer: Address,
     pub failed_proposer_indices: Vec<u32>,
     pub timestamp: U64,
+    /// Whether the transactions of the block used up the block gas limit
+    ///
+    /// Once they do, the transactions proposed after the last one of the block are
+    /// retried in a later block. It's recorded when the block is committed, so it's
+    /// never set for blocks restored from a backup.
+    #[serde(default)]
+    pub block_gas_limit_reached: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
@@ -446,6 +502,17 @@ pub struct VersionedEvent {
     pub typ: MoveType,
     // TODO: Use the real data here, not a JSON representation.
     pub data: serde_json::Value,
+    /// The hash of the transaction that emitted the event, only returned by `/events`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HashValue>,
+    /// The sender of the transaction that emitted the event, if it is a user
+    /// transaction, only returned by `/events`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<Address>,
+    /// Whether the transaction that emitted the event succeeded, only
+    /// returned by `/events`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
 }
 
 impl From<(&EventWithVersion, serde_json::Value)> for VersionedEvent {
@@ -457,6 +524,26 @@ impl From<(&EventWithVersion, serde_json::Value)> for VersionedEvent {
                 sequence_number: v0.sequence_number().into(),
                 typ: v0.type_tag().clone().into(),
                 data,
+                transaction_hash: None,
+                sender: None,
+                success: None,
+            },
+        }
+    }
+}
+
+impl From<(&EventWithTransactionContext, serde_json::Value)> for VersionedEvent {
+    fn from((event, data): (&EventWithTransactionContext, serde_json::Value)) -> Self {
+        match &event.event {
+            ContractEvent::V0(v0) => Self {
+                version: event.transaction_version.into(),
+                key: (*v0.key()).into(),
+                sequence_number: v0.sequence_number().into(),
+                typ: v0.type_tag().clone().into(),
+                data,
+                transaction_hash: Some(event.transaction_hash.into()),
+                sender: event.sender.map(Into::into),
+                success: Some(event.success),
             },
         }
     }
@@ -618,6 +705,50 @@ pub struct WriteTableItem {
     pub data: Option<DecodedTableData>,
 }
 
+/// A change a transaction makes to state, with the decoded values of the changed state before and
+/// after the transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct StateDiff {
+    /// The path of the changed state, as in the paths of the API, e.g.
+    /// `0x1/resource/0x1::account::Account` or `table/1234/0x01`.
+    pub path: String,
+    pub change: StateChange,
+    /// The value before the transaction, if there was one. Modules are not decoded, and table
+    /// items are only decoded if the table indexer is enabled for this node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub before: Option<serde_json::Value>,
+    /// The value after the transaction, unless it deletes the state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum StateChange {
+    Created,
+    Modified,
+    Deleted,
+}
+
+/// The storage a transaction allocates, overwrites and frees, and what it is charged and refunded
+/// for it, in gas units. The gas used includes the charge net of the refund, which never exceeds
+/// the charge.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct StorageFee {
+    pub new_items: U64,
+    pub new_bytes: U64,
+    pub modified_items: U64,
+    pub modified_bytes: U64,
+    pub deleted_items: U64,
+    /// The bytes held by the deleted items before the transaction.
+    pub freed_bytes: U64,
+    pub charge: U64,
+    pub refund: U64,
+}
+
 impl WriteSetChange {
     pub fn type_str(&self) -> &'static str {
         match self {
@@ -958,3 +1089,57 @@ impl TransactionSigningMessage {
         }
     }
 }
+
+/// The role a signer plays in a transaction
+#[derive(Clone, Copy, Debug, Deserialize, Enum, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum TransactionSignerRole {
+    Sender,
+    SecondarySigner,
+}
+
+/// The message a single signer of a transaction must sign
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct SignerSigningMessage {
+    pub signer: Address,
+    pub role: TransactionSignerRole,
+    pub message: HexEncodedBytes,
+}
+
+/// The result of prechecking a transaction
+///
+/// The transaction goes through the checks it would on submission, short of
+/// verifying its signature, without being executed. Each of the checks of the
+/// sender account is reported on its own, so that all of their failures are
+/// known at once, while `vm_status` is the first check the VM failed it on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct TransactionPrecheck {
+    /// Whether the transaction passes all the checks
+    pub success: bool,
+    /// `Executed` if the transaction passes the checks of the VM, else the
+    /// status it failed them with
+    pub vm_status: String,
+    /// Whether the public key of the sender's signature matches the
+    /// authentication key of the sender account
+    pub auth_key: PrecheckResult,
+    /// Whether the sequence number is no older than that of the sender
+    /// account. Newer ones pass, the transaction then waits in mempool for
+    /// the ones before it.
+    pub sequence_number: PrecheckResult,
+    /// Whether the sender account holds enough to pay for the maximum gas of
+    /// the transaction
+    pub gas_balance: PrecheckResult,
+}
+
+/// The result of a single check of a transaction
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct PrecheckResult {
+    pub passed: bool,
+    /// The value the check expected, if known
+    pub expected: Option<String>,
+    /// The value the transaction or its sender had
+    pub actual: Option<String>,
+    /// Why the check failed, if it did
+    pub reason: Option<String>,
+}
@@ -0,0 +1,522 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The parser of the Move struct tags given in paths and query params, e.g.
+//! `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`, which points at what
+//! is wrong with a type that doesn't parse, so the 400 returned for it says
+//! how to fix it.
+
+use crate::Address;
+use move_deps::move_core_types::{
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+};
+use std::fmt;
+
+/// How deeply types may nest in each other, counting the struct tag itself
+/// as depth 1, e.g. `vector<vector<u8>>` is at depth 3 as a type param.
+pub const MAX_TYPE_DEPTH: usize = 16;
+
+/// The characters a part of a type spans, counted from 0 at the start of the
+/// string. The end is exclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.end <= self.start + 1 {
+            write!(f, "position {}", self.start)
+        } else {
+            write!(f, "positions {}..{}", self.start, self.end)
+        }
+    }
+}
+
+/// Why a string didn't parse as a struct tag.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TypeTagParseError {
+    /// A character that can't appear in a type.
+    InvalidCharacter { character: char, position: usize },
+    /// The string ended where more of the type was expected.
+    UnexpectedEnd {
+        expected: &'static str,
+        position: usize,
+    },
+    /// A token is not what was expected where it is.
+    UnexpectedToken {
+        token: String,
+        expected: &'static str,
+        span: Span,
+    },
+    /// The address of a struct doesn't parse.
+    InvalidAddress { reason: String, span: Span },
+    /// The name of a module or struct is not a valid identifier.
+    InvalidIdentifier { identifier: String, span: Span },
+    /// A reference, which can't be the type of a resource or a type param.
+    Reference { span: Span },
+    /// The types nest more deeply than `MAX_TYPE_DEPTH`.
+    TooDeep { max_depth: usize, span: Span },
+    /// The type is not a struct.
+    NotAStruct { found: String, span: Span },
+}
+
+impl fmt::Display for TypeTagParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeTagParseError::InvalidCharacter {
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid character {:?} at position {}",
+                character, position
+            ),
+            TypeTagParseError::UnexpectedEnd { expected, position } => write!(
+                f,
+                "expected {} at position {}, found the end",
+                expected, position
+            ),
+            TypeTagParseError::UnexpectedToken {
+                token,
+                expected,
+                span,
+            } => write!(f, "expected {} at {}, found {:?}", expected, span, token),
+            TypeTagParseError::InvalidAddress { reason, span } => {
+                write!(f, "invalid address at {}: {}", span, reason)
+            }
+            TypeTagParseError::InvalidIdentifier { identifier, span } => {
+                write!(f, "invalid identifier {:?} at {}", identifier, span)
+            }
+            TypeTagParseError::Reference { span } => write!(
+                f,
+                "unexpected reference at {}, resources and type params can't be references",
+                span
+            ),
+            TypeTagParseError::TooDeep { max_depth, span } => write!(
+                f,
+                "the type at {} nests more than {} types deep",
+                span, max_depth
+            ),
+            TypeTagParseError::NotAStruct { found, span } => {
+                write!(f, "expected a struct at {}, found {}", span, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TypeTagParseError {}
+
+/// Parses a struct tag, e.g. `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>`.
+/// Whitespace is allowed between the parts of the type.
+pub fn parse_struct_tag(s: &str) -> Result<StructTag, TypeTagParseError> {
+    let mut parser = Parser::new(s)?;
+    let start = parser.position();
+    let type_tag = parser.parse_type(1)?;
+    if let Some(token) = parser.tokens.get(parser.next) {
+        return Err(token.unexpected("the end"));
+    }
+    match type_tag {
+        TypeTag::Struct(struct_tag) => Ok(struct_tag),
+        type_tag => Err(TypeTagParseError::NotAStruct {
+            found: type_tag.to_string(),
+            span: Span {
+                start,
+                end: parser.end,
+            },
+        }),
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    span: Span,
+}
+
+impl<'a> Token<'a> {
+    fn unexpected(&self, expected: &'static str) -> TypeTagParseError {
+        TypeTagParseError::UnexpectedToken {
+            token: self.text.to_string(),
+            expected,
+            span: self.span,
+        }
+    }
+}
+
+fn is_word_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_'
+}
+
+/// Splits a type into words, i.e. addresses, identifiers and names of
+/// primitive types, and punctuation, skipping whitespace.
+fn tokenize(s: &str) -> Result<Vec<Token>, TypeTagParseError> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().enumerate().peekable();
+    while let Some((position, (byte, character))) = chars.next() {
+        let (byte_end, end) = match character {
+            character if character.is_whitespace() => continue,
+            '<' | '>' | ',' | '&' => (byte + 1, position + 1),
+            ':' => match chars.next_if(|(_, (_, next))| *next == ':') {
+                Some(_) => (byte + 2, position + 2),
+                None => {
+                    return Err(TypeTagParseError::UnexpectedToken {
+                        token: ":".to_string(),
+                        expected: "\"::\"",
+                        span: Span {
+                            start: position,
+                            end: position + 1,
+                        },
+                    })
+                }
+            },
+            character if is_word_character(character) => {
+                let mut end = (byte + 1, position + 1);
+                while let Some((position, (byte, _))) =
+                    chars.next_if(|(_, (_, next))| is_word_character(*next))
+                {
+                    end = (byte + 1, position + 1);
+                }
+                end
+            }
+            character => {
+                return Err(TypeTagParseError::InvalidCharacter {
+                    character,
+                    position,
+                })
+            }
+        };
+        tokens.push(Token {
+            text: &s[byte..byte_end],
+            span: Span {
+                start: position,
+                end,
+            },
+        });
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    /// The index of the next token to parse.
+    next: usize,
+    /// The number of characters of the string.
+    end: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Result<Self, TypeTagParseError> {
+        Ok(Self {
+            tokens: tokenize(s)?,
+            next: 0,
+            end: s.chars().count(),
+        })
+    }
+
+    /// The position of the next token, or of the end if there are no more.
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.end, |token| token.span.start)
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.next).map(|token| token.text)
+    }
+
+    fn next_token(&mut self, expected: &'static str) -> Result<Token<'a>, TypeTagParseError> {
+        match self.tokens.get(self.next) {
+            Some(token) => {
+                self.next += 1;
+                Ok(*token)
+            }
+            None => Err(TypeTagParseError::UnexpectedEnd {
+                expected,
+                position: self.end,
+            }),
+        }
+    }
+
+    fn expect(
+        &mut self,
+        text: &'static str,
+        expected: &'static str,
+    ) -> Result<(), TypeTagParseError> {
+        let token = self.next_token(expected)?;
+        if token.text == text {
+            Ok(())
+        } else {
+            Err(token.unexpected(expected))
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<Identifier, TypeTagParseError> {
+        let token = self.next_token("an identifier")?;
+        if !token.text.starts_with(is_word_character) {
+            return Err(token.unexpected("an identifier"));
+        }
+        Identifier::new(token.text).map_err(|_| TypeTagParseError::InvalidIdentifier {
+            identifier: token.text.to_string(),
+            span: token.span,
+        })
+    }
+
+    fn parse_type(&mut self, depth: usize) -> Result<TypeTag, TypeTagParseError> {
+        let start = self.position();
+        if depth > MAX_TYPE_DEPTH {
+            return Err(TypeTagParseError::TooDeep {
+                max_depth: MAX_TYPE_DEPTH,
+                span: Span {
+                    start,
+                    end: start + 1,
+                },
+            });
+        }
+
+        let token = self.next_token("a type")?;
+        let type_tag = match token.text {
+            "&" => {
+                let mut span = token.span;
+                if self.peek() == Some("mut") {
+                    span.end = self.tokens[self.next].span.end;
+                }
+                return Err(TypeTagParseError::Reference { span });
+            }
+            "bool" => TypeTag::Bool,
+            "u8" => TypeTag::U8,
+            "u64" => TypeTag::U64,
+            "u128" => TypeTag::U128,
+            "address" => TypeTag::Address,
+            "signer" => TypeTag::Signer,
+            "vector" => {
+                self.expect("<", "\"<\"")?;
+                let item = self.parse_type(depth + 1)?;
+                self.expect(">", "\">\"")?;
+                TypeTag::Vector(Box::new(item))
+            }
+            text if text.starts_with(is_word_character) && self.peek() == Some("::") => {
+                let span = token.span;
+                let address =
+                    text.parse::<Address>()
+                        .map_err(|err| TypeTagParseError::InvalidAddress {
+                            reason: err.to_string(),
+                            span,
+                        })?;
+                TypeTag::Struct(self.parse_struct(address, depth)?)
+            }
+            _ => return Err(token.unexpected("a type")),
+        };
+        Ok(type_tag)
+    }
+
+    /// Parses the rest of a struct tag, after its address.
+    fn parse_struct(
+        &mut self,
+        address: Address,
+        depth: usize,
+    ) -> Result<StructTag, TypeTagParseError> {
+        self.expect("::", "\"::\"")?;
+        let module = self.parse_identifier()?;
+        self.expect("::", "\"::\"")?;
+        let name = self.parse_identifier()?;
+        let mut type_params = vec![];
+        if self.peek() == Some("<") {
+            self.next += 1;
+            loop {
+                type_params.push(self.parse_type(depth + 1)?);
+                let token = self.next_token("\",\" or \">\"")?;
+                match token.text {
+                    "," => continue,
+                    ">" => break,
+                    _ => return Err(token.unexpected("\",\" or \">\"")),
+                }
+            }
+        }
+        Ok(StructTag {
+            address: address.into(),
+            module,
+            name,
+            type_params,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_struct_tag, Span, TypeTagParseError, MAX_TYPE_DEPTH};
+    use move_deps::move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{StructTag, TypeTag},
+    };
+    use proptest::prelude::*;
+
+    fn struct_tag(name: &str, type_params: Vec<TypeTag>) -> StructTag {
+        StructTag {
+            address: AccountAddress::from_hex_literal("0x1").unwrap(),
+            module: Identifier::new("m").unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params,
+        }
+    }
+
+    fn span(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    #[test]
+    fn test_parse_nested_generics() {
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<vector<0x1::m::T<u8, bool>>, 0x1::m::T<0x1::m::T<u64>>>"),
+            Ok(struct_tag(
+                "S",
+                vec![
+                    TypeTag::Vector(Box::new(TypeTag::Struct(struct_tag(
+                        "T",
+                        vec![TypeTag::U8, TypeTag::Bool]
+                    )))),
+                    TypeTag::Struct(struct_tag(
+                        "T",
+                        vec![TypeTag::Struct(struct_tag("T", vec![TypeTag::U64]))]
+                    )),
+                ]
+            ))
+        );
+        assert_eq!(
+            parse_struct_tag(" 0x1 :: m :: S < address , signer , u128 > "),
+            Ok(struct_tag(
+                "S",
+                vec![TypeTag::Address, TypeTag::Signer, TypeTag::U128]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_struct_tag("0x1::GUID_Generator"),
+            Err(TypeTagParseError::UnexpectedEnd {
+                expected: "\"::\"",
+                position: 19
+            })
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<&mut u8>"),
+            Err(TypeTagParseError::Reference { span: span(10, 14) })
+        );
+        assert_eq!(
+            parse_struct_tag("&0x1::m::S").unwrap_err().to_string(),
+            "unexpected reference at position 0, resources and type params can't be references"
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<u8,>").unwrap_err().to_string(),
+            "expected a type at position 13, found \">\""
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<u8 u8>")
+                .unwrap_err()
+                .to_string(),
+            "expected \",\" or \">\" at positions 13..15, found \"u8\""
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S>").unwrap_err().to_string(),
+            "expected the end at position 9, found \">\""
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<T0>").unwrap_err().to_string(),
+            "expected a type at positions 10..12, found \"T0\""
+        );
+        assert_eq!(
+            parse_struct_tag("0x1:m::S").unwrap_err().to_string(),
+            "expected \"::\" at position 3, found \":\""
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::S<é>").unwrap_err().to_string(),
+            "invalid character 'é' at position 10"
+        );
+        assert_eq!(
+            parse_struct_tag("0x1::m::1S").unwrap_err().to_string(),
+            "invalid identifier \"1S\" at positions 8..10"
+        );
+        assert_eq!(
+            parse_struct_tag("0xzz::m::S").unwrap_err().to_string(),
+            "invalid address at positions 0..4: invalid account address \"0xzz\": \
+             invalid character 'z' at position 2, expected a hex digit"
+        );
+        assert_eq!(
+            parse_struct_tag("vector<u8>").unwrap_err().to_string(),
+            "expected a struct at positions 0..10, found vector<u8>"
+        );
+    }
+
+    #[test]
+    fn test_parse_max_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "0x1::m::S<{}u8{}>",
+                "vector<".repeat(depth - 2),
+                ">".repeat(depth - 2)
+            )
+        };
+        assert!(parse_struct_tag(&nested(MAX_TYPE_DEPTH)).is_ok());
+        let too_deep = nested(MAX_TYPE_DEPTH + 1);
+        let position = 10 + "vector<".len() * (MAX_TYPE_DEPTH - 1);
+        assert_eq!(
+            parse_struct_tag(&too_deep),
+            Err(TypeTagParseError::TooDeep {
+                max_depth: MAX_TYPE_DEPTH,
+                span: span(position, position + 1),
+            })
+        );
+    }
+
+    fn arb_type_tag() -> impl Strategy<Value = TypeTag> {
+        let leaf = prop_oneof![
+            Just(TypeTag::Bool),
+            Just(TypeTag::U8),
+            Just(TypeTag::U64),
+            Just(TypeTag::U128),
+            Just(TypeTag::Address),
+            Just(TypeTag::Signer),
+        ];
+        leaf.prop_recursive(MAX_TYPE_DEPTH as u32 - 2, 64, 4, |inner| {
+            prop_oneof![
+                inner
+                    .clone()
+                    .prop_map(|item| TypeTag::Vector(Box::new(item))),
+                (
+                    "[a-zA-Z][a-zA-Z0-9_]{0,8}",
+                    proptest::collection::vec(inner, 0..4)
+                )
+                    .prop_map(|(name, type_params)| {
+                        TypeTag::Struct(struct_tag(&name, type_params))
+                    }),
+            ]
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_display_round_trip(
+            type_params in proptest::collection::vec(arb_type_tag(), 0..4)
+        ) {
+            let tag = struct_tag("S", type_params);
+            prop_assert_eq!(parse_struct_tag(&tag.to_string()), Ok(tag));
+        }
+
+        #[test]
+        fn test_parse_arbitrary_string_does_not_panic(s in "[0-9a-zA-Z_:<>,& é]{0,64}") {
+            if let Ok(tag) = parse_struct_tag(&s) {
+                prop_assert_eq!(parse_struct_tag(&tag.to_string()), Ok(tag));
+            }
+        }
+
+        #[test]
+        fn test_parse_any_string_does_not_panic(s in any::<String>()) {
+            let _ = parse_struct_tag(&s);
+        }
+    }
+}
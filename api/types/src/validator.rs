@@ -0,0 +1,115 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, U128, U64};
+use aptos_types::{
+    on_chain_config::ValidatorSet as OnChainValidatorSet, validator_info::ValidatorInfo,
+};
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The validator set in effect on chain
+///
+/// The active validators make up the set of the current epoch, along with the
+/// pending inactive ones, which leave the set at the next epoch. The pending
+/// active validators join the set at the next epoch.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct ValidatorSet {
+    pub active_validators: Vec<Validator>,
+    pub pending_inactive: Vec<Validator>,
+    pub pending_active: Vec<Validator>,
+    pub total_voting_power: U128,
+    pub total_joining_power: U128,
+}
+
+/// A validator and its voting power
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct Validator {
+    pub address: Address,
+    pub voting_power: U64,
+    /// The BLS12-381 public key the validator signs consensus messages with
+    pub consensus_public_key: HexEncodedBytes,
+    pub validator_index: U64,
+}
+
+/// The new epoch a transaction started by triggering a reconfiguration
+///
+/// The validators are compared between the set of the previous epoch and the
+/// set of the new one, i.e. the validator set before and after the
+/// transaction.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Object)]
+pub struct EpochChange {
+    pub new_epoch: U64,
+    /// The validators that joined the set, with their voting power
+    pub validators_added: Vec<Validator>,
+    /// The validators that left the set, with the voting power they had
+    pub validators_removed: Vec<Validator>,
+    /// The validators that stayed in the set with a different voting power,
+    /// with their new voting power
+    pub voting_power_changed: Vec<Validator>,
+    pub total_voting_power: U128,
+}
+
+impl EpochChange {
+    pub fn new(
+        new_epoch: u64,
+        previous: OnChainValidatorSet,
+        validator_set: OnChainValidatorSet,
+    ) -> Self {
+        let total_voting_power = validator_set.total_voting_power.into();
+        let mut previous: BTreeMap<_, _> = previous
+            .into_iter()
+            .map(|validator| (*validator.account_address(), validator))
+            .collect();
+        let mut validators_added = vec![];
+        let mut voting_power_changed = vec![];
+        for validator in validator_set {
+            match previous.remove(validator.account_address()) {
+                None => validators_added.push(validator.into()),
+                Some(before)
+                    if before.consensus_voting_power() != validator.consensus_voting_power() =>
+                {
+                    voting_power_changed.push(validator.into())
+                }
+                Some(_) => (),
+            }
+        }
+        Self {
+            new_epoch: new_epoch.into(),
+            validators_added,
+            validators_removed: previous.into_values().map(Validator::from).collect(),
+            voting_power_changed,
+            total_voting_power,
+        }
+    }
+}
+
+impl From<OnChainValidatorSet> for ValidatorSet {
+    fn from(validator_set: OnChainValidatorSet) -> Self {
+        let to_validators = |validators: Vec<ValidatorInfo>| {
+            validators
+                .into_iter()
+                .map(Validator::from)
+                .collect::<Vec<_>>()
+        };
+        Self {
+            active_validators: to_validators(validator_set.active_validators),
+            pending_inactive: to_validators(validator_set.pending_inactive),
+            pending_active: to_validators(validator_set.pending_active),
+            total_voting_power: validator_set.total_voting_power.into(),
+            total_joining_power: validator_set.total_joining_power.into(),
+        }
+    }
+}
+
+impl From<ValidatorInfo> for Validator {
+    fn from(validator: ValidatorInfo) -> Self {
+        Self {
+            address: (*validator.account_address()).into(),
+            voting_power: validator.consensus_voting_power().into(),
+            consensus_public_key: validator.consensus_public_key().to_bytes().to_vec().into(),
+            validator_index: validator.config().validator_index.into(),
+        }
+    }
+}
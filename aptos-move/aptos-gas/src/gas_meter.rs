@@ -4,7 +4,12 @@
 //! This module contains the official gas meter implementation, along with some top-level gas
 //! parameters and traits to help manipulate them.
 
-use crate::{algebra::Gas, instr::InstructionGasParameters, transaction::TransactionGasParameters};
+use crate::{
+    algebra::Gas,
+    instr::InstructionGasParameters,
+    storage::{ChangeSetStats, StorageFee, StorageGasParameters},
+    transaction::TransactionGasParameters,
+};
 use move_binary_format::errors::{Location, PartialVMError, PartialVMResult, VMResult};
 use move_core_types::{
     gas_algebra::{InternalGas, NumArgs, NumBytes},
@@ -82,11 +87,14 @@ impl InitialGasSchedule for NativeGasParameters {
 }
 
 /// Gas parameters for everything that is needed to run the Aptos blockchain, including
-/// instructions, transactions and native functions from various packages.
+/// instructions, transactions, storage and native functions from various packages.
 #[derive(Debug, Clone)]
 pub struct AptosGasParameters {
     pub instr: InstructionGasParameters,
     pub txn: TransactionGasParameters,
+    /// `None` for gas schedules that predate storage being charged for, i.e. that don't have all
+    /// the storage gas parameters.
+    pub storage: Option<StorageGasParameters>,
     pub natives: NativeGasParameters,
 }
 
@@ -95,6 +103,7 @@ impl FromOnChainGasSchedule for AptosGasParameters {
         Some(Self {
             instr: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule)?,
             txn: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule)?,
+            storage: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule),
             natives: FromOnChainGasSchedule::from_on_chain_gas_schedule(gas_schedule)?,
         })
     }
@@ -104,6 +113,9 @@ impl ToOnChainGasSchedule for AptosGasParameters {
     fn to_on_chain_gas_schedule(&self) -> Vec<(String, u64)> {
         let mut entries = self.instr.to_on_chain_gas_schedule();
         entries.extend(self.txn.to_on_chain_gas_schedule());
+        if let Some(storage) = &self.storage {
+            entries.extend(storage.to_on_chain_gas_schedule());
+        }
         entries.extend(self.natives.to_on_chain_gas_schedule());
         entries
     }
@@ -114,9 +126,20 @@ impl AptosGasParameters {
         Self {
             instr: InstructionGasParameters::zeros(),
             txn: TransactionGasParameters::zeros(),
+            storage: None,
             natives: NativeGasParameters::zeros(),
         }
     }
+
+    /// The initial gas parameters along with the initial storage gas parameters, which make up
+    /// the gas schedule to publish when upgrading to the feature version that charges for
+    /// storage. The genesis gas schedule doesn't have them.
+    pub fn initial_with_storage() -> Self {
+        Self {
+            storage: Some(InitialGasSchedule::initial()),
+            ..Self::initial()
+        }
+    }
 }
 
 impl InitialGasSchedule for AptosGasParameters {
@@ -124,6 +147,7 @@ impl InitialGasSchedule for AptosGasParameters {
         Self {
             instr: InitialGasSchedule::initial(),
             txn: InitialGasSchedule::initial(),
+            storage: None,
             natives: InitialGasSchedule::initial(),
         }
     }
@@ -409,4 +433,34 @@ impl AptosGasMeter {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
         self.charge(cost).map_err(|e| e.finish(Location::Undefined))
     }
+
+    /// Whether the gas schedule has the parameters to charge for storage by.
+    pub fn charges_for_storage(&self) -> bool {
+        self.gas_params.storage.is_some()
+    }
+
+    /// Charges for the storage changed by a transaction, net of the refund for what it frees,
+    /// returning the breakdown of the fee. Fails if the gas schedule doesn't charge for storage.
+    pub fn charge_storage(&mut self, stats: ChangeSetStats) -> VMResult<StorageFee> {
+        let (charge, refund) = match &self.gas_params.storage {
+            Some(storage) => storage.calculate_storage_fee(&stats),
+            None => {
+                return Err(
+                    PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                        .with_message("The gas schedule doesn't charge for storage".to_string())
+                        .finish(Location::Undefined),
+                )
+            }
+        };
+        let net_charge = charge
+            .checked_sub(refund)
+            .expect("Refund should always be capped at the charge");
+        self.charge(net_charge)
+            .map_err(|e| e.finish(Location::Undefined))?;
+        Ok(StorageFee {
+            stats,
+            charge: charge.to_unit_round_down_with_params(&self.gas_params.txn),
+            refund: refund.to_unit_round_down_with_params(&self.gas_params.txn),
+        })
+    }
 }
@@ -6,7 +6,7 @@
 //! More specifically, it
 //!   - Is home to the gas meter implementation
 //!   - Defines the gas parameters and formulae for instructions
-//!   - Defines the gas parameters for transactions
+//!   - Defines the gas parameters for transactions and the storage they change
 //!   - Sets the initial values for all gas parameters, including the instruction, transaction
 //!     move-stdlib and aptos-framework ones.
 //!   - Defines a bi-directional mapping between the (Rust) gas parameter structs and their
@@ -26,6 +26,7 @@ mod aptos_framework;
 mod gas_meter;
 mod instr;
 mod move_stdlib;
+mod storage;
 mod transaction;
 
 pub use algebra::{Fee, FeePerGasUnit, Gas, GasUnit, Octa};
@@ -38,4 +39,5 @@ pub use move_core_types::gas_algebra::{
     Arg, Byte, GasQuantity, InternalGas, InternalGasPerArg, InternalGasPerByte, InternalGasUnit,
     NumArgs, NumBytes, UnitDiv,
 };
+pub use storage::{ChangeSetStats, StorageFee, StorageGasParameters};
 pub use transaction::TransactionGasParameters;
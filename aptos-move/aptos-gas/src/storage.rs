@@ -0,0 +1,149 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the gas parameters for the storage a transaction changes, along with the
+//! statistics of a change set they are applied to and the fee they add up to.
+//!
+//! Storage is charged for what a transaction allocates and writes, and refunded for what it frees,
+//! so deleting state that is no longer needed pays back some of what storing it once cost. The
+//! refund never exceeds what the same transaction is charged for storage, so it can only lower the
+//! storage fee, never the fee for execution.
+
+use crate::algebra::Gas;
+use crate::gas_meter::{FromOnChainGasSchedule, InitialGasSchedule, ToOnChainGasSchedule};
+use move_core_types::gas_algebra::{
+    InternalGas, InternalGasPerArg, InternalGasPerByte, NumArgs, NumBytes,
+};
+use std::collections::BTreeMap;
+
+macro_rules! define_gas_parameters_for_storage {
+    ($([$name: ident : $ty: ty, $key: literal, $initial: expr $(,)?]),* $(,)?) => {
+        /// Gas parameters for the storage changed by transactions.
+        ///
+        /// These are not part of the genesis gas schedule. They are added to the on-chain gas
+        /// schedule by the gas schedule upgrade that goes with the feature version charging for
+        /// storage, see `AptosGasParameters::initial_with_storage`.
+        #[derive(Debug, Clone)]
+        pub struct StorageGasParameters {
+            $(pub $name : $ty),*
+        }
+
+        impl FromOnChainGasSchedule for StorageGasParameters {
+            fn from_on_chain_gas_schedule(gas_schedule: &BTreeMap<String, u64>) -> Option<Self> {
+                Some(Self { $($name: gas_schedule.get(&format!("storage.{}", $key)).cloned()?.into()),* })
+            }
+        }
+
+        impl ToOnChainGasSchedule for StorageGasParameters {
+            fn to_on_chain_gas_schedule(&self) -> Vec<(String, u64)> {
+                vec![$((format!("storage.{}", $key), self.$name.into())),*]
+            }
+        }
+
+        impl StorageGasParameters {
+            pub fn zeros() -> Self {
+                Self {
+                    $($name: 0.into()),*
+                }
+            }
+        }
+
+        impl InitialGasSchedule for StorageGasParameters {
+            fn initial() -> Self {
+                Self {
+                    $($name: $initial.into()),*
+                }
+            }
+        }
+
+        #[test]
+        fn keys_should_be_unique() {
+            let mut map: BTreeMap<&str, ()> = BTreeMap::new();
+
+            for key in [$($key),*] {
+                assert!(map.insert(key, ()).is_none());
+            }
+        }
+    }
+}
+
+// TODO(Gas): set these before proposing the gas schedule upgrade that charges for storage.
+define_gas_parameters_for_storage!(
+    // Charged for every item a transaction creates, and per byte of its value.
+    [per_item_create: InternalGasPerArg, "per_item_create", 0],
+    [per_byte_create: InternalGasPerByte, "per_byte_create", 0],
+    // Charged for every existing item a transaction overwrites, and per byte of its new value.
+    [per_item_write: InternalGasPerArg, "per_item_write", 0],
+    [per_byte_write: InternalGasPerByte, "per_byte_write", 0],
+    // Refunded for every item a transaction deletes, and per byte of the value it held.
+    [per_item_refund: InternalGasPerArg, "per_item_refund", 0],
+    [per_byte_refund: InternalGasPerByte, "per_byte_refund", 0],
+);
+
+/// The storage a change set allocates, overwrites and frees, by the number of items of each kind
+/// of write and the bytes of their values.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ChangeSetStats {
+    pub num_new_items: u64,
+    pub new_bytes: u64,
+    pub num_modified_items: u64,
+    pub modified_bytes: u64,
+    pub num_deleted_items: u64,
+    /// The bytes held by the deleted items before the change set.
+    pub freed_bytes: u64,
+}
+
+impl ChangeSetStats {
+    pub fn record_creation(&mut self, num_bytes: usize) {
+        self.num_new_items += 1;
+        self.new_bytes += num_bytes as u64;
+    }
+
+    pub fn record_modification(&mut self, num_bytes: usize) {
+        self.num_modified_items += 1;
+        self.modified_bytes += num_bytes as u64;
+    }
+
+    pub fn record_deletion(&mut self, freed_bytes: usize) {
+        self.num_deleted_items += 1;
+        self.freed_bytes += freed_bytes as u64;
+    }
+}
+
+/// What a change set is charged and refunded for storage, in gas units rounded down.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StorageFee {
+    pub stats: ChangeSetStats,
+    pub charge: Gas,
+    pub refund: Gas,
+}
+
+impl StorageGasParameters {
+    /// Calculates what a change set with the given statistics is charged and refunded for
+    /// storage, the refund being capped at the charge.
+    pub fn calculate_storage_fee(&self, stats: &ChangeSetStats) -> (InternalGas, InternalGas) {
+        let charge = self.per_item_create * NumArgs::new(stats.num_new_items)
+            + self.per_byte_create * NumBytes::new(stats.new_bytes)
+            + self.per_item_write * NumArgs::new(stats.num_modified_items)
+            + self.per_byte_write * NumBytes::new(stats.modified_bytes);
+        let refund = self.per_item_refund * NumArgs::new(stats.num_deleted_items)
+            + self.per_byte_refund * NumBytes::new(stats.freed_bytes);
+
+        (charge, std::cmp::min(refund, charge))
+    }
+}
+
+#[test]
+fn refund_should_be_capped_at_charge() {
+    let mut params = StorageGasParameters::zeros();
+    params.per_item_create = 10.into();
+    params.per_item_refund = 7.into();
+
+    let mut stats = ChangeSetStats::default();
+    stats.record_creation(100);
+    stats.record_deletion(100);
+    assert_eq!(params.calculate_storage_fee(&stats), (10.into(), 7.into()));
+
+    stats.record_deletion(100);
+    assert_eq!(params.calculate_storage_fee(&stats), (10.into(), 10.into()));
+}
@@ -0,0 +1,90 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err, Context, Result};
+use aptos_config::config::RocksdbConfigs;
+use aptos_temppath::TempPath;
+use aptos_transaction_replay::TransactionReplayer;
+use aptos_types::{on_chain_config::GasSchedule, transaction::Version};
+use aptosdb::AptosDB;
+use std::{fs::File, io, path::PathBuf, sync::Arc};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "aptos-transaction-replay",
+    about = "Re-execute a range of transactions in a local DB and report the outputs that diverge \
+             from those committed."
+)]
+struct Opt {
+    #[structopt(parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// The first version of the range.
+    #[structopt(long)]
+    start_version: Version,
+
+    /// The version after the last version of the range. Defaults to the version after the latest
+    /// version in the DB.
+    #[structopt(long)]
+    end_version: Option<Version>,
+
+    /// A JSON file with a full gas schedule to replay the transactions with, in the format of the
+    /// `GasSchedule` on-chain config, instead of the one on chain.
+    #[structopt(long, parse(from_os_str))]
+    gas_schedule: Option<PathBuf>,
+
+    /// The file to write the report to, instead of stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    // The DB is opened as secondary so the tool is usable along side a running node on the same
+    // DB. Using a TempPath since it won't run for long.
+    let tmpdir = TempPath::new();
+    let db = AptosDB::open_as_secondary(
+        opt.db_dir.as_path(),
+        tmpdir.as_ref(),
+        RocksdbConfigs::default(),
+    )
+    .with_context(|| format_err!("Failed to open DB."))?;
+
+    let end_version = match opt.end_version {
+        Some(end_version) => end_version,
+        None => db.get_latest_version()? + 1,
+    };
+    ensure!(
+        opt.start_version <= end_version,
+        "Start version {} is after end version {}.",
+        opt.start_version,
+        end_version,
+    );
+
+    let mut replayer = TransactionReplayer::new(Arc::new(db));
+    if let Some(path) = &opt.gas_schedule {
+        let gas_schedule: GasSchedule = serde_json::from_reader(File::open(path)?)
+            .with_context(|| format_err!("Failed to parse the gas schedule."))?;
+        replayer.override_gas_schedule(&gas_schedule)?;
+    }
+    let report = replayer
+        .replay(opt.start_version, end_version - opt.start_version)
+        .with_context(|| format_err!("Failed to replay transactions."))?;
+
+    let writer: Box<dyn io::Write> = match &opt.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    report.write_json(writer)?;
+    if !report.divergences.is_empty() {
+        bail!(
+            "{} of the {} transactions replayed diverged.",
+            report.divergences.len(),
+            report.num_transactions,
+        );
+    }
+    Ok(())
+}
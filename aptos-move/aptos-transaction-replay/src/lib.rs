@@ -0,0 +1,284 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! This crate re-executes a range of committed transactions with the VM built into it, over the
+//! state they were committed against, and compares what they produce (write sets, events, gas used
+//! and statuses) with what is stored in AptosDB. Any divergence means the VM, or the gas schedule
+//! the transactions are replayed with, would not have agreed with the network on these
+//! transactions, so replaying a range of mainnet or testnet transactions validates a VM upgrade or
+//! a gas schedule change before it is rolled out.
+
+use anyhow::{ensure, format_err, Result};
+use aptos_state_view::StateView;
+use aptos_types::{
+    contract_event::ContractEvent,
+    on_chain_config::{access_path_for_config, GasSchedule, OnChainConfig},
+    state_store::state_key::StateKey,
+    transaction::{TransactionOutput, TransactionStatus, Version},
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_vm::{AptosVM, VMExecutor};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Debug,
+    io::Write,
+    sync::Arc,
+};
+use storage_interface::{
+    state_view::{DbStateView, DbStateViewAtVersion},
+    DbReader,
+};
+
+/// The most transactions read from the DB and executed as a block at once, which is also the most
+/// the DB returns in one call.
+pub const MAX_BLOCK_SIZE: u64 = 1000;
+
+/// Re-executes committed transactions read from a DB.
+pub struct TransactionReplayer {
+    db: Arc<dyn DbReader>,
+    /// State values served in place of those in the DB throughout the replay.
+    overrides: HashMap<StateKey, Vec<u8>>,
+}
+
+impl TransactionReplayer {
+    pub fn new(db: Arc<dyn DbReader>) -> Self {
+        Self {
+            db,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Replays the transactions with the given gas schedule instead of the one on chain, including
+    /// after the transactions that changed it on chain, if any.
+    pub fn override_gas_schedule(&mut self, gas_schedule: &GasSchedule) -> Result<()> {
+        self.overrides.insert(
+            StateKey::AccessPath(access_path_for_config(GasSchedule::CONFIG_ID)),
+            bcs::to_bytes(gas_schedule)?,
+        );
+        Ok(())
+    }
+
+    /// Replays `num_transactions` transactions from `start_version`. The transactions are executed
+    /// in blocks over the state right before the first transaction of each block. A block ends
+    /// early at a reconfiguration, as the VM asks to retry the transactions after it, so the
+    /// transactions after it are executed with the new configuration, just as they were committed.
+    pub fn replay(&self, start_version: Version, num_transactions: u64) -> Result<ReplayReport> {
+        let ledger_version = self.db.get_latest_version()?;
+        let end_version = start_version + num_transactions;
+        ensure!(
+            end_version <= ledger_version + 1,
+            "End version {} is after the version after the latest version {}.",
+            end_version,
+            ledger_version,
+        );
+
+        let mut report = ReplayReport::new(start_version);
+        let mut version = start_version;
+        while version < end_version {
+            let block_size = std::cmp::min(MAX_BLOCK_SIZE, end_version - version);
+            let (txns, expected_outputs): (Vec<_>, Vec<_>) = self
+                .db
+                .get_transaction_outputs(version, block_size, ledger_version)?
+                .transactions_and_outputs
+                .into_iter()
+                .unzip();
+            let state_view = ReplayStateView {
+                base: self.db.state_view_at_version(version.checked_sub(1))?,
+                overrides: &self.overrides,
+            };
+            let outputs = AptosVM::execute_block(txns, &state_view).map_err(|vm_status| {
+                format_err!(
+                    "Failed to execute the block starting at version {}: {:?}",
+                    version,
+                    vm_status
+                )
+            })?;
+
+            let block_start_version = version;
+            for (expected, output) in expected_outputs.iter().zip(outputs.iter()) {
+                if output.status() == &TransactionStatus::Retry {
+                    break;
+                }
+                report.add_transaction(version, expected, output);
+                version += 1;
+            }
+            ensure!(
+                version > block_start_version,
+                "The transaction at version {} was not executed, the VM asked to retry it.",
+                version,
+            );
+        }
+        Ok(report)
+    }
+}
+
+/// Serves the state of the DB at a version, with the overrides of the replayer on top.
+struct ReplayStateView<'a> {
+    base: DbStateView,
+    overrides: &'a HashMap<StateKey, Vec<u8>>,
+}
+
+impl<'a> StateView for ReplayStateView<'a> {
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        match self.overrides.get(state_key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.base.is_genesis()
+    }
+}
+
+/// What was stored and what was replayed, of something that differs between the two.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Mismatch<T> {
+    pub expected: T,
+    pub actual: T,
+}
+
+impl<T: PartialEq> Mismatch<T> {
+    fn of(expected: T, actual: T) -> Option<Self> {
+        (expected != actual).then(|| Self { expected, actual })
+    }
+}
+
+/// A write to a state key that differs between the stored and the replayed write sets, with either
+/// side missing if only the other one writes to the key.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct WriteMismatch {
+    pub state_key: String,
+    #[serde(flatten)]
+    pub write: Mismatch<Option<String>>,
+}
+
+/// An event that differs between the stored and the replayed events, by its index in the events of
+/// the transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct EventMismatch {
+    pub index: usize,
+    #[serde(flatten)]
+    pub event: Mismatch<Option<String>>,
+}
+
+/// The differences between the stored and the replayed output of a transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Divergence {
+    pub version: Version,
+    pub status: Option<Mismatch<String>>,
+    pub gas_used: Option<Mismatch<u64>>,
+    pub write_set: Vec<WriteMismatch>,
+    pub events: Vec<EventMismatch>,
+}
+
+impl Divergence {
+    /// Compares the output stored for the transaction at `version` with the replayed one, returning
+    /// `None` if they are the same.
+    pub fn compare(
+        version: Version,
+        expected: &TransactionOutput,
+        actual: &TransactionOutput,
+    ) -> Option<Self> {
+        let divergence = Self {
+            version,
+            status: Mismatch::of(
+                format!("{:?}", expected.status()),
+                format!("{:?}", actual.status()),
+            ),
+            gas_used: Mismatch::of(expected.gas_used(), actual.gas_used()),
+            write_set: compare_write_sets(expected.write_set(), actual.write_set()),
+            events: compare_events(expected.events(), actual.events()),
+        };
+        (divergence.status.is_some()
+            || divergence.gas_used.is_some()
+            || !divergence.write_set.is_empty()
+            || !divergence.events.is_empty())
+        .then(|| divergence)
+    }
+}
+
+/// The outcome of replaying a range of transactions.
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    pub start_version: Version,
+    pub num_transactions: u64,
+    /// The total gas used by the transactions as stored and as replayed.
+    pub total_gas_used: Mismatch<u64>,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn new(start_version: Version) -> Self {
+        Self {
+            start_version,
+            num_transactions: 0,
+            total_gas_used: Mismatch {
+                expected: 0,
+                actual: 0,
+            },
+            divergences: vec![],
+        }
+    }
+
+    pub fn add_transaction(
+        &mut self,
+        version: Version,
+        expected: &TransactionOutput,
+        actual: &TransactionOutput,
+    ) {
+        self.num_transactions += 1;
+        self.total_gas_used.expected += expected.gas_used();
+        self.total_gas_used.actual += actual.gas_used();
+        self.divergences
+            .extend(Divergence::compare(version, expected, actual));
+    }
+
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+fn compare_write_sets(expected: &WriteSet, actual: &WriteSet) -> Vec<WriteMismatch> {
+    let expected: BTreeMap<&StateKey, &WriteOp> = expected.iter().map(|(k, op)| (k, op)).collect();
+    let actual: BTreeMap<&StateKey, &WriteOp> = actual.iter().map(|(k, op)| (k, op)).collect();
+    expected
+        .keys()
+        .chain(actual.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|state_key| {
+            Mismatch::of(
+                expected.get(state_key).map(debug_string),
+                actual.get(state_key).map(debug_string),
+            )
+            .map(|write| WriteMismatch {
+                state_key: format!("{:?}", state_key),
+                write,
+            })
+        })
+        .collect()
+}
+
+fn compare_events(expected: &[ContractEvent], actual: &[ContractEvent]) -> Vec<EventMismatch> {
+    (0..std::cmp::max(expected.len(), actual.len()))
+        .filter_map(|index| {
+            Mismatch::of(
+                expected.get(index).map(debug_string),
+                actual.get(index).map(debug_string),
+            )
+            .map(|event| EventMismatch { index, event })
+        })
+        .collect()
+}
+
+fn debug_string<T: Debug>(value: T) -> String {
+    format!("{:?}", value)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,143 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_types::{
+    account_config::new_block_event_key,
+    transaction::ExecutionStatus,
+    vm_status::{AbortLocation, StatusCode},
+    write_set::WriteSetMut,
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+
+fn key(byte: u8) -> StateKey {
+    StateKey::Raw(vec![byte])
+}
+
+fn event(sequence_number: u64) -> ContractEvent {
+    ContractEvent::new(
+        new_block_event_key(),
+        sequence_number,
+        TypeTag::Bool,
+        vec![],
+    )
+}
+
+fn output(
+    writes: Vec<(StateKey, WriteOp)>,
+    events: Vec<ContractEvent>,
+    gas_used: u64,
+    status: ExecutionStatus,
+) -> TransactionOutput {
+    TransactionOutput::new(
+        WriteSetMut::new(writes).freeze().unwrap(),
+        events,
+        gas_used,
+        TransactionStatus::Keep(status),
+    )
+}
+
+#[test]
+fn test_same_outputs() {
+    let expected = output(
+        vec![(key(0), WriteOp::Creation(vec![1]))],
+        vec![event(0)],
+        10,
+        ExecutionStatus::Success,
+    );
+    assert_eq!(Divergence::compare(0, &expected, &expected.clone()), None);
+}
+
+#[test]
+fn test_divergent_outputs() {
+    let expected = output(
+        vec![
+            (key(0), WriteOp::Creation(vec![1])),
+            (key(1), WriteOp::Modification(vec![1])),
+            (key(2), WriteOp::Deletion),
+        ],
+        vec![event(0), event(1)],
+        10,
+        ExecutionStatus::Success,
+    );
+    let actual = output(
+        vec![
+            (key(0), WriteOp::Creation(vec![1])),
+            (key(1), WriteOp::Modification(vec![2])),
+            (key(3), WriteOp::Deletion),
+        ],
+        vec![event(0)],
+        12,
+        ExecutionStatus::MoveAbort {
+            location: AbortLocation::Script,
+            code: 1,
+        },
+    );
+
+    let divergence = Divergence::compare(5, &expected, &actual).unwrap();
+    assert_eq!(divergence.version, 5);
+    assert!(divergence.status.is_some());
+    assert_eq!(
+        divergence.gas_used,
+        Some(Mismatch {
+            expected: 10,
+            actual: 12
+        })
+    );
+    let state_keys: Vec<_> = divergence
+        .write_set
+        .iter()
+        .map(|write| write.state_key.clone())
+        .collect();
+    assert_eq!(
+        state_keys,
+        vec![
+            format!("{:?}", key(1)),
+            format!("{:?}", key(2)),
+            format!("{:?}", key(3))
+        ]
+    );
+    assert_eq!(divergence.write_set[1].write.actual, None);
+    assert_eq!(divergence.write_set[2].write.expected, None);
+    assert_eq!(divergence.events.len(), 1);
+    assert_eq!(divergence.events[0].index, 1);
+    assert_eq!(divergence.events[0].event.actual, None);
+
+    // The status alone diverging counts as a divergence.
+    let failed = output(
+        vec![],
+        vec![],
+        10,
+        ExecutionStatus::MiscellaneousError(Some(StatusCode::OUT_OF_GAS)),
+    );
+    let succeeded = output(vec![], vec![], 10, ExecutionStatus::Success);
+    assert!(Divergence::compare(0, &succeeded, &failed)
+        .unwrap()
+        .status
+        .is_some());
+}
+
+#[test]
+fn test_report() {
+    let expected = output(vec![], vec![], 10, ExecutionStatus::Success);
+    let actual = output(vec![], vec![], 11, ExecutionStatus::Success);
+    let mut report = ReplayReport::new(3);
+    report.add_transaction(3, &expected, &expected);
+    report.add_transaction(4, &expected, &actual);
+
+    assert_eq!(report.num_transactions, 2);
+    assert_eq!(
+        report.total_gas_used,
+        Mismatch {
+            expected: 20,
+            actual: 21
+        }
+    );
+    assert_eq!(report.divergences.len(), 1);
+    assert_eq!(report.divergences[0].version, 4);
+
+    let mut json = vec![];
+    report.write_json(&mut json).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["divergences"][0]["gas_used"]["actual"], 11);
+}
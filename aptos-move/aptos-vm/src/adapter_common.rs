@@ -1,7 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::*, data_cache::StateViewCache, delta_ext::TransactionOutputExt};
+use crate::{
+    block_gas_limit::{retry_output, BlockGasTracker},
+    counters::*,
+    data_cache::StateViewCache,
+    delta_ext::TransactionOutputExt,
+};
 use anyhow::Result;
 use aptos_state_view::StateView;
 use aptos_types::{
@@ -133,6 +138,7 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
 ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
     let mut result = vec![];
     let mut should_restart = false;
+    let mut block_gas_tracker = BlockGasTracker::new(data_cache);
 
     info!(
         AdapterLogSchema::new(data_cache.id(), 0),
@@ -154,12 +160,15 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
     for (idx, txn) in signature_verified_block.into_iter().enumerate() {
         let log_context = AdapterLogSchema::new(data_cache.id(), idx);
         if should_restart {
-            let txn_output =
-                TransactionOutput::new(WriteSet::default(), vec![], 0, TransactionStatus::Retry);
-            result.push((VMStatus::Error(StatusCode::UNKNOWN_STATUS), txn_output));
+            result.push((VMStatus::Error(StatusCode::UNKNOWN_STATUS), retry_output()));
             debug!(log_context, "Retry after reconfiguration");
             continue;
         };
+        if block_gas_tracker.should_retry((&txn).into()) {
+            result.push((VMStatus::Error(StatusCode::UNKNOWN_STATUS), retry_output()));
+            debug!(log_context, "Retry after reaching the block gas limit");
+            continue;
+        }
         let (vm_status, output_ext, sender) = adapter.execute_single_transaction(
             &txn,
             &data_cache.as_move_resolver(),
@@ -168,6 +177,7 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
 
         // Apply deltas.
         let output = output_ext.into_transaction_output(&data_cache)?;
+        block_gas_tracker.record(&output);
 
         if !output.status().is_discarded() {
             data_cache.push_write_set(output.write_set());
@@ -8,9 +8,9 @@ use crate::{
         discard_error_output, discard_error_vm_status, validate_signature_checked_transaction,
         validate_signed_transaction, PreprocessedTransaction, VMAdapter,
     },
-    aptos_vm_impl::{get_transaction_output, AptosVMImpl, AptosVMInternals},
+    aptos_vm_impl::{get_transaction_output, squash_change_sets, AptosVMImpl, AptosVMInternals},
     counters::*,
-    data_cache::{AsMoveResolver, StateViewCache},
+    data_cache::{AsMoveResolver, StateViewCache, WriteSetOverlay},
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
@@ -29,11 +29,11 @@ use aptos_types::account_config::new_block_event_key;
 use aptos_types::{
     account_config,
     block_metadata::BlockMetadata,
-    on_chain_config::{new_epoch_event_key, GasSchedule, Version},
+    on_chain_config::{new_epoch_event_key, GasSchedule, Version, APTOS_VERSION_5},
     transaction::{
-        ChangeSet, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction, SignedTransaction,
-        Transaction, TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        ChangeSet, ExecutionStatus, ModuleBundle, Script, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
@@ -57,7 +57,7 @@ use move_deps::{
 };
 use num_cpus;
 use once_cell::sync::OnceCell;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::{
     cmp::min,
     collections::HashSet,
@@ -194,13 +194,28 @@ impl AptosVM {
         }
     }
 
+    /// Runs the epilogue, which takes the fee, in the session of the transaction. Transactions
+    /// that are charged for the storage they change, from `APTOS_VERSION_5` on and when the gas
+    /// schedule has the storage gas parameters, run it in a session of its own instead, see
+    /// `charge_storage_and_run_success_epilogue`.
     fn success_transaction_cleanup<S: MoveResolverExt>(
         &self,
+        storage: &S,
         mut session: SessionExt<S>,
         gas_meter: &mut AptosGasMeter,
         txn_data: &TransactionMetadata,
         log_context: &AdapterLogSchema,
     ) -> Result<(VMStatus, TransactionOutputExt), VMStatus> {
+        if self.0.get_version()? >= APTOS_VERSION_5 && gas_meter.charges_for_storage() {
+            return self.charge_storage_and_run_success_epilogue(
+                storage,
+                session,
+                gas_meter,
+                txn_data,
+                log_context,
+            );
+        }
+
         self.0
             .run_success_epilogue(&mut session, gas_meter.balance(), txn_data, log_context)?;
 
@@ -216,8 +231,64 @@ impl AptosVM {
         ))
     }
 
+    /// Charges for the storage the transaction changes and runs the epilogue. The epilogue runs
+    /// in a session of its own on top of the changes of the transaction, so that the storage fee
+    /// is known by then, and its own writes are not charged for. The changes of the two sessions
+    /// are squashed into the write set a single session would have produced.
+    fn charge_storage_and_run_success_epilogue<S: MoveResolverExt>(
+        &self,
+        storage: &S,
+        session: SessionExt<S>,
+        gas_meter: &mut AptosGasMeter,
+        txn_data: &TransactionMetadata,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(VMStatus, TransactionOutputExt), VMStatus> {
+        let (change_set, stats) = session
+            .finish()
+            .map_err(|e| e.into_vm_status())?
+            .into_change_set_with_stats(&mut (), storage)?;
+        let storage_fee = gas_meter
+            .charge_storage(stats)
+            .map_err(|e| e.into_vm_status())?;
+
+        let epilogue_change_set = {
+            let storage_with_changes = WriteSetOverlay::new(storage, change_set.write_set());
+            let mut session = self
+                .0
+                .new_session(&storage_with_changes, SessionId::epilogue_meta(txn_data));
+            self.0.run_success_epilogue(
+                &mut session,
+                gas_meter.balance(),
+                txn_data,
+                log_context,
+            )?;
+            session
+                .finish()
+                .map_err(|e| e.into_vm_status())?
+                .into_change_set(&mut ())?
+        };
+
+        let (write_set, events) = squash_change_sets(change_set, epilogue_change_set)?;
+        let gas_used = txn_data
+            .max_gas_amount()
+            .checked_sub(gas_meter.balance())
+            .expect("Balance should always be less than or equal to max gas amount");
+        let txn_output = TransactionOutput::new(
+            write_set,
+            events,
+            gas_used.into(),
+            TransactionStatus::Keep(ExecutionStatus::Success),
+        );
+
+        Ok((
+            VMStatus::Executed,
+            TransactionOutputExt::from(txn_output).with_storage_fee(storage_fee),
+        ))
+    }
+
     fn execute_script_or_script_function<S: MoveResolverExt>(
         &self,
+        storage: &S,
         mut session: SessionExt<S>,
         gas_meter: &mut AptosGasMeter,
         txn_data: &TransactionMetadata,
@@ -287,7 +358,7 @@ impl AptosVM {
 
             self.resolve_pending_code_publish(&mut session, gas_meter)?;
 
-            self.success_transaction_cleanup(session, gas_meter, txn_data, log_context)
+            self.success_transaction_cleanup(storage, session, gas_meter, txn_data, log_context)
         }
     }
 
@@ -375,6 +446,7 @@ impl AptosVM {
     /// NativeCodeContext
     fn execute_modules<S: MoveResolverExt>(
         &self,
+        storage: &S,
         mut session: SessionExt<S>,
         gas_meter: &mut AptosGasMeter,
         txn_data: &TransactionMetadata,
@@ -404,7 +476,7 @@ impl AptosVM {
             &[txn_data.sender()],
         )?;
 
-        self.success_transaction_cleanup(session, gas_meter, txn_data, log_context)
+        self.success_transaction_cleanup(storage, session, gas_meter, txn_data, log_context)
     }
 
     /// Resolve a pending code publish request registered via the NativeCodeContext.
@@ -501,6 +573,7 @@ impl AptosVM {
             payload @ TransactionPayload::Script(_)
             | payload @ TransactionPayload::ScriptFunction(_) => self
                 .execute_script_or_script_function(
+                    storage,
                     session,
                     &mut gas_meter,
                     &txn_data,
@@ -508,7 +581,7 @@ impl AptosVM {
                     log_context,
                 ),
             TransactionPayload::ModuleBundle(m) => {
-                self.execute_modules(session, &mut gas_meter, &txn_data, m, log_context)
+                self.execute_modules(storage, session, &mut gas_meter, &txn_data, m, log_context)
             }
             TransactionPayload::WriteSet(_) => {
                 return discard_error_vm_status(VMStatus::Error(StatusCode::UNREACHABLE));
@@ -862,12 +935,121 @@ impl AptosVM {
         txn: &SignedTransaction,
         state_view: &impl StateView,
     ) -> (VMStatus, TransactionOutputExt) {
-        let vm = AptosVM::new(state_view);
+        Self::simulate_signed_transaction_with_overrides(txn, state_view, &BTreeMap::new(), None)
+    }
+
+    /// Simulates a transaction as `simulate_signed_transaction` does, but charging gas by the
+    /// on-chain gas schedule with the values of `gas_overrides` replacing those of the parameters
+    /// they name, and gating features by `version` rather than by the on-chain version if given,
+    /// so that the costs of a transaction can be estimated under upcoming gas changes.
+    pub fn simulate_signed_transaction_with_overrides(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+        gas_overrides: &BTreeMap<String, u64>,
+        version: Option<Version>,
+    ) -> (VMStatus, TransactionOutputExt) {
+        let vm = AptosVM(AptosVMImpl::new_with_overrides(
+            state_view,
+            gas_overrides,
+            version,
+        ));
         let simulation_vm = AptosSimulationVM(vm);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
         simulation_vm.simulate_signed_transaction(&state_view.as_move_resolver(), txn, &log_context)
     }
 
+    /// Runs the checks a transaction goes through on submission, i.e. those of its format, its gas
+    /// parameters and the prologue, without executing it. Unlike on submission, the signature is
+    /// not verified, so that transactions can be checked before they are signed. As in mempool, a
+    /// sequence number ahead of that of the sender passes.
+    pub fn precheck_transaction(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> Result<(), VMStatus> {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let storage = state_view.as_move_resolver();
+        let txn_data = TransactionMetadata::new(txn);
+        let mut session = vm.0.new_session(&storage, SessionId::txn_meta(&txn_data));
+
+        vm.check_transaction_format(txn)?;
+        match vm.run_prologue_with_payload(&mut session, txn.payload(), &txn_data, &log_context) {
+            Err(err) if err.status_code() != StatusCode::SEQUENCE_NUMBER_TOO_NEW => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Executes a script outside of any transaction, signed by `signers`, over the state of
+    /// `state_view` with the writes of `overrides` applied on top. There is no prologue or
+    /// epilogue, so the signers need no account, and nothing is charged for the gas used. The
+    /// output is only meant to be inspected and must never be committed. Gas is metered up to
+    /// `max_gas_amount`, or up to the maximum of the gas schedule if not given. If the script
+    /// fails, the gas it used up to then is returned along with the status.
+    pub fn execute_script_read_only(
+        script: &Script,
+        signers: Vec<AccountAddress>,
+        overrides: &WriteSet,
+        max_gas_amount: Option<u64>,
+        state_view: &impl StateView,
+    ) -> Result<TransactionOutputExt, (VMStatus, u64)> {
+        let mut state_view_cache = StateViewCache::new(state_view);
+        state_view_cache.push_write_set(overrides);
+        let vm = AptosVM::new(&state_view_cache);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let storage = state_view_cache.as_move_resolver();
+
+        let gas_params =
+            vm.0.get_gas_parameters(&log_context)
+                .map_err(|status| (status, 0))?;
+        let maximum_number_of_gas_units = u64::from(gas_params.txn.maximum_number_of_gas_units);
+        let txn_data = TransactionMetadata {
+            max_gas_amount: max_gas_amount
+                .map_or(maximum_number_of_gas_units, |amount| {
+                    min(amount, maximum_number_of_gas_units)
+                })
+                .into(),
+            ..Default::default()
+        };
+        let mut gas_meter = AptosGasMeter::new(gas_params.clone(), txn_data.max_gas_amount());
+
+        let mut session = vm.0.new_session(&storage, SessionId::Void);
+        let result = Self::execute_script_in_session(&mut session, script, signers, &mut gas_meter)
+            .and_then(|()| {
+                get_transaction_output(
+                    &mut (),
+                    session,
+                    gas_meter.balance(),
+                    &txn_data,
+                    ExecutionStatus::Success,
+                )
+            });
+        result.map_err(|status| {
+            let gas_used = txn_data
+                .max_gas_amount()
+                .checked_sub(gas_meter.balance())
+                .expect("Balance should always be less than or equal to max gas amount");
+            (status, gas_used.into())
+        })
+    }
+
+    fn execute_script_in_session<S: MoveResolverExt>(
+        session: &mut SessionExt<S>,
+        script: &Script,
+        signers: Vec<AccountAddress>,
+        gas_meter: &mut AptosGasMeter,
+    ) -> Result<(), VMStatus> {
+        let loaded_func = session.load_script(script.code(), script.ty_args().to_vec())?;
+        let args = transaction_arg_validation::validate_combine_signer_and_txn_args(
+            session,
+            signers,
+            convert_txn_args(script.args()),
+            &loaded_func,
+        )?;
+        session
+            .execute_script(script.code(), script.ty_args().to_vec(), args, gas_meter)
+            .map_err(|e| e.into_vm_status())
+    }
+
     fn run_prologue_with_payload<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
@@ -877,10 +1059,14 @@ impl AptosVM {
     ) -> Result<(), VMStatus> {
         match payload {
             TransactionPayload::Script(_) => {
+                self.0
+                    .check_script_allowed(session.remote(), txn_data, log_context)?;
                 self.0.check_gas(txn_data, log_context)?;
                 self.0.run_script_prologue(session, txn_data, log_context)
             }
-            TransactionPayload::ScriptFunction(_) => {
+            TransactionPayload::ScriptFunction(script_fn) => {
+                self.0
+                    .check_script_function_allowed(script_fn, log_context)?;
                 // NOTE: Script and ScriptFunction shares the same prologue
                 self.0.check_gas(txn_data, log_context)?;
                 self.0.run_script_prologue(session, txn_data, log_context)
@@ -1117,6 +1303,7 @@ impl AptosSimulationVM {
             payload @ TransactionPayload::Script(_)
             | payload @ TransactionPayload::ScriptFunction(_) => {
                 self.0.execute_script_or_script_function(
+                    storage,
                     session,
                     &mut gas_meter,
                     &txn_data,
@@ -1126,7 +1313,7 @@ impl AptosSimulationVM {
             }
             TransactionPayload::ModuleBundle(m) => {
                 self.0
-                    .execute_modules(session, &mut gas_meter, &txn_data, m, log_context)
+                    .execute_modules(storage, session, &mut gas_meter, &txn_data, m, log_context)
             }
             TransactionPayload::WriteSet(_) => {
                 return discard_error_vm_status(VMStatus::Error(StatusCode::UNREACHABLE));
@@ -15,15 +15,27 @@ use aptos_gas::{AptosGasParameters, FromOnChainGasSchedule, Gas, NativeGasParame
 use aptos_logger::prelude::*;
 use aptos_state_view::StateView;
 use aptos_types::{
-    account_config::{ChainSpecificAccountInfo, APTOS_CHAIN_INFO, CORE_CODE_ADDRESS},
-    on_chain_config::{GasSchedule, OnChainConfig, Version, APTOS_VERSION_3},
-    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    access_path::Path,
+    account_config::{
+        ApprovedExecutionHashesResource, ChainSpecificAccountInfo, APTOS_CHAIN_INFO,
+        CORE_CODE_ADDRESS,
+    },
+    contract_event::ContractEvent,
+    on_chain_config::{
+        GasSchedule, OnChainConfig, ScriptFunctionAllowList, Version, APTOS_VERSION_3,
+    },
+    state_store::state_key::StateKey,
+    transaction::{
+        ChangeSet, ExecutionStatus, ScriptFunction, TransactionOutput, TransactionStatus,
+    },
     vm_status::{StatusCode, VMStatus},
+    write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use fail::fail_point;
 use move_deps::{
     move_binary_format::{errors::VMResult, CompiledModule},
     move_core_types::{
+        account_address::AccountAddress,
         language_storage::ModuleId,
         move_resource::MoveStructType,
         resolver::ResourceResolver,
@@ -32,7 +44,10 @@ use move_deps::{
     move_vm_runtime::logging::expect_no_verification_errors,
     move_vm_types::gas::UnmeteredGasMeter,
 };
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 #[derive(Clone)]
 /// A wrapper to make VMRuntime standalone and thread safe.
@@ -41,16 +56,34 @@ pub struct AptosVMImpl {
     gas_params: Option<AptosGasParameters>,
     version: Option<Version>,
     chain_account_info: Option<ChainSpecificAccountInfo>,
+    script_function_allow_list: Option<ScriptFunctionAllowList>,
 }
 
 impl AptosVMImpl {
     #[allow(clippy::new_without_default)]
     pub fn new<S: StateView>(state: &S) -> Self {
+        Self::new_with_overrides(state, &BTreeMap::new(), None)
+    }
+
+    /// Creates a VM that charges gas by the on-chain gas schedule with the values of
+    /// `gas_overrides` replacing those of the parameters they name, and that gates features by
+    /// `version` rather than by the on-chain version if given. Only simulations may run on such
+    /// a VM, as its outputs differ from those of the validators.
+    pub fn new_with_overrides<S: StateView>(
+        state: &S,
+        gas_overrides: &BTreeMap<String, u64>,
+        version: Option<Version>,
+    ) -> Self {
         let storage = RemoteStorage::new(state);
 
         // TODO(Gas): this should not panic
         let gas_params = GasSchedule::fetch_config(&storage).and_then(|gas_schedule| {
-            let gas_schedule = gas_schedule.to_btree_map();
+            let mut gas_schedule = gas_schedule.to_btree_map();
+            gas_schedule.extend(
+                gas_overrides
+                    .iter()
+                    .map(|(name, value)| (name.clone(), *value)),
+            );
             AptosGasParameters::from_on_chain_gas_schedule(&gas_schedule)
         });
 
@@ -68,8 +101,10 @@ impl AptosVMImpl {
             gas_params,
             version: None,
             chain_account_info: None,
+            script_function_allow_list: None,
         };
-        vm.version = Version::fetch_config(&storage);
+        vm.version = version.or_else(|| Version::fetch_config(&storage));
+        vm.script_function_allow_list = ScriptFunctionAllowList::fetch_config(&storage);
         vm.chain_account_info = Self::get_chain_specific_account_info(&RemoteStorage::new(state));
         vm
     }
@@ -88,6 +123,7 @@ impl AptosVMImpl {
             gas_params: Some(gas_params),
             version: Some(version),
             chain_account_info: None,
+            script_function_allow_list: None,
         }
     }
 
@@ -134,6 +170,57 @@ impl AptosVMImpl {
         })
     }
 
+    /// Rejects the script function unless its module is allowed by the on-chain allow list, if
+    /// one is set.
+    pub fn check_script_function_allowed(
+        &self,
+        script_fn: &ScriptFunction,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(), VMStatus> {
+        match &self.script_function_allow_list {
+            Some(allow_list) if !allow_list.allows(script_fn.module().address()) => {
+                warn!(
+                    *log_context,
+                    "[VM] Script function {}::{} is not allowed",
+                    script_fn.module(),
+                    script_fn.function(),
+                );
+                Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Scripts may call any public function of any module, so while the script function allow list
+    /// is set, only those of governance proposals that passed, whose hashes are approved on chain,
+    /// may be executed.
+    pub fn check_script_allowed<S: MoveResolverExt>(
+        &self,
+        remote_cache: &S,
+        txn_data: &TransactionMetadata,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(), VMStatus> {
+        if self.script_function_allow_list.is_none() {
+            return Ok(());
+        }
+        let approved = remote_cache
+            .get_resource(
+                &CORE_CODE_ADDRESS,
+                &ApprovedExecutionHashesResource::struct_tag(),
+            )
+            .map_err(|_| VMStatus::Error(StatusCode::STORAGE_ERROR))?
+            .and_then(|blob| bcs::from_bytes::<ApprovedExecutionHashesResource>(&blob).ok())
+            .map_or(false, |hashes| hashes.contains(&txn_data.script_hash));
+        if !approved {
+            warn!(
+                *log_context,
+                "[VM] Script is not allowed, its hash is not approved by governance",
+            );
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING));
+        }
+        Ok(())
+    }
+
     pub fn check_gas(
         &self,
         txn_data: &TransactionMetadata,
@@ -555,6 +642,77 @@ pub(crate) fn get_transaction_output<A: AccessPathCache, S: MoveResolverExt>(
     Ok(TransactionOutputExt::new(delta_change_set, txn_output))
 }
 
+/// Squashes the changes of two sessions of the same transaction, the second one having run on top
+/// of the changes of the first, into a single write set and the events of both. The write set lists
+/// its items in the order a single session making both sets of changes would have written them.
+pub(crate) fn squash_change_sets(
+    first: ChangeSet,
+    second: ChangeSet,
+) -> Result<(WriteSet, Vec<ContractEvent>), VMStatus> {
+    let (first_write_set, mut events) = first.into_inner();
+    let (second_write_set, second_events) = second.into_inner();
+
+    let mut write_ops: Vec<(StateKey, Option<WriteOp>)> = first_write_set
+        .into_iter()
+        .map(|(state_key, op)| (state_key, Some(op)))
+        .collect();
+    let mut positions: HashMap<StateKey, usize> = write_ops
+        .iter()
+        .enumerate()
+        .map(|(position, (state_key, _))| (state_key.clone(), position))
+        .collect();
+    for (state_key, op) in second_write_set {
+        match positions.get(&state_key) {
+            Some(&position) => {
+                let first_op = write_ops[position].1.take();
+                write_ops[position].1 = match (first_op, op) {
+                    // The item is still new to storage, whatever the second session did to it.
+                    (Some(WriteOp::Creation(_)), WriteOp::Modification(blob)) => {
+                        Some(WriteOp::Creation(blob))
+                    }
+                    (Some(WriteOp::Creation(_)), WriteOp::Deletion) => None,
+                    (Some(WriteOp::Deletion), WriteOp::Creation(blob)) => {
+                        Some(WriteOp::Modification(blob))
+                    }
+                    (_, op) => Some(op),
+                };
+            }
+            None => {
+                positions.insert(state_key.clone(), write_ops.len());
+                write_ops.push((state_key, Some(op)));
+            }
+        }
+    }
+    // The sort is stable, so the items of the native extensions, which have no order of their own,
+    // stay in the order they were written in.
+    write_ops.sort_by_cached_key(|(state_key, _)| {
+        let order = session_write_order(state_key);
+        (order.is_none(), order)
+    });
+    events.extend(second_events);
+
+    let write_set = write_ops
+        .into_iter()
+        .filter_map(|(state_key, op)| op.map(|op| (state_key, op)))
+        .collect::<WriteSetMut>()
+        .freeze()
+        .map_err(|_| VMStatus::Error(StatusCode::DATA_FORMAT_ERROR))?;
+    Ok((write_set, events))
+}
+
+/// The position of an item among the writes of a session, which writes the resources and then the
+/// modules of each account in address order, followed by the items of the native extensions.
+/// Returns `None` for the latter.
+fn session_write_order(state_key: &StateKey) -> Option<(AccountAddress, bool, Path)> {
+    match state_key {
+        StateKey::AccessPath(access_path) => {
+            let path = access_path.get_path();
+            Some((access_path.address, matches!(path, Path::Code(_)), path))
+        }
+        _ => None,
+    }
+}
+
 #[test]
 fn vm_thread_safe() {
     fn assert_send<T: Send>() {}
@@ -567,3 +725,112 @@ fn vm_thread_safe() {
     assert_send::<MoveVmExt>();
     assert_sync::<MoveVmExt>();
 }
+
+#[test]
+fn squash_change_sets_keeps_creations() {
+    fn change_set(write_ops: Vec<(&str, WriteOp)>) -> ChangeSet {
+        let write_set = write_ops
+            .into_iter()
+            .map(|(key, op)| (StateKey::Raw(key.as_bytes().to_vec()), op))
+            .collect::<WriteSetMut>()
+            .freeze()
+            .unwrap();
+        ChangeSet::new(write_set, vec![])
+    }
+
+    let (write_set, _) = squash_change_sets(
+        change_set(vec![
+            ("created", WriteOp::Creation(vec![1])),
+            ("deleted", WriteOp::Deletion),
+            ("created_and_deleted", WriteOp::Creation(vec![1])),
+            ("modified", WriteOp::Modification(vec![1])),
+        ]),
+        change_set(vec![
+            ("created", WriteOp::Modification(vec![2])),
+            ("deleted", WriteOp::Creation(vec![2])),
+            ("created_and_deleted", WriteOp::Deletion),
+            ("new", WriteOp::Modification(vec![2])),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        write_set.into_iter().collect::<Vec<_>>(),
+        change_set(vec![
+            ("created", WriteOp::Creation(vec![2])),
+            ("deleted", WriteOp::Modification(vec![2])),
+            ("modified", WriteOp::Modification(vec![1])),
+            ("new", WriteOp::Modification(vec![2])),
+        ])
+        .write_set()
+        .clone()
+        .into_iter()
+        .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn squash_change_sets_keeps_session_write_order() {
+    use aptos_types::access_path::AccessPath;
+    use move_deps::move_core_types::{identifier::Identifier, language_storage::StructTag};
+
+    fn resource(address: u8, name: &str) -> StateKey {
+        let address = AccountAddress::from_hex_literal(&format!("0x{}", address)).unwrap();
+        StateKey::AccessPath(AccessPath::new(
+            address,
+            AccessPath::resource_access_vec(StructTag {
+                address,
+                module: Identifier::new("m").unwrap(),
+                name: Identifier::new(name).unwrap(),
+                type_params: vec![],
+            }),
+        ))
+    }
+    fn module(address: u8, name: &str) -> StateKey {
+        let address = AccountAddress::from_hex_literal(&format!("0x{}", address)).unwrap();
+        StateKey::AccessPath(AccessPath::code_access_path(ModuleId::new(
+            address,
+            Identifier::new(name).unwrap(),
+        )))
+    }
+    fn change_set(state_keys: Vec<StateKey>) -> ChangeSet {
+        let write_set = state_keys
+            .into_iter()
+            .map(|state_key| (state_key, WriteOp::Modification(vec![])))
+            .collect::<WriteSetMut>()
+            .freeze()
+            .unwrap();
+        ChangeSet::new(write_set, vec![])
+    }
+    let table_item = StateKey::Raw(b"table_item".to_vec());
+
+    let (write_set, _) = squash_change_sets(
+        change_set(vec![
+            resource(1, "B"),
+            module(1, "m"),
+            resource(2, "A"),
+            table_item.clone(),
+        ]),
+        change_set(vec![
+            resource(1, "A"),
+            resource(1, "B"),
+            module(1, "n"),
+            resource(3, "A"),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        write_set
+            .into_iter()
+            .map(|(state_key, _)| state_key)
+            .collect::<Vec<_>>(),
+        vec![
+            resource(1, "A"),
+            resource(1, "B"),
+            module(1, "m"),
+            module(1, "n"),
+            resource(2, "A"),
+            resource(3, "A"),
+            table_item,
+        ]
+    );
+}
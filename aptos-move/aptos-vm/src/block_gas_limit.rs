@@ -0,0 +1,133 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    adapter_common::PreprocessedTransaction, counters::BLOCK_GAS_LIMIT_RETRIED_TRANSACTIONS,
+    data_cache::RemoteStorage,
+};
+use aptos_state_view::StateView;
+use aptos_types::{
+    on_chain_config::{BlockGasLimit, OnChainConfig},
+    transaction::{Transaction, TransactionOutput, TransactionStatus},
+    write_set::WriteSet,
+};
+
+/// How a transaction takes part in the gas accounting of its block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum BlockGasAccounting {
+    /// The transaction starts a new block, whose gas accounting starts from zero.
+    BlockStart,
+    /// The transaction ends its block, so it is executed regardless of the block gas limit.
+    BlockEnd,
+    Metered,
+}
+
+impl From<&PreprocessedTransaction> for BlockGasAccounting {
+    fn from(txn: &PreprocessedTransaction) -> Self {
+        match txn {
+            PreprocessedTransaction::BlockMetadata(_) => Self::BlockStart,
+            PreprocessedTransaction::StateCheckpoint => Self::BlockEnd,
+            _ => Self::Metered,
+        }
+    }
+}
+
+impl From<&Transaction> for BlockGasAccounting {
+    fn from(txn: &Transaction) -> Self {
+        match txn {
+            Transaction::BlockMetadata(_) => Self::BlockStart,
+            Transaction::StateCheckpoint(_) => Self::BlockEnd,
+            _ => Self::Metered,
+        }
+    }
+}
+
+/// Enforces the on-chain block gas limit: once the transactions kept in a block have used at
+/// least the limit, the remaining transactions of the block are retried in a later block.
+///
+/// The decision only depends on the transactions of the block and their outputs, in order, so
+/// it is the same regardless of how the block is executed, and when the committed transactions
+/// of the block are replayed.
+pub(crate) struct BlockGasTracker {
+    gas_limit: Option<u64>,
+    gas_used: u64,
+}
+
+impl BlockGasTracker {
+    /// Creates a tracker enforcing the block gas limit configured in the state before the
+    /// transactions are executed.
+    pub(crate) fn new<S: StateView>(state_view: &S) -> Self {
+        let gas_limit = BlockGasLimit::fetch_config(&RemoteStorage::new(state_view))
+            .map(|config| config.gas_limit);
+        Self {
+            gas_limit,
+            gas_used: 0,
+        }
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.gas_limit
+            .map_or(false, |gas_limit| self.gas_used >= gas_limit)
+    }
+
+    /// Whether the transaction must be retried in a later block instead of being executed.
+    /// Must be called for every transaction of the block, in order.
+    pub(crate) fn should_retry(&mut self, accounting: BlockGasAccounting) -> bool {
+        let retry = match accounting {
+            BlockGasAccounting::BlockStart => {
+                self.gas_used = 0;
+                false
+            }
+            BlockGasAccounting::BlockEnd => false,
+            BlockGasAccounting::Metered => self.limit_reached(),
+        };
+        if retry {
+            BLOCK_GAS_LIMIT_RETRIED_TRANSACTIONS.inc();
+        }
+        retry
+    }
+
+    /// Records the gas used by a transaction that was executed.
+    pub(crate) fn record(&mut self, output: &TransactionOutput) {
+        if let TransactionStatus::Keep(_) = output.status() {
+            self.gas_used = self.gas_used.saturating_add(output.gas_used());
+        }
+    }
+
+    /// Enforces the block gas limit on outputs that were computed without it, replacing the
+    /// outputs of the transactions past the cutoff. Returns `None` if the outputs can't be
+    /// reconciled, i.e. a new block was executed on top of the writes of transactions that
+    /// must be retried.
+    pub(crate) fn enforce_on_outputs(
+        &mut self,
+        transactions: &[Transaction],
+        outputs: Vec<TransactionOutput>,
+    ) -> Option<Vec<TransactionOutput>> {
+        let mut retried = false;
+        let mut result = Vec::with_capacity(outputs.len());
+        for (txn, output) in transactions.iter().zip(outputs) {
+            if let TransactionStatus::Retry = output.status() {
+                // Transactions after a reconfiguration were not executed at all.
+                result.push(output);
+                continue;
+            }
+            let accounting = BlockGasAccounting::from(txn);
+            if accounting == BlockGasAccounting::BlockStart && retried {
+                return None;
+            }
+            if self.should_retry(accounting) {
+                retried = true;
+                result.push(retry_output());
+            } else {
+                self.record(&output);
+                result.push(output);
+            }
+        }
+        Some(result)
+    }
+}
+
+/// The output of a transaction that is retried in a later block.
+pub(crate) fn retry_output() -> TransactionOutput {
+    TransactionOutput::new(WriteSet::default(), vec![], 0, TransactionStatus::Retry)
+}
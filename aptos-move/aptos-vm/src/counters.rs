@@ -46,6 +46,16 @@ pub static BLOCK_TRANSACTION_COUNT: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count the number of transactions retried in a later block because their block
+/// reached the block gas limit.
+pub static BLOCK_GAS_LIMIT_RETRIED_TRANSACTIONS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_vm_block_gas_limit_retried_transactions",
+        "Number of transactions retried because their block reached the block gas limit"
+    )
+    .unwrap()
+});
+
 pub static TXN_TOTAL_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         "aptos_vm_txn_total_seconds",
@@ -71,3 +81,72 @@ pub static TXN_GAS_USAGE: Lazy<Histogram> = Lazy::new(|| {
 pub static CRITICAL_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("aptos_vm_critical_errors", "Number of critical errors").unwrap()
 });
+
+/// Ratio of validation aborts to transactions of the blocks executed in parallel.
+pub static PARALLEL_EXECUTION_CONFLICT_RATE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_parallel_execution_conflict_rate",
+        "Number of validation aborts per transaction of blocks executed in parallel",
+        vec![0.0, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0]
+    )
+    .unwrap()
+});
+
+pub static PARALLEL_EXECUTION_REEXECUTIONS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_parallel_execution_reexecutions_per_block",
+        "Number of transaction re-executions per block executed in parallel"
+    )
+    .unwrap()
+});
+
+pub static PARALLEL_EXECUTION_DEPENDENCY_WAIT_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_parallel_execution_dependency_wait_seconds",
+        "Time executions waited on dependencies per block executed in parallel, summed over workers"
+    )
+    .unwrap()
+});
+
+pub static PARALLEL_EXECUTION_WORKER_UTILIZATION: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_parallel_execution_worker_utilization",
+        "Fraction of the time of the workers spent executing or validating transactions per block executed in parallel",
+        vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .unwrap()
+});
+
+/// The index in the block of every transaction aborted by a failed validation, which shows
+/// whether conflicts concentrate at the start of blocks or are spread across them.
+pub static PARALLEL_EXECUTION_VALIDATION_ABORT_INDEX: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_vm_parallel_execution_validation_abort_index",
+        "Index in the block of transactions aborted by a failed validation",
+        vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0]
+    )
+    .unwrap()
+});
+
+/// Count the number of times transactions executed in parallel were aborted or
+/// suspended, with a "reason" label to distinguish failed validations from waits
+/// on dependencies.
+pub static PARALLEL_EXECUTION_ABORTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_parallel_execution_aborts",
+        "Number of aborts of transactions executed in parallel",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+/// Count the number of blocks executed sequentially after parallel execution
+/// was abandoned, with a "reason" label.
+pub static PARALLEL_EXECUTION_SEQUENTIAL_FALLBACKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_parallel_execution_sequential_fallbacks",
+        "Number of blocks executed sequentially after parallel execution was abandoned",
+        &["reason"]
+    )
+    .unwrap()
+});
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 //! Scratchpad for on chain values during the execution.
 
-use crate::{counters::CRITICAL_ERRORS, create_access_path, logging::AdapterLogSchema};
+use crate::{
+    counters::CRITICAL_ERRORS, create_access_path, logging::AdapterLogSchema,
+    move_vm_ext::MoveResolverExt,
+};
 #[allow(unused_imports)]
 use anyhow::format_err;
 use anyhow::Error;
@@ -265,3 +268,72 @@ impl<S: StateView> IntoMoveResolver<S> for S {
         RemoteStorageOwned { state_view: self }
     }
 }
+
+/// Serves the writes of a finished session on top of the storage the session ran against, so
+/// that a later session of the same transaction, e.g. the one running its epilogue, observes
+/// them.
+pub(crate) struct WriteSetOverlay<'a, S> {
+    base: &'a S,
+    writes: BTreeMap<&'a StateKey, &'a WriteOp>,
+}
+
+impl<'a, S: MoveResolverExt> WriteSetOverlay<'a, S> {
+    pub fn new(base: &'a S, write_set: &'a WriteSet) -> Self {
+        Self {
+            base,
+            writes: write_set.iter().map(|(key, op)| (key, op)).collect(),
+        }
+    }
+
+    /// The value written under a state key, `None` if there is no write to it.
+    fn get_written(&self, state_key: &StateKey) -> Option<Option<Vec<u8>>> {
+        self.writes.get(state_key).map(|op| match op {
+            WriteOp::Creation(blob) | WriteOp::Modification(blob) => Some(blob.clone()),
+            WriteOp::Deletion => None,
+        })
+    }
+}
+
+impl<'a, S: MoveResolverExt> ModuleResolver for WriteSetOverlay<'a, S> {
+    type Error = S::ExtError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        match self.get_written(&StateKey::AccessPath(AccessPath::from(module_id))) {
+            Some(value) => Ok(value),
+            None => self.base.get_module(module_id),
+        }
+    }
+}
+
+impl<'a, S: MoveResolverExt> ResourceResolver for WriteSetOverlay<'a, S> {
+    type Error = S::ExtError;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        struct_tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        let ap = create_access_path(*address, struct_tag.clone());
+        match self.get_written(&StateKey::AccessPath(ap)) {
+            Some(value) => Ok(value),
+            None => self.base.get_resource(address, struct_tag),
+        }
+    }
+}
+
+impl<'a, S: MoveResolverExt> TableResolver for WriteSetOverlay<'a, S> {
+    fn resolve_table_entry(
+        &self,
+        handle: &TableHandle,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        match self.get_written(&StateKey::table_item((*handle).into(), key.to_vec())) {
+            Some(value) => Ok(value),
+            None => self.base.resolve_table_entry(handle, key),
+        }
+    }
+
+    fn operation_cost(&self, op: TableOperation, key_size: usize, val_size: usize) -> InternalGas {
+        self.base.operation_cost(op, key_size, val_size)
+    }
+}
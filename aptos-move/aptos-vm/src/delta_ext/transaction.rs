@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::delta_ext::DeltaChangeSet;
+use aptos_gas::StorageFee;
 use aptos_state_view::StateView;
 use aptos_types::{
     transaction::{ChangeSet, TransactionOutput},
@@ -32,6 +33,9 @@ impl ChangeSetExt {
 pub struct TransactionOutputExt {
     delta_change_set: DeltaChangeSet,
     output: TransactionOutput,
+    /// The storage fee included in the gas used, for the transactions that are charged for the
+    /// storage they change, i.e. the successful user transactions.
+    storage_fee: Option<StorageFee>,
 }
 
 impl TransactionOutputExt {
@@ -39,9 +43,19 @@ impl TransactionOutputExt {
         TransactionOutputExt {
             delta_change_set,
             output,
+            storage_fee: None,
         }
     }
 
+    pub fn with_storage_fee(mut self, storage_fee: StorageFee) -> Self {
+        self.storage_fee = Some(storage_fee);
+        self
+    }
+
+    pub fn storage_fee(&self) -> Option<&StorageFee> {
+        self.storage_fee.as_ref()
+    }
+
     pub fn delta_change_set(&self) -> &DeltaChangeSet {
         &self.delta_change_set
     }
@@ -108,6 +122,7 @@ impl From<TransactionOutput> for TransactionOutputExt {
         TransactionOutputExt {
             delta_change_set: DeltaChangeSet::empty(),
             output,
+            storage_fee: None,
         }
     }
 }
@@ -113,6 +113,7 @@ pub mod foreign_contracts;
 mod adapter_common;
 pub mod aptos_vm;
 mod aptos_vm_impl;
+mod block_gas_limit;
 pub mod delta_ext;
 mod errors;
 pub mod logging;
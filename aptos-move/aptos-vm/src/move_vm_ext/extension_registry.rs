@@ -0,0 +1,439 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The native extensions installed into every session of the VM, along with how the changes
+//! each of them makes are extracted from a finished session, squashed across sessions and
+//! turned into writes.
+//!
+//! The registry only holds the declarations of the extensions: every session gets new native
+//! contexts, so sessions created while another one is running, e.g. to run the epilogue after a
+//! failed transaction, never observe each other's extension state.
+
+use crate::move_vm_ext::{NativeAggregatorContext, SessionId};
+use aptos_types::{state_store::state_key::StateKey, vm_status::StatusCode, write_set::WriteOp};
+use framework::natives::{code::NativeCodeContext, transaction_context::NativeTransactionContext};
+use move_deps::{
+    move_binary_format::errors::{Location, VMResult},
+    move_core_types::{effects::Op as MoveStorageOp, vm_status::VMStatus},
+    move_table_extension::{NativeTableContext, TableChange, TableChangeSet, TableResolver},
+    move_vm_runtime::native_extensions::NativeContextExtensions,
+};
+use std::{
+    any::Any,
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+};
+
+/// A native extension installed into every session.
+pub trait SessionExtension: Send + Sync {
+    /// The name of the extension, unique within a registry.
+    fn name(&self) -> &'static str;
+
+    /// Adds a new native context of the extension to the extensions of a new session.
+    fn add_context<'r>(
+        &self,
+        extensions: &mut NativeContextExtensions<'r>,
+        session_id: &SessionId,
+        remote: &'r dyn TableResolver,
+    );
+
+    /// Extracts the changes the extension made in a finished session from its native context.
+    /// Extensions that don't change storage return `None`, which is the default.
+    fn extract_changes(
+        &self,
+        _extensions: &mut NativeContextExtensions,
+    ) -> VMResult<Option<Box<dyn ExtensionChanges>>> {
+        Ok(None)
+    }
+}
+
+/// The changes a native extension made in a session.
+pub trait ExtensionChanges: Send {
+    /// Applies the changes of a later session, made by the same extension, on top of these.
+    fn squash(&mut self, other: Box<dyn ExtensionChanges>) -> Result<(), VMStatus>;
+
+    /// Converts the changes into writes to storage.
+    fn into_write_ops(self: Box<Self>) -> Result<Vec<(StateKey, WriteOp)>, VMStatus>;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// The native extensions installed into the sessions of a VM, in the order their changes are
+/// written.
+#[derive(Clone)]
+pub struct ExtensionRegistry {
+    extensions: Vec<Arc<dyn SessionExtension>>,
+}
+
+impl ExtensionRegistry {
+    /// Creates a registry without any extensions.
+    pub fn empty() -> Self {
+        Self { extensions: vec![] }
+    }
+
+    /// Registers an extension. Panics if an extension with the same name is registered already,
+    /// since the changes of extensions are told apart by their names.
+    pub fn register(mut self, extension: impl SessionExtension + 'static) -> Self {
+        assert!(
+            self.get(extension.name()).is_none(),
+            "Native extension {} is registered twice",
+            extension.name()
+        );
+        self.extensions.push(Arc::new(extension));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<dyn SessionExtension>> {
+        self.extensions
+            .iter()
+            .find(|extension| extension.name() == name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.extensions.iter().map(|extension| extension.name())
+    }
+
+    /// Creates the native contexts of all the extensions for a new session.
+    pub(crate) fn new_contexts<'r>(
+        &self,
+        session_id: &SessionId,
+        remote: &'r dyn TableResolver,
+    ) -> NativeContextExtensions<'r> {
+        let mut extensions = NativeContextExtensions::default();
+        for extension in &self.extensions {
+            extension.add_context(&mut extensions, session_id, remote);
+        }
+        extensions
+    }
+
+    /// Extracts the changes of all the extensions from the native contexts of a finished
+    /// session.
+    pub(crate) fn extract_changes(
+        &self,
+        extensions: &mut NativeContextExtensions,
+    ) -> VMResult<ExtensionChangeSet> {
+        let mut changes = BTreeMap::new();
+        for (index, extension) in self.extensions.iter().enumerate() {
+            if let Some(extension_changes) = extension.extract_changes(extensions)? {
+                changes.insert(index, (extension.name(), extension_changes));
+            }
+        }
+        Ok(ExtensionChangeSet { changes })
+    }
+}
+
+impl Default for ExtensionRegistry {
+    /// The extensions of the Aptos framework.
+    fn default() -> Self {
+        Self::empty()
+            .register(TableExtension)
+            .register(AggregatorExtension)
+            .register(TransactionContextExtension)
+            .register(CodeExtension)
+    }
+}
+
+/// The changes made by all the extensions of a registry in a session, keyed by the index of the
+/// extension in the registry, so that they're in registration order however they're squashed.
+#[derive(Default)]
+pub struct ExtensionChangeSet {
+    changes: BTreeMap<usize, (&'static str, Box<dyn ExtensionChanges>)>,
+}
+
+impl ExtensionChangeSet {
+    /// Creates the changes of the given extensions of the default registry.
+    #[cfg(test)]
+    pub(crate) fn new(changes: Vec<(&'static str, Box<dyn ExtensionChanges>)>) -> Self {
+        let registry = ExtensionRegistry::default();
+        Self {
+            changes: changes
+                .into_iter()
+                .map(|(name, changes)| {
+                    let index = registry
+                        .extensions
+                        .iter()
+                        .position(|extension| extension.name() == name)
+                        .expect("Unknown native extension");
+                    (index, (name, changes))
+                })
+                .collect(),
+        }
+    }
+
+    /// Takes the changes made by the extension with the given name, if any.
+    pub fn take<T: ExtensionChanges + 'static>(&mut self, name: &str) -> Option<T> {
+        let index = *self
+            .changes
+            .iter()
+            .find(|(_, (n, _))| *n == name)
+            .map(|(index, _)| index)?;
+        let (_, changes) = self.changes.remove(&index)?;
+        changes.into_any().downcast().ok().map(|changes| *changes)
+    }
+
+    pub fn squash(&mut self, other: Self) -> Result<(), VMStatus> {
+        for (index, (name, other_changes)) in other.changes {
+            match self.changes.entry(index) {
+                Entry::Occupied(mut entry) => entry.get_mut().1.squash(other_changes)?,
+                Entry::Vacant(entry) => {
+                    entry.insert((name, other_changes));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn into_write_ops(self) -> Result<Vec<(StateKey, WriteOp)>, VMStatus> {
+        let mut write_ops = vec![];
+        for (_, (_, changes)) in self.changes {
+            write_ops.extend(changes.into_write_ops()?);
+        }
+        Ok(write_ops)
+    }
+}
+
+pub const TABLE_EXTENSION: &str = "table";
+pub const AGGREGATOR_EXTENSION: &str = "aggregator";
+pub const TRANSACTION_CONTEXT_EXTENSION: &str = "transaction_context";
+pub const CODE_EXTENSION: &str = "code";
+
+struct TableExtension;
+
+impl SessionExtension for TableExtension {
+    fn name(&self) -> &'static str {
+        TABLE_EXTENSION
+    }
+
+    fn add_context<'r>(
+        &self,
+        extensions: &mut NativeContextExtensions<'r>,
+        session_id: &SessionId,
+        remote: &'r dyn TableResolver,
+    ) {
+        extensions.add(NativeTableContext::new(session_id.as_uuid(), remote));
+    }
+
+    fn extract_changes(
+        &self,
+        extensions: &mut NativeContextExtensions,
+    ) -> VMResult<Option<Box<dyn ExtensionChanges>>> {
+        let table_context: NativeTableContext = extensions.remove();
+        let table_change_set = table_context
+            .into_change_set()
+            .map_err(|e| e.finish(Location::Undefined))?;
+        Ok(Some(Box::new(table_change_set)))
+    }
+}
+
+impl ExtensionChanges for TableChangeSet {
+    fn squash(&mut self, other: Box<dyn ExtensionChanges>) -> Result<(), VMStatus> {
+        let other: TableChangeSet = *other
+            .into_any()
+            .downcast()
+            .map_err(|_| VMStatus::Error(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR))?;
+
+        self.new_tables.extend(other.new_tables);
+        for removed_table in &self.removed_tables {
+            self.new_tables.remove(removed_table);
+        }
+        // There's chance that a table is added in `self`, and an item is added to that table in
+        // `self`, and later the item is deleted in `other`, netting to a NOOP for that item,
+        // but this is an tricky edge case that we don't expect to happen too much, it doesn't hurt
+        // too much to just keep the deletion. It's safe as long as we do it that way consistently.
+        self.removed_tables.extend(other.removed_tables.into_iter());
+        for (handle, changes) in other.changes.into_iter() {
+            let my_changes = self.changes.entry(handle).or_insert(TableChange {
+                entries: Default::default(),
+            });
+            my_changes.entries.extend(changes.entries.into_iter());
+        }
+        Ok(())
+    }
+
+    fn into_write_ops(self: Box<Self>) -> Result<Vec<(StateKey, WriteOp)>, VMStatus> {
+        use MoveStorageOp::*;
+
+        let mut write_ops = vec![];
+        for (handle, change) in self.changes {
+            for (key, value_op) in change.entries {
+                let state_key = StateKey::table_item(handle.into(), key);
+                match value_op {
+                    Delete => write_ops.push((state_key, WriteOp::Deletion)),
+                    New(bytes) => write_ops.push((state_key, WriteOp::Creation(bytes))),
+                    Modify(bytes) => write_ops.push((state_key, WriteOp::Modification(bytes))),
+                }
+            }
+        }
+        Ok(write_ops)
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+struct AggregatorExtension;
+
+impl SessionExtension for AggregatorExtension {
+    fn name(&self) -> &'static str {
+        AGGREGATOR_EXTENSION
+    }
+
+    fn add_context<'r>(
+        &self,
+        extensions: &mut NativeContextExtensions<'r>,
+        session_id: &SessionId,
+        remote: &'r dyn TableResolver,
+    ) {
+        extensions.add(NativeAggregatorContext::new(session_id.as_uuid(), remote));
+    }
+
+    // TODO: Once we are ready to connect aggregator with delta writes,
+    // extract its changes here.
+    //
+    // Expected changes will be:
+    //   * Use `Aggregator` for gas fees tracking in coin.
+    //   * Pass `aggregator_change_set` further to produce `DeltaChangeSet`.
+    //   * Have e2e tests and benchmarks.
+}
+
+struct TransactionContextExtension;
+
+impl SessionExtension for TransactionContextExtension {
+    fn name(&self) -> &'static str {
+        TRANSACTION_CONTEXT_EXTENSION
+    }
+
+    fn add_context<'r>(
+        &self,
+        extensions: &mut NativeContextExtensions<'r>,
+        session_id: &SessionId,
+        _remote: &'r dyn TableResolver,
+    ) {
+        let script_hash = match session_id {
+            SessionId::Txn { script_hash, .. } | SessionId::Epilogue { script_hash, .. } => {
+                script_hash.clone()
+            }
+            _ => vec![],
+        };
+        extensions.add(NativeTransactionContext::new(script_hash));
+    }
+}
+
+struct CodeExtension;
+
+impl SessionExtension for CodeExtension {
+    fn name(&self) -> &'static str {
+        CODE_EXTENSION
+    }
+
+    fn add_context<'r>(
+        &self,
+        extensions: &mut NativeContextExtensions<'r>,
+        _session_id: &SessionId,
+        _remote: &'r dyn TableResolver,
+    ) {
+        extensions.add(NativeCodeContext::default());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct TestChanges(Vec<(StateKey, WriteOp)>);
+
+    impl ExtensionChanges for TestChanges {
+        fn squash(&mut self, other: Box<dyn ExtensionChanges>) -> Result<(), VMStatus> {
+            let other: TestChanges = *other.into_any().downcast().unwrap();
+            self.0.extend(other.0);
+            Ok(())
+        }
+
+        fn into_write_ops(self: Box<Self>) -> Result<Vec<(StateKey, WriteOp)>, VMStatus> {
+            Ok(self.0)
+        }
+
+        fn into_any(self: Box<Self>) -> Box<dyn Any> {
+            self
+        }
+    }
+
+    fn write(key: &str) -> (StateKey, WriteOp) {
+        (
+            StateKey::Raw(key.as_bytes().to_vec()),
+            WriteOp::Modification(vec![]),
+        )
+    }
+
+    /// Creates the changes of extensions named after the letter of the alphabet at their index in
+    /// the registry.
+    fn change_set(changes: Vec<(&'static str, Vec<&str>)>) -> ExtensionChangeSet {
+        ExtensionChangeSet {
+            changes: changes
+                .into_iter()
+                .map(|(name, keys)| {
+                    let index = (name.as_bytes()[0] - b'a') as usize;
+                    let changes: Box<dyn ExtensionChanges> =
+                        Box::new(TestChanges(keys.into_iter().map(write).collect()));
+                    (index, (name, changes))
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_default_extensions() {
+        assert_eq!(
+            ExtensionRegistry::default().names().collect::<Vec<_>>(),
+            vec![
+                TABLE_EXTENSION,
+                AGGREGATOR_EXTENSION,
+                TRANSACTION_CONTEXT_EXTENSION,
+                CODE_EXTENSION
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_twice() {
+        ExtensionRegistry::default().register(CodeExtension);
+    }
+
+    #[test]
+    fn test_squash() {
+        let mut changes = change_set(vec![("a", vec!["a1"]), ("b", vec!["b1"])]);
+        changes
+            .squash(change_set(vec![("c", vec!["c1"]), ("a", vec!["a2"])]))
+            .unwrap();
+        assert_eq!(
+            changes.into_write_ops().unwrap(),
+            vec![write("a1"), write("a2"), write("b1"), write("c1")]
+        );
+    }
+
+    #[test]
+    fn test_squash_in_registration_order() {
+        let mut changes = change_set(vec![("b", vec!["b1"])]);
+        changes
+            .squash(change_set(vec![("a", vec!["a1"]), ("c", vec!["c1"])]))
+            .unwrap();
+        changes.squash(change_set(vec![("b", vec!["b2"])])).unwrap();
+        assert_eq!(
+            changes.into_write_ops().unwrap(),
+            vec![write("a1"), write("b1"), write("b2"), write("c1")]
+        );
+    }
+
+    #[test]
+    fn test_take() {
+        let mut changes = change_set(vec![("a", vec!["a1"]), ("b", vec!["b1"])]);
+        assert_eq!(
+            changes.take::<TestChanges>("a").unwrap().0,
+            vec![write("a1")]
+        );
+        assert!(changes.take::<TestChanges>("a").is_none());
+        assert_eq!(changes.into_write_ops().unwrap(), vec![write("b1")]);
+    }
+}
@@ -4,12 +4,19 @@
 ///! MoveVM and Session wrapped, to make sure Aptos natives and extensions are always installed and
 ///! taken care of after session finish.
 mod aggregator_extension;
+mod extension_registry;
 mod resolver;
 mod session;
+#[cfg(test)]
+mod session_squash_test;
 mod vm;
 
 pub use crate::move_vm_ext::{
     aggregator_extension::{aggregator_natives, NativeAggregatorContext},
+    extension_registry::{
+        ExtensionChangeSet, ExtensionChanges, ExtensionRegistry, SessionExtension,
+        AGGREGATOR_EXTENSION, CODE_EXTENSION, TABLE_EXTENSION, TRANSACTION_CONTEXT_EXTENSION,
+    },
     resolver::MoveResolverExt,
     session::{SessionExt, SessionId, SessionOutput},
     vm::MoveVmExt,
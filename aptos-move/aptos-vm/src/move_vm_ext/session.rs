@@ -4,32 +4,37 @@
 use crate::{
     access_path_cache::AccessPathCache,
     delta_ext::{ChangeSetExt, DeltaChangeSet},
-    move_vm_ext::MoveResolverExt,
+    move_vm_ext::{ExtensionChangeSet, ExtensionRegistry, MoveResolverExt},
     transaction_metadata::TransactionMetadata,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_gas::ChangeSetStats;
 use aptos_types::{
+    access_path::Path,
     block_metadata::BlockMetadata,
     contract_event::ContractEvent,
     state_store::state_key::StateKey,
     transaction::{ChangeSet, SignatureCheckedTransaction},
     write_set::{WriteOp, WriteSetMut},
 };
+use fail::fail_point;
 use framework::natives::code::{NativeCodeContext, PublishRequest};
 use move_deps::{
-    move_binary_format::errors::{Location, VMResult},
+    move_binary_format::errors::VMResult,
     move_core_types::{
         account_address::AccountAddress,
         effects::{ChangeSet as MoveChangeSet, Event as MoveEvent, Op as MoveStorageOp},
         language_storage::ModuleId,
+        resolver::{ModuleResolver, ResourceResolver},
         vm_status::{StatusCode, VMStatus},
     },
-    move_table_extension::{NativeTableContext, TableChange, TableChangeSet},
+    move_table_extension::{TableHandle, TableResolver},
     move_vm_runtime::session::Session,
 };
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     convert::TryInto,
     ops::{Deref, DerefMut},
 };
@@ -51,6 +56,13 @@ pub enum SessionId {
     },
     // For those runs that are not a transaction and the output of which won't be committed.
     Void,
+    // For the epilogue of a transaction when it runs in a session of its own, after the session
+    // of the transaction, so that the two never derive the same ids, e.g. of new tables.
+    Epilogue {
+        sender: AccountAddress,
+        sequence_number: u64,
+        script_hash: Vec<u8>,
+    },
 }
 
 impl SessionId {
@@ -66,6 +78,14 @@ impl SessionId {
         }
     }
 
+    pub fn epilogue_meta(txn_data: &TransactionMetadata) -> Self {
+        Self::Epilogue {
+            sender: txn_data.sender,
+            sequence_number: txn_data.sequence_number,
+            script_hash: txn_data.script_hash.clone(),
+        }
+    }
+
     pub fn genesis(id: HashValue) -> Self {
         Self::Genesis { id }
     }
@@ -91,37 +111,39 @@ impl SessionId {
 
 pub struct SessionExt<'r, 'l, S> {
     inner: Session<'r, 'l, S>,
+    remote: &'r S,
+    extension_registry: &'l ExtensionRegistry,
 }
 
 impl<'r, 'l, S> SessionExt<'r, 'l, S>
 where
     S: MoveResolverExt,
 {
-    pub fn new(inner: Session<'r, 'l, S>) -> Self {
-        Self { inner }
+    pub fn new(
+        inner: Session<'r, 'l, S>,
+        remote: &'r S,
+        extension_registry: &'l ExtensionRegistry,
+    ) -> Self {
+        Self {
+            inner,
+            remote,
+            extension_registry,
+        }
+    }
+
+    /// The storage the session runs on top of, without the changes made in the session.
+    pub fn remote(&self) -> &'r S {
+        self.remote
     }
 
     pub fn finish(self) -> VMResult<SessionOutput> {
         let (change_set, events, mut extensions) = self.inner.finish_with_extensions()?;
-        let table_context: NativeTableContext = extensions.remove();
-        let table_change_set = table_context
-            .into_change_set()
-            .map_err(|e| e.finish(Location::Undefined))?;
-
-        // TODO: Once we are ready to connect aggregator with delta writes,
-        // make sure we pass them to the session output.
-        //
-        // Expected changes will be:
-        //   * Use `Aggregator` for gas fees tracking in coin.
-        //   * Pass `aggregator_change_set` further to produce `DeltaChangeSet`.
-        //   * Have e2e tests and benchmarks.
-        // let aggregator_context: NativeAggregatorContext = extensions.remove();
-        // let _ = aggregator_context.into_change_set();
+        let extension_change_set = self.extension_registry.extract_changes(&mut extensions)?;
 
         Ok(SessionOutput {
             change_set,
             events,
-            table_change_set,
+            extension_change_set,
         })
     }
 
@@ -148,7 +170,8 @@ impl<'r, 'l, S> DerefMut for SessionExt<'r, 'l, S> {
 pub struct SessionOutput {
     pub change_set: MoveChangeSet,
     pub events: Vec<MoveEvent>,
-    pub table_change_set: TableChangeSet,
+    /// The changes made by the native extensions, such as tables.
+    pub extension_change_set: ExtensionChangeSet,
 }
 
 impl SessionOutput {
@@ -156,24 +179,64 @@ impl SessionOutput {
         self,
         ap_cache: &mut C,
     ) -> Result<ChangeSet, VMStatus> {
+        self.into_change_set_and_new_resources(ap_cache)
+            .map(|(change_set, _new_resources)| change_set)
+    }
+
+    /// Converts the output into a change set along with the statistics of the storage it
+    /// allocates, overwrites and frees, which storage gas is charged for. `remote` must be the
+    /// storage the session ran against, which the bytes freed by deletions are read from.
+    pub fn into_change_set_with_stats<C: AccessPathCache, S: MoveResolverExt>(
+        self,
+        ap_cache: &mut C,
+        remote: &S,
+    ) -> Result<(ChangeSet, ChangeSetStats), VMStatus> {
+        let (change_set, new_resources) = self.into_change_set_and_new_resources(ap_cache)?;
+
+        let mut stats = ChangeSetStats::default();
+        for (state_key, op) in change_set.write_set().iter() {
+            match op {
+                WriteOp::Creation(blob) => stats.record_creation(blob.len()),
+                // Resources are written as modifications even when they are new.
+                WriteOp::Modification(blob) if new_resources.contains(state_key) => {
+                    stats.record_creation(blob.len())
+                }
+                WriteOp::Modification(blob) => stats.record_modification(blob.len()),
+                WriteOp::Deletion => stats.record_deletion(stored_size(remote, state_key)?),
+            }
+        }
+        Ok((change_set, stats))
+    }
+
+    /// Converts the output into a change set, along with the state keys of the resources it
+    /// creates.
+    fn into_change_set_and_new_resources<C: AccessPathCache>(
+        self,
+        ap_cache: &mut C,
+    ) -> Result<(ChangeSet, HashSet<StateKey>), VMStatus> {
         use MoveStorageOp::*;
 
         let Self {
             change_set,
             events,
-            table_change_set,
+            extension_change_set,
         } = self;
 
         let mut write_set_mut = WriteSetMut::new(Vec::new());
+        let mut new_resources = HashSet::new();
         for (addr, account_changeset) in change_set.into_inner() {
             let (modules, resources) = account_changeset.into_inner();
             for (struct_tag, blob_op) in resources {
-                let ap = ap_cache.get_resource_path(addr, struct_tag);
+                let state_key = StateKey::AccessPath(ap_cache.get_resource_path(addr, struct_tag));
                 let op = match blob_op {
                     Delete => WriteOp::Deletion,
-                    New(blob) | Modify(blob) => WriteOp::Modification(blob),
+                    New(blob) => {
+                        new_resources.insert(state_key.clone());
+                        WriteOp::Modification(blob)
+                    }
+                    Modify(blob) => WriteOp::Modification(blob),
                 };
-                write_set_mut.push((StateKey::AccessPath(ap), op))
+                write_set_mut.push((state_key, op))
             }
 
             for (name, blob_op) in modules {
@@ -188,15 +251,8 @@ impl SessionOutput {
             }
         }
 
-        for (handle, change) in table_change_set.changes {
-            for (key, value_op) in change.entries {
-                let state_key = StateKey::table_item(handle.into(), key);
-                match value_op {
-                    Delete => write_set_mut.push((state_key, WriteOp::Deletion)),
-                    New(bytes) => write_set_mut.push((state_key, WriteOp::Creation(bytes))),
-                    Modify(bytes) => write_set_mut.push((state_key, WriteOp::Modification(bytes))),
-                }
-            }
+        for write_op in extension_change_set.into_write_ops()? {
+            write_set_mut.push(write_op);
         }
 
         let write_set = write_set_mut
@@ -212,7 +268,7 @@ impl SessionOutput {
             })
             .collect::<Result<Vec<_>, VMStatus>>()?;
 
-        Ok(ChangeSet::new(write_set, events))
+        Ok((ChangeSet::new(write_set, events), new_resources))
     }
 
     pub fn into_change_set_ext<C: AccessPathCache>(
@@ -226,35 +282,35 @@ impl SessionOutput {
     }
 
     pub fn squash(&mut self, other: Self) -> Result<(), VMStatus> {
+        fail_point!("move_adapter::session_output::squash", |_| {
+            Err(VMStatus::Error(StatusCode::DATA_FORMAT_ERROR))
+        });
         self.change_set
             .squash(other.change_set)
             .map_err(|_| VMStatus::Error(StatusCode::DATA_FORMAT_ERROR))?;
         self.events.extend(other.events.into_iter());
 
-        // Squash the table changes
-        self.table_change_set
-            .new_tables
-            .extend(other.table_change_set.new_tables);
-        for removed_table in &self.table_change_set.removed_tables {
-            self.table_change_set.new_tables.remove(removed_table);
-        }
-        // There's chance that a table is added in `self`, and an item is added to that table in
-        // `self`, and later the item is deleted in `other`, netting to a NOOP for that item,
-        // but this is an tricky edge case that we don't expect to happen too much, it doesn't hurt
-        // too much to just keep the deletion. It's safe as long as we do it that way consistently.
-        self.table_change_set
-            .removed_tables
-            .extend(other.table_change_set.removed_tables.into_iter());
-        for (handle, changes) in other.table_change_set.changes.into_iter() {
-            let my_changes = self
-                .table_change_set
-                .changes
-                .entry(handle)
-                .or_insert(TableChange {
-                    entries: Default::default(),
-                });
-            my_changes.entries.extend(changes.entries.into_iter());
-        }
+        self.extension_change_set
+            .squash(other.extension_change_set)?;
         Ok(())
     }
 }
+
+/// The size of the value stored under a state key before a session, 0 if there is none.
+fn stored_size<S: MoveResolverExt>(remote: &S, state_key: &StateKey) -> Result<usize, VMStatus> {
+    let value = match state_key {
+        StateKey::AccessPath(ap) => match ap.get_path() {
+            Path::Code(module_id) => remote
+                .get_module(&module_id)
+                .map_err(|_| VMStatus::Error(StatusCode::STORAGE_ERROR))?,
+            Path::Resource(struct_tag) => remote
+                .get_resource(&ap.address, &struct_tag)
+                .map_err(|_| VMStatus::Error(StatusCode::STORAGE_ERROR))?,
+        },
+        StateKey::TableItem { handle, key } => remote
+            .resolve_table_entry(&TableHandle(handle.0), key)
+            .map_err(|_| VMStatus::Error(StatusCode::STORAGE_ERROR))?,
+        StateKey::Raw(_) => None,
+    };
+    Ok(value.map_or(0, |value| value.len()))
+}
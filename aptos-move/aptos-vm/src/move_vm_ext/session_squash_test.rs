@@ -0,0 +1,343 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Property tests of squashing the outputs of sessions, i.e. of the change sets of Move, the
+//! change sets of tables and the events of consecutive sessions.
+//!
+//! The sessions are generated against a model of storage, so that each of them only creates the
+//! items that don't exist and only modifies or deletes the ones that do, as sessions of the VM
+//! would. Deltas are not covered: sessions don't produce them until the aggregator extension
+//! extracts its changes.
+
+use crate::{
+    aptos_vm_impl::squash_change_sets,
+    move_vm_ext::{ExtensionChangeSet, SessionOutput, TABLE_EXTENSION},
+};
+use aptos_types::{
+    event::EventKey, state_store::state_key::StateKey, transaction::ChangeSet, write_set::WriteOp,
+};
+use move_deps::{
+    move_core_types::{
+        account_address::AccountAddress,
+        effects::{AccountChangeSet, ChangeSet as MoveChangeSet, Event as MoveEvent, Op},
+        identifier::Identifier,
+        language_storage::{StructTag, TypeTag},
+    },
+    move_table_extension::{TableChange, TableChangeSet, TableHandle},
+};
+use proptest::{collection::vec, prelude::*, sample::select};
+use std::collections::{BTreeMap, BTreeSet};
+
+const NUM_ADDRESSES: u8 = 2;
+const NUM_NAMES: u8 = 2;
+const NUM_TABLES: u8 = 2;
+const NUM_TABLE_KEYS: u8 = 3;
+
+/// An item of storage sessions write.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Slot {
+    Resource { address: u8, name: u8 },
+    Module { address: u8, name: u8 },
+    TableItem { table: u8, key: u8 },
+}
+
+fn all_slots() -> Vec<Slot> {
+    let mut slots = vec![];
+    for address in 0..NUM_ADDRESSES {
+        for name in 0..NUM_NAMES {
+            slots.push(Slot::Resource { address, name });
+            slots.push(Slot::Module { address, name });
+        }
+    }
+    for table in 0..NUM_TABLES {
+        for key in 0..NUM_TABLE_KEYS {
+            slots.push(Slot::TableItem { table, key });
+        }
+    }
+    slots
+}
+
+/// What a session means to do to an item: write the value, or delete the item if there is none.
+type Intent = (Slot, Option<Vec<u8>>);
+
+fn arb_intents() -> impl Strategy<Value = Vec<Intent>> {
+    vec(
+        (
+            select(all_slots()),
+            proptest::option::of(vec(any::<u8>(), 1..4)),
+        ),
+        0..8,
+    )
+}
+
+/// The writes and events of a session.
+#[derive(Clone, Debug)]
+struct Session {
+    ops: BTreeMap<Slot, Op<Vec<u8>>>,
+    events: Vec<Vec<u8>>,
+}
+
+impl Session {
+    /// Turns the intents into the writes of a session running against the items in `existing`,
+    /// which are updated with the writes. Writing an item creates it if it doesn't exist, and
+    /// deletions of items that don't exist are left out.
+    fn new(existing: &mut BTreeSet<Slot>, intents: Vec<Intent>, events: Vec<Vec<u8>>) -> Self {
+        let mut ops = BTreeMap::new();
+        for (slot, value) in intents {
+            if ops.contains_key(&slot) {
+                continue;
+            }
+            let op = match (existing.contains(&slot), value) {
+                (true, Some(value)) => Op::Modify(value),
+                (true, None) => Op::Delete,
+                (false, Some(value)) => Op::New(value),
+                (false, None) => continue,
+            };
+            ops.insert(slot, op);
+        }
+        for (slot, op) in &ops {
+            match op {
+                Op::Delete => existing.remove(slot),
+                Op::New(_) | Op::Modify(_) => existing.insert(*slot),
+            };
+        }
+        Self { ops, events }
+    }
+
+    /// Leaves out the writes to the items the other session writes.
+    fn without_slots_of(mut self, other: &Session) -> Self {
+        self.ops.retain(|slot, _| !other.ops.contains_key(slot));
+        self
+    }
+
+    fn output(&self) -> SessionOutput {
+        let mut accounts = BTreeMap::new();
+        let mut table_changes = BTreeMap::new();
+        for (slot, op) in &self.ops {
+            match *slot {
+                Slot::Resource { address, name } => {
+                    let (_, resources): &mut (BTreeMap<_, _>, BTreeMap<_, _>) =
+                        accounts.entry(address).or_default();
+                    resources.insert(struct_tag(name), op.clone());
+                }
+                Slot::Module { address, name } => {
+                    let (modules, _): &mut (BTreeMap<_, _>, BTreeMap<_, _>) =
+                        accounts.entry(address).or_default();
+                    modules.insert(identifier(name), op.clone());
+                }
+                Slot::TableItem { table, key } => {
+                    table_changes
+                        .entry(TableHandle(table as u128))
+                        .or_insert_with(|| TableChange {
+                            entries: BTreeMap::new(),
+                        })
+                        .entries
+                        .insert(vec![key], op.clone());
+                }
+            }
+        }
+
+        let mut change_set = MoveChangeSet::new();
+        for (address, (modules, resources)) in accounts {
+            change_set
+                .add_account_changeset(
+                    account_address(address),
+                    AccountChangeSet::from_modules_resources(modules, resources),
+                )
+                .unwrap();
+        }
+        let table_change_set = TableChangeSet {
+            new_tables: BTreeMap::new(),
+            removed_tables: BTreeSet::new(),
+            changes: table_changes,
+        };
+        SessionOutput {
+            change_set,
+            events: self.events.iter().map(|data| event(data)).collect(),
+            extension_change_set: ExtensionChangeSet::new(vec![(
+                TABLE_EXTENSION,
+                Box::new(table_change_set),
+            )]),
+        }
+    }
+
+    fn change_set(&self) -> ChangeSet {
+        self.output().into_change_set(&mut ()).unwrap()
+    }
+}
+
+fn account_address(address: u8) -> AccountAddress {
+    AccountAddress::from_hex_literal(&format!("0x{}", address + 1)).unwrap()
+}
+
+fn identifier(name: u8) -> Identifier {
+    Identifier::new(format!("M{}", name)).unwrap()
+}
+
+fn struct_tag(name: u8) -> StructTag {
+    StructTag {
+        address: account_address(0),
+        module: identifier(0),
+        name: Identifier::new(format!("R{}", name)).unwrap(),
+        type_params: vec![],
+    }
+}
+
+fn event(data: &[u8]) -> MoveEvent {
+    let key = EventKey::new(0, account_address(0));
+    (bcs::to_bytes(&key).unwrap(), 0, TypeTag::U8, data.to_vec())
+}
+
+/// Squashes the outputs of the sessions, in order.
+fn squash(sessions: &[&Session]) -> SessionOutput {
+    let mut output = sessions[0].output();
+    for session in &sessions[1..] {
+        output.squash(session.output()).unwrap();
+    }
+    output
+}
+
+/// The write set and events of a change set, with the writes keyed by state key, since their
+/// order doesn't matter.
+fn contents(change_set: ChangeSet) -> (BTreeMap<StateKey, WriteOp>, Vec<Vec<u8>>) {
+    let (write_set, events) = change_set.into_inner();
+    (
+        write_set.into_iter().collect(),
+        events
+            .into_iter()
+            .map(|event| event.event_data().to_vec())
+            .collect(),
+    )
+}
+
+/// Applies the writes of a change set to storage.
+fn apply(storage: &mut BTreeMap<StateKey, Vec<u8>>, change_set: ChangeSet) {
+    for (state_key, op) in change_set.write_set().iter() {
+        match op {
+            WriteOp::Creation(blob) | WriteOp::Modification(blob) => {
+                storage.insert(state_key.clone(), blob.clone());
+            }
+            WriteOp::Deletion => {
+                storage.remove(state_key);
+            }
+        }
+    }
+}
+
+/// The items that exist before the sessions, and the storage holding them.
+fn initial_state(existing: &[bool]) -> (BTreeSet<Slot>, BTreeMap<StateKey, Vec<u8>>) {
+    let slots: BTreeSet<Slot> = all_slots()
+        .into_iter()
+        .zip(existing)
+        .filter_map(|(slot, exists)| exists.then(|| slot))
+        .collect();
+    let mut storage = BTreeMap::new();
+    let intents = slots.iter().map(|slot| (*slot, Some(vec![0]))).collect();
+    apply(
+        &mut storage,
+        Session::new(&mut BTreeSet::new(), intents, vec![]).change_set(),
+    );
+    (slots, storage)
+}
+
+fn arb_existing() -> impl Strategy<Value = Vec<bool>> {
+    vec(any::<bool>(), all_slots().len())
+}
+
+fn arb_events() -> impl Strategy<Value = Vec<Vec<u8>>> {
+    vec(vec(any::<u8>(), 1..4), 0..3)
+}
+
+proptest! {
+    #[test]
+    fn squash_is_sequential_application(
+        existing in arb_existing(),
+        sessions in vec((arb_intents(), arb_events()), 1..5),
+    ) {
+        let (mut slots, storage) = initial_state(&existing);
+        let sessions: Vec<Session> = sessions
+            .into_iter()
+            .map(|(intents, events)| Session::new(&mut slots, intents, events))
+            .collect();
+        let sessions: Vec<&Session> = sessions.iter().collect();
+
+        let mut expected_storage = storage.clone();
+        let mut expected_events = vec![];
+        for session in &sessions {
+            let change_set = session.change_set();
+            expected_events.extend(contents(change_set.clone()).1);
+            apply(&mut expected_storage, change_set);
+        }
+
+        let squashed = squash(&sessions).into_change_set(&mut ()).unwrap();
+        prop_assert_eq!(&contents(squashed.clone()).1, &expected_events);
+        let mut squashed_storage = storage.clone();
+        apply(&mut squashed_storage, squashed);
+        prop_assert_eq!(&squashed_storage, &expected_storage);
+
+        // Squashing the change sets the sessions are converted into, as the VM does with the
+        // epilogue of a transaction, is equivalent too.
+        let mut change_set = sessions[0].change_set();
+        for session in &sessions[1..] {
+            let (write_set, events) = squash_change_sets(change_set, session.change_set()).unwrap();
+            change_set = ChangeSet::new(write_set, events);
+        }
+        let mut squashed_storage = storage;
+        apply(&mut squashed_storage, change_set);
+        prop_assert_eq!(&squashed_storage, &expected_storage);
+    }
+
+    #[test]
+    fn squash_is_associative(
+        existing in arb_existing(),
+        sessions in vec((arb_intents(), arb_events()), 3),
+    ) {
+        let (mut slots, _) = initial_state(&existing);
+        let sessions: Vec<Session> = sessions
+            .into_iter()
+            .map(|(intents, events)| Session::new(&mut slots, intents, events))
+            .collect();
+        let (a, b, c) = (&sessions[0], &sessions[1], &sessions[2]);
+
+        let left = squash(&[a, b, c]);
+        let mut right = a.output();
+        right.squash(squash(&[b, c])).unwrap();
+        prop_assert_eq!(
+            contents(left.into_change_set(&mut ()).unwrap()),
+            contents(right.into_change_set(&mut ()).unwrap())
+        );
+    }
+
+    #[test]
+    fn squash_of_disjoint_sessions_commutes(
+        existing in arb_existing(),
+        (a_intents, a_events) in (arb_intents(), arb_events()),
+        (b_intents, b_events) in (arb_intents(), arb_events()),
+    ) {
+        // Both sessions run against the same items, since neither writes what the other does.
+        let (slots, _) = initial_state(&existing);
+        let a = Session::new(&mut slots.clone(), a_intents, a_events);
+        let b = Session::new(&mut slots.clone(), b_intents, b_events).without_slots_of(&a);
+
+        let (ab_writes, ab_events) = contents(squash(&[&a, &b]).into_change_set(&mut ()).unwrap());
+        let (ba_writes, ba_events) = contents(squash(&[&b, &a]).into_change_set(&mut ()).unwrap());
+        prop_assert_eq!(ab_writes, ba_writes);
+        prop_assert_eq!(ab_events, [a.events.clone(), b.events.clone()].concat());
+        prop_assert_eq!(ba_events, [b.events, a.events].concat());
+    }
+}
+
+#[cfg(feature = "failpoints")]
+#[test]
+fn squash_failpoint() {
+    let scenario = fail::FailScenario::setup();
+    fail::cfg("move_adapter::session_output::squash", "return").unwrap();
+
+    let session = Session::new(
+        &mut BTreeSet::new(),
+        vec![(Slot::TableItem { table: 0, key: 0 }, Some(vec![1]))],
+        vec![],
+    );
+    assert!(session.output().squash(session.output()).is_err());
+    scenario.teardown();
+}
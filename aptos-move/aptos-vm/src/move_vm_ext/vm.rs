@@ -2,25 +2,32 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    move_vm_ext::{MoveResolverExt, NativeAggregatorContext, SessionExt, SessionId},
+    move_vm_ext::{ExtensionRegistry, MoveResolverExt, SessionExt, SessionId},
     natives::aptos_natives,
 };
 use aptos_gas::NativeGasParameters;
-use framework::natives::{code::NativeCodeContext, transaction_context::NativeTransactionContext};
 use move_deps::{
-    move_binary_format::errors::VMResult,
-    move_bytecode_verifier::VerifierConfig,
-    move_table_extension::NativeTableContext,
-    move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions},
+    move_binary_format::errors::VMResult, move_bytecode_verifier::VerifierConfig,
+    move_vm_runtime::move_vm::MoveVM,
 };
 use std::ops::Deref;
 
 pub struct MoveVmExt {
     inner: MoveVM,
+    extension_registry: ExtensionRegistry,
 }
 
 impl MoveVmExt {
     pub fn new(native_gas_params: NativeGasParameters) -> VMResult<Self> {
+        Self::new_with_extensions(native_gas_params, ExtensionRegistry::default())
+    }
+
+    /// Creates a VM whose sessions get the native extensions of the given registry instead of
+    /// the ones of the Aptos framework.
+    pub fn new_with_extensions(
+        native_gas_params: NativeGasParameters,
+        extension_registry: ExtensionRegistry,
+    ) -> VMResult<Self> {
         Ok(Self {
             inner: MoveVM::new_with_verifier_config(
                 aptos_natives(native_gas_params),
@@ -28,34 +35,30 @@ impl MoveVmExt {
                     max_loop_depth: Some(5),
                 },
             )?,
+            extension_registry,
         })
     }
 
+    pub fn extension_registry(&self) -> &ExtensionRegistry {
+        &self.extension_registry
+    }
+
     pub fn new_session<'r, S: MoveResolverExt>(
         &self,
         remote: &'r S,
         session_id: SessionId,
     ) -> SessionExt<'r, '_, S> {
-        let mut extensions = NativeContextExtensions::default();
-        extensions.add(NativeTableContext::new(session_id.as_uuid(), remote));
-        extensions.add(NativeAggregatorContext::new(session_id.as_uuid(), remote));
-
-        let script_hash = match session_id {
-            SessionId::Txn {
-                sender: _,
-                sequence_number: _,
-                script_hash,
-            } => script_hash,
-            _ => vec![],
-        };
-        extensions.add(NativeTransactionContext::new(script_hash));
-        extensions.add(NativeCodeContext::default());
+        let extensions = self.extension_registry.new_contexts(&session_id, remote);
 
         // The VM code loader has bugs around module upgrade. After a module upgrade, the internal
         // cache needs to be flushed to work around those bugs.
         self.inner.flush_loader_cache_if_invalidated();
 
-        SessionExt::new(self.inner.new_session_with_extensions(remote, extensions))
+        SessionExt::new(
+            self.inner.new_session_with_extensions(remote, extensions),
+            remote,
+            &self.extension_registry,
+        )
     }
 }
 
@@ -7,15 +7,25 @@ mod vm_wrapper;
 use crate::{
     adapter_common::{preprocess_transaction, PreprocessedTransaction},
     aptos_vm::AptosVM,
+    block_gas_limit::BlockGasTracker,
+    counters::{
+        PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_CONFLICT_RATE,
+        PARALLEL_EXECUTION_DEPENDENCY_WAIT_SECONDS, PARALLEL_EXECUTION_REEXECUTIONS,
+        PARALLEL_EXECUTION_SEQUENTIAL_FALLBACKS, PARALLEL_EXECUTION_VALIDATION_ABORT_INDEX,
+        PARALLEL_EXECUTION_WORKER_UTILIZATION,
+    },
+    data_cache::RemoteStorage,
     parallel_executor::vm_wrapper::AptosVMWrapper,
 };
 use aptos_parallel_executor::{
     errors::Error,
     executor::ParallelTransactionExecutor,
+    stats::{BlockExecutionStats, ConflictThreshold, RECENT_BLOCK_STATS},
     task::{Transaction as PTransaction, TransactionOutput as PTransactionOutput},
 };
 use aptos_state_view::StateView;
 use aptos_types::{
+    on_chain_config::{OnChainConfig, ParallelExecutionConfig},
     state_store::state_key::StateKey,
     transaction::{Transaction, TransactionOutput, TransactionStatus},
     write_set::{WriteOp, WriteSet},
@@ -74,19 +84,55 @@ impl ParallelAptosVM {
             .map(|txn| preprocess_transaction::<AptosVM>(txn.clone()))
             .collect();
 
-        match ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
-            concurrency_level,
-        )
-        .execute_transactions_parallel(state_view, signature_verified_block)
-        {
-            Ok(results) => Ok((
-                results
+        let mut executor =
+            ParallelTransactionExecutor::<PreprocessedTransaction, AptosVMWrapper<S>>::new(
+                concurrency_level,
+            );
+        if let Some(conflict_threshold) = Self::conflict_threshold(state_view) {
+            executor = executor.with_conflict_threshold(conflict_threshold);
+        }
+        let (result, stats) =
+            executor.execute_transactions_parallel_with_stats(state_view, signature_verified_block);
+        let block_id = match transactions.first() {
+            Some(Transaction::BlockMetadata(block_metadata)) => Some(block_metadata.id().to_hex()),
+            _ => None,
+        };
+        Self::record_stats(block_id, stats);
+
+        match result {
+            Ok(results) => {
+                let outputs = results
                     .into_iter()
                     .map(AptosTransactionOutput::into)
-                    .collect(),
-                None,
-            )),
-            Err(err @ Error::ModulePathReadWrite) => {
+                    .collect();
+                // The transactions are executed regardless of the block gas limit, which is then
+                // enforced on their outputs.
+                match BlockGasTracker::new(state_view).enforce_on_outputs(&transactions, outputs) {
+                    Some(outputs) => Ok((outputs, None)),
+                    None => {
+                        PARALLEL_EXECUTION_SEQUENTIAL_FALLBACKS
+                            .with_label_values(&["block_gas_limit"])
+                            .inc();
+                        let output =
+                            AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?;
+                        Ok((
+                            output
+                                .into_iter()
+                                .map(|(_vm_status, txn_output)| txn_output)
+                                .collect(),
+                            None,
+                        ))
+                    }
+                }
+            }
+            Err(err @ (Error::ModulePathReadWrite | Error::ConflictThresholdExceeded)) => {
+                let reason = match err {
+                    Error::ModulePathReadWrite => "module_path_read_write",
+                    _ => "conflict_threshold",
+                };
+                PARALLEL_EXECUTION_SEQUENTIAL_FALLBACKS
+                    .with_label_values(&[reason])
+                    .inc();
                 let output = AptosVM::execute_block_and_keep_vm_status(transactions, state_view)?;
                 Ok((
                     output
@@ -102,4 +148,41 @@ impl ParallelAptosVM {
             Err(Error::UserError(err)) => Err(err),
         }
     }
+
+    /// The threshold beyond which blocks are executed sequentially, as configured on-chain.
+    fn conflict_threshold<S: StateView>(state_view: &S) -> Option<ConflictThreshold> {
+        let config = ParallelExecutionConfig::fetch_config(&RemoteStorage::new(state_view))
+            .unwrap_or_default();
+        if !config.sequential_fallback_enabled() {
+            return None;
+        }
+        Some(ConflictThreshold {
+            min_block_size: config.min_block_size_for_fallback() as usize,
+            max_aborts_per_hundred_txns: config.max_aborts_per_hundred_txns() as usize,
+        })
+    }
+
+    /// Records the statistics of a block in the metrics, and keeps them for inspection.
+    fn record_stats(block_id: Option<String>, stats: BlockExecutionStats) {
+        if stats.num_txns == 0 {
+            return;
+        }
+        PARALLEL_EXECUTION_CONFLICT_RATE.observe(stats.conflict_rate());
+        PARALLEL_EXECUTION_REEXECUTIONS.observe(stats.num_reexecutions() as f64);
+        PARALLEL_EXECUTION_ABORTS
+            .with_label_values(&["validation_failure"])
+            .inc_by(stats.num_validation_aborts as u64);
+        PARALLEL_EXECUTION_ABORTS
+            .with_label_values(&["dependency_wait"])
+            .inc_by(stats.num_dependency_waits as u64);
+        PARALLEL_EXECUTION_DEPENDENCY_WAIT_SECONDS
+            .observe(stats.dependency_wait_time.as_secs_f64());
+        PARALLEL_EXECUTION_WORKER_UTILIZATION.observe(stats.worker_utilization());
+        for (idx, num_aborts) in &stats.validation_aborts_by_index {
+            for _ in 0..*num_aborts {
+                PARALLEL_EXECUTION_VALIDATION_ABORT_INDEX.observe(*idx as f64);
+            }
+        }
+        RECENT_BLOCK_STATS.record(block_id, stats);
+    }
 }
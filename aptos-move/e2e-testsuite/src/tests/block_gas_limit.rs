@@ -0,0 +1,82 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::{
+    on_chain_config::{access_path_for_config, BlockGasLimit, OnChainConfig},
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, Transaction, TransactionStatus},
+    write_set::{WriteOp, WriteSetMut},
+};
+use language_e2e_tests::{common_transactions::peer_to_peer_txn, executor::FakeExecutor};
+
+fn set_block_gas_limit(executor: &mut FakeExecutor, gas_limit: u64) {
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::AccessPath(access_path_for_config(BlockGasLimit::CONFIG_ID)),
+        WriteOp::Modification(bcs::to_bytes(&BlockGasLimit { gas_limit }).unwrap()),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+}
+
+fn transfer_block(executor: &mut FakeExecutor, num_transfers: u64) -> Vec<Transaction> {
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    let mut txns: Vec<Transaction> = (0..num_transfers)
+        .map(|i| {
+            Transaction::UserTransaction(peer_to_peer_txn(
+                sender.account(),
+                receiver.account(),
+                10 + i,
+                1_000,
+            ))
+        })
+        .collect();
+    txns.push(Transaction::StateCheckpoint(HashValue::random()));
+    txns
+}
+
+#[test]
+fn block_gas_limit_retries_remaining_transactions() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let txns = transfer_block(&mut executor, 3);
+
+    let outputs = executor.execute_transaction_block(txns.clone()).unwrap();
+    let gas_used = outputs[0].gas_used();
+    assert!(gas_used > 0);
+
+    // The limit is reached by the second transfer, so the third one is retried, but the block
+    // still ends with its state checkpoint.
+    set_block_gas_limit(&mut executor, gas_used + 1);
+    let outputs = executor.execute_transaction_block(txns).unwrap();
+    let statuses: Vec<_> = outputs
+        .iter()
+        .map(|output| output.status().clone())
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            TransactionStatus::Retry,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+        ]
+    );
+    assert_eq!(outputs[2].gas_used(), 0);
+}
+
+#[test]
+fn block_gas_limit_not_reached() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let txns = transfer_block(&mut executor, 3);
+    set_block_gas_limit(&mut executor, u64::MAX);
+
+    let outputs = executor.execute_transaction_block(txns).unwrap();
+    assert!(outputs
+        .iter()
+        .all(|output| output.status() == &TransactionStatus::Keep(ExecutionStatus::Success)));
+}
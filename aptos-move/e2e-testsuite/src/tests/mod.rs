@@ -12,6 +12,7 @@
 //! Set env REGENERATE_GOLDENFILES to update the golden files when running tests..
 
 mod account_universe;
+mod block_gas_limit;
 mod create_account;
 mod data_store;
 mod execution_strategies;
@@ -23,7 +24,9 @@ mod module_publishing;
 mod on_chain_configs;
 mod peer_to_peer;
 mod rotate_key;
+mod script_function_allow_list;
 mod scripts;
+mod storage_gas;
 mod transaction_fuzzer;
 mod verify_txn;
 mod writeset_builder;
@@ -0,0 +1,109 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{ApprovedExecutionHashesResource, CORE_CODE_ADDRESS},
+    on_chain_config::{access_path_for_config, OnChainConfig, ScriptFunctionAllowList},
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, TransactionStatus},
+    vm_status::StatusCode,
+    write_set::{WriteOp, WriteSetMut},
+};
+use language_e2e_tests::{
+    assert_prologue_parity,
+    common_transactions::{empty_txn, peer_to_peer_txn, EMPTY_SCRIPT},
+    executor::FakeExecutor,
+    transaction_status_eq,
+};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+
+fn set_allow_list(executor: &mut FakeExecutor, allowed_module_addresses: Vec<AccountAddress>) {
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::AccessPath(access_path_for_config(ScriptFunctionAllowList::CONFIG_ID)),
+        WriteOp::Modification(
+            bcs::to_bytes(&ScriptFunctionAllowList {
+                allowed_module_addresses,
+            })
+            .unwrap(),
+        ),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+}
+
+fn set_approved_script_hashes(executor: &mut FakeExecutor, hashes: Vec<(u64, Vec<u8>)>) {
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            CORE_CODE_ADDRESS,
+            ApprovedExecutionHashesResource::struct_tag(),
+        ))),
+        WriteOp::Modification(bcs::to_bytes(&hashes).unwrap()),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+}
+
+#[test]
+fn script_function_not_allowed() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    set_allow_list(&mut executor, vec![AccountAddress::random()]);
+
+    // The transfer is a script function of the framework, which is not on the list.
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    assert_prologue_parity!(
+        executor.verify_transaction(txn.clone()).status(),
+        executor.execute_transaction(txn).status(),
+        StatusCode::FEATURE_UNDER_GATING
+    );
+}
+
+#[test]
+fn script_function_allowed() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+    set_allow_list(&mut executor, vec![CORE_CODE_ADDRESS]);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000);
+    assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+    assert_eq!(
+        executor.execute_transaction(txn).status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+}
+
+#[test]
+fn script_not_allowed_with_allow_list() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    executor.add_account_data(&sender);
+
+    let txn = empty_txn(sender.account(), 10, 100_000, 1);
+    assert_eq!(executor.verify_transaction(txn.clone()).status(), None);
+
+    // Scripts could call any module, so only those approved by governance are allowed while the
+    // list is set.
+    set_allow_list(&mut executor, vec![CORE_CODE_ADDRESS]);
+    assert_prologue_parity!(
+        executor.verify_transaction(txn.clone()).status(),
+        executor.execute_transaction(txn.clone()).status(),
+        StatusCode::FEATURE_UNDER_GATING
+    );
+
+    set_approved_script_hashes(
+        &mut executor,
+        vec![(0, HashValue::sha3_256_of(&EMPTY_SCRIPT).to_vec())],
+    );
+    assert_eq!(executor.verify_transaction(txn).status(), None);
+}
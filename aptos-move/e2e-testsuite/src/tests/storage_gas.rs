@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_gas::{AptosGasParameters, InitialGasSchedule, InternalGasPerArg, ToOnChainGasSchedule};
+use aptos_types::{
+    on_chain_config::{
+        access_path_for_config, GasSchedule, OnChainConfig, Version, APTOS_VERSION_4,
+        APTOS_VERSION_5,
+    },
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    write_set::{WriteOp, WriteSetMut},
+};
+use language_e2e_tests::{
+    account::AccountData, common_transactions::peer_to_peer_txn, executor::FakeExecutor,
+};
+
+fn set_version_and_gas_params(
+    executor: &mut FakeExecutor,
+    version: Version,
+    gas_params: &AptosGasParameters,
+) {
+    let write_set = WriteSetMut::new(vec![
+        (
+            StateKey::AccessPath(access_path_for_config(Version::CONFIG_ID)),
+            WriteOp::Modification(bcs::to_bytes(&version).unwrap()),
+        ),
+        (
+            StateKey::AccessPath(access_path_for_config(GasSchedule::CONFIG_ID)),
+            WriteOp::Modification(
+                bcs::to_bytes(&GasSchedule {
+                    entries: gas_params.to_on_chain_gas_schedule(),
+                })
+                .unwrap(),
+            ),
+        ),
+    ])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+}
+
+fn transfer(
+    version: Version,
+    gas_params: &AptosGasParameters,
+    sender: &AccountData,
+    receiver: &AccountData,
+) -> TransactionOutput {
+    let mut executor = FakeExecutor::from_genesis_file();
+    executor.add_account_data(sender);
+    executor.add_account_data(receiver);
+    set_version_and_gas_params(&mut executor, version, gas_params);
+
+    let output = executor.execute_transaction(peer_to_peer_txn(
+        sender.account(),
+        receiver.account(),
+        10,
+        1_000,
+    ));
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    output
+}
+
+#[test]
+fn free_storage_keeps_transaction_output() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+
+    // Charging nothing for storage in a separate epilogue session must not change the output.
+    let before = transfer(
+        APTOS_VERSION_4,
+        &AptosGasParameters::initial(),
+        &sender,
+        &receiver,
+    );
+    let after = transfer(
+        APTOS_VERSION_5,
+        &AptosGasParameters::initial_with_storage(),
+        &sender,
+        &receiver,
+    );
+    assert_eq!(before.write_set(), after.write_set());
+    assert_eq!(before.events(), after.events());
+    assert_eq!(before.gas_used(), after.gas_used());
+}
+
+#[test]
+fn storage_is_charged_from_version_5() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+
+    let mut gas_params = AptosGasParameters::initial_with_storage();
+    gas_params.storage.as_mut().unwrap().per_item_write = InternalGasPerArg::new(100_000);
+
+    let uncharged = transfer(APTOS_VERSION_4, &gas_params, &sender, &receiver);
+    let charged = transfer(APTOS_VERSION_5, &gas_params, &sender, &receiver);
+    assert!(charged.gas_used() > uncharged.gas_used());
+}
@@ -339,6 +339,12 @@ module aptos_framework::aptos_governance {
         simple_map::add(&mut approved_hashes.hashes, proposal_id, execution_hash);
     }
 
+    /// Entry function to add the execution script hash of a successful governance proposal to the approved list, so
+    /// that its script may be executed while the script function allow list is set.
+    public entry fun add_approved_script_hash_script(proposal_id: u64) acquires ApprovedExecutionHashes {
+        add_approved_script_hash(proposal_id)
+    }
+
     /// Resolve a successful proposal. This would fail if the proposal is not successful (not enough votes or more no
     /// than yes).
     public fun resolve(proposal_id: u64, signer_address: address): signer acquires ApprovedExecutionHashes, GovernanceResponsbility {
@@ -0,0 +1,52 @@
+/// Maintains the block gas limit for the blockchain: once the transactions of a block have used
+/// this much gas, the remaining transactions of the block are retried in a later block. Blocks
+/// are unlimited until the limit is set by root.
+module aptos_framework::block_gas_limit {
+    use std::error;
+
+    use aptos_framework::reconfiguration;
+    use aptos_framework::system_addresses;
+
+    struct BlockGasLimit has key {
+        gas_limit: u64,
+    }
+
+    /// The block gas limit must be positive
+    const EZERO_BLOCK_GAS_LIMIT: u64 = 1;
+
+    /// This can be called by on-chain governance to publish or update the block gas limit.
+    public fun set(account: &signer, gas_limit: u64) acquires BlockGasLimit {
+        system_addresses::assert_aptos_framework(account);
+        assert!(gas_limit > 0, error::invalid_argument(EZERO_BLOCK_GAS_LIMIT));
+
+        if (exists<BlockGasLimit>(@aptos_framework)) {
+            borrow_global_mut<BlockGasLimit>(@aptos_framework).gas_limit = gas_limit;
+        } else {
+            move_to(account, BlockGasLimit { gas_limit });
+        };
+
+        // Need to trigger reconfiguration so validator nodes can sync on the updated configs.
+        reconfiguration::reconfigure();
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    public entry fun test_set_gas_limit(aptos_framework: signer) acquires BlockGasLimit {
+        set(&aptos_framework, 1000);
+        assert!(borrow_global<BlockGasLimit>(@aptos_framework).gas_limit == 1000, 0);
+
+        set(&aptos_framework, 2000);
+        assert!(borrow_global<BlockGasLimit>(@aptos_framework).gas_limit == 2000, 1);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x50003)]
+    public entry fun test_set_gas_limit_unauthorized_should_fail(account: signer) acquires BlockGasLimit {
+        set(&account, 1000);
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    #[expected_failure(abort_code = 0x10001)]
+    public entry fun test_set_zero_gas_limit_should_fail(aptos_framework: signer) acquires BlockGasLimit {
+        set(&aptos_framework, 0);
+    }
+}
@@ -0,0 +1,54 @@
+/// Maintains the parallel execution config for the blockchain, which controls when blocks whose
+/// transactions conflict too much are executed sequentially instead. The config is optional:
+/// until it is set by root, nodes never fall back to sequential execution.
+module aptos_framework::parallel_execution_config {
+    use std::error;
+    use std::vector;
+
+    use aptos_framework::reconfiguration;
+    use aptos_framework::system_addresses;
+
+    struct ParallelExecutionConfig has key {
+        config: vector<u8>,
+    }
+
+    /// The provided on chain config bytes are empty or invalid
+    const EINVALID_CONFIG: u64 = 1;
+
+    /// This can be called by on-chain governance to publish or update the parallel execution config.
+    public fun set(account: &signer, config: vector<u8>) acquires ParallelExecutionConfig {
+        system_addresses::assert_aptos_framework(account);
+        assert!(vector::length(&config) > 0, error::invalid_argument(EINVALID_CONFIG));
+
+        if (exists<ParallelExecutionConfig>(@aptos_framework)) {
+            let config_ref = &mut borrow_global_mut<ParallelExecutionConfig>(@aptos_framework).config;
+            *config_ref = config;
+        } else {
+            move_to(account, ParallelExecutionConfig { config });
+        };
+
+        // Need to trigger reconfiguration so validator nodes can sync on the updated configs.
+        reconfiguration::reconfigure();
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    public entry fun test_set_config(aptos_framework: signer) acquires ParallelExecutionConfig {
+        set(&aptos_framework, vector[1]);
+        assert!(borrow_global<ParallelExecutionConfig>(@aptos_framework).config == vector[1], 0);
+
+        set(&aptos_framework, vector[2, 3]);
+        assert!(borrow_global<ParallelExecutionConfig>(@aptos_framework).config == vector[2, 3], 1);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x50003)]
+    public entry fun test_set_config_unauthorized_should_fail(account: signer) acquires ParallelExecutionConfig {
+        set(&account, vector[1]);
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    #[expected_failure(abort_code = 0x10001)]
+    public entry fun test_set_empty_config_should_fail(aptos_framework: signer) acquires ParallelExecutionConfig {
+        set(&aptos_framework, vector[]);
+    }
+}
@@ -0,0 +1,83 @@
+/// Maintains the allow list of script functions for permissioned deployments: only the script
+/// functions of modules published at the listed addresses may be executed, and transactions calling
+/// others are rejected at validation. Any script function may be executed until root sets the list.
+/// While it is set, scripts are only executed if governance approved their hash, so that proposals
+/// can still update or remove the list once `aptos_governance::add_approved_script_hash_script` is
+/// called for them.
+module aptos_framework::script_function_allow_list {
+    use aptos_framework::reconfiguration;
+    use aptos_framework::system_addresses;
+
+    struct ScriptFunctionAllowList has key {
+        allowed_module_addresses: vector<address>,
+    }
+
+    /// This can be called by on-chain governance to publish or update the allow list.
+    public fun set(
+        account: &signer,
+        allowed_module_addresses: vector<address>,
+    ) acquires ScriptFunctionAllowList {
+        system_addresses::assert_aptos_framework(account);
+
+        if (exists<ScriptFunctionAllowList>(@aptos_framework)) {
+            borrow_global_mut<ScriptFunctionAllowList>(@aptos_framework).allowed_module_addresses =
+                allowed_module_addresses;
+        } else {
+            move_to(account, ScriptFunctionAllowList { allowed_module_addresses });
+        };
+
+        // Need to trigger reconfiguration so validator nodes can sync on the updated configs.
+        reconfiguration::reconfigure();
+    }
+
+    /// This can be called by on-chain governance to allow any script function again.
+    public fun remove(account: &signer) acquires ScriptFunctionAllowList {
+        system_addresses::assert_aptos_framework(account);
+
+        if (exists<ScriptFunctionAllowList>(@aptos_framework)) {
+            let ScriptFunctionAllowList { allowed_module_addresses: _ } =
+                move_from<ScriptFunctionAllowList>(@aptos_framework);
+
+            // Need to trigger reconfiguration so validator nodes can sync on the updated configs.
+            reconfiguration::reconfigure();
+        };
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    public entry fun test_set_and_remove_allow_list(
+        aptos_framework: signer,
+    ) acquires ScriptFunctionAllowList {
+        set(&aptos_framework, vector[@0x1]);
+        assert!(
+            borrow_global<ScriptFunctionAllowList>(@aptos_framework).allowed_module_addresses == vector[@0x1],
+            0,
+        );
+
+        set(&aptos_framework, vector[@0x1, @0x123]);
+        assert!(
+            borrow_global<ScriptFunctionAllowList>(@aptos_framework).allowed_module_addresses == vector[@0x1, @0x123],
+            1,
+        );
+
+        remove(&aptos_framework);
+        assert!(!exists<ScriptFunctionAllowList>(@aptos_framework), 2);
+        // Removing a missing list is a no-op.
+        remove(&aptos_framework);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x50003)]
+    public entry fun test_set_allow_list_unauthorized_should_fail(
+        account: signer,
+    ) acquires ScriptFunctionAllowList {
+        set(&account, vector[@0x1]);
+    }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x50003)]
+    public entry fun test_remove_allow_list_unauthorized_should_fail(
+        account: signer,
+    ) acquires ScriptFunctionAllowList {
+        remove(&account);
+    }
+}
@@ -11,9 +11,12 @@ module aptos_framework::reconfiguration {
 
     friend aptos_framework::aptos_governance;
     friend aptos_framework::block;
+    friend aptos_framework::block_gas_limit;
     friend aptos_framework::consensus_config;
     friend aptos_framework::gas_schedule;
     friend aptos_framework::genesis;
+    friend aptos_framework::parallel_execution_config;
+    friend aptos_framework::script_function_allow_list;
     friend aptos_framework::version;
 
     /// Event that signals consensus to start a new epoch,
@@ -101,6 +101,9 @@ impl ReleaseTarget {
                 with_abis: true,
                 with_source_maps: true,
                 with_error_map: true,
+                // The framework is upgraded through governance, which reviews its sources, and
+                // its release bundle is checked in, so it isn't attested.
+                with_source_attestation: false,
                 named_addresses: Default::default(),
             },
             packages: packages.iter().map(|(path, _)| path.to_owned()).collect(),
@@ -3,11 +3,14 @@
 
 use crate::error_map::generate_error_map;
 use crate::natives::code::{ModuleMetadata, PackageMetadata, UpgradePolicy};
+use crate::source_attestation::{ModuleAttestation, SourceAttestation, TOOLCHAIN_VERSION};
 use crate::zip_metadata;
 use aptos_types::account_address::AccountAddress;
 use clap::Parser;
 use move_deps::move_core_types::errmap::ErrorMapping;
-use move_deps::move_package::compilation::compiled_package::CompiledPackage;
+use move_deps::move_package::compilation::compiled_package::{
+    CompiledPackage, CompiledPackageInfo,
+};
 use move_deps::move_package::source_package::manifest_parser::{
     parse_move_manifest_string, parse_source_manifest,
 };
@@ -30,6 +33,9 @@ pub struct BuildOptions {
     pub with_source_maps: bool,
     #[clap(long, default_value = "true")]
     pub with_error_map: bool,
+    /// Attest the hashes of the sources of the modules and of their bytecode in the build info.
+    #[clap(long)]
+    pub with_source_attestation: bool,
     #[clap(skip)] // TODO: have a parser for this; there is one in the CLI buts its  downstream
     pub named_addresses: BTreeMap<String, AccountAddress>,
 }
@@ -43,6 +49,7 @@ impl Default for BuildOptions {
             with_abis: true,
             with_source_maps: true,
             with_error_map: true,
+            with_source_attestation: false,
             named_addresses: Default::default(),
         }
     }
@@ -54,6 +61,7 @@ pub struct BuiltPackage {
     package_path: PathBuf,
     package: CompiledPackage,
     error_map: Option<ErrorMapping>,
+    with_source_attestation: bool,
 }
 
 impl BuiltPackage {
@@ -83,6 +91,7 @@ impl BuiltPackage {
             package_path,
             package,
             error_map,
+            with_source_attestation: options.with_source_attestation,
         })
     }
 
@@ -102,8 +111,6 @@ impl BuiltPackage {
 
     /// Extracts metadata, as needed for releasing a package, from the built package.
     pub fn extract_metadata(&self) -> anyhow::Result<PackageMetadata> {
-        let build_info = serde_yaml::to_string(&self.package.compiled_package_info)?;
-
         let manifest_file = self.package_path.join("Move.toml");
         let manifest = std::fs::read_to_string(&manifest_file)?;
         let custom_props = extract_custom_fields(&manifest)?;
@@ -113,16 +120,33 @@ impl BuiltPackage {
             UpgradePolicy::compat()
         };
         let mut modules = vec![];
+        let mut module_attestations = BTreeMap::new();
         for u in &self.package.root_compiled_units {
             let name = u.unit.name().to_string();
-            let source = zip_metadata(std::fs::read_to_string(&u.source_path)?.as_bytes())?;
+            let source_text = std::fs::read_to_string(&u.source_path)?;
+            let source = zip_metadata(source_text.as_bytes())?;
             let source_map = zip_metadata(&u.unit.serialize_source_map())?;
+            module_attestations.insert(
+                name.clone(),
+                ModuleAttestation::new(source_text.as_bytes(), &u.unit.serialize(None)),
+            );
             modules.push(ModuleMetadata {
                 name,
                 source,
                 source_map,
             })
         }
+        let build_info = if self.with_source_attestation {
+            serde_yaml::to_string(&AttestedBuildInfo {
+                compiled_package_info: &self.package.compiled_package_info,
+                source_attestation: SourceAttestation {
+                    toolchain_version: TOOLCHAIN_VERSION.to_string(),
+                    modules: module_attestations,
+                },
+            })?
+        } else {
+            serde_yaml::to_string(&self.package.compiled_package_info)?
+        };
         let error_map = if let Some(map) = &self.error_map {
             bcs::to_bytes(map).expect("bcs for error map")
         } else {
@@ -147,6 +171,14 @@ impl BuiltPackage {
     }
 }
 
+/// The build info of a package along with the attestation of its sources.
+#[derive(Serialize)]
+struct AttestedBuildInfo<'a> {
+    #[serde(flatten)]
+    compiled_package_info: &'a CompiledPackageInfo,
+    source_attestation: SourceAttestation,
+}
+
 fn extract_custom_fields(toml: &str) -> anyhow::Result<BTreeMap<String, String>> {
     let manifest = parse_source_manifest(parse_move_manifest_string(toml.to_owned())?)?;
     Ok(manifest
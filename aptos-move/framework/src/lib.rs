@@ -21,6 +21,7 @@ mod release_builder;
 pub use release_builder::*;
 mod release_bundle;
 pub use release_bundle::*;
+pub mod source_attestation;
 
 use anyhow::bail;
 use miniz_oxide::deflate::compress_to_vec;
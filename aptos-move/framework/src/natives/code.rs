@@ -7,6 +7,7 @@ use aptos_types::vm_status::StatusCode;
 use better_any::{Tid, TidAble};
 use move_deps::move_binary_format::errors::PartialVMError;
 use move_deps::move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
+use move_deps::move_core_types::{ident_str, identifier::IdentStr, move_resource::MoveStructType};
 use move_deps::move_vm_types::pop_arg;
 use move_deps::move_vm_types::values::Struct;
 use move_deps::{
@@ -32,6 +33,11 @@ pub struct PackageRegistry {
     pub packages: Vec<PackageMetadata>,
 }
 
+impl MoveStructType for PackageRegistry {
+    const MODULE_NAME: &'static IdentStr = ident_str!("code");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("PackageRegistry");
+}
+
 /// The PackageMetadata type.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub struct PackageMetadata {
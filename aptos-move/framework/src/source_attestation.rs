@@ -0,0 +1,99 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attestations by publishers of the sources their modules are compiled from.
+//!
+//! A package built with an attestation carries, in its build info, the hash of the source of
+//! each of its modules and of the bytecode compiled from it, along with the version of the
+//! toolchain that compiled it. The build info is part of the package metadata, so the
+//! attestation is signed by the publisher along with the rest of the publishing transaction.
+//! Anyone can then check that the bytecode at an address is the one its publisher claims to have
+//! compiled from a source, without relying on a third party.
+
+use aptos_crypto::HashValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The version of the toolchain attested by packages built with this crate. Builds with the same
+/// version compile the same sources to the same bytecode.
+pub const TOOLCHAIN_VERSION: &str = concat!("aptos-framework-", env!("CARGO_PKG_VERSION"));
+
+/// The attestation of the sources of the modules of a package.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SourceAttestation {
+    /// The version of the toolchain which compiled the package.
+    pub toolchain_version: String,
+    /// The attestations of the modules of the package, by module name.
+    pub modules: BTreeMap<String, ModuleAttestation>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ModuleAttestation {
+    /// The SHA3-256 hash of the source text of the module.
+    pub source_hash: HashValue,
+    /// The SHA3-256 hash of the bytecode of the module, as published.
+    pub bytecode_hash: HashValue,
+}
+
+impl ModuleAttestation {
+    pub fn new(source: &[u8], bytecode: &[u8]) -> Self {
+        Self {
+            source_hash: HashValue::sha3_256_of(source),
+            bytecode_hash: HashValue::sha3_256_of(bytecode),
+        }
+    }
+}
+
+/// The fields of the build info of a package which hold its attestation. Other fields are
+/// ignored.
+#[derive(Deserialize)]
+struct AttestedBuildInfo {
+    #[serde(default)]
+    source_attestation: Option<SourceAttestation>,
+}
+
+impl SourceAttestation {
+    /// Extracts the attestation from the build info of a package, if the package was built with
+    /// one.
+    pub fn from_build_info(build_info: &str) -> anyhow::Result<Option<Self>> {
+        Ok(serde_yaml::from_str::<AttestedBuildInfo>(build_info)?.source_attestation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct BuildInfo {
+        package_name: String,
+        source_attestation: Option<SourceAttestation>,
+    }
+
+    #[test]
+    fn test_from_build_info() {
+        let attestation = SourceAttestation {
+            toolchain_version: TOOLCHAIN_VERSION.to_string(),
+            modules: [("m".to_string(), ModuleAttestation::new(b"source", b"code"))].into(),
+        };
+        let build_info = serde_yaml::to_string(&BuildInfo {
+            package_name: "p".to_string(),
+            source_attestation: Some(attestation.clone()),
+        })
+        .unwrap();
+        assert_eq!(
+            SourceAttestation::from_build_info(&build_info).unwrap(),
+            Some(attestation)
+        );
+
+        let build_info = serde_yaml::to_string(&BuildInfo {
+            package_name: "p".to_string(),
+            source_attestation: None,
+        })
+        .unwrap();
+        assert_eq!(
+            SourceAttestation::from_build_info(&build_info).unwrap(),
+            None
+        );
+    }
+}
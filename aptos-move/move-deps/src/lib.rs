@@ -3,15 +3,18 @@
 
 pub use move_abigen;
 pub use move_binary_format;
+pub use move_bytecode_source_map;
 pub use move_bytecode_utils;
 pub use move_bytecode_verifier;
 pub use move_cli;
 pub use move_command_line_common;
 pub use move_compiler;
 pub use move_core_types;
+pub use move_disassembler;
 pub use move_docgen;
 pub use move_errmapgen;
 pub use move_ir_compiler;
+pub use move_ir_types;
 pub use move_model;
 pub use move_package;
 pub use move_prover;
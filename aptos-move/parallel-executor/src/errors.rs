@@ -12,6 +12,9 @@ pub enum Error<E> {
     /// TODO: (short-med term) relax the limitation, and (mid-long term) provide proper multi-versioning
     /// for code (like data) for the cache.
     ModulePathReadWrite,
+    /// The transactions of the block conflicted with each other beyond the configured threshold,
+    /// so parallel execution was abandoned. The block is better executed sequentially.
+    ConflictThresholdExceeded,
     /// Execution of a thread yields a non-recoverable error, such error will be propagated back to
     /// the caller.
     UserError(E),
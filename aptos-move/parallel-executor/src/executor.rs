@@ -4,6 +4,7 @@
 use crate::{
     errors::*,
     scheduler::{Scheduler, SchedulerTask, TaskGuard, TxnIndex, Version},
+    stats::{BlockExecutionStats, ConflictThreshold, ExecutionStatsCollector},
     task::{ExecutionStatus, ExecutorTask, ModulePath, Transaction, TransactionOutput},
     txn_last_input_output::{ReadDescriptor, TxnLastInputOutput},
 };
@@ -11,7 +12,9 @@ use aptos_infallible::Mutex;
 use mvhashmap::MVHashMap;
 use num_cpus;
 use once_cell::sync::Lazy;
-use std::{collections::HashSet, hash::Hash, marker::PhantomData, sync::Arc, thread::spawn};
+use std::{
+    collections::HashSet, hash::Hash, marker::PhantomData, sync::Arc, thread::spawn, time::Instant,
+};
 
 static RAYON_EXEC_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
     rayon::ThreadPoolBuilder::new()
@@ -32,6 +35,7 @@ pub struct MVHashMapView<'a, K, V> {
     versioned_map: &'a MVHashMap<K, V>,
     txn_idx: TxnIndex,
     scheduler: &'a Scheduler,
+    stats: &'a ExecutionStatsCollector,
     captured_reads: Mutex<Vec<ReadDescriptor<K>>>,
 }
 
@@ -81,11 +85,20 @@ impl<'a, K: ModulePath + PartialOrd + Send + Clone + Hash + Eq, V: Send + Sync>
                             // thread that aborted dep_idx was alive, and again, since lower txns
                             // than txn_idx are not blocked, so the execution of dep_idx will
                             // eventually finish and lead to unblocking txn_idx, contradiction.
+                            // If the execution of the block is abandoned, the dependency may
+                            // never be resolved, but the scheduler wakes us up.
+                            let wait_start = Instant::now();
                             let (lock, cvar) = &*dep_condition;
                             let mut dep_resolved = lock.lock();
-                            while !*dep_resolved {
+                            while !*dep_resolved && !self.scheduler.halted() {
                                 dep_resolved = cvar.wait(dep_resolved).unwrap();
                             }
+                            self.stats.record_dependency_wait(wait_start.elapsed());
+                            if !*dep_resolved {
+                                // The output of this execution is discarded, so the value
+                                // returned doesn't matter.
+                                return None;
+                            }
                         }
                         None => continue,
                     }
@@ -104,6 +117,8 @@ pub struct ParallelTransactionExecutor<T: Transaction, E: ExecutorTask> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
     // threads that may be concurrently participating in parallel execution.
     concurrency_level: usize,
+    // if set, parallel execution of blocks that conflict beyond the threshold is abandoned.
+    conflict_threshold: Option<ConflictThreshold>,
     phantom: PhantomData<(T, E)>,
 }
 
@@ -122,10 +137,19 @@ where
         );
         Self {
             concurrency_level,
+            conflict_threshold: None,
             phantom: PhantomData,
         }
     }
 
+    /// Abandon the parallel execution of blocks whose transactions conflict beyond the given
+    /// threshold, returning `Error::ConflictThresholdExceeded` so the caller can execute them
+    /// sequentially instead.
+    pub fn with_conflict_threshold(mut self, conflict_threshold: ConflictThreshold) -> Self {
+        self.conflict_threshold = Some(conflict_threshold);
+        self
+    }
+
     fn execute<'a>(
         &self,
         version: Version,
@@ -138,6 +162,7 @@ where
         >,
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &'a Scheduler,
+        stats: &'a ExecutionStatsCollector,
         executor: &E,
     ) -> SchedulerTask<'a> {
         let (idx_to_execute, incarnation) = version;
@@ -147,11 +172,18 @@ where
             versioned_map: versioned_data_cache,
             txn_idx: idx_to_execute,
             scheduler,
+            stats,
             captured_reads: Mutex::new(Vec::new()),
         };
 
         // VM execution.
+        stats.record_execution();
         let execute_result = executor.execute_transaction(&state_view, txn);
+        if scheduler.halted() {
+            // The execution of the block was abandoned while this transaction executed, and
+            // it may have read values that are inconsistent, so discard its output.
+            return SchedulerTask::NoTask;
+        }
         let mut prev_write_set: HashSet<T::Key> = last_input_output.write_set(idx_to_execute);
 
         // For tracking whether the recent execution wrote outside of the previous write set.
@@ -207,7 +239,12 @@ where
         >,
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &'a Scheduler,
+        stats: &ExecutionStatsCollector,
     ) -> SchedulerTask<'a> {
+        if scheduler.halted() {
+            return SchedulerTask::NoTask;
+        }
+        stats.record_validation();
         let (idx_to_validate, incarnation) = version_to_validate;
         let read_set = last_input_output
             .read_set(idx_to_validate)
@@ -224,6 +261,13 @@ where
         let aborted = !valid && scheduler.try_abort(idx_to_validate, incarnation);
 
         if aborted {
+            if stats.record_validation_abort(idx_to_validate) {
+                // The transactions of the block conflict too much, so give up on executing
+                // it in parallel.
+                scheduler.halt();
+                return SchedulerTask::NoTask;
+            }
+
             // Not valid and successfully aborted, mark the latest write-set as estimates.
             for k in &last_input_output.write_set(idx_to_validate) {
                 versioned_data_cache.mark_estimate(k, idx_to_validate);
@@ -246,6 +290,7 @@ where
         >,
         versioned_data_cache: &MVHashMap<<T as Transaction>::Key, <T as Transaction>::Value>,
         scheduler: &Scheduler,
+        stats: &ExecutionStatsCollector,
     ) {
         // Make executor for each task. TODO: fast concurrent executor.
         let executor = E::init(*executor_arguments);
@@ -253,22 +298,34 @@ where
         let mut scheduler_task = SchedulerTask::NoTask;
         loop {
             scheduler_task = match scheduler_task {
-                SchedulerTask::ValidationTask(version_to_validate, guard) => self.validate(
-                    version_to_validate,
-                    guard,
-                    last_input_output,
-                    versioned_data_cache,
-                    scheduler,
-                ),
-                SchedulerTask::ExecutionTask(version_to_execute, None, guard) => self.execute(
-                    version_to_execute,
-                    guard,
-                    block,
-                    last_input_output,
-                    versioned_data_cache,
-                    scheduler,
-                    &executor,
-                ),
+                SchedulerTask::ValidationTask(version_to_validate, guard) => {
+                    let start = Instant::now();
+                    let next_task = self.validate(
+                        version_to_validate,
+                        guard,
+                        last_input_output,
+                        versioned_data_cache,
+                        scheduler,
+                        stats,
+                    );
+                    stats.record_busy_time(start.elapsed());
+                    next_task
+                }
+                SchedulerTask::ExecutionTask(version_to_execute, None, guard) => {
+                    let start = Instant::now();
+                    let next_task = self.execute(
+                        version_to_execute,
+                        guard,
+                        block,
+                        last_input_output,
+                        versioned_data_cache,
+                        scheduler,
+                        stats,
+                        &executor,
+                    );
+                    stats.record_busy_time(start.elapsed());
+                    next_task
+                }
                 SchedulerTask::ExecutionTask(_, Some(condvar), _guard) => {
                     let (lock, cvar) = &*condvar;
                     // Mark dependency resolved.
@@ -291,15 +348,31 @@ where
         executor_initial_arguments: E::Argument,
         signature_verified_block: Vec<T>,
     ) -> Result<Vec<E::Output>, E::Error> {
+        self.execute_transactions_parallel_with_stats(
+            executor_initial_arguments,
+            signature_verified_block,
+        )
+        .0
+    }
+
+    /// Like `execute_transactions_parallel`, but also returns statistics about how much the
+    /// transactions of the block conflicted with each other.
+    pub fn execute_transactions_parallel_with_stats(
+        &self,
+        executor_initial_arguments: E::Argument,
+        signature_verified_block: Vec<T>,
+    ) -> (Result<Vec<E::Output>, E::Error>, BlockExecutionStats) {
         if signature_verified_block.is_empty() {
-            return Ok(vec![]);
+            return (Ok(vec![]), BlockExecutionStats::default());
         }
 
         let num_txns = signature_verified_block.len();
         let versioned_data_cache = MVHashMap::new();
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
+        let stats = ExecutionStatsCollector::new(num_txns, self.conflict_threshold);
 
+        let start = Instant::now();
         RAYON_EXEC_POOL.scope(|s| {
             for _ in 0..self.concurrency_level {
                 s.spawn(|_| {
@@ -309,16 +382,21 @@ where
                         &last_input_output,
                         &versioned_data_cache,
                         &scheduler,
+                        &stats,
                     );
                 });
             }
         });
+        let wall_time = start.elapsed();
 
         // TODO: for large block sizes and many cores, extract outputs in parallel.
         let num_txns = scheduler.num_txn_to_execute();
         let mut final_results = Vec::with_capacity(num_txns);
 
-        let maybe_err = if last_input_output.module_publishing_may_race() {
+        let conflict_threshold_exceeded = scheduler.halted();
+        let maybe_err = if conflict_threshold_exceeded {
+            Some(Error::ConflictThresholdExceeded)
+        } else if last_input_output.module_publishing_may_race() {
             Some(Error::ModulePathReadWrite)
         } else {
             let mut ret = None;
@@ -346,12 +424,20 @@ where
             drop(scheduler);
         });
 
-        match maybe_err {
+        let result = match maybe_err {
             Some(err) => Err(err),
             None => {
                 final_results.resize_with(num_txns, E::Output::skip_output);
                 Ok(final_results)
             }
-        }
+        };
+        (
+            result,
+            stats.finish(
+                conflict_threshold_exceeded,
+                wall_time,
+                self.concurrency_level,
+            ),
+        )
     }
 }
@@ -140,6 +140,7 @@ pub mod executor;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 mod scheduler;
+pub mod stats;
 pub mod task;
 mod txn_last_input_output;
 #[cfg(test)]
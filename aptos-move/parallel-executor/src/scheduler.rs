@@ -129,6 +129,9 @@ pub struct Scheduler {
     num_active_tasks: AtomicUsize,
     /// Shared marker that is set when a thread detects that all txns can be committed.
     done_marker: AtomicBool,
+    /// Shared marker that is set when parallel execution of the block is abandoned, which also
+    /// sets the done marker. The outputs of the block are then incomplete.
+    halted: AtomicBool,
 
     /// An index i maps to indices of other transactions that depend on transaction i, i.e. they
     /// should be re-executed once transaction i's next incarnation finishes.
@@ -147,6 +150,7 @@ impl Scheduler {
             decrease_cnt: AtomicUsize::new(0),
             num_active_tasks: AtomicUsize::new(0),
             done_marker: AtomicBool::new(false),
+            halted: AtomicBool::new(false),
             txn_dependency: (0..num_txns)
                 .map(|_| CachePadded::new(Mutex::new(Vec::new())))
                 .collect(),
@@ -178,6 +182,34 @@ impl Scheduler {
         }
     }
 
+    /// Abandon the execution of the block: no new tasks are handed out, and executions waiting
+    /// on a dependency are woken up, so all threads finish their current task and stop. After
+    /// this, the statuses and outputs of the transactions must not be relied upon.
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::SeqCst);
+        self.done_marker.store(true, Ordering::SeqCst);
+
+        for status in &self.txn_status {
+            let maybe_condvar = match &*status.lock() {
+                TransactionStatus::Suspended(_, dep_condvar) => Some(dep_condvar.clone()),
+                _ => None,
+            };
+            if let Some(dep_condvar) = maybe_condvar {
+                // Acquire the lock so the notification can't be missed by a thread that has
+                // checked the halted marker but isn't waiting yet. A thread that suspends
+                // after this scan checks the marker before waiting, and observes it set.
+                let (lock, cvar) = &*dep_condvar;
+                let _dep_resolved = lock.lock();
+                cvar.notify_all();
+            }
+        }
+    }
+
+    /// Whether the execution of the block was abandoned.
+    pub fn halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
     /// Return the next task for the thread.
     pub fn next_task(&self) -> SchedulerTask {
         loop {
@@ -0,0 +1,223 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_infallible::Mutex;
+use once_cell::sync::Lazy;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// How many of the most recently executed blocks `RECENT_BLOCK_STATS` keeps the statistics of.
+pub const NUM_RECENT_BLOCKS: usize = 20;
+
+/// The statistics of the blocks most recently executed in parallel on this node.
+pub static RECENT_BLOCK_STATS: Lazy<RecentBlockStats> =
+    Lazy::new(|| RecentBlockStats::new(NUM_RECENT_BLOCKS));
+
+/// Statistics about the parallel execution of a block, for understanding how much the
+/// transactions of the block conflicted with each other.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BlockExecutionStats {
+    /// Number of transactions in the block.
+    pub num_txns: usize,
+    /// Number of incarnations executed, including the first incarnation of every transaction.
+    pub num_executions: usize,
+    /// Number of incarnations aborted because their validation failed, i.e. because a lower
+    /// transaction wrote to a location they had read.
+    pub num_validation_aborts: usize,
+    /// Number of validation aborts of each transaction that had any, by index in the block.
+    pub validation_aborts_by_index: BTreeMap<usize, usize>,
+    /// Number of incarnations validated.
+    pub num_validations: usize,
+    /// Number of times an execution waited on a dependency, i.e. read a location that a lower
+    /// transaction was estimated to write.
+    pub num_dependency_waits: usize,
+    /// Time executions spent waiting on dependencies, summed over all the workers.
+    pub dependency_wait_time: Duration,
+    /// Time the workers spent executing and validating transactions, summed over all the
+    /// workers. This includes the time executions spent waiting on dependencies.
+    pub busy_time: Duration,
+    /// Time it took to execute the block.
+    pub wall_time: Duration,
+    /// Number of workers executing the block.
+    pub concurrency_level: usize,
+    /// Whether parallel execution was abandoned before completing the block, because the
+    /// transactions conflicted beyond the configured threshold.
+    pub conflict_threshold_exceeded: bool,
+}
+
+impl BlockExecutionStats {
+    /// Number of executions beyond the first incarnation of each transaction.
+    pub fn num_reexecutions(&self) -> usize {
+        self.num_executions.saturating_sub(self.num_txns)
+    }
+
+    /// Number of validation aborts per transaction of the block.
+    pub fn conflict_rate(&self) -> f64 {
+        if self.num_txns == 0 {
+            0.0
+        } else {
+            self.num_validation_aborts as f64 / self.num_txns as f64
+        }
+    }
+
+    /// The fraction of the time the workers were available that they spent making progress,
+    /// i.e. executing or validating transactions rather than looking for tasks or waiting on
+    /// dependencies. It is low when there are more workers than the block can keep busy.
+    pub fn worker_utilization(&self) -> f64 {
+        let available = self.wall_time.as_secs_f64() * self.concurrency_level as f64;
+        if available == 0.0 {
+            0.0
+        } else {
+            let progress = self.busy_time.saturating_sub(self.dependency_wait_time);
+            (progress.as_secs_f64() / available).min(1.0)
+        }
+    }
+}
+
+/// The statistics of a block executed in parallel, with the id of the block if it is known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockStatsRecord {
+    pub block_id: Option<String>,
+    pub stats: BlockExecutionStats,
+}
+
+/// The statistics of the most recently executed blocks, for inspecting how parallel execution
+/// performs on a node, e.g. to tune the concurrency level to its hardware.
+pub struct RecentBlockStats {
+    capacity: usize,
+    blocks: Mutex<VecDeque<BlockStatsRecord>>,
+}
+
+impl RecentBlockStats {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            blocks: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Records the statistics of a block, forgetting those of the oldest block if full.
+    pub fn record(&self, block_id: Option<String>, stats: BlockExecutionStats) {
+        let mut blocks = self.blocks.lock();
+        if blocks.len() == self.capacity {
+            blocks.pop_back();
+        }
+        if self.capacity > 0 {
+            blocks.push_front(BlockStatsRecord { block_id, stats });
+        }
+    }
+
+    /// The statistics of the recorded blocks, the most recent first.
+    pub fn get(&self) -> Vec<BlockStatsRecord> {
+        self.blocks.lock().iter().cloned().collect()
+    }
+}
+
+/// When to abandon parallel execution of a block whose transactions conflict so much that
+/// executing it sequentially is likely cheaper.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConflictThreshold {
+    /// Blocks with fewer transactions are always executed in parallel to completion.
+    pub min_block_size: usize,
+    /// The number of validation aborts per hundred transactions of the block beyond which
+    /// parallel execution is abandoned.
+    pub max_aborts_per_hundred_txns: usize,
+}
+
+impl ConflictThreshold {
+    fn is_exceeded(&self, num_txns: usize, num_validation_aborts: usize) -> bool {
+        num_txns >= self.min_block_size
+            && num_validation_aborts * 100 > self.max_aborts_per_hundred_txns * num_txns
+    }
+}
+
+/// Collects the statistics of a block concurrently from all the executing threads.
+pub(crate) struct ExecutionStatsCollector {
+    num_txns: usize,
+    threshold: Option<ConflictThreshold>,
+    num_executions: AtomicUsize,
+    num_validation_aborts: AtomicUsize,
+    validation_aborts_by_index: Vec<AtomicUsize>,
+    num_validations: AtomicUsize,
+    num_dependency_waits: AtomicUsize,
+    dependency_wait_nanos: AtomicU64,
+    busy_nanos: AtomicU64,
+}
+
+impl ExecutionStatsCollector {
+    pub(crate) fn new(num_txns: usize, threshold: Option<ConflictThreshold>) -> Self {
+        Self {
+            num_txns,
+            threshold,
+            num_executions: AtomicUsize::new(0),
+            num_validation_aborts: AtomicUsize::new(0),
+            validation_aborts_by_index: (0..num_txns).map(|_| AtomicUsize::new(0)).collect(),
+            num_validations: AtomicUsize::new(0),
+            num_dependency_waits: AtomicUsize::new(0),
+            dependency_wait_nanos: AtomicU64::new(0),
+            busy_nanos: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn record_execution(&self) {
+        self.num_executions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_validation(&self) {
+        self.num_validations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dependency_wait(&self, wait_time: Duration) {
+        self.num_dependency_waits.fetch_add(1, Ordering::Relaxed);
+        self.dependency_wait_nanos
+            .fetch_add(wait_time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Records the time a worker spent executing or validating a transaction.
+    pub(crate) fn record_busy_time(&self, busy_time: Duration) {
+        self.busy_nanos
+            .fetch_add(busy_time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Records a validation abort of the transaction at `txn_idx`, returning whether the block
+    /// now conflicts beyond the threshold.
+    pub(crate) fn record_validation_abort(&self, txn_idx: usize) -> bool {
+        self.validation_aborts_by_index[txn_idx].fetch_add(1, Ordering::Relaxed);
+        let num_validation_aborts = self.num_validation_aborts.fetch_add(1, Ordering::Relaxed) + 1;
+        self.threshold.map_or(false, |threshold| {
+            threshold.is_exceeded(self.num_txns, num_validation_aborts)
+        })
+    }
+
+    pub(crate) fn finish(
+        &self,
+        conflict_threshold_exceeded: bool,
+        wall_time: Duration,
+        concurrency_level: usize,
+    ) -> BlockExecutionStats {
+        BlockExecutionStats {
+            num_txns: self.num_txns,
+            num_executions: self.num_executions.load(Ordering::Relaxed),
+            num_validation_aborts: self.num_validation_aborts.load(Ordering::Relaxed),
+            validation_aborts_by_index: self
+                .validation_aborts_by_index
+                .iter()
+                .enumerate()
+                .map(|(idx, num_aborts)| (idx, num_aborts.load(Ordering::Relaxed)))
+                .filter(|(_, num_aborts)| *num_aborts > 0)
+                .collect(),
+            num_validations: self.num_validations.load(Ordering::Relaxed),
+            num_dependency_waits: self.num_dependency_waits.load(Ordering::Relaxed),
+            dependency_wait_time: Duration::from_nanos(
+                self.dependency_wait_nanos.load(Ordering::Relaxed),
+            ),
+            busy_time: Duration::from_nanos(self.busy_nanos.load(Ordering::Relaxed)),
+            wall_time,
+            concurrency_level,
+            conflict_threshold_exceeded,
+        }
+    }
+}
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    errors::Error,
     executor::ParallelTransactionExecutor,
     proptest_types::types::{ExpectedOutput, KeyType, Task, Transaction},
     scheduler::{Scheduler, SchedulerTask, TaskGuard},
+    stats::{BlockExecutionStats, ConflictThreshold, RecentBlockStats},
     task::ModulePath,
 };
 use rand::random;
@@ -12,6 +14,7 @@ use std::{
     fmt::Debug,
     hash::Hash,
     sync::{atomic::AtomicUsize, Arc},
+    time::Duration,
 };
 
 fn run_and_assert<K, V>(transactions: Vec<Transaction<K, V>>)
@@ -48,6 +51,98 @@ fn cycle_transactions() {
     run_and_assert(transactions)
 }
 
+fn cycle_block() -> Vec<Transaction<KeyType<[u8; 32]>, u64>> {
+    let mut transactions = vec![];
+    for _ in 0..TOTAL_KEY_NUM {
+        let key = random::<[u8; 32]>();
+        for _ in 0..WRITES_PER_KEY {
+            transactions.push(Transaction::Write {
+                incarnation: Arc::new(AtomicUsize::new(0)),
+                reads: vec![vec![KeyType(key, false)]],
+                writes: vec![vec![(KeyType(key, false), random::<u64>())]],
+            })
+        }
+    }
+    transactions
+}
+
+#[test]
+fn execution_stats() {
+    let transactions = cycle_block();
+    let (output, stats) = ParallelTransactionExecutor::<
+        Transaction<KeyType<[u8; 32]>, u64>,
+        Task<_, u64>,
+    >::new(num_cpus::get())
+    .execute_transactions_parallel_with_stats((), transactions.clone());
+
+    assert!(ExpectedOutput::generate_baseline(&transactions).check_output(&output));
+    assert_eq!(stats.num_txns, transactions.len());
+    assert!(stats.num_executions >= transactions.len());
+    assert_eq!(
+        stats.num_reexecutions(),
+        stats.num_executions - transactions.len()
+    );
+    assert!(stats.num_validations >= transactions.len());
+    assert_eq!(
+        stats.validation_aborts_by_index.values().sum::<usize>(),
+        stats.num_validation_aborts
+    );
+    assert!(stats
+        .validation_aborts_by_index
+        .keys()
+        .all(|idx| *idx < transactions.len()));
+    assert!(stats.dependency_wait_time <= stats.busy_time);
+    assert_eq!(stats.concurrency_level, num_cpus::get());
+    assert!(stats.wall_time > Duration::ZERO);
+    let utilization = stats.worker_utilization();
+    assert!((0.0..=1.0).contains(&utilization));
+    assert!(!stats.conflict_threshold_exceeded);
+}
+
+#[test]
+fn recent_block_stats() {
+    let recent = RecentBlockStats::new(2);
+    for num_txns in 1..=3 {
+        recent.record(
+            Some(num_txns.to_string()),
+            BlockExecutionStats {
+                num_txns,
+                ..BlockExecutionStats::default()
+            },
+        );
+    }
+
+    // The oldest block is forgotten, and the most recent comes first.
+    let blocks = recent.get();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0].block_id.as_deref(), Some("3"));
+    assert_eq!(blocks[0].stats.num_txns, 3);
+    assert_eq!(blocks[1].block_id.as_deref(), Some("2"));
+}
+
+#[test]
+fn conflict_threshold_exceeded() {
+    let transactions = cycle_block();
+    let (output, stats) = ParallelTransactionExecutor::<
+        Transaction<KeyType<[u8; 32]>, u64>,
+        Task<_, u64>,
+    >::new(num_cpus::get())
+    .with_conflict_threshold(ConflictThreshold {
+        min_block_size: 0,
+        max_aborts_per_hundred_txns: 0,
+    })
+    .execute_transactions_parallel_with_stats((), transactions.clone());
+
+    // Whether any validation fails depends on the interleaving of the threads, but parallel
+    // execution must be abandoned on the first one.
+    if stats.num_validation_aborts > 0 {
+        assert_eq!(output.unwrap_err(), Error::ConflictThresholdExceeded);
+        assert!(stats.conflict_threshold_exceeded);
+    } else {
+        assert!(ExpectedOutput::generate_baseline(&transactions).check_output(&output));
+    }
+}
+
 const NUM_BLOCKS: u64 = 10;
 const TXN_PER_BLOCK: u64 = 100;
 
@@ -18,8 +18,9 @@ use aptos_types::{
     account_config::{self, events::NewEpochEvent, CORE_CODE_ADDRESS},
     chain_id::ChainId,
     contract_event::ContractEvent,
-    on_chain_config::{ConsensusConfigV1, OnChainConsensusConfig, APTOS_MAX_KNOWN_VERSION},
+    on_chain_config::{OnChainConsensusConfig, APTOS_MAX_KNOWN_VERSION},
     transaction::{authenticator::AuthenticationKey, ChangeSet, Transaction, WriteSetPayload},
+    utility_coin::APTOS_COIN_TYPE,
 };
 use aptos_vm::{
     data_cache::{IntoMoveResolver, StateViewCache},
@@ -64,6 +65,15 @@ pub struct GenesisConfiguration {
     pub rewards_apy_percentage: u64,
     pub voting_duration_secs: u64,
     pub voting_power_increase_limit: u64,
+    // Accounts to create at genesis with their balances, besides the ones of validators.
+    pub accounts: Vec<AccountBalance>,
+}
+
+/// An account created at genesis, holding the balance in Aptos coins.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AccountBalance {
+    pub account_address: AccountAddress,
+    pub balance: u64,
 }
 
 pub static GENESIS_KEYPAIR: Lazy<(Ed25519PrivateKey, Ed25519PublicKey)> = Lazy::new(|| {
@@ -77,11 +87,10 @@ pub fn encode_genesis_transaction(
     aptos_root_key: Ed25519PublicKey,
     validators: &[Validator],
     framework: &ReleaseBundle,
+    consensus_config: OnChainConsensusConfig,
     chain_id: ChainId,
     genesis_config: GenesisConfiguration,
 ) -> Transaction {
-    let consensus_config = OnChainConsensusConfig::V1(ConsensusConfigV1::default());
-
     Transaction::GenesisTransaction(WriteSetPayload::Direct(encode_genesis_change_set(
         &aptos_root_key,
         validators,
@@ -120,6 +129,7 @@ pub fn encode_genesis_change_set(
         initialize_aptos_coin(&mut session);
     }
     initialize_on_chain_governance(&mut session, genesis_config);
+    create_accounts(&mut session, &genesis_config.accounts);
     create_and_initialize_validators(&mut session, validators);
 
     // Reconfiguration should happen after all on-chain invocations.
@@ -306,6 +316,37 @@ fn initialize_on_chain_governance(
     );
 }
 
+/// Creates the accounts and mints their balances, while the framework can still mint coins.
+fn create_accounts(session: &mut SessionExt<impl MoveResolver>, accounts: &[AccountBalance]) {
+    for account in accounts {
+        exec_function(
+            session,
+            "account",
+            "create_account_internal",
+            vec![],
+            serialize_values(&vec![MoveValue::Address(account.account_address)]),
+        );
+        exec_function(
+            session,
+            "coins",
+            "register",
+            vec![APTOS_COIN_TYPE.clone()],
+            serialize_values(&vec![MoveValue::Signer(account.account_address)]),
+        );
+        exec_function(
+            session,
+            "aptos_coin",
+            "mint",
+            vec![],
+            serialize_values(&vec![
+                MoveValue::Signer(CORE_CODE_ADDRESS),
+                MoveValue::Address(account.account_address),
+                MoveValue::U64(account.balance),
+            ]),
+        );
+    }
+}
+
 /// Creates and initializes each validator owner and validator operator. This method creates all
 /// the required accounts, sets the validator operators for each validator owner, and sets the
 /// validator config on-chain.
@@ -536,6 +577,7 @@ pub fn generate_test_genesis(
             rewards_apy_percentage: 10,
             voting_duration_secs: 3600,
             voting_power_increase_limit: 50,
+            accounts: vec![],
         },
     );
     (genesis, test_validators)
@@ -570,6 +612,7 @@ pub fn generate_mainnet_genesis(
             rewards_apy_percentage: 10,
             voting_duration_secs: 7 * 24 * 3600, // 7 days
             voting_power_increase_limit: 30,
+            accounts: vec![],
         },
     );
     (genesis, test_validators)
@@ -15,17 +15,20 @@ use aptos_config::{
 };
 use aptos_data_client::aptosnet::AptosNetDataClient;
 use aptos_infallible::RwLock;
-use aptos_logger::{prelude::*, Level};
+use aptos_logger::{prelude::*, trace_export::TraceExportConfig, Level};
 use aptos_runtime::instrumented_runtime::instrument_tokio_runtime;
 use aptos_sf_stream::runtime::bootstrap as bootstrap_sf_stream;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_time_service::TimeService;
 use aptos_types::{
-    account_config::CORE_CODE_ADDRESS, account_view::AccountView, chain_id::ChainId,
-    on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
+    account_config::{new_block_event_key, CORE_CODE_ADDRESS},
+    account_view::AccountView,
+    chain_id::ChainId,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
-use aptosdb::AptosDB;
+use aptosdb::{consistency_checker::ConsistencyChecker, AptosDB};
 use backup_service::start_backup_service;
 use clap::Parser;
 use consensus::consensus_provider::start_consensus;
@@ -56,15 +59,17 @@ use std::{
         Arc,
     },
     thread,
-    time::Instant,
+    time::{Duration, Instant},
 };
-use storage_interface::{state_view::LatestDbStateCheckpointView, DbReaderWriter};
+use storage_interface::{state_view::LatestDbStateCheckpointView, DbReader, DbReaderWriter};
 use storage_service_client::{StorageServiceClient, StorageServiceMultiSender};
 use storage_service_server::{
     network::StorageServiceNetworkEvents, StorageReader, StorageServiceServer,
 };
 use tokio::runtime::{Builder, Runtime};
 
+mod notification_stream;
+
 const AC_SMP_CHANNEL_BUFFER_SIZE: usize = 1_024;
 const INTRA_NODE_CHANNEL_BUFFER_SIZE: usize = 1;
 const MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE: usize = 1_024;
@@ -151,8 +156,10 @@ pub struct AptosHandle {
     _api: Runtime,
     _backup: Runtime,
     _consensus_runtime: Option<Runtime>,
+    _consistency_checker: Option<ConsistencyChecker>,
     _mempool: Runtime,
     _network_runtimes: Vec<Runtime>,
+    _notification_stream: Option<Runtime>,
     _sf_stream: Option<Runtime>,
     _state_sync_runtimes: StateSyncRuntimes,
     _telemetry_runtime: Option<Runtime>,
@@ -168,6 +175,17 @@ pub fn start(config: NodeConfig, log_file: Option<PathBuf>) -> anyhow::Result<()
         .is_async(config.logger.is_async)
         .level(config.logger.level)
         .console_port(config.logger.console_port)
+        .trace_export(
+            config
+                .logger
+                .otlp_trace_endpoint
+                .clone()
+                .map(|otlp_endpoint| TraceExportConfig {
+                    otlp_endpoint,
+                    service_name: "aptos-node".to_string(),
+                    sampling_ratio: config.logger.trace_sampling_ratio,
+                }),
+        )
         .read_env();
     if config.logger.enable_backtrace {
         logger.enable_backtrace();
@@ -470,6 +488,40 @@ fn setup_state_sync_storage_service(
     Ok(storage_service_runtime)
 }
 
+/// Opens the read replica of the DB the API serves from, if configured, and keeps it caught up with
+/// the DB of the node in the background.
+fn open_api_read_replica(node_config: &NodeConfig) -> anyhow::Result<Option<Arc<AptosDB>>> {
+    let read_replica_config = match &node_config.api.read_replica {
+        Some(read_replica_config) => read_replica_config,
+        None => return Ok(None),
+    };
+    let dir = if read_replica_config.dir.is_relative() {
+        node_config.base.data_dir.join(&read_replica_config.dir)
+    } else {
+        read_replica_config.dir.clone()
+    };
+    let read_replica = Arc::new(
+        AptosDB::open_as_secondary(
+            node_config.storage.dir(),
+            dir,
+            node_config.storage.rocksdb_configs.clone(),
+        )
+        .map_err(|err| anyhow!("API read replica failed to open {}", err))?,
+    );
+
+    let catch_up_interval = Duration::from_millis(read_replica_config.catch_up_interval_ms);
+    let db = Arc::clone(&read_replica);
+    thread::Builder::new()
+        .name("api-read-replica".into())
+        .spawn(move || loop {
+            if let Err(err) = db.try_catch_up_with_primary() {
+                warn!("API read replica failed to catch up with the DB: {}", err);
+            }
+            thread::sleep(catch_up_interval);
+        })?;
+    Ok(Some(read_replica))
+}
+
 pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle> {
     // Start the node inspection service
     let node_config_clone = node_config.clone();
@@ -484,16 +536,36 @@ pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle>
             &node_config.storage.dir(),
             false, /* readonly */
             node_config.storage.storage_pruner_config,
-            node_config.storage.rocksdb_configs,
+            node_config.storage.rocksdb_configs.clone(),
             node_config.storage.enable_indexer,
             node_config.storage.target_snapshot_size,
+            node_config.storage.target_snapshot_interval_in_versions,
         )
+        .and_then(|mut db| {
+            db.set_node_cache_capacity(node_config.storage.state_merkle_node_cache_size_bytes);
+            db.set_state_value_cache_versions(node_config.storage.state_value_cache_versions);
+            if node_config.storage.enable_balance_index {
+                db.enable_balance_index()?;
+            }
+            Ok(db)
+        })
         .map_err(|err| anyhow!("DB failed to open {}", err))?,
     );
     let backup_service = start_backup_service(
         node_config.storage.backup_service_address,
         Arc::clone(&aptos_db),
+        node_config.storage.enable_db_admin_endpoints,
     );
+    let consistency_checker = node_config
+        .storage
+        .consistency_checker_config
+        .enable
+        .then(|| {
+            ConsistencyChecker::start(
+                Arc::clone(&aptos_db),
+                node_config.storage.consistency_checker_config,
+            )
+        });
 
     let genesis_waypoint = node_config.base.waypoint.genesis_waypoint();
     // if there's genesis txn and waypoint, commit it if the result matches.
@@ -535,6 +607,22 @@ pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle>
         None
     };
 
+    // Create an API subscription to new block events, which are emitted in every block, so that
+    // the API can refresh its cached ledger info whenever a commit happens.
+    let api_commit_subscription =
+        event_subscription_service.subscribe_to_events(vec![new_block_event_key()])?;
+
+    // Create the subscriptions of the notification stream, which publishes commits and epoch
+    // changes to local sidecar services (if enabled).
+    let notification_stream_listeners = if node_config.notification_stream.enabled {
+        Some((
+            event_subscription_service.subscribe_to_events(vec![new_block_event_key()])?,
+            event_subscription_service.subscribe_to_reconfigurations()?,
+        ))
+    } else {
+        None
+    };
+
     // Gather all network configs into a single vector.
     let mut network_configs: Vec<&NetworkConfig> = node_config.full_node_networks.iter().collect();
     if let Some(network_config) = node_config.validator_network.as_ref() {
@@ -661,17 +749,32 @@ pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle>
 
     let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
+    let api_db: Arc<dyn DbReader> = match open_api_read_replica(&node_config)? {
+        Some(read_replica) => read_replica,
+        None => aptos_db.clone(),
+    };
     let api_runtime = bootstrap_api(
         &node_config,
         chain_id,
-        aptos_db.clone(),
+        api_db,
         mp_client_sender.clone(),
+        Some(api_commit_subscription),
     )?;
     let sf_runtime = match bootstrap_sf_stream(&node_config, chain_id, aptos_db, mp_client_sender) {
         None => None,
         Some(res) => Some(res?),
     };
 
+    let notification_stream_runtime = match notification_stream_listeners {
+        Some((commit_listener, reconfig_listener)) => Some(notification_stream::bootstrap(
+            &node_config.notification_stream,
+            db_rw.reader.clone(),
+            commit_listener,
+            reconfig_listener,
+        )?),
+        None => None,
+    };
+
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
         channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
@@ -735,8 +838,10 @@ pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle>
         _api: api_runtime,
         _backup: backup_service,
         _consensus_runtime: consensus_runtime,
+        _consistency_checker: consistency_checker,
         _mempool: mempool,
         _network_runtimes: network_runtimes,
+        _notification_stream: notification_stream_runtime,
         _sf_stream: sf_runtime,
         _state_sync_runtimes: state_sync_runtimes,
         _telemetry_runtime: telemetry_runtime,
@@ -0,0 +1,329 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Publishes commits, epoch changes and advances of the ledger pruning window on a local unix
+//! socket, so that sidecar services (indexers, monitoring) can react to them promptly instead of
+//! polling the API.
+//!
+//! Every subscriber connecting to the socket is first sent the latest notification of each kind,
+//! and then every notification published from then on, each as a line of JSON. A subscriber
+//! falling too far behind is sent a `lagged` notification with the number it missed.
+
+use anyhow::Context;
+use aptos_config::config::NotificationStreamConfig;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{account_config::NewBlockEvent, transaction::Version};
+use event_notifications::{
+    EventNotification, EventNotificationListener, ReconfigNotification,
+    ReconfigNotificationListener,
+};
+use futures::StreamExt;
+use serde::Serialize;
+use std::sync::Arc;
+use storage_interface::DbReader;
+use tokio::{
+    io::AsyncWriteExt,
+    net::{UnixListener, UnixStream},
+    runtime::{Builder, Runtime},
+    sync::broadcast::{self, error::RecvError},
+};
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Notification {
+    /// Blocks were committed, up to `version`. The other fields are those of the latest block.
+    Commit {
+        version: Version,
+        epoch: u64,
+        round: u64,
+        block_height: u64,
+        timestamp_usecs: u64,
+    },
+    /// The epoch started at `version`.
+    EpochChange { epoch: u64, version: Version },
+    /// The ledger pruner advanced: the versions before `first_version` can no longer be read,
+    /// and those before `first_viable_version` are about to be pruned.
+    PruningWindow {
+        first_version: Version,
+        first_viable_version: Version,
+    },
+    /// The subscriber fell behind and missed `skipped` notifications.
+    Lagged { skipped: u64 },
+}
+
+/// The latest notification of each kind, sent to subscribers as they connect.
+#[derive(Default)]
+struct LatestNotifications {
+    commit: Option<Notification>,
+    epoch_change: Option<Notification>,
+    pruning_window: Option<Notification>,
+}
+
+impl LatestNotifications {
+    fn update(&mut self, notification: &Notification) {
+        let latest = match notification {
+            Notification::Commit { .. } => &mut self.commit,
+            Notification::EpochChange { .. } => &mut self.epoch_change,
+            Notification::PruningWindow { .. } => &mut self.pruning_window,
+            Notification::Lagged { .. } => return,
+        };
+        *latest = Some(notification.clone());
+    }
+
+    fn to_vec(&self) -> Vec<Notification> {
+        self.epoch_change
+            .iter()
+            .chain(&self.pruning_window)
+            .chain(&self.commit)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Starts the notification stream on the socket of the config, publishing the new block events
+/// of `commit_listener` and the reconfigurations of `reconfig_listener`.
+pub fn bootstrap(
+    config: &NotificationStreamConfig,
+    db: Arc<dyn DbReader>,
+    commit_listener: EventNotificationListener,
+    reconfig_listener: ReconfigNotificationListener,
+) -> anyhow::Result<Runtime> {
+    let runtime = Builder::new_multi_thread()
+        .thread_name("notification-stream")
+        .enable_all()
+        .build()
+        .context("Failed to create the notification stream runtime")?;
+
+    let socket_path = config.socket_path();
+    // A socket left behind by a previous run of the node would fail the bind.
+    if socket_path.exists() {
+        std::fs::remove_file(&socket_path).with_context(|| {
+            format!(
+                "Failed to remove the stale socket {}",
+                socket_path.display()
+            )
+        })?;
+    }
+    let listener = {
+        let _enter = runtime.enter();
+        UnixListener::bind(&socket_path)
+            .with_context(|| format!("Failed to bind the socket {}", socket_path.display()))?
+    };
+    info!("Notification stream listening on {}", socket_path.display());
+
+    let (sender, _) = broadcast::channel(config.max_pending_notifications.max(1));
+    let latest = Arc::new(Mutex::new(LatestNotifications::default()));
+    let publisher = NotificationPublisher {
+        db,
+        sender: sender.clone(),
+        latest: latest.clone(),
+    };
+    runtime.spawn(publisher.run(commit_listener, reconfig_listener));
+    runtime.spawn(serve(listener, sender, latest));
+    Ok(runtime)
+}
+
+struct NotificationPublisher {
+    db: Arc<dyn DbReader>,
+    sender: broadcast::Sender<Notification>,
+    latest: Arc<Mutex<LatestNotifications>>,
+}
+
+impl NotificationPublisher {
+    async fn run(
+        self,
+        mut commit_listener: EventNotificationListener,
+        mut reconfig_listener: ReconfigNotificationListener,
+    ) {
+        loop {
+            ::futures::select! {
+                notification = commit_listener.select_next_some() => {
+                    self.publish_commit(notification);
+                },
+                notification = reconfig_listener.select_next_some() => {
+                    self.publish_epoch_change(notification);
+                },
+                complete => break,
+            }
+        }
+    }
+
+    fn publish_commit(&self, notification: EventNotification) {
+        let new_block = match notification.subscribed_events.last() {
+            Some(event) => match NewBlockEvent::try_from_bytes(event.event_data()) {
+                Ok(new_block) => new_block,
+                Err(e) => {
+                    warn!("Failed to deserialize new block event: {:#}", e);
+                    return;
+                }
+            },
+            None => return,
+        };
+        self.publish(Notification::Commit {
+            version: notification.version,
+            epoch: new_block.epoch(),
+            round: new_block.round(),
+            block_height: new_block.height(),
+            timestamp_usecs: new_block.proposed_time(),
+        });
+
+        // The ledger pruner only advances as new versions get committed, so look for it to have
+        // advanced after every commit.
+        match self.pruning_window() {
+            Ok(pruning_window) => {
+                if self.latest.lock().pruning_window.as_ref() != Some(&pruning_window) {
+                    self.publish(pruning_window);
+                }
+            }
+            Err(e) => warn!("Failed to read the ledger pruning window: {:#}", e),
+        }
+    }
+
+    fn pruning_window(&self) -> anyhow::Result<Notification> {
+        Ok(Notification::PruningWindow {
+            first_version: self.db.get_first_txn_version()?.unwrap_or(0),
+            first_viable_version: self.db.get_first_viable_txn_version()?,
+        })
+    }
+
+    fn publish_epoch_change(&self, notification: ReconfigNotification) {
+        self.publish(Notification::EpochChange {
+            epoch: notification.on_chain_configs.epoch(),
+            version: notification.version,
+        });
+    }
+
+    fn publish(&self, notification: Notification) {
+        self.latest.lock().update(&notification);
+        // Sending only fails when there are no subscribers, which is fine.
+        let _ = self.sender.send(notification);
+    }
+}
+
+async fn serve(
+    listener: UnixListener,
+    sender: broadcast::Sender<Notification>,
+    latest: Arc<Mutex<LatestNotifications>>,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                // Subscribe before taking the latest notifications, so that none are missed in
+                // between, at the cost of possibly sending some twice.
+                let receiver = sender.subscribe();
+                let initial_notifications = latest.lock().to_vec();
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        stream_notifications(stream, initial_notifications, receiver).await
+                    {
+                        debug!("Notification stream subscriber disconnected: {:#}", e);
+                    }
+                });
+            }
+            Err(e) => warn!("Failed to accept notification stream subscriber: {:#}", e),
+        }
+    }
+}
+
+async fn stream_notifications(
+    mut stream: UnixStream,
+    initial_notifications: Vec<Notification>,
+    mut receiver: broadcast::Receiver<Notification>,
+) -> anyhow::Result<()> {
+    for notification in &initial_notifications {
+        write_notification(&mut stream, notification).await?;
+    }
+    loop {
+        let notification = match receiver.recv().await {
+            Ok(notification) => notification,
+            Err(RecvError::Lagged(skipped)) => Notification::Lagged { skipped },
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        write_notification(&mut stream, &notification).await?;
+    }
+}
+
+async fn write_notification(
+    stream: &mut UnixStream,
+    notification: &Notification,
+) -> anyhow::Result<()> {
+    let mut line = serde_json::to_vec(notification)?;
+    line.push(b'\n');
+    stream.write_all(&line).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use serde_json::json;
+    use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+
+    async fn next_line(lines: &mut Lines<BufReader<UnixStream>>) -> serde_json::Value {
+        let line = lines.next_line().await.unwrap().unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stream_notifications() {
+        let socket_path = TempPath::new();
+        let listener = UnixListener::bind(socket_path.path()).unwrap();
+        let (sender, _) = broadcast::channel(2);
+        let latest = Arc::new(Mutex::new(LatestNotifications::default()));
+        latest.lock().update(&Notification::EpochChange {
+            epoch: 2,
+            version: 10,
+        });
+        tokio::spawn(serve(listener, sender.clone(), latest));
+
+        let stream = UnixStream::connect(socket_path.path()).await.unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        assert_eq!(
+            next_line(&mut lines).await,
+            json!({"type": "epoch_change", "epoch": 2, "version": 10})
+        );
+
+        // The subscription is made on accept, which has happened once the latest notifications
+        // were received.
+        sender
+            .send(Notification::Commit {
+                version: 12,
+                epoch: 2,
+                round: 3,
+                block_height: 4,
+                timestamp_usecs: 5,
+            })
+            .unwrap();
+        assert_eq!(
+            next_line(&mut lines).await,
+            json!({
+                "type": "commit",
+                "version": 12,
+                "epoch": 2,
+                "round": 3,
+                "block_height": 4,
+                "timestamp_usecs": 5,
+            })
+        );
+
+        // Falling behind skips to the latest notifications.
+        for version in 13..17 {
+            sender
+                .send(Notification::PruningWindow {
+                    first_version: version,
+                    first_viable_version: version,
+                })
+                .unwrap();
+        }
+        assert_eq!(
+            next_line(&mut lines).await,
+            json!({"type": "lagged", "skipped": 2})
+        );
+        assert_eq!(
+            next_line(&mut lines).await,
+            json!({"type": "pruning_window", "first_version": 15, "first_viable_version": 15})
+        );
+    }
+}
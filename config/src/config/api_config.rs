@@ -1,9 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils;
+use crate::{keys::ConfigKey, utils};
+use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -20,11 +22,197 @@ pub struct ApiConfig {
     pub content_length_limit: Option<u64>,
     #[serde(default = "default_disabled")]
     pub failpoints_enabled: bool,
+    /// How long the API may serve a cached ledger info before reading it from
+    /// storage again, should it not have been refreshed by a commit notification.
+    #[serde(default = "default_ledger_info_max_staleness_ms")]
+    pub ledger_info_max_staleness_ms: u64,
+    /// The largest `limit` a request to a paginated endpoint may ask for.
+    #[serde(default = "default_max_page_size")]
+    pub max_page_size: u16,
+    /// The maximum serialized size of a page returned by a paginated endpoint.
+    /// Pages that would be larger are cut short at an item boundary, and a
+    /// cursor for fetching the remaining items is returned instead.
+    #[serde(default = "default_max_response_size_bytes")]
+    pub max_response_size_bytes: u64,
+    /// The largest `limit` a request for a range of transactions may ask for
+    /// when the transactions are streamed, i.e. when NDJSON is requested or
+    /// the limit is above `max_page_size`. Streamed pages are read and sent
+    /// a chunk at a time, so they aren't bound by `max_response_size_bytes`.
+    /// If unset, limits above `max_page_size` are rejected as before, and
+    /// NDJSON pages are limited to `max_page_size` too.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_streamed_page_size: Option<u16>,
+    /// The maximum number of transactions examined to fill a page of
+    /// transactions selected by filters. Pages of sparse matches come back
+    /// short, with a cursor from which to continue the scan.
+    #[serde(default = "default_max_transaction_filter_scan")]
+    pub max_transaction_filter_scan: u64,
+    /// The maximum number of versions whose write sets are scanned for the
+    /// diff of an account between two versions.
+    #[serde(default = "default_max_account_diff_versions")]
+    pub max_account_diff_versions: u64,
+    /// How long a request to wait for a transaction by hash may wait for the
+    /// transaction to be committed before returning it as pending.
+    #[serde(default = "default_wait_by_hash_timeout_ms")]
+    pub wait_by_hash_timeout_ms: u64,
+    /// How often a request waiting for a transaction by hash checks whether
+    /// it has been committed.
+    #[serde(default = "default_wait_by_hash_poll_interval_ms")]
+    pub wait_by_hash_poll_interval_ms: u64,
+    /// Enables endpoints meant for hosted development environments rather
+    /// than production nodes, e.g. compiling and testing Move packages.
+    #[serde(default = "default_disabled")]
+    pub developer_api_enabled: bool,
+    /// How browsers may call the API from other origins.
+    pub cors: CorsConfig,
+    /// CORS configurations replacing `cors` for the routes under the given
+    /// path prefixes, e.g. `/v1/transactions`. The longest matching prefix
+    /// applies.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub cors_overrides: BTreeMap<String, CorsConfig>,
+    /// Enables the endpoint reloading the API configuration from the node
+    /// config file, which can also be triggered by sending SIGHUP to the node.
+    #[serde(default = "default_disabled")]
+    pub config_reload_endpoint_enabled: bool,
+    /// The node config file this configuration was loaded from, if any, from
+    /// which it is reloaded.
+    #[serde(skip)]
+    pub node_config_path: Option<PathBuf>,
+    /// The maximum number of deserialized modules cached across requests for
+    /// resolving Move types. The cache is disabled when it is 0.
+    #[serde(default = "default_module_cache_capacity")]
+    pub module_cache_capacity: usize,
+    /// Requests cache modules separately per bucket of this many ledger
+    /// versions, so requests reading old versions don't evict the modules of
+    /// the latest ones.
+    #[serde(default = "default_module_cache_version_bucket_size")]
+    pub module_cache_version_bucket_size: u64,
+    /// The maximum number of storage reads of requests running at once,
+    /// each on a blocking thread of the runtime.
+    #[serde(default = "default_storage_read_threads")]
+    pub storage_read_threads: usize,
+    /// The maximum number of simulation results cached, so that simulating
+    /// the same transaction against the same state again doesn't run the VM.
+    /// The cache is disabled when it is 0.
+    #[serde(default = "default_simulation_cache_capacity")]
+    pub simulation_cache_capacity: usize,
+    /// The maximum number of blocks whose fee statistics are cached, so that
+    /// requesting them again doesn't read the transactions of the block. The
+    /// cache is disabled when it is 0.
+    #[serde(default = "default_fee_stats_cache_capacity")]
+    pub fee_stats_cache_capacity: usize,
+    /// The maximum number of multisig signing sessions held at once. The
+    /// multisig session endpoints are disabled when it is 0.
+    #[serde(default = "default_multisig_session_capacity")]
+    pub multisig_session_capacity: usize,
+    /// How long a multisig signing session is held after it is created.
+    #[serde(default = "default_multisig_session_ttl_secs")]
+    pub multisig_session_ttl_secs: u64,
+    /// The faucet funding accounts through the API, for test networks.
+    pub faucet: FaucetConfig,
+    /// Serves the API from a read replica of the DB rather than from the DB
+    /// the node commits to, so that heavy API reads don't compete with
+    /// commits, if set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_replica: Option<ReadReplicaConfig>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// The origins allowed to make cross-origin requests, e.g.
+    /// `https://example.com`. A `*` in an origin matches any characters, e.g.
+    /// `https://*.example.com` matches any subdomain of `example.com`. Any
+    /// origin is allowed if the list is empty.
+    pub allowed_origins: Vec<String>,
+    /// Request headers allowed on top of `Content-Type` and `Accept`.
+    pub allowed_headers: Vec<String>,
+    /// How long browsers may cache the result of a preflight request.
+    pub max_age_secs: u32,
+    /// Whether browsers may send credentials, e.g. cookies, along with
+    /// cross-origin requests.
+    pub allow_credentials: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec![],
+            allowed_headers: vec![],
+            max_age_secs: DEFAULT_CORS_MAX_AGE_SECS,
+            allow_credentials: false,
+        }
+    }
+}
+
+/// The faucet embedded in the API, which creates and funds accounts the way
+/// the faucet service does, so that local swarms and devnets don't need to
+/// run one. It is never enabled on mainnet.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaucetConfig {
+    /// Enables the `/faucet/fund` endpoint.
+    pub enabled: bool,
+    /// The private key of the account minting the coins.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint_key: Option<ConfigKey<Ed25519PrivateKey>>,
+    /// The file holding the BCS encoded private key of the account minting
+    /// the coins, read if `mint_key` isn't set, e.g. the `mint.key` of a
+    /// local swarm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint_key_file_path: Option<PathBuf>,
+    /// The account minting the coins, the test root account `0xa550c18` by
+    /// default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint_account_address: Option<AccountAddress>,
+    /// The largest amount funded per request, larger requests are capped to
+    /// it. Not limited if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_amount: Option<u64>,
+}
+
+/// A read-only secondary instance of the DB of the node, which tails the DB
+/// by catching up with it periodically. Responses lag commits by up to the
+/// catch up interval.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadReplicaConfig {
+    /// Where the secondary instance keeps its own files, e.g. its logs. A
+    /// relative path is relative to the data directory of the node.
+    pub dir: PathBuf,
+    /// How often the secondary instance catches up with the DB of the node.
+    pub catch_up_interval_ms: u64,
+}
+
+impl Default for ReadReplicaConfig {
+    fn default() -> ReadReplicaConfig {
+        ReadReplicaConfig {
+            dir: PathBuf::from(DEFAULT_READ_REPLICA_DIR),
+            catch_up_interval_ms: DEFAULT_READ_REPLICA_CATCH_UP_INTERVAL_MS,
+        }
+    }
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
+pub const DEFAULT_LEDGER_INFO_MAX_STALENESS_MS: u64 = 1000;
+pub const DEFAULT_MAX_PAGE_SIZE: u16 = 1000;
+pub const DEFAULT_MAX_RESPONSE_SIZE_BYTES: u64 = 16 * 1024 * 1024; // 16 MB
+pub const DEFAULT_MAX_TRANSACTION_FILTER_SCAN: u64 = 10_000;
+pub const DEFAULT_MAX_ACCOUNT_DIFF_VERSIONS: u64 = 10_000;
+pub const DEFAULT_WAIT_BY_HASH_TIMEOUT_MS: u64 = 1000;
+pub const DEFAULT_WAIT_BY_HASH_POLL_INTERVAL_MS: u64 = 20;
+pub const DEFAULT_CORS_MAX_AGE_SECS: u32 = 86400;
+pub const DEFAULT_MODULE_CACHE_CAPACITY: usize = 1024;
+pub const DEFAULT_MODULE_CACHE_VERSION_BUCKET_SIZE: u64 = 100_000;
+pub const DEFAULT_STORAGE_READ_THREADS: usize = 16;
+pub const DEFAULT_SIMULATION_CACHE_CAPACITY: usize = 1024;
+pub const DEFAULT_FEE_STATS_CACHE_CAPACITY: usize = 10_000;
+pub const DEFAULT_MULTISIG_SESSION_CAPACITY: usize = 1000;
+pub const DEFAULT_MULTISIG_SESSION_TTL_SECS: u64 = 3600;
+pub const DEFAULT_READ_REPLICA_DIR: &str = "api_read_replica";
+pub const DEFAULT_READ_REPLICA_CATCH_UP_INTERVAL_MS: u64 = 100;
 
 fn default_enabled() -> bool {
     true
@@ -34,6 +222,62 @@ fn default_disabled() -> bool {
     false
 }
 
+fn default_ledger_info_max_staleness_ms() -> u64 {
+    DEFAULT_LEDGER_INFO_MAX_STALENESS_MS
+}
+
+fn default_max_page_size() -> u16 {
+    DEFAULT_MAX_PAGE_SIZE
+}
+
+fn default_max_response_size_bytes() -> u64 {
+    DEFAULT_MAX_RESPONSE_SIZE_BYTES
+}
+
+fn default_max_transaction_filter_scan() -> u64 {
+    DEFAULT_MAX_TRANSACTION_FILTER_SCAN
+}
+
+fn default_max_account_diff_versions() -> u64 {
+    DEFAULT_MAX_ACCOUNT_DIFF_VERSIONS
+}
+
+fn default_wait_by_hash_timeout_ms() -> u64 {
+    DEFAULT_WAIT_BY_HASH_TIMEOUT_MS
+}
+
+fn default_wait_by_hash_poll_interval_ms() -> u64 {
+    DEFAULT_WAIT_BY_HASH_POLL_INTERVAL_MS
+}
+
+fn default_module_cache_capacity() -> usize {
+    DEFAULT_MODULE_CACHE_CAPACITY
+}
+
+fn default_module_cache_version_bucket_size() -> u64 {
+    DEFAULT_MODULE_CACHE_VERSION_BUCKET_SIZE
+}
+
+fn default_storage_read_threads() -> usize {
+    DEFAULT_STORAGE_READ_THREADS
+}
+
+fn default_simulation_cache_capacity() -> usize {
+    DEFAULT_SIMULATION_CACHE_CAPACITY
+}
+
+fn default_fee_stats_cache_capacity() -> usize {
+    DEFAULT_FEE_STATS_CACHE_CAPACITY
+}
+
+fn default_multisig_session_capacity() -> usize {
+    DEFAULT_MULTISIG_SESSION_CAPACITY
+}
+
+fn default_multisig_session_ttl_secs() -> u64 {
+    DEFAULT_MULTISIG_SESSION_TTL_SECS
+}
+
 impl Default for ApiConfig {
     fn default() -> ApiConfig {
         ApiConfig {
@@ -45,6 +289,28 @@ impl Default for ApiConfig {
             tls_key_path: None,
             content_length_limit: None,
             failpoints_enabled: default_disabled(),
+            ledger_info_max_staleness_ms: default_ledger_info_max_staleness_ms(),
+            max_page_size: default_max_page_size(),
+            max_response_size_bytes: default_max_response_size_bytes(),
+            max_streamed_page_size: None,
+            max_transaction_filter_scan: default_max_transaction_filter_scan(),
+            max_account_diff_versions: default_max_account_diff_versions(),
+            wait_by_hash_timeout_ms: default_wait_by_hash_timeout_ms(),
+            wait_by_hash_poll_interval_ms: default_wait_by_hash_poll_interval_ms(),
+            developer_api_enabled: default_disabled(),
+            cors: CorsConfig::default(),
+            cors_overrides: BTreeMap::new(),
+            config_reload_endpoint_enabled: default_disabled(),
+            node_config_path: None,
+            module_cache_capacity: default_module_cache_capacity(),
+            module_cache_version_bucket_size: default_module_cache_version_bucket_size(),
+            storage_read_threads: default_storage_read_threads(),
+            simulation_cache_capacity: default_simulation_cache_capacity(),
+            fee_stats_cache_capacity: default_fee_stats_cache_capacity(),
+            multisig_session_capacity: default_multisig_session_capacity(),
+            multisig_session_ttl_secs: default_multisig_session_ttl_secs(),
+            faucet: FaucetConfig::default(),
+            read_replica: None,
         }
     }
 }
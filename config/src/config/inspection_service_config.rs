@@ -11,6 +11,7 @@ pub struct InspectionServiceConfig {
     pub port: u16,
     pub expose_configuration: bool,
     pub expose_system_information: bool,
+    pub expose_parallel_execution_stats: bool,
 }
 
 impl Default for InspectionServiceConfig {
@@ -20,6 +21,7 @@ impl Default for InspectionServiceConfig {
             port: 9101,
             expose_configuration: false,
             expose_system_information: true,
+            expose_parallel_execution_stats: true,
         }
     }
 }
@@ -18,6 +18,10 @@ pub struct LoggerConfig {
     pub level: Level,
     // tokio-console port
     pub console_port: Option<u16>,
+    // OpenTelemetry collector to export traces to over OTLP/gRPC, e.g. http://localhost:4317
+    pub otlp_trace_endpoint: Option<String>,
+    // The fraction of the traces started by the node that are exported
+    pub trace_sampling_ratio: f64,
 }
 
 impl Default for LoggerConfig {
@@ -28,6 +32,8 @@ impl Default for LoggerConfig {
             is_async: true,
             level: Level::Info,
             console_port: Some(6669),
+            otlp_trace_endpoint: None,
+            trace_sampling_ratio: 0.1,
         }
     }
 }
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -20,6 +21,9 @@ pub struct MempoolConfig {
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub shared_mempool_validator_broadcast: bool,
+    /// Rules rejecting transactions submitted to this node. They are reloaded along with the API
+    /// config, see `ApiConfig::config_reload_endpoint_enabled`.
+    pub submission_filter: SubmissionFilterConfig,
 }
 
 impl Default for MempoolConfig {
@@ -38,6 +42,22 @@ impl Default for MempoolConfig {
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             shared_mempool_validator_broadcast: true,
+            submission_filter: SubmissionFilterConfig::default(),
         }
     }
 }
+
+/// Rules rejecting the transactions submitted to mempool that match any of them, either by clients
+/// or by peers, e.g. to stop transactions exploiting a bug while it is being fixed.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmissionFilterConfig {
+    /// The accounts whose transactions are rejected.
+    pub denied_senders: Vec<AccountAddress>,
+    /// The functions whose calls are rejected, e.g. `0x1::coin::transfer`, or the modules calls to
+    /// any function of which are rejected, e.g. `0x1::coin`.
+    pub denied_functions: Vec<String>,
+    /// The size of the largest transaction payload accepted, in BCS bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_payload_size_bytes: Option<u64>,
+}
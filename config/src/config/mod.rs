@@ -30,6 +30,8 @@ mod mempool_config;
 pub use mempool_config::*;
 mod network_config;
 pub use network_config::*;
+mod notification_stream_config;
+pub use notification_stream_config::*;
 mod secure_backend_config;
 pub use secure_backend_config::*;
 mod state_sync_config;
@@ -76,6 +78,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub metrics: DeprecatedConfig,
     #[serde(default)]
+    pub notification_stream: NotificationStreamConfig,
+    #[serde(default)]
     pub peer_monitoring_service: PeerMonitoringServiceConfig,
     #[serde(default)]
     pub api: ApiConfig,
@@ -251,6 +255,7 @@ impl NodeConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.base.data_dir = data_dir.clone();
         self.consensus.set_data_dir(data_dir.clone());
+        self.notification_stream.set_data_dir(data_dir.clone());
         self.storage.set_data_dir(data_dir);
     }
 
@@ -260,6 +265,7 @@ impl NodeConfig {
     pub fn load<P: AsRef<Path>>(input_path: P) -> Result<Self, Error> {
         let mut config = Self::load_config(&input_path)?;
 
+        config.api.node_config_path = Some(input_path.as_ref().to_path_buf());
         let input_dir = RootPath::new(input_path);
         config.execution.load(&input_dir)?;
 
@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const DEFAULT_MAX_PENDING_NOTIFICATIONS: usize = 1_000;
+
+/// The notification stream publishes commits, epoch changes and advances of the pruning window
+/// on a local unix socket, so that sidecar services can react to them without polling the API.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotificationStreamConfig {
+    pub enabled: bool,
+    /// The path of the unix socket, relative to the data dir unless absolute
+    pub socket_path: PathBuf,
+    /// How many notifications a subscriber may fall behind by before it misses some
+    pub max_pending_notifications: usize,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for NotificationStreamConfig {
+    fn default() -> NotificationStreamConfig {
+        NotificationStreamConfig {
+            enabled: false,
+            socket_path: PathBuf::from("notifications.sock"),
+            max_pending_notifications: DEFAULT_MAX_PENDING_NOTIFICATIONS,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+}
+
+impl NotificationStreamConfig {
+    pub fn socket_path(&self) -> PathBuf {
+        if self.socket_path.is_relative() {
+            self.data_dir.join(&self.socket_path)
+        } else {
+            self.socket_path.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub const DEFAULT_MAX_SUBSCRIPTIONS: usize = 100;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct SfStreamerConfig {
     pub enabled: bool,
@@ -11,4 +14,21 @@ pub struct SfStreamerConfig {
     // Alternatively can set the `STARTING_VERSION` env var
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub starting_version: Option<u64>,
+    // The address to serve event subscriptions at, over websockets. They are served regardless of
+    // `enabled`, but only if this is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subscription_address: Option<SocketAddr>,
+    // The number of event subscriptions served at once, beyond which new ones are refused
+    pub max_subscriptions: usize,
+}
+
+impl Default for SfStreamerConfig {
+    fn default() -> SfStreamerConfig {
+        SfStreamerConfig {
+            enabled: false,
+            starting_version: None,
+            subscription_address: None,
+            max_subscriptions: DEFAULT_MAX_SUBSCRIPTIONS,
+        }
+    }
 }
@@ -152,6 +152,10 @@ pub struct DataStreamingServiceConfig {
 
     // The interval (milliseconds) at which to check the progress of each stream.
     pub progress_check_interval_ms: u64,
+
+    // The limits on the bandwidth and concurrency of data streams while the
+    // node is catching up, e.g., backfilling a fullnode.
+    pub catch_up_throttle: CatchUpThrottleConfig,
 }
 
 impl Default for DataStreamingServiceConfig {
@@ -164,6 +168,36 @@ impl Default for DataStreamingServiceConfig {
             max_request_retry: 3,
             max_notification_id_mappings: 2000,
             progress_check_interval_ms: 100,
+            catch_up_throttle: CatchUpThrottleConfig::default(),
+        }
+    }
+}
+
+/// The throttle of data streams while the node is catching up, so that a node
+/// syncing a lot of data doesn't starve the API and consensus traffic sharing
+/// its network. A stream is catching up while it lags the highest version
+/// advertised by peers by at least `min_lag_versions` (streams of states and
+/// epoch ending ledger infos only run while bootstrapping, so always are).
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CatchUpThrottleConfig {
+    pub enabled: bool, // Whether or not to throttle data streams while catching up
+    pub min_lag_versions: u64, // The min lag (versions) at which a stream is catching up
+    pub max_bytes_per_second: u64, // The max bytes per second of data received by each stream
+    pub max_boosted_bytes_per_second: u64, // The max the cap is raised to when the lag keeps growing
+    pub max_concurrent_requests: u64, // Max num of concurrent data client requests (per stream)
+    pub lag_check_interval_ms: u64,   // The interval (ms) at which to adjust the cap to the lag
+}
+
+impl Default for CatchUpThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_lag_versions: 100_000,
+            max_bytes_per_second: 10 * 1024 * 1024, // 10 MiB/s
+            max_boosted_bytes_per_second: 40 * 1024 * 1024, // 40 MiB/s
+            max_concurrent_requests: 1,
+            lag_check_interval_ms: 10_000,
         }
     }
 }
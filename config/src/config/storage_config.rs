@@ -1,31 +1,140 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils;
+use crate::{config::Error, utils};
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
 };
 
 pub const TARGET_SNAPSHOT_SIZE: usize = 100_000;
+pub const TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS: u64 = 20_000;
 
 /// Port selected RocksDB options for tuning underlying rocksdb instance of AptosDB.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct RocksdbConfig {
     pub max_open_files: i32,
     pub max_total_wal_size: u64,
     pub max_background_jobs: i32,
+    /// Tuning of the column families that are not listed in `column_families`.
+    #[serde(default)]
+    pub default_cf_config: RocksdbCfConfig,
+    /// Tuning of individual column families, keyed by column family name.
+    #[serde(default)]
+    pub column_families: BTreeMap<String, RocksdbCfConfig>,
+}
+
+impl RocksdbConfig {
+    /// Returns the tuning of a column family.
+    pub fn cf_config(&self, cf_name: &str) -> &RocksdbCfConfig {
+        self.column_families
+            .get(cf_name)
+            .unwrap_or(&self.default_cf_config)
+    }
+
+    /// Checks that the column family tuning is usable for a DB with the given column families.
+    pub fn validate(&self, db_name: &str, cf_names: &[&str]) -> Result<(), Error> {
+        if let Some(unknown) = self
+            .column_families
+            .keys()
+            .find(|name| !cf_names.contains(&name.as_str()))
+        {
+            return Err(Error::InvariantViolation(format!(
+                "{} has no column family named {}, known column families: {:?}",
+                db_name, unknown, cf_names,
+            )));
+        }
+        self.default_cf_config
+            .validate(&format!("{} default_cf_config", db_name))?;
+        for (cf_name, cf_config) in &self.column_families {
+            cf_config.validate(&format!("{} column family {}", db_name, cf_name))?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of levels of a RocksDB LSM tree, which is the RocksDB default.
+pub const ROCKSDB_NUM_LEVELS: usize = 7;
+
+/// Tuning of a single RocksDB column family.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RocksdbCfConfig {
+    /// Size in bytes of the LRU block cache of the column family. 0 leaves the block cache to
+    /// RocksDB, which defaults to 8MB.
+    pub block_cache_size: u64,
+    /// Bits per key of the bloom filter of the blocks, 0 disables the bloom filter.
+    pub bloom_filter_bits_per_key: u32,
+    /// Compression of each level, starting from level 0. Levels beyond the end of the list use
+    /// its last entry.
+    pub compression_per_level: Vec<RocksdbCompression>,
+    pub compaction_style: RocksdbCompactionStyle,
+}
+
+impl Default for RocksdbCfConfig {
+    fn default() -> Self {
+        Self {
+            block_cache_size: 0,
+            bloom_filter_bits_per_key: 0,
+            compression_per_level: vec![RocksdbCompression::Lz4],
+            compaction_style: RocksdbCompactionStyle::Level,
+        }
+    }
+}
+
+impl RocksdbCfConfig {
+    fn validate(&self, name: &str) -> Result<(), Error> {
+        if self.compression_per_level.is_empty()
+            || self.compression_per_level.len() > ROCKSDB_NUM_LEVELS
+        {
+            return Err(Error::InvariantViolation(format!(
+                "{}: compression_per_level must have between 1 and {} entries, got {}",
+                name,
+                ROCKSDB_NUM_LEVELS,
+                self.compression_per_level.len(),
+            )));
+        }
+        if self.bloom_filter_bits_per_key > 64 {
+            return Err(Error::InvariantViolation(format!(
+                "{}: bloom_filter_bits_per_key must be at most 64, got {}",
+                name, self.bloom_filter_bits_per_key,
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The compression algorithms RocksDB is built with.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompression {
+    None,
+    Lz4,
+    Lz4hc,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
+/// FIFO compaction is left out on purpose, as it drops the oldest data once the column family
+/// reaches its size limit.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    Level,
+    Universal,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RocksdbConfigs {
     pub ledger_db_config: RocksdbConfig,
     pub state_merkle_db_config: RocksdbConfig,
+    /// Tuning of each shard of the state values, when `state_kv_sharding` shards them.
+    pub state_kv_db_config: RocksdbConfig,
     pub index_db_config: RocksdbConfig,
+    pub state_kv_sharding: StateKvShardingConfig,
 }
 
 impl Default for RocksdbConfigs {
@@ -40,6 +149,8 @@ impl Default for RocksdbConfigs {
                 // This includes threads for flashing and compaction. Rocksdb will decide the # of
                 // threads to use internally.
                 max_background_jobs: 16,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
             },
             state_merkle_db_config: RocksdbConfig {
                 // Allow db to close old sst files, saving memory.
@@ -50,6 +161,20 @@ impl Default for RocksdbConfigs {
                 // This includes threads for flashing and compaction. Rocksdb will decide the # of
                 // threads to use internally.
                 max_background_jobs: 16,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
+            },
+            state_kv_db_config: RocksdbConfig {
+                // Allow db to close old sst files, saving memory.
+                max_open_files: 5000,
+                // For now we set the max total WAL size to be 1G. This config can be useful when column
+                // families are updated at non-uniform frequencies.
+                max_total_wal_size: 1u64 << 30,
+                // This includes threads for flashing and compaction. Rocksdb will decide the # of
+                // threads to use internally.
+                max_background_jobs: 16,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
             },
             index_db_config: RocksdbConfig {
                 // Allow db to close old sst files, saving memory.
@@ -60,8 +185,55 @@ impl Default for RocksdbConfigs {
                 // This includes threads for flashing and compaction. Rocksdb will decide the # of
                 // threads to use internally.
                 max_background_jobs: 16,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
             },
+            state_kv_sharding: StateKvShardingConfig::default(),
+        }
+    }
+}
+
+/// The maximum number of shards the state values may be spread across.
+pub const MAX_STATE_KV_SHARDS: usize = 256;
+
+/// Spreads the state values, and the indices of the stale ones, across RocksDB instances of their
+/// own by the hash of the state key, so that the IO of reading, writing and pruning them can be
+/// spread across multiple disks. The number of shards is recorded when the DB is first opened, and
+/// the DB refuses to open with a different one, or with a shard moved to another shard's path.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateKvShardingConfig {
+    /// The number of shards, 0 keeping the state values in the ledger DB.
+    pub num_shards: usize,
+    /// The directories of the first shards, by shard id, e.g. each on a disk of its own. The
+    /// shards not listed are stored under `state_kv_db` in the DB directory.
+    pub shard_paths: Vec<PathBuf>,
+}
+
+impl StateKvShardingConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.num_shards > MAX_STATE_KV_SHARDS {
+            return Err(Error::InvariantViolation(format!(
+                "state_kv_sharding: num_shards must be at most {}, got {}",
+                MAX_STATE_KV_SHARDS, self.num_shards,
+            )));
+        }
+        if self.shard_paths.len() > self.num_shards {
+            return Err(Error::InvariantViolation(format!(
+                "state_kv_sharding: {} shard_paths given for {} shards",
+                self.shard_paths.len(),
+                self.num_shards,
+            )));
         }
+        for (shard_id, path) in self.shard_paths.iter().enumerate() {
+            if self.shard_paths[..shard_id].contains(path) {
+                return Err(Error::InvariantViolation(format!(
+                    "state_kv_sharding: shard {} has the same path as another shard, {:?}",
+                    shard_id, path,
+                )));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -76,12 +248,35 @@ pub struct StorageConfig {
     data_dir: PathBuf,
     /// The threshold that determine whether a snapshot should be committed to state merkle db.
     pub target_snapshot_size: usize,
+    /// The number of versions after which a snapshot is committed to the state merkle db even if
+    /// it has fewer than `target_snapshot_size` updates. The state after the latest committed
+    /// snapshot is only kept in memory, and is rebuilt on startup by replaying the write sets
+    /// committed since, this many versions at a time. A larger interval means fewer, larger
+    /// snapshot commits, at the cost of more memory and a longer replay after a crash. A node
+    /// refuses to start if far more versions than the interval, or the default one if larger, are
+    /// after the latest snapshot, as that means the state merkle db doesn't go with the ledger db.
+    pub target_snapshot_interval_in_versions: u64,
     /// Rocksdb-specific configurations
     pub rocksdb_configs: RocksdbConfigs,
     /// Try to enable the internal indexer. The indexer expects to have seen all transactions
     /// since genesis. To recover operation after data loss, or to bootstrap a node in fast sync
     /// mode, the indexer db needs to be copied in from another node.
     pub enable_indexer: bool,
+    /// Keep an index of the latest coin balances of the accounts in the internal indexer, which
+    /// requires `enable_indexer`. The first time it is enabled, it is backfilled from the latest
+    /// state snapshot on startup, which takes a while on a large DB.
+    pub enable_balance_index: bool,
+    pub consistency_checker_config: ConsistencyCheckerConfig,
+    /// Serve the DB admin endpoints (RocksDB stats, manual compactions and prunable space
+    /// estimates) on the backup service address. Only enable it where that address isn't reachable
+    /// from outside the node, as compactions take up a lot of IO.
+    pub enable_db_admin_endpoints: bool,
+    /// The memory, in bytes, the cache of the state merkle tree nodes read by commits and proofs
+    /// may take up. 0 disables the cache.
+    pub state_merkle_node_cache_size_bytes: usize,
+    /// The number of most recent versions the state values written by which are cached in memory
+    /// once committed, for reads at the latest versions, e.g. by the API. 0 disables the cache.
+    pub state_value_cache_versions: u64,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -96,6 +291,14 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
         user_pruning_window_offset: 0,
+        max_nodes_to_prune_per_second: 0,
+    },
+    ignore_backup_progress: false,
+    schedule: PrunerScheduleConfig {
+        window_start_minute: 0,
+        window_end_minute: 0,
+        max_load_percent: 0,
+        catch_up_lag_versions: 0,
     },
 };
 
@@ -130,6 +333,24 @@ pub struct StateMerklePrunerConfig {
     pub batch_size: usize,
     /// The offset for user pruning window to adjust
     pub user_pruning_window_offset: u64,
+    /// Caps the rate at which stale nodes are deleted, to bound the IO the pruner takes away from
+    /// commits and reads on large databases. 0 means no limit.
+    pub max_nodes_to_prune_per_second: usize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyCheckerConfig {
+    /// Boolean to enable/disable the consistency checker, which checks the versions in the
+    /// background as they get committed, verifying the stored transactions, write sets, events and
+    /// state checkpoints against the hashes the ledger commits them with.
+    pub enable: bool,
+    /// The number of versions before the latest one to start checking from when the node starts.
+    pub lookback_versions: u64,
+    /// The number of versions to check at a time.
+    pub batch_size: usize,
+    /// How long to wait for new versions to check once the latest one is checked.
+    pub check_interval_ms: u64,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, Default)]
@@ -137,6 +358,33 @@ pub struct StateMerklePrunerConfig {
 pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
+    /// Once a backup coordinator has registered the highest version it backed up, the pruners
+    /// don't prune past it, so that a lagging backup doesn't lose versions for good. Setting this
+    /// lets the pruners run ahead of the backup regardless, e.g. to reclaim disk space when the
+    /// backup is known to be abandoned.
+    pub ignore_backup_progress: bool,
+    /// When the pruners may run, by default as soon as there is anything to prune.
+    pub schedule: PrunerScheduleConfig,
+}
+
+/// Restricts when the pruners run, so that their IO doesn't compete with serving the node at its
+/// busiest. Pruning is not restricted unless a window or a maximum load is set.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrunerScheduleConfig {
+    /// The daily window the pruners run in, from its start included to its end excluded, in
+    /// minutes after midnight UTC. It wraps around midnight if it ends before it starts, and is
+    /// not set if it starts when it ends.
+    pub window_start_minute: u16,
+    pub window_end_minute: u16,
+    /// Outside of the window, the pruners run while the load average of the last minute, in
+    /// percent of the number of CPUs, is below this. The load average counts the tasks waiting
+    /// for IO too on Linux, the only platform it is read on. 0 means not to run on low load.
+    pub max_load_percent: u32,
+    /// A pruner runs regardless of the window and the load while it is at least this many
+    /// versions behind its target, so that the DB doesn't keep growing when the node is never
+    /// quiet. 0 means never to catch up this way.
+    pub catch_up_lag_versions: u64,
 }
 
 impl Default for LedgerPrunerConfig {
@@ -159,6 +407,18 @@ impl Default for StateMerklePrunerConfig {
             // creation benchmark) on a 4B items DB (or 1.33B accounts) yields 300k JMT nodes
             batch_size: 1_000,
             user_pruning_window_offset: 200_000,
+            max_nodes_to_prune_per_second: 0,
+        }
+    }
+}
+
+impl Default for ConsistencyCheckerConfig {
+    fn default() -> Self {
+        ConsistencyCheckerConfig {
+            enable: false,
+            lookback_versions: 100_000,
+            batch_size: 1_000,
+            check_interval_ms: 10_000,
         }
     }
 }
@@ -179,7 +439,13 @@ impl Default for StorageConfig {
             data_dir: PathBuf::from("/opt/aptos/data"),
             rocksdb_configs: RocksdbConfigs::default(),
             enable_indexer: false,
+            enable_balance_index: false,
             target_snapshot_size: TARGET_SNAPSHOT_SIZE,
+            target_snapshot_interval_in_versions: TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            consistency_checker_config: ConsistencyCheckerConfig::default(),
+            enable_db_admin_endpoints: false,
+            state_merkle_node_cache_size_bytes: 128 << 20,
+            state_value_cache_versions: 10_000,
         }
     }
 }
@@ -203,3 +469,86 @@ impl StorageConfig {
             .set_port(utils::get_available_port());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rocksdb_cf_config() {
+        let mut config = RocksdbConfigs::default().ledger_db_config;
+        let cf_names = ["default", "transaction"];
+        config.validate("ledger_db", &cf_names).unwrap();
+
+        let tuned = RocksdbCfConfig {
+            block_cache_size: 1 << 30,
+            bloom_filter_bits_per_key: 10,
+            compression_per_level: vec![RocksdbCompression::None, RocksdbCompression::Lz4],
+            compaction_style: RocksdbCompactionStyle::Universal,
+        };
+        config
+            .column_families
+            .insert("transaction".to_string(), tuned.clone());
+        config.validate("ledger_db", &cf_names).unwrap();
+        assert_eq!(config.cf_config("transaction"), &tuned);
+        assert_eq!(config.cf_config("default"), &RocksdbCfConfig::default());
+
+        // Unknown column family.
+        config
+            .column_families
+            .insert("no_such_cf".to_string(), RocksdbCfConfig::default());
+        assert!(config.validate("ledger_db", &cf_names).is_err());
+        config.column_families.remove("no_such_cf");
+
+        // More compression levels than the tree has.
+        config.default_cf_config.compression_per_level =
+            vec![RocksdbCompression::Lz4; ROCKSDB_NUM_LEVELS + 1];
+        assert!(config.validate("ledger_db", &cf_names).is_err());
+        config.default_cf_config.compression_per_level = vec![];
+        assert!(config.validate("ledger_db", &cf_names).is_err());
+    }
+
+    #[test]
+    fn test_rocksdb_cf_config_from_yaml() {
+        let config: RocksdbConfig = serde_yaml::from_str(
+            r#"
+            max_open_files: 5000
+            max_total_wal_size: 1073741824
+            max_background_jobs: 16
+            column_families:
+                jellyfish_merkle_node:
+                    block_cache_size: 4294967296
+                    bloom_filter_bits_per_key: 10
+                    compression_per_level: [none, none, lz4]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.default_cf_config, RocksdbCfConfig::default());
+        let cf_config = config.cf_config("jellyfish_merkle_node");
+        assert_eq!(cf_config.block_cache_size, 4 << 30);
+        assert_eq!(cf_config.compaction_style, RocksdbCompactionStyle::Level);
+    }
+
+    #[test]
+    fn test_state_kv_sharding_config() {
+        let mut config = StateKvShardingConfig::default();
+        config.validate().unwrap();
+
+        config.num_shards = 4;
+        config.shard_paths = vec![PathBuf::from("/disk0"), PathBuf::from("/disk1")];
+        config.validate().unwrap();
+
+        // The same path for two shards.
+        config.shard_paths.push(PathBuf::from("/disk0"));
+        assert!(config.validate().is_err());
+
+        // More paths than shards.
+        config.num_shards = 1;
+        config.shard_paths = vec![PathBuf::from("/disk0"), PathBuf::from("/disk1")];
+        assert!(config.validate().is_err());
+
+        config.num_shards = MAX_STATE_KV_SHARDS + 1;
+        config.shard_paths = vec![];
+        assert!(config.validate().is_err());
+    }
+}
@@ -162,6 +162,7 @@ mod tests {
                     vm_status: "Executed".to_string(),
                     accumulator_root_hash: HashValue::zero().into(),
                     changes: vec![],
+                    epoch_change: None,
                 };
                 let serializable_txn: aptos_rest_client::aptos_api_types::Transaction = (
                     txn.as_signed_user_txn().unwrap(),
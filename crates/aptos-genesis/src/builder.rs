@@ -23,7 +23,10 @@ use aptos_crypto::{
     PrivateKey,
 };
 use aptos_keygen::KeyGen;
-use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    chain_id::ChainId, on_chain_config::OnChainConsensusConfig, transaction::Transaction,
+    waypoint::Waypoint,
+};
 use framework::ReleaseBundle;
 use rand::Rng;
 use serde::{de::DeserializeOwned, Serialize};
@@ -35,6 +38,7 @@ use std::{
     path::{Path, PathBuf},
     sync::Arc,
 };
+use vm_genesis::AccountBalance;
 
 const VALIDATOR_IDENTITY: &str = "validator-identity.yaml";
 const VFN_IDENTITY: &str = "vfn-identity.yaml";
@@ -389,6 +393,9 @@ pub struct GenesisConfiguration {
     pub rewards_apy_percentage: u64,
     pub voting_duration_secs: u64,
     pub voting_power_increase_limit: u64,
+    pub consensus_config: OnChainConsensusConfig,
+    /// Accounts created at genesis, with their balances.
+    pub accounts: Vec<AccountBalance>,
 }
 
 pub type InitConfigFn = Arc<dyn Fn(usize, &mut NodeConfig, &mut u64) + Send + Sync>;
@@ -580,6 +587,8 @@ impl Builder {
             rewards_apy_percentage: 10,
             voting_duration_secs: ONE_DAY / 24,
             voting_power_increase_limit: 50,
+            consensus_config: OnChainConsensusConfig::default(),
+            accounts: vec![],
         };
         if let Some(init_genesis_config) = &self.init_genesis_config {
             (init_genesis_config)(&mut genesis_config);
@@ -12,16 +12,22 @@ pub mod test_utils;
 
 use crate::builder::GenesisConfiguration;
 use crate::config::ValidatorConfiguration;
-use aptos_config::config::{RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE};
+use aptos_config::config::{
+    RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+    TARGET_SNAPSHOT_SIZE,
+};
 use aptos_crypto::ed25519::Ed25519PublicKey;
 use aptos_temppath::TempPath;
-use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    chain_id::ChainId, on_chain_config::OnChainConsensusConfig, transaction::Transaction,
+    waypoint::Waypoint,
+};
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use framework::ReleaseBundle;
 use std::convert::TryInto;
 use storage_interface::DbReaderWriter;
-use vm_genesis::Validator;
+use vm_genesis::{AccountBalance, Validator};
 
 /// Holder object for all pieces needed to generate a genesis transaction
 #[derive(Clone)]
@@ -58,6 +64,10 @@ pub struct GenesisInfo {
     pub voting_duration_secs: u64,
     /// Percent of current epoch's total voting power that can be added in this epoch.
     pub voting_power_increase_limit: u64,
+    /// The config of consensus on chain
+    pub consensus_config: OnChainConsensusConfig,
+    /// Accounts created at genesis, besides the ones of validators
+    pub accounts: Vec<AccountBalance>,
 }
 
 impl GenesisInfo {
@@ -91,6 +101,8 @@ impl GenesisInfo {
             rewards_apy_percentage: genesis_config.rewards_apy_percentage,
             voting_duration_secs: genesis_config.voting_duration_secs,
             voting_power_increase_limit: genesis_config.voting_power_increase_limit,
+            consensus_config: genesis_config.consensus_config.clone(),
+            accounts: genesis_config.accounts.clone(),
         })
     }
 
@@ -108,6 +120,7 @@ impl GenesisInfo {
             self.root_key.clone(),
             &self.validators,
             &self.framework,
+            self.consensus_config.clone(),
             self.chain_id,
             vm_genesis::GenesisConfiguration {
                 allow_new_validators: self.allow_new_validators,
@@ -121,6 +134,7 @@ impl GenesisInfo {
                 rewards_apy_percentage: self.rewards_apy_percentage,
                 voting_duration_secs: self.voting_duration_secs,
                 voting_power_increase_limit: self.voting_power_increase_limit,
+                accounts: self.accounts.clone(),
             },
         )
     }
@@ -135,6 +149,7 @@ impl GenesisInfo {
             RocksdbConfigs::default(),
             false,
             TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
         )?;
         let db_rw = DbReaderWriter::new(aptosdb);
         executor::db_bootstrapper::generate_waypoint::<AptosVM>(&db_rw, genesis)
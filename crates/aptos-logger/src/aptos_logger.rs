@@ -11,6 +11,7 @@ use crate::{
     },
     logger::Logger,
     struct_log::TcpWriter,
+    trace_export::TraceExportConfig,
     Event, Filter, Key, Level, LevelFilter, Metadata,
 };
 use aptos_infallible::RwLock;
@@ -208,6 +209,7 @@ pub struct AptosDataBuilder {
     printer: Option<Box<dyn Writer>>,
     is_async: bool,
     custom_format: Option<fn(&LogEntry) -> Result<String, fmt::Error>>,
+    trace_export: Option<TraceExportConfig>,
 }
 
 impl AptosDataBuilder {
@@ -223,6 +225,7 @@ impl AptosDataBuilder {
             printer: Some(Box::new(StdoutWriter::new())),
             is_async: false,
             custom_format: None,
+            trace_export: None,
         }
     }
 
@@ -267,6 +270,14 @@ impl AptosDataBuilder {
         self.console_port = console_port;
         self
     }
+
+    /// Exports the spans of `tracing` with OpenTelemetry. Has no effect when tokio-console is
+    /// enabled, as it captures the spans instead.
+    pub fn trace_export(&mut self, trace_export: Option<TraceExportConfig>) -> &mut Self {
+        self.trace_export = trace_export;
+        self
+    }
+
     pub fn is_async(&mut self, is_async: bool) -> &mut Self {
         self.is_async = is_async;
         self
@@ -363,7 +374,7 @@ impl AptosDataBuilder {
             None
         };
 
-        crate::logger::set_global_logger(logger.clone(), console_port);
+        crate::logger::set_global_logger(logger.clone(), console_port, self.trace_export.take());
         logger
     }
 }
@@ -735,7 +746,7 @@ mod tests {
     fn set_test_logger() -> Receiver<LogEntry> {
         let (logger, receiver) = LogStream::new(true);
         let logger = Arc::new(logger);
-        crate::logger::set_global_logger(logger, None);
+        crate::logger::set_global_logger(logger, None, None);
         receiver
     }
 
@@ -151,6 +151,7 @@ mod logger;
 mod macros;
 mod metadata;
 pub mod sample;
+pub mod trace_export;
 pub mod tracing_adapter;
 
 mod security;
@@ -3,7 +3,7 @@
 
 //! Global logger definition and functions
 
-use crate::{counters::STRUCT_LOG_COUNT, error, Event, Metadata};
+use crate::{counters::STRUCT_LOG_COUNT, error, trace_export::TraceExportConfig, Event, Metadata};
 
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
@@ -40,8 +40,12 @@ pub(crate) fn enabled(metadata: &Metadata) -> bool {
         .unwrap_or(false)
 }
 
-/// Sets the global `Logger` exactly once
-pub fn set_global_logger(logger: Arc<dyn Logger>, console_port: Option<u16>) {
+/// Sets the global `Logger` exactly once, along with the export of traces if configured
+pub fn set_global_logger(
+    logger: Arc<dyn Logger>,
+    console_port: Option<u16>,
+    trace_export: Option<TraceExportConfig>,
+) {
     if LOGGER.set(logger).is_err() {
         eprintln!("Global logger has already been set");
         error!("Global logger has already been set");
@@ -65,9 +69,13 @@ pub fn set_global_logger(logger: Arc<dyn Logger>, console_port: Option<u16>) {
         }
     }
     if None == console_port {
+        let opentelemetry_layer = trace_export
+            .as_ref()
+            .and_then(crate::trace_export::opentelemetry_layer);
         let _ = tracing::subscriber::set_global_default(
-            crate::tracing_adapter::TracingToAptosDataLayer
-                .with_subscriber(tracing_subscriber::Registry::default()),
+            tracing_subscriber::registry()
+                .with(opentelemetry_layer)
+                .with(crate::tracing_adapter::TracingToAptosDataLayer),
         );
     } else {
         error!("console_port was set but has no effect, build with --cfg aptos-console");
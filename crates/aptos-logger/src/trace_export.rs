@@ -0,0 +1,112 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exports the spans of `tracing` to an OpenTelemetry collector, so that requests can be traced
+//! across components, e.g. from the API down to storage and the VM.
+
+use crate::error;
+use once_cell::sync::OnceCell;
+use opentelemetry::{
+    global,
+    sdk::{
+        propagation::TraceContextPropagator,
+        trace::{self, Sampler, Tracer},
+        Resource,
+    },
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use tokio::runtime::{Builder, Runtime};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Where and how much to export traces.
+#[derive(Clone, Debug)]
+pub struct TraceExportConfig {
+    /// The endpoint of the OpenTelemetry collector receiving the traces over OTLP/gRPC, e.g.
+    /// `http://localhost:4317`.
+    pub otlp_endpoint: String,
+    /// The name the traces are exported under.
+    pub service_name: String,
+    /// The fraction of the traces started by this process that are exported. Traces continued
+    /// from a caller are exported if the caller sampled them.
+    pub sampling_ratio: f64,
+}
+
+/// The runtime the exporter sends the traces on. The logger is set up before any runtime of the
+/// process is, and the exporter has to outlive them anyway.
+static TRACE_EXPORT_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+/// Builds the layer exporting spans with the given config, and sets the W3C Trace Context
+/// propagator for continuing the traces of callers.
+pub(crate) fn opentelemetry_layer<S>(
+    config: &TraceExportConfig,
+) -> Option<OpenTelemetryLayer<S, Tracer>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let runtime = match TRACE_EXPORT_RUNTIME.get_or_try_init(|| {
+        Builder::new_multi_thread()
+            .thread_name("trace-export")
+            .worker_threads(1)
+            .enable_all()
+            .build()
+    }) {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            error!(error = %e, "Failed to create the runtime exporting traces");
+            return None;
+        }
+    };
+    // The exporter connects to the collector and starts the batch processor on the runtime it
+    // is built in.
+    let _guard = runtime.enter();
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sampling_ratio)));
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.otlp_endpoint),
+        )
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler)
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    config.service_name.clone(),
+                )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio);
+    match tracer {
+        Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+        Err(e) => {
+            error!(
+                endpoint = config.otlp_endpoint,
+                error = %e,
+                "Failed to export traces"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::Registry;
+
+    #[test]
+    fn build_layer_outside_of_runtime() {
+        let config = TraceExportConfig {
+            otlp_endpoint: "http://localhost:4317".to_string(),
+            service_name: "test".to_string(),
+            sampling_ratio: 1.0,
+        };
+        assert!(opentelemetry_layer::<Registry>(&config).is_some());
+    }
+}
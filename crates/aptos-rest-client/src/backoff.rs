@@ -0,0 +1,62 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_retrier::ExponentWithLimitDelay;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// How requests failing for reasons that are likely transient, e.g. a node
+/// that is restarting or shedding load, are retried. The delays between
+/// attempts grow by half each time, from `initial_delay` up to `max_delay`,
+/// and no attempt is made once `timeout` has passed since the first one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Backoff {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub timeout: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial_delay: Duration, max_delay: Duration, timeout: Duration) -> Self {
+        Self {
+            initial_delay,
+            max_delay,
+            timeout,
+        }
+    }
+
+    /// The delays before each retry, starting from now.
+    pub(crate) fn delays(&self) -> ExponentWithLimitDelay {
+        ExponentWithLimitDelay::new(
+            self.initial_delay.as_millis() as u64,
+            self.max_delay.as_millis() as u64,
+            self.timeout.as_millis() as u64,
+        )
+    }
+
+    /// Whether a request with this result is worth retrying. Only failures to
+    /// reach the node and responses saying it can't serve the request right
+    /// now are, other errors would be returned again.
+    pub(crate) fn should_retry(result: &reqwest::Result<reqwest::Response>) -> bool {
+        match result {
+            Ok(response) => matches!(
+                response.status(),
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            Err(error) => error.is_connect() || error.is_timeout(),
+        }
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(100),
+            Duration::from_secs(5),
+            Duration::from_secs(30),
+        )
+    }
+}
@@ -1,6 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_api_types::AptosError;
+use reqwest::StatusCode;
+
 pub type Result<T, E = Error> = ::std::result::Result<T, E>;
 
 #[derive(Debug)]
@@ -139,3 +142,23 @@ impl From<serde_json::Error> for Error {
         Self::decode(e)
     }
 }
+
+/// The error the API responded to a request with, which the client's requests fail with, so that
+/// callers can tell the errors apart, e.g. by downcasting an `anyhow::Error` to it.
+#[derive(Debug)]
+pub struct RestError {
+    pub status_code: StatusCode,
+    pub error: AptosError,
+}
+
+impl std::fmt::Display for RestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Request failed with {}: {:?}",
+            self.status_code, self.error
+        )
+    }
+}
+
+impl std::error::Error for RestError {}
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod aptos;
+pub mod backoff;
+pub use backoff::Backoff;
 pub mod error;
+pub use error::RestError;
 pub mod faucet;
 pub use faucet::FaucetClient;
 pub mod response;
@@ -19,17 +22,23 @@ pub use types::{Account, Resource};
 use crate::aptos::{AptosVersion, Balance};
 use anyhow::{anyhow, Result};
 use aptos_api_types::{
-    mime_types::BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE, AptosError, Block, HexEncodedBytes,
-    VersionedEvent,
+    mime_types::{self, BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE},
+    AptosError, AptosErrorCode, Block, HexEncodedBytes, VersionedEvent, X_APTOS_LEDGER_VERSION_PIN,
 };
 use aptos_crypto::HashValue;
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::{NewBlockEvent, CORE_CODE_ADDRESS},
-    transaction::SignedTransaction,
+    account_config::{AccountResource, NewBlockEvent, CORE_CODE_ADDRESS},
+    state_store::state_key::StateKey,
+    transaction::{SignedTransaction, TransactionOutput},
 };
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveResource};
 use poem_openapi::types::ParseFromJSON;
-use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Client as ReqwestClient, RequestBuilder, StatusCode,
+};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::time::Duration;
@@ -44,6 +53,8 @@ pub struct Client {
     inner: ReqwestClient,
     base_url: Url,
     version_path_base: String,
+    backoff: Option<Backoff>,
+    ledger_version_pin: Option<u64>,
 }
 
 impl Client {
@@ -73,6 +84,8 @@ impl Client {
             inner,
             base_url,
             version_path_base,
+            backoff: None,
+            ledger_version_pin: None,
         }
     }
 
@@ -86,6 +99,46 @@ impl Client {
         Ok(self)
     }
 
+    /// Retry requests failing for transient reasons, e.g. the node being
+    /// unreachable or overloaded, with the given backoff. Without one, which
+    /// is the default, such failures are returned straight away.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = Some(backoff);
+        self
+    }
+
+    /// Returns a client whose reads are all made at the given ledger version,
+    /// by sending it in the X-Aptos-Ledger-Version-Pin header. Requests which
+    /// set a ledger version themselves, e.g. get_account_resource_at_version,
+    /// are made at the version they set instead. The node rejects the reads
+    /// once the version has been pruned.
+    pub fn pinned_to_version(&self, version: u64) -> Self {
+        Self {
+            ledger_version_pin: Some(version),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a client whose reads are all made at the current version of
+    /// the ledger, so that they are consistent with each other.
+    pub async fn pinned_to_latest_version(&self) -> Result<Self> {
+        let version = self.get_ledger_information().await?.into_inner().version;
+        Ok(self.pinned_to_version(version))
+    }
+
+    /// Returns a client reading the latest state again.
+    pub fn unpinned(&self) -> Self {
+        Self {
+            ledger_version_pin: None,
+            ..self.clone()
+        }
+    }
+
+    /// The ledger version the reads of this client are pinned to, if any.
+    pub fn ledger_version_pin(&self) -> Option<u64> {
+        self.ledger_version_pin
+    }
+
     fn build_path(&self, path: &str) -> Result<Url> {
         Ok(self.base_url.join(&self.version_path_base)?.join(path)?)
     }
@@ -165,11 +218,12 @@ impl Client {
         let url = self.build_path("transactions")?;
 
         let response = self
-            .inner
-            .post(url)
-            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
-            .body(txn_payload)
-            .send()
+            .send(
+                self.inner
+                    .post(url)
+                    .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+                    .body(txn_payload),
+            )
             .await?;
 
         self.json(response).await
@@ -257,11 +311,42 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
 
+    /// Gets the outputs of committed transactions, as they were written to
+    /// storage, i.e. with write sets and events in BCS.
+    pub async fn get_transaction_outputs_bcs(
+        &self,
+        start: Option<u64>,
+        limit: Option<u16>,
+    ) -> Result<Response<Vec<TransactionOutput>>> {
+        let url = self.build_path("transactions/outputs")?;
+
+        let mut request = self.inner.get(url).header(ACCEPT, mime_types::BCS);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = self.send(request).await?;
+
+        self.bcs(response).await
+    }
+
+    pub async fn get_transaction_output_by_version_bcs(
+        &self,
+        version: u64,
+    ) -> Result<Response<TransactionOutput>> {
+        let url = self.build_path(&format!("transactions/{}/outputs", version))?;
+        self.get_bcs(url).await
+    }
+
     pub async fn get_transaction_by_hash(&self, hash: HashValue) -> Result<Response<Transaction>> {
         self.json(self.get_transaction_by_hash_inner(hash).await?)
             .await
@@ -269,7 +354,7 @@ impl Client {
 
     async fn get_transaction_by_hash_inner(&self, hash: HashValue) -> Result<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_hash/{}", hash.to_hex_literal()))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send(self.inner.get(url)).await?)
     }
 
     pub async fn get_transaction_by_version(&self, version: u64) -> Result<Response<Transaction>> {
@@ -279,7 +364,7 @@ impl Client {
 
     async fn get_transaction_by_version_inner(&self, version: u64) -> Result<reqwest::Response> {
         let url = self.build_path(&format!("transactions/by_version/{}", version))?;
-        Ok(self.inner.get(url).send().await?)
+        Ok(self.send(self.inner.get(url)).await?)
     }
 
     pub async fn get_account_transactions(
@@ -299,7 +384,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         self.json(response).await
     }
@@ -310,7 +395,7 @@ impl Client {
     ) -> Result<Response<Vec<Resource>>> {
         let url = self.build_path(&format!("accounts/{}/resources", address))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
 
         self.json(response).await
     }
@@ -325,7 +410,7 @@ impl Client {
             address, version
         ))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
 
         self.json(response).await
     }
@@ -357,7 +442,7 @@ impl Client {
     ) -> Result<Response<Option<Resource>>> {
         let url = self.build_path(&format!("accounts/{}/resource/{}", address, resource_type))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -368,21 +453,69 @@ impl Client {
         version: u64,
     ) -> Result<Response<Option<Resource>>> {
         let url = self.build_path(&format!(
-            "accounts/{}/resource/{}?version={}",
+            "accounts/{}/resource/{}?ledger_version={}",
             address, resource_type, version
         ))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
+    /// Gets a resource of an account as its Move type, decoded from the BCS
+    /// bytes in storage, rather than from its JSON representation.
+    pub async fn get_account_resource_bcs<T: MoveResource>(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<T>> {
+        self.get_resource_bcs(address, None).await
+    }
+
+    pub async fn get_account_resource_at_version_bcs<T: MoveResource>(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<T>> {
+        self.get_resource_bcs(address, Some(version)).await
+    }
+
+    async fn get_resource_bcs<T: MoveResource>(
+        &self,
+        address: AccountAddress,
+        version: Option<u64>,
+    ) -> Result<Response<T>> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            T::struct_tag(),
+        )));
+        let mut request = self.inner.post(self.build_path("state_values/raw")?);
+        if let Some(version) = version {
+            request = request.query(&[("ledger_version", version)]);
+        }
+        let data = json!({
+            "key": HexEncodedBytes::from(bcs::to_bytes(&state_key)?),
+        });
+
+        let response = self
+            .send(request.header(ACCEPT, mime_types::BCS).json(&data))
+            .await?;
+        self.bcs(response).await
+    }
+
+    /// Gets the account resource of an account, decoded from BCS.
+    pub async fn get_account_bcs(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<AccountResource>> {
+        self.get_account_resource_bcs(address).await
+    }
+
     pub async fn get_account_modules(
         &self,
         address: AccountAddress,
     ) -> Result<Response<Vec<MoveModuleBytecode>>> {
         let url = self.build_path(&format!("accounts/{}/modules", address))?;
 
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -409,7 +542,7 @@ impl Client {
             request = request.query(&[("limit", limit)])
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         self.json(response).await
     }
 
@@ -491,13 +624,38 @@ impl Client {
             "key": json!(key),
         });
 
-        let response = self.inner.post(url).json(&data).send().await?;
+        let response = self.send(self.inner.post(url).json(&data)).await?;
         self.json(response).await
     }
 
+    /// Gets the raw value stored under `state_key` at `version`, returning
+    /// None if there is no value under the key.
+    pub async fn get_raw_state_value(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        let url = self.build_path(&format!("state_values/raw?ledger_version={}", version))?;
+        let data = json!({
+            "key": HexEncodedBytes::from(bcs::to_bytes(state_key)?),
+        });
+
+        let response = self.send(self.inner.post(url).json(&data)).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            let error = AptosError::parse_from_json(Some(response.json().await?))
+                .map_err(|e| anyhow!("Failed to parse error response: {:?}", e))?;
+            return match error.error_code {
+                AptosErrorCode::StateValueNotFound => Ok(None),
+                _ => Err(anyhow!("Request failed: {:?}", error)),
+            };
+        }
+        let response: Response<HexEncodedBytes> = self.json(response).await?;
+        Ok(Some(response.into_inner().into()))
+    }
+
     pub async fn get_account(&self, address: AccountAddress) -> Result<Response<Account>> {
         let url = self.build_path(&format!("accounts/{}", address))?;
-        let response = self.inner.get(url).send().await?;
+        let response = self.send(self.inner.get(url)).await?;
         self.json(response).await
     }
 
@@ -508,7 +666,7 @@ impl Client {
             .append_pair("name", &name)
             .append_pair("actions", &actions)
             .finish();
-        let response = self.inner.get(url.clone()).send().await?;
+        let response = self.send(self.inner.get(url.clone())).await?;
 
         if !response.status().is_success() {
             let error_response = AptosError::parse_from_json(Some(response.json().await?));
@@ -526,8 +684,11 @@ impl Client {
         response: reqwest::Response,
     ) -> Result<(reqwest::Response, State)> {
         if !response.status().is_success() {
-            let error_response = AptosError::parse_from_json(Some(response.json().await?));
-            return Err(anyhow::anyhow!("Request failed: {:?}", error_response));
+            let status_code = response.status();
+            return match AptosError::parse_from_json(Some(response.json().await?)) {
+                Ok(error) => Err(RestError { status_code, error }.into()),
+                Err(e) => Err(anyhow!("Request failed: {:?}", e)),
+            };
         }
         let state = State::from_headers(response.headers())?;
 
@@ -543,13 +704,16 @@ impl Client {
         Ok(Response::new(json, state))
     }
 
+    async fn bcs<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<Response<T>> {
+        let (response, state) = self.check_response(response).await?;
+        let bytes = response.bytes().await?;
+        Ok(Response::new(bcs::from_bytes(&bytes)?, state))
+    }
+
     pub async fn health_check(&self, seconds: u64) -> Result<()> {
         let url = self.build_path("-/healthy")?;
         let response = self
-            .inner
-            .get(url)
-            .query(&[("duration_secs", seconds)])
-            .send()
+            .send(self.inner.get(url).query(&[("duration_secs", seconds)]))
             .await?;
 
         if !response.status().is_success() {
@@ -560,7 +724,43 @@ impl Client {
     }
 
     async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<Response<T>> {
-        self.json(self.inner.get(url).send().await?).await
+        self.json(self.send(self.inner.get(url)).await?).await
+    }
+
+    async fn get_bcs<T: DeserializeOwned>(&self, url: Url) -> Result<Response<T>> {
+        self.bcs(
+            self.send(self.inner.get(url).header(ACCEPT, mime_types::BCS))
+                .await?,
+        )
+        .await
+    }
+
+    /// Sends a request, pinned to the ledger version of this client if it has
+    /// one, and retried according to its backoff.
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let request = match self.ledger_version_pin {
+            Some(version) => request.header(X_APTOS_LEDGER_VERSION_PIN, version),
+            None => request,
+        };
+        let backoff = match self.backoff {
+            Some(backoff) => backoff,
+            None => return Ok(request.send().await?),
+        };
+
+        let mut delays = backoff.delays();
+        loop {
+            let attempt = request
+                .try_clone()
+                .ok_or_else(|| anyhow!("Failed to clone the request to retry it"))?;
+            let result = attempt.send().await;
+            if Backoff::should_retry(&result) {
+                if let Some(delay) = delays.next() {
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            }
+            return Ok(result?);
+        }
     }
 }
 
@@ -570,6 +770,8 @@ impl From<(ReqwestClient, Url)> for Client {
             inner,
             base_url,
             version_path_base: DEFAULT_VERSION_PATH_BASE.to_string(),
+            backoff: None,
+            ledger_version_pin: None,
         }
     }
 }
@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_rest_client::Client;
+use aptos_state_view::StateView;
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use std::{collections::HashMap, sync::Mutex};
+use tokio::runtime::Handle;
+
+/// A [`StateView`] of a node's state at a fixed version, which reads state
+/// lazily over the REST API as the VM asks for it
+///
+/// Values are cached, so each key is fetched at most once. Reads block on the
+/// given runtime, so this must be used outside of it, e.g. from
+/// `tokio::task::spawn_blocking`.
+pub struct RemoteStateView {
+    client: Client,
+    version: Version,
+    runtime: Handle,
+    cache: Mutex<HashMap<StateKey, Option<Vec<u8>>>>,
+}
+
+impl RemoteStateView {
+    pub fn new(client: Client, version: Version, runtime: Handle) -> Self {
+        Self {
+            client,
+            version,
+            runtime,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The number of state keys read from the node so far
+    pub fn num_keys_read(&self) -> usize {
+        self.cache.lock().unwrap().len()
+    }
+}
+
+impl StateView for RemoteStateView {
+    fn get_state_value(&self, state_key: &StateKey) -> anyhow::Result<Option<Vec<u8>>> {
+        if let Some(value) = self.cache.lock().unwrap().get(state_key) {
+            return Ok(value.clone());
+        }
+        let value = self
+            .runtime
+            .block_on(self.client.get_raw_state_value(state_key, self.version))?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(state_key.clone(), value.clone());
+        Ok(value)
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{
     common::{
         init::{DEFAULT_FAUCET_URL, DEFAULT_REST_URL},
+        remote_state_view::RemoteStateView,
         utils::{
             chain_id, check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
             get_sequence_number, read_from_file, start_logger, to_common_result,
@@ -34,13 +35,25 @@ use aptos_sdk::{
     transaction_builder::TransactionFactory,
     types::LocalAccount,
 };
-use aptos_types::transaction::{
-    authenticator::AuthenticationKey, ScriptFunction, TransactionPayload,
+use aptos_state_view::StateView;
+use aptos_types::{
+    access_path::{AccessPath, Path as AccessPathPath},
+    account_config::AccountResource,
+    chain_id::ChainId,
+    state_store::state_key::StateKey,
+    transaction::{
+        authenticator::AuthenticationKey, ExecutionStatus, ScriptFunction, TransactionOutput,
+        TransactionPayload, TransactionStatus,
+    },
+    write_set::WriteOp,
 };
+use aptos_vm::AptosVM;
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
 use hex::FromHexError;
-use move_deps::move_core_types::account_address::AccountAddress;
+use move_deps::move_core_types::{
+    account_address::AccountAddress, language_storage::ResourceKey, move_resource::MoveStructType,
+};
 use serde::{Deserialize, Serialize};
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -973,6 +986,138 @@ impl From<Transaction> for TransactionSummary {
     }
 }
 
+/// The outcome of a transaction simulated locally against a node's state
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalSimulationSummary {
+    /// The version of the node's state the transaction was simulated against
+    version: u64,
+    success: bool,
+    vm_status: String,
+    gas_used: u64,
+    events: Vec<LocalEventSummary>,
+    changes: Vec<LocalChangeSummary>,
+    /// The number of state values read from the node during the simulation
+    state_values_read: usize,
+}
+
+impl LocalSimulationSummary {
+    fn new(state_view: &RemoteStateView, output: TransactionOutput) -> Self {
+        let (success, vm_status) = match output.status() {
+            TransactionStatus::Keep(ExecutionStatus::Success) => {
+                (true, "Executed successfully".to_string())
+            }
+            TransactionStatus::Keep(status) => (false, format!("{:?}", status)),
+            TransactionStatus::Discard(status) => (false, format!("Discarded: {:?}", status)),
+            TransactionStatus::Retry => (false, "Retry".to_string()),
+        };
+        LocalSimulationSummary {
+            version: state_view.version(),
+            success,
+            vm_status,
+            gas_used: output.gas_used(),
+            events: output
+                .events()
+                .iter()
+                .map(|event| LocalEventSummary {
+                    key: event.key().to_string(),
+                    sequence_number: event.sequence_number(),
+                    type_tag: event.type_tag().to_string(),
+                    data: hex::encode(event.event_data()),
+                })
+                .collect(),
+            changes: output
+                .write_set()
+                .iter()
+                .map(|(state_key, write_op)| LocalChangeSummary::new(state_key, write_op))
+                .collect(),
+            state_values_read: state_view.num_keys_read(),
+        }
+    }
+}
+
+/// An event emitted by a locally simulated transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalEventSummary {
+    key: String,
+    sequence_number: u64,
+    type_tag: String,
+    /// Hex encoded BCS of the event data
+    data: String,
+}
+
+/// A change to the state made by a locally simulated transaction
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LocalChangeSummary {
+    event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<AccountAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    module: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handle: Option<String>,
+    /// Hex encoded key of a table item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<String>,
+    /// Hex encoded BCS of the new value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+}
+
+impl LocalChangeSummary {
+    fn new(state_key: &StateKey, write_op: &WriteOp) -> Self {
+        let value = match write_op {
+            WriteOp::Creation(bytes) | WriteOp::Modification(bytes) => Some(hex::encode(bytes)),
+            WriteOp::Deletion => None,
+        };
+        let deletion = value.is_none();
+        match state_key {
+            StateKey::AccessPath(access_path) => match access_path.get_path() {
+                AccessPathPath::Code(module_id) => LocalChangeSummary {
+                    event: if deletion {
+                        "delete_module"
+                    } else {
+                        "write_module"
+                    },
+                    address: Some(access_path.address),
+                    module: Some(module_id.to_string()),
+                    value,
+                    ..Default::default()
+                },
+                AccessPathPath::Resource(struct_tag) => LocalChangeSummary {
+                    event: if deletion {
+                        "delete_resource"
+                    } else {
+                        "write_resource"
+                    },
+                    address: Some(access_path.address),
+                    resource: Some(struct_tag.to_string()),
+                    value,
+                    ..Default::default()
+                },
+            },
+            StateKey::TableItem { handle, key } => LocalChangeSummary {
+                event: if deletion {
+                    "delete_table_item"
+                } else {
+                    "write_table_item"
+                },
+                handle: Some(format!("{:x}", handle.0)),
+                key: Some(hex::encode(key)),
+                value,
+                ..Default::default()
+            },
+            StateKey::Raw(key) => LocalChangeSummary {
+                event: if deletion { "delete_raw" } else { "write_raw" },
+                key: Some(hex::encode(key)),
+                value,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 /// A summary of a [`WriteSetChange`] for easy printing
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct ChangeSummary {
@@ -1140,6 +1285,71 @@ impl TransactionOptions {
 
         Ok(response.into_inner())
     }
+
+    /// Simulates a transaction in a local VM, against the state of the node at
+    /// `ledger_version` or at its latest version. The state the transaction
+    /// reads is fetched from the node as it executes, so this works without a
+    /// local copy of the chain.
+    pub async fn simulate_transaction_locally(
+        &self,
+        payload: TransactionPayload,
+        ledger_version: Option<u64>,
+    ) -> CliTypedResult<LocalSimulationSummary> {
+        let sender_key = self.private_key()?;
+        let client = self.rest_client()?;
+        let sender_address = self.sender_address()?;
+
+        let state = client
+            .get_ledger_information()
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner();
+        let version = ledger_version.unwrap_or(state.version);
+        let transaction_factory = TransactionFactory::new(ChainId::new(state.chain_id))
+            .with_gas_unit_price(self.gas_options.gas_unit_price)
+            .with_max_gas_amount(self.gas_options.max_gas);
+
+        // The VM reads state synchronously, so run it outside of the runtime
+        // and let the state view block on the requests it makes.
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            let state_view = RemoteStateView::new(client, version, runtime);
+
+            let account_key = StateKey::AccessPath(AccessPath::resource_access_path(
+                ResourceKey::new(sender_address, AccountResource::struct_tag()),
+            ));
+            let account: AccountResource = bcs::from_bytes(
+                &state_view
+                    .get_state_value(&account_key)
+                    .map_err(|err| CliError::ApiError(err.to_string()))?
+                    .ok_or_else(|| {
+                        CliError::CommandArgumentError(format!(
+                            "Account {} doesn't exist at version {}",
+                            sender_address, version
+                        ))
+                    })?,
+            )
+            .map_err(|err| CliError::BCS("AccountResource", err))?;
+
+            let sender_account =
+                &mut LocalAccount::new(sender_address, sender_key, account.sequence_number());
+            let transaction =
+                sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+
+            let (_, output) = AptosVM::simulate_signed_transaction(&transaction, &state_view);
+            let output = output
+                .into_transaction_output(&state_view)
+                .map_err(|status| {
+                    CliError::UnexpectedError(format!(
+                        "Failed to apply the deltas of the transaction: {:?}",
+                        status
+                    ))
+                })?;
+            Ok(LocalSimulationSummary::new(&state_view, output))
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+    }
 }
 
 #[derive(Parser)]
@@ -23,7 +23,7 @@ use aptos_genesis::{
     config::{Layout, ValidatorConfiguration},
     GenesisInfo,
 };
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, on_chain_config::OnChainConsensusConfig};
 use async_trait::async_trait;
 use clap::Parser;
 use std::path::Path;
@@ -163,6 +163,8 @@ pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo
             rewards_apy_percentage: layout.rewards_apy_percentage,
             voting_duration_secs: layout.voting_duration_secs,
             voting_power_increase_limit: layout.voting_power_increase_limit,
+            consensus_config: OnChainConsensusConfig::default(),
+            accounts: vec![],
         },
     )?)
 }
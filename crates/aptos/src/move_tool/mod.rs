@@ -19,8 +19,8 @@ use crate::move_tool::manifest::{
 use crate::{
     common::{
         types::{
-            load_account_arg, CliError, CliTypedResult, MovePackageDir, PromptOptions,
-            TransactionOptions, TransactionSummary,
+            load_account_arg, CliError, CliTypedResult, LocalSimulationSummary, MovePackageDir,
+            PromptOptions, TransactionOptions, TransactionSummary,
         },
         utils::check_if_file_exists,
     },
@@ -73,6 +73,7 @@ pub enum MoveTool {
     Download(DownloadPackage),
     List(ListPackage),
     Run(RunFunction),
+    Simulate(SimulateFunction),
     Test(TestPackage),
     Prove(ProvePackage),
     TransactionalTest(TransactionalTestOpts),
@@ -87,6 +88,7 @@ impl MoveTool {
             MoveTool::Download(tool) => tool.execute_serialized().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
+            MoveTool::Simulate(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
             MoveTool::TransactionalTest(tool) => tool.execute_serialized_success().await,
@@ -405,6 +407,7 @@ impl IncludedArtifacts {
                 with_abis: false,
                 with_source_maps: false,
                 with_error_map: false,
+                with_source_attestation: false,
                 named_addresses,
             },
             Sparse => BuildOptions {
@@ -412,6 +415,7 @@ impl IncludedArtifacts {
                 with_abis: false,
                 with_source_maps: false,
                 with_error_map: false,
+                with_source_attestation: true,
                 named_addresses,
             },
             All => BuildOptions {
@@ -419,6 +423,7 @@ impl IncludedArtifacts {
                 with_abis: true,
                 with_source_maps: true,
                 with_error_map: true,
+                with_source_attestation: true,
                 named_addresses,
             },
         }
@@ -625,6 +630,16 @@ impl CliCommand<TransactionSummary> for RunFunction {
     }
 
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload = self.payload()?;
+        self.txn_options
+            .submit_transaction(payload)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
+
+impl RunFunction {
+    fn payload(&self) -> CliTypedResult<TransactionPayload> {
         let args: Vec<Vec<u8>> = self
             .args
             .iter()
@@ -639,15 +654,41 @@ impl CliCommand<TransactionSummary> for RunFunction {
             type_args.push(type_tag)
         }
 
-        self.txn_options
-            .submit_transaction(TransactionPayload::ScriptFunction(ScriptFunction::new(
-                self.function_id.module_id.clone(),
-                self.function_id.member_id.clone(),
-                type_args,
-                args,
-            )))
+        Ok(TransactionPayload::ScriptFunction(ScriptFunction::new(
+            self.function_id.module_id.clone(),
+            self.function_id.member_id.clone(),
+            type_args,
+            args,
+        )))
+    }
+}
+
+/// Simulate running a Move function locally
+///
+/// The transaction is executed by a local VM against the state of the node,
+/// which is fetched as the transaction reads it. Nothing is submitted, and the
+/// gas used, events and changes to the state are printed.
+#[derive(Parser)]
+pub struct SimulateFunction {
+    #[clap(flatten)]
+    pub(crate) function: RunFunction,
+    /// Ledger version to simulate against, defaults to the latest version
+    #[clap(long)]
+    pub(crate) ledger_version: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<LocalSimulationSummary> for SimulateFunction {
+    fn command_name(&self) -> &'static str {
+        "SimulateFunction"
+    }
+
+    async fn execute(self) -> CliTypedResult<LocalSimulationSummary> {
+        let payload = self.function.payload()?;
+        self.function
+            .txn_options
+            .simulate_transaction_locally(payload, self.ledger_version)
             .await
-            .map(TransactionSummary::from)
     }
 }
 
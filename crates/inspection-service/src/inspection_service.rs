@@ -3,6 +3,7 @@
 
 use crate::{gather_metrics, json_encoder::JsonEncoder, NUM_METRICS};
 use aptos_config::config::NodeConfig;
+use aptos_parallel_executor::stats::RECENT_BLOCK_STATS;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
@@ -11,6 +12,7 @@ use prometheus::{
     proto::{MetricFamily, MetricType},
     Encoder, TextEncoder,
 };
+use serde_json::json;
 use std::{
     collections::HashMap,
     convert::Infallible,
@@ -81,6 +83,40 @@ pub fn get_all_metrics() -> HashMap<String, String> {
     get_metrics(all_metric_families)
 }
 
+/// Returns the statistics of the blocks most recently executed in parallel, the most recent
+/// first, as a JSON array.
+pub fn get_parallel_execution_stats() -> serde_json::Value {
+    RECENT_BLOCK_STATS
+        .get()
+        .into_iter()
+        .map(|record| {
+            let stats = record.stats;
+            let validation_aborts_by_index: serde_json::Map<_, _> = stats
+                .validation_aborts_by_index
+                .iter()
+                .map(|(idx, num_aborts)| (idx.to_string(), json!(num_aborts)))
+                .collect();
+            json!({
+                "block_id": record.block_id,
+                "num_txns": stats.num_txns,
+                "concurrency_level": stats.concurrency_level,
+                "num_executions": stats.num_executions,
+                "num_reexecutions": stats.num_reexecutions(),
+                "num_validations": stats.num_validations,
+                "num_validation_aborts": stats.num_validation_aborts,
+                "validation_aborts_by_index": validation_aborts_by_index,
+                "num_dependency_waits": stats.num_dependency_waits,
+                "dependency_wait_time_us": stats.dependency_wait_time.as_micros() as u64,
+                "busy_time_us": stats.busy_time.as_micros() as u64,
+                "wall_time_us": stats.wall_time.as_micros() as u64,
+                "worker_utilization": stats.worker_utilization(),
+                "conflict_rate": stats.conflict_rate(),
+                "conflict_threshold_exceeded": stats.conflict_threshold_exceeded,
+            })
+        })
+        .collect()
+}
+
 async fn serve_requests(
     req: Request<Body>,
     node_config: NodeConfig,
@@ -128,6 +164,18 @@ async fn serve_requests(
                 *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
             }
         }
+        // Expose the statistics of the blocks recently executed in parallel
+        (&Method::GET, "/parallel_execution_stats") => {
+            if node_config
+                .inspection_service
+                .expose_parallel_execution_stats
+            {
+                let encoded_stats = get_parallel_execution_stats().to_string();
+                *resp.body_mut() = Body::from(encoded_stats);
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
         _ => {
             *resp.status_mut() = StatusCode::NOT_FOUND;
         }
@@ -1,11 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::inspection_service::get_all_metrics;
+use crate::inspection_service::{get_all_metrics, get_parallel_execution_stats};
+use aptos_parallel_executor::stats::{BlockExecutionStats, RECENT_BLOCK_STATS};
 use assert_approx_eq::assert_approx_eq;
 use once_cell::sync::Lazy;
 use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
 use rusty_fork::rusty_fork_test;
+use std::{collections::BTreeMap, time::Duration};
 
 const INT_COUNTER_NAME: &str = "INT_COUNTER";
 pub static INT_COUNTER: Lazy<IntCounter> =
@@ -70,3 +72,33 @@ fn get_all_metrics_test() {
     }
 }
 }
+
+rusty_fork_test! {
+#[test]
+fn parallel_execution_stats_test() {
+    RECENT_BLOCK_STATS.record(
+        Some("block".to_string()),
+        BlockExecutionStats {
+            num_txns: 10,
+            num_executions: 12,
+            num_validation_aborts: 2,
+            validation_aborts_by_index: BTreeMap::from([(3, 2)]),
+            num_validations: 14,
+            busy_time: Duration::from_millis(2),
+            wall_time: Duration::from_millis(1),
+            concurrency_level: 4,
+            ..BlockExecutionStats::default()
+        },
+    );
+
+    let stats = get_parallel_execution_stats();
+    let blocks = stats.as_array().unwrap();
+    assert_eq!(blocks.len(), 1);
+    let block = &blocks[0];
+    assert_eq!(block["block_id"], "block");
+    assert_eq!(block["num_reexecutions"], 2);
+    assert_eq!(block["validation_aborts_by_index"]["3"], 2);
+    assert_eq!(block["wall_time_us"], 1000);
+    assert_approx_eq!(block["worker_utilization"].as_f64().unwrap(), 0.5);
+}
+}
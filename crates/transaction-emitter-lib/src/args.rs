@@ -75,6 +75,15 @@ pub struct EmitArgs {
     /// configured to be ~4x of the max achievable TPS.
     pub mempool_backlog: u64,
 
+    /// Number of accounts each worker submits transactions from. More accounts spread the load
+    /// over a bigger account pool.
+    #[clap(long, default_value = "1")]
+    pub accounts_per_worker: usize,
+
+    /// If set, caps the rate each account submits transactions at.
+    #[clap(long)]
+    pub max_txns_per_account_per_sec: Option<u64>,
+
     #[clap(long, default_value = "0", requires = "burst")]
     pub wait_millis: u64,
 
@@ -22,7 +22,7 @@ use rand_core::SeedableRng;
 use std::{
     cmp::{max, min},
     collections::HashSet,
-    num::NonZeroU64,
+    num::{NonZeroU64, NonZeroUsize},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -68,6 +68,9 @@ pub struct EmitThreadParams {
     pub wait_committed: bool,
     pub txn_expiration_time_secs: u64,
     pub check_stats_at_end: bool,
+    /// Caps the rate each account submits transactions at, so that the load is spread evenly over
+    /// the account pool rather than limited by how fast the accounts' transactions get committed.
+    pub max_txns_per_account_per_sec: Option<NonZeroU64>,
 }
 
 impl Default for EmitThreadParams {
@@ -77,6 +80,7 @@ impl Default for EmitThreadParams {
             wait_committed: true,
             txn_expiration_time_secs: 300,
             check_stats_at_end: true,
+            max_txns_per_account_per_sec: None,
         }
     }
 }
@@ -85,6 +89,7 @@ impl Default for EmitThreadParams {
 pub struct EmitJobRequest {
     rest_clients: Vec<RestClient>,
     mempool_backlog: usize,
+    accounts_per_worker: usize,
     thread_params: EmitThreadParams,
     gas_price: u64,
     invalid_transaction_ratio: usize,
@@ -98,6 +103,7 @@ impl Default for EmitJobRequest {
         Self {
             rest_clients: Vec::new(),
             mempool_backlog: 3000,
+            accounts_per_worker: 1,
             thread_params: EmitThreadParams::default(),
             gas_price: 0,
             invalid_transaction_ratio: 0,
@@ -166,6 +172,13 @@ impl EmitJobRequest {
         self
     }
 
+    /// Sets the number of accounts each worker submits transactions from, which multiplies the size
+    /// of the account pool created (or reused) for the job.
+    pub fn accounts_per_worker(mut self, accounts_per_worker: NonZeroUsize) -> Self {
+        self.accounts_per_worker = accounts_per_worker.get();
+        self
+    }
+
     pub fn reuse_accounts(mut self) -> Self {
         self.reuse_accounts = true;
         self
@@ -248,10 +261,11 @@ impl<'t> TxnEmitter<'t> {
 
     pub async fn start_job(&mut self, req: EmitJobRequest) -> Result<EmitJob> {
         let workers_per_endpoint = req.calculate_workers_per_endpoint();
-        let num_accounts = req.rest_clients.len() * workers_per_endpoint;
+        let num_workers = req.rest_clients.len() * workers_per_endpoint;
+        let num_accounts = num_workers * req.accounts_per_worker;
         info!(
             "Will use {} workers per endpoint for a total of {} endpoint clients",
-            workers_per_endpoint, num_accounts
+            workers_per_endpoint, num_workers
         );
         info!("Will create a total of {} accounts", num_accounts);
         let mut account_minter = AccountMinter::new(
@@ -291,7 +305,7 @@ impl<'t> TxnEmitter<'t> {
         };
         for client in req.rest_clients {
             for _ in 0..workers_per_endpoint {
-                let accounts = (&mut all_accounts).take(1).collect();
+                let accounts = (&mut all_accounts).take(req.accounts_per_worker).collect();
                 let all_addresses = all_addresses.clone();
                 let stop = stop.clone();
                 let params = req.thread_params.clone();
@@ -3,8 +3,8 @@
 
 use crate::{
     emitter::{
-        stats::StatsAccumulator, wait_for_accounts_sequence, MAX_TXN_BATCH_SIZE,
-        TRANSACTIONS_PER_ACCOUNT, TXN_EXPIRATION_SECONDS,
+        query_sequence_numbers, stats::StatsAccumulator, wait_for_accounts_sequence,
+        MAX_TXN_BATCH_SIZE, TRANSACTIONS_PER_ACCOUNT, TXN_EXPIRATION_SECONDS,
     },
     transaction_generator::TransactionGenerator,
     EmitThreadParams,
@@ -24,10 +24,11 @@ use core::{
     time::Duration,
 };
 use futures::future::try_join_all;
+use itertools::zip;
 use rand::seq::IteratorRandom;
 use rand::Rng;
 use std::sync::atomic::AtomicU64;
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 use tokio::time::sleep;
 
 #[derive(Debug)]
@@ -86,7 +87,16 @@ impl SubmissionWorker {
             TXN_EXPIRATION_SECONDS,
         ));
 
-        let wait_duration = Duration::from_millis(self.params.wait_millis);
+        let mut wait_duration = Duration::from_millis(self.params.wait_millis);
+        if let Some(max_txns_per_account_per_sec) = self.params.max_txns_per_account_per_sec {
+            // Every account submits TRANSACTIONS_PER_ACCOUNT transactions per round.
+            wait_duration = max(
+                wait_duration,
+                Duration::from_secs_f64(
+                    TRANSACTIONS_PER_ACCOUNT as f64 / max_txns_per_account_per_sec.get() as f64,
+                ),
+            );
+        }
 
         let start_time = Instant::now();
         let mut total_num_requests = 0;
@@ -220,6 +230,44 @@ impl SubmissionWorker {
                         self.client, uncommitted
                     )
                 );
+                self.resync_sequence_numbers(&uncommitted).await;
+            }
+        }
+    }
+
+    /// Resets the local sequence numbers of the accounts to the ones on chain. The local ones drift
+    /// ahead when transactions are rejected by mempool or expire, after which none of the
+    /// transactions of the account would get committed.
+    async fn resync_sequence_numbers(&mut self, addresses: &HashSet<AccountAddress>) {
+        let accounts: Vec<_> = self
+            .accounts
+            .iter_mut()
+            .filter(|account| addresses.contains(&account.address()))
+            .collect();
+        let addresses: Vec<_> = accounts.iter().map(|account| account.address()).collect();
+        match query_sequence_numbers(&self.client, &addresses).await {
+            Ok(sequence_numbers) => {
+                for (account, sequence_number) in zip(accounts, sequence_numbers) {
+                    if account.sequence_number() != sequence_number {
+                        debug!(
+                            "[{:?}] Resetting sequence number of {} from {} to {}",
+                            self.client,
+                            account.address(),
+                            account.sequence_number(),
+                            sequence_number
+                        );
+                        *account.sequence_number_mut() = sequence_number;
+                    }
+                }
+            }
+            Err(e) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(
+                        "[{:?}] Failed to query sequence numbers to resync: {:?}",
+                        self.client, e
+                    )
+                );
             }
         }
     }
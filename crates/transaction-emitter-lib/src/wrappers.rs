@@ -14,6 +14,7 @@ use rand_core::{OsRng, SeedableRng};
 use std::convert::TryInto;
 use std::{
     cmp::{max, min},
+    num::NonZeroU64,
     time::Duration,
 };
 
@@ -37,6 +38,7 @@ pub async fn emit_transactions_with_cluster(
         wait_committed: !args.burst,
         txn_expiration_time_secs: args.txn_expiration_time_secs,
         check_stats_at_end: !args.do_not_check_stats_at_end,
+        max_txns_per_account_per_sec: args.max_txns_per_account_per_sec.and_then(NonZeroU64::new),
     };
     let duration = Duration::from_secs(args.duration);
     let client = cluster.random_instance().rest_client();
@@ -53,6 +55,7 @@ pub async fn emit_transactions_with_cluster(
         EmitJobRequest::new(cluster.all_instances().map(Instance::rest_client).collect())
             .thread_params(thread_params)
             .mempool_backlog(args.mempool_backlog.try_into().unwrap())
+            .accounts_per_worker(args.accounts_per_worker.try_into()?)
             .invalid_transaction_ratio(args.invalid_tx)
             .transaction_type(args.transaction_type)
             .duration(duration)
@@ -31,6 +31,7 @@ BINS=(
     db-backup-verify
     db-bootstrapper
     db-restore
+    db-snapshot
     forge
     transaction-emitter
 )
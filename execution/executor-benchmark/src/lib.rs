@@ -13,7 +13,8 @@ use crate::{
     transaction_generator::TransactionGenerator,
 };
 use aptos_config::config::{
-    NodeConfig, PrunerConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE,
+    NodeConfig, PrunerConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG,
+    TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE,
 };
 use aptos_jellyfish_merkle::metrics::{
     APTOS_JELLYFISH_INTERNAL_ENCODED_BYTES, APTOS_JELLYFISH_LEAF_ENCODED_BYTES,
@@ -36,6 +37,7 @@ pub fn init_db_and_executor(config: &NodeConfig) -> (DbReaderWriter, BlockExecut
             RocksdbConfigs::default(),
             false,
             config.storage.target_snapshot_size,
+            config.storage.target_snapshot_interval_in_versions,
         )
         .expect("DB should open."),
     );
@@ -59,6 +61,7 @@ fn create_checkpoint(source_dir: impl AsRef<Path>, checkpoint_dir: impl AsRef<Pa
         RocksdbConfigs::default(),
         false,
         TARGET_SNAPSHOT_SIZE,
+        TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
     )
     .expect("db open failure.")
     .create_checkpoint(checkpoint_dir.as_ref())
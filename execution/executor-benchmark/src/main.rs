@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::{LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig};
+use aptos_config::config::{
+    LedgerPrunerConfig, PrunerConfig, PrunerScheduleConfig, StateMerklePrunerConfig,
+};
 use aptos_secure_push_metrics::MetricsPusher;
 use aptos_vm::AptosVM;
 use std::path::PathBuf;
@@ -39,6 +41,7 @@ impl PrunerOpt {
                 prune_window: self.state_prune_window,
                 batch_size: self.state_store_pruning_batch_size,
                 user_pruning_window_offset: 0,
+                max_nodes_to_prune_per_second: 0,
             },
             ledger_pruner_config: LedgerPrunerConfig {
                 enable: self.enable_ledger_pruner,
@@ -46,6 +49,8 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            ignore_backup_progress: false,
+            schedule: PrunerScheduleConfig::default(),
         }
     }
 }
@@ -107,6 +107,17 @@ impl ApplyChunkOutput {
                     matches!(o.status(), TransactionStatus::Keep(_))
                 });
 
+        // Transactions can also be retried in the middle of a block, e.g. when the block reached
+        // its gas limit.
+        let (retried, to_discard): (Vec<_>, Vec<_>) = to_discard
+            .into_iter()
+            .partition(|(_, o)| matches!(o.status(), TransactionStatus::Retry));
+        let to_retry = retried
+            .into_iter()
+            .map(|(t, _)| t)
+            .chain(to_retry)
+            .collect();
+
         // Sanity check transactions with the Discard status:
         let to_discard = to_discard
             .into_iter()
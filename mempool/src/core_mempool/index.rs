@@ -3,13 +3,7 @@
 
 /// This module provides various indexes used by Mempool.
 use crate::core_mempool::transaction::{MempoolTransaction, SequenceInfo, TimelineState};
-use crate::{
-    counters,
-    logging::{LogEntry, LogSchema},
-};
-use aptos_logger::prelude::*;
 use aptos_types::account_address::AccountAddress;
-use rand::seq::SliceRandom;
 use std::{
     cmp::Ordering,
     collections::{btree_set::Iter, BTreeMap, BTreeSet, HashMap},
@@ -257,89 +251,110 @@ impl TimelineIndex {
 /// ParkingLotIndex keeps track of "not_ready" transactions, e.g., transactions that
 /// can't be included in the next block because their sequence number is too high.
 /// We keep a separate index to be able to efficiently evict them when Mempool is full.
+///
+/// Accounts with parked transactions hold more transactions than can go in the next block, so
+/// eviction picks among them: the parked transaction with the highest sequence number of each
+/// account is a candidate, and the candidate paying the lowest gas unit price is evicted first.
+/// Evicting from the end of an account's transactions keeps the rest of them in sequence.
 pub struct ParkingLotIndex {
     // DS invariants:
     // 1. for each entry (account, txns) in `data`, `txns` is never empty
-    // 2. for all accounts, data.get(account_indices.get(`account`)) == (account, sequence numbers of account's txns)
-    data: Vec<(AccountAddress, BTreeSet<u64>)>,
-    account_indices: HashMap<AccountAddress, usize>,
+    // 2. `eviction_candidates` holds exactly one key per account in `data`, made of the gas unit
+    // price and sequence number of the last entry of the account's txns
+    data: HashMap<AccountAddress, BTreeMap<u64, u64>>,
+    eviction_candidates: BTreeSet<EvictionKey>,
     size: usize,
 }
 
+/// Orders eviction candidates by gas unit price, lowest first.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+struct EvictionKey {
+    gas_unit_price: u64,
+    address: AccountAddress,
+    sequence_number: u64,
+}
+
 impl ParkingLotIndex {
     pub(crate) fn new() -> Self {
         Self {
-            data: vec![],
-            account_indices: HashMap::new(),
+            data: HashMap::new(),
+            eviction_candidates: BTreeSet::new(),
             size: 0,
         }
     }
 
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        let sender = &txn.txn.sender();
+        let sender = txn.txn.sender();
         let sequence_number = txn.txn.sequence_number();
-        let is_new_entry = match self.account_indices.get(sender) {
-            Some(index) => {
-                if let Some((_account, seq_nums)) = self.data.get_mut(*index) {
-                    seq_nums.insert(sequence_number)
-                } else {
-                    counters::CORE_MEMPOOL_INVARIANT_VIOLATION_COUNT.inc();
-                    error!(
-                        LogSchema::new(LogEntry::InvariantViolated),
-                        "Parking lot invariant violated: for account {}, account index exists but missing entry in data",
-                        sender
-                    );
-                    return;
-                }
-            }
-            None => {
-                let seq_nums = [sequence_number].iter().cloned().collect::<BTreeSet<_>>();
-                self.data.push((*sender, seq_nums));
-                self.account_indices.insert(*sender, self.data.len() - 1);
-                true
-            }
-        };
-        if is_new_entry {
+        let txns = self.data.entry(sender).or_insert_with(BTreeMap::new);
+        let last_txn = Self::last_txn(sender, txns);
+        if txns.insert(sequence_number, txn.get_gas_price()).is_none() {
             self.size += 1;
         }
+        Self::update_candidate(
+            &mut self.eviction_candidates,
+            last_txn,
+            Self::last_txn(sender, txns),
+        );
     }
 
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        let sender = &txn.txn.sender();
-        if let Some(index) = self.account_indices.get(sender).cloned() {
-            if let Some((_account, txns)) = self.data.get_mut(index) {
-                if txns.remove(&txn.txn.sequence_number()) {
-                    self.size -= 1;
-                }
-
-                // maintain DS invariant
-                if txns.is_empty() {
-                    // remove account with no more txns
-                    self.data.swap_remove(index);
-                    self.account_indices.remove(sender);
-
-                    // update DS for account that was swapped in `swap_remove`
-                    if let Some((swapped_account, _)) = self.data.get(index) {
-                        self.account_indices.insert(*swapped_account, index);
-                    }
-                }
+        let sender = txn.txn.sender();
+        if let Some(txns) = self.data.get_mut(&sender) {
+            let last_txn = Self::last_txn(sender, txns);
+            if txns.remove(&txn.txn.sequence_number()).is_some() {
+                self.size -= 1;
+            }
+            let new_last_txn = Self::last_txn(sender, txns);
+
+            // maintain DS invariant
+            if txns.is_empty() {
+                // remove account with no more txns
+                self.data.remove(&sender);
             }
+            Self::update_candidate(&mut self.eviction_candidates, last_txn, new_last_txn);
+        }
+    }
+
+    fn last_txn(address: AccountAddress, txns: &BTreeMap<u64, u64>) -> Option<EvictionKey> {
+        txns.iter()
+            .next_back()
+            .map(|(sequence_number, gas_unit_price)| EvictionKey {
+                gas_unit_price: *gas_unit_price,
+                address,
+                sequence_number: *sequence_number,
+            })
+    }
+
+    fn update_candidate(
+        eviction_candidates: &mut BTreeSet<EvictionKey>,
+        old: Option<EvictionKey>,
+        new: Option<EvictionKey>,
+    ) {
+        if old == new {
+            return;
+        }
+        if let Some(old) = old {
+            eviction_candidates.remove(&old);
+        }
+        if let Some(new) = new {
+            eviction_candidates.insert(new);
         }
     }
 
     pub(crate) fn contains(&self, account: &AccountAddress, seq_num: &u64) -> bool {
-        self.account_indices
+        self.data
             .get(account)
-            .and_then(|idx| self.data.get(*idx))
-            .map_or(false, |(_account, txns)| txns.contains(seq_num))
+            .map_or(false, |txns| txns.contains_key(seq_num))
     }
 
-    /// Returns a random "non-ready" transaction (with highest sequence number for that account).
+    /// Returns the "non-ready" transaction to evict first: the one paying the lowest gas unit
+    /// price among the parked transactions with the highest sequence number of their account.
     pub(crate) fn get_poppable(&self) -> Option<TxnPointer> {
-        let mut rng = rand::thread_rng();
-        self.data
-            .choose(&mut rng)
-            .and_then(|(sender, txns)| txns.iter().rev().next().map(|seq_num| (*sender, *seq_num)))
+        self.eviction_candidates
+            .iter()
+            .next()
+            .map(|key| (key.address, key.sequence_number))
     }
 
     pub(crate) fn size(&self) -> usize {
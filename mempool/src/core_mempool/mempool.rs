@@ -6,7 +6,7 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{EvictionReason, MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
     },
@@ -123,6 +123,18 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Why the transaction with this hash was evicted, if it recently was.
+    pub(crate) fn get_eviction_reason(&self, hash: HashValue) -> Option<EvictionReason> {
+        self.transactions.get_eviction_reason(&hash)
+    }
+
+    pub(crate) fn get_account_transactions(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<SignedTransaction> {
+        self.transactions.get_account_transactions(address)
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -294,6 +306,16 @@ impl Mempool {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
 
+    /// The number of transactions in mempool.
+    pub(crate) fn size(&self) -> usize {
+        self.transactions.size()
+    }
+
+    /// The number of transactions mempool may hold.
+    pub(crate) fn capacity(&self) -> usize {
+        self.transactions.capacity()
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
@@ -1,13 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::counters;
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress, account_config::AccountSequenceInfo,
     transaction::SignedTransaction,
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{fmt, time::Duration};
 
 #[derive(Clone, Debug)]
 pub struct MempoolTransaction {
@@ -66,3 +67,39 @@ pub struct SequenceInfo {
     pub transaction_sequence_number: u64,
     pub account_sequence_number_type: AccountSequenceInfo,
 }
+
+/// Why a transaction was removed from mempool before it was committed or expired.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EvictionReason {
+    /// Mempool was full when a transaction ready for broadcast arrived, and this one was the
+    /// parked transaction paying the lowest gas unit price.
+    MempoolFull,
+    /// The sender submitted the same transaction again with a higher gas unit price.
+    ReplacedByHigherGasPrice,
+}
+
+impl EvictionReason {
+    /// A short name of the reason, which labels the eviction counter.
+    pub fn name(self) -> &'static str {
+        match self {
+            EvictionReason::MempoolFull => counters::EVICTION_MEMPOOL_FULL_LABEL,
+            EvictionReason::ReplacedByHigherGasPrice => counters::EVICTION_REPLACED_LABEL,
+        }
+    }
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvictionReason::MempoolFull => write!(
+                f,
+                "mempool was full and it paid the lowest gas unit price of the transactions that \
+                 were not ready for broadcast"
+            ),
+            EvictionReason::ReplacedByHigherGasPrice => write!(
+                f,
+                "it was replaced by the same transaction with a higher gas unit price"
+            ),
+        }
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
             TimelineIndex,
         },
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{EvictionReason, MempoolTransaction, TimelineState},
         ttl_cache::TtlCache,
     },
     counters,
@@ -52,6 +52,11 @@ pub struct TransactionStore {
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
 
+    // Why recently evicted transactions were evicted, by committed hash, so that clients looking
+    // them up learn they won't be committed. Entries are kept for as long as the evicted
+    // transactions could have stayed in mempool.
+    evicted_txns: TtlCache<HashValue, EvictionReason>,
+
     // configuration
     capacity: usize,
     capacity_per_user: usize,
@@ -72,6 +77,10 @@ impl TransactionStore {
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
+            evicted_txns: TtlCache::new(
+                config.capacity,
+                Duration::from_secs(config.system_transaction_timeout_secs),
+            ),
 
             // configuration
             capacity: config.capacity,
@@ -102,6 +111,22 @@ impl TransactionStore {
         }
     }
 
+    /// Why the transaction with this hash was evicted, if it recently was.
+    pub(crate) fn get_eviction_reason(&self, hash: &HashValue) -> Option<EvictionReason> {
+        self.evicted_txns.get(hash).copied()
+    }
+
+    /// Fetch the transactions of an account, ordered by sequence number.
+    pub(crate) fn get_account_transactions(
+        &self,
+        address: &AccountAddress,
+    ) -> Vec<SignedTransaction> {
+        self.transactions
+            .get(address)
+            .map(|txns| txns.values().map(|txn| txn.txn.clone()).collect())
+            .unwrap_or_default()
+    }
+
     /// Fetch mempool transaction by account address + sequence_number.
     pub(crate) fn get_mempool_txn(
         &self,
@@ -146,7 +171,7 @@ impl TransactionStore {
                 } else if current_version.txn.gas_unit_price() < txn.get_gas_price() {
                     // Update txn if gas unit price is a larger value than before
                     if let Some(txn) = txns.remove(&sequence_number.transaction_sequence_number) {
-                        self.index_remove(&txn);
+                        self.evict(&txn, EvictionReason::ReplacedByHigherGasPrice);
                     };
                 } else if current_version.get_gas_price() > txn.get_gas_price() {
                     return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
@@ -194,10 +219,12 @@ impl TransactionStore {
             }
 
             // insert into storage and other indexes
+            let hash = txn.get_committed_hash();
+            self.evicted_txns.remove(&hash);
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
             self.hash_index.insert(
-                txn.get_committed_hash(),
+                hash,
                 (
                     txn.get_sender(),
                     sequence_number.transaction_sequence_number,
@@ -238,7 +265,8 @@ impl TransactionStore {
     }
 
     /// Checks if Mempool is full.
-    /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
+    /// If it's full, tries to free some space by evicting transactions from the ParkingLot, lowest
+    /// gas unit price first (see `ParkingLotIndex`).
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
@@ -259,9 +287,10 @@ impl TransactionStore {
                         LogSchema::new(LogEntry::MempoolFullEvictedTxn).txns(TxnsLog::new_txn(
                             txn.get_sender(),
                             txn.sequence_info.transaction_sequence_number
-                        ))
+                        )),
+                        gas_unit_price = txn.get_gas_price(),
                     );
-                    self.index_remove(&txn);
+                    self.evict(&txn, EvictionReason::MempoolFull);
                 }
             }
         }
@@ -416,6 +445,16 @@ impl TransactionStore {
         }
     }
 
+    /// Removes a transaction that was neither committed nor expired from all indexes, and
+    /// remembers why it was.
+    fn evict(&mut self, txn: &MempoolTransaction, reason: EvictionReason) {
+        counters::CORE_MEMPOOL_EVICTED_TXNS
+            .with_label_values(&[reason.name()])
+            .inc();
+        self.evicted_txns.insert(txn.get_committed_hash(), reason);
+        self.index_remove(txn);
+    }
+
     /// Removes transaction from all indexes.
     fn index_remove(&mut self, txn: &MempoolTransaction) {
         counters::CORE_MEMPOOL_REMOVED_TXNS.inc();
@@ -473,6 +512,7 @@ impl TransactionStore {
         let now = aptos_infallible::duration_since_epoch();
 
         self.gc(now, true, metrics_cache);
+        self.evicted_txns.gc(SystemTime::now());
     }
 
     /// Garbage collect old transactions based on client-specified expiration time.
@@ -580,6 +620,15 @@ impl TransactionStore {
         txns_log
     }
 
+    /// The number of transactions in the store.
+    pub(crate) fn size(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
@@ -130,13 +130,13 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetAccountTransactions,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
     BroadcastTransaction,
     BroadcastACK,
     ReceiveACK,
-    InvariantViolated,
     AddTxn,
     RemoveTxn,
     MempoolFullEvictedTxn,
@@ -147,6 +147,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    SubmissionFilter,
 }
 
 #[derive(Clone, Copy, Serialize)]
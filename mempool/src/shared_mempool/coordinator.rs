@@ -8,6 +8,7 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     network::{MempoolNetworkEvents, MempoolSyncMsg},
     shared_mempool::{
+        submission_filter::SubmissionFilter,
         tasks,
         tasks::process_committed_transactions,
         types::{notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification},
@@ -36,7 +37,7 @@ use tokio::{runtime::Handle, time::interval};
 use tokio_stream::wrappers::IntervalStream;
 use vm_validator::vm_validator::TransactionValidation;
 
-use super::types::MempoolClientRequest;
+use super::types::{MempoolClientRequest, MempoolSize};
 
 /// Coordinator that handles inbound network events and outbound txn broadcasts.
 pub(crate) async fn coordinator<V>(
@@ -148,6 +149,57 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetTransactionEvictionReason(hash, callback) => {
+            let reason = smp.mempool.lock().get_eviction_reason(hash);
+            if callback.send(reason).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        }
+        MempoolClientRequest::GetAccountTransactions(address, callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_account_transactions(
+                    smp.clone(),
+                    address,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::UpdateSubmissionFilter(config, callback) => {
+            let result = SubmissionFilter::new(&config).map(|submission_filter| {
+                *smp.submission_filter.write() = submission_filter;
+                info!(
+                    LogSchema::event_log(LogEntry::SubmissionFilter, LogEvent::Success),
+                    "Updated submission filter: {:?}", config
+                );
+            });
+            if callback.send(result).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        }
+        MempoolClientRequest::GetSize(callback) => {
+            let size = {
+                let mempool = smp.mempool.lock();
+                MempoolSize {
+                    num_txns: mempool.size(),
+                    capacity: mempool.capacity(),
+                }
+            };
+            if callback.send(size).is_err() {
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        }
     }
 }
 
@@ -3,6 +3,7 @@
 
 pub mod network;
 mod runtime;
+pub(crate) mod submission_filter;
 pub(crate) mod types;
 pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
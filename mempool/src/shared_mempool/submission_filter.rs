@@ -0,0 +1,94 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The rules of `SubmissionFilterConfig`, checked against every transaction submitted to mempool
+//! before it is validated, so that operators can reject transactions during an incident.
+
+use anyhow::{format_err, Result};
+use aptos_config::config::SubmissionFilterConfig;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct SubmissionFilter {
+    denied_senders: HashSet<AccountAddress>,
+    /// Modules calls to any function of which are rejected, by address and name.
+    denied_modules: HashSet<(AccountAddress, String)>,
+    /// Functions calls to which are rejected, by module address, module name and name.
+    denied_functions: HashSet<(AccountAddress, String, String)>,
+    max_payload_size_bytes: Option<u64>,
+}
+
+impl SubmissionFilter {
+    pub fn new(config: &SubmissionFilterConfig) -> Result<Self> {
+        let mut filter = Self {
+            denied_senders: config.denied_senders.iter().cloned().collect(),
+            max_payload_size_bytes: config.max_payload_size_bytes,
+            ..Self::default()
+        };
+        for function in &config.denied_functions {
+            let invalid = || {
+                format_err!(
+                    "Invalid denied function {:?}, expected `<address>::<module>` or `<address>::<module>::<function>`",
+                    function
+                )
+            };
+            let parts: Vec<_> = function.split("::").collect();
+            let address = AccountAddress::from_hex_literal(parts[0]).map_err(|_| invalid())?;
+            if parts[1..].iter().any(|part| part.is_empty()) {
+                return Err(invalid());
+            }
+            match parts[1..] {
+                [module] => {
+                    filter.denied_modules.insert((address, module.to_string()));
+                }
+                [module, name] => {
+                    filter
+                        .denied_functions
+                        .insert((address, module.to_string(), name.to_string()));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Returns why the transaction is rejected, if it matches any rule.
+    pub fn check(&self, txn: &SignedTransaction) -> Option<String> {
+        if self.denied_senders.contains(&txn.sender()) {
+            return Some(format!("sender {} is denied", txn.sender()));
+        }
+        if let TransactionPayload::ScriptFunction(script_function) = txn.payload() {
+            let module = script_function.module();
+            let module_key = (*module.address(), module.name().to_string());
+            if self.denied_modules.contains(&module_key) {
+                return Some(format!("calls to module {} are denied", module));
+            }
+            let function_key = (
+                module_key.0,
+                module_key.1,
+                script_function.function().to_string(),
+            );
+            if self.denied_functions.contains(&function_key) {
+                return Some(format!(
+                    "calls to function {}::{} are denied",
+                    module,
+                    script_function.function()
+                ));
+            }
+        }
+        if let Some(max_payload_size_bytes) = self.max_payload_size_bytes {
+            let payload_size = bcs::serialized_size(txn.payload()).unwrap_or(usize::MAX) as u64;
+            if payload_size > max_payload_size_bytes {
+                return Some(format!(
+                    "payload of {} bytes is larger than the maximum of {} bytes",
+                    payload_size, max_payload_size_bytes
+                ));
+            }
+        }
+        None
+    }
+}
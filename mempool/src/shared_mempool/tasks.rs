@@ -6,7 +6,7 @@ use crate::thread_pool::IO_POOL;
 use crate::{
     core_mempool::{CoreMempool, TimelineState, TxnPointer},
     counters,
-    logging::{LogEntry, LogEvent, LogSchema},
+    logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::{BroadcastError, MempoolSyncMsg},
     shared_mempool::types::{
         notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
@@ -21,6 +21,7 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics_core::HistogramTimer;
 use aptos_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
@@ -151,6 +152,26 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+pub(crate) async fn process_client_get_account_transactions<V>(
+    smp: SharedMempool<V>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Vec<SignedTransaction>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let txns = smp.mempool.lock().get_account_transactions(&address);
+
+    if callback.send(txns).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetAccountTransactions,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -242,6 +263,37 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject transactions matching the submission filter before spending any work on them.
+    let transactions: Vec<_> = {
+        let submission_filter = smp.submission_filter.read();
+        transactions
+            .into_iter()
+            .filter_map(|t| match submission_filter.check(&t) {
+                None => Some(t),
+                Some(reason) => {
+                    warn!(
+                        LogSchema::new(LogEntry::SubmissionFilter)
+                            .txns(TxnsLog::new_txn(t.sender(), t.sequence_number())),
+                        "Transaction rejected by submission filter: {}", reason
+                    );
+                    counters::SUBMISSION_FILTER_REJECTED_COUNT.inc();
+                    statuses.push((
+                        t,
+                        (
+                            MempoolStatus::new(MempoolStatusCode::RejectedByFilter)
+                                .with_message(reason),
+                            None,
+                        ),
+                    ));
+                    None
+                }
+            })
+            .collect()
+    };
+    if transactions.is_empty() {
+        return statuses;
+    }
+
     let start_storage_read = Instant::now();
     let state_view = smp
         .db
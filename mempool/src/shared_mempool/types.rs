@@ -3,18 +3,20 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
-    shared_mempool::network::MempoolNetworkSender,
+    core_mempool::{CoreMempool, EvictionReason},
+    network::MempoolNetworkInterface,
+    shared_mempool::{network::MempoolNetworkSender, submission_filter::SubmissionFilter},
 };
 use anyhow::Result;
 use aptos_config::{
-    config::{MempoolConfig, RoleType},
+    config::{MempoolConfig, RoleType, SubmissionFilterConfig},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_types::{
-    mempool_status::MempoolStatus, transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress, mempool_status::MempoolStatus, transaction::SignedTransaction,
+    vm_status::DiscardedVMStatus,
 };
 use consensus_types::common::TransactionSummary;
 use futures::{
@@ -48,6 +50,8 @@ where
     pub db: Arc<dyn DbReader>,
     pub validator: Arc<RwLock<V>>,
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
+    /// Starts with the rules in `config`, which are replaced when reloaded.
+    pub submission_filter: Arc<RwLock<SubmissionFilter>>,
 }
 
 impl<V: TransactionValidation + 'static> SharedMempool<V> {
@@ -67,6 +71,8 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             role,
             config.clone(),
         );
+        let submission_filter = SubmissionFilter::new(&config.submission_filter)
+            .expect("Invalid mempool submission filter config");
         SharedMempool {
             mempool,
             config,
@@ -74,6 +80,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             db,
             validator,
             subscribers,
+            submission_filter: Arc::new(RwLock::new(submission_filter)),
         }
     }
 
@@ -204,6 +211,23 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Gets why the transaction with the hash was evicted from mempool before it was committed
+    /// or expired, if it recently was.
+    GetTransactionEvictionReason(HashValue, oneshot::Sender<Option<EvictionReason>>),
+    /// Gets the transactions of an account in mempool, ordered by sequence number.
+    GetAccountTransactions(AccountAddress, oneshot::Sender<Vec<SignedTransaction>>),
+    /// Replaces the rules transactions submitted to mempool are rejected by, unless they are
+    /// invalid.
+    UpdateSubmissionFilter(SubmissionFilterConfig, oneshot::Sender<Result<()>>),
+    /// Gets the number of transactions in mempool and how many it may hold.
+    GetSize(oneshot::Sender<MempoolSize>),
+}
+
+/// The number of transactions in mempool, out of the number it may hold.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MempoolSize {
+    pub num_txns: usize,
+    pub capacity: usize,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
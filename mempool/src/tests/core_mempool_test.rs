@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TtlCache},
+    core_mempool::{CoreMempool, EvictionReason, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
         TestTransaction,
@@ -10,7 +10,10 @@ use crate::{
 };
 use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
-use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
+use aptos_types::{
+    account_address::AccountAddress, account_config::AccountSequenceInfo,
+    transaction::SignedTransaction,
+};
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
@@ -513,6 +516,51 @@ fn test_parking_lot_eviction() {
     assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
 }
 
+#[test]
+fn test_parking_lot_evicts_lowest_gas_price_first() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 5;
+    let mut pool = CoreMempool::new(&config);
+    // Account 1 has txns 5 and 6 parked, account 2 has txn 5 parked.
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 5, 1),
+            TestTransaction::new(1, 6, 10),
+            TestTransaction::new(2, 0, 1),
+            TestTransaction::new(2, 5, 2),
+        ],
+    );
+    let hash = |index: usize| txns[index].clone().committed_hash();
+
+    // Only the last parked txn of each account is a candidate, so account 2's txn is evicted
+    // although account 1's txn 5 pays less.
+    add_txn(&mut pool, TestTransaction::new(3, 0, 1)).unwrap();
+    assert!(pool.get_by_hash(hash(4)).is_none());
+    assert_eq!(
+        pool.get_eviction_reason(hash(4)),
+        Some(EvictionReason::MempoolFull)
+    );
+    assert!(pool.get_by_hash(hash(1)).is_some());
+
+    // Account 1's txns are then evicted from the last one.
+    add_txn(&mut pool, TestTransaction::new(3, 1, 1)).unwrap();
+    assert!(pool.get_by_hash(hash(2)).is_none());
+    assert!(pool.get_by_hash(hash(1)).is_some());
+    add_txn(&mut pool, TestTransaction::new(3, 2, 1)).unwrap();
+    assert!(pool.get_by_hash(hash(1)).is_none());
+    assert_eq!(
+        pool.get_eviction_reason(hash(1)),
+        Some(EvictionReason::MempoolFull)
+    );
+
+    // Ready txns are never evicted.
+    assert!(add_txn(&mut pool, TestTransaction::new(3, 3, 1)).is_err());
+    assert_eq!(pool.get_eviction_reason(hash(0)), None);
+    assert_eq!(pool.get_parking_lot_size(), 0);
+}
+
 #[test]
 fn test_parking_lot_evict_only_for_ready_txn_insertion() {
     let mut config = NodeConfig::random();
@@ -643,6 +691,39 @@ fn test_get_transaction_by_hash() {
     assert!(ret.is_none());
 }
 
+#[test]
+fn test_get_account_transactions() {
+    let mut pool = setup_mempool().0;
+    let db_sequence_number = 10;
+    let txns: Vec<_> = vec![12, 10]
+        .into_iter()
+        .map(|sequence_number| {
+            TestTransaction::new(0, sequence_number, 1).make_signed_transaction()
+        })
+        .collect();
+    for txn in &txns {
+        pool.add_txn(
+            txn.clone(),
+            1,
+            AccountSequenceInfo::Sequential(db_sequence_number),
+            TimelineState::NotReady,
+        );
+    }
+    let other = TestTransaction::new(1, db_sequence_number, 1).make_signed_transaction();
+    pool.add_txn(
+        other,
+        1,
+        AccountSequenceInfo::Sequential(db_sequence_number),
+        TimelineState::NotReady,
+    );
+
+    let ret = pool.get_account_transactions(&txns[0].sender());
+    assert_eq!(ret, vec![txns[1].clone(), txns[0].clone()]);
+
+    let ret = pool.get_account_transactions(&AccountAddress::random());
+    assert!(ret.is_empty());
+}
+
 #[test]
 fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let mut pool = setup_mempool().0;
@@ -668,7 +749,12 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
 
     let txn_by_old_hash = pool.get_by_hash(hash);
     assert!(txn_by_old_hash.is_none());
+    assert_eq!(
+        pool.get_eviction_reason(hash),
+        Some(EvictionReason::ReplacedByHigherGasPrice)
+    );
 
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
+    assert_eq!(pool.get_eviction_reason(new_txn_hash), None);
 }
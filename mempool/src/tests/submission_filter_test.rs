@@ -0,0 +1,62 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{shared_mempool::submission_filter::SubmissionFilter, tests::common::TestTransaction};
+use aptos_config::config::SubmissionFilterConfig;
+
+#[test]
+fn test_invalid_denied_functions() {
+    for function in [
+        "coin::transfer",
+        "0x1",
+        "0x1::",
+        "0x1::coin::",
+        "0x1::coin::transfer::extra",
+    ] {
+        let config = SubmissionFilterConfig {
+            denied_functions: vec![function.to_string()],
+            ..SubmissionFilterConfig::default()
+        };
+        assert!(SubmissionFilter::new(&config).is_err(), "{}", function);
+    }
+
+    let config = SubmissionFilterConfig {
+        denied_functions: vec!["0x1::coin".to_string(), "0x1::coin::transfer".to_string()],
+        ..SubmissionFilterConfig::default()
+    };
+    assert!(SubmissionFilter::new(&config).is_ok());
+}
+
+#[test]
+fn test_denied_sender() {
+    let config = SubmissionFilterConfig {
+        denied_senders: vec![TestTransaction::get_address(1)],
+        ..SubmissionFilterConfig::default()
+    };
+    let filter = SubmissionFilter::new(&config).unwrap();
+
+    let txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    assert!(filter.check(&txn).is_some());
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    assert!(filter.check(&txn).is_none());
+}
+
+#[test]
+fn test_max_payload_size() {
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let payload_size = bcs::serialized_size(txn.payload()).unwrap() as u64;
+
+    let mut config = SubmissionFilterConfig {
+        max_payload_size_bytes: Some(payload_size),
+        ..SubmissionFilterConfig::default()
+    };
+    assert!(SubmissionFilter::new(&config)
+        .unwrap()
+        .check(&txn)
+        .is_none());
+    config.max_payload_size_bytes = Some(payload_size - 1);
+    assert!(SubmissionFilter::new(&config)
+        .unwrap()
+        .check(&txn)
+        .is_some());
+}
@@ -4,6 +4,7 @@
 pub mod convert;
 pub mod metrics;
 pub mod runtime;
+pub mod subscription;
 
 #[cfg(test)]
 pub(crate) mod tests;
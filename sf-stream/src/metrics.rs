@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_int_counter, IntCounter};
+use aptos_metrics_core::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
 use once_cell::sync::Lazy;
 
 pub static TRANSACTIONS_SENT: Lazy<IntCounter> = Lazy::new(|| {
@@ -19,3 +22,28 @@ pub static BLOCKS_SENT: Lazy<IntCounter> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static SUBSCRIPTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_sf_stream_subscriptions",
+        "Event subscriptions being served"
+    )
+    .unwrap()
+});
+
+pub static SUBSCRIPTIONS_REFUSED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_sf_stream_subscriptions_refused_count",
+        "Event subscriptions refused as the maximum number of subscriptions were being served",
+    )
+    .unwrap()
+});
+
+pub static SUBSCRIPTION_EVENTS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_sf_stream_subscription_events_sent_count",
+        "Events sent to subscriptions, by format",
+        &["format"]
+    )
+    .unwrap()
+});
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics;
+use crate::{metrics, subscription};
 use aptos_protos::extractor::v1 as extractor;
 
 use crate::convert::convert_transaction;
@@ -22,6 +22,7 @@ use tokio::runtime::{Builder, Runtime};
 use tokio::time::sleep;
 
 /// Creates a runtime which creates a thread pool which pushes firehose of block protobuf to SF endpoint
+/// and serves event subscriptions, if either is enabled
 /// Returns corresponding Tokio runtime
 pub fn bootstrap(
     config: &NodeConfig,
@@ -29,7 +30,7 @@ pub fn bootstrap(
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
 ) -> Option<anyhow::Result<Runtime>> {
-    if !config.sf_stream.enabled {
+    if !config.sf_stream.enabled && config.sf_stream.subscription_address.is_none() {
         return None;
     }
 
@@ -44,6 +45,16 @@ pub fn bootstrap(
     runtime.spawn(async move {
         let context = Context::new(chain_id, db, mp_sender.clone(), node_config.clone());
         let context_arc = Arc::new(context);
+        if let Some(address) = node_config.sf_stream.subscription_address {
+            tokio::spawn(subscription::serve(
+                context_arc.clone(),
+                address,
+                node_config.sf_stream.max_subscriptions,
+            ));
+        }
+        if !node_config.sf_stream.enabled {
+            return;
+        }
         // Let the env variable take precedence over the config file
         let config_starting_version = node_config.sf_stream.starting_version.unwrap_or(0);
         let starting_version = std::env::var("STARTING_VERSION")
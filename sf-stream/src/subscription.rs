@@ -0,0 +1,310 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Subscriptions to the events committed, served over websockets.
+//!
+//! A client opens a websocket at `/v1/events/subscribe` and sends its subscription as the first
+//! text message, e.g. `{"event_types": ["0x1::coin::DepositEvent"], "start_version": "10"}`. The
+//! events matching its filters are then sent as they are committed, one per message: as JSON text
+//! messages by default, or, in the `bcs` format, as binary messages holding the BCS of the version
+//! and the `ContractEvent`, which skips converting the events for high-throughput consumers.
+//! Subscriptions beyond the configured limit are refused with a 503.
+
+use crate::metrics;
+use anyhow::{ensure, format_err, Result};
+use aptos_api::context::Context;
+use aptos_api_types::{Address, AsConverter, Event, MoveType, U64};
+use aptos_logger::debug;
+use aptos_types::{account_address::AccountAddress, contract_event::ContractEvent};
+use futures::{stream::SplitSink, SinkExt, StreamExt};
+use move_deps::move_core_types::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::{TryFrom, TryInto},
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time::sleep;
+use warp::{
+    http::StatusCode,
+    ws::{Message, WebSocket, Ws},
+    Filter, Rejection, Reply,
+};
+
+/// How long a subscription waits for new transactions once it caught up with the ledger.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The number of transactions a subscription reads at once.
+const BATCH_SIZE: u16 = 100;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFormat {
+    /// Events converted to JSON, as the API returns them
+    Json,
+    /// The BCS of the version and the event, as they are stored
+    Bcs,
+}
+
+impl Default for EventFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+/// The options of a subscription, sent by the client as its first message.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubscriptionRequest {
+    /// Only events of these types are sent, or of any type if empty
+    pub event_types: Vec<MoveType>,
+    /// Only events whose event key was created by these accounts are sent, or of any account if
+    /// empty
+    pub addresses: Vec<Address>,
+    /// The version to send the events from, or the next one committed if unset
+    pub start_version: Option<U64>,
+    pub format: EventFormat,
+}
+
+/// An event sent to a subscription in the JSON format.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SubscriptionEvent {
+    pub version: U64,
+    pub event: Event,
+}
+
+/// Matches the events a subscription is sent.
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    event_types: Vec<TypeTag>,
+    addresses: Vec<AccountAddress>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, event: &ContractEvent) -> bool {
+        (self.event_types.is_empty() || self.event_types.contains(event.type_tag()))
+            && (self.addresses.is_empty()
+                || self.addresses.contains(&event.key().get_creator_address()))
+    }
+}
+
+impl TryFrom<&SubscriptionRequest> for EventFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(request: &SubscriptionRequest) -> Result<Self> {
+        Ok(Self {
+            event_types: request
+                .event_types
+                .iter()
+                .cloned()
+                .map(TryInto::try_into)
+                .collect::<Result<_>>()?,
+            addresses: request.addresses.iter().map(Into::into).collect(),
+        })
+    }
+}
+
+/// Bounds the number of subscriptions served at once.
+struct SubscriptionSlots {
+    active: AtomicUsize,
+    max: usize,
+}
+
+impl SubscriptionSlots {
+    fn acquire(self: &Arc<Self>) -> Option<SubscriptionSlot> {
+        self.active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                if active < self.max {
+                    Some(active + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()?;
+        metrics::SUBSCRIPTIONS.inc();
+        Some(SubscriptionSlot(self.clone()))
+    }
+}
+
+/// Frees its slot once the subscription ends.
+struct SubscriptionSlot(Arc<SubscriptionSlots>);
+
+impl Drop for SubscriptionSlot {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+        metrics::SUBSCRIPTIONS.dec();
+    }
+}
+
+/// Serves the event subscriptions at the address.
+pub async fn serve(context: Arc<Context>, address: SocketAddr, max_subscriptions: usize) {
+    warp::serve(routes(context, max_subscriptions))
+        .bind(address)
+        .await
+}
+
+pub fn routes(
+    context: Arc<Context>,
+    max_subscriptions: usize,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let slots = Arc::new(SubscriptionSlots {
+        active: AtomicUsize::new(0),
+        max: max_subscriptions,
+    });
+    warp::path!("v1" / "events" / "subscribe")
+        .and(warp::ws())
+        .map(move |ws: Ws| -> Box<dyn Reply> {
+            let slot = match slots.acquire() {
+                Some(slot) => slot,
+                None => {
+                    metrics::SUBSCRIPTIONS_REFUSED.inc();
+                    return Box::new(warp::reply::with_status(
+                        format!(
+                            "Too many subscriptions, at most {} are served at once",
+                            max_subscriptions
+                        ),
+                        StatusCode::SERVICE_UNAVAILABLE,
+                    ));
+                }
+            };
+            let context = context.clone();
+            Box::new(ws.on_upgrade(move |socket| async move {
+                serve_subscription(&context, socket).await;
+                drop(slot);
+            }))
+        })
+}
+
+async fn serve_subscription(context: &Context, socket: WebSocket) {
+    let (mut sender, mut receiver) = socket.split();
+    let request = match receiver.next().await {
+        Some(Ok(message)) => message
+            .to_str()
+            .map_err(|_| format_err!("The subscription must be sent as a text message"))
+            .and_then(|text| {
+                serde_json::from_str::<SubscriptionRequest>(text)
+                    .map_err(|e| format_err!("Invalid subscription: {}", e))
+            }),
+        // The client left before subscribing.
+        _ => return,
+    };
+
+    let result = match request {
+        Ok(request) => {
+            tokio::select! {
+                result = stream_events(context, &request, &mut sender) => result,
+                // The client closed the subscription.
+                _ = async { while let Some(Ok(_)) = receiver.next().await {} } => Ok(()),
+            }
+        }
+        Err(err) => Err(err),
+    };
+    if let Err(err) = result {
+        debug!("[sf-stream] subscription ended: {}", err);
+        let error = serde_json::json!({ "error": err.to_string() }).to_string();
+        let _ = sender.send(Message::text(error)).await;
+        let _ = sender.send(Message::close()).await;
+    }
+}
+
+async fn stream_events(
+    context: &Context,
+    request: &SubscriptionRequest,
+    sender: &mut SplitSink<WebSocket, Message>,
+) -> Result<()> {
+    let filter = EventFilter::try_from(request)?;
+    let mut next_version = match request.start_version {
+        Some(version) => version.0,
+        None => context.get_latest_ledger_info_wrapped()?.version() + 1,
+    };
+    loop {
+        let ledger_info = context.get_latest_ledger_info_wrapped()?;
+        ensure!(
+            next_version >= ledger_info.oldest_ledger_version.0,
+            "Version {} is pruned, the oldest version is {}",
+            next_version,
+            ledger_info.oldest_ledger_version,
+        );
+        if next_version > ledger_info.version() {
+            sleep(POLL_INTERVAL).await;
+            continue;
+        }
+
+        let (events, version) = next_events(context, &filter, next_version, ledger_info.version())?;
+        for message in encode_events(context, events, request.format)? {
+            sender.send(message).await?;
+            metrics::SUBSCRIPTION_EVENTS_SENT
+                .with_label_values(&[match request.format {
+                    EventFormat::Json => "json",
+                    EventFormat::Bcs => "bcs",
+                }])
+                .inc();
+        }
+        if version == next_version {
+            sleep(POLL_INTERVAL).await;
+        }
+        next_version = version;
+    }
+}
+
+/// Reads a batch of transactions from `start_version` up to the ledger version, and returns the
+/// events among them matching the filter, with their versions, and the version to read next.
+pub fn next_events(
+    context: &Context,
+    filter: &EventFilter,
+    start_version: u64,
+    ledger_version: u64,
+) -> Result<(Vec<(u64, ContractEvent)>, u64)> {
+    let limit = (ledger_version - start_version + 1).min(BATCH_SIZE as u64) as u16;
+    let transactions = context.get_transactions(start_version, limit, ledger_version)?;
+    let next_version = transactions
+        .last()
+        .map_or(start_version, |txn| txn.version + 1);
+    let events = transactions
+        .into_iter()
+        .flat_map(|txn| {
+            let version = txn.version;
+            txn.events
+                .into_iter()
+                .filter(|event| filter.matches(event))
+                .map(move |event| (version, event))
+        })
+        .collect();
+    Ok((events, next_version))
+}
+
+fn encode_events(
+    context: &Context,
+    events: Vec<(u64, ContractEvent)>,
+    format: EventFormat,
+) -> Result<Vec<Message>> {
+    match format {
+        EventFormat::Json => {
+            if events.is_empty() {
+                return Ok(vec![]);
+            }
+            let resolver = context.move_resolver()?;
+            let converter = resolver.as_converter(context.db.clone());
+            events
+                .into_iter()
+                .map(|(version, event)| {
+                    let event = converter
+                        .try_into_events(std::slice::from_ref(&event))?
+                        .pop()
+                        .ok_or_else(|| format_err!("Event at version {} not converted", version))?;
+                    Ok(Message::text(serde_json::to_string(&SubscriptionEvent {
+                        version: version.into(),
+                        event,
+                    })?))
+                })
+                .collect()
+        }
+        EventFormat::Bcs => events
+            .iter()
+            .map(|event| Ok(Message::binary(bcs::to_bytes(event)?)))
+            .collect(),
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    subscription::{routes, EventFilter, SubscriptionEvent, SubscriptionRequest},
+    tests::new_test_context,
+};
+
+use aptos_api_test_context::current_function_name;
+use aptos_types::{
+    account_config::{NewEpochEvent, CORE_CODE_ADDRESS},
+    contract_event::ContractEvent,
+    event::EventKey,
+};
+use move_deps::move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
+use serde_json::json;
+use std::{convert::TryFrom, sync::Arc};
+
+const PATH: &str = "/v1/events/subscribe";
+
+#[test]
+fn test_event_filter() {
+    let new_epoch_type = TypeTag::Struct(NewEpochEvent::struct_tag());
+    let event = ContractEvent::new(
+        EventKey::new(0, CORE_CODE_ADDRESS),
+        0,
+        new_epoch_type,
+        vec![],
+    );
+    let filter = |request| {
+        EventFilter::try_from(&serde_json::from_value::<SubscriptionRequest>(request).unwrap())
+            .unwrap()
+    };
+
+    assert!(filter(json!({})).matches(&event));
+    assert!(filter(json!({
+        "event_types": ["0x1::reconfiguration::NewEpochEvent"],
+        "addresses": ["0x1"],
+    }))
+    .matches(&event));
+    assert!(!filter(json!({ "event_types": ["0x1::coin::DepositEvent"] })).matches(&event));
+    assert!(!filter(json!({ "addresses": ["0xa550c18"] })).matches(&event));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscription_sends_matching_events() {
+    let test_context = new_test_context(current_function_name!());
+    let routes = routes(Arc::new(test_context.context), 1);
+
+    let mut client = warp::test::ws()
+        .path(PATH)
+        .handshake(routes.clone())
+        .await
+        .unwrap();
+    client
+        .send_text(
+            json!({
+                "event_types": ["0x1::reconfiguration::NewEpochEvent"],
+                "start_version": "0",
+            })
+            .to_string(),
+        )
+        .await;
+    let message = client.recv().await.unwrap();
+    let event: SubscriptionEvent = serde_json::from_str(message.to_str().unwrap()).unwrap();
+    assert_eq!(event.version.0, 0);
+    assert_eq!(
+        event.event.typ.to_string(),
+        "0x1::reconfiguration::NewEpochEvent"
+    );
+
+    // Only one subscription is served at once.
+    assert!(warp::test::ws().path(PATH).handshake(routes).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_subscription_sends_bcs() {
+    let test_context = new_test_context(current_function_name!());
+    let routes = routes(Arc::new(test_context.context), 1);
+
+    let mut client = warp::test::ws().path(PATH).handshake(routes).await.unwrap();
+    client
+        .send_text(json!({ "start_version": "0", "format": "bcs" }).to_string())
+        .await;
+    let message = client.recv().await.unwrap();
+    assert!(message.is_binary());
+    let (version, _event): (u64, ContractEvent) = bcs::from_bytes(message.as_bytes()).unwrap();
+    assert_eq!(version, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_invalid_subscription() {
+    let test_context = new_test_context(current_function_name!());
+    let routes = routes(Arc::new(test_context.context), 1);
+
+    let mut client = warp::test::ws().path(PATH).handshake(routes).await.unwrap();
+    client
+        .send_text(json!({ "event_types": ["not a type"] }).to_string())
+        .await;
+    let message = client.recv().await.unwrap();
+    let error: serde_json::Value = serde_json::from_str(message.to_str().unwrap()).unwrap();
+    assert!(error["error"].is_string());
+}
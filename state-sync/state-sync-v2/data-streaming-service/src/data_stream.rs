@@ -15,6 +15,7 @@ use crate::{
     metrics::{increment_counter, start_timer},
     stream_engine::{DataStreamEngine, StreamEngine},
     streaming_client::{NotificationFeedback, StreamRequest},
+    throttle::CatchUpThrottle,
 };
 use aptos_config::config::DataStreamingServiceConfig;
 use aptos_data_client::{
@@ -27,11 +28,13 @@ use aptos_logger::prelude::*;
 use channel::{aptos_channel, message_queues::QueueStyle};
 use futures::{stream::FusedStream, Stream};
 use std::{
+    cmp::{max, min},
     collections::{BTreeMap, VecDeque},
+    mem::size_of,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::task::JoinHandle;
 
@@ -96,6 +99,11 @@ pub struct DataStream<T> {
     // notification to the listener. If so, the stream is dead and it will
     // stop sending notifications. This handles when clients drop the listener.
     send_failure: bool,
+
+    // The throttle of the data received while the node is catching up, and
+    // whether the stream was catching up when its progress was last checked.
+    catch_up_throttle: CatchUpThrottle,
+    catching_up: bool,
 }
 
 impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
@@ -132,6 +140,8 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
             stream_end_notification_id: None,
             request_failure_count: 0,
             send_failure: false,
+            catch_up_throttle: CatchUpThrottle::new(config.catch_up_throttle, Instant::now()),
+            catching_up: false,
         };
 
         Ok((data_stream, data_stream_listener))
@@ -149,6 +159,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
     ) -> Result<(), Error> {
         // Initialize the data client requests queue
         self.sent_data_requests = Some(VecDeque::new());
+        self.update_catch_up_status(&global_data_summary);
 
         // Create and send the data client requests to the network
         self.create_and_send_client_requests(&global_data_summary)
@@ -202,9 +213,42 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
     /// Returns the maximum number of concurrent requests that can be executing
     /// at any given time.
     fn get_max_concurrent_requests(&self) -> u64 {
-        match self.stream_engine {
+        let max_concurrent_requests = match self.stream_engine {
             StreamEngine::StateStreamEngine(_) => self.config.max_concurrent_state_requests,
             _ => self.config.max_concurrent_requests,
+        };
+        if self.catching_up {
+            // Always allow a request, or the stream would never make progress
+            max(
+                min(
+                    max_concurrent_requests,
+                    self.catch_up_throttle.max_concurrent_requests(),
+                ),
+                1,
+            )
+        } else {
+            max_concurrent_requests
+        }
+    }
+
+    /// Returns the number of versions the stream lags the highest version
+    /// advertised by peers, or `None` if the stream doesn't stream versions.
+    fn get_stream_lag(&self, advertised_data: &AdvertisedData) -> Option<u64> {
+        let next_stream_version = self.stream_engine.get_next_stream_version()?;
+        let highest_advertised_version = advertised_data
+            .highest_synced_ledger_info()
+            .map(|ledger_info| ledger_info.ledger_info().version())
+            .unwrap_or(0);
+        Some(highest_advertised_version.saturating_sub(next_stream_version))
+    }
+
+    /// Checks whether the stream is catching up and, if it is, adjusts the
+    /// throttle to its lag.
+    fn update_catch_up_status(&mut self, global_data_summary: &GlobalDataSummary) {
+        let lag = self.get_stream_lag(&global_data_summary.advertised_data);
+        self.catching_up = self.catch_up_throttle.is_catching_up(lag);
+        if let (true, Some(lag)) = (self.catching_up, lag) {
+            self.catch_up_throttle.check_lag(lag, Instant::now());
         }
     }
 
@@ -213,14 +257,17 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
         &mut self,
         global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
-        // Determine how many requests (at most) can be sent to the network
+        // Hold off sending requests while the catch up budget is spent
+        if self.catching_up && !self.catch_up_throttle.can_send_requests(Instant::now()) {
+            return Ok(());
+        }
+
+        // Determine how many requests (at most) can be sent to the network.
+        // More requests may be in flight than allowed if the stream started
+        // catching up since they were sent.
         let num_sent_requests = self.get_sent_data_requests().len() as u64;
         let max_concurrent_requests = self.get_max_concurrent_requests();
-        let max_num_requests_to_send = max_concurrent_requests
-            .checked_sub(num_sent_requests)
-            .ok_or_else(|| {
-                Error::IntegerOverflow("Max number of requests to send has overflown!".into())
-            })?;
+        let max_num_requests_to_send = max_concurrent_requests.saturating_sub(num_sent_requests);
 
         if max_num_requests_to_send > 0 {
             let client_requests = self
@@ -327,6 +374,7 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
             }
             return Ok(()); // There's nothing left to do
         }
+        self.update_catch_up_status(&global_data_summary);
 
         // Process any ready data responses
         for _ in 0..self.get_max_concurrent_requests() {
@@ -459,6 +507,17 @@ impl<T: AptosDataClient + Send + Clone + 'static> DataStream<T> {
     ) -> Result<(), Error> {
         let (response_context, response_payload) = data_client_response.into_parts();
 
+        // Pay for the received data from the catch up budget
+        let num_bytes = get_num_bytes(&response_payload);
+        metrics::increment_counter_by(
+            &metrics::RECEIVED_DATA_RESPONSE_BYTES,
+            response_payload.get_label().into(),
+            num_bytes,
+        );
+        if self.catching_up {
+            self.catch_up_throttle.record_received_bytes(num_bytes);
+        }
+
         // Create a new data notification
         if let Some(data_notification) = self
             .stream_engine
@@ -685,6 +744,31 @@ fn sanity_check_client_response(
 
 /// Transforms the notification feedback into a specific response error that
 /// can be sent to the Aptos data client.
+/// Returns the number of bytes of the given response payload when serialized
+fn get_num_bytes(response_payload: &ResponsePayload) -> u64 {
+    let num_bytes = match response_payload {
+        ResponsePayload::EpochEndingLedgerInfos(ledger_infos) => bcs::serialized_size(ledger_infos),
+        ResponsePayload::NewTransactionOutputsWithProof(outputs_with_proof) => {
+            bcs::serialized_size(outputs_with_proof)
+        }
+        ResponsePayload::NewTransactionsWithProof(transactions_with_proof) => {
+            bcs::serialized_size(transactions_with_proof)
+        }
+        ResponsePayload::NumberOfStates(_) => Ok(size_of::<u64>()),
+        ResponsePayload::StateValuesWithProof(state_values_with_proof) => {
+            bcs::serialized_size(state_values_with_proof)
+        }
+        ResponsePayload::TransactionOutputsWithProof(outputs_with_proof) => {
+            bcs::serialized_size(outputs_with_proof)
+        }
+        ResponsePayload::TransactionsWithProof(transactions_with_proof) => {
+            bcs::serialized_size(transactions_with_proof)
+        }
+    };
+    // The payloads were deserialized from the network, so always serialize
+    num_bytes.unwrap_or(0) as u64
+}
+
 fn extract_response_error(notification_feedback: &NotificationFeedback) -> ResponseError {
     match notification_feedback {
         NotificationFeedback::InvalidPayloadData => ResponseError::InvalidData,
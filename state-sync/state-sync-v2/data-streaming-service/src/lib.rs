@@ -11,6 +11,7 @@ mod metrics;
 mod stream_engine;
 pub mod streaming_client;
 pub mod streaming_service;
+mod throttle;
 
 #[cfg(test)]
 mod tests;
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    register_histogram_vec, register_int_counter_vec, HistogramTimer, HistogramVec, IntCounterVec,
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, HistogramTimer,
+    HistogramVec, IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -76,6 +77,25 @@ pub static RECEIVED_RESPONSE_ERROR: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the bytes of received data responses
+pub static RECEIVED_DATA_RESPONSE_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_streaming_service_received_data_response_bytes",
+        "Counters related to the bytes of received data responses",
+        &["response_type"]
+    )
+    .unwrap()
+});
+
+/// Gauge for the bandwidth cap of data streams while catching up
+pub static CATCH_UP_BYTES_PER_SECOND: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_catch_up_bytes_per_second",
+        "The cap on the bytes per second received by data streams while catching up"
+    )
+    .unwrap()
+});
+
 /// Time it takes to process a data request
 pub static DATA_REQUEST_PROCESSING_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -91,6 +111,16 @@ pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: String) {
     counter.with_label_values(&[&label]).inc();
 }
 
+/// Increments the given counter with the provided label values, by the given amount.
+pub fn increment_counter_by(counter: &Lazy<IntCounterVec>, label: String, value: u64) {
+    counter.with_label_values(&[&label]).inc_by(value);
+}
+
+/// Sets the gauge to the given value.
+pub fn set_gauge(gauge: &Lazy<IntGauge>, value: i64) {
+    gauge.set(value);
+}
+
 /// Starts the timer for the provided histogram and label values.
 pub fn start_timer(histogram: &Lazy<HistogramVec>, label: String) -> HistogramTimer {
     histogram.with_label_values(&[&label]).start_timer()
@@ -72,6 +72,10 @@ pub trait DataStreamEngine {
     /// Returns true iff the stream has sent all data to the stream listener.
     fn is_stream_complete(&self) -> bool;
 
+    /// Returns the version of the next data to send along the stream, or
+    /// `None` if the stream doesn't stream versioned data (e.g., states).
+    fn get_next_stream_version(&self) -> Option<Version>;
+
     /// Transforms a given data client response (for the previously sent
     /// request) into a data notification to be sent along the data stream.
     /// Note: this call may return `None`, in which case, no notification needs
@@ -241,6 +245,10 @@ impl DataStreamEngine for StateStreamEngine {
         self.stream_is_complete
     }
 
+    fn get_next_stream_version(&self) -> Option<Version> {
+        None
+    }
+
     fn transform_client_response_into_notification(
         &mut self,
         client_request: &DataClientRequest,
@@ -798,6 +806,10 @@ impl DataStreamEngine for ContinuousTransactionStreamEngine {
         self.stream_is_complete
     }
 
+    fn get_next_stream_version(&self) -> Option<Version> {
+        Some(self.next_stream_version_and_epoch.0)
+    }
+
     fn transform_client_response_into_notification(
         &mut self,
         client_request: &DataClientRequest,
@@ -979,6 +991,10 @@ impl DataStreamEngine for EpochEndingStreamEngine {
         self.stream_is_complete
     }
 
+    fn get_next_stream_version(&self) -> Option<Version> {
+        None
+    }
+
     fn transform_client_response_into_notification(
         &mut self,
         client_request: &DataClientRequest,
@@ -1174,6 +1190,10 @@ impl DataStreamEngine for TransactionStreamEngine {
         self.stream_is_complete
     }
 
+    fn get_next_stream_version(&self) -> Option<Version> {
+        Some(self.next_stream_version)
+    }
+
     fn transform_client_response_into_notification(
         &mut self,
         client_request: &DataClientRequest,
@@ -18,7 +18,7 @@ use crate::{
         MIN_ADVERTISED_STATES, MIN_ADVERTISED_TRANSACTION_OUTPUT,
     },
 };
-use aptos_config::config::DataStreamingServiceConfig;
+use aptos_config::config::{CatchUpThrottleConfig, DataStreamingServiceConfig};
 use aptos_data_client::{
     AdvertisedData, GlobalDataSummary, OptimalChunkSizes, Response, ResponseContext,
     ResponsePayload,
@@ -316,6 +316,47 @@ async fn test_epoch_stream_out_of_order_responses() {
     assert_none!(stream_listener.select_next_some().now_or_never());
 }
 
+#[tokio::test]
+async fn test_epoch_stream_catch_up_throttle() {
+    // Create an epoch ending data stream that is throttled (it is always
+    // catching up, as it doesn't stream versions).
+    let streaming_service_config = DataStreamingServiceConfig {
+        max_concurrent_requests: 3,
+        catch_up_throttle: CatchUpThrottleConfig {
+            enabled: true,
+            max_bytes_per_second: 1,
+            max_concurrent_requests: 1,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (mut data_stream, mut stream_listener) =
+        create_epoch_ending_stream(streaming_service_config, MIN_ADVERTISED_EPOCH_END);
+
+    // Initialize the data stream and verify a single request has been made
+    let global_data_summary = create_global_data_summary(1);
+    data_stream
+        .initialize_data_requests(global_data_summary.clone())
+        .unwrap();
+    let (sent_requests, _) = data_stream.get_sent_requests_and_notifications();
+    assert_eq!(sent_requests.as_ref().unwrap().len(), 1);
+
+    // Set a response for the request and verify a notification is sent
+    set_epoch_ending_response_in_queue(&mut data_stream, 0);
+    data_stream
+        .process_data_responses(global_data_summary.clone())
+        .unwrap();
+    verify_epoch_ending_notification(
+        &mut stream_listener,
+        create_ledger_info(0, MIN_ADVERTISED_EPOCH_END, true),
+    )
+    .await;
+
+    // Verify no further request has been made as the bandwidth budget is spent
+    let (sent_requests, _) = data_stream.get_sent_requests_and_notifications();
+    assert!(sent_requests.as_ref().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_state_stream_out_of_order_responses() {
     // Create a state value data stream
@@ -5,4 +5,5 @@ mod data_stream;
 mod stream_engine;
 mod streaming_client;
 mod streaming_service;
+mod throttle;
 mod utils;
@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::throttle::CatchUpThrottle;
+use aptos_config::config::CatchUpThrottleConfig;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_catching_up() {
+    // A disabled throttle never throttles
+    let throttle = CatchUpThrottle::new(CatchUpThrottleConfig::default(), Instant::now());
+    assert!(!throttle.is_catching_up(None));
+    assert!(!throttle.is_catching_up(Some(u64::MAX)));
+
+    // Verify streams are catching up when they lag enough (or have no lag)
+    let throttle = CatchUpThrottle::new(create_throttle_config(), Instant::now());
+    assert!(throttle.is_catching_up(None));
+    assert!(throttle.is_catching_up(Some(1000)));
+    assert!(!throttle.is_catching_up(Some(999)));
+}
+
+#[test]
+fn test_bandwidth_budget() {
+    let start = Instant::now();
+    let mut throttle = CatchUpThrottle::new(create_throttle_config(), start);
+    assert!(throttle.can_send_requests(start));
+
+    // Spend more than the budget and verify requests are held off
+    throttle.record_received_bytes(1500);
+    assert!(!throttle.can_send_requests(start));
+
+    // Verify the budget refills at the cap
+    assert!(!throttle.can_send_requests(start + Duration::from_millis(500)));
+    assert!(throttle.can_send_requests(start + Duration::from_millis(600)));
+
+    // Verify the budget holds at most a second's worth of bytes
+    throttle.can_send_requests(start + Duration::from_secs(100));
+    throttle.record_received_bytes(1000);
+    assert!(!throttle.can_send_requests(start + Duration::from_secs(100)));
+}
+
+#[test]
+fn test_cap_adjusted_to_lag() {
+    let start = Instant::now();
+    let mut throttle = CatchUpThrottle::new(create_throttle_config(), start);
+    throttle.check_lag(10_000, start);
+    assert_eq!(throttle.bytes_per_second(), 1000);
+
+    // Verify the lag isn't checked again before the interval elapses
+    throttle.check_lag(20_000, start + Duration::from_millis(500));
+    assert_eq!(throttle.bytes_per_second(), 1000);
+
+    // Verify the cap is raised while the lag grows, up to the boosted cap
+    throttle.check_lag(20_000, start + Duration::from_secs(1));
+    assert_eq!(throttle.bytes_per_second(), 1500);
+    throttle.check_lag(30_000, start + Duration::from_secs(2));
+    assert_eq!(throttle.bytes_per_second(), 2000);
+
+    // Verify the cap is unchanged while the lag holds
+    throttle.check_lag(30_000, start + Duration::from_secs(3));
+    assert_eq!(throttle.bytes_per_second(), 2000);
+
+    // Verify the cap is lowered while the lag shrinks, down to the base cap
+    throttle.check_lag(25_000, start + Duration::from_secs(4));
+    assert_eq!(throttle.bytes_per_second(), 1333);
+    throttle.check_lag(20_000, start + Duration::from_secs(5));
+    assert_eq!(throttle.bytes_per_second(), 1000);
+}
+
+fn create_throttle_config() -> CatchUpThrottleConfig {
+    CatchUpThrottleConfig {
+        enabled: true,
+        min_lag_versions: 1000,
+        max_bytes_per_second: 1000,
+        max_boosted_bytes_per_second: 2000,
+        max_concurrent_requests: 1,
+        lag_check_interval_ms: 1000,
+    }
+}
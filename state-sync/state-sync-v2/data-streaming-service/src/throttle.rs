@@ -0,0 +1,120 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics;
+use aptos_config::config::CatchUpThrottleConfig;
+use std::{
+    cmp::{max, min},
+    time::{Duration, Instant},
+};
+
+/// Throttles the data received by a data stream while the node is catching up.
+///
+/// The bytes received are paid for from a budget that refills at the current
+/// cap (bytes per second) and holds at most a second's worth of bytes. No new
+/// requests are sent while the budget is spent. To keep a node from falling
+/// further behind because of the throttle, the cap is raised (up to
+/// `max_boosted_bytes_per_second`) each time the lag is found to have grown
+/// since the last check, and lowered back towards `max_bytes_per_second` each
+/// time it has shrunk.
+#[derive(Debug)]
+pub struct CatchUpThrottle {
+    config: CatchUpThrottleConfig,
+
+    // The current cap on the bytes received per second
+    bytes_per_second: u64,
+
+    // The bytes that can still be received before the budget is spent. This
+    // goes negative when a response is larger than what was left.
+    available_bytes: i64,
+
+    // The last time the budget was refilled
+    last_refill_time: Instant,
+
+    // The lag (versions) at the last check, and when it was made
+    last_lag_check: Option<(u64, Instant)>,
+}
+
+impl CatchUpThrottle {
+    pub fn new(config: CatchUpThrottleConfig, now: Instant) -> Self {
+        Self {
+            config,
+            bytes_per_second: config.max_bytes_per_second,
+            available_bytes: config.max_bytes_per_second as i64,
+            last_refill_time: now,
+            last_lag_check: None,
+        }
+    }
+
+    /// Returns true iff a stream with the given lag is catching up. Streams
+    /// with no lag (i.e., that don't stream versions) always are.
+    pub fn is_catching_up(&self, lag: Option<u64>) -> bool {
+        self.config.enabled && lag.map_or(true, |lag| lag >= self.config.min_lag_versions)
+    }
+
+    /// Returns the maximum number of concurrent requests while catching up
+    pub fn max_concurrent_requests(&self) -> u64 {
+        self.config.max_concurrent_requests
+    }
+
+    /// Returns the current cap on the bytes received per second
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Records the given number of bytes as received by the stream
+    pub fn record_received_bytes(&mut self, num_bytes: u64) {
+        self.available_bytes = self
+            .available_bytes
+            .saturating_sub(min(num_bytes, i64::MAX as u64) as i64);
+    }
+
+    /// Refills the budget and returns true iff requests can be sent
+    pub fn can_send_requests(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill_time);
+        let refill = (elapsed.as_secs_f64() * self.bytes_per_second as f64) as i64;
+        self.available_bytes = min(
+            self.available_bytes.saturating_add(refill),
+            self.bytes_per_second as i64,
+        );
+        self.last_refill_time = now;
+
+        self.available_bytes > 0
+    }
+
+    /// Adjusts the cap to the given lag, if the lag hasn't been checked in
+    /// the last `lag_check_interval_ms`.
+    pub fn check_lag(&mut self, lag: u64, now: Instant) {
+        let lag_check_interval = Duration::from_millis(self.config.lag_check_interval_ms);
+        match self.last_lag_check {
+            Some((_, last_check_time))
+                if now.saturating_duration_since(last_check_time) < lag_check_interval =>
+            {
+                return;
+            }
+            Some((last_lag, _)) => {
+                let min_bytes_per_second = self.config.max_bytes_per_second;
+                let max_bytes_per_second = max(
+                    self.config.max_boosted_bytes_per_second,
+                    min_bytes_per_second,
+                );
+                if lag > last_lag {
+                    self.bytes_per_second = min(
+                        self.bytes_per_second.saturating_mul(3) / 2,
+                        max_bytes_per_second,
+                    );
+                } else if lag < last_lag {
+                    self.bytes_per_second =
+                        max(self.bytes_per_second * 2 / 3, min_bytes_per_second);
+                }
+            }
+            None => (),
+        }
+        self.last_lag_check = Some((lag, now));
+
+        metrics::set_gauge(
+            &metrics::CATCH_UP_BYTES_PER_SECOND,
+            self.bytes_per_second() as i64,
+        );
+    }
+}
@@ -3,7 +3,7 @@
 
 use crate::driver_factory::DriverFactory;
 use crate::metadata_storage::PersistentMetadataStorage;
-use aptos_config::config::TARGET_SNAPSHOT_SIZE;
+use aptos_config::config::{TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE};
 use aptos_config::{
     config::{RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG},
     utils::get_genesis_txn,
@@ -39,6 +39,7 @@ fn test_new_initialized_configs() {
         RocksdbConfigs::default(),
         false,
         TARGET_SNAPSHOT_SIZE,
+        TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
     )
     .unwrap();
     let (_, db_rw) = DbReaderWriter::wrap(db);
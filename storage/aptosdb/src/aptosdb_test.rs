@@ -1,12 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
-use aptos_config::config::{LedgerPrunerConfig, StateMerklePrunerConfig};
+use aptos_config::config::{LedgerPrunerConfig, PrunerScheduleConfig, StateMerklePrunerConfig};
 use proptest::prelude::*;
 
 use crate::{
+    change_set::ChangeSet,
     error_if_version_is_pruned, get_first_seq_num_and_limit,
     pruner::{
         ledger_pruner_manager::LedgerPrunerManager, state_pruner_manager::StatePrunerManager,
@@ -19,12 +20,20 @@ use crate::{
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_temppath::TempPath;
 use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::BlockMetadata,
+    on_chain_config::{access_path_for_config, BlockGasLimit, OnChainConfig},
     proof::SparseMerkleLeafNode,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{ExecutionStatus, TransactionInfo},
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionToCommit},
+    write_set::WriteSet,
 };
 use storage_interface::{DbReader, ExecutedTrees, Order};
-use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
+use test_helper::{
+    test_auth_key_index_catch_up_impl, test_crash_after_state_kv_commit_impl,
+    test_crash_after_state_merkle_commit_impl, test_crash_recovery_impl, test_save_blocks_impl,
+    test_secondary_catch_up_impl, test_sync_transactions_impl,
+};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -38,6 +47,35 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit(), threshold in 10..20usize) {
         test_sync_transactions_impl(input, threshold);
     }
+
+    #[test]
+    fn test_secondary_catch_up(input in arb_blocks_to_commit()) {
+        test_secondary_catch_up_impl(input);
+    }
+
+    #[test]
+    fn test_crash_recovery(
+        input in arb_blocks_to_commit(),
+        threshold in 10..20usize,
+        interval in 1..10u64,
+    ) {
+        test_crash_recovery_impl(input, threshold, interval);
+    }
+
+    #[test]
+    fn test_crash_after_state_kv_commit(input in arb_blocks_to_commit()) {
+        test_crash_after_state_kv_commit_impl(input);
+    }
+
+    #[test]
+    fn test_crash_after_state_merkle_commit(input in arb_blocks_to_commit()) {
+        test_crash_after_state_merkle_commit_impl(input);
+    }
+
+    #[test]
+    fn test_auth_key_index_catch_up(input in arb_blocks_to_commit()) {
+        test_auth_key_index_catch_up_impl(input);
+    }
 }
 
 #[test]
@@ -90,12 +128,17 @@ fn test_storage_config() {
         for enable_state in [false, true] {
             let state_pruner = StatePrunerManager::new(
                 Arc::clone(&aptos_db.state_merkle_db),
+                Arc::clone(&aptos_db.state_kv_db),
+                Arc::clone(&aptos_db.node_cache),
                 StateMerklePrunerConfig {
                     enable: enable_state,
                     prune_window: 20,
                     batch_size: 1,
                     user_pruning_window_offset: 0,
+                    max_nodes_to_prune_per_second: 0,
                 },
+                PrunerScheduleConfig::default(),
+                None,
             );
 
             assert_eq!(state_pruner.is_pruner_enabled(), enable_state);
@@ -109,6 +152,8 @@ fn test_storage_config() {
                     batch_size: 1,
                     user_pruning_window_offset: 0,
                 },
+                PrunerScheduleConfig::default(),
+                None,
             );
             assert_eq!(ledger_pruner.is_pruner_enabled(), enable_ledger);
             assert_eq!(ledger_pruner.get_pruner_window(), 100);
@@ -122,12 +167,17 @@ fn test_error_if_version_is_pruned() {
     let aptos_db = AptosDB::new_for_test(&tmp_dir);
     let state_pruner = StatePrunerManager::new(
         Arc::clone(&aptos_db.state_merkle_db),
+        Arc::clone(&aptos_db.state_kv_db),
+        Arc::clone(&aptos_db.node_cache),
         StateMerklePrunerConfig {
             enable: true,
             prune_window: 0,
             batch_size: 1,
             user_pruning_window_offset: 0,
+            max_nodes_to_prune_per_second: 0,
         },
+        PrunerScheduleConfig::default(),
+        None,
     );
 
     let ledger_pruner = LedgerPrunerManager::new(
@@ -138,6 +188,8 @@ fn test_error_if_version_is_pruned() {
             batch_size: 1,
             user_pruning_window_offset: 0,
         },
+        PrunerScheduleConfig::default(),
+        None,
     );
     state_pruner.testonly_update_min_version(5);
     ledger_pruner.testonly_update_min_version(10);
@@ -209,3 +261,76 @@ fn test_rocksdb_properties_reporter() {
     std::thread::sleep(Duration::from_secs(1));
     assert_eq!(get_metric(), 1);
 }
+
+#[test]
+fn test_put_block_gas_limits_reached() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    let gas_limit_key = StateKey::AccessPath(access_path_for_config(BlockGasLimit::CONFIG_ID));
+    let txn = |transaction, gas_used, gas_limit: Option<Option<u64>>| {
+        let mut state_updates = HashMap::new();
+        if let Some(gas_limit) = gas_limit {
+            state_updates.insert(
+                gas_limit_key.clone(),
+                gas_limit.map(|gas_limit| {
+                    StateValue::from(bcs::to_bytes(&BlockGasLimit { gas_limit }).unwrap())
+                }),
+            );
+        }
+        TransactionToCommit::new(
+            transaction,
+            TransactionInfo::new(
+                HashValue::random(),
+                HashValue::random(),
+                HashValue::random(),
+                None,
+                gas_used,
+                ExecutionStatus::Success,
+            ),
+            state_updates,
+            WriteSet::default(),
+            vec![],
+            false,
+        )
+    };
+    let block = || {
+        Transaction::BlockMetadata(BlockMetadata::new(
+            HashValue::random(),
+            0,
+            0,
+            AccountAddress::ZERO,
+            None,
+            vec![],
+            vec![],
+            0,
+        ))
+    };
+    let other = || Transaction::StateCheckpoint(HashValue::random());
+
+    let txns_to_commit = vec![
+        // The limit is set before the first block.
+        txn(other(), 0, Some(Some(10))),
+        // Reaches the limit.
+        txn(block(), 0, None),
+        txn(other(), 6, None),
+        txn(other(), 6, None),
+        // Stays below the limit, and unsets it for later blocks.
+        txn(block(), 0, None),
+        txn(other(), 5, Some(None)),
+        // Isn't limited.
+        txn(block(), 0, None),
+        txn(other(), 100, None),
+    ];
+    let mut cs = ChangeSet::new();
+    db.put_block_gas_limits_reached(0, &txns_to_commit, &mut cs)
+        .unwrap();
+    db.ledger_db.write_schemas(cs.batch).unwrap();
+
+    assert_eq!(
+        db.event_store.get_block_gas_limit_reached(1).unwrap(),
+        Some(10)
+    );
+    assert_eq!(db.event_store.get_block_gas_limit_reached(4).unwrap(), None);
+    assert_eq!(db.event_store.get_block_gas_limit_reached(6).unwrap(), None);
+}
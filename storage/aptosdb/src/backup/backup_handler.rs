@@ -8,6 +8,7 @@ use crate::{
         BACKUP_EPOCH_ENDING_EPOCH, BACKUP_STATE_SNAPSHOT_LEAF_IDX, BACKUP_STATE_SNAPSHOT_VERSION,
         BACKUP_TXN_VERSION,
     },
+    pruner::backup_progress::BackupProgress,
     state_store::StateStore,
     transaction_store::TransactionStore,
 };
@@ -31,6 +32,7 @@ pub struct BackupHandler {
     transaction_store: Arc<TransactionStore>,
     state_store: Arc<StateStore>,
     event_store: Arc<EventStore>,
+    backup_progress: Arc<BackupProgress>,
 }
 
 impl BackupHandler {
@@ -39,12 +41,14 @@ impl BackupHandler {
         transaction_store: Arc<TransactionStore>,
         state_store: Arc<StateStore>,
         event_store: Arc<EventStore>,
+        backup_progress: Arc<BackupProgress>,
     ) -> Self {
         Self {
             ledger_store,
             transaction_store,
             state_store,
             event_store,
+            backup_progress,
         }
     }
 
@@ -165,6 +169,12 @@ impl BackupHandler {
                 li
             }))
     }
+
+    /// Records that all versions up to `version` are in the backup, so that the pruners may prune
+    /// them. Until a version is recorded, the pruners don't wait on the backup.
+    pub fn record_backed_up_version(&self, version: Version) -> Result<()> {
+        self.backup_progress.record_backed_up_version(version)
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -7,7 +7,7 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_crypto::HashValue;
-use aptos_jellyfish_merkle::restore::StateSnapshotRestore;
+use aptos_jellyfish_merkle::{restore::StateSnapshotRestore, StateValueWriter};
 use aptos_types::{
     contract_event::ContractEvent,
     ledger_info::LedgerInfoWithSignatures,
@@ -49,11 +49,16 @@ impl RestoreHandler {
         }
     }
 
+    /// Gets a receiver restoring the state snapshot at `version`, which resumes from the progress
+    /// of a previous attempt to restore it if that was interrupted.
     pub fn get_state_restore_receiver(
         &self,
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<StateSnapshotRestore<StateKey, StateValue>> {
+        if self.state_store.get_progress(version)?.is_some() {
+            return self.get_resumable_state_restore_receiver(version, expected_root_hash);
+        }
         StateSnapshotRestore::new_overwrite(
             &self.state_store.state_merkle_db,
             &self.state_store,
@@ -62,6 +67,21 @@ impl RestoreHandler {
         )
     }
 
+    /// Like `get_state_restore_receiver`, but always picks up from what is already in the DB at
+    /// `version`, so that a restore that was interrupted is resumed instead of started over.
+    pub fn get_resumable_state_restore_receiver(
+        &self,
+        version: Version,
+        expected_root_hash: HashValue,
+    ) -> Result<StateSnapshotRestore<StateKey, StateValue>> {
+        StateSnapshotRestore::new(
+            &self.state_store.state_merkle_db,
+            &self.state_store,
+            version,
+            expected_root_hash,
+        )
+    }
+
     pub fn reset_state_store(&self) {
         self.state_store.reset();
     }
@@ -108,6 +128,13 @@ impl RestoreHandler {
         )
     }
 
+    /// Returns the root hash of the state tree at `version`, if the tree has a root there.
+    pub fn get_state_root_hash_option(&self, version: Version) -> Result<Option<HashValue>> {
+        self.state_store
+            .state_merkle_db
+            .get_root_hash_option(version)
+    }
+
     pub fn get_next_expected_transaction_version(&self) -> Result<Version> {
         Ok(self
             .aptosdb
@@ -4,7 +4,7 @@
 use crate::ledger_counters::LedgerCounterBumps;
 use aptos_types::transaction::Version;
 use schemadb::SchemaBatch;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Structure that collects changes to be made to the DB in one transaction.
 ///
@@ -14,6 +14,8 @@ use std::collections::HashMap;
 pub struct ChangeSet {
     /// A batch of db alternations.
     pub batch: SchemaBatch,
+    /// Batches of db alternations to the shards of the state values by shard id, if sharded.
+    pub state_kv_batches: BTreeMap<usize, SchemaBatch>,
     /// Counter bumps to be made on commit.
     counter_bumps: HashMap<Version, LedgerCounterBumps>,
 }
@@ -23,6 +25,7 @@ impl ChangeSet {
     pub fn new() -> Self {
         Self {
             batch: SchemaBatch::new(),
+            state_kv_batches: BTreeMap::new(),
             counter_bumps: HashMap::new(),
         }
     }
@@ -37,6 +40,7 @@ impl ChangeSet {
     pub fn new_with_bumps(counter_bumps: HashMap<Version, LedgerCounterBumps>) -> Self {
         Self {
             batch: SchemaBatch::new(),
+            state_kv_batches: BTreeMap::new(),
             counter_bumps,
         }
     }
@@ -48,4 +52,6 @@ impl ChangeSet {
 pub(crate) struct SealedChangeSet {
     /// A batch of db alternations.
     pub batch: SchemaBatch,
+    /// Batches of db alternations to the shards of the state values by shard id, if sharded.
+    pub state_kv_batches: BTreeMap<usize, SchemaBatch>,
 }
@@ -0,0 +1,337 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides the consistency checker, which verifies the data in the DB against the
+//! hashes committed to by the ledger, so that a corruption (e.g. by a failing disk or a bug in the
+//! storage layer) is caught and alerted on before the node crashes on it or serves it to peers.
+//!
+//! For each version checked, it verifies that
+//! - the transaction, its write set and its events hash to what its transaction info says,
+//! - the transaction info is the leaf at the version of the transaction accumulator whose root
+//!   hash is in the latest ledger info, and
+//! - the state checkpoint hash in the transaction info, if any, is the root hash of the state
+//!   merkle tree at the version, if the tree at the version is in the DB.
+
+use crate::{
+    metrics::{CONSISTENCY_CHECKER_INCONSISTENCIES, CONSISTENCY_CHECKER_VERSION},
+    AptosDB, PrunerManager,
+};
+use anyhow::Result;
+use aptos_config::config::ConsistencyCheckerConfig;
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    HashValue,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    ledger_info::LedgerInfo,
+    proof::accumulator::InMemoryAccumulator,
+    transaction::{TransactionInfo, Version},
+};
+use itertools::zip_eq;
+use std::{
+    cmp::{max, min},
+    fmt,
+    sync::{mpsc, Arc},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// A piece of data found to be inconsistent with the hash the ledger commits to it with.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Inconsistency {
+    /// The transaction at the version doesn't hash to the transaction hash in its transaction info.
+    TransactionHash {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// The write set at the version doesn't hash to the state change hash in its transaction info.
+    WriteSetHash {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// The events at the version don't hash to the event root hash in its transaction info.
+    EventRootHash {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// The root of the state merkle tree at the version isn't the state checkpoint hash in its
+    /// transaction info.
+    StateCheckpointHash {
+        version: Version,
+        expected: HashValue,
+        actual: HashValue,
+    },
+    /// The transaction infos in the range don't prove to the transaction accumulator root hash in
+    /// the ledger info at `ledger_version`.
+    TransactionAccumulator {
+        first_version: Version,
+        num_versions: u64,
+        ledger_version: Version,
+    },
+}
+
+impl Inconsistency {
+    /// The kind of the inconsistency, as reported in metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TransactionHash { .. } => "transaction_hash",
+            Self::WriteSetHash { .. } => "write_set_hash",
+            Self::EventRootHash { .. } => "event_root_hash",
+            Self::StateCheckpointHash { .. } => "state_checkpoint_hash",
+            Self::TransactionAccumulator { .. } => "transaction_accumulator",
+        }
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TransactionHash {
+                version,
+                expected,
+                actual,
+            }
+            | Self::WriteSetHash {
+                version,
+                expected,
+                actual,
+            }
+            | Self::EventRootHash {
+                version,
+                expected,
+                actual,
+            }
+            | Self::StateCheckpointHash {
+                version,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{} mismatch at version {}: expected {:x}, got {:x}.",
+                self.kind(),
+                version,
+                expected,
+                actual,
+            ),
+            Self::TransactionAccumulator {
+                first_version,
+                num_versions,
+                ledger_version,
+            } => write!(
+                f,
+                "Transaction infos of versions [{}, {}) are not in the transaction accumulator at \
+                 version {}.",
+                first_version,
+                first_version + num_versions,
+                ledger_version,
+            ),
+        }
+    }
+}
+
+/// Checks `num_versions` versions from `first_version` against the transaction accumulator in
+/// `ledger_info`, which the caller ensures covers them.
+pub(crate) fn check_versions(
+    db: &AptosDB,
+    first_version: Version,
+    num_versions: u64,
+    ledger_info: &LedgerInfo,
+) -> Result<Vec<Inconsistency>> {
+    let mut inconsistencies = Vec::new();
+    let limit = num_versions as usize;
+    let txn_infos = db
+        .ledger_store
+        .get_transaction_info_iter(first_version, limit)?
+        .collect::<Result<Vec<_>>>()?;
+    let txns = db
+        .transaction_store
+        .get_transaction_iter(first_version, limit)?;
+    let write_sets = db
+        .transaction_store
+        .get_write_set_iter(first_version, limit)?;
+    let events = db
+        .event_store
+        .get_events_by_version_iter(first_version, limit)?;
+
+    for (version, (txn_info, ((txn, write_set), events))) in
+        (first_version..).zip(zip_eq(&txn_infos, zip_eq(zip_eq(txns, write_sets), events)))
+    {
+        let actual = txn?.hash();
+        if actual != txn_info.transaction_hash() {
+            inconsistencies.push(Inconsistency::TransactionHash {
+                version,
+                expected: txn_info.transaction_hash(),
+                actual,
+            });
+        }
+
+        let actual = CryptoHash::hash(&write_set?);
+        if actual != txn_info.state_change_hash() {
+            inconsistencies.push(Inconsistency::WriteSetHash {
+                version,
+                expected: txn_info.state_change_hash(),
+                actual,
+            });
+        }
+
+        let event_hashes: Vec<_> = events?.iter().map(CryptoHash::hash).collect();
+        let actual =
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash();
+        if actual != txn_info.event_root_hash() {
+            inconsistencies.push(Inconsistency::EventRootHash {
+                version,
+                expected: txn_info.event_root_hash(),
+                actual,
+            });
+        }
+
+        // Only some of the state checkpoints are persisted in the state merkle tree, and the
+        // older ones get pruned.
+        if let Some(expected) = txn_info.state_checkpoint_hash() {
+            if let Some(actual) = db
+                .state_store
+                .state_merkle_db
+                .get_root_hash_option(version)?
+            {
+                if actual != expected {
+                    inconsistencies.push(Inconsistency::StateCheckpointHash {
+                        version,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+    }
+
+    let txn_info_hashes: Vec<_> = txn_infos.iter().map(TransactionInfo::hash).collect();
+    let proof = db.ledger_store.get_transaction_range_proof(
+        Some(first_version),
+        num_versions,
+        ledger_info.version(),
+    )?;
+    if proof
+        .verify(
+            ledger_info.transaction_accumulator_hash(),
+            Some(first_version),
+            &txn_info_hashes,
+        )
+        .is_err()
+    {
+        inconsistencies.push(Inconsistency::TransactionAccumulator {
+            first_version,
+            num_versions,
+            ledger_version: ledger_info.version(),
+        });
+    }
+
+    Ok(inconsistencies)
+}
+
+/// Runs the consistency checker in the background, checking the versions as they get committed,
+/// starting from `lookback_versions` versions before the latest one.
+///
+/// It creates a worker thread on construction and joins it on destruction.
+#[derive(Debug)]
+pub struct ConsistencyChecker {
+    sender: Mutex<mpsc::Sender<()>>,
+    join_handle: Option<JoinHandle<()>>,
+}
+
+impl ConsistencyChecker {
+    pub fn start(db: Arc<AptosDB>, config: ConsistencyCheckerConfig) -> Self {
+        let (send, recv) = mpsc::channel();
+        let join_handle = Some(
+            thread::Builder::new()
+                .name("aptosdb_consistency_checker".into())
+                .spawn(move || {
+                    let mut next_version = None;
+                    loop {
+                        let caught_up = check_next_batch(&db, &config, &mut next_version)
+                            .unwrap_or_else(|e| {
+                                warn!(error = ?e, "Consistency check failed.");
+                                true
+                            });
+
+                        // Keep going without waiting until caught up with the latest version.
+                        let wait = if caught_up {
+                            Duration::from_millis(config.check_interval_ms)
+                        } else {
+                            Duration::ZERO
+                        };
+                        match recv.recv_timeout(wait) {
+                            Ok(_) => break,
+                            Err(mpsc::RecvTimeoutError::Timeout) => (),
+                            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                        }
+                    }
+                })
+                .expect("Creating consistency checker thread should succeed."),
+        );
+        Self {
+            sender: Mutex::new(send),
+            join_handle,
+        }
+    }
+}
+
+impl Drop for ConsistencyChecker {
+    fn drop(&mut self) {
+        // Notify the consistency checker thread to exit
+        self.sender.lock().send(()).unwrap();
+        self.join_handle
+            .take()
+            .expect("Consistency checker thread must exist.")
+            .join()
+            .expect("Consistency checker thread should join peacefully.");
+    }
+}
+
+/// Checks the next batch of versions, if any, and returns whether the latest version is checked.
+fn check_next_batch(
+    db: &AptosDB,
+    config: &ConsistencyCheckerConfig,
+    next_version: &mut Option<Version>,
+) -> Result<bool> {
+    let ledger_info = match db.ledger_store.get_latest_ledger_info_option() {
+        Some(ledger_info_with_sigs) => ledger_info_with_sigs.ledger_info().clone(),
+        None => return Ok(true),
+    };
+    let num_committed_versions = ledger_info.version() + 1;
+
+    // Versions pruned since the last batch are skipped.
+    let first_version = max(
+        next_version
+            .unwrap_or_else(|| num_committed_versions.saturating_sub(config.lookback_versions)),
+        db.ledger_pruner.get_min_readable_version(),
+    );
+    if first_version >= num_committed_versions {
+        return Ok(true);
+    }
+    let num_versions = min(
+        config.batch_size as u64,
+        num_committed_versions - first_version,
+    );
+
+    for inconsistency in check_versions(db, first_version, num_versions, &ledger_info)? {
+        error!(
+            kind = inconsistency.kind(),
+            "Inconsistent data in the DB: {}", inconsistency
+        );
+        CONSISTENCY_CHECKER_INCONSISTENCIES
+            .with_label_values(&[inconsistency.kind()])
+            .inc();
+    }
+    *next_version = Some(first_version + num_versions);
+    CONSISTENCY_CHECKER_VERSION.set((first_version + num_versions - 1) as i64);
+
+    Ok(first_version + num_versions == num_committed_versions)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,57 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    schema::transaction_info::TransactionInfoSchema,
+    test_helper::{arb_blocks_to_commit, save_blocks},
+};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_check_consistency(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let num_versions = save_blocks(&db, &input);
+        prop_assert_eq!(db.check_consistency(0, num_versions).unwrap(), vec![]);
+        prop_assert!(db.check_consistency(0, num_versions + 1).is_err());
+
+        // Corrupt the transaction info of the latest version.
+        let version = num_versions - 1;
+        let txn_info = db.ledger_store.get_transaction_info(version).unwrap();
+        let corrupted_txn_info = TransactionInfo::new(
+            HashValue::zero(),
+            txn_info.state_change_hash(),
+            txn_info.event_root_hash(),
+            txn_info.state_checkpoint_hash(),
+            txn_info.gas_used(),
+            txn_info.status().clone(),
+        );
+        db.ledger_db
+            .put::<TransactionInfoSchema>(&version, &corrupted_txn_info)
+            .unwrap();
+        prop_assert_eq!(
+            db.check_consistency(0, num_versions).unwrap(),
+            vec![
+                Inconsistency::TransactionHash {
+                    version,
+                    expected: HashValue::zero(),
+                    actual: txn_info.transaction_hash(),
+                },
+                Inconsistency::TransactionAccumulator {
+                    first_version: 0,
+                    num_versions,
+                    ledger_version: version,
+                },
+            ]
+        );
+        // The versions before it are still consistent.
+        if version > 0 {
+            prop_assert_eq!(db.check_consistency(0, version).unwrap(), vec![]);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides the functionalities operators use to look into how the DB takes up disk
+//! space and to reclaim it, e.g. during disk-pressure incidents: RocksDB statistics per column
+//! family, manual compactions, and estimates of the space pruning would reclaim. It also provides
+//! the backfill of the indices of DBs written before the indices were maintained.
+
+use crate::{
+    db_options::{
+        ledger_db_column_families, state_kv_db_column_families, state_merkle_db_column_families,
+    },
+    pruner::pruner_metadata::{PrunerMetadata, PrunerTag},
+    pruner_metadata::PrunerMetadataSchema,
+    schema::state_value::StateValueSchema,
+    state_kv_db::shard_name,
+    state_store::put_resource_by_module,
+    AptosDB, PrunerManager, LEDGER_DB_NAME, STATE_MERKLE_DB_NAME,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use schemadb::{ColumnFamilyName, ReadOptions, SchemaBatch, DB};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// RocksDB statistics of a column family.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ColumnFamilyStats {
+    pub db_name: String,
+    pub cf_name: String,
+    pub estimate_num_keys: u64,
+    /// The size of the data not deleted or overwritten, which is what the column family would
+    /// take up once fully compacted.
+    pub estimate_live_data_size: u64,
+    pub total_sst_files_size: u64,
+    pub estimate_pending_compaction_bytes: u64,
+    /// The live SST files of the column family by level, for the levels with any.
+    pub levels: Vec<LevelStats>,
+}
+
+/// Statistics of the live SST files in a level of a column family.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LevelStats {
+    pub level: i32,
+    pub num_files: u64,
+    pub size: u64,
+    pub num_entries: u64,
+    pub num_deletions: u64,
+}
+
+/// An estimate of the space the pruner of a DB reclaims once caught up with its prune window.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PrunableSpace {
+    pub db_name: String,
+    pub pruner_enabled: bool,
+    pub min_readable_version: Version,
+    /// The version the pruner prunes until, given the latest version and the prune window.
+    pub prune_target_version: Version,
+    /// The highest version reported backed up, past which the pruner doesn't prune unless
+    /// configured to ignore the backup.
+    pub backed_up_version: Option<Version>,
+    pub estimate_live_data_size: u64,
+    /// The share of the live data taken up by the versions before the prune target, assuming
+    /// versions take up the same space on average.
+    pub estimate_reclaimable_bytes: u64,
+}
+
+/// The RocksDB instances of the DB by name, including each shard of the state values if sharded.
+fn dbs(db: &AptosDB) -> Vec<(String, &DB, Vec<ColumnFamilyName>)> {
+    let mut dbs = vec![
+        (
+            LEDGER_DB_NAME.to_string(),
+            db.ledger_db.as_ref(),
+            ledger_db_column_families(),
+        ),
+        (
+            STATE_MERKLE_DB_NAME.to_string(),
+            db.state_merkle_db.as_ref(),
+            state_merkle_db_column_families(),
+        ),
+    ];
+    for (shard_id, shard) in db.state_kv_db.shards().iter().enumerate() {
+        dbs.push((
+            shard_name(shard_id),
+            shard.as_ref(),
+            state_kv_db_column_families(),
+        ));
+    }
+    dbs
+}
+
+pub(crate) fn get_db_stats(db: &AptosDB) -> Result<Vec<ColumnFamilyStats>> {
+    let mut stats = Vec::new();
+    for (db_name, rocksdb, cf_names) in dbs(db) {
+        let mut levels_by_cf: BTreeMap<String, BTreeMap<i32, LevelStats>> = BTreeMap::new();
+        for file in rocksdb.get_live_files()? {
+            let level = levels_by_cf
+                .entry(file.column_family_name)
+                .or_default()
+                .entry(file.level)
+                .or_insert_with(|| LevelStats {
+                    level: file.level,
+                    ..Default::default()
+                });
+            level.num_files += 1;
+            level.size += file.size as u64;
+            level.num_entries += file.num_entries;
+            level.num_deletions += file.num_deletions;
+        }
+
+        for cf_name in cf_names {
+            stats.push(ColumnFamilyStats {
+                db_name: db_name.clone(),
+                cf_name: cf_name.to_string(),
+                estimate_num_keys: rocksdb.get_property(cf_name, "rocksdb.estimate-num-keys")?,
+                estimate_live_data_size: rocksdb
+                    .get_property(cf_name, "rocksdb.estimate-live-data-size")?,
+                total_sst_files_size: rocksdb
+                    .get_property(cf_name, "rocksdb.total-sst-files-size")?,
+                estimate_pending_compaction_bytes: rocksdb
+                    .get_property(cf_name, "rocksdb.estimate-pending-compaction-bytes")?,
+                levels: levels_by_cf
+                    .remove(cf_name)
+                    .unwrap_or_default()
+                    .into_values()
+                    .collect(),
+            });
+        }
+    }
+    Ok(stats)
+}
+
+pub(crate) fn compact(db: &AptosDB, db_name: &str, cf_name: &str) -> Result<()> {
+    let (_, rocksdb, cf_names) = dbs(db)
+        .into_iter()
+        .find(|(name, _, _)| name == db_name)
+        .ok_or_else(|| format_err!("Unknown DB {}.", db_name))?;
+    ensure!(
+        cf_names.contains(&cf_name),
+        "Unknown column family {} in {}.",
+        cf_name,
+        db_name
+    );
+    rocksdb.compact_cf(cf_name)
+}
+
+pub(crate) fn estimate_prunable_space(db: &AptosDB) -> Result<Vec<PrunableSpace>> {
+    let latest_version = match db.ledger_store.get_latest_ledger_info_option() {
+        Some(ledger_info) => ledger_info.ledger_info().version(),
+        None => return Ok(Vec::new()),
+    };
+    let backed_up_version = db.backup_progress.backed_up_version();
+
+    let pruners: Vec<(&str, &DB, Vec<ColumnFamilyName>, &dyn PrunerManager)> = vec![
+        (
+            LEDGER_DB_NAME,
+            &db.ledger_db,
+            ledger_db_column_families(),
+            &db.ledger_pruner,
+        ),
+        (
+            STATE_MERKLE_DB_NAME,
+            &db.state_merkle_db,
+            state_merkle_db_column_families(),
+            &db.state_pruner,
+        ),
+    ];
+    pruners
+        .into_iter()
+        .map(|(db_name, rocksdb, cf_names, pruner)| {
+            let mut estimate_live_data_size = 0;
+            for cf_name in cf_names {
+                estimate_live_data_size +=
+                    rocksdb.get_property(cf_name, "rocksdb.estimate-live-data-size")?;
+            }
+            let min_readable_version = pruner.get_min_readable_version();
+            let prune_target_version = latest_version.saturating_sub(pruner.get_pruner_window());
+            let num_versions = (latest_version + 1).saturating_sub(min_readable_version);
+            let num_prunable_versions = prune_target_version.saturating_sub(min_readable_version);
+            let estimate_reclaimable_bytes = if num_versions == 0 {
+                0
+            } else {
+                (estimate_live_data_size as u128 * num_prunable_versions as u128
+                    / num_versions as u128) as u64
+            };
+
+            Ok(PrunableSpace {
+                db_name: db_name.to_string(),
+                pruner_enabled: pruner.is_pruner_enabled(),
+                min_readable_version,
+                prune_target_version,
+                backed_up_version,
+                estimate_live_data_size,
+                estimate_reclaimable_bytes,
+            })
+        })
+        .collect()
+}
+
+/// The number of resources the backfill of the index of resources by module indexes per write.
+const RESOURCE_BY_MODULE_BACKFILL_BATCH_SIZE: usize = 10_000;
+
+pub(crate) fn backfill_resource_by_module_index(db: &AptosDB) -> Result<usize> {
+    let mut num_indexed = 0;
+    for shard_id in 0..db.state_kv_db.num_shards() {
+        let mut read_opts = ReadOptions::default();
+        // Go through the keys in total order, rather than within the prefix of the first one.
+        read_opts.set_total_order_seek(true);
+        let mut iter = db
+            .state_kv_db
+            .db_shard(shard_id)
+            .iter::<StateValueSchema>(read_opts)?;
+        iter.seek_to_first();
+
+        let mut batch = SchemaBatch::new();
+        let mut batch_size = 0;
+        let mut prev_key = None;
+        for item in iter {
+            let ((state_key, _version), state_value) = item?;
+            // The versions of a key come in a row, the key is indexed if it has a value at any.
+            if state_value.is_none() || prev_key.as_ref() == Some(&state_key) {
+                continue;
+            }
+            if put_resource_by_module(&state_key, &batch)? {
+                batch_size += 1;
+            }
+            prev_key = Some(state_key);
+            if batch_size == RESOURCE_BY_MODULE_BACKFILL_BATCH_SIZE {
+                db.ledger_db
+                    .write_schemas(std::mem::replace(&mut batch, SchemaBatch::new()))?;
+                num_indexed += batch_size;
+                batch_size = 0;
+            }
+        }
+        db.ledger_db.write_schemas(batch)?;
+        num_indexed += batch_size;
+        info!(
+            shard_id = shard_id,
+            num_indexed = num_indexed,
+            "Backfilled the index of resources by module."
+        );
+    }
+
+    // The resources committed since the iterators were created are indexed at commit, so the index
+    // lists the resources at any version now.
+    db.ledger_db.put::<PrunerMetadataSchema>(
+        &PrunerTag::ResourceByModuleIndex,
+        &PrunerMetadata::LatestVersion(0),
+    )?;
+    Ok(num_indexed)
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,54 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::test_helper::{arb_blocks_to_commit, save_blocks};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_db_admin(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        prop_assert_eq!(db.estimate_prunable_space().unwrap(), vec![]);
+        let num_versions = save_blocks(&db, &input);
+
+        let stats = db.get_db_stats().unwrap();
+        prop_assert_eq!(
+            stats.len(),
+            ledger_db_column_families().len() + state_merkle_db_column_families().len()
+        );
+
+        db.compact(LEDGER_DB_NAME, "transaction").unwrap();
+        let stats = db.get_db_stats().unwrap();
+        let transaction_stats = stats
+            .iter()
+            .find(|stats| stats.db_name == LEDGER_DB_NAME && stats.cf_name == "transaction")
+            .unwrap();
+        // Compaction flushes the memtable, so all transactions are in the SST files now.
+        prop_assert!(transaction_stats.levels.iter().all(|level| level.level > 0));
+        prop_assert_eq!(
+            transaction_stats
+                .levels
+                .iter()
+                .map(|level| level.num_entries)
+                .sum::<u64>(),
+            num_versions
+        );
+        prop_assert!(db.compact(LEDGER_DB_NAME, "unknown").is_err());
+        prop_assert!(db.compact("unknown", "transaction").is_err());
+
+        // Without a pruner, the prune window is 0.
+        let prunable_space = db.estimate_prunable_space().unwrap();
+        prop_assert_eq!(prunable_space.len(), 2);
+        for space in prunable_space {
+            prop_assert!(!space.pruner_enabled);
+            prop_assert_eq!(space.min_readable_version, 0);
+            prop_assert_eq!(space.prune_target_version, num_versions - 1);
+            prop_assert!(space.estimate_reclaimable_bytes <= space.estimate_live_data_size);
+        }
+    }
+}
@@ -1,18 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::schema::*;
+use crate::schema::{resource_by_module, *};
+use aptos_config::config::RocksdbConfig;
+use aptos_rocksdb_options::{gen_cf_options, gen_cf_options_from_cf_config};
 use aptos_types::transaction::Version;
 use schemadb::{
-    ColumnFamilyDescriptor, ColumnFamilyName, DBCompressionType, Options, SliceTransform,
-    DEFAULT_COLUMN_FAMILY_NAME,
+    ColumnFamilyDescriptor, ColumnFamilyName, Options, SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
 
 const VERSION_SIZE: usize = std::mem::size_of::<Version>();
 
+/// Bits per key of the bloom filter of the index of resources by module, unless tuned otherwise.
+const RESOURCE_BY_MODULE_BLOOM_FILTER_BITS_PER_KEY: u32 = 10;
+
 pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
+        AUTH_KEY_BY_ACCOUNT_CF_NAME,
+        BLOCK_GAS_LIMIT_REACHED_CF_NAME,
         EPOCH_BY_VERSION_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
@@ -20,6 +26,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         EVENT_CF_NAME,
         LEDGER_COUNTERS_CF_NAME,
         LEDGER_INFO_CF_NAME,
+        RESOURCE_BY_MODULE_CF_NAME,
         STALE_STATE_VALUE_INDEX_CF_NAME,
         STATE_VALUE_CF_NAME,
         TRANSACTION_CF_NAME,
@@ -42,12 +49,35 @@ pub(super) fn state_merkle_db_column_families() -> Vec<ColumnFamilyName> {
     ]
 }
 
-pub(super) fn gen_ledger_cfds() -> Vec<ColumnFamilyDescriptor> {
-    let cfs = ledger_db_column_families();
+/// The column families of each shard of the state values, when sharded.
+pub(super) fn state_kv_db_column_families() -> Vec<ColumnFamilyName> {
+    vec![
+        /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
+        STALE_STATE_VALUE_INDEX_CF_NAME,
+        STATE_VALUE_CF_NAME,
+        DB_METADATA_CF_NAME,
+    ]
+}
+
+pub(super) fn gen_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
+    gen_cfds_with_state_values(rocksdb_config, ledger_db_column_families())
+}
+
+pub(super) fn gen_state_kv_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
+    gen_cfds_with_state_values(rocksdb_config, state_kv_db_column_families())
+}
+
+fn gen_cfds_with_state_values(
+    rocksdb_config: &RocksdbConfig,
+    cfs: Vec<ColumnFamilyName>,
+) -> Vec<ColumnFamilyDescriptor> {
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
-        let mut cf_opts = Options::default();
-        cf_opts.set_compression_type(DBCompressionType::Lz4);
+        let mut cf_opts = if cf_name == RESOURCE_BY_MODULE_CF_NAME {
+            gen_resource_by_module_cf_options(rocksdb_config)
+        } else {
+            gen_cf_options(rocksdb_config, cf_name)
+        };
         // set cf options separately
         if cf_name == STATE_VALUE_CF_NAME {
             // TODO(lightmark): Use the defaults for bloom filter for now, will tune later.
@@ -60,17 +90,34 @@ pub(super) fn gen_ledger_cfds() -> Vec<ColumnFamilyDescriptor> {
     cfds
 }
 
-pub(super) fn gen_state_merkle_cfds() -> Vec<ColumnFamilyDescriptor> {
+pub(super) fn gen_state_merkle_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = state_merkle_db_column_families();
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
-        let mut cf_opts = Options::default();
-        cf_opts.set_compression_type(DBCompressionType::Lz4);
+        let cf_opts = gen_cf_options(rocksdb_config, cf_name);
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
     }
     cfds
 }
 
+/// The index of resources by module is only read by (address, module) prefix, so its bloom filter
+/// is built on the prefixes, letting the reads for accounts without resources of a module skip
+/// most SST files.
+fn gen_resource_by_module_cf_options(rocksdb_config: &RocksdbConfig) -> Options {
+    let mut cf_config = rocksdb_config.cf_config(RESOURCE_BY_MODULE_CF_NAME).clone();
+    if !rocksdb_config
+        .column_families
+        .contains_key(RESOURCE_BY_MODULE_CF_NAME)
+    {
+        cf_config.bloom_filter_bits_per_key = RESOURCE_BY_MODULE_BLOOM_FILTER_BITS_PER_KEY;
+    }
+    let mut cf_opts = gen_cf_options_from_cf_config(&cf_config);
+    cf_opts.set_prefix_extractor(SliceTransform::create_fixed_prefix(
+        resource_by_module::PREFIX_LENGTH,
+    ));
+    cf_opts
+}
+
 fn state_key_extractor(state_value_raw_key: &[u8]) -> &[u8] {
     &state_value_raw_key[..(state_value_raw_key.len() - VERSION_SIZE)]
 }
@@ -11,8 +11,9 @@ use crate::{
     errors::AptosDbError,
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        block_gas_limit_reached::BlockGasLimitReachedSchema, event::EventSchema,
+        event_accumulator::EventAccumulatorSchema, event_by_key::EventByKeySchema,
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -95,6 +96,21 @@ impl EventStore {
             })
     }
 
+    /// Gets the events at the given versions and indices, in order, in a single multi get.
+    pub fn get_events_by_version_and_index(
+        &self,
+        keys: &[(Version, u64)],
+    ) -> Result<Vec<ContractEvent>> {
+        keys.iter()
+            .zip(self.db.multi_get::<EventSchema>(keys)?)
+            .map(|((version, index), event)| {
+                event.ok_or_else(|| {
+                    AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)).into()
+                })
+            })
+            .collect()
+    }
+
     pub fn get_txn_ver_by_seq_num(&self, event_key: &EventKey, seq_num: u64) -> Result<u64> {
         let (ver, _) = self
             .db
@@ -301,6 +317,24 @@ impl EventStore {
         Ok((first_version, payload))
     }
 
+    /// Returns the block gas limit the transactions of the block starting at `block_version` used
+    /// up, if they did.
+    pub fn get_block_gas_limit_reached(&self, block_version: Version) -> Result<Option<u64>> {
+        self.db.get::<BlockGasLimitReachedSchema>(&block_version)
+    }
+
+    /// Records that the transactions of the block starting at `block_version` used up the block
+    /// gas limit `gas_limit` in effect for it.
+    pub fn put_block_gas_limit_reached(
+        &self,
+        block_version: Version,
+        gas_limit: u64,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        cs.batch
+            .put::<BlockGasLimitReachedSchema>(&block_version, &gas_limit)
+    }
+
     /// Save contract events yielded by the transaction at `version` and return root hash of the
     /// event accumulator formed by these events.
     pub fn put_events(
@@ -504,6 +538,7 @@ impl EventStore {
             current_version += 1;
         }
         self.prune_event_accumulator(start, end, db_batch)?;
+        db_batch.delete_range::<BlockGasLimitReachedSchema>(&start, &end)?;
         Ok(())
     }
 
@@ -46,16 +46,8 @@ pub struct LedgerStore {
 impl LedgerStore {
     pub fn new(db: Arc<DB>) -> Self {
         // Upon restart, read the latest ledger info and signatures and cache them in memory.
-        let ledger_info = {
-            let mut iter = db
-                .iter::<LedgerInfoSchema>(ReadOptions::default())
-                .expect("Constructing iterator should work.");
-            iter.seek_to_last();
-            iter.next()
-                .transpose()
-                .expect("Reading latest ledger info from DB should work.")
-                .map(|kv| kv.1)
-        };
+        let ledger_info = Self::read_latest_ledger_info(&db)
+            .expect("Reading latest ledger info from DB should work.");
 
         Self {
             db,
@@ -63,6 +55,21 @@ impl LedgerStore {
         }
     }
 
+    fn read_latest_ledger_info(db: &DB) -> Result<Option<LedgerInfoWithSignatures>> {
+        let mut iter = db.iter::<LedgerInfoSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        Ok(iter.next().transpose()?.map(|kv| kv.1))
+    }
+
+    /// Reads the latest ledger info and signatures from the DB into the cache again, for DBs
+    /// written to by another instance, e.g. secondary instances catching up with their primary.
+    pub fn reload_latest_ledger_info(&self) -> Result<()> {
+        if let Some(ledger_info_with_sigs) = Self::read_latest_ledger_info(&self.db)? {
+            self.set_latest_ledger_info(ledger_info_with_sigs);
+        }
+        Ok(())
+    }
+
     pub fn get_epoch(&self, version: Version) -> Result<u64> {
         let mut iter = self
             .db
@@ -167,6 +174,26 @@ impl LedgerStore {
             .ok_or_else(|| format_err!("No TransactionInfo at version {}", version))
     }
 
+    /// Get the `num_transaction_infos` transaction infos starting from `start_version`, in a
+    /// single multi get.
+    pub fn get_transaction_infos(
+        &self,
+        start_version: Version,
+        num_transaction_infos: u64,
+    ) -> Result<Vec<TransactionInfo>> {
+        let end_version = start_version
+            .checked_add(num_transaction_infos)
+            .ok_or_else(|| format_err!("too many transaction infos requested"))?;
+        let versions = (start_version..end_version).collect::<Vec<_>>();
+        versions
+            .iter()
+            .zip(self.db.multi_get::<TransactionInfoSchema>(&versions)?)
+            .map(|(version, txn_info)| {
+                txn_info.ok_or_else(|| format_err!("No TransactionInfo at version {}", version))
+            })
+            .collect()
+    }
+
     pub fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
         let mut iter = self
             .db
@@ -14,6 +14,8 @@
 pub mod test_helper;
 
 pub mod backup;
+pub mod consistency_checker;
+pub mod db_admin;
 pub mod errors;
 pub mod metrics;
 pub mod schema;
@@ -23,9 +25,12 @@ mod db_options;
 mod event_store;
 mod ledger_counters;
 mod ledger_store;
+mod node_cache;
 mod pruner;
+mod state_kv_db;
 mod state_merkle_db;
 mod state_store;
+mod state_value_cache;
 mod system_store;
 mod transaction_store;
 
@@ -39,7 +44,7 @@ use crate::{
     change_set::{ChangeSet, SealedChangeSet},
     db_options::{
         gen_ledger_cfds, gen_state_merkle_cfds, ledger_db_column_families,
-        state_merkle_db_column_families,
+        state_kv_db_column_families, state_merkle_db_column_families,
     },
     errors::AptosDbError,
     event_store::EventStore,
@@ -49,15 +54,19 @@ use crate::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
         OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES,
     },
+    node_cache::{NodeCache, DEFAULT_NODE_CACHE_CAPACITY_BYTES},
     pruner::{pruner_manager::PrunerManager, utils},
     schema::*,
+    state_kv_db::StateKvDb,
     state_store::StateStore,
+    state_value_cache::{StateValueCache, DEFAULT_STATE_VALUE_CACHE_VERSIONS},
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE,
+    PrunerConfig, RocksdbConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG,
+    TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE,
 };
 use aptos_crypto::hash::HashValue;
 use aptos_infallible::Mutex;
@@ -66,11 +75,12 @@ use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_types::{
     account_address::AccountAddress,
     account_config::{new_block_event_key, NewBlockEvent},
-    contract_event::EventWithVersion,
+    contract_event::{EventWithTransactionContext, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{access_path_for_config, BlockGasLimit, OnChainConfig},
     proof::{
         accumulator::InMemoryAccumulator, AccumulatorConsistencyProof, SparseMerkleProofExt,
         TransactionInfoListWithProof,
@@ -92,6 +102,7 @@ use aptos_types::{
 use aptos_vm::data_cache::AsMoveResolver;
 use aptosdb_indexer::Indexer;
 use itertools::zip_eq;
+use move_deps::move_core_types::language_storage::{ModuleId, TypeTag};
 use move_deps::move_resource_viewer::MoveValueAnnotator;
 use once_cell::sync::Lazy;
 use schemadb::DB;
@@ -105,17 +116,21 @@ use std::{
     time::{Duration, Instant},
 };
 
+use crate::consistency_checker::Inconsistency;
+use crate::db_admin::{ColumnFamilyStats, PrunableSpace};
 use crate::pruner::{
-    ledger_pruner_manager::LedgerPrunerManager, ledger_store::ledger_store_pruner::LedgerPruner,
-    state_pruner_manager::StatePrunerManager, state_store::StateMerklePruner,
+    backup_progress::BackupProgress, ledger_pruner_manager::LedgerPrunerManager,
+    ledger_store::ledger_store_pruner::LedgerPruner, state_pruner_manager::StatePrunerManager,
+    state_store::StateMerklePruner,
 };
 use storage_interface::{
     state_delta::StateDelta, state_view::DbStateView, DbReader, DbWriter, ExecutedTrees, Order,
-    StateSnapshotReceiver,
+    StateSnapshotReceiver, TransactionFilter, TransactionType,
 };
 
 pub const LEDGER_DB_NAME: &str = "ledger_db";
 pub const STATE_MERKLE_DB_NAME: &str = "state_merkle_db";
+pub const STATE_KV_DB_NAME: &str = "state_kv_db";
 
 const MAX_LIMIT: u64 = 5000;
 
@@ -170,6 +185,27 @@ fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<(
     }
 }
 
+/// Returns the type a `TransactionFilter` knows the transaction as, if any.
+fn type_of_transaction(txn: &Transaction) -> Option<TransactionType> {
+    match txn {
+        Transaction::UserTransaction(_) => Some(TransactionType::User),
+        Transaction::BlockMetadata(_) => Some(TransactionType::BlockMetadata),
+        Transaction::StateCheckpoint(_) => Some(TransactionType::StateCheckpoint),
+        Transaction::GenesisTransaction(_) => None,
+    }
+}
+
+/// Takes the item at `version` from an iterator that yields one item per version.
+fn next_at_version<T>(
+    iter: &mut impl Iterator<Item = Result<T>>,
+    data_type: &str,
+    version: Version,
+) -> Result<T> {
+    iter.next().transpose()?.ok_or_else(|| {
+        AptosDbError::NotFound(format!("{} at version {}", data_type, version)).into()
+    })
+}
+
 fn error_if_version_is_pruned(
     pruner: &(dyn PrunerManager),
     data_type: &str,
@@ -257,58 +293,89 @@ impl Drop for RocksdbPropertyReporter {
 pub struct AptosDB {
     ledger_db: Arc<DB>,
     state_merkle_db: Arc<DB>,
+    state_kv_db: Arc<StateKvDb>,
+    node_cache: Arc<NodeCache>,
     event_store: Arc<EventStore>,
     ledger_store: Arc<LedgerStore>,
     state_store: Arc<StateStore>,
+    state_value_cache: Arc<StateValueCache>,
     system_store: Arc<SystemStore>,
     transaction_store: Arc<TransactionStore>,
     state_pruner: StatePrunerManager,
     ledger_pruner: LedgerPrunerManager,
+    backup_progress: Arc<BackupProgress>,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     ledger_commit_lock: std::sync::Mutex<()>,
     indexer: Option<Indexer>,
+    /// Whether this is a secondary instance, tailing the DB of another by catching up with it.
+    secondary: bool,
 }
 
 impl AptosDB {
     fn new_with_dbs(
         ledger_rocksdb: DB,
         state_merkle_rocksdb: DB,
+        state_kv_shards: Vec<DB>,
         pruner_config: PrunerConfig,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
         hack_for_tests: bool,
     ) -> Self {
         let arc_ledger_rocksdb = Arc::new(ledger_rocksdb);
         let arc_state_merkle_rocksdb = Arc::new(state_merkle_rocksdb);
+        let state_kv_db = Arc::new(StateKvDb::new(
+            Arc::clone(&arc_ledger_rocksdb),
+            state_kv_shards.into_iter().map(Arc::new).collect(),
+        ));
+        let backup_progress = Arc::new(BackupProgress::new(Arc::clone(&arc_ledger_rocksdb)));
+        let pruner_backup_progress =
+            (!pruner_config.ignore_backup_progress).then(|| Arc::clone(&backup_progress));
+        let node_cache = Arc::new(NodeCache::new(DEFAULT_NODE_CACHE_CAPACITY_BYTES));
         let state_pruner = StatePrunerManager::new(
             Arc::clone(&arc_state_merkle_rocksdb),
+            Arc::clone(&state_kv_db),
+            Arc::clone(&node_cache),
             pruner_config.state_merkle_pruner_config,
+            pruner_config.schedule,
+            pruner_backup_progress.clone(),
         );
         let ledger_pruner = LedgerPrunerManager::new(
             Arc::clone(&arc_ledger_rocksdb),
             pruner_config.ledger_pruner_config,
+            pruner_config.schedule,
+            pruner_backup_progress,
         );
 
         AptosDB {
             ledger_db: Arc::clone(&arc_ledger_rocksdb),
             state_merkle_db: Arc::clone(&arc_state_merkle_rocksdb),
+            state_kv_db: Arc::clone(&state_kv_db),
+            node_cache: Arc::clone(&node_cache),
             event_store: Arc::new(EventStore::new(Arc::clone(&arc_ledger_rocksdb))),
             ledger_store: Arc::new(LedgerStore::new(Arc::clone(&arc_ledger_rocksdb))),
             state_store: Arc::new(StateStore::new(
                 Arc::clone(&arc_ledger_rocksdb),
                 Arc::clone(&arc_state_merkle_rocksdb),
+                state_kv_db,
+                node_cache,
                 target_snapshot_size,
+                target_snapshot_interval_in_versions,
                 hack_for_tests,
+                state_pruner.commit_tracker(),
             )),
+            state_value_cache: Arc::new(StateValueCache::new(DEFAULT_STATE_VALUE_CACHE_VERSIONS)),
             system_store: Arc::new(SystemStore::new(Arc::clone(&arc_ledger_rocksdb))),
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&arc_ledger_rocksdb))),
             state_pruner,
             ledger_pruner,
+            backup_progress,
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(
                 Arc::clone(&arc_ledger_rocksdb),
                 Arc::clone(&arc_state_merkle_rocksdb),
             ),
             ledger_commit_lock: std::sync::Mutex::new(()),
             indexer: None,
+            secondary: false,
         }
     }
 
@@ -319,11 +386,27 @@ impl AptosDB {
         rocksdb_configs: RocksdbConfigs,
         enable_indexer: bool,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
     ) -> Result<Self> {
         ensure!(
             pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
             "Do not set prune_window when opening readonly.",
         );
+        ensure!(
+            target_snapshot_interval_in_versions > 0,
+            "target_snapshot_interval_in_versions must be positive.",
+        );
+
+        rocksdb_configs
+            .ledger_db_config
+            .validate(LEDGER_DB_NAME, &ledger_db_column_families())?;
+        rocksdb_configs
+            .state_merkle_db_config
+            .validate(STATE_MERKLE_DB_NAME, &state_merkle_db_column_families())?;
+        rocksdb_configs
+            .state_kv_db_config
+            .validate(STATE_KV_DB_NAME, &state_kv_db_column_families())?;
+        rocksdb_configs.state_kv_sharding.validate()?;
 
         let ledger_db_path = db_root_path.as_ref().join(LEDGER_DB_NAME);
         let state_merkle_db_path = db_root_path.as_ref().join(STATE_MERKLE_DB_NAME);
@@ -350,22 +433,32 @@ impl AptosDB {
                     &gen_rocksdb_options(&rocksdb_configs.ledger_db_config, false),
                     ledger_db_path.clone(),
                     "ledger_db",
-                    gen_ledger_cfds(),
+                    gen_ledger_cfds(&rocksdb_configs.ledger_db_config),
                 )?,
                 DB::open_cf(
                     &gen_rocksdb_options(&rocksdb_configs.state_merkle_db_config, false),
                     state_merkle_db_path.clone(),
                     "state_merkle_db",
-                    gen_state_merkle_cfds(),
+                    gen_state_merkle_cfds(&rocksdb_configs.state_merkle_db_config),
                 )?,
             )
         };
 
+        let state_kv_shards = StateKvDb::open_shards(
+            db_root_path.as_ref(),
+            &ledger_db,
+            &rocksdb_configs.state_kv_sharding,
+            &rocksdb_configs.state_kv_db_config,
+            readonly,
+        )?;
+
         let mut myself = Self::new_with_dbs(
             ledger_db,
             state_merkle_db,
+            state_kv_shards,
             pruner_config,
             target_snapshot_size,
+            target_snapshot_interval_in_versions,
             readonly,
         );
 
@@ -376,6 +469,7 @@ impl AptosDB {
         info!(
             ledger_db_path = ledger_db_path,
             state_merkle_db_path = state_merkle_db_path,
+            num_state_kv_shards = rocksdb_configs.state_kv_sharding.num_shards,
             time_ms = %instant.elapsed().as_millis(),
             "Opened AptosDB (LedgerDB + StateMerkleDB).",
         );
@@ -395,7 +489,15 @@ impl AptosDB {
             "Opened AptosDB Indexer.",
         );
 
-        if indexer.next_version() < ledger_next_version {
+        // The authentication keys are indexed in the ledger db, so the indexer may be ahead of
+        // them if killed in between. DBs from before they recorded how far they are indexed have
+        // them indexed as far as the indexer.
+        let auth_keys_next_version = self
+            .transaction_store
+            .get_auth_key_index_next_version()?
+            .unwrap_or_else(|| indexer.next_version());
+        let catch_up_from = std::cmp::min(indexer.next_version(), auth_keys_next_version);
+        if catch_up_from < ledger_next_version {
             let state_view = DbStateView {
                 db: self.state_store.clone(),
                 version: Some(ledger_next_version - 1),
@@ -404,7 +506,7 @@ impl AptosDB {
             let annotator = MoveValueAnnotator::new(&resolver);
 
             const BATCH_SIZE: Version = 10000;
-            let mut next_version = indexer.next_version();
+            let mut next_version = catch_up_from;
             while next_version < ledger_next_version {
                 info!(next_version = next_version, "AptosDB Indexer catching up. ",);
                 let end_version = std::cmp::min(ledger_next_version, next_version + BATCH_SIZE);
@@ -412,7 +514,24 @@ impl AptosDB {
                     .transaction_store
                     .get_write_sets(next_version, end_version)?;
                 let write_sets_ref: Vec<_> = write_sets.iter().collect();
-                indexer.index_with_annotator(&annotator, next_version, &write_sets_ref)?;
+                let indexed = indexer.next_version().saturating_sub(next_version) as usize;
+                if indexed < write_sets.len() {
+                    indexer.index_with_annotator(
+                        &annotator,
+                        next_version + indexed as Version,
+                        &write_sets_ref[indexed..],
+                    )?;
+                }
+                let indexed = auth_keys_next_version.saturating_sub(next_version) as usize;
+                if indexed < write_sets.len() {
+                    let mut cs = ChangeSet::new();
+                    self.transaction_store.put_auth_keys(
+                        next_version + indexed as Version,
+                        &write_sets[indexed..],
+                        &mut cs,
+                    )?;
+                    self.ledger_db.write_schemas(cs.batch)?;
+                }
 
                 next_version = end_version;
             }
@@ -423,6 +542,68 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Keeps the coin balance index of the indexer up to date, backfilling it first if it is
+    /// behind, e.g. because it has never been enabled. The backfill starts from the latest state
+    /// snapshot, so it doesn't need the pruned write sets, and catches up from there with the write
+    /// sets of the versions since.
+    pub fn enable_balance_index(&mut self) -> Result<()> {
+        let indexer = match &mut self.indexer {
+            Some(indexer) => indexer,
+            None => bail!("The balance index requires the indexer to be enabled."),
+        };
+        let next_version = indexer.next_version();
+        if indexer.balances_next_version() < next_version {
+            let (snapshot_version, _) = self
+                .state_store
+                .get_state_snapshot_before(next_version)?
+                .ok_or_else(|| {
+                format_err!("No state snapshot to backfill the balance index.")
+            })?;
+            info!(
+                snapshot_version = snapshot_version,
+                "Backfilling the balance index from the state snapshot.",
+            );
+            indexer.backfill_balances(
+                self.state_store
+                    .get_state_key_and_value_iter(snapshot_version, HashValue::zero())?,
+                snapshot_version,
+            )?;
+
+            const BATCH_SIZE: Version = 10000;
+            let mut version = snapshot_version + 1;
+            while version < next_version {
+                let end_version = std::cmp::min(next_version, version + BATCH_SIZE);
+                let write_sets = self
+                    .transaction_store
+                    .get_write_sets(version, end_version)?;
+                let write_sets_ref: Vec<_> = write_sets.iter().collect();
+                indexer.index_balances(version, &write_sets_ref)?;
+                version = end_version;
+            }
+            info!("Balance index caught up.");
+        }
+        indexer.enable_balance_index()
+    }
+
+    fn balance_index(&self) -> Result<&Indexer> {
+        match &self.indexer {
+            Some(indexer) if indexer.balance_index_enabled() => Ok(indexer),
+            _ => bail!("Balance index not enabled."),
+        }
+    }
+
+    /// Changes the memory the cache of state merkle tree nodes may take up, in bytes. 0 disables
+    /// the cache.
+    pub fn set_node_cache_capacity(&self, capacity_bytes: usize) {
+        self.node_cache.set_capacity(capacity_bytes)
+    }
+
+    /// Changes the number of most recent versions the state values written by which are cached
+    /// once committed. 0 disables the cache.
+    pub fn set_state_value_cache_versions(&self, versions: u64) {
+        self.state_value_cache.set_max_versions(versions)
+    }
+
     pub fn open_as_secondary<P: AsRef<Path> + Clone>(
         db_root_path: P,
         secondary_db_root_path: P,
@@ -437,15 +618,24 @@ impl AptosDB {
         // Secondary needs `max_open_files = -1` per https://github.com/facebook/rocksdb/wiki/Secondary-instance
         rocksdb_configs.ledger_db_config.max_open_files = -1;
         rocksdb_configs.state_merkle_db_config.max_open_files = -1;
-
-        Ok(Self::new_with_dbs(
-            DB::open_cf_as_secondary(
-                &gen_rocksdb_options(&rocksdb_configs.ledger_db_config, false),
-                ledger_db_primary_path,
-                ledger_db_secondary_path,
-                "ledgerdb_sec",
-                ledger_db_column_families(),
-            )?,
+        rocksdb_configs.state_kv_db_config.max_open_files = -1;
+
+        let ledger_db = DB::open_cf_as_secondary(
+            &gen_rocksdb_options(&rocksdb_configs.ledger_db_config, false),
+            ledger_db_primary_path,
+            ledger_db_secondary_path,
+            "ledgerdb_sec",
+            ledger_db_column_families(),
+        )?;
+        let state_kv_shards = StateKvDb::open_shards_as_secondary(
+            db_root_path.as_ref(),
+            secondary_db_root_path.as_ref(),
+            &ledger_db,
+            &rocksdb_configs.state_kv_sharding,
+            &rocksdb_configs.state_kv_db_config,
+        )?;
+        let mut db = Self::new_with_dbs(
+            ledger_db,
             DB::open_cf_as_secondary(
                 &gen_rocksdb_options(&rocksdb_configs.state_merkle_db_config, false),
                 state_merkle_db_primary_path,
@@ -453,10 +643,34 @@ impl AptosDB {
                 "state_merkle_db_sec",
                 state_merkle_db_column_families(),
             )?,
+            state_kv_shards,
             NO_OP_STORAGE_PRUNER_CONFIG,
             TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
             true,
-        ))
+        );
+        db.secondary = true;
+        Ok(db)
+    }
+
+    /// Makes what the primary instance committed since this secondary instance was opened or last
+    /// caught up visible to its readers, which can be done periodically to tail the primary.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        ensure!(
+            self.secondary,
+            "Only secondary instances catch up with a primary."
+        );
+        // Catch up with the ledger last, so that the state of every version it has is there.
+        self.state_merkle_db.try_catch_up_with_primary()?;
+        for shard in self.state_kv_db.shards() {
+            shard.try_catch_up_with_primary()?;
+        }
+        self.ledger_db.try_catch_up_with_primary()?;
+
+        self.ledger_store.reload_latest_ledger_info()?;
+        self.ledger_pruner.reload_min_readable_version()?;
+        self.state_pruner.reload_min_readable_version()?;
+        Ok(())
     }
 
     #[cfg(any(test, feature = "fuzzing"))]
@@ -464,6 +678,7 @@ impl AptosDB {
         db_root_path: P,
         readonly: bool,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
         enable_indexer: bool,
     ) -> Self {
         Self::open(
@@ -473,6 +688,7 @@ impl AptosDB {
             RocksdbConfigs::default(),
             enable_indexer,
             target_snapshot_size,
+            target_snapshot_interval_in_versions,
         )
         .expect("Unable to open AptosDB")
     }
@@ -480,13 +696,29 @@ impl AptosDB {
     /// This opens db in non-readonly mode, without the pruner.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        Self::new_without_pruner(db_root_path, false, TARGET_SNAPSHOT_SIZE, false)
+        Self::new_without_pruner(
+            db_root_path,
+            false,
+            TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            false,
+        )
     }
 
-    /// This opens db in non-readonly mode, without the pruner, and with the indexer
+    /// This opens db in non-readonly mode, without the pruner, and with the indexer, including
+    /// its balance index
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test_with_indexer<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        Self::new_without_pruner(db_root_path, false, TARGET_SNAPSHOT_SIZE, true)
+        let mut db = Self::new_without_pruner(
+            db_root_path,
+            false,
+            TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            true,
+        );
+        db.enable_balance_index()
+            .expect("Unable to enable the balance index");
+        db
     }
 
     /// This opens db in non-readonly mode, without the pruner.
@@ -495,13 +727,63 @@ impl AptosDB {
         db_root_path: P,
         target_snapshot_size: usize,
     ) -> Self {
-        Self::new_without_pruner(db_root_path, false, target_snapshot_size, false)
+        Self::new_without_pruner(
+            db_root_path,
+            false,
+            target_snapshot_size,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            false,
+        )
+    }
+
+    /// This opens db in non-readonly mode, without the pruner, committing a snapshot every
+    /// `target_snapshot_interval_in_versions` versions at the latest.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_target_snapshot_interval<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
+    ) -> Self {
+        Self::new_without_pruner(
+            db_root_path,
+            false,
+            target_snapshot_size,
+            target_snapshot_interval_in_versions,
+            false,
+        )
+    }
+
+    /// This opens db in non-readonly mode, without the pruner, and with the state values sharded
+    /// across `num_shards` RocksDB instances.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_for_test_with_state_kv_shards<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        num_shards: usize,
+    ) -> Self {
+        let mut rocksdb_configs = RocksdbConfigs::default();
+        rocksdb_configs.state_kv_sharding.num_shards = num_shards;
+        Self::open(
+            db_root_path,
+            false,
+            NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+            rocksdb_configs,
+            false,
+            TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+        )
+        .expect("Unable to open AptosDB")
     }
 
     /// This opens db in non-readonly mode, without the pruner.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_readonly_for_test<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        Self::new_without_pruner(db_root_path, true, TARGET_SNAPSHOT_SIZE, false)
+        Self::new_without_pruner(
+            db_root_path,
+            true,
+            TARGET_SNAPSHOT_SIZE,
+            TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            false,
+        )
     }
 
     /// This gets the current buffered_state in StateStore.
@@ -607,6 +889,135 @@ impl AptosDB {
         })
     }
 
+    /// Scans at most `max_scanned` transactions from `start_version` for the ones matching
+    /// `filter`, which selects no sender. Transactions are only read to check their type and
+    /// transaction infos only to check their status.
+    fn scan_filtered_transaction_versions(
+        &self,
+        start_version: Version,
+        limit: u64,
+        max_scanned: u64,
+        ledger_version: Version,
+        filter: &TransactionFilter,
+    ) -> Result<(Vec<Version>, Version)> {
+        let end_version = (ledger_version + 1).min(start_version.saturating_add(max_scanned));
+        let num_versions = (end_version - start_version) as usize;
+        let mut txn_iter = filter
+            .transaction_type
+            .map(|_| {
+                self.transaction_store
+                    .get_transaction_iter(start_version, num_versions)
+            })
+            .transpose()?;
+        let mut txn_info_iter = filter
+            .success
+            .map(|_| {
+                self.ledger_store
+                    .get_transaction_info_iter(start_version, num_versions)
+            })
+            .transpose()?;
+
+        let mut versions = Vec::new();
+        for version in start_version..end_version {
+            let mut matches = true;
+            if let (Some(iter), Some(transaction_type)) =
+                (txn_iter.as_mut(), filter.transaction_type)
+            {
+                let txn = next_at_version(iter, "Transaction", version)?;
+                matches &= type_of_transaction(&txn) == Some(transaction_type);
+            }
+            if let (Some(iter), Some(success)) = (txn_info_iter.as_mut(), filter.success) {
+                let txn_info = next_at_version(iter, "TransactionInfo", version)?;
+                matches &= txn_info.status().is_success() == success;
+            }
+            if matches {
+                versions.push(version);
+                if versions.len() as u64 == limit {
+                    return Ok((versions, version + 1));
+                }
+            }
+        }
+        Ok((versions, end_version))
+    }
+
+    /// Like `scan_filtered_transaction_versions`, but scans the transactions sent by `sender`
+    /// through the account transaction index instead of the whole ledger.
+    fn scan_filtered_account_transaction_versions(
+        &self,
+        sender: AccountAddress,
+        start_version: Version,
+        limit: u64,
+        max_scanned: u64,
+        ledger_version: Version,
+        filter: &TransactionFilter,
+    ) -> Result<(Vec<Version>, Version)> {
+        // Only user transactions have a sender.
+        if filter.transaction_type.map_or(false, |transaction_type| {
+            transaction_type != TransactionType::User
+        }) {
+            return Ok((Vec::new(), ledger_version + 1));
+        }
+        let start_seq_num = match self
+            .transaction_store
+            .get_account_sequence_number_at_or_after_version(sender, start_version)?
+        {
+            Some(seq_num) => seq_num,
+            None => return Ok((Vec::new(), ledger_version + 1)),
+        };
+
+        let mut versions = Vec::new();
+        let mut num_scanned = 0;
+        for result in self
+            .transaction_store
+            .get_account_transaction_version_iter(
+                sender,
+                start_seq_num,
+                max_scanned,
+                ledger_version,
+            )?
+        {
+            let (_seq_num, version) = result?;
+            num_scanned += 1;
+            let matches = match filter.success {
+                Some(success) => {
+                    self.ledger_store
+                        .get_transaction_info(version)?
+                        .status()
+                        .is_success()
+                        == success
+                }
+                None => true,
+            };
+            if matches {
+                versions.push(version);
+            }
+            if versions.len() as u64 == limit || num_scanned == max_scanned {
+                return Ok((versions, version + 1));
+            }
+        }
+        Ok((versions, ledger_version + 1))
+    }
+
+    /// Gets an iterator that yields the transactions in
+    /// `[start_version, start_version + num_transactions)` along with their write sets, for tools
+    /// analyzing the history of the ledger.
+    pub fn get_transaction_and_write_set_iter(
+        &self,
+        start_version: Version,
+        num_transactions: usize,
+    ) -> Result<impl Iterator<Item = Result<(Transaction, WriteSet)>> + '_> {
+        error_if_version_is_pruned(&self.ledger_pruner, "Transaction", start_version)?;
+
+        let txn_iter = self
+            .transaction_store
+            .get_transaction_iter(start_version, num_transactions)?;
+        let write_set_iter = self
+            .transaction_store
+            .get_write_set_iter(start_version, num_transactions)?;
+        Ok(zip_eq(txn_iter, write_set_iter)
+            .map(|(txn_res, write_set_res)| Ok((txn_res?, write_set_res?))))
+    }
+
     // ================================== Backup APIs ===================================
 
     /// Gets an instance of `BackupHandler` for data backup purpose.
@@ -616,9 +1027,70 @@ impl AptosDB {
             Arc::clone(&self.transaction_store),
             Arc::clone(&self.state_store),
             Arc::clone(&self.event_store),
+            Arc::clone(&self.backup_progress),
         )
     }
 
+    // ================================ Consistency check ================================
+
+    /// Checks `num_versions` versions from `first_version` for data inconsistent with the hashes
+    /// the latest ledger info commits to it with.
+    pub fn check_consistency(
+        &self,
+        first_version: Version,
+        num_versions: u64,
+    ) -> Result<Vec<Inconsistency>> {
+        error_if_version_is_pruned(&self.ledger_pruner, "Transaction", first_version)?;
+        let ledger_info = self.ledger_store.get_latest_ledger_info()?;
+        let ledger_version = ledger_info.ledger_info().version();
+        ensure!(
+            num_versions > 0 && first_version + num_versions <= ledger_version + 1,
+            "Versions [{}, {}) are not all committed, latest version is {}.",
+            first_version,
+            first_version + num_versions,
+            ledger_version,
+        );
+        consistency_checker::check_versions(
+            self,
+            first_version,
+            num_versions,
+            ledger_info.ledger_info(),
+        )
+    }
+
+    // ================================ DB admin ================================
+
+    /// Gets the RocksDB statistics of every column family of the DB.
+    pub fn get_db_stats(&self) -> Result<Vec<ColumnFamilyStats>> {
+        db_admin::get_db_stats(self)
+    }
+
+    /// Compacts a column family of `db_name`, which is either `LEDGER_DB_NAME` or
+    /// `STATE_MERKLE_DB_NAME`. Blocks until the compaction is done, which may take long.
+    pub fn compact(&self, db_name: &str, cf_name: &str) -> Result<()> {
+        db_admin::compact(self, db_name, cf_name)
+    }
+
+    /// Estimates the space the pruners reclaim once caught up with their prune windows.
+    pub fn estimate_prunable_space(&self) -> Result<Vec<PrunableSpace>> {
+        db_admin::estimate_prunable_space(self)
+    }
+
+    /// Indexes by module the resources of the state values in the DB, for DBs written before the
+    /// index of resources by module was maintained, after which the listings of resources by
+    /// module read the index rather than scan all the resources of the account. The resources
+    /// committed meanwhile are indexed as usual, so the DB may stay in use. Returns the number of
+    /// resources indexed.
+    pub fn backfill_resource_by_module_index(&self) -> Result<usize> {
+        db_admin::backfill_resource_by_module_index(self)
+    }
+
+    /// Forgets the version the backup coordinator reported as backed up, so that the pruners stop
+    /// waiting on a backup which is no longer running.
+    pub fn clear_backup_progress(&self) -> Result<()> {
+        self.backup_progress.clear()
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let start = Instant::now();
@@ -627,6 +1099,7 @@ impl AptosDB {
         self.ledger_db.create_checkpoint(&ledger_db_path)?;
         self.state_merkle_db
             .create_checkpoint(&state_merkle_db_path)?;
+        self.state_kv_db.create_checkpoint(path.as_ref())?;
         info!(
             path = path.as_ref(),
             time_ms = %start.elapsed().as_millis(),
@@ -682,10 +1155,16 @@ impl AptosDB {
             }
         }
 
+        let events = self.event_store.get_events_by_version_and_index(
+            &event_indices
+                .iter()
+                .map(|(_, ver, idx)| (*ver, *idx))
+                .collect::<Vec<_>>(),
+        )?;
         let mut events_with_version = event_indices
             .into_iter()
-            .map(|(seq, ver, idx)| {
-                let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+            .zip(events)
+            .map(|((seq, ver, _), event)| {
                 ensure!(
                     seq == event.sequence_number(),
                     "Index broken, expected seq:{}, actual:{}",
@@ -723,7 +1202,13 @@ impl AptosDB {
             None
         };
 
-        Ok((SealedChangeSet { batch: cs.batch }, counters))
+        Ok((
+            SealedChangeSet {
+                batch: cs.batch,
+                state_kv_batches: cs.state_kv_batches,
+            },
+            counters,
+        ))
     }
 
     fn save_transactions_impl(
@@ -773,6 +1258,15 @@ impl AptosDB {
                         .put_write_set(ver, txn_to_commit.write_set(), cs)
                 },
             )?;
+            // The authentication key index takes a read per account write, so it's only kept
+            // along with the indexer.
+            if self.indexer.is_some() {
+                self.transaction_store.put_auth_keys(
+                    first_version,
+                    txns_to_commit.iter().map(|t| t.write_set()),
+                    cs,
+                )?;
+            }
             // Transaction accumulator updates. Get result root hash.
             let txn_infos: Vec<_> = txns_to_commit
                 .iter()
@@ -782,13 +1276,96 @@ impl AptosDB {
             self.ledger_store
                 .put_transaction_infos(first_version, &txn_infos, cs)?
         };
+        self.put_block_gas_limits_reached(first_version, txns_to_commit, cs)?;
         Ok(new_root_hash)
     }
 
+    /// Records the blocks whose transactions used up the block gas limit in effect for them, among
+    /// the ones the transactions from `first_version` are in. It only depends on the committed
+    /// transactions, so every node records the same blocks however they were executed or synced.
+    fn put_block_gas_limits_reached(
+        &self,
+        first_version: Version,
+        txns_to_commit: &[TransactionToCommit],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let gas_limit_key = StateKey::AccessPath(access_path_for_config(BlockGasLimit::CONFIG_ID));
+        let committed_gas_limit = |version: Version| -> Result<Option<u64>> {
+            self.state_store
+                .get_state_value_by_version(&gas_limit_key, version)?
+                .map(|value| Ok(BlockGasLimit::deserialize_into_config(&value.bytes)?.gas_limit))
+                .transpose()
+        };
+
+        // The first version, gas limit and gas used so far of the block being looked at, while it
+        // has a gas limit it didn't reach yet.
+        let mut block = None;
+        let starts_with_block = matches!(
+            txns_to_commit.first().map(|t| t.transaction()),
+            Some(Transaction::BlockMetadata(_))
+        );
+        if !starts_with_block && first_version > 0 {
+            if let Some((block_version, _, _)) = self
+                .event_store
+                .lookup_event_before_or_at_version(&new_block_event_key(), first_version - 1)?
+            {
+                if let Some(gas_limit) = committed_gas_limit(block_version - 1)? {
+                    let gas_used = self
+                        .ledger_store
+                        .get_transaction_info_iter(
+                            block_version,
+                            (first_version - block_version) as usize,
+                        )?
+                        .try_fold(0u64, |gas_used, info| -> Result<u64> {
+                            Ok(gas_used.saturating_add(info?.gas_used()))
+                        })?;
+                    block = Some((block_version, gas_limit, gas_used));
+                }
+            }
+        }
+
+        // The gas limit in effect before the transaction being looked at, once read.
+        let mut gas_limit_before = None;
+        for (version, txn_to_commit) in (first_version..).zip(txns_to_commit) {
+            if let Transaction::BlockMetadata(_) = txn_to_commit.transaction() {
+                let gas_limit = match gas_limit_before {
+                    Some(gas_limit) => gas_limit,
+                    None => match first_version.checked_sub(1) {
+                        Some(committed_version) => committed_gas_limit(committed_version)?,
+                        None => None,
+                    },
+                };
+                gas_limit_before = Some(gas_limit);
+                block = gas_limit.map(|gas_limit| (version, gas_limit, 0));
+            }
+            if let Some((block_version, gas_limit, gas_used)) = block {
+                let gas_used = gas_used.saturating_add(txn_to_commit.transaction_info().gas_used());
+                if gas_used >= gas_limit {
+                    self.event_store
+                        .put_block_gas_limit_reached(block_version, gas_limit, cs)?;
+                    block = None;
+                } else {
+                    block = Some((block_version, gas_limit, gas_used));
+                }
+            }
+            if let Some(value) = txn_to_commit.state_updates().get(&gas_limit_key) {
+                gas_limit_before = Some(
+                    value
+                        .as_ref()
+                        .map(|value| BlockGasLimit::deserialize_into_config(&value.bytes))
+                        .transpose()?
+                        .map(|config| config.gas_limit),
+                );
+            }
+        }
+        Ok(())
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support. Also committed are the
     /// LedgerCounters.
     fn commit(&self, sealed_cs: SealedChangeSet) -> Result<()> {
+        self.state_kv_db.commit(sealed_cs.state_kv_batches)?;
         self.ledger_db.write_schemas(sealed_cs.batch)?;
         Ok(())
     }
@@ -845,6 +1422,18 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_resources_by_module(
+        &self,
+        address: AccountAddress,
+        module_id: &ModuleId,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        gauged_api("get_resources_by_module", || {
+            self.state_store
+                .get_resources_by_module(address, module_id, version)
+        })
+    }
+
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         gauged_api("get_latest_ledger_info_option", || {
             Ok(self.ledger_store.get_latest_ledger_info_option())
@@ -905,13 +1494,44 @@ impl DbReader for AptosDB {
         fetch_events: bool,
     ) -> Result<Option<TransactionWithProof>> {
         gauged_api("get_transaction_by_hash", || {
-            self.transaction_store
-                .get_transaction_version_by_hash(&hash, ledger_version)?
+            self.get_transaction_version_by_hash(hash, ledger_version)?
                 .map(|v| self.get_transaction_with_proof(v, ledger_version, fetch_events))
                 .transpose()
         })
     }
 
+    /// Looks up the version of a transaction in the hash index, without reading the transaction
+    /// or its proof. Transactions that have been pruned are reported as not found, as their
+    /// index entries are pruned along with them.
+    fn get_transaction_version_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        gauged_api("get_transaction_version_by_hash", || {
+            Ok(self
+                .transaction_store
+                .get_transaction_version_by_hash(&hash, ledger_version)?
+                // Never point into the range the pruner has already declared unreadable.
+                .filter(|version| *version >= self.ledger_pruner.get_min_readable_version()))
+        })
+    }
+
+    /// Gets the authentication keys `address` had up to `ledger_version`, with the versions that
+    /// set them. The index is only kept with the indexer enabled. The pruner drops the keys
+    /// replaced before the min readable version, but not the one the account was created with.
+    fn get_auth_key_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Vec<u8>)>> {
+        gauged_api("get_auth_key_history", || {
+            ensure!(self.indexer.is_some(), "Indexer not enabled.");
+            self.transaction_store
+                .get_auth_key_history(address, ledger_version)
+        })
+    }
+
     /// Returns the transaction by version, delegates to `AptosDB::get_transaction_with_proof`.
     /// Returns an error if the provided version is not found.
     fn get_transaction_by_version(
@@ -925,6 +1545,49 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns the versions of up to `limit` transactions in `[start_version, ledger_version]`
+    /// that match `filter`, in ascending order, along with the version to resume from. At most
+    /// `max_scanned` transactions are examined, so a filter that matches few transactions costs
+    /// no more than a page of them; the returned versions may then be fewer than `limit` even
+    /// though more matches follow. The version to resume from is past `ledger_version` once
+    /// there is nothing left to examine.
+    ///
+    /// Filtering by sender walks the transactions sent by the account rather than the ledger.
+    fn get_filtered_transaction_versions(
+        &self,
+        start_version: Version,
+        limit: u64,
+        max_scanned: u64,
+        ledger_version: Version,
+        filter: &TransactionFilter,
+    ) -> Result<(Vec<Version>, Version)> {
+        gauged_api("get_filtered_transaction_versions", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            if start_version > ledger_version || limit == 0 || max_scanned == 0 {
+                return Ok((Vec::new(), start_version));
+            }
+            error_if_version_is_pruned(&self.ledger_pruner, "Transaction", start_version)?;
+
+            match filter.sender {
+                Some(sender) => self.scan_filtered_account_transaction_versions(
+                    sender,
+                    start_version,
+                    limit,
+                    max_scanned,
+                    ledger_version,
+                    filter,
+                ),
+                None => self.scan_filtered_transaction_versions(
+                    start_version,
+                    limit,
+                    max_scanned,
+                    ledger_version,
+                    filter,
+                ),
+            }
+        })
+    }
+
     // ======================= State Synchronizer Internal APIs ===================================
     /// Returns batch of transactions for the purpose of synchronizing state to another node.
     ///
@@ -949,12 +1612,12 @@ impl DbReader for AptosDB {
 
             let limit = std::cmp::min(limit, ledger_version - start_version + 1);
 
-            let txns = (start_version..start_version + limit)
-                .map(|version| self.transaction_store.get_transaction(version))
-                .collect::<Result<Vec<_>>>()?;
-            let txn_infos = (start_version..start_version + limit)
-                .map(|version| self.ledger_store.get_transaction_info(version))
-                .collect::<Result<Vec<_>>>()?;
+            let txns = self
+                .transaction_store
+                .get_transactions(start_version, limit)?;
+            let txn_infos = self
+                .ledger_store
+                .get_transaction_infos(start_version, limit)?;
             let events = if fetch_events {
                 Some(
                     (start_version..start_version + limit)
@@ -1090,6 +1753,81 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_events_by_version_range(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        gauged_api("get_events_by_version_range", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(Vec::new());
+            }
+            error_if_version_is_pruned(&self.ledger_pruner, "Transaction", start_version)?;
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+            let mut events_with_version = Vec::new();
+            for (version, events) in (start_version..).zip(
+                self.event_store
+                    .get_events_by_version_iter(start_version, limit as usize)?,
+            ) {
+                events_with_version.extend(
+                    events?
+                        .into_iter()
+                        .map(|event| EventWithVersion::new(version, event)),
+                );
+            }
+            Ok(events_with_version)
+        })
+    }
+
+    fn get_events_with_transaction_context(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithTransactionContext>> {
+        gauged_api("get_events_with_transaction_context", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            if start_version > ledger_version || limit == 0 {
+                return Ok(Vec::new());
+            }
+            error_if_version_is_pruned(&self.ledger_pruner, "Transaction", start_version)?;
+
+            let limit = std::cmp::min(limit, ledger_version - start_version + 1) as usize;
+            let mut txn_iter = self
+                .transaction_store
+                .get_transaction_iter(start_version, limit)?;
+            let mut txn_info_iter = self
+                .ledger_store
+                .get_transaction_info_iter(start_version, limit)?;
+            let mut events_iter = self
+                .event_store
+                .get_events_by_version_iter(start_version, limit)?;
+
+            let mut events_with_context = Vec::new();
+            for version in start_version..start_version + limit as u64 {
+                let txn = next_at_version(&mut txn_iter, "Transaction", version)?;
+                let txn_info = next_at_version(&mut txn_info_iter, "TransactionInfo", version)?;
+                let events = next_at_version(&mut events_iter, "Events", version)?;
+                let sender = txn.as_signed_user_txn().ok().map(|txn| txn.sender());
+                events_with_context.extend(events.into_iter().map(|event| {
+                    EventWithTransactionContext {
+                        transaction_version: version,
+                        transaction_hash: txn_info.transaction_hash(),
+                        sender,
+                        success: txn_info.status().is_success(),
+                        event,
+                    }
+                }));
+            }
+            Ok(events_with_context)
+        })
+    }
+
     /// Gets ledger info at specified version and ensures it's an epoch ending.
     fn get_epoch_ending_ledger_info(&self, version: u64) -> Result<LedgerInfoWithSignatures> {
         gauged_api("get_epoch_ending_ledger_info", || {
@@ -1139,11 +1877,34 @@ impl DbReader for AptosDB {
         gauged_api("get_state_value_by_version", || {
             error_if_version_is_pruned(&self.state_pruner, "State", version)?;
 
+            if let Some(state_value) = self.state_value_cache.get(state_store_key, version) {
+                return Ok(state_value);
+            }
             self.state_store
                 .get_state_value_by_version(state_store_key, version)
         })
     }
 
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        gauged_api("get_state_values_by_version", || {
+            error_if_version_is_pruned(&self.state_pruner, "State", version)?;
+
+            let cached = state_keys
+                .iter()
+                .map(|state_key| self.state_value_cache.get(state_key, version))
+                .collect::<Option<Vec<_>>>();
+            if let Some(state_values) = cached {
+                return Ok(state_values);
+            }
+            self.state_store
+                .get_state_values_by_version(state_keys, version)
+        })
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -1253,6 +2014,16 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_block_gas_limit_reached(&self, version: Version) -> Result<bool> {
+        gauged_api("get_block_gas_limit_reached", || {
+            let (block_version, _) = self.event_store.get_block_metadata(version)?;
+            Ok(self
+                .event_store
+                .get_block_gas_limit_reached(block_version)?
+                .is_some())
+        })
+    }
+
     fn get_block_info_by_height(&self, height: u64) -> Result<(Version, Version, NewBlockEvent)> {
         gauged_api("get_block_info_by_height", || {
             let latest_li = self.get_latest_ledger_info()?;
@@ -1294,6 +2065,14 @@ impl DbReader for AptosDB {
 
     fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
         gauged_api("get_latest_state_checkpoint_version", || {
+            // The in-memory state of a secondary instance doesn't follow the primary, but the
+            // version of the latest ledger info is always a state checkpoint.
+            if self.secondary {
+                return Ok(self
+                    .ledger_store
+                    .get_latest_ledger_info_option()
+                    .map(|ledger_info| ledger_info.ledger_info().version()));
+            }
             Ok(self
                 .state_store
                 .buffered_state()
@@ -1377,10 +2156,77 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_table_items(
+        &self,
+        handle: TableHandle,
+        cursor: Option<Vec<u8>>,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<(Vec<u8>, StateValue)>> {
+        gauged_api("get_table_items", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            error_if_version_is_pruned(&self.state_pruner, "State", version)?;
+            let indexer = match &self.indexer {
+                Some(indexer) => indexer,
+                None => bail!("Indexer not enabled."),
+            };
+
+            // The index holds every key ever written to the table, so the keys without a value at
+            // the version are skipped.
+            let mut items = Vec::new();
+            for key in indexer.get_table_key_iter(handle, cursor)? {
+                if items.len() as u64 >= limit {
+                    break;
+                }
+                let key = key?;
+                let state_key = StateKey::table_item(handle, key.clone());
+                if let Some(value) = self
+                    .state_store
+                    .get_state_value_by_version(&state_key, version)?
+                {
+                    items.push((key, value));
+                }
+            }
+            Ok(items)
+        })
+    }
+
     /// Returns whether the indexer DB has been enabled or not
     fn indexer_enabled(&self) -> bool {
         self.indexer.is_some()
     }
+
+    fn get_coin_balances(&self, address: AccountAddress) -> Result<(Version, Vec<(TypeTag, u64)>)> {
+        gauged_api("get_coin_balances", || {
+            let indexer = self.balance_index()?;
+            let next_version = indexer.balances_next_version();
+            ensure!(next_version > 0, "Balance index is empty.");
+            Ok((next_version - 1, indexer.get_coin_balances(address)?))
+        })
+    }
+
+    fn get_top_coin_holders(
+        &self,
+        coin_type: TypeTag,
+        limit: u64,
+    ) -> Result<(Version, Vec<(AccountAddress, u64)>)> {
+        gauged_api("get_top_coin_holders", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            let indexer = self.balance_index()?;
+            let next_version = indexer.balances_next_version();
+            ensure!(next_version > 0, "Balance index is empty.");
+            Ok((
+                next_version - 1,
+                indexer.get_top_coin_holders(&coin_type, limit as usize)?,
+            ))
+        })
+    }
+
+    fn balance_index_enabled(&self) -> bool {
+        self.indexer
+            .as_ref()
+            .map_or(false, Indexer::balance_index_enabled)
+    }
 }
 
 impl DbWriter for AptosDB {
@@ -1458,6 +2304,15 @@ impl DbWriter for AptosDB {
                     .start_timer();
                 self.commit(sealed_cs)?;
             }
+            // Only cache the state values once committed, so that what's cached is never ahead of
+            // the DB.
+            self.state_value_cache.add_versions(
+                first_version,
+                &txns_to_commit
+                    .iter()
+                    .map(TransactionToCommit::state_updates)
+                    .collect::<Vec<_>>(),
+            );
 
             {
                 let mut buffered_state = self.state_store.buffered_state().lock();
@@ -1607,6 +2462,13 @@ impl DbWriter for AptosDB {
                 &events,
                 Some(&mut change_set),
             )?;
+            if self.indexer.is_some() {
+                self.transaction_store.put_auth_keys(
+                    version,
+                    outputs.iter().map(|output| output.write_set()),
+                    &mut change_set,
+                )?;
+            }
             restore_utils::save_transaction_outputs(
                 self.ledger_db.clone(),
                 self.transaction_store.clone(),
@@ -1624,13 +2486,19 @@ impl DbWriter for AptosDB {
             )?;
 
             // Delete the genesis transaction
-            StateMerklePruner::prune_genesis(self.state_merkle_db.clone(), &mut change_set)?;
+            StateMerklePruner::prune_genesis(
+                self.state_merkle_db.clone(),
+                self.state_kv_db.clone(),
+                self.node_cache.clone(),
+                &mut change_set,
+            )?;
             LedgerPruner::prune_genesis(self.ledger_db.clone(), &mut change_set)?;
 
             // Apply the change set writes to the database (atomically) and update in-memory state
             self.ledger_db.clone().write_schemas(change_set.batch)?;
             restore_utils::update_latest_ledger_info(self.ledger_store.clone(), ledger_infos)?;
             self.state_store.reset();
+            self.state_value_cache.clear();
 
             Ok(())
         })
@@ -1672,6 +2540,8 @@ fn gauged_api<T, F>(api_name: &'static str, api_impl: F) -> Result<T>
 where
     F: FnOnce() -> Result<T>,
 {
+    // Traced as a child of the span of e.g. the API request the read is for.
+    let _span = tracing::info_span!("aptosdb", otel.name = api_name).entered();
     let timer = Instant::now();
 
     let res = api_impl();
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_counter, register_int_gauge,
-    register_int_gauge_vec, HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -102,6 +103,41 @@ pub static PRUNER_BATCH_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static PRUNER_SCHEDULE_PAUSED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_pruner_schedule_paused",
+        // metric description
+        "Whether the pruner is held back by its schedule, 1 if it is",
+        // metric labels (dimensions)
+        &["pruner_name",]
+    )
+    .unwrap()
+});
+
+pub static PRUNER_LAG: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_pruner_lag_versions",
+        // metric description
+        "Number of versions the pruner is behind its target version",
+        // metric labels (dimensions)
+        &["pruner_name",]
+    )
+    .unwrap()
+});
+
+/// The highest version backed up, which the pruners don't prune past.
+pub static PRUNER_BACKUP_HOLD_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        // metric name
+        "aptos_pruner_backup_hold_version",
+        // metric description
+        "Highest version backed up, which the pruners don't prune past"
+    )
+    .unwrap()
+});
+
 pub static API_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -128,6 +164,81 @@ pub static OTHER_TIMERS_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Requests to the state merkle tree node cache, by whether they hit or missed.
+pub static NODE_CACHE_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_node_cache_requests",
+        "Number of requests to the state merkle tree node cache",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static NODE_CACHE_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_node_cache_bytes",
+        "Approximate memory taken up by the state merkle tree node cache"
+    )
+    .unwrap()
+});
+
+pub static NODE_CACHE_ITEMS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_node_cache_items",
+        "Number of nodes in the state merkle tree node cache"
+    )
+    .unwrap()
+});
+
+pub static STATE_VALUE_CACHE_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_state_value_cache_requests",
+        "Number of requests to the cache of the state values written by recent versions",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static STATE_VALUE_CACHE_ITEMS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_state_value_cache_items",
+        "Number of state values in the cache of the state values written by recent versions"
+    )
+    .unwrap()
+});
+
+/// State values written to each shard of the state KV DB, the ledger DB being shard 0 when the
+/// state values are not sharded.
+pub static STATE_KV_SHARD_VALUES_WRITTEN: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_state_kv_shard_values_written",
+        "Number of state values written to each shard of the state KV DB",
+        &["shard_id"]
+    )
+    .unwrap()
+});
+
+/// Time taken to write a batch to each shard of the state KV DB, when sharded.
+pub static STATE_KV_SHARD_COMMIT_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_storage_state_kv_shard_commit_seconds",
+        "Time taken to write a batch to each shard of the state KV DB",
+        &["shard_id"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+/// The version the stale state values of each shard of the state KV DB are pruned until.
+pub static STATE_KV_SHARD_PRUNED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_state_kv_shard_pruned_version",
+        "Version the stale state values of each shard of the state KV DB are pruned until",
+        &["shard_id"]
+    )
+    .unwrap()
+});
+
 /// Rocksdb metrics
 pub static ROCKSDB_PROPERTIES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -141,6 +252,25 @@ pub static ROCKSDB_PROPERTIES: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Latest version checked by the consistency checker.
+pub static CONSISTENCY_CHECKER_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_consistency_checker_version",
+        "Latest version checked by the consistency checker"
+    )
+    .unwrap()
+});
+
+/// Inconsistencies found by the consistency checker, by kind. Any of them is worth an alert.
+pub static CONSISTENCY_CHECKER_INCONSISTENCIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_consistency_checker_inconsistencies",
+        "Number of inconsistencies found by the consistency checker",
+        &["kind"]
+    )
+    .unwrap()
+});
+
 // Backup progress gauges:
 
 pub(crate) static BACKUP_EPOCH_ENDING_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
@@ -0,0 +1,160 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines the in-memory cache of state merkle tree nodes, shared by all the readers of
+//! the tree: the updates of the tree at commit time, which read the nodes of the previous version,
+//! and the proofs served to other nodes, e.g. during state sync.
+//!
+//! A node key (the version a node was created at and its path) identifies a single node for good,
+//! as nodes are never rewritten, only pruned, so cached nodes never go stale. The state merkle
+//! pruner removes the nodes it deletes from the cache once they are deleted from the DB, so that
+//! pruned versions stop being readable just as before. A read racing with the pruner may cache a
+//! node again right after it is pruned; such a node is never asked for again and gets evicted in
+//! time.
+
+use crate::metrics::{NODE_CACHE_BYTES, NODE_CACHE_ITEMS, NODE_CACHE_REQUESTS};
+use crate::state_merkle_db::Node;
+use aptos_infallible::Mutex;
+use aptos_jellyfish_merkle::node_type::{Child, NodeKey};
+use lru::LruCache;
+use std::{
+    collections::hash_map::DefaultHasher,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    mem::size_of,
+};
+
+#[cfg(test)]
+mod test;
+
+/// The default capacity of the node cache, in bytes.
+pub const DEFAULT_NODE_CACHE_CAPACITY_BYTES: usize = 128 << 20;
+
+/// The cache is split into shards with a lock each, so that the many threads reading the tree at
+/// once don't all contend on a single lock.
+const NUM_SHARDS: usize = 16;
+
+struct Shard {
+    nodes: LruCache<NodeKey, (Node, usize)>,
+    size_bytes: usize,
+}
+
+/// A least recently used cache of state merkle tree nodes, bounded by the approximate memory its
+/// nodes take up.
+pub struct NodeCache {
+    shards: Vec<Mutex<Shard>>,
+    shard_capacity_bytes: Mutex<usize>,
+}
+
+impl NodeCache {
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            shards: (0..NUM_SHARDS)
+                .map(|_| {
+                    Mutex::new(Shard {
+                        nodes: LruCache::unbounded(),
+                        size_bytes: 0,
+                    })
+                })
+                .collect(),
+            shard_capacity_bytes: Mutex::new(capacity_bytes / NUM_SHARDS),
+        }
+    }
+
+    /// Changes the capacity of the cache, evicting nodes if it shrinks. 0 disables the cache.
+    pub fn set_capacity(&self, capacity_bytes: usize) {
+        let shard_capacity_bytes = capacity_bytes / NUM_SHARDS;
+        *self.shard_capacity_bytes.lock() = shard_capacity_bytes;
+        for shard in &self.shards {
+            Self::evict(&mut shard.lock(), shard_capacity_bytes);
+        }
+    }
+
+    pub fn get(&self, node_key: &NodeKey) -> Option<Node> {
+        let node = self
+            .shard(node_key)
+            .lock()
+            .nodes
+            .get(node_key)
+            .map(|(node, _)| node.clone());
+        NODE_CACHE_REQUESTS
+            .with_label_values(&[if node.is_some() { "hit" } else { "miss" }])
+            .inc();
+        node
+    }
+
+    pub fn insert(&self, node_key: NodeKey, node: Node) {
+        let shard_capacity_bytes = *self.shard_capacity_bytes.lock();
+        let size_bytes = approximate_size(&node_key, &node);
+        if size_bytes > shard_capacity_bytes {
+            return;
+        }
+
+        let mut shard = self.shard(&node_key).lock();
+        if let Some((_, replaced_size_bytes)) = shard.nodes.put(node_key, (node, size_bytes)) {
+            Self::account_removal(&mut shard, replaced_size_bytes);
+        }
+        shard.size_bytes += size_bytes;
+        NODE_CACHE_BYTES.add(size_bytes as i64);
+        NODE_CACHE_ITEMS.inc();
+        Self::evict(&mut shard, shard_capacity_bytes);
+    }
+
+    pub fn remove(&self, node_key: &NodeKey) {
+        let mut shard = self.shard(node_key).lock();
+        if let Some((_, size_bytes)) = shard.nodes.pop(node_key) {
+            Self::account_removal(&mut shard, size_bytes);
+        }
+    }
+
+    fn shard(&self, node_key: &NodeKey) -> &Mutex<Shard> {
+        let mut hasher = DefaultHasher::new();
+        node_key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % NUM_SHARDS]
+    }
+
+    fn evict(shard: &mut Shard, shard_capacity_bytes: usize) {
+        while shard.size_bytes > shard_capacity_bytes {
+            match shard.nodes.pop_lru() {
+                Some((_, (_, size_bytes))) => Self::account_removal(shard, size_bytes),
+                None => break,
+            }
+        }
+    }
+
+    fn account_removal(shard: &mut Shard, size_bytes: usize) {
+        shard.size_bytes -= size_bytes;
+        NODE_CACHE_BYTES.sub(size_bytes as i64);
+        NODE_CACHE_ITEMS.dec();
+    }
+
+    #[cfg(test)]
+    fn size_bytes(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.lock().size_bytes)
+            .sum()
+    }
+}
+
+impl Debug for NodeCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeCache")
+            .field("shard_capacity_bytes", &*self.shard_capacity_bytes.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The memory a node takes up in the cache, approximately: what the key and node take inline,
+/// plus the children of internal nodes and the state keys of leaves they point to.
+fn approximate_size(node_key: &NodeKey, node: &Node) -> usize {
+    let inline = size_of::<NodeKey>() + node_key.nibble_path().bytes().len() + size_of::<Node>();
+    inline
+        + match node {
+            Node::Internal(internal_node) => {
+                internal_node.children_sorted().count() * size_of::<Child>()
+            }
+            Node::Leaf(leaf_node) => leaf_node.value_index().0.size(),
+            Node::Null => 0,
+        }
+}
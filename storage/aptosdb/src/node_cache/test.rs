@@ -0,0 +1,58 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_crypto::HashValue;
+use aptos_types::state_store::state_key::StateKey;
+
+fn leaf(key_len: usize) -> Node {
+    Node::new_leaf(
+        HashValue::random(),
+        HashValue::random(),
+        (StateKey::Raw(vec![0; key_len]), 0),
+    )
+}
+
+#[test]
+fn test_get_and_insert() {
+    let cache = NodeCache::new(DEFAULT_NODE_CACHE_CAPACITY_BYTES);
+    let node_key = NodeKey::new_empty_path(1);
+    assert_eq!(cache.get(&node_key), None);
+
+    let node = leaf(10);
+    cache.insert(node_key.clone(), node.clone());
+    assert_eq!(cache.get(&node_key), Some(node));
+    assert_eq!(cache.get(&NodeKey::new_empty_path(2)), None);
+
+    // Inserting the same node again doesn't count it twice.
+    let size_bytes = cache.size_bytes();
+    cache.insert(node_key.clone(), cache.get(&node_key).unwrap());
+    assert_eq!(cache.size_bytes(), size_bytes);
+
+    cache.remove(&node_key);
+    assert_eq!(cache.get(&node_key), None);
+    assert_eq!(cache.size_bytes(), 0);
+}
+
+#[test]
+fn test_evicts_least_recently_used() {
+    let node = leaf(100);
+    let node_size = approximate_size(&NodeKey::new_empty_path(0), &node);
+    // Room for 4 nodes in each shard.
+    let cache = NodeCache::new(node_size * 4 * NUM_SHARDS);
+
+    let num_nodes = 1000;
+    for version in 0..num_nodes {
+        cache.insert(NodeKey::new_empty_path(version), node.clone());
+        // Keep the first node recently used.
+        assert!(cache.get(&NodeKey::new_empty_path(0)).is_some());
+    }
+    assert!(cache.size_bytes() <= node_size * 4 * NUM_SHARDS);
+    assert!(cache.get(&NodeKey::new_empty_path(num_nodes - 1)).is_some());
+    assert!((1..num_nodes).any(|version| cache.get(&NodeKey::new_empty_path(version)).is_none()));
+
+    cache.set_capacity(0);
+    assert_eq!(cache.size_bytes(), 0);
+    cache.insert(NodeKey::new_empty_path(0), node);
+    assert_eq!(cache.get(&NodeKey::new_empty_path(0)), None);
+}
@@ -0,0 +1,143 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides `BackupProgress`, the hold point the backup coordinator moves forward as it
+//! backs up the DB. The pruners don't prune past it, so that a backup lagging behind the pruners
+//! doesn't lose versions for good.
+
+use crate::{
+    metrics::PRUNER_BACKUP_HOLD_VERSION,
+    pruner::pruner_metadata::{PrunerMetadata, PrunerTag},
+    pruner_metadata::PrunerMetadataSchema,
+};
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_logger::{
+    prelude::{sample, SampleRate},
+    warn,
+};
+use aptos_types::transaction::Version;
+use schemadb::DB;
+use std::{
+    cmp::{max, min},
+    sync::Arc,
+    time::Duration,
+};
+
+#[derive(Debug)]
+pub(crate) struct BackupProgress {
+    ledger_db: Arc<DB>,
+    /// The highest version backed up, persisted so that the hold survives restarts. `None` until
+    /// a backup coordinator registers a version, in which case the pruners are not held.
+    backed_up_version: Mutex<Option<Version>>,
+}
+
+impl BackupProgress {
+    pub fn new(ledger_db: Arc<DB>) -> Self {
+        let backed_up_version = ledger_db
+            .get::<PrunerMetadataSchema>(&PrunerTag::BackupProgress)
+            .expect("Reading the backup progress should succeed.")
+            .map(|metadata| match metadata {
+                PrunerMetadata::LatestVersion(version) => version,
+            });
+        if let Some(version) = backed_up_version {
+            PRUNER_BACKUP_HOLD_VERSION.set(version as i64);
+        }
+        Self {
+            ledger_db,
+            backed_up_version: Mutex::new(backed_up_version),
+        }
+    }
+
+    pub fn backed_up_version(&self) -> Option<Version> {
+        *self.backed_up_version.lock()
+    }
+
+    /// Records that all versions up to `version` are backed up. The hold point never moves
+    /// backwards: registering a version lower than the one recorded, e.g. by a coordinator which
+    /// restarted from an older backup, is a no-op.
+    pub fn record_backed_up_version(&self, version: Version) -> Result<()> {
+        let mut backed_up_version = self.backed_up_version.lock();
+        if backed_up_version.map_or(false, |backed_up| backed_up >= version) {
+            return Ok(());
+        }
+        self.ledger_db.put::<PrunerMetadataSchema>(
+            &PrunerTag::BackupProgress,
+            &PrunerMetadata::LatestVersion(version),
+        )?;
+        *backed_up_version = Some(version);
+        PRUNER_BACKUP_HOLD_VERSION.set(version as i64);
+        Ok(())
+    }
+
+    /// Forgets the backed up version, e.g. once the backup coordinator is decommissioned, so that
+    /// the pruners are no longer held. A coordinator still running registers again on its next
+    /// report.
+    pub fn clear(&self) -> Result<()> {
+        let mut backed_up_version = self.backed_up_version.lock();
+        self.ledger_db
+            .delete::<PrunerMetadataSchema>(&PrunerTag::BackupProgress)?;
+        *backed_up_version = None;
+        PRUNER_BACKUP_HOLD_VERSION.set(0);
+        Ok(())
+    }
+
+    /// Caps the version a pruner is about to prune until at the backed up version, so that the
+    /// versions not backed up yet remain readable. As pruner targets can't move backwards, the
+    /// result is never lower than `current_target`.
+    pub fn hold(&self, target: Version, current_target: Version) -> Version {
+        match self.backed_up_version() {
+            Some(backed_up_version) => {
+                if backed_up_version < target {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(60)),
+                        warn!(
+                            target_version = target,
+                            backed_up_version = backed_up_version,
+                            "Pruning is held by the backup progress. Clear it if the backup \
+                            coordinator is gone."
+                        )
+                    );
+                }
+                max(min(target, backed_up_version), current_target)
+            }
+            None => target,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BackupProgress;
+    use crate::AptosDB;
+    use aptos_temppath::TempPath;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_backup_progress() {
+        let tmp_dir = TempPath::new();
+        let aptos_db = AptosDB::new_for_test(&tmp_dir);
+        let progress = BackupProgress::new(Arc::clone(&aptos_db.ledger_db));
+
+        // Pruners are not held until a backup is registered.
+        assert_eq!(progress.backed_up_version(), None);
+        assert_eq!(progress.hold(100, 0), 100);
+
+        progress.record_backed_up_version(50).unwrap();
+        assert_eq!(progress.hold(100, 0), 50);
+        assert_eq!(progress.hold(40, 0), 40);
+        assert_eq!(progress.hold(100, 60), 60);
+
+        // The hold point never moves backwards, and survives restarts.
+        progress.record_backed_up_version(30).unwrap();
+        assert_eq!(progress.backed_up_version(), Some(50));
+        let progress = BackupProgress::new(Arc::clone(&aptos_db.ledger_db));
+        assert_eq!(progress.backed_up_version(), Some(50));
+
+        // Once cleared, the pruners are no longer held, across restarts too.
+        progress.clear().unwrap();
+        assert_eq!(progress.hold(100, 0), 100);
+        let progress = BackupProgress::new(Arc::clone(&aptos_db.ledger_db));
+        assert_eq!(progress.backed_up_version(), None);
+    }
+}
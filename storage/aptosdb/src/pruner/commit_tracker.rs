@@ -0,0 +1,33 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Keeps track of the foreground commits in flight to a DB, so that a background pruner deleting
+/// from the same DB can hold off while they are being written.
+#[derive(Debug, Default)]
+pub(crate) struct CommitTracker {
+    num_in_flight: AtomicUsize,
+}
+
+impl CommitTracker {
+    /// Marks a commit as in flight until the returned guard is dropped.
+    pub fn track(&self) -> CommitGuard<'_> {
+        self.num_in_flight.fetch_add(1, Ordering::Relaxed);
+        CommitGuard { tracker: self }
+    }
+
+    pub fn is_committing(&self) -> bool {
+        self.num_in_flight.load(Ordering::Relaxed) > 0
+    }
+}
+
+pub(crate) struct CommitGuard<'a> {
+    tracker: &'a CommitTracker,
+}
+
+impl Drop for CommitGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.num_in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{AptosDB, ChangeSet, EventStore, LedgerPrunerManager, PrunerManager};
-use aptos_config::config::LedgerPrunerConfig;
+use aptos_config::config::{LedgerPrunerConfig, PrunerScheduleConfig};
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::transaction::Version;
@@ -79,6 +79,8 @@ fn verify_event_store_pruner(events: Vec<Vec<ContractEvent>>) {
                 batch_size: 1,
                 user_pruning_window_offset: 0,
             },
+            PrunerScheduleConfig::default(),
+            None,
         );
         pruner
             .wake_and_wait_pruner(i as u64 /* latest_version */)
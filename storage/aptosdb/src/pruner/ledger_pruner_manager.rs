@@ -3,9 +3,10 @@
 
 use crate::metrics::{PRUNER_BATCH_SIZE, PRUNER_WINDOW};
 
-use aptos_config::config::LedgerPrunerConfig;
+use aptos_config::config::{LedgerPrunerConfig, PrunerScheduleConfig};
 use aptos_infallible::Mutex;
 
+use crate::pruner::backup_progress::BackupProgress;
 use crate::pruner::db_pruner::DBPruner;
 use crate::pruner::ledger_pruner_worker::LedgerPrunerWorker;
 use crate::pruner::ledger_store::ledger_store_pruner::LedgerPruner;
@@ -40,6 +41,9 @@ pub struct LedgerPrunerManager {
     latest_version: Arc<Mutex<Version>>,
     /// Offset for displaying to users
     user_pruning_window_offset: u64,
+    /// The progress of the backup, past which the pruner doesn't prune. `None` if the pruner
+    /// ignores the backup.
+    backup_progress: Option<Arc<BackupProgress>>,
 }
 
 impl PrunerManager for LedgerPrunerManager {
@@ -55,6 +59,12 @@ impl PrunerManager for LedgerPrunerManager {
         self.pruner.as_ref().min_readable_version()
     }
 
+    fn reload_min_readable_version(&self) -> anyhow::Result<()> {
+        let min_readable_version = self.pruner.initialize_min_readable_version()?;
+        self.pruner.record_progress(min_readable_version);
+        Ok(())
+    }
+
     fn get_min_viable_version(&self) -> Version {
         let min_version = self.get_min_readable_version();
         if self.is_pruner_enabled() {
@@ -86,9 +96,13 @@ impl PrunerManager for LedgerPrunerManager {
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_enabled);
+        let mut target_version = latest_version.saturating_sub(self.prune_window);
+        if let Some(backup_progress) = &self.backup_progress {
+            target_version = backup_progress.hold(target_version, self.pruner.target_version());
+        }
         self.pruner_worker
             .as_ref()
-            .set_target_db_version(latest_version.saturating_sub(self.prune_window));
+            .set_target_db_version(target_version);
     }
 
     #[cfg(test)]
@@ -128,7 +142,12 @@ impl PrunerManager for LedgerPrunerManager {
 
 impl LedgerPrunerManager {
     /// Creates a worker thread that waits on a channel for pruning commands.
-    pub fn new(ledger_rocksdb: Arc<DB>, ledger_pruner_config: LedgerPrunerConfig) -> Self {
+    pub fn new(
+        ledger_rocksdb: Arc<DB>,
+        ledger_pruner_config: LedgerPrunerConfig,
+        schedule: PrunerScheduleConfig,
+        backup_progress: Option<Arc<BackupProgress>>,
+    ) -> Self {
         let ledger_db_clone = Arc::clone(&ledger_rocksdb);
 
         let ledger_pruner = utils::create_ledger_pruner(ledger_db_clone);
@@ -146,6 +165,7 @@ impl LedgerPrunerManager {
         let ledger_pruner_worker = Arc::new(LedgerPrunerWorker::new(
             Arc::clone(&ledger_pruner),
             ledger_pruner_config,
+            schedule,
         ));
 
         let ledger_pruner_worker_clone = Arc::clone(&ledger_pruner_worker);
@@ -173,6 +193,7 @@ impl LedgerPrunerManager {
             pruning_batch_size: ledger_pruner_config.batch_size,
             latest_version: Arc::new(Mutex::new(min_readable_version)),
             user_pruning_window_offset: ledger_pruner_config.user_pruning_window_offset,
+            backup_progress,
         }
     }
 
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::pruner::db_pruner::DBPruner;
 use crate::pruner::ledger_store::ledger_store_pruner::LedgerPruner;
-use aptos_config::config::LedgerPrunerConfig;
+use crate::pruner::schedule::{PruningSchedule, PAUSE_INTERVAL};
+use aptos_config::config::{LedgerPrunerConfig, PrunerScheduleConfig};
 use aptos_logger::{
     error,
     prelude::{sample, SampleRate},
@@ -25,6 +26,8 @@ pub struct LedgerPrunerWorker {
     /// Max items to prune per batch. For the ledger pruner, this means the max versions to prune
     /// and for the state pruner, this means the max stale nodes to prune.
     max_versions_to_prune_per_batch: u64,
+    /// Decides when pruning may run.
+    schedule: PruningSchedule,
     /// Indicates whether the pruning loop should be running. Will only be set to true on pruner
     /// destruction.
     quit_worker: AtomicBool,
@@ -34,11 +37,13 @@ impl LedgerPrunerWorker {
     pub(crate) fn new(
         ledger_pruner: Arc<LedgerPruner>,
         ledger_pruner_config: LedgerPrunerConfig,
+        schedule: PrunerScheduleConfig,
     ) -> Self {
         Self {
             pruning_time_interval_in_ms: if cfg!(test) { 100 } else { 1 },
             pruner: ledger_pruner,
             max_versions_to_prune_per_batch: ledger_pruner_config.batch_size as u64,
+            schedule: PruningSchedule::new(schedule, "ledger_pruner"),
             quit_worker: AtomicBool::new(false),
         }
     }
@@ -46,6 +51,17 @@ impl LedgerPrunerWorker {
     // Loop that does the real pruning job.
     pub(crate) fn work(&self) {
         while !self.quit_worker.load(Ordering::Relaxed) {
+            if self.pruner.is_pruning_pending()
+                && !self.schedule.may_prune(
+                    self.pruner
+                        .target_version()
+                        .saturating_sub(self.pruner.min_readable_version()),
+                )
+            {
+                sleep(PAUSE_INTERVAL);
+                continue;
+            }
+
             let pruner_result = self
                 .pruner
                 .prune(self.max_versions_to_prune_per_batch as usize);
@@ -15,9 +15,13 @@ use crate::{
     },
     utils, ChangeSet, EventStore, LedgerStore, TransactionStore,
 };
+use aptos_logger::error;
 use aptos_types::transaction::{AtomicVersion, Version};
 use schemadb::{SchemaBatch, DB};
-use std::sync::{atomic::Ordering, Arc};
+use std::{
+    cmp::{max, min},
+    sync::{atomic::Ordering, Arc},
+};
 
 pub const LEDGER_PRUNER_NAME: &str = "ledger_pruner";
 
@@ -28,6 +32,9 @@ pub struct LedgerPruner {
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
     min_readable_version: AtomicVersion,
+    /// The lowest version the sub-pruners have pruned to, which is below the min readable
+    /// version while any of them is behind.
+    min_sub_pruner_progress: AtomicVersion,
     transaction_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     event_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     write_set_pruner: Arc<dyn DBSubPruner + Send + Sync>,
@@ -46,10 +53,13 @@ impl DBPruner for LedgerPruner {
 
         // Collect the schema batch writes
         let mut db_batch = SchemaBatch::new();
-        let current_target_version = self.prune_inner(max_versions, &mut db_batch)?;
+        let (min_readable_version, min_sub_pruner_progress) =
+            self.prune_inner(max_versions, &mut db_batch)?;
+        // Keep recording the progress of the ledger pruner as a whole, as the min readable
+        // version of the ledger.
         db_batch.put::<PrunerMetadataSchema>(
             &PrunerTag::LedgerPruner,
-            &PrunerMetadata::LatestVersion(current_target_version),
+            &PrunerMetadata::LatestVersion(min_readable_version),
         )?;
         // Commit all the changes to DB atomically
         self.db.write_schemas(db_batch)?;
@@ -57,17 +67,23 @@ impl DBPruner for LedgerPruner {
         // TODO(zcc): recording progress after writing schemas might provide wrong answers to
         // API calls when they query min_readable_version while the write_schemas are still in
         // progress.
-        self.record_progress(current_target_version);
-        Ok(current_target_version)
+        self.record_progress(min_readable_version);
+        self.min_sub_pruner_progress
+            .store(min_sub_pruner_progress, Ordering::Relaxed);
+        Ok(min_readable_version)
     }
 
     fn initialize_min_readable_version(&self) -> anyhow::Result<Version> {
-        Ok(self
-            .db
-            .get::<PrunerMetadataSchema>(&PrunerTag::LedgerPruner)?
-            .map_or(0, |pruned_until_version| match pruned_until_version {
-                PrunerMetadata::LatestVersion(version) => version,
-            }))
+        let mut min_readable_version = self.legacy_progress()?;
+        let mut min_sub_pruner_progress = Version::MAX;
+        for (tag, _, _) in self.sub_pruners() {
+            let progress = self.sub_pruner_progress(tag)?;
+            min_readable_version = max(min_readable_version, progress);
+            min_sub_pruner_progress = min(min_sub_pruner_progress, progress);
+        }
+        self.min_sub_pruner_progress
+            .store(min_sub_pruner_progress, Ordering::Relaxed);
+        Ok(min_readable_version)
     }
 
     fn min_readable_version(&self) -> Version {
@@ -82,6 +98,10 @@ impl DBPruner for LedgerPruner {
         self.target_version.load(Ordering::Relaxed)
     }
 
+    fn is_pruning_pending(&self) -> bool {
+        self.target_version() > self.min_sub_pruner_progress.load(Ordering::Relaxed)
+    }
+
     fn record_progress(&self, min_readable_version: Version) {
         self.min_readable_version
             .store(min_readable_version, Ordering::Relaxed);
@@ -107,6 +127,7 @@ impl LedgerPruner {
             db,
             target_version: AtomicVersion::new(0),
             min_readable_version: AtomicVersion::new(0),
+            min_sub_pruner_progress: AtomicVersion::new(0),
             ledger_counter_pruner: Arc::new(LedgerCounterPruner::new(ledger_store)),
             transaction_store_pruner: Arc::new(TransactionStorePruner::new(
                 transaction_store.clone(),
@@ -118,6 +139,55 @@ impl LedgerPruner {
         pruner
     }
 
+    /// The sub-pruners, with the tags their progress is recorded under and their names.
+    fn sub_pruners(&self) -> [(PrunerTag, &'static str, &dyn DBSubPruner); 4] {
+        [
+            (
+                PrunerTag::TransactionStorePruner,
+                "transaction_store_pruner",
+                self.transaction_store_pruner.as_ref(),
+            ),
+            (
+                PrunerTag::WriteSetPruner,
+                "write_set_pruner",
+                self.write_set_pruner.as_ref(),
+            ),
+            (
+                PrunerTag::LedgerCounterPruner,
+                "ledger_counter_pruner",
+                self.ledger_counter_pruner.as_ref(),
+            ),
+            (
+                PrunerTag::EventStorePruner,
+                "event_store_pruner",
+                self.event_store_pruner.as_ref(),
+            ),
+        ]
+    }
+
+    fn get_progress(&self, tag: &PrunerTag) -> anyhow::Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<PrunerMetadataSchema>(tag)?
+            .map(|pruned_until_version| match pruned_until_version {
+                PrunerMetadata::LatestVersion(version) => version,
+            }))
+    }
+
+    /// The progress of the ledger pruner as a whole, which databases written before the
+    /// sub-pruners recorded their own progress have in its place.
+    fn legacy_progress(&self) -> anyhow::Result<Version> {
+        Ok(self.get_progress(&PrunerTag::LedgerPruner)?.unwrap_or(0))
+    }
+
+    /// The version the sub-pruner with the tag has pruned its store to.
+    fn sub_pruner_progress(&self, tag: PrunerTag) -> anyhow::Result<Version> {
+        match self.get_progress(&tag)? {
+            Some(version) => Ok(version),
+            None => self.legacy_progress(),
+        }
+    }
+
     /// Prunes the genesis transaction and saves the db alterations to the given change set
     pub fn prune_genesis(ledger_db: Arc<DB>, change_set: &mut ChangeSet) -> anyhow::Result<()> {
         let target_version = 1; // The genesis version is 0. Delete [0,1) (exclusive)
@@ -130,29 +200,55 @@ impl LedgerPruner {
         Ok(())
     }
 
+    /// Prunes a batch of versions, returning the min readable version and the lowest progress of
+    /// the sub-pruners once the batch is committed.
     fn prune_inner(
         &self,
         max_versions: usize,
         db_batch: &mut SchemaBatch,
-    ) -> anyhow::Result<Version> {
-        let min_readable_version = self.min_readable_version();
-
+    ) -> anyhow::Result<(Version, Version)> {
         // Current target version might be less than the target version to ensure we don't prune
         // more than max_version in one go.
         let current_target_version = self.get_currrent_batch_target(max_versions as Version);
 
-        self.transaction_store_pruner.prune(
-            db_batch,
-            min_readable_version,
-            current_target_version,
-        )?;
-        self.write_set_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
-        self.ledger_counter_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
-        self.event_store_pruner
-            .prune(db_batch, min_readable_version, current_target_version)?;
-
-        Ok(current_target_version)
+        // Each sub-pruner prunes from its own progress, so one that fell behind, e.g. because it
+        // failed, catches up without the others redoing their work. A failure of one sub-pruner
+        // doesn't hold back the others: its progress isn't recorded, so it retries next time.
+        // Whatever it deleted before failing is deleted again then.
+        let mut min_readable_version = self.min_readable_version();
+        let mut min_sub_pruner_progress = Version::MAX;
+        for (tag, name, sub_pruner) in self.sub_pruners() {
+            let progress = self.sub_pruner_progress(tag.clone())?;
+            let target_version = min(current_target_version, progress + max_versions as Version);
+            if target_version <= progress {
+                min_sub_pruner_progress = min(min_sub_pruner_progress, progress);
+                continue;
+            }
+            match sub_pruner.prune(db_batch, progress, target_version) {
+                Ok(()) => {
+                    db_batch.put::<PrunerMetadataSchema>(
+                        &tag,
+                        &PrunerMetadata::LatestVersion(target_version),
+                    )?;
+                    PRUNER_LEAST_READABLE_VERSION
+                        .with_label_values(&[name])
+                        .set(target_version as i64);
+                    min_readable_version = max(min_readable_version, target_version);
+                    min_sub_pruner_progress = min(min_sub_pruner_progress, target_version);
+                }
+                Err(e) => {
+                    min_sub_pruner_progress = min(min_sub_pruner_progress, progress);
+                    error!(
+                        error = ?e,
+                        progress = progress,
+                        target_version = target_version,
+                        "{} failed to prune, will retry.",
+                        name
+                    );
+                }
+            }
+        }
+
+        Ok((min_readable_version, min_sub_pruner_progress))
     }
 }
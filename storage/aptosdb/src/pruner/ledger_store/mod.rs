@@ -3,3 +3,6 @@
 
 pub(crate) mod ledger_counter_pruner;
 pub(crate) mod ledger_store_pruner;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,170 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    pruner::{
+        db_pruner::DBPruner,
+        pruner_metadata::{PrunerMetadata, PrunerTag},
+        utils,
+    },
+    pruner_metadata::PrunerMetadataSchema,
+    schema::transaction::TransactionSchema,
+    AptosDB, ChangeSet,
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_temppath::TempPath;
+use aptos_types::transaction::{Transaction, Version};
+use schemadb::{SchemaBatch, DB};
+use std::sync::Arc;
+
+const SUB_PRUNER_TAGS: [PrunerTag; 4] = [
+    PrunerTag::EventStorePruner,
+    PrunerTag::WriteSetPruner,
+    PrunerTag::TransactionStorePruner,
+    PrunerTag::LedgerCounterPruner,
+];
+
+fn put_progress(db: &DB, tag: PrunerTag, version: Version) {
+    db.put::<PrunerMetadataSchema>(&tag, &PrunerMetadata::LatestVersion(version))
+        .unwrap();
+}
+
+fn get_progress(db: &DB, tag: PrunerTag) -> Option<Version> {
+    db.get::<PrunerMetadataSchema>(&tag)
+        .unwrap()
+        .map(|metadata| match metadata {
+            PrunerMetadata::LatestVersion(version) => version,
+        })
+}
+
+#[test]
+fn test_migrate_from_single_tag() {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_db = Arc::clone(&aptos_db.ledger_db);
+    // Only the progress of the ledger pruner as a whole is recorded.
+    put_progress(&ledger_db, PrunerTag::LedgerPruner, 5);
+
+    let pruner = utils::create_ledger_pruner(Arc::clone(&ledger_db));
+    assert_eq!(pruner.min_readable_version(), 5);
+    assert!(!pruner.is_pruning_pending());
+
+    pruner.set_target_version(8);
+    assert_eq!(pruner.prune(10).unwrap(), 8);
+    for tag in SUB_PRUNER_TAGS {
+        assert_eq!(get_progress(&ledger_db, tag), Some(8));
+    }
+    assert_eq!(get_progress(&ledger_db, PrunerTag::LedgerPruner), Some(8));
+}
+
+#[test]
+fn test_lagging_sub_pruner_catches_up() {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_db = Arc::clone(&aptos_db.ledger_db);
+    put_progress(&ledger_db, PrunerTag::LedgerPruner, 6);
+    for tag in SUB_PRUNER_TAGS {
+        put_progress(&ledger_db, tag, 6);
+    }
+    put_progress(&ledger_db, PrunerTag::EventStorePruner, 2);
+
+    // The min readable version is what the most advanced sub-pruner pruned to, but the lagging
+    // one still has work to do.
+    let pruner = utils::create_ledger_pruner(Arc::clone(&ledger_db));
+    assert_eq!(pruner.min_readable_version(), 6);
+    pruner.set_target_version(6);
+    assert!(pruner.is_pruning_pending());
+
+    assert_eq!(pruner.prune(3).unwrap(), 6);
+    assert_eq!(
+        get_progress(&ledger_db, PrunerTag::EventStorePruner),
+        Some(5)
+    );
+    assert!(pruner.is_pruning_pending());
+
+    assert_eq!(pruner.prune(3).unwrap(), 6);
+    for tag in SUB_PRUNER_TAGS {
+        assert_eq!(get_progress(&ledger_db, tag), Some(6));
+    }
+    assert!(!pruner.is_pruning_pending());
+
+    // A restarted pruner picks up the progress of each sub-pruner.
+    let pruner = utils::create_ledger_pruner(Arc::clone(&ledger_db));
+    assert_eq!(pruner.min_readable_version(), 6);
+    pruner.set_target_version(6);
+    assert!(!pruner.is_pruning_pending());
+}
+
+#[test]
+fn test_failed_transaction_store_pruner_keeps_hash_index() {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_db = Arc::clone(&aptos_db.ledger_db);
+    let transaction_store = &aptos_db.transaction_store;
+    let txns: Vec<_> = (0..10)
+        .map(|_| Transaction::StateCheckpoint(HashValue::random()))
+        .collect();
+    let mut cs = ChangeSet::new();
+    for (version, txn) in txns.iter().enumerate() {
+        transaction_store
+            .put_transaction(version as Version, txn, &mut cs)
+            .unwrap();
+    }
+    ledger_db.write_schemas(cs.batch).unwrap();
+
+    // A missing transaction makes the transaction store pruner fail to load the ones to prune.
+    let batch = SchemaBatch::new();
+    batch.delete::<TransactionSchema>(&3).unwrap();
+    ledger_db.write_schemas(batch).unwrap();
+
+    let pruner = utils::create_ledger_pruner(Arc::clone(&ledger_db));
+    pruner.set_target_version(6);
+    assert_eq!(pruner.prune(10).unwrap(), 6);
+    assert_eq!(
+        get_progress(&ledger_db, PrunerTag::TransactionStorePruner),
+        None
+    );
+    assert!(pruner.is_pruning_pending());
+    // The transactions and their hash index are left alone, rather than pruned apart.
+    for (version, txn) in txns.iter().enumerate() {
+        if version != 3 {
+            assert_eq!(
+                transaction_store
+                    .get_transaction(version as Version)
+                    .unwrap(),
+                *txn
+            );
+        }
+        assert_eq!(
+            transaction_store
+                .get_transaction_version_by_hash(&txn.hash(), 9)
+                .unwrap(),
+            Some(version as Version)
+        );
+    }
+
+    // Once the transaction is back, the pruner catches up on the retry.
+    let mut cs = ChangeSet::new();
+    transaction_store
+        .put_transaction(3, &txns[3], &mut cs)
+        .unwrap();
+    ledger_db.write_schemas(cs.batch).unwrap();
+    assert_eq!(pruner.prune(10).unwrap(), 6);
+    assert_eq!(
+        get_progress(&ledger_db, PrunerTag::TransactionStorePruner),
+        Some(6)
+    );
+    assert!(!pruner.is_pruning_pending());
+    for (version, txn) in txns.iter().enumerate() {
+        let version = version as Version;
+        let version_by_hash = transaction_store
+            .get_transaction_version_by_hash(&txn.hash(), 9)
+            .unwrap();
+        if version < 6 {
+            assert!(transaction_store.get_transaction(version).is_err());
+            assert_eq!(version_by_hash, None);
+        } else {
+            assert_eq!(version_by_hash, Some(version));
+        }
+    }
+}
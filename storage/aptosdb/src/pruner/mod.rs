@@ -1,5 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
+pub(crate) mod backup_progress;
+pub(crate) mod commit_tracker;
 pub(crate) mod db_pruner;
 pub(crate) mod db_sub_pruner;
 pub(crate) mod event_store;
@@ -7,6 +9,8 @@ pub(crate) mod ledger_pruner_worker;
 pub(crate) mod ledger_store;
 pub(crate) mod pruner_manager;
 pub(crate) mod pruner_metadata;
+pub(crate) mod schedule;
+pub(crate) mod state_kv_store;
 pub(crate) mod state_pruner_worker;
 pub(crate) mod state_store;
 pub(crate) mod transaction_store;
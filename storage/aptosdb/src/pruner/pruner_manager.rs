@@ -21,6 +21,10 @@ pub trait PrunerManager: Debug + Sync {
 
     fn get_min_readable_version(&self) -> Version;
 
+    /// Reads the min readable version from the progress recorded in the DB again, for DBs pruned
+    /// by another instance, e.g. secondary instances catching up with their primary.
+    fn reload_min_readable_version(&self) -> anyhow::Result<()>;
+
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version);
 
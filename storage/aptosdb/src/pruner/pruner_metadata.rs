@@ -17,5 +17,25 @@ pub(crate) enum PrunerMetadata {
 #[repr(u8)]
 pub(crate) enum PrunerTag {
     StateMerklePruner = 0,
+    /// The min readable version of the ledger, i.e. the highest version any of the ledger
+    /// sub-pruners has pruned to. Before the sub-pruners recorded their own progress, this was
+    /// the progress of all of them.
     LedgerPruner = 1,
+    /// The highest version backed up, which the pruners don't prune past.
+    BackupProgress = 2,
+    /// Recorded in each shard of the state values, the version the values that became stale
+    /// before are pruned in the shard.
+    StateKvPruner = 3,
+    /// The version each ledger sub-pruner has pruned its store to.
+    EventStorePruner = 4,
+    WriteSetPruner = 5,
+    TransactionStorePruner = 6,
+    LedgerCounterPruner = 7,
+    /// The version from which the index of resources by module lists every resource. Absent
+    /// until the index is complete, i.e. in DBs written before the index or restored without it
+    /// and not backfilled since.
+    ResourceByModuleIndex = 8,
+    /// The latest version whose authentication keys are indexed, recorded in the batch writing
+    /// them. The index is kept along with the indexer, whose own progress is in its own DB.
+    AuthKeyIndex = 9,
 }
@@ -0,0 +1,161 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metrics::PRUNER_SCHEDULE_PAUSED;
+use aptos_config::config::PrunerScheduleConfig;
+use aptos_infallible::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+/// How long a decision is reused for, so that the clock and the load are only read once in a while
+/// rather than before every batch.
+const DECISION_TTL: Duration = Duration::from_secs(1);
+
+/// How long a pruner held back by its schedule waits before checking it again.
+pub(crate) const PAUSE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Decides whether a pruner may run at the moment, according to its `PrunerScheduleConfig`.
+#[derive(Debug)]
+pub(crate) struct PruningSchedule {
+    config: PrunerScheduleConfig,
+    /// Labels the paused gauge of the pruner.
+    pruner_name: &'static str,
+    last_decision: Mutex<Option<(Instant, bool)>>,
+}
+
+impl PruningSchedule {
+    pub fn new(config: PrunerScheduleConfig, pruner_name: &'static str) -> Self {
+        Self {
+            config,
+            pruner_name,
+            last_decision: Mutex::new(None),
+        }
+    }
+
+    fn is_restricted(&self) -> bool {
+        self.has_window() || self.config.max_load_percent > 0
+    }
+
+    fn has_window(&self) -> bool {
+        self.config.window_start_minute % MINUTES_PER_DAY
+            != self.config.window_end_minute % MINUTES_PER_DAY
+    }
+
+    /// Whether the pruner, `lag` versions behind its target, may prune now.
+    pub fn may_prune(&self, lag: u64) -> bool {
+        if !self.is_restricted() {
+            return true;
+        }
+        let mut last_decision = self.last_decision.lock();
+        if let Some((decided_at, may_prune)) = *last_decision {
+            if decided_at.elapsed() < DECISION_TTL {
+                return may_prune;
+            }
+        }
+        let may_prune = self.may_prune_at(lag, minute_of_day(), load_percent);
+        *last_decision = Some((Instant::now(), may_prune));
+        PRUNER_SCHEDULE_PAUSED
+            .with_label_values(&[self.pruner_name])
+            .set(i64::from(!may_prune));
+        may_prune
+    }
+
+    /// Takes the load lazily, as reading it is only needed outside of the window.
+    fn may_prune_at(
+        &self,
+        lag: u64,
+        minute_of_day: u16,
+        load_percent: impl FnOnce() -> Option<u32>,
+    ) -> bool {
+        if self.config.catch_up_lag_versions > 0 && lag >= self.config.catch_up_lag_versions {
+            return true;
+        }
+        if self.has_window() && self.is_in_window(minute_of_day) {
+            return true;
+        }
+        self.config.max_load_percent > 0
+            && load_percent().map_or(false, |load| load < self.config.max_load_percent)
+    }
+
+    fn is_in_window(&self, minute_of_day: u16) -> bool {
+        let start = self.config.window_start_minute % MINUTES_PER_DAY;
+        let end = self.config.window_end_minute % MINUTES_PER_DAY;
+        if start < end {
+            start <= minute_of_day && minute_of_day < end
+        } else {
+            start <= minute_of_day || minute_of_day < end
+        }
+    }
+}
+
+fn minute_of_day() -> u16 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    ((secs / 60) % MINUTES_PER_DAY as u64) as u16
+}
+
+/// The load average of the last minute in percent of the number of CPUs, if it can be read.
+#[cfg(target_os = "linux")]
+fn load_percent() -> Option<u32> {
+    let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+    let load: f64 = loadavg.split_whitespace().next()?.parse().ok()?;
+    let num_cpus = std::thread::available_parallelism().ok()?.get();
+    Some((load * 100.0 / num_cpus as f64) as u32)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn load_percent() -> Option<u32> {
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::PruningSchedule;
+    use aptos_config::config::PrunerScheduleConfig;
+
+    #[test]
+    fn test_unrestricted_schedule() {
+        let schedule = PruningSchedule::new(PrunerScheduleConfig::default(), "test_pruner");
+        assert!(schedule.may_prune(0));
+    }
+
+    #[test]
+    fn test_window() {
+        // From 22:00 to 02:00 UTC.
+        let schedule = PruningSchedule::new(
+            PrunerScheduleConfig {
+                window_start_minute: 22 * 60,
+                window_end_minute: 2 * 60,
+                ..PrunerScheduleConfig::default()
+            },
+            "test_pruner",
+        );
+        assert!(schedule.may_prune_at(0, 23 * 60, || None));
+        assert!(schedule.may_prune_at(0, 60, || None));
+        assert!(!schedule.may_prune_at(0, 2 * 60, || None));
+        assert!(!schedule.may_prune_at(0, 12 * 60, || None));
+        // No load threshold is set, so the load doesn't matter.
+        assert!(!schedule.may_prune_at(0, 12 * 60, || Some(0)));
+    }
+
+    #[test]
+    fn test_load_and_catch_up() {
+        let schedule = PruningSchedule::new(
+            PrunerScheduleConfig {
+                max_load_percent: 50,
+                catch_up_lag_versions: 1000,
+                ..PrunerScheduleConfig::default()
+            },
+            "test_pruner",
+        );
+        assert!(schedule.may_prune_at(0, 0, || Some(20)));
+        assert!(!schedule.may_prune_at(0, 0, || Some(80)));
+        assert!(!schedule.may_prune_at(0, 0, || None));
+        // Far enough behind, the pruner catches up whatever the load.
+        assert!(!schedule.may_prune_at(999, 0, || Some(80)));
+        assert!(schedule.may_prune_at(1000, 0, || Some(80)));
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics::STATE_KV_SHARD_PRUNED_VERSION,
+    pruner::pruner_metadata::{PrunerMetadata, PrunerTag},
+    pruner_metadata::PrunerMetadataSchema,
+    schema::state_value::StateValueSchema,
+    stale_state_value_index::StaleStateValueIndexSchema,
+    state_kv_db::StateKvDb,
+};
+use anyhow::Result;
+use aptos_types::transaction::Version;
+use rayon::prelude::*;
+use schemadb::{ReadOptions, SchemaBatch};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod test;
+
+/// The maximum number of stale state values deleted from a shard in a single write.
+const MAX_STALE_VALUES_PER_BATCH: usize = 10_000;
+
+#[derive(Debug)]
+/// Responsible for pruning the state values that have become stale, in every shard.
+pub struct StateKvPruner {
+    state_kv_db: Arc<StateKvDb>,
+}
+
+impl StateKvPruner {
+    pub fn new(state_kv_db: Arc<StateKvDb>) -> Self {
+        Self { state_kv_db }
+    }
+
+    /// Prunes the state values that became stale at `min_readable_version` or before, which are
+    /// not readable at any version from it on. Each shard is pruned from where it was left, so a
+    /// shard that fell behind, e.g. because of a crash, catches up.
+    pub fn prune(&self, min_readable_version: Version) -> Result<()> {
+        (0..self.state_kv_db.num_shards())
+            .into_par_iter()
+            .try_for_each(|shard_id| self.prune_shard(shard_id, min_readable_version))
+    }
+
+    fn prune_shard(&self, shard_id: usize, min_readable_version: Version) -> Result<()> {
+        let db = self.state_kv_db.db_shard(shard_id);
+        // The values that became stale before this version are pruned.
+        let mut pruned_until_version = db
+            .get::<PrunerMetadataSchema>(&PrunerTag::StateKvPruner)?
+            .map_or(0, |pruned_until_version| match pruned_until_version {
+                PrunerMetadata::LatestVersion(version) => version,
+            });
+
+        while pruned_until_version <= min_readable_version {
+            let batch = SchemaBatch::new();
+            let mut next_pruned_until_version = min_readable_version + 1;
+            let mut num_values = 0;
+            let mut iter = db.iter::<StaleStateValueIndexSchema>(ReadOptions::default())?;
+            iter.seek(&pruned_until_version)?;
+            for item in iter {
+                let (index, _) = item?;
+                if index.stale_since_version > min_readable_version {
+                    break;
+                }
+                if num_values == MAX_STALE_VALUES_PER_BATCH {
+                    next_pruned_until_version = index.stale_since_version;
+                    break;
+                }
+                batch.delete::<StaleStateValueIndexSchema>(&index)?;
+                batch.delete::<StateValueSchema>(&(index.state_key, index.version))?;
+                num_values += 1;
+            }
+            batch.put::<PrunerMetadataSchema>(
+                &PrunerTag::StateKvPruner,
+                &PrunerMetadata::LatestVersion(next_pruned_until_version),
+            )?;
+            db.write_schemas(batch)?;
+
+            pruned_until_version = next_pruned_until_version;
+            STATE_KV_SHARD_PRUNED_VERSION
+                .with_label_values(&[shard_id.to_string().as_str()])
+                .set(pruned_until_version as i64);
+        }
+        Ok(())
+    }
+}
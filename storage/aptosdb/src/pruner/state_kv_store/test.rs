@@ -0,0 +1,262 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use aptos_config::config::{
+    RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+    TARGET_SNAPSHOT_SIZE,
+};
+use aptos_temppath::TempPath;
+use aptos_types::state_store::{state_key::StateKey, state_value::StateValue};
+use storage_interface::DbReader;
+
+use crate::{change_set::ChangeSet, AptosDB};
+
+use super::*;
+
+const NUM_SHARDS: usize = 4;
+
+fn put_value_set(db: &AptosDB, value_set: HashMap<StateKey, Option<StateValue>>, version: Version) {
+    let mut cs = ChangeSet::new();
+    db.state_store
+        .put_value_sets(vec![&value_set], version, &mut cs)
+        .unwrap();
+    db.state_kv_db.commit(cs.state_kv_batches).unwrap();
+    db.ledger_db.write_schemas(cs.batch).unwrap();
+}
+
+fn state_key(i: usize) -> StateKey {
+    StateKey::Raw(format!("test_key{}", i).into_bytes())
+}
+
+fn count_state_values(db: &AptosDB) -> usize {
+    (0..db.state_kv_db.num_shards())
+        .map(|shard_id| {
+            let mut read_opts = ReadOptions::default();
+            read_opts.set_total_order_seek(true);
+            let mut iter = db
+                .state_kv_db
+                .db_shard(shard_id)
+                .iter::<StateValueSchema>(read_opts)
+                .unwrap();
+            iter.seek_to_first();
+            iter.count()
+        })
+        .sum()
+}
+
+#[test]
+fn test_state_values_spread_across_shards() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_state_kv_shards(&tmp_dir, NUM_SHARDS);
+    let num_keys = 32;
+    put_value_set(
+        &db,
+        (0..num_keys)
+            .map(|i| (state_key(i), Some(StateValue::from(vec![i as u8]))))
+            .collect(),
+        0, /* version */
+    );
+
+    for i in 0..num_keys {
+        let key = state_key(i);
+        let shard_id = db.state_kv_db.shard_id(&key);
+        assert!(shard_id < NUM_SHARDS);
+        assert_eq!(
+            db.state_kv_db
+                .db_shard(shard_id)
+                .get::<StateValueSchema>(&(key.clone(), 0))
+                .unwrap(),
+            Some(Some(StateValue::from(vec![i as u8])))
+        );
+        assert_eq!(
+            db.state_store.get_state_value_by_version(&key, 0).unwrap(),
+            Some(StateValue::from(vec![i as u8]))
+        );
+    }
+    // Nothing is left in the ledger DB.
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_total_order_seek(true);
+    let mut iter = db.ledger_db.iter::<StateValueSchema>(read_opts).unwrap();
+    iter.seek_to_first();
+    assert!(iter.next().is_none());
+    assert_eq!(count_state_values(&db), num_keys);
+}
+
+#[test]
+fn test_state_kv_pruner() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_state_kv_shards(&tmp_dir, NUM_SHARDS);
+    let num_keys = 8;
+    let num_versions = 5;
+    for version in 0..num_versions {
+        put_value_set(
+            &db,
+            (0..num_keys)
+                .map(|i| (state_key(i), Some(StateValue::from(vec![version as u8]))))
+                .collect(),
+            version,
+        );
+    }
+    // The last key is deleted at the last version, which leaves a tombstone.
+    put_value_set(
+        &db,
+        vec![(state_key(num_keys - 1), None)].into_iter().collect(),
+        num_versions,
+    );
+    assert_eq!(
+        count_state_values(&db),
+        num_keys * num_versions as usize + 1
+    );
+
+    let pruner = StateKvPruner::new(Arc::clone(&db.state_kv_db));
+    // Nothing is stale at version 0.
+    pruner.prune(0).unwrap();
+    assert_eq!(
+        count_state_values(&db),
+        num_keys * num_versions as usize + 1
+    );
+
+    // The values written at versions 0 and 1 became stale at versions 1 and 2.
+    pruner.prune(2).unwrap();
+    assert_eq!(
+        count_state_values(&db),
+        num_keys * (num_versions as usize - 2) + 1
+    );
+    for i in 0..num_keys {
+        assert_eq!(
+            db.state_store
+                .get_state_value_by_version(&state_key(i), 2)
+                .unwrap(),
+            Some(StateValue::from(vec![2]))
+        );
+    }
+    // Each shard records its own progress.
+    for shard_id in 0..NUM_SHARDS {
+        assert_eq!(
+            db.state_kv_db
+                .db_shard(shard_id)
+                .get::<PrunerMetadataSchema>(&PrunerTag::StateKvPruner)
+                .unwrap(),
+            Some(PrunerMetadata::LatestVersion(3))
+        );
+    }
+
+    // Pruning again until the same version is a no-op.
+    pruner.prune(2).unwrap();
+    assert_eq!(
+        count_state_values(&db),
+        num_keys * (num_versions as usize - 2) + 1
+    );
+
+    // Once pruned until the last version, only the latest values are left, and the tombstone is
+    // gone along with the value it deleted.
+    pruner.prune(num_versions).unwrap();
+    assert_eq!(count_state_values(&db), num_keys - 1);
+    for i in 0..num_keys - 1 {
+        assert_eq!(
+            db.state_store
+                .get_state_value_by_version(&state_key(i), num_versions)
+                .unwrap(),
+            Some(StateValue::from(vec![num_versions as u8 - 1]))
+        );
+    }
+    assert_eq!(
+        db.state_store
+            .get_state_value_by_version(&state_key(num_keys - 1), num_versions)
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_sharding_cannot_be_turned_on_for_existing_state_values() {
+    let tmp_dir = TempPath::new();
+    {
+        let db = AptosDB::new_for_test(&tmp_dir);
+        put_value_set(
+            &db,
+            vec![(state_key(0), Some(StateValue::from(vec![0])))]
+                .into_iter()
+                .collect(),
+            0, /* version */
+        );
+    }
+    let mut rocksdb_configs = RocksdbConfigs::default();
+    rocksdb_configs.state_kv_sharding.num_shards = NUM_SHARDS;
+    assert!(AptosDB::open(
+        &tmp_dir,
+        false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        rocksdb_configs,
+        false,
+        TARGET_SNAPSHOT_SIZE,
+        TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+    )
+    .is_err());
+}
+
+fn open_with_sharding(
+    db_root_path: &TempPath,
+    num_shards: usize,
+    shard_paths: Vec<std::path::PathBuf>,
+) -> anyhow::Result<AptosDB> {
+    let mut rocksdb_configs = RocksdbConfigs::default();
+    rocksdb_configs.state_kv_sharding.num_shards = num_shards;
+    rocksdb_configs.state_kv_sharding.shard_paths = shard_paths;
+    AptosDB::open(
+        db_root_path,
+        false,
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        rocksdb_configs,
+        false,
+        TARGET_SNAPSHOT_SIZE,
+        TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+    )
+}
+
+#[test]
+fn test_number_of_shards_cannot_change() {
+    let tmp_dir = TempPath::new();
+    {
+        let _db = AptosDB::new_for_test_with_state_kv_shards(&tmp_dir, NUM_SHARDS);
+    }
+
+    assert!(open_with_sharding(&tmp_dir, NUM_SHARDS - 1, vec![]).is_err());
+    assert!(open_with_sharding(&tmp_dir, NUM_SHARDS + 1, vec![]).is_err());
+    assert!(open_with_sharding(&tmp_dir, 0, vec![]).is_err());
+    open_with_sharding(&tmp_dir, NUM_SHARDS, vec![]).unwrap();
+
+    // Nor can a DB whose state values are in the ledger DB become sharded, even if it has none.
+    let tmp_dir = TempPath::new();
+    {
+        let _db = AptosDB::new_for_test(&tmp_dir);
+    }
+    assert!(open_with_sharding(&tmp_dir, NUM_SHARDS, vec![]).is_err());
+}
+
+#[test]
+fn test_shards_at_custom_paths_are_checked() {
+    let tmp_dir = TempPath::new();
+    let shard_dirs = [TempPath::new(), TempPath::new()];
+    let shard_paths: Vec<_> = shard_dirs
+        .iter()
+        .map(|dir| dir.path().to_path_buf())
+        .collect();
+    {
+        let _db = open_with_sharding(&tmp_dir, NUM_SHARDS, shard_paths.clone()).unwrap();
+    }
+
+    // Unsharding when none of the shards are under the DB directory.
+    assert!(open_with_sharding(&tmp_dir, 0, vec![]).is_err());
+    // The shards at custom paths not configured anymore, or swapped.
+    assert!(open_with_sharding(&tmp_dir, NUM_SHARDS, vec![]).is_err());
+    assert!(open_with_sharding(
+        &tmp_dir,
+        NUM_SHARDS,
+        vec![shard_paths[1].clone(), shard_paths[0].clone()]
+    )
+    .is_err());
+    open_with_sharding(&tmp_dir, NUM_SHARDS, shard_paths).unwrap();
+}
@@ -5,8 +5,9 @@
 //! meant to be triggered by other threads as they commit new data to the DB.
 
 use crate::metrics::{PRUNER_BATCH_SIZE, PRUNER_WINDOW};
+use crate::node_cache::NodeCache;
 
-use aptos_config::config::StateMerklePrunerConfig;
+use aptos_config::config::{PrunerScheduleConfig, StateMerklePrunerConfig};
 use aptos_infallible::Mutex;
 
 use crate::pruner::pruner_manager::PrunerManager;
@@ -14,9 +15,12 @@ use aptos_types::transaction::Version;
 use schemadb::DB;
 use std::{sync::Arc, thread::JoinHandle};
 
+use crate::pruner::backup_progress::BackupProgress;
+use crate::pruner::commit_tracker::CommitTracker;
 use crate::pruner::db_pruner::DBPruner;
 use crate::pruner::state_pruner_worker::StatePrunerWorker;
 use crate::pruner::state_store::StateMerklePruner;
+use crate::state_kv_db::StateKvDb;
 use crate::utils;
 
 /// The `Pruner` is meant to be part of a `AptosDB` instance and runs in the background to prune old
@@ -47,6 +51,11 @@ pub struct StatePrunerManager {
     latest_version: Arc<Mutex<Version>>,
     /// Offset for displaying to users
     user_pruning_window_offset: u64,
+    /// The state merkle commits the pruner yields to report into this.
+    commit_tracker: Arc<CommitTracker>,
+    /// The progress of the backup, past which the pruner doesn't prune. `None` if the pruner
+    /// ignores the backup.
+    backup_progress: Option<Arc<BackupProgress>>,
 }
 
 impl PrunerManager for StatePrunerManager {
@@ -62,6 +71,12 @@ impl PrunerManager for StatePrunerManager {
         self.pruner.as_ref().min_readable_version()
     }
 
+    fn reload_min_readable_version(&self) -> anyhow::Result<()> {
+        let min_readable_version = self.pruner.initialize_min_readable_version()?;
+        self.pruner.record_progress(min_readable_version);
+        Ok(())
+    }
+
     fn get_min_viable_version(&self) -> Version {
         let min_version = self.get_min_readable_version();
         if self.is_pruner_enabled() {
@@ -88,9 +103,13 @@ impl PrunerManager for StatePrunerManager {
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_enabled);
+        let mut target_version = latest_version.saturating_sub(self.prune_window);
+        if let Some(backup_progress) = &self.backup_progress {
+            target_version = backup_progress.hold(target_version, self.pruner.target_version());
+        }
         self.pruner_worker
             .as_ref()
-            .set_target_db_version(latest_version.saturating_sub(self.prune_window));
+            .set_target_db_version(target_version);
     }
 
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
@@ -128,10 +147,14 @@ impl StatePrunerManager {
     /// Creates a worker thread that waits on a channel for pruning commands.
     pub fn new(
         state_merkle_rocksdb: Arc<DB>,
+        state_kv_db: Arc<StateKvDb>,
+        node_cache: Arc<NodeCache>,
         state_merkle_pruner_config: StateMerklePrunerConfig,
+        schedule: PrunerScheduleConfig,
+        backup_progress: Option<Arc<BackupProgress>>,
     ) -> Self {
         let state_db_clone = Arc::clone(&state_merkle_rocksdb);
-        let state_pruner = utils::create_state_pruner(state_db_clone);
+        let state_pruner = utils::create_state_pruner(state_db_clone, state_kv_db, node_cache);
 
         if state_merkle_pruner_config.enable {
             PRUNER_WINDOW
@@ -143,9 +166,12 @@ impl StatePrunerManager {
                 .set(state_merkle_pruner_config.batch_size as i64);
         }
 
+        let commit_tracker = Arc::new(CommitTracker::default());
         let state_pruner_worker = Arc::new(StatePrunerWorker::new(
             Arc::clone(&state_pruner),
             state_merkle_pruner_config,
+            Arc::clone(&commit_tracker),
+            schedule,
         ));
         let state_pruner_worker_clone = Arc::clone(&state_pruner_worker);
 
@@ -170,9 +196,17 @@ impl StatePrunerManager {
             last_version_sent_to_pruner: Arc::new(Mutex::new(min_readable_version)),
             latest_version: Arc::new(Mutex::new(min_readable_version)),
             user_pruning_window_offset: state_merkle_pruner_config.user_pruning_window_offset,
+            commit_tracker,
+            backup_progress,
         }
     }
 
+    /// Returns the tracker that foreground state merkle commits should report into, so that the
+    /// pruner holds off while they are written.
+    pub fn commit_tracker(&self) -> Arc<CommitTracker> {
+        Arc::clone(&self.commit_tracker)
+    }
+
     #[cfg(test)]
     pub fn testonly_update_min_version(&self, version: Version) {
         self.pruner.testonly_update_min_version(version);
@@ -1,8 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
+use crate::metrics::PRUNER_LAG;
+use crate::pruner::commit_tracker::CommitTracker;
 use crate::pruner::db_pruner::DBPruner;
+use crate::pruner::schedule::{PruningSchedule, PAUSE_INTERVAL};
 use crate::pruner::state_store::StateMerklePruner;
-use aptos_config::config::StateMerklePrunerConfig;
+use aptos_config::config::{PrunerScheduleConfig, StateMerklePrunerConfig};
 use aptos_logger::{
     error,
     prelude::{sample, SampleRate},
@@ -12,10 +15,14 @@ use aptos_types::transaction::Version;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Maintains the state store pruner and periodically calls the db_pruner's prune method to prune
 /// the DB. This also exposes API to report the progress to the parent thread.
+///
+/// Pruning runs at a low priority: the worker holds off while a state merkle batch is being
+/// committed, and optionally caps the number of stale nodes it deletes per second and only runs
+/// when its schedule allows.
 #[derive(Debug)]
 pub struct StatePrunerWorker {
     /// The worker will sleep for this period of time after pruning each batch.
@@ -24,6 +31,13 @@ pub struct StatePrunerWorker {
     pruner: Arc<StateMerklePruner>,
     /// Max items to prune per batch (i.e. the max stale nodes to prune.)
     max_node_to_prune_per_batch: u64,
+    /// Each batch takes at least this long, so that no more than the configured number of nodes
+    /// are deleted per second. Zero if there is no limit.
+    min_batch_duration: Duration,
+    /// Tracks the state merkle commits to yield to.
+    commit_tracker: Arc<CommitTracker>,
+    /// Decides when pruning may run.
+    schedule: PruningSchedule,
     /// Indicates whether the pruning loop should be running. Will only be set to true on pruner
     /// destruction.
     quit_worker: AtomicBool,
@@ -33,11 +47,23 @@ impl StatePrunerWorker {
     pub(crate) fn new(
         state_pruner: Arc<StateMerklePruner>,
         state_merkle_pruner_config: StateMerklePrunerConfig,
+        commit_tracker: Arc<CommitTracker>,
+        schedule: PrunerScheduleConfig,
     ) -> Self {
+        // Each batch is charged its full size, even if it turns out to hold fewer nodes.
+        let min_batch_duration = match state_merkle_pruner_config.max_nodes_to_prune_per_second {
+            0 => Duration::ZERO,
+            max_nodes_per_second => Duration::from_secs_f64(
+                state_merkle_pruner_config.batch_size as f64 / max_nodes_per_second as f64,
+            ),
+        };
         Self {
             pruning_time_interval_in_ms: if cfg!(test) { 100 } else { 1 },
             pruner: state_pruner,
             max_node_to_prune_per_batch: state_merkle_pruner_config.batch_size as u64,
+            min_batch_duration,
+            commit_tracker,
+            schedule: PruningSchedule::new(schedule, "state_store"),
             quit_worker: AtomicBool::new(false),
         }
     }
@@ -45,6 +71,23 @@ impl StatePrunerWorker {
     // Loop that does the real pruning job.
     pub(crate) fn work(&self) {
         while !self.quit_worker.load(Ordering::Relaxed) {
+            let lag = self
+                .pruner
+                .target_version()
+                .saturating_sub(self.pruner.min_readable_version());
+            PRUNER_LAG
+                .with_label_values(&["state_store"])
+                .set(lag as i64);
+            if self.commit_tracker.is_committing() {
+                sleep(Duration::from_millis(1));
+                continue;
+            }
+            if self.pruner.is_pruning_pending() && !self.schedule.may_prune(lag) {
+                sleep(PAUSE_INTERVAL);
+                continue;
+            }
+
+            let batch_start = Instant::now();
             let pruner_result = self.pruner.prune(self.max_node_to_prune_per_batch as usize);
             if pruner_result.is_err() {
                 sample!(
@@ -57,6 +100,10 @@ impl StatePrunerWorker {
             }
             if !self.pruner.is_pruning_pending() {
                 sleep(Duration::from_millis(self.pruning_time_interval_in_ms));
+            } else if let Some(remaining) =
+                self.min_batch_duration.checked_sub(batch_start.elapsed())
+            {
+                sleep(remaining);
             }
         }
     }
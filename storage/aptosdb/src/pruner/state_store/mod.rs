@@ -5,7 +5,8 @@ use crate::pruner::pruner_metadata::{PrunerMetadata, PrunerTag};
 use crate::pruner_metadata::PrunerMetadataSchema;
 use crate::{
     jellyfish_merkle_node::JellyfishMerkleNodeSchema, metrics::PRUNER_LEAST_READABLE_VERSION,
-    pruner::db_pruner::DBPruner, stale_node_index::StaleNodeIndexSchema, utils, ChangeSet,
+    node_cache::NodeCache, pruner::db_pruner::DBPruner, pruner::state_kv_store::StateKvPruner,
+    stale_node_index::StaleNodeIndexSchema, state_kv_db::StateKvDb, utils, ChangeSet,
     OTHER_TIMERS_SECONDS,
 };
 use anyhow::Result;
@@ -28,6 +29,10 @@ pub const STATE_MERKLE_PRUNER_NAME: &str = "state_merkle_pruner";
 pub struct StateMerklePruner {
     /// State DB.
     state_merkle_db: Arc<DB>,
+    /// The cache of the nodes read from the state DB, which the pruned nodes are removed from.
+    node_cache: Arc<NodeCache>,
+    /// Prunes the state values that are no longer readable once the tree is pruned.
+    state_kv_pruner: StateKvPruner,
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
     min_readable_version: AtomicVersion,
@@ -105,9 +110,15 @@ impl DBPruner for StateMerklePruner {
 }
 
 impl StateMerklePruner {
-    pub fn new(state_merkle_db: Arc<DB>) -> Self {
+    pub fn new(
+        state_merkle_db: Arc<DB>,
+        state_kv_db: Arc<StateKvDb>,
+        node_cache: Arc<NodeCache>,
+    ) -> Self {
         let pruner = StateMerklePruner {
             state_merkle_db,
+            node_cache,
+            state_kv_pruner: StateKvPruner::new(state_kv_db),
             target_version: AtomicVersion::new(0),
             min_readable_version: AtomicVersion::new(0),
             pruned_to_the_end_of_target_version: AtomicBool::new(false),
@@ -117,11 +128,16 @@ impl StateMerklePruner {
     }
 
     /// Prunes the genesis state and saves the db alterations to the given change set
-    pub fn prune_genesis(state_merkle_db: Arc<DB>, change_set: &mut ChangeSet) -> Result<()> {
+    pub fn prune_genesis(
+        state_merkle_db: Arc<DB>,
+        state_kv_db: Arc<StateKvDb>,
+        node_cache: Arc<NodeCache>,
+        change_set: &mut ChangeSet,
+    ) -> Result<()> {
         let target_version = 1; // The genesis version is 0. Delete [0,1) (exclusive)
         let max_version = 1; // We should only be pruning a single version
 
-        let state_pruner = utils::create_state_pruner(state_merkle_db);
+        let state_pruner = utils::create_state_pruner(state_merkle_db, state_kv_db, node_cache);
         state_pruner.set_target_version(target_version);
 
         let min_readable_version = state_pruner.min_readable_version.load(Ordering::Relaxed);
@@ -138,7 +154,7 @@ impl StateMerklePruner {
 
     // If the existing schema batch is not none, this function only adds items need to be
     // deleted to the schema batch and the caller is responsible for committing the schema batches
-    // to the DB.
+    // to the DB. Otherwise the state values no longer readable are pruned along.
     pub fn prune_state_store(
         &self,
         min_readable_version: Version,
@@ -150,6 +166,9 @@ impl StateMerklePruner {
         let (indices, is_end_of_target_version) =
             self.get_stale_node_indices(min_readable_version, target_version, batch_size)?;
         if indices.is_empty() {
+            if existing_schema_batch.is_none() {
+                self.state_kv_pruner.prune(target_version)?;
+            }
             self.pruned_to_the_end_of_target_version
                 .store(is_end_of_target_version, Ordering::Relaxed);
             self.record_progress(target_version);
@@ -163,15 +182,15 @@ impl StateMerklePruner {
 
             // Delete stale nodes.
             if let Some(existing_schema_batch) = existing_schema_batch {
-                indices.into_iter().try_for_each(|index| {
+                indices.iter().try_for_each(|index| {
                     existing_schema_batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key)?;
-                    existing_schema_batch.delete::<StaleNodeIndexSchema>(&index)
+                    existing_schema_batch.delete::<StaleNodeIndexSchema>(index)
                 })?;
             } else {
                 let batch = SchemaBatch::new();
-                indices.into_iter().try_for_each(|index| {
+                indices.iter().try_for_each(|index| {
                     batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key)?;
-                    batch.delete::<StaleNodeIndexSchema>(&index)
+                    batch.delete::<StaleNodeIndexSchema>(index)
                 })?;
 
                 batch.put::<PrunerMetadataSchema>(
@@ -181,7 +200,11 @@ impl StateMerklePruner {
 
                 // Commit to DB.
                 self.state_merkle_db.write_schemas(batch)?;
+                self.state_kv_pruner.prune(new_min_readable_version)?;
             }
+            indices
+                .iter()
+                .for_each(|index| self.node_cache.remove(&index.node_key));
 
             // TODO(zcc): recording progress after writing schemas might provide wrong answers to
             // API calls when they query min_readable_version while the write_schemas are still in
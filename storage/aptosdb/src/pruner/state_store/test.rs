@@ -1,9 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::StateMerklePrunerConfig;
+use aptos_config::config::{PrunerScheduleConfig, StateMerklePrunerConfig};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
 
 use aptos_crypto::HashValue;
 use aptos_temppath::TempPath;
@@ -12,6 +14,8 @@ use aptos_types::transaction::Version;
 use schemadb::{ReadOptions, DB};
 use storage_interface::{jmt_update_refs, jmt_updates, DbReader};
 
+use crate::pruner::commit_tracker::CommitTracker;
+use crate::pruner::db_pruner::DBPruner;
 use crate::pruner::state_pruner_worker::StatePrunerWorker;
 use crate::stale_node_index::StaleNodeIndexSchema;
 use crate::{
@@ -62,18 +66,20 @@ fn verify_state_in_store(
     assert_eq!(value.as_ref(), expected_value);
 }
 
-fn create_state_pruner_manager(
-    state_merkle_db: &Arc<DB>,
-    prune_batch_size: usize,
-) -> StatePrunerManager {
+fn create_state_pruner_manager(aptos_db: &AptosDB, prune_batch_size: usize) -> StatePrunerManager {
     StatePrunerManager::new(
-        Arc::clone(state_merkle_db),
+        Arc::clone(&aptos_db.state_merkle_db),
+        Arc::clone(&aptos_db.state_kv_db),
+        Arc::clone(&aptos_db.node_cache),
         StateMerklePrunerConfig {
             enable: true,
             prune_window: 0,
             batch_size: prune_batch_size,
             user_pruning_window_offset: 0,
+            max_nodes_to_prune_per_second: 0,
         },
+        PrunerScheduleConfig::default(),
+        None,
     )
 }
 
@@ -88,8 +94,12 @@ fn test_state_store_pruner() {
     let state_store = &StateStore::new(
         Arc::clone(&aptos_db.ledger_db),
         Arc::clone(&aptos_db.state_merkle_db),
+        Arc::clone(&aptos_db.state_kv_db),
+        Arc::clone(&aptos_db.node_cache),
         1000,  /* snapshot_size_threshold, does not matter */
+        1000,  /* snapshot_interval_threshold, does not matter */
         false, /* hack_for_tests */
+        Arc::new(CommitTracker::default()),
     );
 
     let mut root_hashes = vec![];
@@ -107,7 +117,7 @@ fn test_state_store_pruner() {
     // Prune till version=0. This should basically be a no-op. Create a new pruner everytime to
     // test the min_readable_version initialization logic.
     {
-        let pruner = create_state_pruner_manager(&aptos_db.state_merkle_db, prune_batch_size);
+        let pruner = create_state_pruner_manager(&aptos_db, prune_batch_size);
         pruner.wake_and_wait_pruner(0 /* latest_version */).unwrap();
         for i in 0..num_versions {
             verify_state_in_store(
@@ -123,7 +133,7 @@ fn test_state_store_pruner() {
     // we expect versions 0 to 9 to be pruned. Create a new pruner everytime to test the
     // min_readable_version initialization logic.
     {
-        let pruner = create_state_pruner_manager(&aptos_db.state_merkle_db, prune_batch_size);
+        let pruner = create_state_pruner_manager(&aptos_db, prune_batch_size);
         pruner
             .wake_and_wait_pruner(prune_batch_size as u64 /* latest_version */)
             .unwrap();
@@ -200,7 +210,7 @@ fn test_state_store_pruner_partial_version() {
     // Prune till version=0. This should basically be a no-op. Create a new pruner every time
     // to test the min_readable_version initialization logic.
     {
-        let pruner = create_state_pruner_manager(&aptos_db.state_merkle_db, prune_batch_size);
+        let pruner = create_state_pruner_manager(&aptos_db, prune_batch_size);
         pruner.wake_and_wait_pruner(0 /* latest_version */).unwrap();
         verify_state_in_store(state_store, key1.clone(), Some(&value1), 1);
         verify_state_in_store(state_store, key2.clone(), Some(&value2_update), 1);
@@ -211,7 +221,7 @@ fn test_state_store_pruner_partial_version() {
     // should prune 1 stale node with the version 0. Create a new pruner everytime to test the
     // min_readable_version initialization logic.
     {
-        let pruner = create_state_pruner_manager(&aptos_db.state_merkle_db, prune_batch_size);
+        let pruner = create_state_pruner_manager(&aptos_db, prune_batch_size);
         assert!(pruner.wake_and_wait_pruner(1 /* latest_version */,).is_ok());
         assert!(state_store
             .get_state_value_with_proof_by_version(&key1, 0_u64)
@@ -224,7 +234,7 @@ fn test_state_store_pruner_partial_version() {
     // Prune 3 more times. All version 0 and 1 stale nodes should be gone. Create a new pruner
     // everytime to test the min_readable_version initialization logic.
     {
-        let pruner = create_state_pruner_manager(&aptos_db.state_merkle_db, prune_batch_size);
+        let pruner = create_state_pruner_manager(&aptos_db, prune_batch_size);
         assert!(pruner.wake_and_wait_pruner(2 /* latest_version */,).is_ok());
         assert!(pruner.wake_and_wait_pruner(2 /* latest_version */,).is_ok());
 
@@ -339,7 +349,11 @@ fn test_worker_quit_eagerly() {
     );
 
     {
-        let state_pruner = utils::create_state_pruner(Arc::clone(&aptos_db.state_merkle_db));
+        let state_pruner = utils::create_state_pruner(
+            Arc::clone(&aptos_db.state_merkle_db),
+            Arc::clone(&aptos_db.state_kv_db),
+            Arc::clone(&aptos_db.node_cache),
+        );
         let worker = StatePrunerWorker::new(
             state_pruner,
             StateMerklePrunerConfig {
@@ -347,7 +361,10 @@ fn test_worker_quit_eagerly() {
                 prune_window: 1,
                 batch_size: 100,
                 user_pruning_window_offset: 0,
+                max_nodes_to_prune_per_second: 0,
             },
+            Arc::new(CommitTracker::default()),
+            PrunerScheduleConfig::default(),
         );
         worker.set_target_db_version(/*target_db_version=*/ 1);
         worker.set_target_db_version(/*target_db_version=*/ 2);
@@ -359,3 +376,66 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, key, Some(&value2), 2);
     }
 }
+
+#[test]
+fn test_worker_yields_to_commits() {
+    let key = StateKey::Raw(String::from("test_key1").into_bytes());
+
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let db = Arc::clone(&aptos_db.ledger_db);
+    let state_store = &aptos_db.state_store;
+
+    for i in 0..3 {
+        put_value_set(
+            &db,
+            state_store,
+            vec![(key.clone(), StateValue::from(vec![i as u8]))],
+            i, /* version */
+        );
+    }
+
+    let state_pruner = utils::create_state_pruner(
+        Arc::clone(&aptos_db.state_merkle_db),
+        Arc::clone(&aptos_db.state_kv_db),
+        Arc::clone(&aptos_db.node_cache),
+    );
+    let commit_tracker = Arc::new(CommitTracker::default());
+    let worker = Arc::new(StatePrunerWorker::new(
+        Arc::clone(&state_pruner),
+        StateMerklePrunerConfig {
+            enable: true,
+            prune_window: 1,
+            batch_size: 100,
+            user_pruning_window_offset: 0,
+            max_nodes_to_prune_per_second: 0,
+        },
+        Arc::clone(&commit_tracker),
+        PrunerScheduleConfig::default(),
+    ));
+    worker.set_target_db_version(/*target_db_version=*/ 2);
+
+    let commit_guard = commit_tracker.track();
+    let worker_clone = Arc::clone(&worker);
+    let worker_thread = thread::spawn(move || worker_clone.work());
+
+    // Nothing is pruned while a commit is in flight.
+    sleep(Duration::from_millis(200));
+    assert_eq!(state_pruner.min_readable_version(), 0);
+    verify_state_in_store(
+        state_store,
+        key.clone(),
+        Some(&StateValue::from(vec![0])),
+        0,
+    );
+
+    drop(commit_guard);
+    let end = Instant::now() + Duration::from_secs(10);
+    while state_pruner.min_readable_version() < 2 {
+        assert!(Instant::now() < end, "Timeout waiting for pruner worker.");
+        sleep(Duration::from_millis(1));
+    }
+    worker.stop_pruning();
+    worker_thread.join().unwrap();
+    verify_state_in_store(state_store, key, Some(&StateValue::from(vec![2])), 2);
+}
@@ -15,7 +15,8 @@ use aptos_types::{
 };
 
 use accumulator::HashReader;
-use aptos_config::config::LedgerPrunerConfig;
+use aptos_config::config::{LedgerPrunerConfig, PrunerScheduleConfig};
+use aptos_crypto::hash::CryptoHash;
 use aptos_types::proof::position::Position;
 use aptos_types::{
     transaction::{TransactionInfo, Version},
@@ -61,6 +62,8 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
             batch_size: 1,
             user_pruning_window_offset: 0,
         },
+        PrunerScheduleConfig::default(),
+        None,
     );
 
     // write sets
@@ -107,6 +110,8 @@ fn verify_txn_store_pruner(
             batch_size: 1,
             user_pruning_window_offset: 0,
         },
+        PrunerScheduleConfig::default(),
+        None,
     );
 
     let ledger_version = num_transaction as Version - 1;
@@ -164,6 +169,11 @@ fn verify_txn_not_in_store(
 ) {
     // Ensure that all transaction from transaction schema store has been pruned
     assert!(transaction_store.get_transaction(index).is_err());
+    // Ensure that transaction by hash store has been pruned
+    assert!(transaction_store
+        .get_transaction_version_by_hash(&txns.get(index as usize).unwrap().hash(), ledger_version)
+        .unwrap()
+        .is_none());
     // Ensure that transaction by account store has been pruned
     if let Transaction::UserTransaction(txn) = txns.get(index as usize).unwrap() {
         assert!(transaction_store
@@ -185,6 +195,15 @@ fn verify_txn_in_store(
         txns.get(index as usize).unwrap(),
         index as u64,
     );
+    assert_eq!(
+        transaction_store
+            .get_transaction_version_by_hash(
+                &txns.get(index as usize).unwrap().hash(),
+                ledger_version
+            )
+            .unwrap(),
+        Some(index)
+    );
     if let Transaction::UserTransaction(txn) = txns.get(index as usize).unwrap() {
         verify_transaction_in_account_txn_by_version_index(
             transaction_store,
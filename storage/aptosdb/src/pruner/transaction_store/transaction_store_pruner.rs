@@ -22,6 +22,8 @@ impl DBSubPruner for TransactionStorePruner {
 
         let candidate_transactions =
             self.get_pruning_candidate_transactions(min_readable_version, target_version)?;
+        // The hash index is pruned along with the transactions, as they are the only way to find
+        // its entries.
         self.transaction_store
             .prune_transaction_by_hash(&candidate_transactions, db_batch)?;
         self.transaction_store
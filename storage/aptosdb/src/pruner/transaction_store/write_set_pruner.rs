@@ -16,6 +16,15 @@ impl DBSubPruner for WriteSetPruner {
         min_readable_version: u64,
         target_version: u64,
     ) -> anyhow::Result<()> {
+        // The authentication keys to prune are found from the write sets setting them, so they
+        // are pruned here before the write sets are gone.
+        if self.transaction_store.has_auth_keys()? {
+            let write_sets = self
+                .transaction_store
+                .get_write_sets(min_readable_version, target_version)?;
+            self.transaction_store
+                .prune_auth_keys(target_version, &write_sets, db_batch)?;
+        }
         self.transaction_store
             .prune_write_set(min_readable_version, target_version, db_batch)?;
         Ok(())
@@ -4,7 +4,9 @@
 //! This module provides common utilities for the DB pruner.
 
 use crate::{
+    node_cache::NodeCache,
     pruner::{ledger_store::ledger_store_pruner::LedgerPruner, state_store::StateMerklePruner},
+    state_kv_db::StateKvDb,
     EventStore, LedgerStore, TransactionStore,
 };
 
@@ -12,8 +14,16 @@ use schemadb::DB;
 use std::sync::Arc;
 
 /// A utility function to instantiate the state pruner
-pub fn create_state_pruner(state_merkle_db: Arc<DB>) -> Arc<StateMerklePruner> {
-    Arc::new(StateMerklePruner::new(Arc::clone(&state_merkle_db)))
+pub fn create_state_pruner(
+    state_merkle_db: Arc<DB>,
+    state_kv_db: Arc<StateKvDb>,
+    node_cache: Arc<NodeCache>,
+) -> Arc<StateMerklePruner> {
+    Arc::new(StateMerklePruner::new(
+        Arc::clone(&state_merkle_db),
+        state_kv_db,
+        node_cache,
+    ))
 }
 
 /// A utility function to instantiate the ledger pruner
@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index of the authentication keys of
+//! accounts: for each version at which the authentication key of `address` was set, i.e. when the
+//! account was created and each time its key was rotated, the key it was set to.
+//!
+//! ```text
+//! |<-------key------->|<--value-->|
+//! | address | txn_ver | auth_key  |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, AUTH_KEY_BY_ACCOUNT_CF_NAME};
+use anyhow::Result;
+use aptos_types::{account_address::AccountAddress, transaction::Version};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+
+define_schema!(
+    AuthKeyByAccountSchema,
+    Key,
+    AuthKey,
+    AUTH_KEY_BY_ACCOUNT_CF_NAME
+);
+
+type Key = (AccountAddress, Version);
+type AuthKey = Vec<u8>;
+
+impl KeyCodec<AuthKeyByAccountSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref account_address, version) = *self;
+
+        let mut encoded = account_address.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let version = (&data[AccountAddress::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((address, version))
+    }
+}
+
+impl ValueCodec<AuthKeyByAccountSchema> for AuthKey {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
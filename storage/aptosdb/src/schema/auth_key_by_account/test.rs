@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        version in any::<Version>(),
+        auth_key in vec(any::<u8>(), 0..64),
+    ) {
+        assert_encode_decode::<AuthKeyByAccountSchema>(&(address, version), &auth_key);
+    }
+}
+
+test_no_panic_decoding!(AuthKeyByAccountSchema);
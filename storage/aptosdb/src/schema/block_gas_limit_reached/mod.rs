@@ -0,0 +1,57 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the blocks whose transactions used up the block
+//! gas limit, so the transactions proposed after the last one of the block were retried in a later
+//! block. A pair (`version`, `gas_limit`) indicates that the block starting at `version` reached
+//! the limit `gas_limit` in effect for it.
+//!
+//! ```text
+//! |<--key-->|<---value-->|
+//! | version | gas_limit  |
+//! ```
+//!
+//! `version` is serialized in big endian so that records in RocksDB will be in order of their
+//! numeric value.
+
+use crate::schema::{ensure_slice_len_eq, BLOCK_GAS_LIMIT_REACHED_CF_NAME};
+use anyhow::Result;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    BlockGasLimitReachedSchema,
+    Version,
+    u64, // gas_limit
+    BLOCK_GAS_LIMIT_REACHED_CF_NAME
+);
+
+impl KeyCodec<BlockGasLimitReachedSchema> for Version {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<BlockGasLimitReachedSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,18 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        version in any::<Version>(),
+        gas_limit in any::<u64>(),
+    ) {
+        assert_encode_decode::<BlockGasLimitReachedSchema>(&version, &gas_limit);
+    }
+}
+
+test_no_panic_decoding!(BlockGasLimitReachedSchema);
@@ -6,6 +6,8 @@
 //!
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
+pub(crate) mod auth_key_by_account;
+pub(crate) mod block_gas_limit_reached;
 pub(crate) mod epoch_by_version;
 pub(crate) mod event;
 pub(crate) mod event_accumulator;
@@ -15,8 +17,11 @@ pub(crate) mod jellyfish_merkle_node;
 pub(crate) mod ledger_counters;
 pub(crate) mod ledger_info;
 pub(crate) mod pruner_metadata;
+pub(crate) mod resource_by_module;
 pub(crate) mod stale_node_index;
 pub(crate) mod stale_state_value_index;
+pub(crate) mod state_kv_sharding;
+pub(crate) mod state_snapshot_restore_progress;
 pub(crate) mod state_value;
 pub(crate) mod transaction;
 pub(crate) mod transaction_accumulator;
@@ -29,6 +34,8 @@ pub(crate) mod write_set;
 use anyhow::{ensure, Result};
 use schemadb::ColumnFamilyName;
 
+pub const AUTH_KEY_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "auth_key_by_account";
+pub const BLOCK_GAS_LIMIT_REACHED_CF_NAME: ColumnFamilyName = "block_gas_limit_reached";
 pub const EPOCH_BY_VERSION_CF_NAME: ColumnFamilyName = "epoch_by_version";
 pub const EVENT_ACCUMULATOR_CF_NAME: ColumnFamilyName = "event_accumulator";
 pub const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
@@ -38,6 +45,7 @@ pub const INDEXER_METADATA_CF_NAME: ColumnFamilyName = "indexer_metadata";
 pub const JELLYFISH_MERKLE_NODE_CF_NAME: ColumnFamilyName = "jellyfish_merkle_node";
 pub const LEDGER_COUNTERS_CF_NAME: ColumnFamilyName = "ledger_counters";
 pub const LEDGER_INFO_CF_NAME: ColumnFamilyName = "ledger_info";
+pub const RESOURCE_BY_MODULE_CF_NAME: ColumnFamilyName = "resource_by_module";
 pub const STALE_NODE_INDEX_CF_NAME: ColumnFamilyName = "stale_node_index";
 pub const STALE_STATE_VALUE_INDEX_CF_NAME: ColumnFamilyName = "stale_state_value_index";
 pub const STATE_VALUE_CF_NAME: ColumnFamilyName = "state_value";
@@ -78,6 +86,10 @@ pub mod fuzzing {
     pub fn fuzz_decode(data: &[u8]) {
         #[allow(unused_must_use)]
         {
+            assert_no_panic_decoding::<super::auth_key_by_account::AuthKeyByAccountSchema>(data);
+            assert_no_panic_decoding::<super::block_gas_limit_reached::BlockGasLimitReachedSchema>(
+                data,
+            );
             assert_no_panic_decoding::<super::epoch_by_version::EpochByVersionSchema>(data);
             assert_no_panic_decoding::<super::event::EventSchema>(data);
             assert_no_panic_decoding::<super::event_accumulator::EventAccumulatorSchema>(data);
@@ -88,6 +100,7 @@ pub mod fuzzing {
             );
             assert_no_panic_decoding::<super::ledger_counters::LedgerCountersSchema>(data);
             assert_no_panic_decoding::<super::ledger_info::LedgerInfoSchema>(data);
+            assert_no_panic_decoding::<super::resource_by_module::ResourceByModuleSchema>(data);
             assert_no_panic_decoding::<super::stale_node_index::StaleNodeIndexSchema>(data);
             assert_no_panic_decoding::<super::stale_state_value_index::StaleStateValueIndexSchema>(
                 data,
@@ -105,6 +118,10 @@ pub mod fuzzing {
             assert_no_panic_decoding::<super::version_data::VersionDataSchema>(data);
             assert_no_panic_decoding::<super::write_set::WriteSetSchema>(data);
             assert_no_panic_decoding::<super::pruner_metadata::PrunerMetadataSchema>(data);
+            assert_no_panic_decoding::<
+                super::state_snapshot_restore_progress::StateSnapshotRestoreProgressSchema,
+            >(data);
+            assert_no_panic_decoding::<super::state_kv_sharding::StateKvShardingSchema>(data);
         }
     }
 }
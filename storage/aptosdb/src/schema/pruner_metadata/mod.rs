@@ -2,7 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 //! This module defines the physical storage schema for indexes of min_readable_version of pruners.
 //! For the state pruner, the metadata represents the key of the StaleNodeIndexSchema and for the
-//! ledger pruner, the metadata represents the key of the TransactionSchema.
+//! ledger pruner, the metadata represents the key of the TransactionSchema. For the state KV
+//! pruner, the metadata represents the key of the StaleStateValueIndexSchema in each shard.
+//!
+//! Each sub-pruner of the ledger pruner records its own progress under its own tag, so that they
+//! can fall behind each other, e.g. when one of them fails, and catch up independently. Databases
+//! written before only have the progress of the ledger pruner as a whole, which stands for the
+//! progress of every sub-pruner that hasn't recorded its own yet.
 //!
 //! ```text
 //! |<------key---->|<------ value ----->|
@@ -0,0 +1,85 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index of the resources of accounts by the
+//! module declaring their types: for each resource ever written under `address` whose struct tag
+//! is declared in a module, the state key of the resource, keyed by the hash of the module id.
+//!
+//! ```text
+//! |<--------------------key-------------------->|<--value-->|
+//! | address | hash(module id) | state key        |   empty   |
+//! ```
+//!
+//! The module id is hashed so that the (address, module) prefix has a fixed length, which is what
+//! the prefix extractor, and the bloom filter built on it, of the column family expect. Entries are
+//! never removed, a resource deleted since is still listed and is told apart by its value.
+
+use crate::schema::{ensure_slice_len_eq, ensure_slice_len_gt, RESOURCE_BY_MODULE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_types::{account_address::AccountAddress, state_store::state_key::StateKey};
+use move_deps::move_core_types::language_storage::ModuleId;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::convert::TryFrom;
+
+/// The length of the (address, hash(module id)) prefix of the keys.
+pub(crate) const PREFIX_LENGTH: usize = AccountAddress::LENGTH + HashValue::LENGTH;
+
+define_schema!(ResourceByModuleSchema, Key, (), RESOURCE_BY_MODULE_CF_NAME);
+
+type Key = (AccountAddress, HashValue, StateKey);
+
+/// The hash of a module id in the keys.
+pub(crate) fn hash_module_id(module_id: &ModuleId) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(module_id)?))
+}
+
+impl KeyCodec<ResourceByModuleSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref address, ref module_hash, ref state_key) = *self;
+
+        let mut encoded = address.to_vec();
+        encoded.extend_from_slice(module_hash.as_ref());
+        encoded.extend(state_key.encode()?);
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_gt(data, PREFIX_LENGTH)?;
+
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let module_hash = HashValue::from_slice(&data[AccountAddress::LENGTH..PREFIX_LENGTH])?;
+        let state_key = StateKey::decode(&data[PREFIX_LENGTH..])?;
+
+        Ok((address, module_hash, state_key))
+    }
+}
+
+impl ValueCodec<ResourceByModuleSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<ResourceByModuleSchema> for &(AccountAddress, HashValue) {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        let (address, module_hash) = self;
+
+        let mut encoded = address.to_vec();
+        encoded.extend_from_slice(module_hash.as_ref());
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        module_hash in any::<HashValue>(),
+        state_key in any::<StateKey>(),
+    ) {
+        assert_encode_decode::<ResourceByModuleSchema>(&(address, module_hash, state_key), &());
+    }
+}
+
+test_no_panic_decoding!(ResourceByModuleSchema);
@@ -0,0 +1,59 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the physical storage schema for how the state values of the DB are sharded,
+//! which is recorded in the ledger DB and in each shard when the DB is first opened, so that it's
+//! never opened with the state values sharded differently.
+//!
+//! ```text
+//! |<--key-->|<---value--->|
+//! | prefix  | metadata    |
+//! ```
+//!
+//! The schema shares the column family of the pruner metadata, its key being a prefix that never
+//! collides with pruner tags or the keys of the state snapshot restore progress.
+
+use crate::{
+    schema::{ensure_slice_len_eq, DB_METADATA_CF_NAME},
+    state_kv_db::StateKvShardingMetadata,
+};
+use anyhow::{ensure, Result};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+const KEY_PREFIX: u8 = 0xfe;
+
+define_schema!(
+    StateKvShardingSchema,
+    (),
+    StateKvShardingMetadata,
+    DB_METADATA_CF_NAME
+);
+
+impl KeyCodec<StateKvShardingSchema> for () {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![KEY_PREFIX])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>())?;
+        ensure!(data[0] == KEY_PREFIX, "Unexpected key prefix {}.", data[0]);
+        Ok(())
+    }
+}
+
+impl ValueCodec<StateKvShardingSchema> for StateKvShardingMetadata {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
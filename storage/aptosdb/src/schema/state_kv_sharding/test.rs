@@ -0,0 +1,15 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(metadata in any::<StateKvShardingMetadata>()) {
+        assert_encode_decode::<StateKvShardingSchema>(&(), &metadata);
+    }
+}
+
+test_no_panic_decoding!(StateKvShardingSchema);
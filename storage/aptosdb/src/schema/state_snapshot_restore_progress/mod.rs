@@ -0,0 +1,61 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the physical storage schema for the progress of restoring the state snapshot
+//! at a version, which is written along with the state values restored so that an interrupted
+//! restore resumes after the last of them.
+//!
+//! ```text
+//! |<--------key------->|<--value-->|
+//! | prefix | version   | progress  |
+//! ```
+//!
+//! The schema shares the column family of the pruner metadata, its keys being prefixed so that
+//! they never collide with pruner tags.
+
+use crate::schema::{ensure_slice_len_eq, DB_METADATA_CF_NAME};
+use anyhow::{ensure, Result};
+use aptos_jellyfish_merkle::StateSnapshotProgress;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+const KEY_PREFIX: u8 = 0xff;
+
+define_schema!(
+    StateSnapshotRestoreProgressSchema,
+    Version,
+    StateSnapshotProgress,
+    DB_METADATA_CF_NAME
+);
+
+impl KeyCodec<StateSnapshotRestoreProgressSchema> for Version {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = vec![KEY_PREFIX];
+        encoded.extend_from_slice(&self.to_be_bytes());
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u8>() + size_of::<Version>())?;
+        ensure!(data[0] == KEY_PREFIX, "Unexpected key prefix {}.", data[0]);
+        Ok((&data[1..]).read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<StateSnapshotRestoreProgressSchema> for StateSnapshotProgress {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
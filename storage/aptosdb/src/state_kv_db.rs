@@ -0,0 +1,288 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines where the state values and the indices of the stale ones are stored: in the
+//! ledger DB, or sharded by the hash of the state key across RocksDB instances of their own, which
+//! may each be on a disk of its own.
+
+use crate::{
+    change_set::ChangeSet,
+    db_options::{gen_state_kv_cfds, state_kv_db_column_families},
+    metrics::STATE_KV_SHARD_COMMIT_SECONDS,
+    schema::{state_kv_sharding::StateKvShardingSchema, state_value::StateValueSchema},
+    STATE_KV_DB_NAME,
+};
+use anyhow::{ensure, Result};
+use aptos_config::config::{RocksdbConfig, StateKvShardingConfig};
+use aptos_crypto::hash::CryptoHash;
+use aptos_rocksdb_options::gen_rocksdb_options;
+use aptos_types::state_store::state_key::StateKey;
+use rayon::prelude::*;
+use schemadb::{ReadOptions, SchemaBatch, DB};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// How the state values of a DB are sharded, as recorded in its ledger DB and in each of its shards.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
+pub(crate) struct StateKvShardingMetadata {
+    /// The number of shards, 0 if the state values are stored in the ledger DB.
+    pub num_shards: u64,
+    /// The id of the shard it's recorded in, none in the ledger DB.
+    pub shard_id: Option<u64>,
+}
+
+impl StateKvShardingMetadata {
+    fn shard(num_shards: u64, shard_id: usize) -> Self {
+        Self {
+            num_shards,
+            shard_id: Some(shard_id as u64),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct StateKvDb {
+    ledger_db: Arc<DB>,
+    /// The shards by shard id, empty if the state values are stored in the ledger DB.
+    shards: Vec<Arc<DB>>,
+}
+
+impl StateKvDb {
+    pub fn new(ledger_db: Arc<DB>, shards: Vec<Arc<DB>>) -> Self {
+        Self { ledger_db, shards }
+    }
+
+    /// Opens the shards of the state values, if configured to be sharded, checking that the state
+    /// values are stored the way they are configured to be. How they are sharded is recorded in
+    /// the ledger DB and in each shard the first time the DB is opened, so that neither a different
+    /// number of shards nor a shard moved to the wrong path goes unnoticed.
+    pub fn open_shards(
+        db_root_path: &Path,
+        ledger_db: &DB,
+        sharding: &StateKvShardingConfig,
+        rocksdb_config: &RocksdbConfig,
+        readonly: bool,
+    ) -> Result<Vec<DB>> {
+        let num_shards = sharding.num_shards as u64;
+        let recorded = ledger_db.get::<StateKvShardingSchema>(&())?;
+        match &recorded {
+            Some(metadata) => ensure!(
+                metadata.num_shards == num_shards,
+                "The state values of the DB are stored in {} shards, but state_kv_sharding \
+                 configures {}.",
+                metadata.num_shards,
+                num_shards,
+            ),
+            // Not recorded yet, either because the DB is new or because it was written before
+            // the sharding was recorded.
+            None if num_shards == 0 => ensure!(
+                !db_root_path.join(STATE_KV_DB_NAME).exists(),
+                "The state values of the DB are sharded, state_kv_sharding must be configured."
+            ),
+            None => {
+                let mut read_opts = ReadOptions::default();
+                read_opts.set_total_order_seek(true);
+                let mut iter = ledger_db.iter::<StateValueSchema>(read_opts)?;
+                iter.seek_to_first();
+                ensure!(
+                    iter.next().is_none(),
+                    "The state values of the DB are stored in the ledger DB, they can't be sharded."
+                );
+            }
+        }
+
+        let shards = (0..sharding.num_shards)
+            .map(|shard_id| -> Result<DB> {
+                let path = shard_path(db_root_path, sharding, shard_id);
+                let shard = if readonly {
+                    DB::open_cf_readonly(
+                        &gen_rocksdb_options(rocksdb_config, true),
+                        &path,
+                        "state_kv_db_ro",
+                        state_kv_db_column_families(),
+                    )?
+                } else {
+                    DB::open_cf(
+                        &gen_rocksdb_options(rocksdb_config, false),
+                        &path,
+                        "state_kv_db",
+                        gen_state_kv_cfds(rocksdb_config),
+                    )?
+                };
+                match shard.get::<StateKvShardingSchema>(&())? {
+                    Some(metadata) => ensure!(
+                        metadata == StateKvShardingMetadata::shard(num_shards, shard_id),
+                        "The state value shard at {:?} is shard {:?} of {} shards, not shard {} \
+                         of {}.",
+                        path,
+                        metadata.shard_id,
+                        metadata.num_shards,
+                        shard_id,
+                        num_shards,
+                    ),
+                    // The shards are recorded before the ledger DB, so this one is new.
+                    None => ensure!(
+                        recorded.is_none(),
+                        "There is no state value shard of the DB at {:?}.",
+                        path,
+                    ),
+                }
+                Ok(shard)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if recorded.is_none() && !readonly {
+            for (shard_id, shard) in shards.iter().enumerate() {
+                shard.put::<StateKvShardingSchema>(
+                    &(),
+                    &StateKvShardingMetadata::shard(num_shards, shard_id),
+                )?;
+            }
+            ledger_db.put::<StateKvShardingSchema>(
+                &(),
+                &StateKvShardingMetadata {
+                    num_shards,
+                    shard_id: None,
+                },
+            )?;
+        }
+        Ok(shards)
+    }
+
+    /// Opens the shards of the state values as secondary, if configured to be sharded, each
+    /// following the shard of the primary with the same id, checking that the primary has as many.
+    pub fn open_shards_as_secondary(
+        db_root_path: &Path,
+        secondary_db_root_path: &Path,
+        ledger_db: &DB,
+        sharding: &StateKvShardingConfig,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Vec<DB>> {
+        if let Some(metadata) = ledger_db.get::<StateKvShardingSchema>(&())? {
+            ensure!(
+                metadata.num_shards == sharding.num_shards as u64,
+                "The state values of the primary DB are stored in {} shards, but \
+                 state_kv_sharding configures {}.",
+                metadata.num_shards,
+                sharding.num_shards,
+            );
+        }
+        (0..sharding.num_shards)
+            .map(|shard_id| {
+                DB::open_cf_as_secondary(
+                    &gen_rocksdb_options(rocksdb_config, false),
+                    shard_path(db_root_path, sharding, shard_id),
+                    default_shard_path(secondary_db_root_path, shard_id),
+                    "state_kv_db_sec",
+                    state_kv_db_column_families(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn is_sharded(&self) -> bool {
+        !self.shards.is_empty()
+    }
+
+    /// The number of shards, the ledger DB counting as a single one if not sharded.
+    pub fn num_shards(&self) -> usize {
+        std::cmp::max(self.shards.len(), 1)
+    }
+
+    /// The shard the value of a state key is stored in, by the first 8 bytes of the hash of the
+    /// key. Always 0 if not sharded.
+    pub fn shard_id(&self, state_key: &StateKey) -> usize {
+        if !self.is_sharded() {
+            return 0;
+        }
+        let hash = state_key.hash();
+        let prefix = (0..8).fold(0u64, |prefix, i| prefix << 8 | hash[i] as u64);
+        (prefix % self.shards.len() as u64) as usize
+    }
+
+    /// The DB of a shard, which is the ledger DB if not sharded.
+    pub fn db_shard(&self, shard_id: usize) -> &DB {
+        if self.is_sharded() {
+            &self.shards[shard_id]
+        } else {
+            &self.ledger_db
+        }
+    }
+
+    /// The DB the value of a state key is stored in.
+    pub fn db_for_key(&self, state_key: &StateKey) -> &DB {
+        self.db_shard(self.shard_id(state_key))
+    }
+
+    /// The shards as they are laid out on disk, if sharded.
+    pub fn shards(&self) -> &[Arc<DB>] {
+        &self.shards
+    }
+
+    /// The batch of a change set the writes to a shard go to, which is the batch of the ledger DB
+    /// if not sharded.
+    pub fn batch<'a>(&self, shard_id: usize, cs: &'a mut ChangeSet) -> &'a SchemaBatch {
+        if self.is_sharded() {
+            cs.state_kv_batches
+                .entry(shard_id)
+                .or_insert_with(SchemaBatch::new)
+        } else {
+            &cs.batch
+        }
+    }
+
+    /// The batch of a change set the writes for a state key go to.
+    pub fn batch_for<'a>(&self, state_key: &StateKey, cs: &'a mut ChangeSet) -> &'a SchemaBatch {
+        self.batch(self.shard_id(state_key), cs)
+    }
+
+    /// Writes the batches of the shards, in parallel. They are written before the batch of the
+    /// ledger DB they go with, so that the state values are there once the ledger says they are.
+    pub fn commit(&self, batches: BTreeMap<usize, SchemaBatch>) -> Result<()> {
+        batches
+            .into_par_iter()
+            .map(|(shard_id, batch)| {
+                let _timer = STATE_KV_SHARD_COMMIT_SECONDS
+                    .with_label_values(&[shard_id.to_string().as_str()])
+                    .start_timer();
+                self.db_shard(shard_id).write_schemas(batch)
+            })
+            .collect()
+    }
+
+    /// Creates a checkpoint of each shard, in the default layout under `path`.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        if self.is_sharded() {
+            std::fs::create_dir_all(path.join(STATE_KV_DB_NAME))?;
+        }
+        for (shard_id, shard) in self.shards.iter().enumerate() {
+            shard.create_checkpoint(default_shard_path(path, shard_id))?;
+        }
+        Ok(())
+    }
+}
+
+/// The directory of a shard, which is under the DB directory unless configured otherwise.
+fn shard_path(db_root_path: &Path, sharding: &StateKvShardingConfig, shard_id: usize) -> PathBuf {
+    sharding
+        .shard_paths
+        .get(shard_id)
+        .cloned()
+        .unwrap_or_else(|| default_shard_path(db_root_path, shard_id))
+}
+
+fn default_shard_path(db_root_path: &Path, shard_id: usize) -> PathBuf {
+    db_root_path
+        .join(STATE_KV_DB_NAME)
+        .join(format!("shard_{}", shard_id))
+}
+
+/// The name of a shard in the DB admin APIs.
+pub fn shard_name(shard_id: usize) -> String {
+    format!("{}/shard_{}", STATE_KV_DB_NAME, shard_id)
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::node_cache::NodeCache;
 use crate::schema::jellyfish_merkle_node::JellyfishMerkleNodeSchema;
 use crate::stale_node_index::StaleNodeIndexSchema;
 use crate::OTHER_TIMERS_SECONDS;
@@ -25,18 +26,24 @@ pub(crate) type Node = aptos_jellyfish_merkle::node_type::Node<StateKey>;
 type NodeBatch = aptos_jellyfish_merkle::NodeBatch<StateKey>;
 
 #[derive(Debug)]
-pub struct StateMerkleDb(Arc<DB>);
+pub struct StateMerkleDb {
+    db: Arc<DB>,
+    node_cache: Arc<NodeCache>,
+}
 
 impl Deref for StateMerkleDb {
     type Target = DB;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.db
     }
 }
 
 impl StateMerkleDb {
-    pub fn new(state_merkle_rocksdb: Arc<DB>) -> Self {
-        Self(state_merkle_rocksdb)
+    pub fn new(state_merkle_rocksdb: Arc<DB>, node_cache: Arc<NodeCache>) -> Self {
+        Self {
+            db: state_merkle_rocksdb,
+            node_cache,
+        }
     }
 
     pub fn get_with_proof_ext(
@@ -62,6 +69,10 @@ impl StateMerkleDb {
         JellyfishMerkleTree::new(self).get_root_hash(version)
     }
 
+    pub fn get_root_hash_option(&self, version: Version) -> Result<Option<HashValue>> {
+        JellyfishMerkleTree::new(self).get_root_hash_option(version)
+    }
+
     pub fn get_leaf_count(&self, version: Version) -> Result<usize> {
         JellyfishMerkleTree::new(self).get_leaf_count(version)
     }
@@ -173,25 +184,23 @@ impl StateMerkleDb {
 
 impl TreeReader<StateKey> for StateMerkleDb {
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node>> {
-        self.get::<JellyfishMerkleNodeSchema>(node_key)
+        if let Some(node) = self.node_cache.get(node_key) {
+            return Ok(Some(node));
+        }
+        let node = self.get::<JellyfishMerkleNodeSchema>(node_key)?;
+        if let Some(node) = &node {
+            self.node_cache.insert(node_key.clone(), node.clone());
+        }
+        Ok(node)
     }
 
-    fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode)>> {
-        // Since everything has the same version during restore, we seek to the first node and get
-        // its version.
-        let mut iter = self.iter::<JellyfishMerkleNodeSchema>(Default::default())?;
-        iter.seek_to_first();
-        let version = match iter.next().transpose()? {
-            Some((node_key, _node)) => node_key.version(),
-            None => return Ok(None),
-        };
-
+    fn get_rightmost_leaf(&self, version: Version) -> Result<Option<(NodeKey, LeafNode)>> {
         // The encoding of key and value in DB looks like:
         //
         // | <-------------- key --------------> | <- value -> |
         // | version | num_nibbles | nibble_path |    node     |
         //
-        // Here version is fixed. For each num_nibbles, there could be a range of nibble paths
+        // Here version is fixed, nodes of other versions being skipped. For each num_nibbles, there could be a range of nibble paths
         // of the same length. If one of them is the rightmost leaf R, it must be at the end of this
         // range. Otherwise let's assume the R is in the middle of the range, so we
         // call the node at the end of this range X:
@@ -214,7 +223,10 @@ impl TreeReader<StateKey> for StateMerkleDb {
             iter.seek_for_prev(&seek_key)?;
 
             if let Some((node_key, node)) = iter.next().transpose()? {
-                debug_assert_eq!(node_key.version(), version);
+                // The range is empty if the seek lands on a node of an earlier version.
+                if node_key.version() != version {
+                    continue;
+                }
                 debug_assert!(node_key.nibble_path().num_nibbles() < num_nibbles);
 
                 if let Node::Leaf(leaf_node) = node {
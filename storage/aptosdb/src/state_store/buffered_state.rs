@@ -18,7 +18,6 @@ use std::thread::JoinHandle;
 use storage_interface::state_delta::StateDelta;
 
 pub(crate) const ASYNC_COMMIT_CHANNEL_BUFFER_SIZE: u64 = 1;
-pub(crate) const TARGET_SNAPSHOT_INTERVAL_IN_VERSION: u64 = 20_000;
 
 /// The in-memory buffered state that consists of two pieces:
 /// `state_until_checkpoint`: The ready-to-commit data in range (last snapshot, latest checkpoint].
@@ -34,6 +33,7 @@ pub struct BufferedState {
     state_after_checkpoint: StateDelta,
     state_commit_sender: SyncSender<CommitMessage<Arc<StateDelta>>>,
     target_snapshot_size: usize,
+    target_snapshot_interval_in_versions: u64,
     snapshot_ready_receivers: VecDeque<Receiver<()>>,
     join_handle: Option<JoinHandle<()>>,
 }
@@ -53,6 +53,7 @@ impl BufferedState {
         state_db: &Arc<StateDb>,
         state_after_checkpoint: StateDelta,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
     ) -> Self {
         let (state_commit_sender, state_commit_receiver) =
             mpsc::sync_channel(ASYNC_COMMIT_CHANNEL_BUFFER_SIZE as usize);
@@ -72,6 +73,7 @@ impl BufferedState {
             state_after_checkpoint,
             state_commit_sender,
             target_snapshot_size,
+            target_snapshot_interval_in_versions,
             snapshot_ready_receivers: VecDeque::from([initial_snapshot_ready_receiver]),
             // The join handle of the async state commit thread for graceful drop.
             join_handle: Some(join_handle),
@@ -121,7 +123,7 @@ impl BufferedState {
                 state_until_checkpoint.updates_since_base.len() >= self.target_snapshot_size
                     || state_until_checkpoint.current_version.map_or(0, |v| v + 1)
                         - state_until_checkpoint.base_version.map_or(0, |v| v + 1)
-                        >= TARGET_SNAPSHOT_INTERVAL_IN_VERSION
+                        >= self.target_snapshot_interval_in_versions
             };
             if take_out_to_commit {
                 let to_commit: Arc<StateDelta> = self
@@ -4,13 +4,15 @@
 //! This file defines state store APIs that are related account state Merkle tree.
 
 use anyhow::{anyhow, ensure, format_err, Result};
+use aptos_config::config::TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS;
 use aptos_crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
 };
 use aptos_infallible::Mutex;
 use aptos_jellyfish_merkle::{
-    iterator::JellyfishMerkleIterator, restore::StateSnapshotRestore, StateValueWriter,
+    iterator::JellyfishMerkleIterator, restore::StateSnapshotRestore, StateSnapshotProgress,
+    StateValueWriter,
 };
 use aptos_logger::info;
 use aptos_state_view::StateViewId;
@@ -18,6 +20,8 @@ use aptos_state_view::StateViewId;
 use aptos_types::nibble::nibble_path::NibblePath;
 use aptos_types::state_store::state_value::StaleStateValueIndex;
 use aptos_types::{
+    access_path::Path,
+    account_address::AccountAddress,
     proof::{definition::LeafCount, SparseMerkleProofExt, SparseMerkleRangeProof},
     state_store::{
         state_key::StateKey,
@@ -27,21 +31,32 @@ use aptos_types::{
     transaction::Version,
 };
 use executor_types::in_memory_state_calculator::InMemoryStateCalculator;
+use move_deps::move_core_types::language_storage::ModuleId;
 use schemadb::{ReadOptions, SchemaBatch, DB};
 use std::ops::Deref;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
 use storage_interface::{
     cached_state_view::CachedStateView, state_delta::StateDelta,
     sync_proof_fetcher::SyncProofFetcher, DbReader, StateSnapshotReceiver,
 };
 
-use crate::metrics::{STATE_ITEMS, TOTAL_STATE_BYTES};
+use crate::metrics::{STATE_ITEMS, STATE_KV_SHARD_VALUES_WRITTEN, TOTAL_STATE_BYTES};
+use crate::node_cache::NodeCache;
+use crate::pruner::commit_tracker::CommitTracker;
+use crate::pruner::pruner_metadata::{PrunerMetadata, PrunerTag};
+use crate::pruner_metadata::PrunerMetadataSchema;
+use crate::resource_by_module::{hash_module_id, ResourceByModuleSchema};
 use crate::stale_state_value_index::StaleStateValueIndexSchema;
+use crate::state_snapshot_restore_progress::StateSnapshotRestoreProgressSchema;
 use crate::state_store::buffered_state::BufferedState;
 use crate::version_data::{VersionData, VersionDataSchema};
 use crate::{
-    change_set::ChangeSet, schema::state_value::StateValueSchema, state_merkle_db::StateMerkleDb,
-    AptosDbError, LedgerStore, TransactionStore, OTHER_TIMERS_SECONDS,
+    change_set::ChangeSet, schema::state_value::StateValueSchema, state_kv_db::StateKvDb,
+    state_merkle_db::StateMerkleDb, AptosDbError, LedgerStore, TransactionStore,
+    OTHER_TIMERS_SECONDS,
 };
 
 pub(crate) mod buffered_state;
@@ -53,15 +68,25 @@ mod state_store_test;
 type StateValueBatch = aptos_jellyfish_merkle::StateValueBatch<StateKey, Option<StateValue>>;
 
 pub const MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX: usize = 10_000;
-// We assume TARGET_SNAPSHOT_INTERVAL_IN_VERSION > block size.
-const MAX_WRITE_SETS_AFTER_SNAPSHOT: LeafCount = buffered_state::TARGET_SNAPSHOT_INTERVAL_IN_VERSION
-    * (buffered_state::ASYNC_COMMIT_CHANNEL_BUFFER_SIZE + 2 + 1/*  Rendezvous channel */)
-    * 2;
+
+// We assume the snapshot interval > block size. A node may be restarted with a shorter interval
+// than it was killed with, so the interval is taken to be the default one at least.
+fn max_write_sets_after_snapshot(target_snapshot_interval_in_versions: u64) -> LeafCount {
+    target_snapshot_interval_in_versions
+        .max(TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS)
+        // The extra 1 is for the rendezvous channel.
+        .saturating_mul(buffered_state::ASYNC_COMMIT_CHANNEL_BUFFER_SIZE + 2 + 1)
+        .saturating_mul(2)
+}
 
 #[derive(Debug)]
 pub(crate) struct StateDb {
     pub ledger_db: Arc<DB>,
     pub state_merkle_db: Arc<StateMerkleDb>,
+    /// Where the state values are, which is the ledger DB unless they are sharded.
+    pub state_kv_db: Arc<StateKvDb>,
+    /// State merkle commits are reported here for the state pruner to yield to them.
+    pub commit_tracker: Arc<CommitTracker>,
 }
 
 #[derive(Debug)]
@@ -72,6 +97,7 @@ pub(crate) struct StateStore {
     // write set stored in ledger_db.
     buffered_state: Mutex<BufferedState>,
     target_snapshot_size: usize,
+    target_snapshot_interval_in_versions: u64,
 }
 
 impl Deref for StateStore {
@@ -111,6 +137,40 @@ impl DbReader for StateDb {
             .map(|(_, value)| value))
     }
 
+    /// Get the latest state values of the given keys up to the given version, seeking a single
+    /// iterator per shard to each key in turn rather than creating one per key.
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        let mut iters = HashMap::new();
+        state_keys
+            .iter()
+            .map(|state_key| {
+                let shard_id = self.state_kv_db.shard_id(state_key);
+                let iter = match iters.entry(shard_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let mut read_opts = ReadOptions::default();
+                        // We want `None` if the state_key changes in iteration.
+                        read_opts.set_prefix_same_as_start(true);
+                        entry.insert(
+                            self.state_kv_db
+                                .db_shard(shard_id)
+                                .iter::<StateValueSchema>(read_opts)?,
+                        )
+                    }
+                };
+                iter.seek(&(state_key.clone(), version))?;
+                Ok(iter
+                    .next()
+                    .transpose()?
+                    .and_then(|(_, value_opt)| value_opt))
+            })
+            .collect()
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -153,7 +213,10 @@ impl StateDb {
         let mut read_opts = ReadOptions::default();
         // We want `None` if the state_key changes in iteration.
         read_opts.set_prefix_same_as_start(true);
-        let mut iter = self.ledger_db.iter::<StateValueSchema>(read_opts)?;
+        let mut iter = self
+            .state_kv_db
+            .db_for_key(state_key)
+            .iter::<StateValueSchema>(read_opts)?;
         iter.seek(&(state_key.clone(), version))?;
         Ok(iter
             .next()
@@ -182,6 +245,15 @@ impl DbReader for StateStore {
         self.deref().get_state_value_by_version(state_key, version)
     }
 
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        self.deref()
+            .get_state_values_by_version(state_keys, version)
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -226,18 +298,25 @@ impl StateStore {
     pub fn new(
         ledger_db: Arc<DB>,
         state_merkle_db: Arc<DB>,
+        state_kv_db: Arc<StateKvDb>,
+        node_cache: Arc<NodeCache>,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
         hack_for_tests: bool,
+        commit_tracker: Arc<CommitTracker>,
     ) -> Self {
-        let state_merkle_db = Arc::new(StateMerkleDb::new(state_merkle_db));
+        let state_merkle_db = Arc::new(StateMerkleDb::new(state_merkle_db, node_cache));
         let state_db = Arc::new(StateDb {
             ledger_db,
             state_merkle_db,
+            state_kv_db,
+            commit_tracker,
         });
         let buffered_state = Mutex::new(
             Self::create_buffered_state_from_latest_snapshot(
                 &state_db,
                 target_snapshot_size,
+                target_snapshot_interval_in_versions,
                 hack_for_tests,
             )
             .expect("buffered state creation failed."),
@@ -246,12 +325,21 @@ impl StateStore {
             state_db,
             buffered_state,
             target_snapshot_size,
+            target_snapshot_interval_in_versions,
         }
     }
 
+    /// Creates the buffered state on top of the latest snapshot in the state merkle db, which may
+    /// be behind the latest transaction committed to the ledger db, since snapshots are committed
+    /// asynchronously and only every so often. Whatever point a previous process was killed at,
+    /// the write sets of the transactions after that snapshot are in the ledger db, since they are
+    /// committed with the transactions. They are replayed on top of the snapshot to rebuild the
+    /// state in memory, `target_snapshot_interval_in_versions` at a time, and the snapshots at the
+    /// checkpoints among them are committed as usual.
     fn create_buffered_state_from_latest_snapshot(
         state_db: &Arc<StateDb>,
         target_snapshot_size: usize,
+        target_snapshot_interval_in_versions: u64,
         hack_for_tests: bool,
     ) -> Result<BufferedState> {
         let ledger_store = LedgerStore::new(Arc::clone(&state_db.ledger_db));
@@ -276,6 +364,7 @@ impl StateStore {
             state_db,
             StateDelta::new_at_checkpoint(latest_snapshot_root_hash, latest_snapshot_version),
             target_snapshot_size,
+            target_snapshot_interval_in_versions,
         );
 
         // In some backup-restore tests we hope to open the db without consistency check.
@@ -298,44 +387,27 @@ impl StateStore {
         // Replaying the committed write sets after the latest snapshot.
         if snapshot_next_version < num_transactions {
             ensure!(
-                num_transactions - snapshot_next_version <= MAX_WRITE_SETS_AFTER_SNAPSHOT,
+                num_transactions - snapshot_next_version
+                    <= max_write_sets_after_snapshot(target_snapshot_interval_in_versions),
                 "Too many versions after state snapshot. snapshot_next_version: {}, num_transactions: {}",
                 snapshot_next_version,
                 num_transactions,
             );
-            let latest_snapshot_state_view = CachedStateView::new(
-                StateViewId::Miscellaneous,
-                state_db.clone(),
-                num_transactions,
-                buffered_state.current_state().current.clone(),
-                Arc::new(SyncProofFetcher::new(state_db.clone())),
-            )?;
-            let write_sets = TransactionStore::new(Arc::clone(&state_db.ledger_db))
-                .get_write_sets(snapshot_next_version, num_transactions)?;
-            let txn_info_iter =
-                ledger_store.get_transaction_info_iter(snapshot_next_version, write_sets.len())?;
-            let last_checkpoint_index = txn_info_iter
-                .into_iter()
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .enumerate()
-                .filter(|(_idx, txn_info)| txn_info.is_state_checkpoint())
-                .last()
-                .map(|(idx, _)| idx);
-            latest_snapshot_state_view.prime_cache_by_write_set(&write_sets)?;
-            let calculator = InMemoryStateCalculator::new(
-                buffered_state.current_state(),
-                latest_snapshot_state_view.into_state_cache(),
-            );
-            let (updates_until_last_checkpoint, state_after_last_checkpoint) = calculator
-                .calculate_for_write_sets_after_snapshot(last_checkpoint_index, &write_sets)?;
-
+            let mut next_version = snapshot_next_version;
+            while next_version < num_transactions {
+                let end_version = num_transactions
+                    .min(next_version.saturating_add(target_snapshot_interval_in_versions));
+                Self::replay_write_sets(
+                    state_db,
+                    &ledger_store,
+                    &mut buffered_state,
+                    next_version,
+                    end_version,
+                )?;
+                next_version = end_version;
+            }
             // synchronously commit the snapshot at the last checkpoint here if not committed to disk yet.
-            buffered_state.update(
-                updates_until_last_checkpoint,
-                state_after_last_checkpoint,
-                true, /* sync_commit */
-            )?;
+            buffered_state.sync_commit();
         }
 
         info!(
@@ -348,10 +420,53 @@ impl StateStore {
         Ok(buffered_state)
     }
 
+    /// Applies the write sets of the versions in `[begin_version, end_version)` to the buffered
+    /// state, which must be at `begin_version - 1`.
+    fn replay_write_sets(
+        state_db: &Arc<StateDb>,
+        ledger_store: &LedgerStore,
+        buffered_state: &mut BufferedState,
+        begin_version: Version,
+        end_version: Version,
+    ) -> Result<()> {
+        let state_view = CachedStateView::new(
+            StateViewId::Miscellaneous,
+            state_db.clone(),
+            end_version,
+            buffered_state.current_state().current.clone(),
+            Arc::new(SyncProofFetcher::new(state_db.clone())),
+        )?;
+        let write_sets = TransactionStore::new(Arc::clone(&state_db.ledger_db))
+            .get_write_sets(begin_version, end_version)?;
+        let txn_info_iter =
+            ledger_store.get_transaction_info_iter(begin_version, write_sets.len())?;
+        let last_checkpoint_index = txn_info_iter
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .enumerate()
+            .filter(|(_idx, txn_info)| txn_info.is_state_checkpoint())
+            .last()
+            .map(|(idx, _)| idx);
+        state_view.prime_cache_by_write_set(&write_sets)?;
+        let calculator = InMemoryStateCalculator::new(
+            buffered_state.current_state(),
+            state_view.into_state_cache(),
+        );
+        let (updates_until_last_checkpoint, state_after_last_checkpoint) = calculator
+            .calculate_for_write_sets_after_snapshot(last_checkpoint_index, &write_sets)?;
+        buffered_state.update(
+            updates_until_last_checkpoint,
+            state_after_last_checkpoint,
+            false, /* sync_commit */
+        )
+    }
+
     pub fn reset(&self) {
         *self.buffered_state.lock() = Self::create_buffered_state_from_latest_snapshot(
             &self.state_db,
             self.target_snapshot_size,
+            self.target_snapshot_interval_in_versions,
             false,
         )
         .expect("buffered state creation failed.");
@@ -369,6 +484,25 @@ impl StateStore {
         key_prefix: &StateKeyPrefix,
         desired_version: Version,
     ) -> Result<HashMap<StateKey, StateValue>> {
+        // The keys with the prefix are spread across all the shards.
+        let mut result = HashMap::new();
+        for shard_id in 0..self.state_kv_db.num_shards() {
+            Self::get_shard_values_by_key_prefix(
+                self.state_kv_db.db_shard(shard_id),
+                key_prefix,
+                desired_version,
+                &mut result,
+            )?;
+        }
+        Ok(result)
+    }
+
+    fn get_shard_values_by_key_prefix(
+        db: &DB,
+        key_prefix: &StateKeyPrefix,
+        desired_version: Version,
+        result: &mut HashMap<StateKey, StateValue>,
+    ) -> Result<()> {
         let mut read_opts = ReadOptions::default();
         // Without this, iterators are not guaranteed a total order of all keys, but only keys for the same prefix.
         // For example,
@@ -379,8 +513,7 @@ impl StateStore {
         // here will stick with prefix `aptos/abc` and return `None` or any arbitrary result after visited all the
         // keys starting with `aptos/abc`.
         read_opts.set_total_order_seek(true);
-        let mut iter = self.ledger_db.iter::<StateValueSchema>(read_opts)?;
-        let mut result = HashMap::new();
+        let mut iter = db.iter::<StateValueSchema>(read_opts)?;
         let mut prev_key = None;
         iter.seek(&(key_prefix))?;
         while let Some(((state_key, version), state_value_opt)) = iter.next().transpose()? {
@@ -416,9 +549,64 @@ impl StateStore {
             // Seek to the next key - this can be done by seeking to the current key with version 0
             iter.seek(&(state_key, 0))?;
         }
+        Ok(())
+    }
+
+    /// Returns the resources under `address` at `desired_version` whose types are declared in
+    /// `module_id`. They are read through the index of resources by module if it lists every
+    /// resource at the version, otherwise all the values under the address are scanned.
+    pub fn get_resources_by_module(
+        &self,
+        address: AccountAddress,
+        module_id: &ModuleId,
+        desired_version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let indexed_since = self.get_resource_by_module_index_version()?;
+        if indexed_since.map_or(true, |indexed_since| indexed_since > desired_version) {
+            let mut values =
+                self.get_values_by_key_prefix(&StateKeyPrefix::from(address), desired_version)?;
+            values.retain(|state_key, _| {
+                resource_module_id(state_key).map_or(false, |(_, id)| &id == module_id)
+            });
+            return Ok(values);
+        }
+
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_prefix_same_as_start(true);
+        let mut iter = self.ledger_db.iter::<ResourceByModuleSchema>(read_opts)?;
+        iter.seek(&(address, hash_module_id(module_id)?))?;
+        let mut result = HashMap::new();
+        for item in iter {
+            let ((_, _, state_key), ()) = item?;
+            // Resources deleted since are still in the index.
+            if let Some(state_value) =
+                self.get_state_value_by_version(&state_key, desired_version)?
+            {
+                result.insert(state_key, state_value);
+            }
+            if result.len() > MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX {
+                return Err(anyhow!(
+                    "Too many resources of module {} under {} - maximum allowed {:?}",
+                    module_id,
+                    address,
+                    MAX_VALUES_TO_FETCH_FOR_KEY_PREFIX
+                ));
+            }
+        }
         Ok(result)
     }
 
+    /// Returns the version from which the index of resources by module lists every resource, if
+    /// it does from any.
+    pub fn get_resource_by_module_index_version(&self) -> Result<Option<Version>> {
+        Ok(self
+            .ledger_db
+            .get::<PrunerMetadataSchema>(&PrunerTag::ResourceByModuleIndex)?
+            .map(|metadata| match metadata {
+                PrunerMetadata::LatestVersion(version) => version,
+            }))
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_value_range_proof(
         &self,
@@ -436,6 +624,13 @@ impl StateStore {
         cs: &mut ChangeSet,
     ) -> Result<()> {
         self.put_stats_and_indices(&value_state_sets, first_version, cs)?;
+        // The resources written from genesis on are all indexed by module as they are written.
+        if first_version == 0 {
+            cs.batch.put::<PrunerMetadataSchema>(
+                &PrunerTag::ResourceByModuleIndex,
+                &PrunerMetadata::LatestVersion(0),
+            )?;
+        }
 
         let kv_batch = value_state_sets
             .iter()
@@ -445,7 +640,29 @@ impl StateStore {
                     .map(move |(k, v)| ((k.clone(), first_version + i as Version), v.clone()))
             })
             .collect::<HashMap<_, _>>();
-        add_kv_batch(&mut cs.batch, &kv_batch)
+        self.add_kv_batch(cs, &kv_batch)
+    }
+
+    fn add_kv_batch(&self, cs: &mut ChangeSet, kv_batch: &StateValueBatch) -> Result<()> {
+        let mut num_values_by_shard = vec![0; self.state_kv_db.num_shards()];
+        for (k, v) in kv_batch {
+            let shard_id = self.state_kv_db.shard_id(&k.0);
+            num_values_by_shard[shard_id] += 1;
+            self.state_kv_db
+                .batch(shard_id, cs)
+                .put::<StateValueSchema>(k, v)?;
+            if v.is_some() {
+                put_resource_by_module(&k.0, &cs.batch)?;
+            }
+        }
+        for (shard_id, num_values) in num_values_by_shard.into_iter().enumerate() {
+            if num_values > 0 {
+                STATE_KV_SHARD_VALUES_WRITTEN
+                    .with_label_values(&[shard_id.to_string().as_str()])
+                    .inc_by(num_values);
+            }
+        }
+        Ok(())
     }
 
     pub fn get_usage(&self, version: Option<Version>) -> Result<(usize, usize)> {
@@ -496,14 +713,16 @@ impl StateStore {
                     total_state_bytes += key.size() + value.size();
                 } else {
                     // stale index of the tombstone at current version.
-                    cs.batch.put::<StaleStateValueIndexSchema>(
-                        &StaleStateValueIndex {
-                            stale_since_version: version,
-                            version,
-                            state_key: key.clone(),
-                        },
-                        &(),
-                    )?;
+                    self.state_kv_db
+                        .batch_for(key, cs)
+                        .put::<StaleStateValueIndexSchema>(
+                            &StaleStateValueIndex {
+                                stale_since_version: version,
+                                version,
+                                state_key: key.clone(),
+                            },
+                            &(),
+                        )?;
                 }
 
                 if version > 0 {
@@ -522,14 +741,16 @@ impl StateStore {
                         state_items -= 1;
                         total_state_bytes -= key.size() + old_value.size();
                         // stale index of the old value at its version.
-                        cs.batch.put::<StaleStateValueIndexSchema>(
-                            &StaleStateValueIndex {
-                                stale_since_version: version,
-                                version: old_version,
-                                state_key: key.clone(),
-                            },
-                            &(),
-                        )?;
+                        self.state_kv_db
+                            .batch_for(key, cs)
+                            .put::<StaleStateValueIndexSchema>(
+                                &StaleStateValueIndex {
+                                    stale_since_version: version,
+                                    version: old_version,
+                                    state_key: key.clone(),
+                                },
+                                &(),
+                            )?;
                     }
                 }
             }
@@ -642,20 +863,71 @@ impl StateStore {
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<Box<dyn StateSnapshotReceiver<StateKey, StateValue>>> {
-        Ok(Box::new(StateSnapshotRestore::new_overwrite(
-            &self.state_merkle_db,
-            self,
-            version,
-            expected_root_hash,
-        )?))
+        // Resume if a previous attempt to restore the snapshot at this version was interrupted.
+        if self.get_progress(version)?.is_some() {
+            Ok(Box::new(StateSnapshotRestore::new(
+                &self.state_merkle_db,
+                self,
+                version,
+                expected_root_hash,
+            )?))
+        } else {
+            Ok(Box::new(StateSnapshotRestore::new_overwrite(
+                &self.state_merkle_db,
+                self,
+                version,
+                expected_root_hash,
+            )?))
+        }
+    }
+}
+
+/// The address of the resource at `state_key` and the module declaring its type, if it is a
+/// resource.
+fn resource_module_id(state_key: &StateKey) -> Option<(AccountAddress, ModuleId)> {
+    match state_key {
+        StateKey::AccessPath(access_path) => match bcs::from_bytes(&access_path.path) {
+            Ok(Path::Resource(struct_tag)) => Some((
+                access_path.address,
+                ModuleId::new(struct_tag.address, struct_tag.module),
+            )),
+            _ => None,
+        },
+        StateKey::TableItem { .. } | StateKey::Raw(_) => None,
+    }
+}
+
+/// Adds the resource at `state_key` to the index of resources by module. Returns false, leaving
+/// the index as is, if `state_key` is not a resource.
+pub(crate) fn put_resource_by_module(state_key: &StateKey, batch: &SchemaBatch) -> Result<bool> {
+    match resource_module_id(state_key) {
+        Some((address, module_id)) => {
+            batch.put::<ResourceByModuleSchema>(
+                &(address, hash_module_id(&module_id)?, state_key.clone()),
+                &(),
+            )?;
+            Ok(true)
+        }
+        None => Ok(false),
     }
 }
 
 impl StateValueWriter<StateKey, StateValue> for StateStore {
-    fn write_kv_batch(&self, node_batch: &StateValueBatch) -> Result<()> {
-        let mut batch = SchemaBatch::new();
-        add_kv_batch(&mut batch, node_batch)?;
-        self.ledger_db.write_schemas(batch)
+    fn write_kv_batch(
+        &self,
+        version: Version,
+        node_batch: &StateValueBatch,
+        progress: Option<StateSnapshotProgress>,
+    ) -> Result<()> {
+        let mut cs = ChangeSet::new();
+        self.add_kv_batch(&mut cs, node_batch)?;
+        if let Some(progress) = progress {
+            cs.batch
+                .put::<StateSnapshotRestoreProgressSchema>(&version, &progress)?;
+        }
+        // The progress is only recorded once the values it covers are written.
+        self.state_kv_db.commit(cs.state_kv_batches)?;
+        self.ledger_db.write_schemas(cs.batch)
     }
 
     fn write_usage(&self, version: Version, items: usize, total_bytes: usize) -> Result<()> {
@@ -667,11 +939,9 @@ impl StateValueWriter<StateKey, StateValue> for StateStore {
             },
         )
     }
-}
 
-fn add_kv_batch(batch: &mut SchemaBatch, kv_batch: &StateValueBatch) -> Result<()> {
-    for (k, v) in kv_batch {
-        batch.put::<StateValueSchema>(k, v)?;
+    fn get_progress(&self, version: Version) -> Result<Option<StateSnapshotProgress>> {
+        self.ledger_db
+            .get::<StateSnapshotRestoreProgressSchema>(&version)
     }
-    Ok(())
 }
@@ -57,10 +57,12 @@ impl StateMerkleBatchCommitter {
                     let _timer = OTHER_TIMERS_SECONDS
                         .with_label_values(&["commit_jellyfish_merkle_nodes"])
                         .start_timer();
+                    let commit_guard = self.state_db.commit_tracker.track();
                     self.state_db
                         .state_merkle_db
                         .write_schemas(batch)
                         .expect("State merkle batch commit failed.");
+                    drop(commit_guard);
                     snapshot_ready_sender.send(()).unwrap();
                     info!(
                         version = state_delta.current_version,
@@ -12,6 +12,10 @@ use aptos_temppath::TempPath;
 use aptos_types::{
     access_path::AccessPath, account_address::AccountAddress, state_store::state_key::StateKeyTag,
 };
+use move_deps::move_core_types::{
+    identifier::Identifier,
+    language_storage::{ResourceKey, StructTag},
+};
 use storage_interface::{jmt_update_refs, jmt_updates, DbReader, DbWriter, StateSnapshotReceiver};
 
 use crate::{pruner::state_store::StateMerklePruner, AptosDB};
@@ -148,6 +152,41 @@ fn test_state_store_reader_writer() {
     verify_value_and_proof(store, key3, Some(&value3), 1, root);
 }
 
+#[test]
+fn test_get_state_values_by_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::Raw(String::from("test_key2").into_bytes());
+    let key3 = StateKey::Raw(String::from("test_key3").into_bytes());
+
+    let value1 = StateValue::from(String::from("test_val1").into_bytes());
+    let value1_update = StateValue::from(String::from("test_val1_update").into_bytes());
+    let value2 = StateValue::from(String::from("test_val2").into_bytes());
+
+    put_value_set(store, vec![(key1.clone(), value1.clone())], 0, None);
+    put_value_set(
+        store,
+        vec![
+            (key1.clone(), value1_update.clone()),
+            (key2.clone(), value2.clone()),
+        ],
+        1,
+        Some(0),
+    );
+
+    let keys = [key3, key2, key1];
+    assert_eq!(
+        store.get_state_values_by_version(&keys, 0).unwrap(),
+        vec![None, None, Some(value1)]
+    );
+    assert_eq!(
+        store.get_state_values_by_version(&keys, 1).unwrap(),
+        vec![None, Some(value2), Some(value1_update)]
+    );
+}
+
 #[test]
 fn test_get_values_by_key_prefix() {
     let tmp_dir = TempPath::new();
@@ -239,6 +278,128 @@ fn test_get_values_by_key_prefix() {
     assert_eq!(*key_value_map.get(&key5).unwrap(), value5_v2);
 }
 
+fn resource_key(address: AccountAddress, module: &str, name: &str) -> StateKey {
+    StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        address,
+        StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        },
+    )))
+}
+
+fn put_values(store: &StateStore, values: Vec<(StateKey, Option<StateValue>)>, version: Version) {
+    let value_set = values.into_iter().collect();
+    let mut cs = ChangeSet::new();
+    store
+        .put_value_sets(vec![&value_set], version, &mut cs)
+        .unwrap();
+    store.ledger_db.write_schemas(cs.batch).unwrap();
+}
+
+#[test]
+fn test_get_resources_by_module() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let address = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+    let other_address = AccountAddress::new([22u8; AccountAddress::LENGTH]);
+    let coin = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+    let account = ModuleId::new(AccountAddress::ONE, Identifier::new("account").unwrap());
+
+    let coin_store = resource_key(address, "coin", "CoinStore");
+    let coin_info = resource_key(address, "coin", "CoinInfo");
+    let account_resource = resource_key(address, "account", "Account");
+    let coin_module = StateKey::AccessPath(AccessPath::code_access_path(coin.clone()));
+    let other_coin_store = resource_key(other_address, "coin", "CoinStore");
+    let value = |v: &str| StateValue::from(v.as_bytes().to_vec());
+
+    // Committing from genesis, the resources are indexed from version 0.
+    put_values(
+        store,
+        vec![
+            (coin_store.clone(), Some(value("coin_store_v0"))),
+            (account_resource.clone(), Some(value("account_v0"))),
+            (coin_module, Some(value("coin_module_v0"))),
+            (other_coin_store, Some(value("other_coin_store_v0"))),
+        ],
+        0,
+    );
+    assert_eq!(
+        store.get_resource_by_module_index_version().unwrap(),
+        Some(0)
+    );
+    put_values(
+        store,
+        vec![
+            (coin_store.clone(), None),
+            (coin_info.clone(), Some(value("coin_info_v1"))),
+        ],
+        1,
+    );
+
+    let check = |store: &StateStore| {
+        let resources = store.get_resources_by_module(address, &coin, 0).unwrap();
+        assert_eq!(
+            resources,
+            vec![(coin_store.clone(), value("coin_store_v0"))]
+                .into_iter()
+                .collect()
+        );
+        // The deleted resource is left out.
+        let resources = store.get_resources_by_module(address, &coin, 1).unwrap();
+        assert_eq!(
+            resources,
+            vec![(coin_info.clone(), value("coin_info_v1"))]
+                .into_iter()
+                .collect()
+        );
+        let resources = store.get_resources_by_module(address, &account, 1).unwrap();
+        assert_eq!(
+            resources,
+            vec![(account_resource.clone(), value("account_v0"))]
+                .into_iter()
+                .collect()
+        );
+        let unknown = ModuleId::new(AccountAddress::ONE, Identifier::new("unknown").unwrap());
+        assert!(store
+            .get_resources_by_module(address, &unknown, 1)
+            .unwrap()
+            .is_empty());
+    };
+    check(store);
+
+    // A DB written before the index falls back to scanning the resources of the account, until
+    // the index is backfilled.
+    let index_keys = store
+        .ledger_db
+        .iter::<ResourceByModuleSchema>(ReadOptions::default())
+        .unwrap()
+        .map(|item| item.map(|(key, ())| key))
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(index_keys.len(), 4);
+    let batch = SchemaBatch::new();
+    for key in &index_keys {
+        batch.delete::<ResourceByModuleSchema>(key).unwrap();
+    }
+    batch
+        .delete::<PrunerMetadataSchema>(&PrunerTag::ResourceByModuleIndex)
+        .unwrap();
+    store.ledger_db.write_schemas(batch).unwrap();
+    assert_eq!(store.get_resource_by_module_index_version().unwrap(), None);
+    check(store);
+
+    assert_eq!(db.backfill_resource_by_module_index().unwrap(), 4);
+    assert_eq!(
+        store.get_resource_by_module_index_version().unwrap(),
+        Some(0)
+    );
+    check(store);
+}
+
 #[test]
 fn test_stale_node_index() {
     let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
@@ -298,7 +459,11 @@ fn test_stale_node_index() {
     // Prune with limit = 2 and target_min_readable_version = 2, two entries with
     // stale_since_version = 1 will be pruned. min_readable_version will be promoted to 1.
     {
-        let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+        let pruner = StateMerklePruner::new(
+            Arc::clone(&db.state_merkle_db),
+            Arc::clone(&db.state_kv_db),
+            Arc::clone(&db.node_cache),
+        );
         assert_eq!(
             prune_stale_indices(
                 &pruner, 0, /* min_readable_version */
@@ -319,7 +484,11 @@ fn test_stale_node_index() {
     // stale_since_version = 2 will be pruned. Min readable version will change even though there
     // is one more entry with stale_since_version = 2 remaining.
     {
-        let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+        let pruner = StateMerklePruner::new(
+            Arc::clone(&db.state_merkle_db),
+            Arc::clone(&db.state_kv_db),
+            Arc::clone(&db.node_cache),
+        );
         assert_eq!(
             prune_stale_indices(
                 &pruner, 1, /* min_readable_version */
@@ -341,7 +510,11 @@ fn test_stale_node_index() {
     // stale_since_version = 2 will be pruned. Min_readable_version will change since there is
     // one more entry with stale_since_version = 2 remaining.
     {
-        let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+        let pruner = StateMerklePruner::new(
+            Arc::clone(&db.state_merkle_db),
+            Arc::clone(&db.state_kv_db),
+            Arc::clone(&db.node_cache),
+        );
         assert_eq!(
             prune_stale_indices(
                 &pruner, 1, /* min_readable_version */
@@ -421,7 +594,11 @@ fn test_stale_node_index_with_target_version() {
     // stale_since_version = 1 will be pruned. min_readable_version will be promoted to 1. Create a
     // new pruner everytime to test the min_readable_version initialization logic.
     {
-        let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+        let pruner = StateMerklePruner::new(
+            Arc::clone(&db.state_merkle_db),
+            Arc::clone(&db.state_kv_db),
+            Arc::clone(&db.node_cache),
+        );
         assert_eq!(
             prune_stale_indices(
                 &pruner, 0, /* min_readable_version */
@@ -450,7 +627,11 @@ fn test_stale_node_index_with_target_version() {
     // stale_since_version = 2 will not be pruned. Create a new pruner everytime to test the
     // min_readable_version initialization logic.
     {
-        let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+        let pruner = StateMerklePruner::new(
+            Arc::clone(&db.state_merkle_db),
+            Arc::clone(&db.state_kv_db),
+            Arc::clone(&db.node_cache),
+        );
         assert_eq!(
             prune_stale_indices(
                 &pruner, 1, /* min_readable_version */
@@ -485,7 +666,11 @@ fn test_stale_node_index_all_at_once() {
     let tmp_dir = TempPath::new();
     let db = AptosDB::new_for_test(&tmp_dir);
     let store = &db.state_store;
-    let pruner = StateMerklePruner::new(Arc::clone(&db.state_merkle_db));
+    let pruner = StateMerklePruner::new(
+        Arc::clone(&db.state_merkle_db),
+        Arc::clone(&db.state_kv_db),
+        Arc::clone(&db.node_cache),
+    );
 
     // Update.
     // ```text
@@ -724,6 +909,62 @@ proptest! {
         );
     }
 
+    #[test]
+    fn test_restore_resumes_after_interruption(
+        (input, batch_size, num_batches_before_interruption) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
+            .prop_flat_map(|input| {
+                let len = input.len();
+                (Just(input), 1..len)
+            })
+            .prop_flat_map(|(input, batch_size)| {
+                let num_batches = (input.len() + batch_size - 1) / batch_size;
+                (Just(input), Just(batch_size), 1..=num_batches)
+            })
+    ) {
+        let tmp_dir1 = TempPath::new();
+        let db1 = AptosDB::new_for_test(&tmp_dir1);
+        let store1 = &db1.state_store;
+        init_store(store1, input.clone().into_iter());
+
+        let version = (input.len() - 1) as Version;
+        let expected_root_hash = store1.get_root_hash(version).unwrap();
+
+        let tmp_dir2 = TempPath::new();
+        let db2 = AptosDB::new_for_test(&tmp_dir2);
+        let store2 = &db2.state_store;
+
+        {
+            let mut restore = store2.get_snapshot_receiver(version, expected_root_hash).unwrap();
+            for idx in 0..num_batches_before_interruption {
+                let chunk = store1.get_value_chunk_with_proof(version, idx * batch_size, batch_size).unwrap();
+                restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+            }
+            // Do not call `finish`.
+        }
+        prop_assert!(store2.get_progress(version).unwrap().is_some());
+
+        // Resume by replaying all the chunks, as a restore run again from the start does.
+        let mut restore = store2.get_snapshot_receiver(version, expected_root_hash).unwrap();
+        let mut current_idx = 0;
+        while current_idx < input.len() {
+            let chunk = store1.get_value_chunk_with_proof(version, current_idx, batch_size).unwrap();
+            restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+            current_idx += batch_size;
+        }
+
+        restore.finish_box().unwrap();
+        let actual_root_hash = store2.get_root_hash(version).unwrap();
+        prop_assert_eq!(actual_root_hash, expected_root_hash);
+        prop_assert_eq!(
+            store2.get_value_count(version).unwrap(),
+            input.len()
+        );
+        prop_assert_eq!(
+            store2.get_usage(Some(version)).unwrap(),
+            store1.get_usage(Some(version)).unwrap()
+        );
+    }
+
     #[test]
     fn test_get_rightmost_leaf(
         (input, batch1_size) in hash_map(any::<StateKey>(), any::<StateValue>(), 2..1000)
@@ -764,7 +1005,7 @@ proptest! {
         restore.add_chunk(batch1, proof_of_batch1).unwrap();
 
         let expected = store2.state_merkle_db.get_rightmost_leaf_naive().unwrap();
-        let actual = store2.state_merkle_db.get_rightmost_leaf().unwrap();
+        let actual = store2.state_merkle_db.get_rightmost_leaf(version).unwrap();
         prop_assert_eq!(actual, expected);
     }
 
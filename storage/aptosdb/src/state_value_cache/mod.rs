@@ -0,0 +1,186 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines the in-memory cache of the state values written by the most recent versions,
+//! filled right after each commit, so that reads of state values at those versions, e.g. the API's
+//! at the latest version, are served from memory rather than from RocksDB.
+//!
+//! The cache holds every write of a contiguous range of versions, ending at the latest version
+//! committed. The value of a state key at a version in that range is known if the key was written
+//! in the range at or before the version; otherwise it was last written before the range, and the
+//! read falls back to the DB. Should a commit not follow the last one cached, e.g. after a state
+//! snapshot was restored, the cache starts over from that commit rather than leaving a hole in the
+//! range.
+
+use crate::metrics::{STATE_VALUE_CACHE_ITEMS, STATE_VALUE_CACHE_REQUESTS};
+use aptos_infallible::RwLock;
+use aptos_logger::warn;
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    fmt::{self, Debug, Formatter},
+};
+
+#[cfg(test)]
+mod test;
+
+/// The default number of most recent versions the cache holds the writes of.
+pub const DEFAULT_STATE_VALUE_CACHE_VERSIONS: u64 = 10_000;
+
+#[derive(Default)]
+struct Inner {
+    /// The first version the writes of which are all cached, if any are.
+    first_version: Option<Version>,
+    /// The version after the last one cached.
+    next_version: Version,
+    /// The values written to each state key in the range, by version, None being a deletion.
+    values: HashMap<StateKey, BTreeMap<Version, Option<StateValue>>>,
+    /// The state keys written by each version in the range, oldest first, to evict by version.
+    keys_by_version: VecDeque<(Version, Vec<StateKey>)>,
+}
+
+/// A cache of the state values written by the most recent versions, bounded by the number of
+/// versions.
+pub struct StateValueCache {
+    max_versions: RwLock<u64>,
+    inner: RwLock<Inner>,
+}
+
+impl StateValueCache {
+    pub fn new(max_versions: u64) -> Self {
+        Self {
+            max_versions: RwLock::new(max_versions),
+            inner: RwLock::new(Inner::default()),
+        }
+    }
+
+    /// Changes the number of versions the cache holds the writes of, evicting the oldest versions
+    /// if it shrinks. 0 disables the cache.
+    pub fn set_max_versions(&self, max_versions: u64) {
+        *self.max_versions.write() = max_versions;
+        Self::evict(&mut self.inner.write(), max_versions);
+    }
+
+    /// Returns the value of the state key at the version, which is Some(None) if the key doesn't
+    /// exist then, or None if the cache doesn't know it.
+    pub fn get(&self, state_key: &StateKey, version: Version) -> Option<Option<StateValue>> {
+        let inner = self.inner.read();
+        let value = match inner.first_version {
+            Some(first_version) if version >= first_version && version < inner.next_version => {
+                inner
+                    .values
+                    .get(state_key)
+                    .and_then(|values| values.range(..=version).next_back())
+                    .map(|(_, value)| value.clone())
+            }
+            _ => None,
+        };
+        STATE_VALUE_CACHE_REQUESTS
+            .with_label_values(&[if value.is_some() { "hit" } else { "miss" }])
+            .inc();
+        value
+    }
+
+    /// Caches the writes of the versions starting at `first_version`, once they are committed.
+    pub fn add_versions(
+        &self,
+        first_version: Version,
+        state_updates: &[&HashMap<StateKey, Option<StateValue>>],
+    ) {
+        let max_versions = *self.max_versions.read();
+        let mut inner = self.inner.write();
+        if max_versions == 0 {
+            Self::evict(&mut inner, 0);
+            return;
+        }
+        if inner.first_version.is_some() && inner.next_version != first_version {
+            warn!(
+                next_version = inner.next_version,
+                first_version = first_version,
+                "Versions committed don't follow the ones cached, clearing the state value cache.",
+            );
+            Self::evict(&mut inner, 0);
+        }
+        if inner.first_version.is_none() {
+            inner.first_version = Some(first_version);
+        }
+
+        for (version, updates) in (first_version..).zip(state_updates) {
+            for (state_key, value) in updates.iter() {
+                if inner
+                    .values
+                    .entry(state_key.clone())
+                    .or_default()
+                    .insert(version, value.clone())
+                    .is_none()
+                {
+                    STATE_VALUE_CACHE_ITEMS.inc();
+                }
+            }
+            inner
+                .keys_by_version
+                .push_back((version, updates.keys().cloned().collect()));
+        }
+        inner.next_version = first_version + state_updates.len() as u64;
+        Self::evict(&mut inner, max_versions);
+    }
+
+    /// Forgets every version cached, e.g. once the state is replaced by a state snapshot.
+    pub fn clear(&self) {
+        Self::evict(&mut self.inner.write(), 0);
+    }
+
+    /// Evicts the oldest versions until at most `max_versions` are left.
+    fn evict(inner: &mut Inner, max_versions: u64) {
+        let first_version = match inner.first_version {
+            Some(first_version) => first_version,
+            None => return,
+        };
+        let new_first_version = first_version.max(inner.next_version.saturating_sub(max_versions));
+        while let Some((version, _)) = inner.keys_by_version.front() {
+            if *version >= new_first_version {
+                break;
+            }
+            let (version, state_keys) = inner.keys_by_version.pop_front().expect("Checked above");
+            for state_key in state_keys {
+                if let Some(values) = inner.values.get_mut(&state_key) {
+                    if values.remove(&version).is_some() {
+                        STATE_VALUE_CACHE_ITEMS.dec();
+                    }
+                    if values.is_empty() {
+                        inner.values.remove(&state_key);
+                    }
+                }
+            }
+        }
+        inner.first_version = if new_first_version < inner.next_version {
+            Some(new_first_version)
+        } else {
+            None
+        };
+    }
+
+    #[cfg(test)]
+    fn num_items(&self) -> usize {
+        self.inner
+            .read()
+            .values
+            .values()
+            .map(|values| values.len())
+            .sum()
+    }
+}
+
+impl Debug for StateValueCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let inner = self.inner.read();
+        f.debug_struct("StateValueCache")
+            .field("max_versions", &*self.max_versions.read())
+            .field("first_version", &inner.first_version)
+            .field("next_version", &inner.next_version)
+            .finish_non_exhaustive()
+    }
+}
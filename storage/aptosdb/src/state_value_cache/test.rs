@@ -0,0 +1,83 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+
+fn key(i: u8) -> StateKey {
+    StateKey::Raw(vec![i])
+}
+
+fn value(i: u8) -> Option<StateValue> {
+    Some(StateValue::from(vec![i]))
+}
+
+fn updates(writes: &[(u8, Option<StateValue>)]) -> HashMap<StateKey, Option<StateValue>> {
+    writes
+        .iter()
+        .map(|(i, value)| (key(*i), value.clone()))
+        .collect()
+}
+
+#[test]
+fn test_get_at_cached_versions() {
+    let cache = StateValueCache::new(DEFAULT_STATE_VALUE_CACHE_VERSIONS);
+    assert_eq!(cache.get(&key(1), 0), None);
+
+    let v10 = updates(&[(1, value(1)), (2, value(2))]);
+    let v11 = updates(&[(1, value(3))]);
+    let v12 = updates(&[(2, None)]);
+    cache.add_versions(10, &[&v10, &v11, &v12]);
+
+    assert_eq!(cache.get(&key(1), 10), Some(value(1)));
+    assert_eq!(cache.get(&key(1), 11), Some(value(3)));
+    assert_eq!(cache.get(&key(1), 12), Some(value(3)));
+    assert_eq!(cache.get(&key(2), 11), Some(value(2)));
+    // A deletion is known, unlike a key never written in the cached versions.
+    assert_eq!(cache.get(&key(2), 12), Some(None));
+    assert_eq!(cache.get(&key(3), 12), None);
+    // Versions before and after the cached ones are not known.
+    assert_eq!(cache.get(&key(1), 9), None);
+    assert_eq!(cache.get(&key(1), 13), None);
+}
+
+#[test]
+fn test_evicts_oldest_versions() {
+    let cache = StateValueCache::new(2);
+    let v0 = updates(&[(1, value(1))]);
+    let v1 = updates(&[(2, value(2))]);
+    let v2 = updates(&[(3, value(3))]);
+    cache.add_versions(0, &[&v0, &v1]);
+    cache.add_versions(2, &[&v2]);
+
+    // Key 1 was last written at a version evicted, so its value is not known anymore.
+    assert_eq!(cache.get(&key(1), 2), None);
+    assert_eq!(cache.get(&key(2), 2), Some(value(2)));
+    assert_eq!(cache.get(&key(3), 2), Some(value(3)));
+    assert_eq!(cache.num_items(), 2);
+
+    cache.set_max_versions(0);
+    assert_eq!(cache.get(&key(3), 2), None);
+    assert_eq!(cache.num_items(), 0);
+    let v3 = updates(&[(4, value(4))]);
+    cache.add_versions(3, &[&v3]);
+    assert_eq!(cache.get(&key(4), 3), None);
+}
+
+#[test]
+fn test_starts_over_after_gap() {
+    let cache = StateValueCache::new(DEFAULT_STATE_VALUE_CACHE_VERSIONS);
+    let v0 = updates(&[(1, value(1))]);
+    cache.add_versions(0, &[&v0]);
+
+    // Versions 1 to 4 were never cached, e.g. restored from a state snapshot.
+    let v5 = updates(&[(2, value(2))]);
+    cache.add_versions(5, &[&v5]);
+    assert_eq!(cache.get(&key(1), 5), None);
+    assert_eq!(cache.get(&key(1), 0), None);
+    assert_eq!(cache.get(&key(2), 5), Some(value(2)));
+    assert_eq!(cache.num_items(), 1);
+
+    cache.clear();
+    assert_eq!(cache.get(&key(2), 5), None);
+    assert_eq!(cache.num_items(), 0);
+}
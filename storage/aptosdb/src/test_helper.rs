@@ -4,7 +4,12 @@
 ///! This module provides reusable helpers in tests.
 use super::*;
 use crate::{
-    jellyfish_merkle_node::JellyfishMerkleNodeSchema, schema::state_value::StateValueSchema,
+    jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+    pruner::pruner_metadata::{PrunerMetadata, PrunerTag},
+    schema::{
+        auth_key_by_account::AuthKeyByAccountSchema, pruner_metadata::PrunerMetadataSchema,
+        state_value::StateValueSchema,
+    },
 };
 use aptos_types::ledger_info::generate_ledger_info_with_sig;
 
@@ -19,7 +24,10 @@ use aptos_types::{
 };
 use executor_types::ProofReader;
 use proptest::{collection::vec, prelude::*};
+use schemadb::SchemaBatch;
 use scratchpad::SparseMerkleTree;
+use std::collections::HashMap;
+use storage_interface::{jmt_update_refs, jmt_updates};
 
 pub fn update_in_memory_state(state: &mut StateDelta, txns_to_commit: &[TransactionToCommit]) {
     let mut next_version = state.current_version.map_or(0, |v| v + 1);
@@ -138,6 +146,34 @@ prop_compose! {
     }
 }
 
+/// Saves the blocks to the DB from version 0 and returns the number of versions saved.
+pub fn save_blocks(
+    db: &AptosDB,
+    blocks: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+) -> Version {
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in blocks {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    cur_ver
+}
+
 pub fn arb_blocks_to_commit(
 ) -> impl Strategy<Value = Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>> {
     arb_blocks_to_commit_impl(
@@ -336,6 +372,409 @@ pub fn test_save_blocks_impl(
     );
 }
 
+pub fn test_secondary_catch_up_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let secondary_tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let secondary_db = AptosDB::open_as_secondary(
+        tmp_dir.path(),
+        secondary_tmp_dir.path(),
+        RocksdbConfigs::default(),
+    )
+    .unwrap();
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+
+        secondary_db.try_catch_up_with_primary().unwrap();
+        let ledger_version = ledger_info_with_sigs.ledger_info().version();
+        assert_eq!(
+            secondary_db.get_latest_ledger_info().unwrap(),
+            *ledger_info_with_sigs
+        );
+        assert_eq!(
+            secondary_db.get_latest_state_checkpoint_version().unwrap(),
+            Some(ledger_version)
+        );
+        for (version, txn_to_commit) in (cur_ver..).zip(txns_to_commit) {
+            assert_eq!(
+                secondary_db
+                    .get_transaction_by_version(version, ledger_version, false)
+                    .unwrap()
+                    .transaction,
+                *txn_to_commit.transaction()
+            );
+            for (state_key, state_value) in txn_to_commit.state_updates() {
+                assert_eq!(
+                    secondary_db
+                        .get_state_value_by_version(state_key, version)
+                        .unwrap(),
+                    *state_value
+                );
+            }
+        }
+
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+/// Saves the blocks and, after each of them, opens a checkpoint of the DB the way a node opens
+/// its DB after being killed: once with the snapshots of the block's checkpoints possibly still
+/// being committed, and once they all are. Each must recover the state the blocks saved so far
+/// left, and recover it again if killed right after recovering. The checkpoints are opened with
+/// a snapshot interval that may be shorter than the versions to replay, as when a node restarts
+/// with a shorter interval than it was killed with.
+pub fn test_crash_recovery_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    target_snapshot_size: usize,
+    target_snapshot_interval_in_versions: u64,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_target_snapshot_size(&tmp_dir, target_snapshot_size);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+
+        let (_checkpoint_dir, recovered) = recover_from_checkpoint(
+            &db,
+            target_snapshot_size,
+            target_snapshot_interval_in_versions,
+        );
+        verify_recovered_state(&recovered, ledger_info_with_sigs, &in_memory_state);
+        // Killed again before anything was committed after recovering.
+        let (_checkpoint_dir, recovered_again) = recover_from_checkpoint(
+            &recovered,
+            target_snapshot_size,
+            target_snapshot_interval_in_versions,
+        );
+        verify_recovered_state(&recovered_again, ledger_info_with_sigs, &in_memory_state);
+
+        db.state_store.buffered_state().lock().sync_commit();
+        let (_checkpoint_dir, recovered) = recover_from_checkpoint(
+            &db,
+            target_snapshot_size,
+            target_snapshot_interval_in_versions,
+        );
+        verify_recovered_state(&recovered, ledger_info_with_sigs, &in_memory_state);
+    }
+}
+
+/// The number of shards of the state values in the tests killing the DB between the writes of a
+/// commit, so that the state values are written separately from the rest of the ledger.
+const NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS: usize = 2;
+
+/// Saves the blocks, and before each of them is saved, opens a checkpoint of the DB as if it was
+/// killed after the state values of the block were written, but before the state merkle tree and
+/// the rest of the ledger, and then one as if it was killed after the rest of the ledger was
+/// written too, but before the state merkle tree. The first must recover the state before the
+/// block, ignoring the state values written, and the second the state after it, replaying the
+/// block's write sets. The blocks after are saved to the second one.
+pub fn test_crash_after_state_kv_commit_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let mut db_dirs = vec![TempPath::new()];
+    let mut db = AptosDB::new_for_test_with_state_kv_shards(
+        &db_dirs[0],
+        NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS,
+    );
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut latest_ledger_info: Option<&LedgerInfoWithSignatures> = None;
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        let state_before_block = in_memory_state.clone();
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+
+        let mut cs = ChangeSet::new();
+        db.save_transactions_impl(txns_to_commit, cur_ver, &mut cs)
+            .unwrap();
+        db.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut cs)
+            .unwrap();
+        let (sealed_cs, _) = db
+            .seal_change_set(cur_ver, txns_to_commit.len() as u64, cs)
+            .unwrap();
+
+        db.state_kv_db.commit(sealed_cs.state_kv_batches).unwrap();
+        let (_checkpoint_dir, recovered) = recover_from_checkpoint_with(&db, |path| {
+            AptosDB::new_for_test_with_state_kv_shards(path, NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS)
+        });
+        verify_recovered_state_option(&recovered, latest_ledger_info, &state_before_block);
+        drop(recovered);
+
+        db.ledger_db.write_schemas(sealed_cs.batch).unwrap();
+        let (checkpoint_dir, recovered) = recover_from_checkpoint_with(&db, |path| {
+            AptosDB::new_for_test_with_state_kv_shards(path, NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS)
+        });
+        verify_recovered_state(&recovered, ledger_info_with_sigs, &in_memory_state);
+        db = recovered;
+        db_dirs.push(checkpoint_dir);
+
+        latest_ledger_info = Some(ledger_info_with_sigs);
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+/// Saves the blocks, and before each of them is saved, opens a checkpoint of the DB as if it was
+/// killed after the state values of the block and the snapshot of the state merkle tree at its
+/// last checkpoint were written, but before the rest of the ledger, which records what was
+/// committed. It must recover the state before the block, ignoring the snapshot, and the block
+/// must then be saved to it as usual.
+pub fn test_crash_after_state_merkle_commit_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let mut db_dirs = vec![TempPath::new()];
+    let mut db = AptosDB::new_for_test_with_state_kv_shards(
+        &db_dirs[0],
+        NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS,
+    );
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut latest_ledger_info: Option<&LedgerInfoWithSignatures> = None;
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        let state_before_block = in_memory_state.clone();
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        // The blocks end with a state checkpoint, and the previous one is persisted.
+        let mut updates_since_previous_snapshot = HashMap::new();
+        for txn_to_commit in txns_to_commit {
+            updates_since_previous_snapshot.extend(txn_to_commit.state_updates().clone());
+        }
+
+        let mut cs = ChangeSet::new();
+        db.save_transactions_impl(txns_to_commit, cur_ver, &mut cs)
+            .unwrap();
+        let (sealed_cs, _) = db
+            .seal_change_set(cur_ver, txns_to_commit.len() as u64, cs)
+            .unwrap();
+        db.state_kv_db.commit(sealed_cs.state_kv_batches).unwrap();
+        let (batch, root_hash) = db
+            .state_store
+            .state_merkle_db
+            .merklize_value_set(
+                jmt_update_refs(&jmt_updates(&updates_since_previous_snapshot)),
+                None,
+                in_memory_state.base_version.unwrap(),
+                state_before_block.base_version,
+            )
+            .unwrap();
+        assert_eq!(root_hash, in_memory_state.base.root_hash());
+        db.state_store.state_merkle_db.write_schemas(batch).unwrap();
+
+        let (checkpoint_dir, recovered) = recover_from_checkpoint_with(&db, |path| {
+            AptosDB::new_for_test_with_state_kv_shards(path, NUM_STATE_KV_SHARDS_FOR_CRASH_TESTS)
+        });
+        verify_recovered_state_option(&recovered, latest_ledger_info, &state_before_block);
+
+        recovered
+            .save_transactions(
+                txns_to_commit,
+                cur_ver,                /* first_version */
+                cur_ver.checked_sub(1), /* base_state_version */
+                Some(ledger_info_with_sigs),
+                true, /* sync_commit */
+                in_memory_state.clone(),
+            )
+            .unwrap();
+        verify_recovered_state(&recovered, ledger_info_with_sigs, &in_memory_state);
+        db = recovered;
+        db_dirs.push(checkpoint_dir);
+
+        latest_ledger_info = Some(ledger_info_with_sigs);
+        cur_ver += txns_to_commit.len() as u64;
+    }
+}
+
+/// Saves the blocks to a DB with the indexer, and reopens it after removing the authentication keys
+/// indexed for the second half of the versions, as if it was killed after the indexer indexed them
+/// but before their authentication keys were. Opening the DB must index them again.
+pub fn test_auth_key_index_catch_up_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test_with_indexer(&tmp_dir);
+
+    let mut in_memory_state = db
+        .state_store
+        .buffered_state()
+        .lock()
+        .current_state()
+        .clone();
+    let mut cur_ver: Version = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver,                /* first_version */
+            cur_ver.checked_sub(1), /* base_state_version */
+            Some(ledger_info_with_sigs),
+            false, /* sync_commit */
+            in_memory_state.clone(),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let auth_keys = get_auth_keys(&db);
+    assert_eq!(
+        db.transaction_store
+            .get_auth_key_index_next_version()
+            .unwrap(),
+        Some(cur_ver)
+    );
+
+    let rewind_to = (cur_ver + 1) / 2;
+    let batch = SchemaBatch::new();
+    for (key, _auth_key) in &auth_keys {
+        if key.1 >= rewind_to {
+            batch.delete::<AuthKeyByAccountSchema>(key).unwrap();
+        }
+    }
+    batch
+        .put::<PrunerMetadataSchema>(
+            &PrunerTag::AuthKeyIndex,
+            &PrunerMetadata::LatestVersion(rewind_to - 1),
+        )
+        .unwrap();
+    db.ledger_db.write_schemas(batch).unwrap();
+    drop(db);
+
+    let db = AptosDB::new_for_test_with_indexer(&tmp_dir);
+    assert_eq!(get_auth_keys(&db), auth_keys);
+    assert_eq!(
+        db.transaction_store
+            .get_auth_key_index_next_version()
+            .unwrap(),
+        Some(cur_ver)
+    );
+}
+
+fn get_auth_keys(db: &AptosDB) -> Vec<((AccountAddress, Version), Vec<u8>)> {
+    let mut iter = db
+        .ledger_db
+        .iter::<AuthKeyByAccountSchema>(Default::default())
+        .unwrap();
+    iter.seek_to_first();
+    iter.collect::<Result<_>>().unwrap()
+}
+
+/// Opens a checkpoint of the DB, which has whatever it had persisted when it was taken, as if the
+/// process using the DB had been killed at that point. The checkpoint is deleted once the returned
+/// path is dropped, which must be after the DB is.
+fn recover_from_checkpoint(
+    db: &AptosDB,
+    target_snapshot_size: usize,
+    target_snapshot_interval_in_versions: u64,
+) -> (TempPath, AptosDB) {
+    recover_from_checkpoint_with(db, |path| {
+        AptosDB::new_for_test_with_target_snapshot_interval(
+            path,
+            target_snapshot_size,
+            target_snapshot_interval_in_versions,
+        )
+    })
+}
+
+/// Like `recover_from_checkpoint`, opening the checkpoint with `open`.
+fn recover_from_checkpoint_with(
+    db: &AptosDB,
+    open: impl FnOnce(&TempPath) -> AptosDB,
+) -> (TempPath, AptosDB) {
+    let checkpoint_dir = TempPath::new();
+    checkpoint_dir.create_as_dir().unwrap();
+    db.create_checkpoint(&checkpoint_dir).unwrap();
+    let recovered = open(&checkpoint_dir);
+    (checkpoint_dir, recovered)
+}
+
+/// Like `verify_recovered_state`, for a DB that may have had nothing committed yet.
+fn verify_recovered_state_option(
+    db: &AptosDB,
+    ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+    expected_state: &StateDelta,
+) {
+    match ledger_info_with_sigs {
+        Some(ledger_info_with_sigs) => {
+            verify_recovered_state(db, ledger_info_with_sigs, expected_state)
+        }
+        None => {
+            assert!(db.ledger_store.get_latest_ledger_info_option().is_none());
+            let buffered_state = db.state_store.buffered_state().lock();
+            assert_eq!(buffered_state.current_state().current_version, None);
+        }
+    }
+}
+
+fn verify_recovered_state(
+    db: &AptosDB,
+    ledger_info_with_sigs: &LedgerInfoWithSignatures,
+    expected_state: &StateDelta,
+) {
+    assert_eq!(
+        db.ledger_store.get_latest_ledger_info().unwrap(),
+        *ledger_info_with_sigs
+    );
+    let buffered_state = db.state_store.buffered_state().lock();
+    let state = buffered_state.current_state();
+    assert_eq!(state.current_version, expected_state.current_version);
+    assert_eq!(state.root_hash(), expected_state.root_hash());
+    // The snapshot at the latest checkpoint is committed while recovering.
+    assert_eq!(state.base_version, expected_state.base_version);
+    assert_eq!(state.base.root_hash(), expected_state.base.root_hash());
+    if let Some(checkpoint_version) = expected_state.base_version {
+        assert_eq!(
+            db.state_store
+                .get_state_snapshot_before(checkpoint_version + 1)
+                .unwrap(),
+            Some((checkpoint_version, expected_state.base.root_hash()))
+        );
+    }
+}
+
 fn verify_snapshots(
     db: &AptosDB,
     start_version: Version,
@@ -702,6 +1141,50 @@ pub fn verify_committed_transactions(
         ledger_info,
         is_latest,
     );
+    let events_by_version: Vec<_> = db
+        .get_events_by_version_range(first_version, txns_to_commit.len() as u64, ledger_version)
+        .unwrap()
+        .into_iter()
+        .map(|event| (event.transaction_version, event.event))
+        .collect();
+    let expected_events_by_version: Vec<_> = (first_version..)
+        .zip(txns_to_commit)
+        .flat_map(|(version, txn)| {
+            txn.events()
+                .iter()
+                .map(move |event| (version, event.clone()))
+        })
+        .collect();
+    assert_eq!(events_by_version, expected_events_by_version);
+    let events_with_context = db
+        .get_events_with_transaction_context(
+            first_version,
+            txns_to_commit.len() as u64,
+            ledger_version,
+        )
+        .unwrap();
+    let expected_events_with_context: Vec<_> = (first_version..)
+        .zip(txns_to_commit)
+        .flat_map(|(version, txn)| {
+            let transaction_hash = txn.transaction().hash();
+            let sender = txn
+                .transaction()
+                .as_signed_user_txn()
+                .ok()
+                .map(|txn| txn.sender());
+            let success = txn.status().is_success();
+            txn.events()
+                .iter()
+                .map(move |event| EventWithTransactionContext {
+                    transaction_version: version,
+                    transaction_hash,
+                    sender,
+                    success,
+                    event: event.clone(),
+                })
+        })
+        .collect();
+    assert_eq!(events_with_context, expected_events_with_context);
 
     // Fetch and verify batch transactions by account
     verify_account_txns(db, group_txns_by_account(txns_to_commit), ledger_info);
@@ -723,7 +1206,8 @@ pub fn put_as_state_root(db: &AptosDB, version: Version, key: StateKey, value: S
     let smt = SparseMerkleTree::<StateValue>::default()
         .batch_update(vec![(key.hash(), Some(&value))], &ProofReader::new_empty())
         .unwrap();
-    db.ledger_db
+    db.state_kv_db
+        .db_for_key(&key)
         .put::<StateValueSchema>(&(key.clone(), version), &Some(value.clone()))
         .unwrap();
     let mut in_memory_state = db
@@ -7,8 +7,11 @@ use crate::transaction_accumulator::TransactionAccumulatorSchema;
 use crate::{
     change_set::ChangeSet,
     errors::AptosDbError,
+    pruner::pruner_metadata::{PrunerMetadata, PrunerTag},
+    pruner_metadata::PrunerMetadataSchema,
     schema::{
-        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
+        auth_key_by_account::AuthKeyByAccountSchema, transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
         transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
     },
     transaction_info::TransactionInfoSchema,
@@ -17,12 +20,18 @@ use anyhow::{ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_types::{
     account_address::AccountAddress,
+    account_config::AccountResource,
     proof::position::Position,
+    state_store::state_key::StateKey,
     transaction::{Transaction, Version},
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
+use move_deps::move_core_types::move_resource::MoveResource;
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
-use std::sync::Arc;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
 
 #[derive(Clone, Debug)]
 pub struct TransactionStore {
@@ -53,6 +62,58 @@ impl TransactionStore {
         Ok(None)
     }
 
+    /// Gets the lowest sequence number among the transactions sent by `address` at or after
+    /// `version`, or `None` if the account sent none. Binary searches the sequence numbers of the
+    /// account, which map to strictly increasing versions.
+    pub fn get_account_sequence_number_at_or_after_version(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<Option<u64>> {
+        let mut iter = self
+            .db
+            .iter::<TransactionByAccountSchema>(ReadOptions::default())?;
+        iter.seek(&(address, 0))?;
+        let mut low = match iter.next().transpose()? {
+            Some(((account, seq_num), first_version)) if account == address => {
+                if first_version >= version {
+                    return Ok(Some(seq_num));
+                }
+                seq_num
+            }
+            _ => return Ok(None),
+        };
+        iter.seek_for_prev(&(address, u64::MAX))?;
+        let mut high = match iter.next().transpose()? {
+            Some(((account, seq_num), last_version))
+                if account == address && last_version >= version =>
+            {
+                seq_num
+            }
+            _ => return Ok(None),
+        };
+
+        // The transaction at `low` is before `version`, the one at `high` is at or after it.
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            let mid_version = self
+                .db
+                .get::<TransactionByAccountSchema>(&(address, mid))?
+                .ok_or_else(|| {
+                    AptosDbError::NotFound(format!(
+                        "Transaction of account {} at sequence number {}",
+                        address, mid
+                    ))
+                })?;
+            if mid_version < version {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(Some(high))
+    }
+
     /// Gets the version of a transaction by its hash.
     pub fn get_transaction_version_by_hash(
         &self,
@@ -101,6 +162,26 @@ impl TransactionStore {
             .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
     }
 
+    /// Get the `num_transactions` transactions starting from `start_version`, in a single multi
+    /// get.
+    pub fn get_transactions(
+        &self,
+        start_version: Version,
+        num_transactions: u64,
+    ) -> Result<Vec<Transaction>> {
+        let end_version = start_version
+            .checked_add(num_transactions)
+            .ok_or_else(|| format_err!("too many transactions requested"))?;
+        let versions = (start_version..end_version).collect::<Vec<_>>();
+        versions
+            .iter()
+            .zip(self.db.multi_get::<TransactionSchema>(&versions)?)
+            .map(|(version, txn)| {
+                txn.ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
+            })
+            .collect()
+    }
+
     /// Gets an iterator that yields `num_transactions` transactions starting from `start_version`.
     pub fn get_transaction_iter(
         &self,
@@ -254,6 +335,24 @@ impl TransactionStore {
         Ok(ret)
     }
 
+    /// Gets an iterator that yields the write sets of `num_transactions` transactions starting
+    /// from `start_version`.
+    pub fn get_write_set_iter(
+        &self,
+        start_version: Version,
+        num_transactions: usize,
+    ) -> Result<WriteSetIter> {
+        let mut iter = self.db.iter::<WriteSetSchema>(ReadOptions::default())?;
+        iter.seek(&start_version)?;
+        Ok(WriteSetIter {
+            inner: iter,
+            expected_next_version: start_version,
+            end_version: start_version
+                .checked_add(num_transactions as u64)
+                .ok_or_else(|| format_err!("too many write sets requested"))?,
+        })
+    }
+
     /// Save executed transaction vm output given `version`
     pub fn put_write_set(
         &self,
@@ -264,6 +363,152 @@ impl TransactionStore {
         cs.batch.put::<WriteSetSchema>(&version, write_set)
     }
 
+    /// Gets the authentication keys of `address` with the versions at which they were set, up to
+    /// `ledger_version`, oldest first. The first one was set when the account was created.
+    pub fn get_auth_key_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Vec<u8>)>> {
+        let mut iter = self
+            .db
+            .iter::<AuthKeyByAccountSchema>(ReadOptions::default())?;
+        iter.seek(&(address, 0))?;
+        let mut history = vec![];
+        for res in iter {
+            let ((account, version), auth_key) = res?;
+            if account != address || version > ledger_version {
+                break;
+            }
+            history.push((version, auth_key));
+        }
+        Ok(history)
+    }
+
+    /// Gets the latest authentication key of `address` in the index, if any.
+    fn get_latest_auth_key(&self, address: AccountAddress) -> Result<Option<Vec<u8>>> {
+        let mut iter = self
+            .db
+            .iter::<AuthKeyByAccountSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&(address, Version::MAX))?;
+        Ok(match iter.next().transpose()? {
+            Some(((account, _version), auth_key)) if account == address => Some(auth_key),
+            _ => None,
+        })
+    }
+
+    /// Indexes the authentication keys set by the write sets of the transactions from
+    /// `first_version`, i.e. the writes to `Account` resources which create the account or change
+    /// its key, and records the index as caught up to the last of them.
+    pub fn put_auth_keys<'a>(
+        &self,
+        first_version: Version,
+        write_sets: impl IntoIterator<Item = &'a WriteSet>,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let account_resource_path = AccountResource::resource_path();
+        // The keys set earlier in the batch, which aren't in the DB yet.
+        let mut latest_auth_keys = HashMap::new();
+        let mut latest_version = None;
+        for (version, write_set) in (first_version..).zip(write_sets) {
+            latest_version = Some(version);
+            for (state_key, op) in write_set {
+                let (address, bytes) = match (state_key, op) {
+                    (StateKey::AccessPath(access_path), WriteOp::Creation(bytes))
+                    | (StateKey::AccessPath(access_path), WriteOp::Modification(bytes))
+                        if access_path.path == account_resource_path =>
+                    {
+                        (access_path.address, bytes)
+                    }
+                    _ => continue,
+                };
+                let auth_key = bcs::from_bytes::<AccountResource>(bytes)?
+                    .authentication_key()
+                    .to_vec();
+                if !latest_auth_keys.contains_key(&address) {
+                    if let Some(latest_auth_key) = self.get_latest_auth_key(address)? {
+                        latest_auth_keys.insert(address, latest_auth_key);
+                    }
+                }
+                if latest_auth_keys.get(&address) != Some(&auth_key) {
+                    cs.batch
+                        .put::<AuthKeyByAccountSchema>(&(address, version), &auth_key)?;
+                    latest_auth_keys.insert(address, auth_key);
+                }
+            }
+        }
+        if let Some(latest_version) = latest_version {
+            cs.batch.put::<PrunerMetadataSchema>(
+                &PrunerTag::AuthKeyIndex,
+                &PrunerMetadata::LatestVersion(latest_version),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the version from which the authentication keys are yet to be indexed, or `None` if
+    /// the DB predates the index recording it.
+    pub fn get_auth_key_index_next_version(&self) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<PrunerMetadataSchema>(&PrunerTag::AuthKeyIndex)?
+            .map(|metadata| match metadata {
+                PrunerMetadata::LatestVersion(version) => version + 1,
+            }))
+    }
+
+    /// Returns whether any authentication key has been indexed, i.e. whether the index is kept.
+    pub fn has_auth_keys(&self) -> Result<bool> {
+        let mut iter = self
+            .db
+            .iter::<AuthKeyByAccountSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        Ok(iter.next().transpose()?.is_some())
+    }
+
+    /// Prune the authentication keys of the accounts written by the given write sets which have
+    /// been replaced by `end`. The key each account was created with is kept, as is the one it
+    /// has at `end`.
+    pub fn prune_auth_keys(
+        &self,
+        end: Version,
+        write_sets: &[WriteSet],
+        db_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        let account_resource_path = AccountResource::resource_path();
+        let addresses: BTreeSet<_> = write_sets
+            .iter()
+            .flat_map(|write_set| write_set.iter())
+            .filter_map(|(state_key, _op)| match state_key {
+                StateKey::AccessPath(access_path) if access_path.path == account_resource_path => {
+                    Some(access_path.address)
+                }
+                _ => None,
+            })
+            .collect();
+        for address in addresses {
+            let mut iter = self
+                .db
+                .iter::<AuthKeyByAccountSchema>(ReadOptions::default())?;
+            iter.seek(&(address, 0))?;
+            let mut versions = vec![];
+            for res in iter {
+                let ((account, version), _auth_key) = res?;
+                if account != address || version > end {
+                    break;
+                }
+                versions.push(version);
+            }
+            if versions.len() <= 2 {
+                continue;
+            }
+            for version in &versions[1..versions.len() - 1] {
+                db_batch.delete::<AuthKeyByAccountSchema>(&(address, *version))?;
+            }
+        }
+        Ok(())
+    }
+
     /// Prune the transaction by hash store given a list of transaction
     pub fn prune_transaction_by_hash(
         &self,
@@ -426,6 +671,42 @@ impl<'a> Iterator for TransactionIter<'a> {
     }
 }
 
+pub struct WriteSetIter<'a> {
+    inner: SchemaIterator<'a, WriteSetSchema>,
+    expected_next_version: Version,
+    end_version: Version,
+}
+
+impl<'a> WriteSetIter<'a> {
+    fn next_impl(&mut self) -> Result<Option<WriteSet>> {
+        if self.expected_next_version >= self.end_version {
+            return Ok(None);
+        }
+
+        let ret = match self.inner.next().transpose()? {
+            Some((version, write_set)) => {
+                ensure!(
+                    version == self.expected_next_version,
+                    "Write set versions are not consecutive.",
+                );
+                self.expected_next_version += 1;
+                Some(write_set)
+            }
+            None => None,
+        };
+
+        Ok(ret)
+    }
+}
+
+impl<'a> Iterator for WriteSetIter<'a> {
+    type Item = Result<WriteSet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_impl().transpose()
+    }
+}
+
 // TODO(philiphayes): this will need to change to support CRSNs
 // (Conflict-Resistant Sequence Numbers)[https://github.com/diem/dip/blob/main/dips/dip-168.md].
 //
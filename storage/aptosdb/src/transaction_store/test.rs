@@ -6,11 +6,14 @@ use crate::AptosDB;
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::{
+    access_path::AccessPath,
+    event::{EventHandle, EventKey},
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
     transaction::Transaction,
+    write_set::WriteSetMut,
 };
 use proptest::{collection::vec, prelude::*};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -114,6 +117,36 @@ proptest! {
         prop_assert!(store.get_transaction_iter(10, usize::max_value()).is_err());
     }
 
+    #[test]
+    fn test_get_write_set_iter(write_sets in vec(any::<WriteSet>(), 1..10)) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+
+        let mut cs = ChangeSet::new();
+        for (ver, ws) in write_sets.iter().enumerate() {
+            store.put_write_set(ver as Version, ws, &mut cs).unwrap();
+        }
+        store.db.write_schemas(cs.batch).unwrap();
+
+        let total_num_write_sets = write_sets.len();
+        let actual = store
+            .get_write_set_iter(0, total_num_write_sets + 1)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        prop_assert_eq!(&actual, &write_sets);
+
+        let actual = store
+            .get_write_set_iter(1, total_num_write_sets - 1)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        prop_assert_eq!(&actual[..], &write_sets[1..]);
+
+        prop_assert!(store.get_write_set_iter(10, usize::max_value()).is_err());
+    }
+
     #[test]
     fn test_get_account_transaction_version_iter(
         universe in any_with::<AccountInfoUniverse>(5),
@@ -196,6 +229,184 @@ proptest! {
 
         prop_assert_eq!(&actual_scan, &expected_scan);
     }
+
+    #[test]
+    fn test_get_account_sequence_number_at_or_after_version(
+        universe in any_with::<AccountInfoUniverse>(5),
+        gens in vec(
+            (any::<Index>(), any::<SignatureCheckedTransactionGen>()),
+            1..=50,
+        ),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        let txns = init_store(universe, gens, store);
+
+        let txns = txns
+            .iter()
+            .enumerate()
+            .map(|(version, txn)| (version as u64, txn.as_signed_user_txn().unwrap()))
+            .collect::<Vec<_>>();
+        let mut addresses = txns.iter().map(|(_, txn)| txn.sender()).collect::<BTreeSet<_>>();
+        // An account that sent nothing.
+        addresses.insert(AccountAddress::from_hex_literal("0x1234").unwrap());
+
+        for address in addresses {
+            for version in 0..=txns.len() as Version {
+                let expected = txns
+                    .iter()
+                    .find(|(txn_version, txn)| *txn_version >= version && txn.sender() == address)
+                    .map(|(_, txn)| txn.sequence_number());
+                prop_assert_eq!(
+                    store
+                        .get_account_sequence_number_at_or_after_version(address, version)
+                        .unwrap(),
+                    expected
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_put_auth_keys() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.transaction_store;
+    let alice = AccountAddress::random();
+    let bob = AccountAddress::random();
+    assert_eq!(store.get_auth_key_index_next_version().unwrap(), None);
+
+    let mut cs = ChangeSet::new();
+    store
+        .put_auth_keys(
+            0,
+            &[
+                write_set(vec![(alice, 0, vec![1], true), (bob, 0, vec![2], true)]),
+                // Bumping the sequence number doesn't change the key.
+                write_set(vec![(alice, 1, vec![1], false)]),
+                write_set(vec![(alice, 2, vec![3], false)]),
+            ],
+            &mut cs,
+        )
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+
+    // Keys set in an earlier batch are compared with those in the DB.
+    let mut cs = ChangeSet::new();
+    store
+        .put_auth_keys(
+            3,
+            &[
+                write_set(vec![(alice, 3, vec![3], false)]),
+                write_set(vec![(bob, 1, vec![4], false)]),
+            ],
+            &mut cs,
+        )
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+    assert_eq!(store.get_auth_key_index_next_version().unwrap(), Some(5));
+
+    assert_eq!(
+        store.get_auth_key_history(alice, 4).unwrap(),
+        vec![(0, vec![1]), (2, vec![3])]
+    );
+    assert_eq!(
+        store.get_auth_key_history(bob, 4).unwrap(),
+        vec![(0, vec![2]), (4, vec![4])]
+    );
+    assert_eq!(
+        store.get_auth_key_history(bob, 3).unwrap(),
+        vec![(0, vec![2])]
+    );
+    assert!(store
+        .get_auth_key_history(AccountAddress::random(), 4)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_prune_auth_keys() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.transaction_store;
+    let alice = AccountAddress::random();
+    let bob = AccountAddress::random();
+    assert!(!store.has_auth_keys().unwrap());
+
+    let write_sets = vec![
+        write_set(vec![(alice, 0, vec![1], true), (bob, 0, vec![2], true)]),
+        write_set(vec![(alice, 1, vec![3], false)]),
+        write_set(vec![(alice, 2, vec![4], false)]),
+        write_set(vec![(bob, 1, vec![5], false)]),
+        write_set(vec![(alice, 3, vec![6], false)]),
+    ];
+    let mut cs = ChangeSet::new();
+    store.put_auth_keys(0, &write_sets, &mut cs).unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+    assert!(store.has_auth_keys().unwrap());
+
+    // Keeps the key alice was created with and the key in effect at version 3.
+    let mut batch = SchemaBatch::new();
+    store
+        .prune_auth_keys(3, &write_sets[..3], &mut batch)
+        .unwrap();
+    store.db.write_schemas(batch).unwrap();
+    assert_eq!(
+        store.get_auth_key_history(alice, 4).unwrap(),
+        vec![(0, vec![1]), (2, vec![4]), (4, vec![6])]
+    );
+    assert_eq!(
+        store.get_auth_key_history(bob, 4).unwrap(),
+        vec![(0, vec![2]), (3, vec![5])]
+    );
+
+    // The key in effect at version 3 is replaced by version 5.
+    let mut batch = SchemaBatch::new();
+    store
+        .prune_auth_keys(5, &write_sets[3..], &mut batch)
+        .unwrap();
+    store.db.write_schemas(batch).unwrap();
+    assert_eq!(
+        store.get_auth_key_history(alice, 4).unwrap(),
+        vec![(0, vec![1]), (4, vec![6])]
+    );
+    assert_eq!(
+        store.get_auth_key_history(bob, 4).unwrap(),
+        vec![(0, vec![2]), (3, vec![5])]
+    );
+}
+
+/// Builds a write set writing the `Account` resources of the given accounts, each with its
+/// sequence number, authentication key and whether it is created.
+fn write_set(accounts: Vec<(AccountAddress, u64, Vec<u8>, bool)>) -> WriteSet {
+    WriteSetMut::new(
+        accounts
+            .into_iter()
+            .map(|(address, sequence_number, auth_key, created)| {
+                let bytes = bcs::to_bytes(&AccountResource::new(
+                    sequence_number,
+                    auth_key,
+                    EventHandle::new(EventKey::new(0, address), 0),
+                ))
+                .unwrap();
+                (
+                    StateKey::AccessPath(AccessPath::new(
+                        address,
+                        AccountResource::resource_path(),
+                    )),
+                    if created {
+                        WriteOp::Creation(bytes)
+                    } else {
+                        WriteOp::Modification(bytes)
+                    },
+                )
+            })
+            .collect(),
+    )
+    .freeze()
+    .unwrap()
 }
 
 fn init_store(
@@ -49,6 +49,7 @@ fn end_to_end() {
     let rt = start_backup_service(
         SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
         src_db,
+        false, /* enable_db_admin_endpoints */
     );
     let client = Arc::new(BackupServiceClient::new(format!(
         "http://localhost:{}",
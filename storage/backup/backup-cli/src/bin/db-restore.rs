@@ -10,7 +10,10 @@ use backup_cli::{
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
         transaction::restore::{TransactionRestoreController, TransactionRestoreOpt},
     },
-    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+    coordinators::{
+        restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+        verify_db::{VerifyDbCoordinator, VerifyDbOpt},
+    },
     storage::StorageOpt,
     utils::{GlobalRestoreOpt, GlobalRestoreOptions},
 };
@@ -52,6 +55,11 @@ enum RestoreType {
         #[structopt(subcommand)]
         storage: StorageOpt,
     },
+    /// Checks the DB at --target-db-dir against the proofs it holds, e.g. after restoring it.
+    Verify {
+        #[structopt(flatten)]
+        opt: VerifyDbOpt,
+    },
 }
 
 #[tokio::main]
@@ -100,6 +108,9 @@ async fn main_impl() -> Result<()> {
                 .run()
                 .await?;
         }
+        RestoreType::Verify { opt } => {
+            VerifyDbCoordinator::new(opt, global_opt)?.run().await?;
+        }
     }
 
     Ok(())
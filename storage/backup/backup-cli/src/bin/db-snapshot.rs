@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_config::config::{
+    NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE,
+};
+use aptos_logger::{prelude::*, Level, Logger};
+use aptosdb::{AptosDB, GetRestoreHandler};
+use backup_cli::{
+    portable_snapshot::{
+        export::{SnapshotExportOpt, SnapshotExporter},
+        import::{SnapshotImportOpt, SnapshotImporter},
+    },
+    utils::RocksdbOpt,
+};
+use std::{path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    about = "Exports the state at the end of an epoch from an AptosDB into portable, content \
+    addressed files, or imports such files into a fresh AptosDB."
+)]
+enum Command {
+    Export {
+        #[structopt(long = "db-dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        #[structopt(flatten)]
+        opt: SnapshotExportOpt,
+        #[structopt(flatten)]
+        rocksdb_opt: RocksdbOpt,
+    },
+    Import {
+        #[structopt(long = "target-db-dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        #[structopt(flatten)]
+        opt: SnapshotImportOpt,
+        #[structopt(flatten)]
+        rocksdb_opt: RocksdbOpt,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    main_impl().await.map_err(|e| {
+        error!("main_impl() failed: {}", e);
+        e
+    })
+}
+
+async fn main_impl() -> Result<()> {
+    Logger::new().level(Level::Info).read_env().init();
+
+    match Command::from_args() {
+        Command::Export {
+            db_dir,
+            opt,
+            rocksdb_opt,
+        } => {
+            let db = Arc::new(AptosDB::open(
+                db_dir,
+                true,                        /* read_only */
+                NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+                rocksdb_opt.into(),
+                false, /* indexer */
+                TARGET_SNAPSHOT_SIZE,
+                TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            )?);
+            SnapshotExporter::new(opt, db).run().await?;
+        }
+        Command::Import {
+            db_dir,
+            opt,
+            rocksdb_opt,
+        } => {
+            let restore_handler = Arc::new(AptosDB::open(
+                db_dir,
+                false,                       /* read_only */
+                NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+                rocksdb_opt.into(),
+                false, /* indexer */
+                TARGET_SNAPSHOT_SIZE,
+                TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
+            )?)
+            .get_restore_handler();
+            SnapshotImporter::new(opt, restore_handler).run().await?;
+        }
+    }
+
+    Ok(())
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use aptos_config::config::{NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE};
+use aptos_config::config::{
+    NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE,
+};
 use aptos_logger::{prelude::*, Level, Logger};
 use aptos_types::transaction::Version;
 use aptosdb::{AptosDB, GetRestoreHandler};
@@ -61,6 +63,7 @@ async fn main_impl() -> Result<()> {
         opt.rocksdb_opt.into(),
         false,
         TARGET_SNAPSHOT_SIZE,
+        TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
     )?)
     .get_restore_handler();
     ReplayVerifyCoordinator::new(
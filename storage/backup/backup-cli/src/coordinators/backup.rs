@@ -274,6 +274,16 @@ impl BackupCoordinator {
             .await?;
 
             last_transaction_version_in_backup = Some(last);
+
+            // Let the node's pruners go past the versions now in the backup. The backup itself
+            // succeeded, so failing to report it is not an error: it's reported again after the
+            // next batch.
+            if let Err(e) = self.client.record_backed_up_version(last).await {
+                warn!(
+                    "Failed reporting backed up version {} to local node: {}.",
+                    last, e
+                );
+            }
         }
     }
 
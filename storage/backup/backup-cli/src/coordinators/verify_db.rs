@@ -0,0 +1,359 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics::verify::{VERIFY_DB_EPOCH, VERIFY_DB_TRANSACTION_VERSION},
+    utils::{GlobalRestoreOptions, RestoreRunMode},
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionInfo, Version},
+    waypoint::Waypoint,
+};
+use aptosdb::backup::restore_handler::RestoreHandler;
+use std::{cmp::min, collections::HashMap, sync::Arc};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct VerifyDbOpt {
+    #[structopt(
+        long = "start-version",
+        default_value = "0",
+        help = "Transactions before this version are not verified. Set it to the first version \
+        restored when the DB was restored from a state snapshot rather than from genesis."
+    )]
+    pub start_version: Version,
+
+    #[structopt(
+        long = "verify-batch-size",
+        default_value = "1000",
+        help = "Number of transactions read from the DB and verified together, at most 5000."
+    )]
+    pub batch_size: u64,
+}
+
+/// Walks a restored DB and checks it against the proofs it holds: the epoch ending LedgerInfos
+/// form a chain each one signed by the validators of the previous epoch, the transactions and
+/// their infos hash to the accumulator roots in those LedgerInfos, and the state checkpoint hashes
+/// in the transaction infos match the roots of the state tree. Stops at the first divergence.
+pub struct VerifyDbCoordinator {
+    restore_handler: RestoreHandler,
+    trusted_waypoints: Arc<HashMap<Version, Waypoint>>,
+    start_version: Version,
+    batch_size: u64,
+}
+
+impl VerifyDbCoordinator {
+    pub fn new(opt: VerifyDbOpt, global_opt: GlobalRestoreOptions) -> Result<Self> {
+        let restore_handler = match global_opt.run_mode.as_ref() {
+            RestoreRunMode::Restore { restore_handler } => restore_handler.clone(),
+            RestoreRunMode::Verify => bail!("--target-db-dir is required to verify a restored DB."),
+        };
+        ensure!(opt.batch_size > 0, "Batch size must be positive.");
+        Ok(Self {
+            restore_handler,
+            trusted_waypoints: global_opt.trusted_waypoints,
+            start_version: opt.start_version,
+            batch_size: opt.batch_size,
+        })
+    }
+
+    pub async fn run(self) -> Result<()> {
+        info!("Verify DB coordinator started.");
+
+        let ret = self.run_impl();
+
+        if let Err(e) = &ret {
+            error!(
+                error = ?e,
+                "Verify DB coordinator failed."
+            );
+        } else {
+            info!("Verify DB coordinator exiting with success.");
+        }
+
+        ret
+    }
+
+    fn run_impl(&self) -> Result<()> {
+        let db = &self.restore_handler.aptosdb;
+        let latest_li = db
+            .get_latest_ledger_info_option()?
+            .ok_or_else(|| anyhow!("DB holds no LedgerInfo."))?;
+
+        let epoch_endings = self.verify_epoch_endings(&latest_li)?;
+        self.verify_ledger_info(&latest_li, &epoch_endings)?;
+
+        // Every transaction is verified against the first LedgerInfo at or after it.
+        let mut anchors: Vec<&LedgerInfoWithSignatures> = epoch_endings.iter().collect();
+        if anchors.last().map_or(true, |li| {
+            li.ledger_info().version() < latest_li.ledger_info().version()
+        }) {
+            anchors.push(&latest_li);
+        }
+
+        let mut version = self.start_version;
+        for anchor in anchors {
+            let anchor_version = anchor.ledger_info().version();
+            if anchor_version < version {
+                continue;
+            }
+            self.verify_accumulator_root_hash(anchor)?;
+            while version <= anchor_version {
+                let limit = min(self.batch_size, anchor_version - version + 1);
+                self.verify_transactions(version, limit, anchor)?;
+                version += limit;
+                VERIFY_DB_TRANSACTION_VERSION.set(version as i64 - 1);
+            }
+        }
+
+        if let Some((latest_version, _)) = db.get_latest_transaction_info_option()? {
+            if latest_version >= version {
+                warn!(
+                    "Transactions in [{}, {}] are beyond the latest LedgerInfo and are not verified.",
+                    version, latest_version,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads all the epoch ending LedgerInfos and checks that each one is signed by the
+    /// validators named in the previous one, or matches a trusted waypoint.
+    fn verify_epoch_endings(
+        &self,
+        latest_li: &LedgerInfoWithSignatures,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        let db = &self.restore_handler.aptosdb;
+        let end_epoch = latest_li.ledger_info().next_block_epoch();
+
+        let mut epoch_endings: Vec<LedgerInfoWithSignatures> = Vec::new();
+        while (epoch_endings.len() as u64) < end_epoch {
+            let proof = db.get_epoch_ending_ledger_infos(epoch_endings.len() as u64, end_epoch)?;
+            ensure!(
+                !proof.ledger_info_with_sigs.is_empty(),
+                "Epoch ending LedgerInfo of epoch {} not found.",
+                epoch_endings.len(),
+            );
+            for li in proof.ledger_info_with_sigs {
+                let epoch = epoch_endings.len() as u64;
+                ensure!(
+                    li.ledger_info().epoch() == epoch,
+                    "First divergence at epoch {}: epoch ending LedgerInfo is of epoch {}.",
+                    epoch,
+                    li.ledger_info().epoch(),
+                );
+                ensure!(
+                    li.ledger_info().ends_epoch(),
+                    "First divergence at epoch {}: LedgerInfo at version {} does not end the epoch.",
+                    epoch,
+                    li.ledger_info().version(),
+                );
+                self.verify_ledger_info(&li, &epoch_endings)?;
+                VERIFY_DB_EPOCH.set(epoch as i64);
+                epoch_endings.push(li);
+            }
+        }
+
+        Ok(epoch_endings)
+    }
+
+    fn verify_ledger_info(
+        &self,
+        li: &LedgerInfoWithSignatures,
+        epoch_endings: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        let epoch = li.ledger_info().epoch();
+        let version = li.ledger_info().version();
+        if let Some(waypoint) = self.trusted_waypoints.get(&version) {
+            return waypoint.verify(li.ledger_info()).map_err(|e| {
+                anyhow!(
+                    "First divergence at version {}: LedgerInfo does not match trusted waypoint. {}",
+                    version,
+                    e,
+                )
+            });
+        }
+
+        if epoch == 0 {
+            warn!(
+                "No trusted waypoint at version {}, LedgerInfo of epoch 0 is not verified.",
+                version,
+            );
+            return Ok(());
+        }
+        let prev_li = epoch_endings.get(epoch as usize - 1).ok_or_else(|| {
+            anyhow!(
+                "Epoch ending LedgerInfo of epoch {} not found to verify LedgerInfo at version {}.",
+                epoch - 1,
+                version,
+            )
+        })?;
+        prev_li
+            .ledger_info()
+            .next_epoch_state()
+            .ok_or_else(|| {
+                anyhow!(
+                    "LedgerInfo at version {} does not carry the next epoch state.",
+                    prev_li.ledger_info().version(),
+                )
+            })?
+            .verify(li)
+            .map_err(|e| {
+                anyhow!(
+                    "First divergence at version {}: LedgerInfo signatures do not verify. {}",
+                    version,
+                    e,
+                )
+            })
+    }
+
+    fn verify_accumulator_root_hash(&self, li: &LedgerInfoWithSignatures) -> Result<()> {
+        let version = li.ledger_info().version();
+        let root_hash = self
+            .restore_handler
+            .aptosdb
+            .get_accumulator_root_hash(version)?;
+        ensure!(
+            root_hash == li.ledger_info().transaction_accumulator_hash(),
+            "First divergence at version {}: transaction accumulator root hash mismatch. \
+            In DB: {}, in LedgerInfo: {}",
+            version,
+            root_hash,
+            li.ledger_info().transaction_accumulator_hash(),
+        );
+        Ok(())
+    }
+
+    fn verify_transactions(
+        &self,
+        first_version: Version,
+        limit: u64,
+        anchor: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        let db = &self.restore_handler.aptosdb;
+        let anchor_version = anchor.ledger_info().version();
+        let verified = db
+            .get_transactions(
+                first_version,
+                limit,
+                anchor_version,
+                true, /* fetch_events */
+            )
+            .and_then(|txn_list| {
+                txn_list.verify(anchor.ledger_info(), Some(first_version))?;
+                Ok(txn_list)
+            });
+        let txn_list = match verified {
+            Ok(txn_list) => txn_list,
+            Err(e) => {
+                // Narrows the failure down to a single transaction.
+                for version in first_version..first_version + limit {
+                    db.get_transactions(version, 1, anchor_version, true /* fetch_events */)
+                        .and_then(|txn_list| txn_list.verify(anchor.ledger_info(), Some(version)))
+                        .map_err(|e| anyhow!("First divergence at version {}: {}", version, e))?;
+                }
+                bail!(
+                    "First divergence in versions [{}, {}]: {}",
+                    first_version,
+                    first_version + limit - 1,
+                    e,
+                );
+            }
+        };
+
+        for (version, txn_info) in (first_version..).zip(txn_list.proof.transaction_infos.iter()) {
+            self.verify_state_root_hash(version, txn_info)?;
+        }
+        Ok(())
+    }
+
+    /// The state tree only has a root at versions where a snapshot was committed, so other
+    /// checkpoints are covered by the transaction info hashes alone.
+    fn verify_state_root_hash(&self, version: Version, txn_info: &TransactionInfo) -> Result<()> {
+        if let Some(state_checkpoint_hash) = txn_info.state_checkpoint_hash() {
+            if let Some(root_hash) = self.restore_handler.get_state_root_hash_option(version)? {
+                ensure!(
+                    root_hash == state_checkpoint_hash,
+                    "First divergence at version {}: state root hash mismatch. \
+                    In state tree: {}, in transaction info: {}",
+                    version,
+                    root_hash,
+                    state_checkpoint_hash,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        coordinators::verify_db::{VerifyDbCoordinator, VerifyDbOpt},
+        utils::{GlobalRestoreOptions, RestoreRunMode},
+    };
+    use aptos_types::waypoint::Waypoint;
+    use aptosdb::AptosDB;
+    use executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+    use std::{collections::HashMap, sync::Arc};
+    use storage_interface::DbReader;
+    use tokio::runtime::Runtime;
+
+    fn verify_db(
+        db: &Arc<AptosDB>,
+        trusted_waypoints: HashMap<u64, Waypoint>,
+    ) -> anyhow::Result<()> {
+        let global_opt = GlobalRestoreOptions {
+            target_version: u64::max_value(),
+            trusted_waypoints: Arc::new(trusted_waypoints),
+            run_mode: Arc::new(RestoreRunMode::Restore {
+                restore_handler: db.get_restore_handler(),
+            }),
+            concurrent_downloads: 1,
+        };
+        let opt = VerifyDbOpt {
+            start_version: 0,
+            batch_size: 3,
+        };
+        Runtime::new()
+            .unwrap()
+            .block_on(VerifyDbCoordinator::new(opt, global_opt)?.run())
+    }
+
+    #[test]
+    fn test_verify_db() {
+        let db = test_execution_with_storage_impl();
+        let genesis_li = db
+            .get_epoch_ending_ledger_infos(0, 1)
+            .unwrap()
+            .ledger_info_with_sigs
+            .pop()
+            .unwrap();
+        let genesis_waypoint = Waypoint::new_epoch_boundary(genesis_li.ledger_info()).unwrap();
+
+        verify_db(&db, HashMap::from([(0, genesis_waypoint)])).unwrap();
+    }
+
+    #[test]
+    fn test_verify_db_reports_untrusted_genesis() {
+        let db = test_execution_with_storage_impl();
+        let epoch_1_li = db
+            .get_epoch_ending_ledger_infos(1, 2)
+            .unwrap()
+            .ledger_info_with_sigs
+            .pop()
+            .unwrap();
+        // A waypoint at the genesis version that is not of the genesis LedgerInfo.
+        let waypoint = Waypoint::new_epoch_boundary(epoch_1_li.ledger_info()).unwrap();
+
+        let err = verify_db(&db, HashMap::from([(0, waypoint)])).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("First divergence at version 0:"));
+    }
+}
@@ -7,5 +7,6 @@ pub mod backup_types;
 pub mod coordinators;
 pub mod metadata;
 pub mod metrics;
+pub mod portable_snapshot;
 pub mod storage;
 pub mod utils;
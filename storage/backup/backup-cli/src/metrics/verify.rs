@@ -75,3 +75,19 @@ pub static VERIFY_COORDINATOR_FAIL_TS: Lazy<IntGauge> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static VERIFY_DB_EPOCH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_db_backup_verify_db_epoch",
+        "Epoch of the epoch ending LedgerInfo in the restored DB being verified."
+    )
+    .unwrap()
+});
+
+pub static VERIFY_DB_TRANSACTION_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_db_backup_verify_db_transaction_version",
+        "Version of the transaction in the restored DB being verified."
+    )
+    .unwrap()
+});
@@ -0,0 +1,186 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    portable_snapshot::manifest::{
+        content_file_name, PortableSnapshotManifest, SnapshotChunk, CHUNK_FILE_EXTENSION,
+        MANIFEST_FILE_NAME, PROOF_FILE_EXTENSION,
+    },
+    utils::path_exists,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use std::{cmp::min, path::PathBuf, sync::Arc};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct SnapshotExportOpt {
+    #[structopt(
+        long = "output-dir",
+        parse(from_os_str),
+        help = "Directory to write the snapshot to. Files already in it from an earlier, \
+        interrupted export of the same snapshot are reused."
+    )]
+    pub output_dir: PathBuf,
+
+    #[structopt(
+        long,
+        help = "Epoch at the end of which the state is exported, defaulting to the latest \
+        finished epoch."
+    )]
+    pub epoch: Option<u64>,
+
+    #[structopt(
+        long = "chunk-size",
+        default_value = "10000",
+        help = "Maximum number of state values in a chunk file."
+    )]
+    pub chunk_size: usize,
+}
+
+pub struct SnapshotExporter {
+    db: Arc<dyn DbReader>,
+    output_dir: PathBuf,
+    epoch: Option<u64>,
+    chunk_size: usize,
+}
+
+impl SnapshotExporter {
+    pub fn new(opt: SnapshotExportOpt, db: Arc<dyn DbReader>) -> Self {
+        Self {
+            db,
+            output_dir: opt.output_dir,
+            epoch: opt.epoch,
+            chunk_size: opt.chunk_size,
+        }
+    }
+
+    pub async fn run(self) -> Result<PortableSnapshotManifest> {
+        info!(
+            "Portable state snapshot export started, into {:?}.",
+            self.output_dir
+        );
+        let manifest = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Portable state snapshot export failed: {}", e))?;
+        info!(
+            "Portable state snapshot export succeeded. Epoch: {}, version: {}, chunks: {}",
+            manifest.epoch,
+            manifest.version,
+            manifest.chunks.len(),
+        );
+        Ok(manifest)
+    }
+
+    async fn run_impl(self) -> Result<PortableSnapshotManifest> {
+        ensure!(self.chunk_size > 0, "Chunk size must be positive.");
+        tokio::fs::create_dir_all(&self.output_dir).await?;
+
+        let li = self.get_epoch_ending_ledger_info()?;
+        let version = li.ledger_info().version();
+        let output_with_proof = self.db.get_transaction_outputs(version, 1, version)?;
+        let root_hash = output_with_proof
+            .proof
+            .transaction_infos
+            .first()
+            .ok_or_else(|| anyhow!("Transaction info at version {} not found.", version))?
+            .ensure_state_checkpoint_hash()?;
+
+        let num_values = self.db.get_state_leaf_count(version)?;
+        ensure!(num_values > 0, "State at version {} is empty.", version);
+
+        let mut chunks = vec![];
+        let mut next_idx = 0;
+        while next_idx < num_values {
+            let chunk_size = min(self.chunk_size, num_values - next_idx);
+            let chunk = self
+                .db
+                .get_state_value_chunk_with_proof(version, next_idx, chunk_size)?;
+            ensure!(
+                chunk.root_hash == root_hash,
+                "Root hash mismatch for chunk at index {}. root hash: {}, expected: {}",
+                next_idx,
+                chunk.root_hash,
+                root_hash,
+            );
+            let hash = self
+                .write_content_file(
+                    &bcs::to_bytes(&(&chunk.raw_values, &chunk.proof))?,
+                    CHUNK_FILE_EXTENSION,
+                )
+                .await?;
+            chunks.push(SnapshotChunk {
+                first_idx: chunk.first_index,
+                last_idx: chunk.last_index,
+                first_key: chunk.first_key,
+                last_key: chunk.last_key,
+                hash,
+            });
+            next_idx += chunk_size;
+        }
+
+        let proof = self
+            .write_content_file(
+                &bcs::to_bytes(&(&output_with_proof, &li))?,
+                PROOF_FILE_EXTENSION,
+            )
+            .await?;
+
+        let manifest = PortableSnapshotManifest {
+            version,
+            epoch: li.ledger_info().epoch(),
+            root_hash,
+            chunks,
+            proof,
+        };
+        // The manifest is written last, so that its presence means the export is complete.
+        self.write_file(MANIFEST_FILE_NAME, &serde_json::to_vec(&manifest)?)
+            .await?;
+        Ok(manifest)
+    }
+
+    fn get_epoch_ending_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        let epoch = match self.epoch {
+            Some(epoch) => epoch,
+            None => self
+                .db
+                .get_latest_ledger_info()?
+                .ledger_info()
+                .next_block_epoch()
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("No epoch has finished yet."))?,
+        };
+        self.db
+            .get_epoch_ending_ledger_infos(epoch, epoch + 1)?
+            .ledger_info_with_sigs
+            .pop()
+            .ok_or_else(|| anyhow!("Epoch ending ledger info of epoch {} not found.", epoch))
+    }
+
+    /// Writes content to a file named by its hash and returns the hash. Files that already exist
+    /// are left alone, as their name guarantees they hold the same content.
+    async fn write_content_file(&self, bytes: &[u8], extension: &str) -> Result<HashValue> {
+        let hash = HashValue::sha3_256_of(bytes);
+        let name = content_file_name(&hash, extension);
+        if path_exists(&self.output_dir.join(&name)).await {
+            debug!("{} exists, skipping.", name);
+        } else {
+            self.write_file(&name, bytes).await?;
+        }
+        Ok(hash)
+    }
+
+    /// Writes a file through a temporary one, so that an interrupted export never leaves a
+    /// partially written file behind under its final name.
+    async fn write_file(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.output_dir.join(name);
+        let tmp_path = self.output_dir.join(format!("{}.tmp", name));
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::portable_snapshot::manifest::{
+    content_file_name, PortableSnapshotManifest, CHUNK_FILE_EXTENSION, MANIFEST_FILE_NAME,
+    PROOF_FILE_EXTENSION,
+};
+use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::SparseMerkleRangeProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::TransactionOutputListWithProof,
+    waypoint::Waypoint,
+};
+use aptosdb::backup::restore_handler::RestoreHandler;
+use std::path::PathBuf;
+use storage_interface::{DbWriter, StateSnapshotReceiver};
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+pub struct SnapshotImportOpt {
+    #[structopt(
+        long = "input-dir",
+        parse(from_os_str),
+        help = "Directory holding the snapshot, as written by the export."
+    )]
+    pub input_dir: PathBuf,
+
+    #[structopt(
+        long,
+        help = "Waypoint of the epoch ending LedgerInfo the snapshot is proven against. \
+        Without it the snapshot is only checked to be consistent with the LedgerInfo it comes \
+        with, whose signatures are NOT checked."
+    )]
+    pub waypoint: Option<Waypoint>,
+}
+
+pub struct SnapshotImporter {
+    restore_handler: RestoreHandler,
+    input_dir: PathBuf,
+    waypoint: Option<Waypoint>,
+}
+
+impl SnapshotImporter {
+    pub fn new(opt: SnapshotImportOpt, restore_handler: RestoreHandler) -> Self {
+        Self {
+            restore_handler,
+            input_dir: opt.input_dir,
+            waypoint: opt.waypoint,
+        }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        info!(
+            "Portable state snapshot import started, from {:?}.",
+            self.input_dir
+        );
+        self.run_impl()
+            .await
+            .map_err(|e| anyhow!("Portable state snapshot import failed: {}", e))?;
+        info!("Portable state snapshot import succeeded.");
+        Ok(())
+    }
+
+    async fn run_impl(self) -> Result<()> {
+        let manifest: PortableSnapshotManifest = serde_json::from_slice(
+            &tokio::fs::read(self.input_dir.join(MANIFEST_FILE_NAME)).await?,
+        )?;
+
+        let (output_with_proof, li): (TransactionOutputListWithProof, LedgerInfoWithSignatures) =
+            bcs::from_bytes(
+                &self
+                    .read_content_file(&manifest.proof, PROOF_FILE_EXTENSION)
+                    .await?,
+            )?;
+        ensure!(
+            li.ledger_info().ends_epoch() && li.ledger_info().epoch() == manifest.epoch,
+            "LedgerInfo in proof is not the epoch ending one of epoch {}.",
+            manifest.epoch,
+        );
+        match &self.waypoint {
+            Some(waypoint) => waypoint.verify(li.ledger_info())?,
+            None => warn!(
+                "No waypoint given, the LedgerInfo at version {} is not verified.",
+                li.ledger_info().version(),
+            ),
+        }
+        output_with_proof.verify(li.ledger_info(), Some(manifest.version))?;
+        let state_root_hash = output_with_proof
+            .proof
+            .transaction_infos
+            .first()
+            .ok_or_else(|| anyhow!("Transaction info missing in proof."))?
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            state_root_hash == manifest.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            manifest.root_hash,
+            state_root_hash,
+        );
+
+        // Picks up from the rightmost state value in the DB if an earlier import was interrupted,
+        // chunks up to it are still verified but not written again.
+        let mut receiver = self
+            .restore_handler
+            .get_resumable_state_restore_receiver(manifest.version, manifest.root_hash)?;
+        let mut next_idx = 0;
+        for chunk in &manifest.chunks {
+            ensure!(
+                chunk.first_idx == next_idx && chunk.last_idx >= chunk.first_idx,
+                "Chunk [{}, {}] does not continue from index {}.",
+                chunk.first_idx,
+                chunk.last_idx,
+                next_idx,
+            );
+            let (values, proof): (Vec<(StateKey, StateValue)>, SparseMerkleRangeProof) =
+                bcs::from_bytes(
+                    &self
+                        .read_content_file(&chunk.hash, CHUNK_FILE_EXTENSION)
+                        .await?,
+                )?;
+            ensure!(
+                values.len() as u64 == chunk.last_idx - chunk.first_idx + 1,
+                "Chunk {} holds {} state values, expected {}.",
+                chunk.hash,
+                values.len(),
+                chunk.last_idx - chunk.first_idx + 1,
+            );
+            receiver.add_chunk(values, proof)?;
+            next_idx = chunk.last_idx + 1;
+        }
+        receiver.finish()?;
+
+        self.restore_handler.aptosdb.finalize_state_snapshot(
+            manifest.version,
+            output_with_proof,
+            &[li],
+        )?;
+        Ok(())
+    }
+
+    /// Reads a content addressed file, making sure it holds what its hash says it does.
+    async fn read_content_file(&self, hash: &HashValue, extension: &str) -> Result<Vec<u8>> {
+        let name = content_file_name(hash, extension);
+        let bytes = tokio::fs::read(self.input_dir.join(&name)).await?;
+        let actual_hash = HashValue::sha3_256_of(&bytes);
+        ensure!(
+            actual_hash == *hash,
+            "File {} is corrupted, its content hashes to {}.",
+            name,
+            actual_hash,
+        );
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use serde::{Deserialize, Serialize};
+
+/// Name of the manifest file in a snapshot directory. It is the only file in the directory that
+/// is not content addressed.
+pub const MANIFEST_FILE_NAME: &str = "snapshot.manifest";
+
+pub const CHUNK_FILE_EXTENSION: &str = "chunk";
+pub const PROOF_FILE_EXTENSION: &str = "proof";
+
+/// Name of the file holding content with the given hash.
+pub fn content_file_name(hash: &HashValue, extension: &str) -> String {
+    format!("{}.{}", hash.to_hex(), extension)
+}
+
+/// A chunk of a portable state snapshot, holding the state values in the key range
+/// [`first_key`, `last_key`] (right side inclusive).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SnapshotChunk {
+    /// index of the first state value in this chunk over all state values.
+    pub first_idx: u64,
+    /// index of the last state value in this chunk over all state values.
+    pub last_idx: u64,
+    /// hashed key of the first state value in this chunk.
+    pub first_key: HashValue,
+    /// hashed key of the last state value in this chunk.
+    pub last_key: HashValue,
+    /// SHA3-256 hash of the chunk file, which also names it. The file holds the BCS serialized
+    /// tuple `(Vec<(StateKey, StateValue)>, SparseMerkleRangeProof)`, where the proof shows the
+    /// chunk adds up to `PortableSnapshotManifest::root_hash`.
+    pub hash: HashValue,
+}
+
+/// Manifest of a portable state snapshot, representing the complete state at the end of an epoch.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PortableSnapshotManifest {
+    /// Version at which this state snapshot is taken, i.e. the last version of `epoch`.
+    pub version: Version,
+    /// Epoch at the end of which this state snapshot is taken.
+    pub epoch: u64,
+    /// Hash of the state tree root.
+    pub root_hash: HashValue,
+    /// All state values in chunks, in key order.
+    pub chunks: Vec<SnapshotChunk>,
+    /// SHA3-256 hash of the proof file, which also names it. The file holds the BCS serialized
+    /// tuple `(TransactionOutputListWithProof, LedgerInfoWithSignatures)`.
+    ///   - The `TransactionOutputListWithProof` holds the single transaction at `version`, whose
+    /// info carries `root_hash` above. It is needed to finalize the import the same way state sync
+    /// finalizes a state snapshot.
+    ///   - The `LedgerInfoWithSignatures` is the epoch ending ledger info of `epoch`, which the
+    /// above is proven against. It can be checked against a trusted waypoint.
+    pub proof: HashValue,
+}
@@ -0,0 +1,13 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Portable state snapshots are exported directly from an AptosDB into a directory of content
+//! addressed files, which can be served by any static file host (e.g. a CDN) and imported into a
+//! fresh AptosDB to bootstrap a node, without a backup service or backup storage.
+
+pub mod export;
+pub mod import;
+pub mod manifest;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,162 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    portable_snapshot::{
+        export::{SnapshotExportOpt, SnapshotExporter},
+        import::{SnapshotImportOpt, SnapshotImporter},
+        manifest::{content_file_name, PortableSnapshotManifest, CHUNK_FILE_EXTENSION},
+    },
+    utils::test_utils::{tmp_db_empty, tmp_db_with_random_content},
+};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    proof::SparseMerkleRangeProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    waypoint::Waypoint,
+};
+use aptosdb::{AptosDB, GetRestoreHandler};
+use std::sync::Arc;
+use storage_interface::{DbReader, StateSnapshotReceiver};
+use tokio::runtime::Runtime;
+
+fn export(rt: &Runtime, db: Arc<AptosDB>, dir: &TempPath) -> PortableSnapshotManifest {
+    rt.block_on(
+        SnapshotExporter::new(
+            SnapshotExportOpt {
+                output_dir: dir.path().to_path_buf(),
+                epoch: None,
+                chunk_size: 5,
+            },
+            db,
+        )
+        .run(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn end_to_end() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
+    let snapshot_dir = TempPath::new();
+    let rt = Runtime::new().unwrap();
+
+    let manifest = export(&rt, Arc::clone(&src_db), &snapshot_dir);
+    // Exporting into the same directory again reuses the files already there.
+    assert_eq!(export(&rt, Arc::clone(&src_db), &snapshot_dir), manifest);
+
+    let li = src_db
+        .get_epoch_ending_ledger_infos(manifest.epoch, manifest.epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap();
+    rt.block_on(
+        SnapshotImporter::new(
+            SnapshotImportOpt {
+                input_dir: snapshot_dir.path().to_path_buf(),
+                waypoint: Some(Waypoint::new_epoch_boundary(li.ledger_info()).unwrap()),
+            },
+            tgt_db.get_restore_handler(),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(manifest.version + 1)
+            .unwrap()
+            .unwrap(),
+        (manifest.version, manifest.root_hash)
+    );
+    assert_eq!(tgt_db.get_latest_version().unwrap(), manifest.version);
+}
+
+#[test]
+fn import_rejects_corrupted_chunk() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
+    let snapshot_dir = TempPath::new();
+    let rt = Runtime::new().unwrap();
+
+    let manifest = export(&rt, src_db, &snapshot_dir);
+    let chunk_path = snapshot_dir.path().join(content_file_name(
+        &manifest.chunks.last().unwrap().hash,
+        CHUNK_FILE_EXTENSION,
+    ));
+    std::fs::write(chunk_path, b"corrupted").unwrap();
+
+    let err = rt
+        .block_on(
+            SnapshotImporter::new(
+                SnapshotImportOpt {
+                    input_dir: snapshot_dir.path().to_path_buf(),
+                    waypoint: None,
+                },
+                tgt_db.get_restore_handler(),
+            )
+            .run(),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("is corrupted"));
+}
+
+#[test]
+fn import_resumes_after_interruption() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let (_tgt_db_dir, tgt_db) = tmp_db_empty();
+    let snapshot_dir = TempPath::new();
+    let rt = Runtime::new().unwrap();
+
+    let manifest = export(&rt, Arc::clone(&src_db), &snapshot_dir);
+    assert!(manifest.chunks.len() > 1);
+
+    // Simulates an import that died after writing the first half of the chunks.
+    let mut receiver = tgt_db
+        .get_restore_handler()
+        .get_resumable_state_restore_receiver(manifest.version, manifest.root_hash)
+        .unwrap();
+    for chunk in &manifest.chunks[..manifest.chunks.len() / 2] {
+        let (values, proof): (Vec<(StateKey, StateValue)>, SparseMerkleRangeProof) =
+            bcs::from_bytes(
+                &std::fs::read(
+                    snapshot_dir
+                        .path()
+                        .join(content_file_name(&chunk.hash, CHUNK_FILE_EXTENSION)),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        receiver.add_chunk(values, proof).unwrap();
+    }
+    drop(receiver);
+
+    let li = src_db
+        .get_epoch_ending_ledger_infos(manifest.epoch, manifest.epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap();
+    rt.block_on(
+        SnapshotImporter::new(
+            SnapshotImportOpt {
+                input_dir: snapshot_dir.path().to_path_buf(),
+                waypoint: Some(Waypoint::new_epoch_boundary(li.ledger_info()).unwrap()),
+            },
+            tgt_db.get_restore_handler(),
+        )
+        .run(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(manifest.version + 1)
+            .unwrap()
+            .unwrap(),
+        (manifest.version, manifest.root_hash)
+    );
+    assert_eq!(tgt_db.get_latest_version().unwrap(), manifest.version);
+}
@@ -57,6 +57,18 @@ impl BackupServiceClient {
             .compat())
     }
 
+    async fn post(&self, path: &str) -> Result<()> {
+        let url = format!("{}/{}", self.address, path);
+        self.client
+            .post(&url)
+            .send()
+            .await
+            .err_notes(&url)?
+            .error_for_status()
+            .err_notes(&url)?;
+        Ok(())
+    }
+
     pub async fn get_db_state(&self) -> Result<Option<DbState>> {
         let mut buf = Vec::new();
         self.get("db_state").await?.read_to_end(&mut buf).await?;
@@ -120,4 +132,10 @@ impl BackupServiceClient {
         ))
         .await
     }
+
+    /// Reports to the node that all versions up to `version` are backed up, so that its pruners
+    /// may prune them.
+    pub async fn record_backed_up_version(&self, version: Version) -> Result<()> {
+        self.post(&format!("backed_up_version/{}", version)).await
+    }
 }
@@ -12,12 +12,14 @@ pub mod test_utils;
 
 use anyhow::{anyhow, Result};
 use aptos_config::config::{
-    RocksdbConfig, RocksdbConfigs, NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_SIZE,
+    RocksdbCfConfig, RocksdbConfig, RocksdbConfigs, StateKvShardingConfig,
+    NO_OP_STORAGE_PRUNER_CONFIG, TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS, TARGET_SNAPSHOT_SIZE,
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::duration_since_epoch;
 use aptos_jellyfish_merkle::{
-    restore::StateSnapshotRestore, NodeBatch, StateValueBatch, StateValueWriter, TreeWriter,
+    restore::StateSnapshotRestore, NodeBatch, StateSnapshotProgress, StateValueBatch,
+    StateValueWriter, TreeWriter,
 };
 use aptos_types::{
     state_store::{state_key::StateKey, state_value::StateValue},
@@ -26,7 +28,7 @@ use aptos_types::{
 };
 use aptosdb::{backup::restore_handler::RestoreHandler, AptosDB, GetRestoreHandler};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryFrom,
     mem::size_of,
     path::{Path, PathBuf},
@@ -56,6 +58,14 @@ pub struct RocksdbOpt {
     state_merkle_db_max_open_files: i32,
     #[structopt(long, default_value = "1073741824")] // 1GB
     state_merkle_db_max_total_wal_size: u64,
+    #[structopt(long, default_value = "5000")]
+    state_kv_db_max_open_files: i32,
+    #[structopt(long, default_value = "1073741824")] // 1GB
+    state_kv_db_max_total_wal_size: u64,
+    /// The number of shards to spread the state values across, 0 keeping them in the ledger DB.
+    /// Must match the DB restored to.
+    #[structopt(long, default_value = "0")]
+    state_kv_db_num_shards: usize,
     #[structopt(long, default_value = "1000")]
     index_db_max_open_files: i32,
     #[structopt(long, default_value = "1073741824")] // 1GB
@@ -71,16 +81,33 @@ impl From<RocksdbOpt> for RocksdbConfigs {
                 max_open_files: opt.ledger_db_max_open_files,
                 max_total_wal_size: opt.ledger_db_max_total_wal_size,
                 max_background_jobs: opt.max_background_jobs,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
             },
             state_merkle_db_config: RocksdbConfig {
                 max_open_files: opt.state_merkle_db_max_open_files,
                 max_total_wal_size: opt.state_merkle_db_max_total_wal_size,
                 max_background_jobs: opt.max_background_jobs,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
+            },
+            state_kv_db_config: RocksdbConfig {
+                max_open_files: opt.state_kv_db_max_open_files,
+                max_total_wal_size: opt.state_kv_db_max_total_wal_size,
+                max_background_jobs: opt.max_background_jobs,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
             },
             index_db_config: RocksdbConfig {
                 max_open_files: opt.index_db_max_open_files,
                 max_total_wal_size: opt.index_db_max_total_wal_size,
                 max_background_jobs: opt.max_background_jobs,
+                default_cf_config: RocksdbCfConfig::default(),
+                column_families: BTreeMap::new(),
+            },
+            state_kv_sharding: StateKvShardingConfig {
+                num_shards: opt.state_kv_db_num_shards,
+                shard_paths: vec![],
             },
         }
     }
@@ -138,7 +165,9 @@ impl TreeWriter<StateKey> for MockStore {
 impl StateValueWriter<StateKey, StateValue> for MockStore {
     fn write_kv_batch(
         &self,
+        _version: Version,
         _kv_batch: &StateValueBatch<StateKey, Option<StateValue>>,
+        _progress: Option<StateSnapshotProgress>,
     ) -> Result<()> {
         Ok(())
     }
@@ -146,6 +175,10 @@ impl StateValueWriter<StateKey, StateValue> for MockStore {
     fn write_usage(&self, _version: Version, _items: usize, _total_bytes: usize) -> Result<()> {
         Ok(())
     }
+
+    fn get_progress(&self, _version: Version) -> Result<Option<StateSnapshotProgress>> {
+        Ok(None)
+    }
 }
 
 impl RestoreRunMode {
@@ -216,6 +249,7 @@ impl TryFrom<GlobalRestoreOpt> for GlobalRestoreOptions {
                 opt.rocksdb_opt.into(),
                 false,
                 TARGET_SNAPSHOT_SIZE,
+                TARGET_SNAPSHOT_INTERVAL_IN_VERSIONS,
             )?)
             .get_restore_handler();
             RestoreRunMode::Restore { restore_handler }
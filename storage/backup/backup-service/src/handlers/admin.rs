@@ -0,0 +1,87 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::handlers::utils::{handle_rejection, reply_with_json, unwrap_or_500};
+use aptosdb::AptosDB;
+use std::{convert::Infallible, sync::Arc};
+use warp::{filters::BoxedFilter, reply::Reply, Filter};
+
+pub(super) static DB_STATS: &str = "db_stats";
+pub(super) static PRUNABLE_SPACE: &str = "prunable_space";
+pub(super) static COMPACT: &str = "compact";
+pub(super) static BACKFILL_RESOURCE_BY_MODULE_INDEX: &str = "backfill_resource_by_module_index";
+pub(super) static CLEAR_BACKUP_PROGRESS: &str = "clear_backup_progress";
+
+pub(super) fn get_admin_routes(db: Arc<AptosDB>) -> BoxedFilter<(impl Reply,)> {
+    // GET db_stats
+    let d = Arc::clone(&db);
+    let db_stats = warp::path::end()
+        .map(move || reply_with_json(&d.get_db_stats()?))
+        .map(unwrap_or_500)
+        .recover(handle_rejection);
+
+    // GET prunable_space
+    let d = Arc::clone(&db);
+    let prunable_space = warp::path::end()
+        .map(move || reply_with_json(&d.estimate_prunable_space()?))
+        .map(unwrap_or_500)
+        .recover(handle_rejection);
+
+    // POST compact/<db_name>/<cf_name>
+    let d = Arc::clone(&db);
+    let compact = warp::post()
+        .and(warp::path!(String / String))
+        .and_then(move |db_name: String, cf_name: String| {
+            let db = Arc::clone(&d);
+            async move {
+                // Compacting a big column family takes minutes, so don't block the server on it.
+                let result = tokio::task::spawn_blocking(move || {
+                    db.compact(&db_name, &cf_name)?;
+                    reply_with_json(&())
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.into()));
+                Ok::<_, Infallible>(unwrap_or_500(result))
+            }
+        })
+        .recover(handle_rejection);
+
+    // POST clear_backup_progress
+    let d = Arc::clone(&db);
+    let clear_backup_progress = warp::post()
+        .and(warp::path::end())
+        .map(move || {
+            d.clear_backup_progress()?;
+            reply_with_json(&())
+        })
+        .map(unwrap_or_500)
+        .recover(handle_rejection);
+
+    // POST backfill_resource_by_module_index
+    let backfill_resource_by_module_index = warp::post()
+        .and(warp::path::end())
+        .and_then(move || {
+            let db = Arc::clone(&db);
+            async move {
+                // The backfill goes through all the state values, like a compaction.
+                let result = tokio::task::spawn_blocking(move || {
+                    reply_with_json(&db.backfill_resource_by_module_index()?)
+                })
+                .await
+                .unwrap_or_else(|e| Err(e.into()));
+                Ok::<_, Infallible>(unwrap_or_500(result))
+            }
+        })
+        .recover(handle_rejection);
+
+    warp::get()
+        .and(
+            warp::path(DB_STATS)
+                .and(db_stats)
+                .or(warp::path(PRUNABLE_SPACE).and(prunable_space)),
+        )
+        .or(warp::path(COMPACT).and(compact))
+        .or(warp::path(BACKFILL_RESOURCE_BY_MODULE_INDEX).and(backfill_resource_by_module_index))
+        .or(warp::path(CLEAR_BACKUP_PROGRESS).and(clear_backup_progress))
+        .boxed()
+}
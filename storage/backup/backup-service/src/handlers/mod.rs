@@ -1,15 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod admin;
 mod utils;
 
-use crate::handlers::utils::{
-    handle_rejection, reply_with_async_channel_writer, reply_with_bcs_bytes,
-    send_size_prefixed_bcs_bytes, unwrap_or_500, LATENCY_HISTOGRAM,
+use crate::handlers::{
+    admin::get_admin_routes,
+    utils::{
+        handle_rejection, reply_with_async_channel_writer, reply_with_bcs_bytes,
+        send_size_prefixed_bcs_bytes, unwrap_or_500, LATENCY_HISTOGRAM,
+    },
 };
 use aptos_crypto::hash::HashValue;
 use aptos_types::transaction::Version;
-use aptosdb::backup::backup_handler::BackupHandler;
+use aptosdb::AptosDB;
+use std::sync::Arc;
 use warp::{filters::BoxedFilter, reply::Reply, Filter};
 
 static DB_STATE: &str = "db_state";
@@ -19,8 +24,14 @@ static STATE_ROOT_PROOF: &str = "state_root_proof";
 static EPOCH_ENDING_LEDGER_INFOS: &str = "epoch_ending_ledger_infos";
 static TRANSACTIONS: &str = "transactions";
 static TRANSACTION_RANGE_PROOF: &str = "transaction_range_proof";
+static BACKED_UP_VERSION: &str = "backed_up_version";
+
+pub(crate) fn get_routes(
+    db: Arc<AptosDB>,
+    enable_db_admin_endpoints: bool,
+) -> BoxedFilter<(impl Reply,)> {
+    let backup_handler = db.get_backup_handler();
 
-pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Reply,)> {
     // GET db_state
     let bh = backup_handler.clone();
     let db_state = warp::path::end()
@@ -96,7 +107,7 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .recover(handle_rejection);
 
     // GET transaction_range_proof/<first_version>/<last_version>
-    let bh = backup_handler;
+    let bh = backup_handler.clone();
     let transaction_range_proof = warp::path!(Version / Version)
         .map(move |first_version, last_version| {
             reply_with_bcs_bytes(
@@ -107,6 +118,17 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .map(unwrap_or_500)
         .recover(handle_rejection);
 
+    // POST backed_up_version/<version>
+    let bh = backup_handler;
+    let backed_up_version = warp::post()
+        .and(warp::path!(Version))
+        .map(move |version| {
+            bh.record_backed_up_version(version)?;
+            reply_with_bcs_bytes(BACKED_UP_VERSION, &())
+        })
+        .map(unwrap_or_500)
+        .recover(handle_rejection);
+
     // Route by endpoint name.
     let routes = warp::any()
         .and(warp::path(DB_STATE).and(db_state))
@@ -117,9 +139,24 @@ pub(crate) fn get_routes(backup_handler: BackupHandler) -> BoxedFilter<(impl Rep
         .or(warp::path(TRANSACTIONS).and(transactions))
         .or(warp::path(TRANSACTION_RANGE_PROOF).and(transaction_range_proof));
 
-    // Serve all routes for GET only.
+    // The DB admin endpoints are not found unless enabled.
+    let admin_routes = warp::any()
+        .and_then(move || async move {
+            if enable_db_admin_endpoints {
+                Ok::<_, warp::Rejection>(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+        .and(get_admin_routes(db));
+
+    // Serve all routes for GET only, except for the backup coordinator to report its progress and
+    // the DB admin endpoints.
     warp::get()
         .and(routes)
+        .or(warp::path(BACKED_UP_VERSION).and(backed_up_version))
+        .or(admin_routes)
         .with(warp::log::custom(|info| {
             let endpoint = info.path().split('/').nth(1).unwrap_or("-");
             LATENCY_HISTOGRAM
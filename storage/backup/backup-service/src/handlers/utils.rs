@@ -43,6 +43,11 @@ pub(super) fn reply_with_bcs_bytes<R: Serialize>(
     Ok(Box::new(bytes))
 }
 
+/// Replies in JSON, for the endpoints meant to be called by operators rather than the backup tools.
+pub(super) fn reply_with_json<R: Serialize>(record: &R) -> Result<Box<dyn Reply>> {
+    Ok(Box::new(warp::reply::json(record)))
+}
+
 pub(super) struct BytesSender {
     endpoint: &'static str,
     inner: hyper::body::Sender,
@@ -9,9 +9,14 @@ use aptosdb::AptosDB;
 use std::{net::SocketAddr, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
 
-pub fn start_backup_service(address: SocketAddr, db: Arc<AptosDB>) -> Runtime {
-    let backup_handler = db.get_backup_handler();
-    let routes = get_routes(backup_handler);
+/// Starts the backup service, which also serves the DB admin endpoints if
+/// `enable_db_admin_endpoints` is set.
+pub fn start_backup_service(
+    address: SocketAddr,
+    db: Arc<AptosDB>,
+    enable_db_admin_endpoints: bool,
+) -> Runtime {
+    let routes = get_routes(db, enable_db_admin_endpoints);
 
     let runtime = Builder::new_multi_thread()
         .thread_name("backup")
@@ -39,7 +44,8 @@ mod tests {
     use aptos_config::utils::get_available_port;
     use aptos_crypto::hash::HashValue;
     use aptos_temppath::TempPath;
-    use reqwest::blocking::get;
+    use aptosdb::db_admin::{ColumnFamilyStats, PrunableSpace};
+    use reqwest::blocking::{get, Client};
     use std::net::{IpAddr, Ipv4Addr};
 
     /// 404 - endpoint not found
@@ -52,7 +58,11 @@ mod tests {
         let tmpdir = TempPath::new();
         let db = Arc::new(AptosDB::new_for_test(&tmpdir));
         let port = get_available_port();
-        let _rt = start_backup_service(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), db);
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            false, /* enable_db_admin_endpoints */
+        );
 
         // Endpoint doesn't exist.
         let resp = get(&format!("http://127.0.0.1:{}/", port)).unwrap();
@@ -93,5 +103,112 @@ mod tests {
         assert_eq!(resp.status(), 200);
         assert_eq!(resp.content_length(), None);
         assert!(resp.bytes().is_err());
+
+        // The backup progress is reported with POST only.
+        let client = Client::new();
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/backed_up_version/5", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let resp = get(&format!("http://127.0.0.1:{}/backed_up_version/5", port)).unwrap();
+        assert_eq!(resp.status(), 400);
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/backed_up_version/x", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 400);
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/db_state", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 405);
+
+        // The DB admin endpoints are not found unless enabled.
+        let resp = get(&format!("http://127.0.0.1:{}/db_stats", port)).unwrap();
+        assert_eq!(resp.status(), 404);
+    }
+
+    #[test]
+    fn db_admin_endpoints() {
+        let tmpdir = TempPath::new();
+        let db = Arc::new(AptosDB::new_for_test(&tmpdir));
+        let port = get_available_port();
+        let _rt = start_backup_service(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port),
+            db,
+            true, /* enable_db_admin_endpoints */
+        );
+
+        let resp = get(&format!("http://127.0.0.1:{}/db_stats", port)).unwrap();
+        assert_eq!(resp.status(), 200);
+        let stats: Vec<ColumnFamilyStats> = resp.json().unwrap();
+        assert!(stats
+            .iter()
+            .any(|cf| cf.db_name == "ledger_db" && cf.cf_name == "transaction"));
+
+        // Nothing to prune in an empty DB.
+        let resp = get(&format!("http://127.0.0.1:{}/prunable_space", port)).unwrap();
+        assert_eq!(resp.status(), 200);
+        let prunable_space: Vec<PrunableSpace> = resp.json().unwrap();
+        assert!(prunable_space.is_empty());
+
+        // Compactions are triggered with POST only.
+        let client = Client::new();
+        let resp = client
+            .post(&format!(
+                "http://127.0.0.1:{}/compact/ledger_db/transaction",
+                port
+            ))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let resp = get(&format!(
+            "http://127.0.0.1:{}/compact/ledger_db/transaction",
+            port
+        ))
+        .unwrap();
+        assert_eq!(resp.status(), 400);
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/compact/ledger_db", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 400);
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/compact/ledger_db/x", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 500);
+
+        // Nothing to index in an empty DB.
+        let resp = client
+            .post(&format!(
+                "http://127.0.0.1:{}/backfill_resource_by_module_index",
+                port
+            ))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let num_indexed: usize = resp.json().unwrap();
+        assert_eq!(num_indexed, 0);
+        let resp = get(&format!(
+            "http://127.0.0.1:{}/backfill_resource_by_module_index",
+            port
+        ))
+        .unwrap();
+        assert_eq!(resp.status(), 400);
+        // A hold on the pruners left by a backup coordinator can be cleared.
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/backed_up_version/50", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let resp = client
+            .post(&format!("http://127.0.0.1:{}/clear_backup_progress", port))
+            .send()
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        let resp = get(&format!("http://127.0.0.1:{}/clear_backup_progress", port)).unwrap();
+        assert_eq!(resp.status(), 400);
     }
 }
@@ -0,0 +1,83 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::schema::{coin_balance::CoinBalanceSchema, coin_holder::CoinHolderSchema};
+use anyhow::Result;
+use aptos_types::access_path::{AccessPath, Path};
+use aptos_types::account_address::AccountAddress;
+use aptos_types::account_config::CoinStoreResource;
+use aptos_types::state_store::state_key::StateKey;
+use aptos_types::write_set::WriteOp;
+use move_deps::move_core_types::identifier::IdentStr;
+use move_deps::move_core_types::language_storage::TypeTag;
+use schemadb::{SchemaBatch, DB};
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/// Collects the latest balance of each `0x1::coin::CoinStore` written to, to update the coin
+/// balance index with.
+pub(crate) struct BalanceParser {
+    balances: HashMap<(AccountAddress, TypeTag), Option<u64>>,
+}
+
+impl BalanceParser {
+    pub fn new() -> Self {
+        Self {
+            balances: HashMap::new(),
+        }
+    }
+
+    /// Records the balance written by the write op, if it writes to a coin store. Later writes
+    /// to the same coin store override earlier ones.
+    pub fn parse_write_op(&mut self, state_key: &StateKey, write_op: &WriteOp) -> Result<()> {
+        if let StateKey::AccessPath(access_path) = state_key {
+            if let Some(coin_type) = Self::coin_store_coin_type(access_path)? {
+                let balance = match write_op {
+                    WriteOp::Creation(bytes) | WriteOp::Modification(bytes) => {
+                        Some(bcs::from_bytes::<CoinStoreResource>(bytes)?.coin())
+                    }
+                    WriteOp::Deletion => None,
+                };
+                self.balances
+                    .insert((access_path.address, coin_type), balance);
+            }
+        }
+        Ok(())
+    }
+
+    fn coin_store_coin_type(access_path: &AccessPath) -> Result<Option<TypeTag>> {
+        let path: Path = (&access_path.path).try_into()?;
+        Ok(match path {
+            Path::Resource(struct_tag)
+                if struct_tag.address == AccountAddress::ONE
+                    && struct_tag.module.as_ref() == IdentStr::new("coin").unwrap()
+                    && struct_tag.name.as_ref() == IdentStr::new("CoinStore").unwrap()
+                    && struct_tag.type_params.len() == 1 =>
+            {
+                struct_tag.type_params.into_iter().next()
+            }
+            _ => None,
+        })
+    }
+
+    /// Puts the balances into the batch, moving the holders indexed by their previous balance in
+    /// `db`. Holders with a zero balance are left out of the holder index.
+    pub fn finish(self, db: &DB, batch: &mut SchemaBatch) -> Result<()> {
+        for ((address, coin_type), balance) in self.balances {
+            let key = (address, coin_type);
+            if let Some(old_balance) = db.get::<CoinBalanceSchema>(&key)? {
+                batch.delete::<CoinHolderSchema>(&(key.1.clone(), old_balance, address))?;
+            }
+            match balance {
+                Some(balance) => {
+                    batch.put::<CoinBalanceSchema>(&key, &balance)?;
+                    if balance > 0 {
+                        batch.put::<CoinHolderSchema>(&(key.1, balance, address), &())?;
+                    }
+                }
+                None => batch.delete::<CoinBalanceSchema>(&key)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,22 +1,28 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod balances;
 mod db;
 mod metadata;
 mod schema;
 
+use crate::balances::BalanceParser;
 use crate::db::INDEX_DB_NAME;
 use crate::metadata::{Metadata, MetadataTag};
+use crate::schema::coin_balance::CoinBalanceSchema;
+use crate::schema::coin_holder::CoinHolderSchema;
 use crate::schema::column_families;
 use crate::schema::indexer_metadata::IndexerMetadataSchema;
 use crate::schema::table_info::TableInfoSchema;
+use crate::schema::table_key::TableKeySchema;
 use anyhow::{bail, ensure, Result};
 use aptos_config::config::RocksdbConfig;
-use aptos_logger::warn;
-use aptos_rocksdb_options::gen_rocksdb_options;
+use aptos_logger::{info, warn};
+use aptos_rocksdb_options::{gen_cf_options, gen_rocksdb_options};
 use aptos_types::access_path::Path;
 use aptos_types::account_address::AccountAddress;
 use aptos_types::state_store::state_key::StateKey;
+use aptos_types::state_store::state_value::StateValue;
 use aptos_types::state_store::table::TableHandle;
 use aptos_types::state_store::table::TableInfo;
 use aptos_types::transaction::{AtomicVersion, Version};
@@ -25,7 +31,7 @@ use aptos_vm::data_cache::{AsMoveResolver, RemoteStorage};
 use move_deps::move_core_types::identifier::IdentStr;
 use move_deps::move_core_types::language_storage::{StructTag, TypeTag};
 use move_deps::move_resource_viewer::{AnnotatedMoveValue, MoveValueAnnotator};
-use schemadb::{SchemaBatch, DB};
+use schemadb::{ColumnFamilyDescriptor, ReadOptions, SchemaBatch, DB};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::sync::atomic::Ordering;
@@ -37,6 +43,10 @@ use storage_interface::DbReader;
 pub struct Indexer {
     db: DB,
     next_version: AtomicVersion,
+    // The coin balance index is only kept up to date once enabled, which requires it to have
+    // caught up with the rest of the index.
+    index_balances: bool,
+    balances_next_version: AtomicVersion,
 }
 
 impl Indexer {
@@ -44,27 +54,140 @@ impl Indexer {
         db_root_path: impl AsRef<std::path::Path>,
         rocksdb_config: RocksdbConfig,
     ) -> Result<Self> {
+        rocksdb_config.validate(INDEX_DB_NAME, &column_families())?;
         let db_path = db_root_path.as_ref().join(INDEX_DB_NAME);
 
-        let db = DB::open(
+        let cfds = column_families()
+            .into_iter()
+            .map(|cf_name| {
+                ColumnFamilyDescriptor::new(cf_name, gen_cf_options(&rocksdb_config, cf_name))
+            })
+            .collect();
+        let db = DB::open_cf(
+            &gen_rocksdb_options(&rocksdb_config, false),
             db_path,
             "index_db",
-            column_families(),
-            &gen_rocksdb_options(&rocksdb_config, false),
+            cfds,
         )?;
 
         let next_version = db
             .get::<IndexerMetadataSchema>(&MetadataTag::LatestVersion)?
             .map_or(0, |meta| match meta {
-                Metadata::LatestVersion(version) => version + 1,
+                Metadata::LatestVersion(version) | Metadata::BalancesLatestVersion(version) => {
+                    version + 1
+                }
+            });
+        let balances_next_version = db
+            .get::<IndexerMetadataSchema>(&MetadataTag::BalancesLatestVersion)?
+            .map_or(0, |meta| match meta {
+                Metadata::LatestVersion(version) | Metadata::BalancesLatestVersion(version) => {
+                    version + 1
+                }
             });
 
         Ok(Self {
             db,
             next_version: AtomicVersion::new(next_version),
+            index_balances: false,
+            balances_next_version: AtomicVersion::new(balances_next_version),
         })
     }
 
+    /// Keeps the coin balance index up to date from now on. The balance index must have caught
+    /// up with the rest of the index, see `backfill_balances` and `index_balances`.
+    pub fn enable_balance_index(&mut self) -> Result<()> {
+        ensure!(
+            self.balances_next_version() == self.next_version(),
+            "The balance index is behind the rest of the index: expecting version {}, got {}.",
+            self.next_version(),
+            self.balances_next_version(),
+        );
+        self.index_balances = true;
+        Ok(())
+    }
+
+    pub fn balance_index_enabled(&self) -> bool {
+        self.index_balances
+    }
+
+    pub fn balances_next_version(&self) -> Version {
+        self.balances_next_version.load(Ordering::Relaxed)
+    }
+
+    /// Rebuilds the coin balance index from the state at `version`, given as all its keys and
+    /// values. Balances are written in batches, but the index is only considered built up to
+    /// `version` once all of them are.
+    pub fn backfill_balances(
+        &self,
+        state: impl Iterator<Item = Result<(StateKey, StateValue)>>,
+        version: Version,
+    ) -> Result<()> {
+        const BATCH_SIZE: usize = 10000;
+
+        let mut parser = BalanceParser::new();
+        let mut num_keys = 0;
+        for item in state {
+            let (state_key, state_value) = item?;
+            parser.parse_write_op(&state_key, &WriteOp::Modification(state_value.bytes))?;
+            num_keys += 1;
+            if num_keys % BATCH_SIZE == 0 {
+                let mut batch = SchemaBatch::new();
+                parser.finish(&self.db, &mut batch)?;
+                self.db.write_schemas(batch)?;
+                parser = BalanceParser::new();
+                info!(
+                    num_keys = num_keys,
+                    version = version,
+                    "Backfilling the coin balance index."
+                );
+            }
+        }
+
+        let mut batch = SchemaBatch::new();
+        parser.finish(&self.db, &mut batch)?;
+        batch.put::<IndexerMetadataSchema>(
+            &MetadataTag::BalancesLatestVersion,
+            &Metadata::BalancesLatestVersion(version),
+        )?;
+        self.db.write_schemas(batch)?;
+        self.balances_next_version
+            .store(version + 1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Updates the coin balance index alone with the write sets, to catch it up with the rest of
+    /// the index.
+    pub fn index_balances(&self, first_version: Version, write_sets: &[&WriteSet]) -> Result<()> {
+        let next_version = self.balances_next_version();
+        ensure!(
+            first_version == next_version,
+            "Balance index expects to see continuous transaction versions. Expecting: {}, got: {}",
+            next_version,
+            first_version,
+        );
+        if write_sets.is_empty() {
+            return Ok(());
+        }
+        let end_version = first_version + write_sets.len() as Version;
+
+        let mut batch = SchemaBatch::new();
+        let mut parser = BalanceParser::new();
+        for write_set in write_sets {
+            for (state_key, write_op) in write_set.iter() {
+                parser.parse_write_op(state_key, write_op)?;
+            }
+        }
+        parser.finish(&self.db, &mut batch)?;
+        batch.put::<IndexerMetadataSchema>(
+            &MetadataTag::BalancesLatestVersion,
+            &Metadata::BalancesLatestVersion(end_version - 1),
+        )?;
+        self.db.write_schemas(batch)?;
+        self.balances_next_version
+            .store(end_version, Ordering::Relaxed);
+        Ok(())
+    }
+
     pub fn index(
         &self,
         db_reader: Arc<dyn DbReader>,
@@ -105,21 +228,42 @@ impl Indexer {
             return Ok(());
         }
 
+        let mut batch = SchemaBatch::new();
         let mut table_info_parser = TableInfoParser::new(self, annotator);
+        let mut balance_parser = BalanceParser::new();
         for write_set in write_sets {
             for (state_key, write_op) in write_set.iter() {
                 table_info_parser.parse_write_op(state_key, write_op)?;
+                if self.index_balances {
+                    balance_parser.parse_write_op(state_key, write_op)?;
+                }
+                // Keys are kept once deleted, as they may be listed at an older version.
+                if let StateKey::TableItem { handle, key } = state_key {
+                    if !matches!(write_op, WriteOp::Deletion) {
+                        batch.put::<TableKeySchema>(&(*handle, key.clone()), &())?;
+                    }
+                }
             }
         }
 
-        let mut batch = SchemaBatch::new();
         table_info_parser.finish(&mut batch)?;
+        if self.index_balances {
+            balance_parser.finish(&self.db, &mut batch)?;
+            batch.put::<IndexerMetadataSchema>(
+                &MetadataTag::BalancesLatestVersion,
+                &Metadata::BalancesLatestVersion(end_version - 1),
+            )?;
+        }
         batch.put::<IndexerMetadataSchema>(
             &MetadataTag::LatestVersion,
             &Metadata::LatestVersion(end_version - 1),
         )?;
         self.db.write_schemas(batch)?;
         self.next_version.store(end_version, Ordering::Relaxed);
+        if self.index_balances {
+            self.balances_next_version
+                .store(end_version, Ordering::Relaxed);
+        }
 
         Ok(())
     }
@@ -131,6 +275,69 @@ impl Indexer {
     pub fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         self.db.get::<TableInfoSchema>(&handle)
     }
+
+    /// Gets an iterator over the raw keys ever written to the table, in the order of their BCS
+    /// encoding, starting after `cursor` if given. Keys deleted since are included.
+    pub fn get_table_key_iter(
+        &self,
+        handle: TableHandle,
+        cursor: Option<Vec<u8>>,
+    ) -> Result<impl Iterator<Item = Result<Vec<u8>>> + '_> {
+        let mut iter = self.db.iter::<TableKeySchema>(ReadOptions::default())?;
+        match &cursor {
+            Some(key) => iter.seek(&(handle, key.clone()))?,
+            None => iter.seek(&handle)?,
+        }
+        Ok(iter
+            .map(|res| res.map(|(key, ())| key))
+            .take_while(move |res| match res {
+                Ok((key_handle, _)) => *key_handle == handle,
+                Err(_) => true,
+            })
+            .map(|res| res.map(|(_, key)| key))
+            .filter(move |res| match (res, &cursor) {
+                (Ok(key), Some(cursor)) => key != cursor,
+                _ => true,
+            }))
+    }
+
+    /// Gets the latest balances of the account from the coin balance index, by coin type.
+    pub fn get_coin_balances(&self, address: AccountAddress) -> Result<Vec<(TypeTag, u64)>> {
+        let mut iter = self.db.iter::<CoinBalanceSchema>(ReadOptions::default())?;
+        iter.seek(&address)?;
+        let mut balances = Vec::new();
+        for res in iter {
+            let ((holder, coin_type), balance) = res?;
+            if holder != address {
+                break;
+            }
+            balances.push((coin_type, balance));
+        }
+        Ok(balances)
+    }
+
+    /// Gets up to `limit` accounts holding the most of the coin, from the largest balance to the
+    /// smallest, from the coin balance index. Accounts with a zero balance are left out.
+    pub fn get_top_coin_holders(
+        &self,
+        coin_type: &TypeTag,
+        limit: usize,
+    ) -> Result<Vec<(AccountAddress, u64)>> {
+        let mut iter = self.db.iter::<CoinHolderSchema>(ReadOptions::default())?;
+        iter.seek(coin_type)?;
+        let mut holders = Vec::new();
+        for res in iter {
+            if holders.len() >= limit {
+                break;
+            }
+            let ((holder_coin_type, balance, address), ()) = res?;
+            if holder_coin_type != *coin_type {
+                break;
+            }
+            holders.push((address, balance));
+        }
+        Ok(holders)
+    }
 }
 
 struct TableInfoParser<'a> {
@@ -10,6 +10,9 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(any(test, feature = "fuzzing"), derive(proptest_derive::Arbitrary))]
 pub(crate) enum Metadata {
     LatestVersion(Version),
+    /// The latest version the coin balance index is built up to, which lags `LatestVersion` while
+    /// the balance index is disabled.
+    BalancesLatestVersion(Version),
 }
 
 #[derive(Clone, Debug, Deserialize, FromPrimitive, PartialEq, ToPrimitive, Serialize)]
@@ -17,4 +20,5 @@ pub(crate) enum Metadata {
 #[repr(u8)]
 pub(crate) enum MetadataTag {
     LatestVersion = 0,
+    BalancesLatestVersion = 1,
 }
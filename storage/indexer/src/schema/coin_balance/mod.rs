@@ -0,0 +1,72 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema holding the latest coin balances of each account,
+//! by coin type.
+//!
+//! ```text
+//! |<---------key-------->|<--value-->|
+//! | address | coin_type  |  balance  |
+//! ```
+//!
+//! `address` comes first so that the balances of an account are stored together.
+
+use crate::schema::COIN_BALANCE_CF_NAME;
+use anyhow::{ensure, Result};
+use aptos_types::account_address::AccountAddress;
+use byteorder::{BigEndian, ReadBytesExt};
+use move_deps::move_core_types::language_storage::TypeTag;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::convert::TryFrom;
+
+type Key = (AccountAddress, TypeTag);
+
+define_schema!(CoinBalanceSchema, Key, u64, COIN_BALANCE_CF_NAME);
+
+impl KeyCodec<CoinBalanceSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (address, coin_type) = self;
+        let mut encoded = address.to_vec();
+        encoded.extend(bcs::to_bytes(coin_type)?);
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() > AccountAddress::LENGTH,
+            "Unexpected data len {}, expected more than {}.",
+            data.len(),
+            AccountAddress::LENGTH,
+        );
+        let address = AccountAddress::try_from(&data[..AccountAddress::LENGTH])?;
+        let coin_type = bcs::from_bytes(&data[AccountAddress::LENGTH..])?;
+        Ok((address, coin_type))
+    }
+}
+
+impl ValueCodec<CoinBalanceSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() == 8,
+            "Unexpected data len {}, expected 8.",
+            data.len()
+        );
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl SeekKeyCodec<CoinBalanceSchema> for AccountAddress {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        address in any::<AccountAddress>(),
+        coin_type in any::<TypeTag>(),
+        balance in any::<u64>(),
+    ) {
+        assert_encode_decode::<CoinBalanceSchema>(&(address, coin_type), &balance);
+    }
+}
+
+test_no_panic_decoding!(CoinBalanceSchema);
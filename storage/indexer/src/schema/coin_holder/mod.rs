@@ -0,0 +1,80 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema indexing the accounts holding a non-zero balance of
+//! each coin type by their balance, so that the top holders of a coin can be listed.
+//!
+//! ```text
+//! |<--------------key-------------->|<-value->|
+//! | coin_type | !balance | address  |  empty  |
+//! ```
+//!
+//! The balance is bitwise inverted and serialized in big endian so that the holders of a coin are
+//! stored from the largest balance to the smallest. The BCS encoding of `coin_type` is
+//! self-delimiting, so no coin type's holders get mixed with another's.
+
+use crate::schema::COIN_HOLDER_CF_NAME;
+use anyhow::{ensure, Result};
+use aptos_types::account_address::AccountAddress;
+use byteorder::{BigEndian, ReadBytesExt};
+use move_deps::move_core_types::language_storage::TypeTag;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+
+type Key = (TypeTag, u64, AccountAddress);
+
+define_schema!(CoinHolderSchema, Key, (), COIN_HOLDER_CF_NAME);
+
+impl KeyCodec<CoinHolderSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (coin_type, balance, address) = self;
+        let mut encoded = bcs::to_bytes(coin_type)?;
+        encoded.extend_from_slice(&(!balance).to_be_bytes());
+        encoded.extend(address.to_vec());
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        const SUFFIX_SIZE: usize = size_of::<u64>() + AccountAddress::LENGTH;
+
+        ensure!(
+            data.len() > SUFFIX_SIZE,
+            "Unexpected data len {}, expected more than {}.",
+            data.len(),
+            SUFFIX_SIZE,
+        );
+        let balance_offset = data.len() - SUFFIX_SIZE;
+        let address_offset = balance_offset + size_of::<u64>();
+        let coin_type = bcs::from_bytes(&data[..balance_offset])?;
+        let balance = !(&data[balance_offset..address_offset]).read_u64::<BigEndian>()?;
+        let address = AccountAddress::try_from(&data[address_offset..])?;
+        Ok((coin_type, balance, address))
+    }
+}
+
+impl ValueCodec<CoinHolderSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.is_empty(),
+            "Unexpected data len {}, expected 0.",
+            data.len()
+        );
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<CoinHolderSchema> for TypeTag {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
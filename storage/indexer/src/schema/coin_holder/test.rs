@@ -0,0 +1,30 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        coin_type in any::<TypeTag>(),
+        balance in any::<u64>(),
+        address in any::<AccountAddress>(),
+    ) {
+        assert_encode_decode::<CoinHolderSchema>(&(coin_type, balance, address), &());
+    }
+
+    #[test]
+    fn test_larger_balance_first(
+        coin_type in any::<TypeTag>(),
+        balance in 0..u64::MAX,
+        address in any::<AccountAddress>(),
+    ) {
+        let larger = (coin_type.clone(), balance + 1, address).encode_key().unwrap();
+        let smaller = (coin_type, balance, address).encode_key().unwrap();
+        prop_assert!(larger < smaller);
+    }
+}
+
+test_no_panic_decoding!(CoinHolderSchema);
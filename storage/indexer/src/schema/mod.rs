@@ -6,19 +6,28 @@
 //!
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
+pub(crate) mod coin_balance;
+pub(crate) mod coin_holder;
 pub(crate) mod indexer_metadata;
 pub(crate) mod table_info;
+pub(crate) mod table_key;
 
 use schemadb::ColumnFamilyName;
 
 pub const DEFAULT_COLUMN_FAMILY_NAME: ColumnFamilyName = "default";
 pub const INDEXER_METADATA_CF_NAME: ColumnFamilyName = "indexer_metadata";
 pub const TABLE_INFO_CF_NAME: ColumnFamilyName = "table_info";
+pub const TABLE_KEY_CF_NAME: ColumnFamilyName = "table_key";
+pub const COIN_BALANCE_CF_NAME: ColumnFamilyName = "coin_balance";
+pub const COIN_HOLDER_CF_NAME: ColumnFamilyName = "coin_holder";
 
 pub fn column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
         INDEXER_METADATA_CF_NAME,
         TABLE_INFO_CF_NAME,
+        TABLE_KEY_CF_NAME,
+        COIN_BALANCE_CF_NAME,
+        COIN_HOLDER_CF_NAME,
     ]
 }
@@ -0,0 +1,73 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema indexing the keys ever written to each table, so
+//! that the items of a table can be listed.
+//!
+//! ```text
+//! |<-------key------->|<-value->|
+//! | handle | raw_key  |  empty  |
+//! ```
+//!
+//! `handle` is serialized in big endian so that the keys of a table are stored together, in the
+//! order of their BCS encoding.
+
+use crate::schema::TABLE_KEY_CF_NAME;
+use anyhow::{ensure, Result};
+use aptos_types::state_store::table::TableHandle;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+type Key = (TableHandle, Vec<u8>);
+
+define_schema!(TableKeySchema, Key, (), TABLE_KEY_CF_NAME);
+
+impl KeyCodec<TableKeySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (handle, raw_key) = self;
+        let mut encoded = handle.0.to_be_bytes().to_vec();
+        encoded.extend_from_slice(raw_key);
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        const HANDLE_SIZE: usize = size_of::<u128>();
+
+        ensure!(
+            data.len() >= HANDLE_SIZE,
+            "Unexpected data len {}, expected at least {}.",
+            data.len(),
+            HANDLE_SIZE,
+        );
+        let handle = TableHandle((&data[..HANDLE_SIZE]).read_u128::<BigEndian>()?);
+        Ok((handle, data[HANDLE_SIZE..].to_vec()))
+    }
+}
+
+impl ValueCodec<TableKeySchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.is_empty(),
+            "Unexpected data len {}, expected 0.",
+            data.len()
+        );
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<TableKeySchema> for TableHandle {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(self.0.to_be_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
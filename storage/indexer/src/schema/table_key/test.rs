@@ -0,0 +1,18 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        handle in any::<TableHandle>(),
+        raw_key in vec(any::<u8>(), 0..100),
+    ) {
+        assert_encode_decode::<TableKeySchema>(&(handle, raw_key), &());
+    }
+}
+
+test_no_panic_decoding!(TableKeySchema);
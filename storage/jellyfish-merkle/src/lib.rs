@@ -97,7 +97,7 @@ use proptest::arbitrary::Arbitrary;
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashMap},
     hash::Hash,
@@ -134,9 +134,9 @@ pub trait TreeReader<K> {
     /// Gets node given a node key. Returns `None` if the node does not exist.
     fn get_node_option(&self, node_key: &NodeKey) -> Result<Option<Node<K>>>;
 
-    /// Gets the rightmost leaf. Note that this assumes we are in the process of restoring the tree
-    /// and all nodes are at the same version.
-    fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode<K>)>>;
+    /// Gets the rightmost leaf at `version`. Note that this assumes we are in the process of
+    /// restoring the tree at `version`.
+    fn get_rightmost_leaf(&self, version: Version) -> Result<Option<(NodeKey, LeafNode<K>)>>;
 }
 
 pub trait TreeWriter<K>: Send + Sync {
@@ -144,11 +144,30 @@ pub trait TreeWriter<K>: Send + Sync {
     fn write_node_batch(&self, node_batch: &HashMap<NodeKey, Node<K>>) -> Result<()>;
 }
 
+/// How far the restore of the state values of a snapshot went: the hash of the last key written,
+/// and the number of items and bytes written up to and including it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct StateSnapshotProgress {
+    pub key_hash: HashValue,
+    pub num_items: usize,
+    pub total_bytes: usize,
+}
+
 pub trait StateValueWriter<K, V>: Send + Sync {
-    /// Writes a kv batch into storage.
-    fn write_kv_batch(&self, kv_batch: &StateValueBatch<K, Option<V>>) -> Result<()>;
+    /// Writes a kv batch into storage. If `progress` is given, it is written along with the batch
+    /// as the progress of restoring the snapshot at `version`.
+    fn write_kv_batch(
+        &self,
+        version: Version,
+        kv_batch: &StateValueBatch<K, Option<V>>,
+        progress: Option<StateSnapshotProgress>,
+    ) -> Result<()>;
 
     fn write_usage(&self, version: Version, items: usize, total_bytes: usize) -> Result<()>;
+
+    /// Gets the progress last written along with a kv batch of the snapshot at `version`.
+    fn get_progress(&self, version: Version) -> Result<Option<StateSnapshotProgress>>;
 }
 
 pub trait Key: Clone + Serialize + DeserializeOwned + Send + Sync {
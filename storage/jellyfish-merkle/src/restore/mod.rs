@@ -12,7 +12,8 @@ use crate::{
         get_child_and_sibling_half_start, Child, Children, InternalNode, LeafNode, Node, NodeKey,
         NodeType,
     },
-    NibbleExt, StateValueWriter, TreeReader, TreeWriter, ROOT_NIBBLE_HEIGHT,
+    NibbleExt, StateSnapshotProgress, StateValueBatch, StateValueWriter, TreeReader, TreeWriter,
+    IO_POOL, ROOT_NIBBLE_HEIGHT,
 };
 use anyhow::{ensure, Result};
 use aptos_crypto::{
@@ -30,9 +31,13 @@ use aptos_types::{
 };
 use itertools::Itertools;
 use mirai_annotations::*;
+use rayon::prelude::*;
 use std::{cmp::Eq, collections::HashMap, hash::Hash, sync::Arc};
 use storage_interface::StateSnapshotReceiver;
 
+/// The fewest values written to storage in one of the sub-batches a chunk is split into.
+const MIN_KV_SUB_BATCH_SIZE: usize = 1000;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum ChildInfo<K> {
     /// This child is an internal node. The hash of the internal node is stored here if it is
@@ -174,8 +179,7 @@ where
     ) -> Result<Self> {
         let tree_reader = Arc::clone(&store);
         let (partial_nodes, previous_leaf) =
-            if let Some((node_key, leaf_node)) = tree_reader.get_rightmost_leaf()? {
-                // TODO: confirm rightmost leaf is at the desired version
+            if let Some((node_key, leaf_node)) = tree_reader.get_rightmost_leaf(version)? {
                 // If the system crashed in the middle of the previous restoration attempt, we need
                 // to recover the partial nodes to the state right before the crash.
                 (
@@ -290,18 +294,21 @@ where
         Ok(partial_nodes)
     }
 
-    /// Restores a chunk of accounts. This function will verify that the given chunk is correct
-    /// using the proof and root hash, then write things to storage. If the chunk is invalid, an
-    /// error will be returned and nothing will be written to storage.
+    /// Restores a chunk of accounts, each given with the hash of its key and the hash of its value.
+    /// This function will verify that the given chunk is correct using the proof and root hash,
+    /// then write things to storage. If the chunk is invalid, an error will be returned and nothing
+    /// will be written to storage.
     fn add_chunk_impl(
         &mut self,
-        mut chunk: Vec<(&K, HashValue)>,
+        mut chunk: Vec<(&K, HashValue, HashValue)>,
         proof: SparseMerkleRangeProof,
     ) -> Result<()> {
         if let Some(prev_leaf) = &self.previous_leaf {
             let skip_until = chunk
                 .iter()
-                .find_position(|(key, _hash)| key.hash() > prev_leaf.account_key());
+                .find_position(|(_key, hashed_key, _value_hash)| {
+                    *hashed_key > prev_leaf.account_key()
+                });
             chunk = match skip_until {
                 None => {
                     info!("Skipping entire chunk.");
@@ -322,8 +329,7 @@ where
             return Ok(());
         }
 
-        for (key, value_hash) in chunk {
-            let hashed_key = key.hash();
+        for (key, hashed_key, value_hash) in chunk {
             if let Some(ref prev_leaf) = self.previous_leaf {
                 ensure!(
                     hashed_key > prev_leaf.account_key(),
@@ -335,7 +341,7 @@ where
                 value_hash,
                 (key.clone(), self.version),
             ));
-            self.add_one(key, value_hash);
+            self.add_one(key, hashed_key, value_hash);
             self.num_keys_received += 1;
         }
 
@@ -349,8 +355,7 @@ where
     }
 
     /// Restores one account.
-    fn add_one(&mut self, new_key: &K, new_value_hash: HashValue) {
-        let new_hashed_key = new_key.hash();
+    fn add_one(&mut self, new_key: &K, new_hashed_key: HashValue, new_value_hash: HashValue) {
         let nibble_path = NibblePath::new_even(new_hashed_key.to_vec());
         let mut nibbles = nibble_path.nibbles();
 
@@ -375,6 +380,7 @@ where
                             child_index,
                             existing_leaf,
                             new_key,
+                            new_hashed_key,
                             new_value_hash,
                             nibbles,
                         );
@@ -412,6 +418,7 @@ where
         child_index: usize,
         existing_leaf: LeafNode<K>,
         new_key: &K,
+        new_hashed_key: HashValue,
         new_value_hash: HashValue,
         mut remaining_nibbles: NibbleIterator,
     ) {
@@ -429,7 +436,6 @@ where
 
         // Next we build the new internal nodes from top to bottom. All these internal node except
         // the bottom one will now have a single internal node child.
-        let new_hashed_key = CryptoHash::hash(new_key);
         let common_prefix_len = existing_leaf
             .account_key()
             .common_prefix_nibbles_len(new_hashed_key);
@@ -711,35 +717,100 @@ where
 struct StateValueRestore<K, V> {
     version: Version,
     db: Arc<dyn StateValueWriter<K, V>>,
-    num_items: usize,
-    total_bytes: usize,
+    /// How far the values were restored, `None` until the first of them is written.
+    progress: Option<StateSnapshotProgress>,
 }
 
 impl<K: crate::Key + Hash + Eq, V: crate::Value> StateValueRestore<K, V> {
-    pub fn new<D: 'static + StateValueWriter<K, V>>(db: Arc<D>, version: Version) -> Self {
+    /// Resumes from the progress of a previous attempt to restore the values at `version`, if any.
+    pub fn new<D: 'static + StateValueWriter<K, V>>(db: Arc<D>, version: Version) -> Result<Self> {
+        let progress = db.get_progress(version)?;
+        if let Some(progress) = &progress {
+            info!(
+                version = version,
+                num_items = progress.num_items,
+                "Resuming restoring state values."
+            );
+        }
+        Ok(Self {
+            version,
+            db,
+            progress,
+        })
+    }
+
+    pub fn new_overwrite<D: 'static + StateValueWriter<K, V>>(
+        db: Arc<D>,
+        version: Version,
+    ) -> Self {
         Self {
             version,
             db,
-            num_items: 0,
-            total_bytes: 0,
+            progress: None,
         }
     }
 
-    pub fn add_chunk(&mut self, chunk: Vec<(K, V)>) -> Result<()> {
-        let kv_batch = chunk
-            .into_iter()
-            .map(|(k, v)| {
-                self.num_items += 1;
-                self.total_bytes += k.key_size() + v.value_size();
-                ((k, self.version), Some(v))
-            })
-            .collect();
-        self.db.write_kv_batch(&kv_batch)
+    /// Writes the values of a chunk, given along with the hashes of its keys, skipping the ones
+    /// written before the restore was interrupted.
+    ///
+    /// The chunk is split into sub-batches that are written in parallel, except for the last one,
+    /// which is written along with the progress only after all the others are. So the progress
+    /// never covers a value that was not written.
+    pub fn add_chunk(&mut self, chunk: &[(K, V)], key_hashes: &[HashValue]) -> Result<()> {
+        let num_to_skip = match &self.progress {
+            Some(progress) => key_hashes
+                .iter()
+                .position(|key_hash| *key_hash > progress.key_hash)
+                .unwrap_or_else(|| key_hashes.len()),
+            None => 0,
+        };
+        let chunk = &chunk[num_to_skip..];
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        let (num_items, total_bytes) = self.progress.as_ref().map_or((0, 0), |progress| {
+            (progress.num_items, progress.total_bytes)
+        });
+        let progress = StateSnapshotProgress {
+            key_hash: *key_hashes.last().expect("The chunk is not empty."),
+            num_items: num_items + chunk.len(),
+            total_bytes: total_bytes
+                + chunk
+                    .par_iter()
+                    .map(|(k, v)| k.key_size() + v.value_size())
+                    .sum::<usize>(),
+        };
+
+        let version = self.version;
+        let to_kv_batch = |kvs: &[(K, V)]| -> StateValueBatch<K, Option<V>> {
+            kvs.iter()
+                .map(|(k, v)| ((k.clone(), version), Some(v.clone())))
+                .collect()
+        };
+        let sub_batch_size = std::cmp::max(
+            MIN_KV_SUB_BATCH_SIZE,
+            chunk.len() / IO_POOL.current_num_threads(),
+        );
+        let (leading, last) =
+            chunk.split_at(chunk.len() - std::cmp::min(sub_batch_size, chunk.len()));
+        IO_POOL.install(|| {
+            leading
+                .par_chunks(sub_batch_size)
+                .try_for_each(|kvs| self.db.write_kv_batch(version, &to_kv_batch(kvs), None))
+        })?;
+        self.db
+            .write_kv_batch(version, &to_kv_batch(last), Some(progress.clone()))?;
+
+        self.progress = Some(progress);
+        Ok(())
     }
 
     pub fn finish(self) -> Result<()> {
-        self.db
-            .write_usage(self.version, self.num_items, self.total_bytes)
+        let (num_items, total_bytes) = self.progress.map_or((0, 0), |progress| {
+            (progress.num_items, progress.total_bytes)
+        });
+        self.db.write_usage(self.version, num_items, total_bytes)
     }
 }
 
@@ -761,7 +832,7 @@ impl<K: crate::Key + CryptoHash + Hash + Eq, V: crate::Value> StateSnapshotResto
                 version,
                 expected_root_hash,
             )?,
-            kv_restore: StateValueRestore::new(Arc::clone(value_store), version),
+            kv_restore: StateValueRestore::new(Arc::clone(value_store), version)?,
         })
     }
 
@@ -777,7 +848,7 @@ impl<K: crate::Key + CryptoHash + Hash + Eq, V: crate::Value> StateSnapshotResto
                 version,
                 expected_root_hash,
             )?,
-            kv_restore: StateValueRestore::new(Arc::clone(value_store), version),
+            kv_restore: StateValueRestore::new_overwrite(Arc::clone(value_store), version),
         })
     }
 }
@@ -786,12 +857,24 @@ impl<K: crate::Key + CryptoHash + Hash + Eq, V: crate::Value> StateSnapshotRecei
     for StateSnapshotRestore<K, V>
 {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()> {
-        // Write KV out first because we are likely to resume according to the rightmost key in the
-        // tree after crashing.
-        self.kv_restore.add_chunk(chunk.clone())?;
-        self.tree_restore
-            .add_chunk_impl(chunk.iter().map(|(k, v)| (k, v.hash())).collect(), proof)?;
-        Ok(())
+        let (key_hashes, value_hashes): (Vec<_>, Vec<_>) = chunk
+            .par_iter()
+            .map(|(k, v)| (CryptoHash::hash(k), v.hash()))
+            .unzip();
+
+        // The tree is restored first, as that verifies the chunk against the proof, and the values
+        // are only written once the chunk is known to be correct. Each of them resumes on its own
+        // after a crash: the values from the progress written along with them, and the tree from
+        // its rightmost leaf.
+        self.tree_restore.add_chunk_impl(
+            chunk
+                .iter()
+                .zip(key_hashes.iter().zip(value_hashes.iter()))
+                .map(|((k, _v), (key_hash, value_hash))| (k, *key_hash, *value_hash))
+                .collect(),
+            proof,
+        )?;
+        self.kv_restore.add_chunk(&chunk, &key_hashes)
     }
 
     fn finish(self) -> Result<()> {
@@ -6,21 +6,26 @@ use crate::{
     node_type::{LeafNode, Node, NodeKey},
     restore::StateSnapshotRestore,
     test_helper::{init_mock_db, ValueBlob},
-    JellyfishMerkleTree, NodeBatch, StateValueBatch, StateValueWriter, TestKey, TestValue,
-    TreeReader, TreeWriter,
+    JellyfishMerkleTree, Key, NodeBatch, StateSnapshotProgress, StateValueBatch, StateValueWriter,
+    TestKey, TestValue, TreeReader, TreeWriter, Value,
 };
 use anyhow::Result;
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_infallible::RwLock;
 use aptos_types::transaction::Version;
 use proptest::{collection::btree_map, prelude::*};
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 use storage_interface::StateSnapshotReceiver;
 
 #[derive(Default)]
 struct MockSnapshotStore<K: TestKey, V: TestValue> {
     tree_store: MockTreeStore<K>,
     kv_store: RwLock<BTreeMap<(K, Version), V>>,
+    progress: RwLock<HashMap<Version, StateSnapshotProgress>>,
+    usage: RwLock<HashMap<Version, (usize, usize)>>,
 }
 
 impl<K, V> MockSnapshotStore<K, V>
@@ -32,12 +37,18 @@ where
         Self {
             tree_store: MockTreeStore::new(overwrite),
             kv_store: RwLock::new(BTreeMap::default()),
+            progress: RwLock::new(HashMap::default()),
+            usage: RwLock::new(HashMap::default()),
         }
     }
 
     fn get_value_at_version(&self, k: &(K, Version)) -> Option<V> {
         self.kv_store.read().get(k).cloned()
     }
+
+    fn get_usage(&self, version: Version) -> Option<(usize, usize)> {
+        self.usage.read().get(&version).cloned()
+    }
 }
 
 impl<K, V> StateValueWriter<K, V> for MockSnapshotStore<K, V>
@@ -45,7 +56,12 @@ where
     K: TestKey,
     V: TestValue,
 {
-    fn write_kv_batch(&self, kv_batch: &StateValueBatch<K, Option<V>>) -> Result<()> {
+    fn write_kv_batch(
+        &self,
+        version: Version,
+        kv_batch: &StateValueBatch<K, Option<V>>,
+        progress: Option<StateSnapshotProgress>,
+    ) -> Result<()> {
         for (k, v) in kv_batch {
             if let Some(v) = v {
                 self.kv_store.write().insert(k.clone(), v.clone());
@@ -53,12 +69,20 @@ where
                 self.kv_store.write().remove(k);
             }
         }
+        if let Some(progress) = progress {
+            self.progress.write().insert(version, progress);
+        }
         Ok(())
     }
 
-    fn write_usage(&self, _version: Version, _items: usize, _total_bytes: usize) -> Result<()> {
+    fn write_usage(&self, version: Version, items: usize, total_bytes: usize) -> Result<()> {
+        self.usage.write().insert(version, (items, total_bytes));
         Ok(())
     }
+
+    fn get_progress(&self, version: Version) -> Result<Option<StateSnapshotProgress>> {
+        Ok(self.progress.read().get(&version).cloned())
+    }
 }
 
 impl<K, V> TreeReader<K> for MockSnapshotStore<K, V>
@@ -70,8 +94,8 @@ where
         self.tree_store.get_node_option(node_key)
     }
 
-    fn get_rightmost_leaf(&self) -> Result<Option<(NodeKey, LeafNode<K>)>> {
-        self.tree_store.get_rightmost_leaf()
+    fn get_rightmost_leaf(&self, version: Version) -> Result<Option<(NodeKey, LeafNode<K>)>> {
+        self.tree_store.get_rightmost_leaf(version)
     }
 }
 
@@ -104,6 +128,8 @@ where
         MockSnapshotStore {
             tree_store,
             kv_store: RwLock::new(kv_store),
+            progress: RwLock::new(HashMap::default()),
+            usage: RwLock::new(HashMap::default()),
         },
         version,
     )
@@ -175,6 +201,68 @@ proptest! {
         }
 
         assert_success(&restore_db, expected_root_hash, &all, version);
+        // Values restored before the interruption are counted once.
+        prop_assert_eq!(restore_db.get_usage(version), Some(expected_usage(&all)));
+    }
+
+    #[test]
+    fn test_restore_resumes_from_progress(
+        (all, batch1_size) in arb_btree_map(2)
+            .prop_flat_map(|btree| {
+                let len = btree.len();
+                (Just(btree), 1..len)
+            })
+    ) {
+        let (db, version) = init_mock_store(&all.clone().into_iter().map(|(_, kv)| kv).collect());
+        let tree = JellyfishMerkleTree::new(&db);
+        let expected_root_hash = tree.get_root_hash(version).unwrap();
+        let batch1: Vec<_> = all.clone().into_iter().take(batch1_size).collect();
+        let last_key_hash = batch1.last().map(|(key_hash, _)| *key_hash).unwrap();
+
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        {
+            let mut restore =
+                StateSnapshotRestore::new(&restore_db, &restore_db, version, expected_root_hash).unwrap();
+            let proof = tree.get_range_proof(last_key_hash, version).unwrap();
+            restore.add_chunk(batch1.iter().map(|(_, kv)| kv.clone()).collect(), proof).unwrap();
+        }
+        prop_assert_eq!(
+            restore_db.get_progress(version).unwrap(),
+            Some(StateSnapshotProgress {
+                key_hash: last_key_hash,
+                num_items: batch1_size,
+                total_bytes: expected_usage(&batch1.into_iter().collect()).1,
+            })
+        );
+
+        // Resume by replaying all the chunks, as a restore run again from the start does.
+        {
+            let mut restore =
+                StateSnapshotRestore::new(&restore_db, &restore_db, version, expected_root_hash).unwrap();
+            let proof = tree.get_range_proof(*all.keys().last().unwrap(), version).unwrap();
+            restore.add_chunk(all.values().cloned().collect(), proof).unwrap();
+            restore.finish().unwrap();
+        }
+
+        assert_success(&restore_db, expected_root_hash, &all, version);
+        prop_assert_eq!(restore_db.get_usage(version), Some(expected_usage(&all)));
+    }
+
+    #[test]
+    fn test_restore_rejects_chunk_before_writing_values(btree in arb_btree_map(1)) {
+        let (db, version) = init_mock_store(&btree.clone().into_iter().map(|(_, kv)| kv).collect());
+        let tree = JellyfishMerkleTree::new(&db);
+        let expected_root_hash = tree.get_root_hash(version).unwrap();
+        let proof = tree.get_range_proof(*btree.keys().last().unwrap(), version).unwrap();
+        let mut chunk: Vec<_> = btree.values().cloned().collect();
+        chunk.last_mut().unwrap().1 = ValueBlob::from(HashValue::random().to_vec());
+
+        let restore_db = Arc::new(MockSnapshotStore::default());
+        let mut restore =
+            StateSnapshotRestore::new(&restore_db, &restore_db, version, expected_root_hash).unwrap();
+        prop_assert!(restore.add_chunk(chunk, proof).is_err());
+        prop_assert!(restore_db.kv_store.read().is_empty());
+        prop_assert_eq!(restore_db.get_progress(version).unwrap(), None);
     }
 
     #[test]
@@ -190,6 +278,19 @@ proptest! {
     }
 }
 
+fn expected_usage<V>(btree: &BTreeMap<HashValue, (V, V)>) -> (usize, usize)
+where
+    V: crate::TestKey + crate::TestValue,
+{
+    (
+        btree.len(),
+        btree
+            .values()
+            .map(|(k, v)| k.key_size() + v.value_size())
+            .sum(),
+    )
+}
+
 fn assert_success<V>(
     db: &MockSnapshotStore<V, V>,
     expected_root_hash: HashValue,
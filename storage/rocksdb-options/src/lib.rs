@@ -1,8 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::RocksdbConfig;
-use rocksdb::Options;
+use aptos_config::config::{
+    RocksdbCfConfig, RocksdbCompactionStyle, RocksdbCompression, RocksdbConfig,
+};
+use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, DBCompressionType, Options};
 
 // TODO: Clean this up. It is currently separated into its own crate
 // to avoid circular dependencies, because it depends on aptos-config (which
@@ -20,3 +22,52 @@ pub fn gen_rocksdb_options(config: &RocksdbConfig, readonly: bool) -> Options {
 
     db_opts
 }
+
+/// Generates the options of a column family from the tuning configured for it. The config is
+/// expected to have been validated with `RocksdbConfig::validate`.
+pub fn gen_cf_options(config: &RocksdbConfig, cf_name: &str) -> Options {
+    gen_cf_options_from_cf_config(config.cf_config(cf_name))
+}
+
+/// Generates the options of a column family from its tuning, for column families whose tuning
+/// is adjusted before use.
+pub fn gen_cf_options_from_cf_config(cf_config: &RocksdbCfConfig) -> Options {
+    let mut cf_opts = Options::default();
+
+    let compression_per_level: Vec<_> = cf_config
+        .compression_per_level
+        .iter()
+        .copied()
+        .map(compression_type)
+        .collect();
+    if let Some(last) = compression_per_level.last() {
+        cf_opts.set_compression_type(*last);
+    }
+    cf_opts.set_compression_per_level(&compression_per_level);
+
+    cf_opts.set_compaction_style(match cf_config.compaction_style {
+        RocksdbCompactionStyle::Level => DBCompactionStyle::Level,
+        RocksdbCompactionStyle::Universal => DBCompactionStyle::Universal,
+    });
+
+    let mut table_opts = BlockBasedOptions::default();
+    if cf_config.block_cache_size > 0 {
+        let cache = Cache::new_lru_cache(cf_config.block_cache_size as usize)
+            .expect("Creating block cache should succeed.");
+        table_opts.set_block_cache(&cache);
+    }
+    if cf_config.bloom_filter_bits_per_key > 0 {
+        table_opts.set_bloom_filter(cf_config.bloom_filter_bits_per_key as i32, false);
+    }
+    cf_opts.set_block_based_table_factory(&table_opts);
+
+    cf_opts
+}
+
+fn compression_type(compression: RocksdbCompression) -> DBCompressionType {
+    match compression {
+        RocksdbCompression::None => DBCompressionType::None,
+        RocksdbCompression::Lz4 => DBCompressionType::Lz4,
+        RocksdbCompression::Lz4hc => DBCompressionType::Lz4hc,
+    }
+}
@@ -12,6 +12,11 @@
 //! families.  To use this library to store a kind of key-value pairs, the user needs to use the
 //! [`define_schema!`] macro to define the schema name, the types of key and value, and name of the
 //! column family.
+//!
+//! With the `failpoints` feature, latency or errors can be injected into reads and writes at
+//! runtime via the `schemadb::get` (which also covers `multi_get`), `schemadb::iter` and
+//! `schemadb::write_schemas` failpoints, e.g. `sleep(100)` to emulate a slow disk or `10%return`
+//! to fail one in ten operations.
 
 mod metrics;
 #[macro_use]
@@ -20,21 +25,27 @@ pub mod schema;
 use crate::{
     metrics::{
         APTOS_SCHEMADB_BATCH_COMMIT_BYTES, APTOS_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS,
-        APTOS_SCHEMADB_BATCH_PUT_LATENCY_SECONDS, APTOS_SCHEMADB_DELETES, APTOS_SCHEMADB_GET_BYTES,
-        APTOS_SCHEMADB_GET_LATENCY_SECONDS, APTOS_SCHEMADB_INCLUSIVE_RANGE_DELETES,
-        APTOS_SCHEMADB_ITER_BYTES, APTOS_SCHEMADB_ITER_LATENCY_SECONDS, APTOS_SCHEMADB_PUT_BYTES,
-        APTOS_SCHEMADB_RANGE_DELETES,
+        APTOS_SCHEMADB_BATCH_COMMIT_OPS, APTOS_SCHEMADB_BATCH_DELETE_LATENCY_SECONDS,
+        APTOS_SCHEMADB_BATCH_PUT_LATENCY_SECONDS, APTOS_SCHEMADB_CF_BATCH_PUT_LATENCY_SECONDS,
+        APTOS_SCHEMADB_CF_COMMIT_LATENCY_SECONDS, APTOS_SCHEMADB_DELETES,
+        APTOS_SCHEMADB_DELETE_BYTES, APTOS_SCHEMADB_GET_BYTES, APTOS_SCHEMADB_GET_LATENCY_SECONDS,
+        APTOS_SCHEMADB_INCLUSIVE_RANGE_DELETES, APTOS_SCHEMADB_ITER_BYTES,
+        APTOS_SCHEMADB_ITER_LATENCY_SECONDS, APTOS_SCHEMADB_MULTI_GET_KEYS,
+        APTOS_SCHEMADB_MULTI_GET_LATENCY_SECONDS, APTOS_SCHEMADB_PUT_BYTES,
+        APTOS_SCHEMADB_RANGE_DELETES, APTOS_SCHEMADB_READ_BYTES,
+        APTOS_SCHEMADB_SEEK_LATENCY_SECONDS, APTOS_SCHEMADB_WRITE_BYTES,
     },
     schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
 };
 use anyhow::{format_err, Result};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
-use std::{collections::HashMap, iter::Iterator, marker::PhantomData, path::Path};
+use fail::fail_point;
+use std::{collections::HashMap, iter::Iterator, marker::PhantomData, path::Path, time::Instant};
 
 /// Type alias to `rocksdb::ReadOptions`. See [`rocksdb doc`](https://github.com/pingcap/rust-rocksdb/blob/master/src/rocksdb_options.rs)
 pub use rocksdb::{
-    ColumnFamilyDescriptor, DBCompressionType, Options, ReadOptions, SliceTransform,
+    ColumnFamilyDescriptor, DBCompressionType, LiveFile, Options, ReadOptions, SliceTransform,
     DEFAULT_COLUMN_FAMILY_NAME,
 };
 pub type ColumnFamilyName = &'static str;
@@ -73,6 +84,9 @@ impl SchemaBatch {
         let _timer = APTOS_SCHEMADB_BATCH_PUT_LATENCY_SECONDS
             .with_label_values(&["unknown"])
             .start_timer();
+        let _cf_timer = APTOS_SCHEMADB_CF_BATCH_PUT_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let key = <S::Key as KeyCodec<S>>::encode_key(key)?;
         let value = <S::Value as ValueCodec<S>>::encode_value(value)?;
         self.rows
@@ -86,6 +100,9 @@ impl SchemaBatch {
 
     /// Adds a delete operation to the batch.
     pub fn delete<S: Schema>(&self, key: &S::Key) -> Result<()> {
+        let _timer = APTOS_SCHEMADB_BATCH_DELETE_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let key = <S::Key as KeyCodec<S>>::encode_key(key)?;
         self.rows
             .lock()
@@ -98,6 +115,9 @@ impl SchemaBatch {
 
     /// Adds a delete range operation that delete a range [start, end)
     pub fn delete_range<S: Schema>(&self, begin: &S::Key, end: &S::Key) -> Result<()> {
+        let _timer = APTOS_SCHEMADB_BATCH_DELETE_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let begin = <S::Key as KeyCodec<S>>::encode_key(begin)?;
         let end = <S::Key as KeyCodec<S>>::encode_key(end)?;
         self.rows
@@ -110,6 +130,9 @@ impl SchemaBatch {
 
     /// Adds a delete range operation that delete a range [start, end] including end
     pub fn delete_range_inclusive<S: Schema>(&self, begin: &S::Key, end: &S::Key) -> Result<()> {
+        let _timer = APTOS_SCHEMADB_BATCH_DELETE_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let begin = <S::Key as KeyCodec<S>>::encode_key(begin)?;
         let end = <S::Key as KeyCodec<S>>::encode_key(end)?;
         self.rows
@@ -148,11 +171,17 @@ where
 
     /// Seeks to the first key.
     pub fn seek_to_first(&mut self) {
+        let _timer = APTOS_SCHEMADB_SEEK_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         self.db_iter.seek_to_first();
     }
 
     /// Seeks to the last key.
     pub fn seek_to_last(&mut self) {
+        let _timer = APTOS_SCHEMADB_SEEK_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         self.db_iter.seek_to_last();
     }
 
@@ -162,6 +191,9 @@ where
     where
         SK: SeekKeyCodec<S>,
     {
+        let _timer = APTOS_SCHEMADB_SEEK_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
         self.db_iter.seek(&key);
         Ok(())
@@ -175,6 +207,9 @@ where
     where
         SK: SeekKeyCodec<S>,
     {
+        let _timer = APTOS_SCHEMADB_SEEK_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
         self.db_iter.seek_for_prev(&key);
         Ok(())
@@ -184,6 +219,9 @@ where
         let _timer = APTOS_SCHEMADB_ITER_LATENCY_SECONDS
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
             .start_timer();
+        fail_point!("schemadb::iter", |_| Err(format_err!(
+            "Injected error in schemadb::iter."
+        )));
 
         if !self.db_iter.valid() {
             self.db_iter.status()?;
@@ -195,6 +233,9 @@ where
         APTOS_SCHEMADB_ITER_BYTES
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
             .observe((raw_key.len() + raw_value.len()) as f64);
+        APTOS_SCHEMADB_READ_BYTES
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .inc_by((raw_key.len() + raw_value.len()) as u64);
 
         let key = <S::Key as KeyCodec<S>>::decode_key(raw_key)?;
         let value = <S::Value as ValueCodec<S>>::decode_value(raw_value)?;
@@ -286,6 +327,13 @@ impl DB {
         Ok(Self::log_construct(name, inner))
     }
 
+    /// Makes the writes of the primary instance visible to a DB opened with
+    /// `open_cf_as_secondary`.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.inner.try_catch_up_with_primary()?;
+        Ok(())
+    }
+
     fn log_construct(name: &'static str, inner: rocksdb::DB) -> DB {
         info!(rocksdb_name = name, "Opened RocksDB.");
         DB { name, inner }
@@ -296,6 +344,9 @@ impl DB {
         let _timer = APTOS_SCHEMADB_GET_LATENCY_SECONDS
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
             .start_timer();
+        fail_point!("schemadb::get", |_| Err(format_err!(
+            "Injected error in schemadb::get."
+        )));
 
         let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
         let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
@@ -304,12 +355,52 @@ impl DB {
         APTOS_SCHEMADB_GET_BYTES
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
             .observe(result.as_ref().map_or(0.0, |v| v.len() as f64));
+        APTOS_SCHEMADB_READ_BYTES
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .inc_by(result.as_ref().map_or(0, |v| v.len() as u64));
 
         result
             .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
             .transpose()
     }
 
+    /// Reads the records of multiple keys at once, via RocksDB's MultiGet, which looks the keys up
+    /// in batches rather than one at a time. The values are returned in the order of the keys.
+    pub fn multi_get<S: Schema>(&self, schema_keys: &[S::Key]) -> Result<Vec<Option<S::Value>>> {
+        let _timer = APTOS_SCHEMADB_MULTI_GET_LATENCY_SECONDS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .start_timer();
+        fail_point!("schemadb::get", |_| Err(format_err!(
+            "Injected error in schemadb::get."
+        )));
+        APTOS_SCHEMADB_MULTI_GET_KEYS
+            .with_label_values(&[S::COLUMN_FAMILY_NAME])
+            .observe(schema_keys.len() as f64);
+
+        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+        let keys = schema_keys
+            .iter()
+            .map(|key| Ok((cf_handle, <S::Key as KeyCodec<S>>::encode_key(key)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.inner
+            .multi_get_cf(keys)
+            .into_iter()
+            .map(|result| {
+                let result = result?;
+                APTOS_SCHEMADB_GET_BYTES
+                    .with_label_values(&[S::COLUMN_FAMILY_NAME])
+                    .observe(result.as_ref().map_or(0.0, |v| v.len() as f64));
+                APTOS_SCHEMADB_READ_BYTES
+                    .with_label_values(&[S::COLUMN_FAMILY_NAME])
+                    .inc_by(result.as_ref().map_or(0, |v| v.len() as u64));
+                result
+                    .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
+                    .transpose()
+            })
+            .collect()
+    }
+
     /// Writes single record.
     pub fn put<S: Schema>(&self, key: &S::Key, value: &S::Value) -> Result<()> {
         // Not necessary to use a batch, but we'd like a central place to bump counters.
@@ -364,6 +455,10 @@ impl DB {
         let _timer = APTOS_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS
             .with_label_values(&[self.name])
             .start_timer();
+        let commit_start = Instant::now();
+        fail_point!("schemadb::write_schemas", |_| Err(format_err!(
+            "Injected error in schemadb::write_schemas."
+        )));
         let rows_locked = batch.rows.lock();
 
         let mut db_batch = rocksdb::WriteBatch::default();
@@ -388,16 +483,33 @@ impl DB {
         self.inner.write_opt(db_batch, &default_write_options())?;
 
         // Bump counters only after DB write succeeds.
+        let commit_latency = commit_start.elapsed().as_secs_f64();
+        let mut num_ops = 0;
         for (cf_name, rows) in rows_locked.iter() {
+            // The batch is committed atomically, so each column family written to is attributed
+            // the latency of the whole commit.
+            APTOS_SCHEMADB_CF_COMMIT_LATENCY_SECONDS
+                .with_label_values(&[cf_name])
+                .observe(commit_latency);
+            num_ops += rows.len();
             for write_op in rows {
                 match write_op {
                     WriteOp::Value { key, value } => {
                         APTOS_SCHEMADB_PUT_BYTES
                             .with_label_values(&[cf_name])
                             .observe((key.len() + value.len()) as f64);
+                        APTOS_SCHEMADB_WRITE_BYTES
+                            .with_label_values(&[cf_name])
+                            .inc_by((key.len() + value.len()) as u64);
                     }
-                    WriteOp::Deletion { key: _ } => {
+                    WriteOp::Deletion { key } => {
                         APTOS_SCHEMADB_DELETES.with_label_values(&[cf_name]).inc();
+                        APTOS_SCHEMADB_DELETE_BYTES
+                            .with_label_values(&[cf_name])
+                            .observe(key.len() as f64);
+                        APTOS_SCHEMADB_WRITE_BYTES
+                            .with_label_values(&[cf_name])
+                            .inc_by(key.len() as u64);
                     }
                     WriteOp::DeletionRange { begin: _, end: _ } => {
                         APTOS_SCHEMADB_RANGE_DELETES
@@ -415,6 +527,9 @@ impl DB {
         APTOS_SCHEMADB_BATCH_COMMIT_BYTES
             .with_label_values(&[self.name])
             .observe(serialized_size as f64);
+        APTOS_SCHEMADB_BATCH_COMMIT_OPS
+            .with_label_values(&[self.name])
+            .observe(num_ops as f64);
 
         Ok(())
     }
@@ -446,6 +561,19 @@ impl DB {
             })
     }
 
+    /// Compacts the whole key range of a column family, which reclaims the space taken by deleted
+    /// data right away instead of as background compactions get to it.
+    pub fn compact_cf(&self, cf_name: &str) -> Result<()> {
+        self.inner
+            .compact_range_cf::<&[u8], &[u8]>(self.get_cf_handle(cf_name)?, None, None);
+        Ok(())
+    }
+
+    /// Gets the metadata of the live SST files of all column families.
+    pub fn get_live_files(&self) -> Result<Vec<LiveFile>> {
+        Ok(self.inner.live_files()?)
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
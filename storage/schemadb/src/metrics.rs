@@ -45,6 +45,32 @@ pub static APTOS_SCHEMADB_GET_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(||
     .unwrap()
 });
 
+pub static APTOS_SCHEMADB_MULTI_GET_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_multi_get_latency_seconds",
+        // metric description
+        "Aptos schemadb multi get latency in seconds",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_MULTI_GET_KEYS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_multi_get_keys",
+        // metric description
+        "Aptos schemadb number of keys read per multi get call",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 14).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static APTOS_SCHEMADB_GET_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -133,3 +159,98 @@ pub static APTOS_SCHEMADB_BATCH_PUT_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::
     )
     .unwrap()
 });
+
+pub static APTOS_SCHEMADB_CF_BATCH_PUT_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_cf_batch_put_latency_seconds",
+        // metric description
+        "Aptos schemadb latency in seconds of the schema batch puts to a column family",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_BATCH_DELETE_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_batch_delete_latency_seconds",
+        // metric description
+        "Aptos schemadb schema batch delete latency in seconds",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_SEEK_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_seek_latency_seconds",
+        // metric description
+        "Aptos schemadb iterator seek latency in seconds",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_CF_COMMIT_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_cf_commit_latency_seconds",
+        // metric description
+        "Aptos schemadb latency in seconds of the schema batch commits writing to a column family",
+        // metric labels (dimensions)
+        &["cf_name"],
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_DELETE_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_delete_bytes",
+        // metric description
+        "Aptos schemadb delete call deleted key size in bytes",
+        // metric labels (dimensions)
+        &["cf_name"]
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_READ_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_schemadb_read_bytes_total",
+        "Aptos schemadb bytes read by get calls and iterators",
+        &["cf_name"]
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_WRITE_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_schemadb_write_bytes_total",
+        "Aptos schemadb bytes of the keys and values written or deleted by schema batches",
+        &["cf_name"]
+    )
+    .unwrap()
+});
+
+pub static APTOS_SCHEMADB_BATCH_COMMIT_OPS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_batch_commit_ops",
+        // metric description
+        "Aptos schemadb number of write operations in a schema batch commit",
+        // metric labels (dimensions)
+        &["db_name"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 24).unwrap(),
+    )
+    .unwrap()
+});
@@ -173,6 +173,22 @@ fn test_schema_put_get() {
     );
 }
 
+#[test]
+fn test_schema_multi_get() {
+    let db = TestDB::new();
+
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
+    db.put::<TestSchema2>(&TestField(1), &TestField(3)).unwrap();
+
+    assert_eq!(
+        db.multi_get::<TestSchema1>(&[TestField(2), TestField(1), TestField(0)])
+            .unwrap(),
+        vec![Some(TestField(2)), None, Some(TestField(0))],
+    );
+    assert_eq!(db.multi_get::<TestSchema2>(&[]).unwrap(), vec![]);
+}
+
 fn test_schemabatch_delete_range_util(begin: u32, end: u32, is_inclusive: bool) {
     let db = TestDB::new();
     let db_batch = SchemaBatch::new();
@@ -404,6 +420,15 @@ fn test_open_as_secondary() {
         db_sec.get::<TestSchema1>(&TestField(0)).unwrap(),
         Some(TestField(0)),
     );
+
+    // Writes of the primary are only visible once the secondary catches up.
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+    assert_eq!(db_sec.get::<TestSchema1>(&TestField(1)).unwrap(), None);
+    db_sec.try_catch_up_with_primary().unwrap();
+    assert_eq!(
+        db_sec.get::<TestSchema1>(&TestField(1)).unwrap(),
+        Some(TestField(1)),
+    );
 }
 
 #[test]
@@ -441,6 +466,40 @@ fn test_report_size() {
     );
 }
 
+#[test]
+fn test_compact_cf() {
+    let db = TestDB::new();
+
+    // Each flush creates an SST file in level 0, too few to trigger a compaction.
+    for i in 0..3 {
+        db.put::<TestSchema1>(&TestField(i), &TestField(i)).unwrap();
+        db.flush_cf("TestCF1").unwrap();
+    }
+    let live_files = db.get_live_files().unwrap();
+    assert_eq!(
+        live_files
+            .iter()
+            .filter(|file| file.column_family_name == "TestCF1" && file.level == 0)
+            .count(),
+        3
+    );
+
+    db.compact_cf("TestCF1").unwrap();
+    let live_files: Vec<_> = db
+        .get_live_files()
+        .unwrap()
+        .into_iter()
+        .filter(|file| file.column_family_name == "TestCF1")
+        .collect();
+    assert_eq!(live_files.len(), 1);
+    assert_ne!(live_files[0].level, 0);
+    assert_eq!(live_files[0].num_entries, 3);
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(2)).unwrap(),
+        Some(TestField(2))
+    );
+}
+
 #[test]
 fn test_checkpoint() {
     let tmpdir = aptos_temppath::TempPath::new();
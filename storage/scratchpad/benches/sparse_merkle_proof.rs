@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_types::{proof::SparseMerkleProof, state_store::state_value::StateValue};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{distributions::Standard, prelude::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use scratchpad::test_utils::naive_smt::NaiveSmt;
+
+/// The number of leaves in the tree the proofs are of.
+const NUM_LEAVES: usize = 100_000;
+
+/// Proofs of elements of the same tree, with the values they prove.
+struct Batch {
+    root_hash: HashValue,
+    proofs: Vec<(SparseMerkleProof, HashValue, StateValue)>,
+}
+
+impl Batch {
+    fn elements(&self) -> Vec<(&SparseMerkleProof, HashValue, Option<&StateValue>)> {
+        self.proofs
+            .iter()
+            .map(|(proof, key, value)| (proof, *key, Some(value)))
+            .collect()
+    }
+}
+
+fn gen_batches(batch_sizes: &[usize]) -> Vec<Batch> {
+    let mut rng = rng();
+    let leaves =
+        std::iter::repeat_with(|| (HashValue::random_with_rng(&mut rng), gen_value(&mut rng)))
+            .take(NUM_LEAVES)
+            .collect::<Vec<_>>();
+    let mut naive_smt = NaiveSmt::new(
+        leaves
+            .iter()
+            .map(|(key, value)| (*key, value))
+            .collect::<Vec<_>>()
+            .as_slice(),
+    );
+    let root_hash = naive_smt.get_root_hash();
+
+    batch_sizes
+        .iter()
+        .map(|batch_size| Batch {
+            root_hash,
+            proofs: leaves
+                .choose_multiple(&mut rng, *batch_size)
+                .map(|(key, value)| (naive_smt.get_proof(key).into(), *key, value.clone()))
+                .collect(),
+        })
+        .collect()
+}
+
+fn gen_value(rng: &mut StdRng) -> StateValue {
+    rng.sample_iter(&Standard)
+        .take(100)
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+fn rng() -> StdRng {
+    let seed: &[_] = &[1, 2, 3, 4];
+    let mut actual_seed = [0u8; 32];
+    actual_seed[..seed.len()].copy_from_slice(seed);
+
+    StdRng::from_seed(actual_seed)
+}
+
+fn sparse_merkle_proof_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify sparse merkle proofs");
+
+    for batch in gen_batches(&[1, 10, 100, 1000, 10000]) {
+        let batch_size = batch.proofs.len();
+        let elements = batch.elements();
+        group.throughput(Throughput::Elements(batch_size as u64));
+
+        group.bench_function(BenchmarkId::new("verify", batch_size), |b| {
+            b.iter(|| {
+                for (proof, key, value) in &elements {
+                    proof.verify(batch.root_hash, *key, *value).unwrap();
+                }
+            })
+        });
+
+        group.bench_function(BenchmarkId::new("batch_verify", batch_size), |b| {
+            b.iter(|| {
+                SparseMerkleProof::batch_verify(batch.root_hash, elements.iter().cloned()).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sparse_merkle_proof_benches);
+criterion_main!(benches);
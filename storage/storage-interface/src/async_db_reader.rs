@@ -0,0 +1,287 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics::{ASYNC_READS_PENDING, TIMER},
+    DbReader, Order,
+};
+use anyhow::{format_err, Result};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{EventWithTransactionContext, EventWithVersion},
+    event::EventKey,
+    state_store::{state_key::StateKey, state_value::StateValue, table::TableHandle},
+    transaction::{TransactionListWithProof, TransactionWithProof, Version},
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Runs reads of the DB on the blocking threads of the tokio runtime, so that async callers such
+/// as the API don't pin the worker threads of their runtime while RocksDB reads.
+///
+/// At most `max_concurrent_reads` reads run at once. The others wait for one of them to complete
+/// without taking a thread, in the order they were made. A read that panics fails on its own.
+#[derive(Clone)]
+pub struct AsyncDbReader {
+    reader: Arc<dyn DbReader>,
+    permits: Arc<Semaphore>,
+}
+
+/// Counts a read in `ASYNC_READS_PENDING` until it starts running or is dropped.
+struct PendingRead;
+
+impl PendingRead {
+    fn new() -> Self {
+        ASYNC_READS_PENDING.inc();
+        Self
+    }
+}
+
+impl Drop for PendingRead {
+    fn drop(&mut self) {
+        ASYNC_READS_PENDING.dec();
+    }
+}
+
+impl AsyncDbReader {
+    pub fn new(reader: Arc<dyn DbReader>, max_concurrent_reads: usize) -> Self {
+        Self {
+            reader,
+            permits: Arc::new(Semaphore::new(max_concurrent_reads.max(1))),
+        }
+    }
+
+    pub fn reader(&self) -> &Arc<dyn DbReader> {
+        &self.reader
+    }
+
+    /// Runs the given read on a blocking thread once fewer than `max_concurrent_reads` reads run,
+    /// timed under the given name. The read may do several reads of the DB, which then count as
+    /// one. It keeps running if the caller goes away, in which case the result is dropped.
+    pub async fn read<T, F>(&self, name: &'static str, read: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn DbReader) -> Result<T> + Send + 'static,
+    {
+        let permit = {
+            let _pending = PendingRead::new();
+            self.permits
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|_| format_err!("The async DB reader is closed."))?
+        };
+        let reader = self.reader.clone();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _timer = TIMER.with_label_values(&[name]).start_timer();
+            read(reader.as_ref())
+        })
+        .await
+        .map_err(|err| format_err!("The DB read {} failed to complete: {}", name, err))?
+    }
+
+    pub async fn get_state_value_by_version(
+        &self,
+        state_key: StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        self.read("async_get_state_value_by_version", move |reader| {
+            reader.get_state_value_by_version(&state_key, version)
+        })
+        .await
+    }
+
+    pub async fn get_state_values_by_version(
+        &self,
+        state_keys: Vec<StateKey>,
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        self.read("async_get_state_values_by_version", move |reader| {
+            reader.get_state_values_by_version(&state_keys, version)
+        })
+        .await
+    }
+
+    pub async fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.read("async_get_transactions", move |reader| {
+            reader.get_transactions(start_version, batch_size, ledger_version, fetch_events)
+        })
+        .await
+    }
+
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.read("async_get_transaction_by_hash", move |reader| {
+            reader.get_transaction_by_hash(hash, ledger_version, fetch_events)
+        })
+        .await
+    }
+
+    pub async fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        self.read("async_get_transaction_by_version", move |reader| {
+            reader.get_transaction_by_version(version, ledger_version, fetch_events)
+        })
+        .await
+    }
+
+    pub async fn get_events(
+        &self,
+        event_key: EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.read("async_get_events", move |reader| {
+            reader.get_events(&event_key, start, order, limit, ledger_version)
+        })
+        .await
+    }
+
+    pub async fn get_events_by_version_range(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.read("async_get_events_by_version_range", move |reader| {
+            reader.get_events_by_version_range(start_version, limit, ledger_version)
+        })
+        .await
+    }
+
+    pub async fn get_events_with_transaction_context(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithTransactionContext>> {
+        self.read("async_get_events_with_transaction_context", move |reader| {
+            reader.get_events_with_transaction_context(start_version, limit, ledger_version)
+        })
+        .await
+    }
+
+    pub async fn get_table_items(
+        &self,
+        handle: TableHandle,
+        cursor: Option<Vec<u8>>,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<(Vec<u8>, StateValue)>> {
+        self.read("async_get_table_items", move |reader| {
+            reader.get_table_items(handle, cursor, limit, version)
+        })
+        .await
+    }
+
+    pub async fn get_coin_balances(
+        &self,
+        address: AccountAddress,
+    ) -> Result<(Version, Vec<(TypeTag, u64)>)> {
+        self.read("async_get_coin_balances", move |reader| {
+            reader.get_coin_balances(address)
+        })
+        .await
+    }
+
+    pub async fn get_top_coin_holders(
+        &self,
+        coin_type: TypeTag,
+        limit: u64,
+    ) -> Result<(Version, Vec<(AccountAddress, u64)>)> {
+        self.read("async_get_top_coin_holders", move |reader| {
+            reader.get_top_coin_holders(coin_type, limit)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockDbReaderWriter;
+    use futures::future::join_all;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
+
+    #[tokio::test]
+    async fn test_get_state_value_by_version() {
+        let reader = AsyncDbReader::new(Arc::new(MockDbReaderWriter), 2);
+        let reads = (0..10).map(|i| {
+            let state_key = StateKey::Raw(format!("test_key_{}", i).into_bytes());
+            reader.get_state_value_by_version(state_key, 0)
+        });
+        for (i, result) in join_all(reads).await.into_iter().enumerate() {
+            assert_eq!(
+                result.unwrap(),
+                Some(StateValue::from(format!("test_key_{}", i).into_bytes()))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_error() {
+        let reader = AsyncDbReader::new(Arc::new(MockDbReaderWriter), 1);
+        let result: Result<()> = reader
+            .read("test_read_error", |_| Err(format_err!("Failed to read.")))
+            .await;
+        assert_eq!(result.unwrap_err().to_string(), "Failed to read.");
+    }
+
+    #[tokio::test]
+    async fn test_read_panic() {
+        let reader = AsyncDbReader::new(Arc::new(MockDbReaderWriter), 1);
+        let result: Result<()> = reader
+            .read("test_read_panic", |_| panic!("Failed to read."))
+            .await;
+        assert!(result.is_err());
+
+        // The panic doesn't take the permit with it.
+        let result = reader.read("test_read_after_panic", |_| Ok(1)).await;
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_max_concurrent_reads() {
+        let reader = AsyncDbReader::new(Arc::new(MockDbReaderWriter), 2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let reads = (0..10).map(|_| {
+            let running = running.clone();
+            let max_running = max_running.clone();
+            reader.read("test_max_concurrent_reads", move |_| {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(10));
+                running.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            })
+        });
+        for result in join_all(reads).await {
+            result.unwrap();
+        }
+        assert!(max_running.load(Ordering::SeqCst) <= 2);
+    }
+}
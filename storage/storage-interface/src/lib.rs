@@ -9,7 +9,7 @@ use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::CORE_CODE_ADDRESS,
-    contract_event::EventWithVersion,
+    contract_event::{EventWithTransactionContext, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
@@ -32,10 +32,12 @@ use aptos_types::{
     },
     write_set::WriteSet,
 };
+use move_deps::move_core_types::language_storage::{ModuleId, TypeTag};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 
+pub mod async_db_reader;
 pub mod async_proof_fetcher;
 pub mod cached_state_view;
 mod executed_trees;
@@ -95,6 +97,31 @@ pub enum Order {
     Descending,
 }
 
+/// The types of transactions a [TransactionFilter] can select.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransactionType {
+    User,
+    BlockMetadata,
+    StateCheckpoint,
+}
+
+/// Selects the transactions returned by `DbReader::get_filtered_transaction_versions`. A
+/// transaction matches if it matches every field that is set.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TransactionFilter {
+    pub transaction_type: Option<TransactionType>,
+    /// Whether the transaction executed successfully, rather than being kept with a failed status.
+    pub success: Option<bool>,
+    /// Only user transactions have a sender.
+    pub sender: Option<AccountAddress>,
+}
+
+impl TransactionFilter {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 /// Trait that is implemented by a DB that supports certain public (to client) read APIs
 /// expected of an Aptos DB
 #[allow(unused_variables)]
@@ -124,6 +151,21 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// See [AptosDB::get_filtered_transaction_versions].
+    ///
+    /// [AptosDB::get_filtered_transaction_versions]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_filtered_transaction_versions
+    fn get_filtered_transaction_versions(
+        &self,
+        start_version: Version,
+        limit: u64,
+        max_scanned: u64,
+        ledger_version: Version,
+        filter: &TransactionFilter,
+    ) -> Result<(Vec<Version>, Version)> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_transaction_by_hash].
     ///
     /// [AptosDB::get_transaction_by_hash]: ../aptosdb/struct.AptosDB.html#method.get_transaction_by_hash
@@ -136,6 +178,28 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// See [AptosDB::get_transaction_version_by_hash].
+    ///
+    /// [AptosDB::get_transaction_version_by_hash]: ../aptosdb/struct.AptosDB.html#method.get_transaction_version_by_hash
+    fn get_transaction_version_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<Version>> {
+        unimplemented!()
+    }
+
+    /// See [AptosDB::get_auth_key_history].
+    ///
+    /// [AptosDB::get_auth_key_history]: ../aptosdb/struct.AptosDB.html#method.get_auth_key_history
+    fn get_auth_key_history(
+        &self,
+        address: AccountAddress,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, Vec<u8>)>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_transaction_by_version].
     ///
     /// [AptosDB::get_transaction_by_version]: ../aptosdb/struct.AptosDB.html#method.get_transaction_by_version
@@ -204,6 +268,29 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the events emitted by `limit` consecutive transactions from `start_version`, in
+    /// the order they were emitted.
+    fn get_events_by_version_range(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        unimplemented!()
+    }
+
+    /// Returns the events emitted by `limit` consecutive transactions from `start_version`, like
+    /// `get_events_by_version_range`, each along with the hash, sender and status of the
+    /// transaction that emitted it.
+    fn get_events_with_transaction_context(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithTransactionContext>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_block_timestamp].
     ///
     /// [AptosDB::get_block_timestamp]:
@@ -216,6 +303,13 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns whether the transactions of the block containing the input transaction version used
+    /// up the block gas limit in effect for it, so the transactions proposed after its last one
+    /// were retried in a later block.
+    fn get_block_gas_limit_reached(&self, version: Version) -> Result<bool> {
+        unimplemented!()
+    }
+
     /// Returns the start_version, end_version and NewBlockEvent of the block containing the input
     /// transaction version.
     fn get_block_info_by_version(
@@ -266,6 +360,18 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the resources of an account at a version whose types are declared in a module,
+    /// i.e. those `get_state_values_by_key_prefix` returns for the address which are of the
+    /// module, without reading the others.
+    fn get_resources_by_module(
+        &self,
+        address: AccountAddress,
+        module_id: &ModuleId,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        unimplemented!()
+    }
+
     /// Returns the latest ledger info, if any.
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         unimplemented!()
@@ -364,6 +470,19 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Gets the latest state values of the given keys up to the given version, in the order of
+    /// the keys, as a single read.
+    fn get_state_values_by_version(
+        &self,
+        state_keys: &[StateKey],
+        version: Version,
+    ) -> Result<Vec<Option<StateValue>>> {
+        state_keys
+            .iter()
+            .map(|state_key| self.get_state_value_by_version(state_key, version))
+            .collect()
+    }
+
     /// Returns the proof of the given state key and version.
     fn get_state_proof_by_version_ext(
         &self,
@@ -500,10 +619,45 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Get up to `limit` items of the table, as raw keys and their values at `version`, using the
+    /// keys indexed by the internal indexer. Items are in the order of the BCS encoding of their
+    /// keys, starting after the `cursor` key if given.
+    fn get_table_items(
+        &self,
+        handle: TableHandle,
+        cursor: Option<Vec<u8>>,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<(Vec<u8>, StateValue)>> {
+        unimplemented!()
+    }
+
     /// Returns whether the internal indexer DB has been enabled or not
     fn indexer_enabled(&self) -> bool {
         unimplemented!()
     }
+
+    /// Get the latest balances of the account by coin type, from the coin balance index of the
+    /// internal indexer, along with the version the index is built up to when read.
+    fn get_coin_balances(&self, address: AccountAddress) -> Result<(Version, Vec<(TypeTag, u64)>)> {
+        unimplemented!()
+    }
+
+    /// Get up to `limit` accounts holding the most of the coin, from the largest balance to the
+    /// smallest, from the coin balance index of the internal indexer, along with the version the
+    /// index is built up to when read.
+    fn get_top_coin_holders(
+        &self,
+        coin_type: TypeTag,
+        limit: u64,
+    ) -> Result<(Version, Vec<(AccountAddress, u64)>)> {
+        unimplemented!()
+    }
+
+    /// Returns whether the coin balance index of the internal indexer has been enabled or not
+    fn balance_index_enabled(&self) -> bool {
+        unimplemented!()
+    }
 }
 
 impl MoveStorage for &dyn DbReader {
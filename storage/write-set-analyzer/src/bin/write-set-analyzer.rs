@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, format_err, Context, Result};
+use aptos_config::config::RocksdbConfigs;
+use aptos_temppath::TempPath;
+use aptos_types::transaction::Version;
+use aptosdb::AptosDB;
+use std::{fs::File, io, path::PathBuf, str::FromStr};
+use storage_interface::DbReader;
+use structopt::StructOpt;
+use write_set_analyzer::{WriteSetAnalyzer, DEFAULT_NUM_HOTTEST_TABLES};
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "write-set-analyzer",
+    about = "Classify and size the write sets of a range of transactions in a local DB."
+)]
+struct Opt {
+    #[structopt(parse(from_os_str))]
+    db_dir: PathBuf,
+
+    /// The first version of the range.
+    #[structopt(long, default_value = "0")]
+    start_version: Version,
+
+    /// The version after the last version of the range. Defaults to the version after the latest
+    /// version in the DB.
+    #[structopt(long)]
+    end_version: Option<Version>,
+
+    /// The number of the table handles written the most to report. Defaults to 20.
+    #[structopt(long)]
+    num_hottest_tables: Option<usize>,
+
+    /// `json` or `csv`.
+    #[structopt(long, default_value = "json")]
+    format: OutputFormat,
+
+    /// The file to write the statistics to, instead of stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+enum OutputFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => bail!("Unknown output format {}, expected json or csv.", s),
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let opt = Opt::from_args();
+
+    // The DB is opened as secondary so the tool is usable along side a running node on the same
+    // DB. Using a TempPath since it won't run for long.
+    let tmpdir = TempPath::new();
+    let db = AptosDB::open_as_secondary(
+        opt.db_dir.as_path(),
+        tmpdir.as_ref(),
+        RocksdbConfigs::default(),
+    )
+    .with_context(|| format_err!("Failed to open DB."))?;
+
+    let end_version = match opt.end_version {
+        Some(end_version) => end_version,
+        None => db.get_latest_version()? + 1,
+    };
+    ensure!(
+        opt.start_version <= end_version,
+        "Start version {} is after end version {}.",
+        opt.start_version,
+        end_version,
+    );
+
+    let analyzer = WriteSetAnalyzer::analyze_db(
+        &db,
+        opt.start_version,
+        (end_version - opt.start_version) as usize,
+    )
+    .with_context(|| format_err!("Failed to analyze write sets."))?;
+    let report = analyzer.report(opt.num_hottest_tables.unwrap_or(DEFAULT_NUM_HOTTEST_TABLES));
+
+    let writer: Box<dyn io::Write> = match &opt.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    match opt.format {
+        OutputFormat::Json => report.write_json(writer),
+        OutputFormat::Csv => report.write_csv(writer),
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! This crate computes statistics on the write sets of a range of transactions in AptosDB: how
+//! many writes each kind of transaction, each module and each resource type account for, how many
+//! bytes they write, and which table handles are written the most. The statistics are meant to
+//! guide decisions on the gas schedule and on the layout of the storage.
+
+use anyhow::Result;
+use aptos_types::{
+    access_path::Path,
+    state_store::{state_key::StateKey, table::TableHandle},
+    transaction::{Transaction, TransactionPayload, Version},
+    write_set::{WriteOp, WriteSet},
+};
+use aptosdb::AptosDB;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+/// The number of table handles reported by default.
+pub const DEFAULT_NUM_HOTTEST_TABLES: usize = 20;
+
+/// Statistics on the writes to one item, e.g. a module or a resource type.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct WriteStats {
+    /// The number of transactions writing to the item.
+    pub transactions: u64,
+    pub creations: u64,
+    pub modifications: u64,
+    pub deletions: u64,
+    /// The number of bytes of the values written, keys excluded.
+    pub bytes_written: u64,
+}
+
+impl WriteStats {
+    pub fn num_ops(&self) -> u64 {
+        self.creations + self.modifications + self.deletions
+    }
+
+    fn add(&mut self, op: &WriteOp) {
+        match op {
+            WriteOp::Creation(bytes) => {
+                self.creations += 1;
+                self.bytes_written += bytes.len() as u64;
+            }
+            WriteOp::Modification(bytes) => {
+                self.modifications += 1;
+                self.bytes_written += bytes.len() as u64;
+            }
+            WriteOp::Deletion => self.deletions += 1,
+        }
+    }
+}
+
+/// Accumulates the statistics of the write sets of transactions, one transaction at a time.
+#[derive(Debug, Default)]
+pub struct WriteSetAnalyzer {
+    start_version: Option<Version>,
+    num_transactions: u64,
+    transaction_types: BTreeMap<String, WriteStats>,
+    modules: BTreeMap<String, WriteStats>,
+    resource_types: BTreeMap<String, WriteStats>,
+    table_handles: BTreeMap<TableHandle, WriteStats>,
+}
+
+impl WriteSetAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Analyzes the write sets of `num_transactions` transactions from `start_version`, streaming
+    /// them from the DB.
+    pub fn analyze_db(
+        db: &AptosDB,
+        start_version: Version,
+        num_transactions: usize,
+    ) -> Result<Self> {
+        let mut analyzer = Self::new();
+        analyzer.start_version = Some(start_version);
+        for res in db.get_transaction_and_write_set_iter(start_version, num_transactions)? {
+            let (txn, write_set) = res?;
+            analyzer.add_transaction(&txn, &write_set);
+        }
+        Ok(analyzer)
+    }
+
+    pub fn add_transaction(&mut self, txn: &Transaction, write_set: &WriteSet) {
+        self.num_transactions += 1;
+
+        let txn_stats = self
+            .transaction_types
+            .entry(transaction_type(txn))
+            .or_default();
+        txn_stats.transactions += 1;
+
+        let mut touched_modules = BTreeSet::new();
+        let mut touched_resource_types = BTreeSet::new();
+        let mut touched_table_handles = BTreeSet::new();
+        for (state_key, op) in write_set {
+            txn_stats.add(op);
+            match state_key {
+                StateKey::AccessPath(access_path) => {
+                    // Access paths that aren't paths of Move code or resources are skipped.
+                    match bcs::from_bytes::<Path>(&access_path.path) {
+                        Ok(Path::Code(module_id)) => record(
+                            &mut self.modules,
+                            &mut touched_modules,
+                            module_id.short_str_lossless(),
+                            op,
+                        ),
+                        Ok(Path::Resource(struct_tag)) => {
+                            let module = struct_tag.module_id().short_str_lossless();
+                            // Instances of generic resources are counted under their generic
+                            // type, e.g. `0x1::coin::CoinStore`.
+                            let resource_type = format!("{}::{}", module, struct_tag.name);
+                            record(&mut self.modules, &mut touched_modules, module, op);
+                            record(
+                                &mut self.resource_types,
+                                &mut touched_resource_types,
+                                resource_type,
+                                op,
+                            );
+                        }
+                        Err(_) => (),
+                    }
+                }
+                StateKey::TableItem { handle, .. } => record(
+                    &mut self.table_handles,
+                    &mut touched_table_handles,
+                    *handle,
+                    op,
+                ),
+                StateKey::Raw(_) => (),
+            }
+        }
+    }
+
+    /// Produces the report of the transactions analyzed so far, with the given number of the
+    /// hottest table handles, i.e. those written the most.
+    pub fn report(&self, num_hottest_tables: usize) -> WriteSetReport {
+        let mut hottest_table_handles: Vec<_> = self
+            .table_handles
+            .iter()
+            .map(|(handle, stats)| ReportRow {
+                name: handle.0.to_string(),
+                stats: *stats,
+            })
+            .collect();
+        hottest_table_handles.sort_by(|a, b| {
+            (b.stats.num_ops(), b.stats.bytes_written)
+                .cmp(&(a.stats.num_ops(), a.stats.bytes_written))
+        });
+        hottest_table_handles.truncate(num_hottest_tables);
+
+        WriteSetReport {
+            start_version: self.start_version,
+            num_transactions: self.num_transactions,
+            transaction_types: rows_by_bytes_written(&self.transaction_types),
+            modules: rows_by_bytes_written(&self.modules),
+            resource_types: rows_by_bytes_written(&self.resource_types),
+            hottest_table_handles,
+        }
+    }
+}
+
+/// The statistics of the write sets of a range of transactions.
+#[derive(Debug, Serialize)]
+pub struct WriteSetReport {
+    pub start_version: Option<Version>,
+    pub num_transactions: u64,
+    /// Per kind of transaction, with user transactions calling script functions classified by
+    /// the function they call.
+    pub transaction_types: Vec<ReportRow>,
+    pub modules: Vec<ReportRow>,
+    pub resource_types: Vec<ReportRow>,
+    pub hottest_table_handles: Vec<ReportRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportRow {
+    pub name: String,
+    #[serde(flatten)]
+    pub stats: WriteStats,
+}
+
+impl WriteSetReport {
+    pub fn write_json<W: Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Writes the report as CSV, with a row per item and the kind of the item, e.g. `module`, in
+    /// the first column.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<()> {
+        writeln!(
+            writer,
+            "category,name,transactions,creations,modifications,deletions,bytes_written"
+        )?;
+        for (category, rows) in [
+            ("transaction_type", &self.transaction_types),
+            ("module", &self.modules),
+            ("resource_type", &self.resource_types),
+            ("table_handle", &self.hottest_table_handles),
+        ] {
+            for row in rows {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{}",
+                    category,
+                    csv_field(&row.name),
+                    row.stats.transactions,
+                    row.stats.creations,
+                    row.stats.modifications,
+                    row.stats.deletions,
+                    row.stats.bytes_written,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn transaction_type(txn: &Transaction) -> String {
+    match txn {
+        Transaction::UserTransaction(user_txn) => match user_txn.payload() {
+            TransactionPayload::ScriptFunction(script_function) => format!(
+                "{}::{}",
+                script_function.module().short_str_lossless(),
+                script_function.function()
+            ),
+            TransactionPayload::Script(_) => "script".to_string(),
+            TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+            TransactionPayload::WriteSet(_) => "write_set".to_string(),
+        },
+        Transaction::GenesisTransaction(_) => "genesis".to_string(),
+        Transaction::BlockMetadata(_) => "block_metadata".to_string(),
+        Transaction::StateCheckpoint(_) => "state_checkpoint".to_string(),
+    }
+}
+
+/// Records a write to the given item, counting the transaction writing it unless it wrote to the
+/// item already.
+fn record<K: Clone + Ord>(
+    stats: &mut BTreeMap<K, WriteStats>,
+    touched: &mut BTreeSet<K>,
+    key: K,
+    op: &WriteOp,
+) {
+    let item_stats = stats.entry(key.clone()).or_default();
+    if touched.insert(key) {
+        item_stats.transactions += 1;
+    }
+    item_stats.add(op);
+}
+
+fn rows_by_bytes_written(stats: &BTreeMap<String, WriteStats>) -> Vec<ReportRow> {
+    let mut rows: Vec<_> = stats
+        .iter()
+        .map(|(name, stats)| ReportRow {
+            name: name.clone(),
+            stats: *stats,
+        })
+        .collect();
+    // The sort is stable, so rows writing as many bytes stay ordered by name.
+    rows.sort_by(|a, b| b.stats.bytes_written.cmp(&a.stats.bytes_written));
+    rows
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,160 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_crypto::HashValue;
+use aptos_types::{
+    access_path::AccessPath, account_address::AccountAddress, write_set::WriteSetMut,
+};
+use move_deps::move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
+};
+
+fn resource(module: &str, name: &str, type_params: Vec<TypeTag>) -> StateKey {
+    StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::ONE,
+        StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params,
+        },
+    )))
+}
+
+fn code(module: &str) -> StateKey {
+    StateKey::AccessPath(AccessPath::code_access_path(ModuleId::new(
+        AccountAddress::ONE,
+        Identifier::new(module).unwrap(),
+    )))
+}
+
+fn table_item(handle: u128, key: u8) -> StateKey {
+    StateKey::TableItem {
+        handle: TableHandle(handle),
+        key: vec![key],
+    }
+}
+
+fn analyze(write_sets: Vec<Vec<(StateKey, WriteOp)>>) -> WriteSetReport {
+    let mut analyzer = WriteSetAnalyzer::new();
+    for write_set in write_sets {
+        analyzer.add_transaction(
+            &Transaction::StateCheckpoint(HashValue::zero()),
+            &WriteSetMut::new(write_set).freeze().unwrap(),
+        );
+    }
+    analyzer.report(DEFAULT_NUM_HOTTEST_TABLES)
+}
+
+fn stats(report_rows: &[ReportRow], name: &str) -> WriteStats {
+    report_rows
+        .iter()
+        .find(|row| row.name == name)
+        .unwrap_or_else(|| panic!("No row for {}", name))
+        .stats
+}
+
+#[test]
+fn test_modules_and_resource_types() {
+    let report = analyze(vec![
+        vec![
+            (
+                resource("coin", "CoinStore", vec![TypeTag::U64]),
+                WriteOp::Modification(vec![0; 10]),
+            ),
+            (
+                resource("coin", "CoinStore", vec![TypeTag::Bool]),
+                WriteOp::Creation(vec![0; 20]),
+            ),
+            (resource("account", "Account", vec![]), WriteOp::Deletion),
+        ],
+        vec![(code("coin"), WriteOp::Modification(vec![0; 100]))],
+    ]);
+
+    assert_eq!(report.num_transactions, 2);
+    assert_eq!(
+        stats(&report.transaction_types, "state_checkpoint"),
+        WriteStats {
+            transactions: 2,
+            creations: 1,
+            modifications: 2,
+            deletions: 1,
+            bytes_written: 130,
+        }
+    );
+    assert_eq!(
+        stats(&report.modules, "0x1::coin"),
+        WriteStats {
+            transactions: 2,
+            creations: 1,
+            modifications: 2,
+            deletions: 0,
+            bytes_written: 130,
+        }
+    );
+    // Instances of a generic resource are counted under the generic resource.
+    assert_eq!(
+        stats(&report.resource_types, "0x1::coin::CoinStore"),
+        WriteStats {
+            transactions: 1,
+            creations: 1,
+            modifications: 1,
+            deletions: 0,
+            bytes_written: 30,
+        }
+    );
+    assert_eq!(
+        stats(&report.resource_types, "0x1::account::Account").deletions,
+        1
+    );
+
+    // Rows are ordered by the bytes written.
+    let modules: Vec<_> = report.modules.iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(modules, vec!["0x1::coin", "0x1::account"]);
+}
+
+#[test]
+fn test_hottest_table_handles() {
+    let mut analyzer = WriteSetAnalyzer::new();
+    let write_set = WriteSetMut::new(vec![
+        (table_item(1, 0), WriteOp::Creation(vec![0; 10])),
+        (table_item(2, 0), WriteOp::Creation(vec![0; 10])),
+        (table_item(2, 1), WriteOp::Modification(vec![0; 10])),
+        (table_item(3, 0), WriteOp::Deletion),
+        (table_item(3, 1), WriteOp::Deletion),
+        (table_item(3, 2), WriteOp::Deletion),
+    ])
+    .freeze()
+    .unwrap();
+    analyzer.add_transaction(&Transaction::StateCheckpoint(HashValue::zero()), &write_set);
+
+    let report = analyzer.report(2);
+    let handles: Vec<_> = report
+        .hottest_table_handles
+        .iter()
+        .map(|row| row.name.as_str())
+        .collect();
+    assert_eq!(handles, vec!["3", "2"]);
+    assert_eq!(stats(&report.hottest_table_handles, "3").transactions, 1);
+}
+
+#[test]
+fn test_write_csv() {
+    let report = analyze(vec![vec![(
+        resource("coin", "CoinStore", vec![]),
+        WriteOp::Creation(vec![0; 10]),
+    )]]);
+
+    let mut csv = vec![];
+    report.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "category,name,transactions,creations,modifications,deletions,bytes_written\n\
+         transaction_type,state_checkpoint,1,1,0,0,10\n\
+         module,0x1::coin,1,1,0,0,10\n\
+         resource_type,0x1::coin::CoinStore,1,1,0,0,10\n"
+    );
+    assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+}
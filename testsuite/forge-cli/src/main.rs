@@ -17,9 +17,11 @@ use testcases::network_latency_test::NetworkLatencyTest;
 use testcases::network_loss_test::NetworkLossTest;
 use testcases::performance_with_fullnode_test::PerformanceBenchmarkWithFN;
 use testcases::{
-    compatibility_test::SimpleValidatorUpgrade, forge_setup_test::ForgeSetupTest, generate_traffic,
+    api_pruning_test::ApiPruningTest, compatibility_test::SimpleValidatorUpgrade,
+    forge_setup_test::ForgeSetupTest, generate_traffic,
     network_partition_test::NetworkPartitionTest, performance_test::PerformanceBenchmark,
     reconfiguration_test::ReconfigurationTest, state_sync_performance::StateSyncPerformance,
+    storage_latency_test::StorageLatencyTest,
 };
 use tokio::runtime::Runtime;
 use url::Url;
@@ -440,6 +442,10 @@ fn single_test_suite(test_name: &str) -> Result<ForgeConfig<'static>> {
         "network_partition" => config.with_network_tests(&[&NetworkPartitionTest]),
         "network_latency" => config.with_network_tests(&[&NetworkLatencyTest]),
         "network_bandwidth" => config.with_network_tests(&[&NetworkBandwidthTest]),
+        "storage_latency" => config.with_network_tests(&[&StorageLatencyTest]),
+        "api_pruning" => config
+            .with_initial_fullnode_count(2)
+            .with_network_tests(&[&ApiPruningTest]),
         "setup_test" => config
             .with_initial_fullnode_count(1)
             .with_network_tests(&[&ForgeSetupTest]),
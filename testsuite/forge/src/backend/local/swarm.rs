@@ -546,6 +546,6 @@ impl Swarm for LocalSwarm {
         _time: Option<i64>,
         _timeout: Option<i64>,
     ) -> Result<PromqlResult> {
-        todo!()
+        bail!("Local swarms have no prometheus to query")
     }
 }
@@ -1,13 +1,22 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::time::Duration;
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use super::Test;
 use crate::success_criteria::SuccessCriteria;
-use crate::{CoreContext, Result, Swarm, TestReport};
+use crate::{CoreContext, ReportedResourceUsage, Result, Swarm, TestPhase, TestReport};
+use aptos_logger::warn;
+use tokio::runtime::Runtime;
 use transaction_emitter_lib::{EmitJobRequest, TxnStats};
 
+const CPU_USAGE_QUERY: &str = r#"sum by (pod) (rate(container_cpu_usage_seconds_total{container=~"validator|fullnode"}[1m]))"#;
+const MEMORY_USAGE_QUERY: &str =
+    r#"sum by (pod) (container_memory_working_set_bytes{container=~"validator|fullnode"})"#;
+
 /// The testing interface which defines a test written with full control over an existing network.
 /// Tests written against this interface will have access to both the Root account as well as the
 /// nodes which comprise the network.
@@ -18,6 +27,7 @@ pub trait NetworkTest: Test {
 
 pub struct NetworkContext<'t> {
     core: CoreContext,
+    test_name: String,
     swarm: &'t mut dyn Swarm,
     pub report: &'t mut TestReport,
     pub global_job: EmitJobRequest,
@@ -27,6 +37,7 @@ pub struct NetworkContext<'t> {
 impl<'t> NetworkContext<'t> {
     pub fn new(
         core: CoreContext,
+        test_name: String,
         swarm: &'t mut dyn Swarm,
         report: &'t mut TestReport,
         global_job: EmitJobRequest,
@@ -34,6 +45,7 @@ impl<'t> NetworkContext<'t> {
     ) -> Self {
         Self {
             core,
+            test_name,
             swarm,
             report,
             global_job,
@@ -48,8 +60,80 @@ impl<'t> NetworkContext<'t> {
     pub fn core(&mut self) -> &mut CoreContext {
         &mut self.core
     }
-    pub fn check_for_success(&self, stats: &TxnStats, window: &Duration) -> Result<()> {
-        self.success_criteria
-            .check_for_success(stats, window, self.swarm)
+
+    pub fn test_name(&self) -> &str {
+        &self.test_name
+    }
+
+    pub fn check_for_success(&mut self, stats: &TxnStats, window: &Duration) -> Result<()> {
+        self.success_criteria.check_for_success(
+            &self.test_name,
+            stats,
+            window,
+            self.swarm,
+            self.report,
+        )
+    }
+
+    /// Samples the CPU and memory each node uses, at the time if given or else now, from the
+    /// metrics of the swarm into the report. Swarms without metrics to query, e.g. local ones, are
+    /// not sampled.
+    pub fn report_resource_usage(
+        &mut self,
+        phase: Option<TestPhase>,
+        time: Option<SystemTime>,
+    ) -> Result<()> {
+        let time = time
+            .map(|time| time.duration_since(UNIX_EPOCH).map(|t| t.as_secs() as i64))
+            .transpose()?;
+        let runtime = Runtime::new()?;
+        let (cpu_usage, memory_usage) = match runtime.block_on(async {
+            Ok::<_, anyhow::Error>((
+                self.query_usage_by_pod(CPU_USAGE_QUERY, time).await?,
+                self.query_usage_by_pod(MEMORY_USAGE_QUERY, time).await?,
+            ))
+        }) {
+            Ok(usage) => usage,
+            Err(e) => {
+                warn!("Failed to sample the resource usage of the nodes: {}", e);
+                return Ok(());
+            }
+        };
+
+        let mut nodes = cpu_usage
+            .keys()
+            .chain(memory_usage.keys())
+            .collect::<Vec<_>>();
+        nodes.sort();
+        nodes.dedup();
+        for node in nodes {
+            self.report.report_resource_usage(ReportedResourceUsage {
+                test_name: self.test_name.clone(),
+                phase,
+                node: node.clone(),
+                cpu_cores: cpu_usage.get(node).copied(),
+                memory_bytes: memory_usage.get(node).copied(),
+            });
+        }
+        Ok(())
+    }
+
+    async fn query_usage_by_pod(
+        &self,
+        query: &str,
+        time: Option<i64>,
+    ) -> Result<BTreeMap<String, f64>> {
+        let result = self.swarm.query_metrics(query, time, None).await?;
+        Ok(result
+            .as_instant()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|vector| {
+                vector
+                    .metric()
+                    .get("pod")
+                    .map(|pod| (pod.clone(), vector.sample().value()))
+            })
+            .collect())
     }
 }
@@ -1,13 +1,17 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::Result;
 use serde::Serialize;
-use std::{fmt, time::Duration};
+use std::{fmt, fs, path::Path, time::Duration};
 use transaction_emitter_lib::emitter::stats::TxnStats;
 
 #[derive(Default, Debug, Serialize)]
 pub struct TestReport {
     metrics: Vec<ReportedMetric>,
+    phases: Vec<ReportedPhase>,
+    resource_usage: Vec<ReportedResourceUsage>,
+    criteria: Vec<ReportedCriterion>,
     text: String,
 }
 
@@ -18,6 +22,57 @@ pub struct ReportedMetric {
     pub value: f64,
 }
 
+/// A phase of the traffic a test emits, whose txn stats are reported apart, so that the steady
+/// state isn't skewed by the ramp up and down around it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestPhase {
+    Warmup,
+    SteadyState,
+    Cooldown,
+}
+
+/// The txn stats of a phase of the traffic a test emitted.
+#[derive(Debug)]
+pub struct PhaseTxnStats {
+    pub phase: TestPhase,
+    pub stats: TxnStats,
+    pub window: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportedPhase {
+    pub test_name: String,
+    pub phase: TestPhase,
+    pub duration_secs: u64,
+    pub submitted_txn: u64,
+    pub committed_txn: u64,
+    pub expired_txn: u64,
+    pub avg_tps: u64,
+    pub avg_latency: u64,
+    pub p99_latency: u64,
+}
+
+/// A sample of the resources a node used, at the end of a phase if the test has phases.
+#[derive(Debug, Serialize)]
+pub struct ReportedResourceUsage {
+    pub test_name: String,
+    pub phase: Option<TestPhase>,
+    pub node: String,
+    pub cpu_cores: Option<f64>,
+    pub memory_bytes: Option<f64>,
+}
+
+/// The evaluation of a success criterion of a test.
+#[derive(Debug, Serialize)]
+pub struct ReportedCriterion {
+    pub test_name: String,
+    pub criterion: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub passed: bool,
+}
+
 impl TestReport {
     pub fn new() -> Self {
         Default::default()
@@ -64,6 +119,55 @@ impl TestReport {
         ));
     }
 
+    pub fn report_phase_txn_stats(&mut self, test_name: String, phase_stats: &PhaseTxnStats) {
+        let PhaseTxnStats {
+            phase,
+            stats,
+            window,
+        } = phase_stats;
+        let rate = stats.rate(*window);
+        self.phases.push(ReportedPhase {
+            test_name,
+            phase: *phase,
+            duration_secs: window.as_secs(),
+            submitted_txn: stats.submitted,
+            committed_txn: stats.committed,
+            expired_txn: stats.expired,
+            avg_tps: rate.committed,
+            avg_latency: rate.latency,
+            p99_latency: rate.p99_latency,
+        });
+    }
+
+    pub fn report_resource_usage(&mut self, usage: ReportedResourceUsage) {
+        self.resource_usage.push(usage);
+    }
+
+    pub fn report_criterion<E: ToString, C: ToString>(
+        &mut self,
+        test: E,
+        criterion: C,
+        value: f64,
+        threshold: f64,
+        passed: bool,
+    ) {
+        self.criteria.push(ReportedCriterion {
+            test_name: test.to_string(),
+            criterion: criterion.to_string(),
+            value,
+            threshold,
+            passed,
+        });
+    }
+
+    /// Writes the report as JSON to the file, for dashboards to pick up rather than scrape it from
+    /// the logs.
+    pub fn write_json(&self, path: &Path) -> Result<()> {
+        let json_report = serde_json::to_string_pretty(&self)?;
+        fs::write(path, json_report)?;
+        Ok(())
+    }
+
     pub fn print_report(&self) {
         println!("Test Statistics: ");
         println!("{}", self);
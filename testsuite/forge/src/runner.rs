@@ -7,6 +7,7 @@ use std::time::Duration;
 use std::{
     io::{self, Write},
     num::NonZeroUsize,
+    path::PathBuf,
     process,
 };
 use structopt::{clap::arg_enum, StructOpt};
@@ -65,6 +66,9 @@ pub struct Options {
     /// NO-OP: unsupported option, exists for compatibility with the default test harness
     /// Show captured stdout of successful tests
     show_output: bool,
+    #[structopt(long, env = "FORGE_REPORT_PATH", parse(from_os_str))]
+    /// Write the report of the tests as JSON to this file
+    pub report_path: Option<PathBuf>,
 }
 
 impl Options {
@@ -321,6 +325,7 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
             for test in self.filter_tests(self.tests.network_tests.iter()) {
                 let mut network_ctx = NetworkContext::new(
                     CoreContext::from_rng(&mut rng),
+                    test.name().to_string(),
                     &mut *swarm,
                     &mut report,
                     self.global_job_request.clone(),
@@ -331,6 +336,9 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
             }
 
             report.print_report();
+            if let Some(report_path) = &self.options.report_path {
+                report.write_json(report_path)?;
+            }
 
             io::stdout().flush()?;
             io::stderr().flush()?;
@@ -6,7 +6,7 @@ use serde::Serialize;
 use std::time::{Duration, Instant};
 use transaction_emitter_lib::emitter::stats::TxnStats;
 
-use crate::{Swarm, SwarmExt};
+use crate::{Swarm, SwarmExt, TestReport};
 
 #[derive(Default, Clone, Debug, Serialize)]
 pub struct SuccessCriteria {
@@ -28,17 +28,28 @@ impl SuccessCriteria {
         }
     }
 
+    /// Checks the criteria, reporting how each of them was evaluated.
     pub fn check_for_success(
         &self,
+        test_name: &str,
         stats: &TxnStats,
         window: &Duration,
         swarm: &dyn Swarm,
+        report: &mut TestReport,
     ) -> anyhow::Result<()> {
         // TODO: Add more success criteria like expired transactions, CPU, memory usage etc
         let avg_tps = stats.committed / window.as_secs();
         let is_triggerd_by_github_actions =
             std::env::var("FORGE_TRIGGERED_BY").unwrap_or_default() == "github-actions";
-        if avg_tps < self.avg_tps as u64 {
+        let tps_passed = avg_tps >= self.avg_tps as u64;
+        report.report_criterion(
+            test_name,
+            "avg_tps",
+            avg_tps as f64,
+            self.avg_tps as f64,
+            tps_passed,
+        );
+        if !tps_passed {
             let error_message = format!(
                 "TPS requirement failed. Average TPS {}, minimum TPS requirement {}",
                 avg_tps, self.avg_tps
@@ -51,11 +62,20 @@ impl SuccessCriteria {
         }
 
         if let Some(duration) = self.wait_for_all_nodes_to_catchup {
-            futures::executor::block_on(async {
+            let start = Instant::now();
+            let result = futures::executor::block_on(async {
                 swarm
                     .wait_for_all_nodes_to_catchup(Instant::now() + duration)
                     .await
-            })?;
+            });
+            report.report_criterion(
+                test_name,
+                "catchup_secs",
+                start.elapsed().as_secs_f64(),
+                duration.as_secs_f64(),
+                result.is_ok(),
+            );
+            result?;
         }
 
         // TODO(skedia) Add latency success criteria after we have support for querying prometheus
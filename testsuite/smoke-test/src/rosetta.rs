@@ -60,6 +60,7 @@ pub async fn setup_test(
         tls_key_path: None,
         content_length_limit: None,
         failpoints_enabled: false,
+        ..Default::default()
     };
 
     // Start the server
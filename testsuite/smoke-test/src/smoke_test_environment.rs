@@ -7,7 +7,12 @@ use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_faucet::FaucetArgs;
 use aptos_genesis::builder::{InitConfigFn, InitGenesisConfigFn};
 use aptos_logger::info;
-use aptos_types::{account_config::aptos_test_root_address, chain_id::ChainId};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{aptos_test_root_address, CORE_CODE_ADDRESS},
+    chain_id::ChainId,
+    on_chain_config::{ConsensusConfigV1, OnChainConsensusConfig},
+};
 use forge::Node;
 use forge::{Factory, LocalFactory, LocalSwarm};
 use framework::ReleaseBundle;
@@ -15,13 +20,14 @@ use once_cell::sync::Lazy;
 use rand::rngs::OsRng;
 use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
 use tokio::task::JoinHandle;
+use vm_genesis::AccountBalance;
 
 pub struct SwarmBuilder {
     local: bool,
     num_validators: NonZeroUsize,
     genesis_framework: Option<ReleaseBundle>,
     init_config: Option<InitConfigFn>,
-    init_genesis_config: Option<InitGenesisConfigFn>,
+    init_genesis_configs: Vec<InitGenesisConfigFn>,
 }
 
 impl SwarmBuilder {
@@ -31,7 +37,7 @@ impl SwarmBuilder {
             num_validators: NonZeroUsize::new(num_validators).unwrap(),
             genesis_framework: None,
             init_config: None,
-            init_genesis_config: None,
+            init_genesis_configs: vec![],
         }
     }
 
@@ -49,11 +55,36 @@ impl SwarmBuilder {
         self
     }
 
+    /// Starts the swarm from a genesis built with the framework, rather than the one of the
+    /// workspace.
+    pub fn with_framework(mut self, framework: ReleaseBundle) -> Self {
+        self.genesis_framework = Some(framework);
+        self
+    }
+
+    /// Adds a modification of the genesis configuration, applied in the order they were added.
     pub fn with_init_genesis_config(mut self, init_genesis_config: InitGenesisConfigFn) -> Self {
-        self.init_genesis_config = Some(init_genesis_config);
+        self.init_genesis_configs.push(init_genesis_config);
         self
     }
 
+    /// Starts the swarm with the consensus config on chain, e.g. to test features it gates.
+    pub fn with_consensus_config(self, consensus_config: OnChainConsensusConfig) -> Self {
+        self.with_init_genesis_config(Arc::new(move |genesis_config| {
+            genesis_config.consensus_config = consensus_config.clone();
+        }))
+    }
+
+    /// Creates the account at genesis, holding the balance in Aptos coins.
+    pub fn with_genesis_account(self, account_address: AccountAddress, balance: u64) -> Self {
+        self.with_init_genesis_config(Arc::new(move |genesis_config| {
+            genesis_config.accounts.push(AccountBalance {
+                account_address,
+                balance,
+            });
+        }))
+    }
+
     // Gas is not enabled with this setup, it's enabled via forge instance.
     pub async fn build(self) -> LocalSwarm {
         ::aptos_logger::Logger::new().init();
@@ -67,7 +98,7 @@ impl SwarmBuilder {
 
         info!("Node finished compiling");
 
-        let init_genesis_config = self.init_genesis_config;
+        let init_genesis_configs = self.init_genesis_configs;
 
         FACTORY
             .new_swarm_with_version(
@@ -77,7 +108,7 @@ impl SwarmBuilder {
                 self.genesis_framework,
                 self.init_config,
                 Some(Arc::new(move |genesis_config| {
-                    if let Some(init_genesis_config) = &init_genesis_config {
+                    for init_genesis_config in &init_genesis_configs {
                         (init_genesis_config)(genesis_config);
                     }
                 })),
@@ -139,6 +170,49 @@ async fn test_prevent_starting_nodes_twice() {
     assert!(validator.start().is_err());
 }
 
+#[tokio::test]
+async fn test_custom_genesis() {
+    let account_address = AccountAddress::random();
+    let consensus_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+        decoupled_execution: false,
+        ..ConsensusConfigV1::default()
+    });
+    let swarm = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_consensus_config(consensus_config.clone())
+        .with_genesis_account(account_address, 1_000)
+        .build()
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+
+    let balance = client
+        .get_account_balance(account_address)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(balance.get(), 1_000);
+
+    let resource = client
+        .get_resource::<serde_json::Value>(
+            CORE_CODE_ADDRESS,
+            "0x1::consensus_config::ConsensusConfig",
+        )
+        .await
+        .unwrap()
+        .into_inner();
+    let config_bytes = hex::decode(
+        resource["config"]
+            .as_str()
+            .unwrap()
+            .trim_start_matches("0x"),
+    )
+    .unwrap();
+    assert_eq!(
+        bcs::from_bytes::<OnChainConsensusConfig>(&config_bytes).unwrap(),
+        consensus_config
+    );
+}
+
 pub fn launch_faucet(
     endpoint: reqwest::Url,
     mint_key: Ed25519PrivateKey,
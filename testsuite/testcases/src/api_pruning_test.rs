@@ -0,0 +1,315 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::ensure;
+use aptos_rest_client::{aptos_api_types::AptosErrorCode, Client as RestClient, RestError};
+use aptos_sdk::types::{account_address::AccountAddress, PeerId};
+use forge::{NetworkContext, NetworkTest, NodeExt, Result, Test};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use tokio::runtime::Runtime;
+
+/// Queries the API of the fullnodes for transactions, resources and events while they are
+/// restarted and the transactions committed advance the prune window, and checks that:
+/// - what they return matches what a validator returns at the same version, down to the
+///   accumulator root hashes of transactions,
+/// - data before the oldest ledger version is reported as pruned, with a 404 `version_pruned`,
+///   and versions ahead of the ledger as not found, rather than failing otherwise,
+/// - the oldest ledger version of a fullnode never goes backwards, restarts included.
+///
+/// The prune window only advances if the nodes keep fewer versions than the test commits,
+/// otherwise only the consistency and the not found errors are checked.
+pub struct ApiPruningTest;
+
+const NUM_RESTARTS: u32 = 3;
+const QUERY_INTERVAL: Duration = Duration::from_millis(500);
+const RESTART_TIMEOUT: Duration = Duration::from_secs(120);
+const NUM_EVENTS: u16 = 10;
+/// How far ahead of the ledger versions that must not be found are.
+const VERSIONS_AHEAD: u64 = 1_000_000_000;
+/// The number of violations the report of a failure lists.
+const MAX_VIOLATIONS_REPORTED: usize = 10;
+
+impl Test for ApiPruningTest {
+    fn name(&self) -> &'static str {
+        "api::pruning-test"
+    }
+}
+
+impl NetworkTest for ApiPruningTest {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let runtime = Runtime::new()?;
+        let duration = ctx.global_job.duration;
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let all_fullnodes = ctx
+            .swarm()
+            .full_nodes()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        ensure!(!all_fullnodes.is_empty(), "The test requires fullnodes.");
+        let validator_client = ctx
+            .swarm()
+            .validators()
+            .next()
+            .map(|v| v.rest_client())
+            .expect("There is always a validator");
+        let fullnode_clients = ctx
+            .swarm()
+            .full_nodes()
+            .map(|v| (v.peer_id(), v.rest_client()))
+            .collect::<Vec<_>>();
+        let root_address = ctx.swarm().chain_info().root_account.address();
+
+        // QUERY THE FULLNODES IN THE BACKGROUND
+        let stop = Arc::new(AtomicBool::new(false));
+        let checker = runtime.spawn(check_api(
+            validator_client,
+            fullnode_clients,
+            root_address,
+            stop.clone(),
+        ));
+
+        // EMIT TXNS, RESTARTING THE FULLNODES IN BETWEEN
+        for round in 0..NUM_RESTARTS {
+            let round_duration = duration / NUM_RESTARTS;
+            let txn_stat = generate_traffic(ctx, &all_validators, round_duration, 1)?;
+            ctx.report.report_txn_stats(
+                format!("{}:round-{}", self.name(), round),
+                &txn_stat,
+                round_duration,
+            );
+            for fullnode in &all_fullnodes {
+                let node = ctx.swarm().full_node_mut(*fullnode).unwrap();
+                println!("Fullnode {} is going to restart", node.name());
+                runtime.block_on(node.restart())?;
+                runtime.block_on(node.wait_until_healthy(Instant::now() + RESTART_TIMEOUT))?;
+            }
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        let stats = runtime.block_on(checker)?;
+        let msg = format!(
+            "Checked the API of {} fullnodes {} times, the oldest ledger version reaching {}",
+            all_fullnodes.len(),
+            stats.num_checks,
+            stats.max_oldest_ledger_version,
+        );
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+
+        ensure!(
+            stats.num_checks > 0,
+            "The API of the fullnodes was never checked."
+        );
+        ensure!(
+            stats.violations.is_empty(),
+            "The API of the fullnodes was inconsistent {} times, e.g.:\n{}",
+            stats.violations.len(),
+            stats
+                .violations
+                .iter()
+                .take(MAX_VIOLATIONS_REPORTED)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct CheckStats {
+    num_checks: u64,
+    max_oldest_ledger_version: u64,
+    violations: Vec<String>,
+}
+
+async fn check_api(
+    validator_client: RestClient,
+    fullnode_clients: Vec<(PeerId, RestClient)>,
+    root_address: AccountAddress,
+    stop: Arc<AtomicBool>,
+) -> CheckStats {
+    let mut stats = CheckStats::default();
+    let mut oldest_ledger_versions = HashMap::new();
+    while !stop.load(Ordering::Relaxed) {
+        for (peer_id, client) in &fullnode_clients {
+            let violations = match check_fullnode(
+                &validator_client,
+                client,
+                root_address,
+                oldest_ledger_versions.entry(*peer_id).or_default(),
+            )
+            .await
+            {
+                Some(violations) => violations,
+                // The fullnode is down, e.g. restarting.
+                None => continue,
+            };
+            stats.num_checks += 1;
+            stats.violations.extend(
+                violations
+                    .into_iter()
+                    .map(|violation| format!("Fullnode {}: {}", peer_id, violation)),
+            );
+        }
+        stats.max_oldest_ledger_version =
+            oldest_ledger_versions.values().copied().max().unwrap_or(0);
+        tokio::time::sleep(QUERY_INTERVAL).await;
+    }
+    stats
+}
+
+/// Checks the API of a fullnode against the one of a validator, returning what was inconsistent, or
+/// None if the fullnode couldn't be reached.
+async fn check_fullnode(
+    validator_client: &RestClient,
+    client: &RestClient,
+    root_address: AccountAddress,
+    oldest_ledger_version: &mut u64,
+) -> Option<Vec<String>> {
+    let state = client.get_ledger_information().await.ok()?.into_inner();
+    let mut violations = vec![];
+    if state.oldest_ledger_version < *oldest_ledger_version {
+        violations.push(format!(
+            "The oldest ledger version went back from {} to {}",
+            oldest_ledger_version, state.oldest_ledger_version
+        ));
+    }
+    *oldest_ledger_version = state.oldest_ledger_version;
+    let version = state.version;
+
+    // Transactions
+    if let (Ok(txn), Ok(expected_txn)) = (
+        client.get_transaction_by_version(version).await,
+        validator_client.get_transaction_by_version(version).await,
+    ) {
+        let root_hash = txn
+            .inner()
+            .transaction_info()
+            .map(|info| info.accumulator_root_hash);
+        let expected_root_hash = expected_txn
+            .inner()
+            .transaction_info()
+            .map(|info| info.accumulator_root_hash);
+        if root_hash.ok() != expected_root_hash.ok() {
+            violations.push(format!(
+                "The transaction at version {} doesn't match the validator's",
+                version
+            ));
+        }
+    }
+    violations.extend(check_not_found(
+        client
+            .get_transaction_by_version(version + VERSIONS_AHEAD)
+            .await,
+        AptosErrorCode::TransactionNotFound,
+        "Getting a transaction ahead of the ledger",
+    ));
+
+    // Resources
+    if let (Ok(resources), Ok(expected_resources)) = (
+        client
+            .get_account_resources_at_version(root_address, version)
+            .await,
+        validator_client
+            .get_account_resources_at_version(root_address, version)
+            .await,
+    ) {
+        if resources.inner() != expected_resources.inner() {
+            violations.push(format!(
+                "The resources of {} at version {} don't match the validator's",
+                root_address, version
+            ));
+        }
+    }
+    violations.extend(check_not_found(
+        client
+            .get_account_resources_at_version(root_address, version + VERSIONS_AHEAD)
+            .await,
+        AptosErrorCode::VersionNotFound,
+        "Getting resources ahead of the ledger",
+    ));
+
+    // Pruned data
+    if state.oldest_ledger_version > 0 {
+        let pruned_version = state.oldest_ledger_version - 1;
+        violations.extend(check_not_found(
+            client.get_transaction_by_version(pruned_version).await,
+            AptosErrorCode::VersionPruned,
+            "Getting a pruned transaction",
+        ));
+        violations.extend(check_not_found(
+            client
+                .get_account_resources_at_version(root_address, pruned_version)
+                .await,
+            AptosErrorCode::VersionPruned,
+            "Getting pruned resources",
+        ));
+    }
+
+    // Events
+    if let Ok(events) = client.get_new_block_events(None, Some(NUM_EVENTS)).await {
+        let events = events.into_inner();
+        if let Some(first_event) = events.first() {
+            if let Ok(expected_events) = validator_client
+                .get_new_block_events(Some(first_event.sequence_number), Some(events.len() as u16))
+                .await
+            {
+                let expected_events = expected_events.into_inner();
+                if events
+                    .iter()
+                    .map(|e| (e.sequence_number, e.version))
+                    .ne(expected_events
+                        .iter()
+                        .map(|e| (e.sequence_number, e.version)))
+                {
+                    violations.push(format!(
+                        "The new block events from sequence number {} don't match the validator's",
+                        first_event.sequence_number
+                    ));
+                }
+            }
+        }
+    }
+
+    Some(violations)
+}
+
+/// Checks that a request was answered with a 404 with the error code, unless the fullnode couldn't
+/// be reached.
+fn check_not_found<T>(
+    result: anyhow::Result<T>,
+    error_code: AptosErrorCode,
+    request: &str,
+) -> Option<String> {
+    match result {
+        Ok(_) => Some(format!(
+            "{} succeeded, expected a 404 {:?}",
+            request, error_code
+        )),
+        Err(err) => match err.downcast_ref::<RestError>() {
+            Some(error)
+                if error.status_code.as_u16() == 404 && error.error.error_code == error_code =>
+            {
+                None
+            }
+            Some(error) => Some(format!(
+                "{} failed with {}, expected a 404 {:?}",
+                request, error, error_code
+            )),
+            None => None,
+        },
+    }
+}
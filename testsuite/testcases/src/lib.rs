@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod api_pruning_test;
 pub mod compatibility_test;
 pub mod forge_setup_test;
 pub mod gas_price_test;
@@ -13,13 +14,17 @@ pub mod performance_test;
 pub mod performance_with_fullnode_test;
 pub mod reconfiguration_test;
 pub mod state_sync_performance;
+pub mod storage_latency_test;
 
 use anyhow::{anyhow, ensure};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::PeerId};
-use forge::{NetworkContext, NodeExt, Result, TxnEmitter, TxnStats, Version};
+use forge::{
+    EmitJobRequest, NetworkContext, NodeExt, PhaseTxnStats, Result, TestPhase, TxnEmitter,
+    TxnStats, Version,
+};
 use rand::SeedableRng;
-use std::time::{Duration, Instant};
-use tokio::runtime::Builder;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::runtime::{Builder, Runtime};
 
 async fn batch_update(
     ctx: &mut NetworkContext<'_>,
@@ -49,6 +54,71 @@ pub fn generate_traffic<'t>(
     duration: Duration,
     gas_price: u64,
 ) -> Result<TxnStats> {
+    emit_traffic(ctx, nodes, duration, gas_price, |rt, emitter, request| {
+        rt.block_on(emitter.emit_txn_for(request))
+    })
+}
+
+/// Generates traffic like `generate_traffic`, reporting the txn stats of its warmup, steady state
+/// and cooldown apart, along with the resources the nodes used by the end of each, and returns the
+/// stats of the whole traffic.
+pub fn generate_traffic_with_phases<'t>(
+    ctx: &mut NetworkContext<'t>,
+    nodes: &[PeerId],
+    duration: Duration,
+    gas_price: u64,
+    warmup: Duration,
+    cooldown: Duration,
+) -> Result<TxnStats> {
+    ensure!(
+        warmup + cooldown < duration,
+        "warmup and cooldown must be shorter than the traffic"
+    );
+    let phases = [
+        (TestPhase::Warmup, warmup),
+        (TestPhase::SteadyState, duration - warmup - cooldown),
+        (TestPhase::Cooldown, cooldown),
+    ];
+    let (stats, phase_stats) =
+        emit_traffic(ctx, nodes, duration, gas_price, |rt, emitter, request| {
+            rt.block_on(async {
+                let job = emitter.start_job(request).await?;
+                let mut phase_stats = vec![];
+                let mut prev_stats = TxnStats::default();
+                for &(phase, window) in &phases {
+                    tokio::time::sleep(window).await;
+                    let stats = emitter.peek_job_stats(&job);
+                    phase_stats.push((
+                        PhaseTxnStats {
+                            phase,
+                            stats: &stats - &prev_stats,
+                            window,
+                        },
+                        SystemTime::now(),
+                    ));
+                    prev_stats = stats;
+                }
+                Ok((emitter.stop_job(job).await, phase_stats))
+            })
+        })?;
+
+    let test_name = ctx.test_name().to_string();
+    for (phase_stats, end_time) in &phase_stats {
+        ctx.report
+            .report_phase_txn_stats(test_name.clone(), phase_stats);
+        ctx.report_resource_usage(Some(phase_stats.phase), Some(*end_time))?;
+    }
+    Ok(stats)
+}
+
+/// Runs `emit` with an emitter sending transactions to the nodes, on a runtime of its own.
+fn emit_traffic<'t, R>(
+    ctx: &mut NetworkContext<'t>,
+    nodes: &[PeerId],
+    duration: Duration,
+    gas_price: u64,
+    emit: impl FnOnce(&Runtime, &mut TxnEmitter, EmitJobRequest) -> Result<R>,
+) -> Result<R> {
     ensure!(gas_price > 0, "gas_price is required to be non zero");
     let mut runtime_builder = Builder::new_multi_thread();
     runtime_builder.enable_all();
@@ -86,7 +156,5 @@ pub fn generate_traffic<'t>(
         .rest_clients(all_node_clients)
         .gas_price(gas_price)
         .duration(duration);
-    let stats = rt.block_on(emitter.emit_txn_for(emit_job_request))?;
-
-    Ok(stats)
+    emit(&rt, &mut emitter, emit_job_request)
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::generate_traffic;
+use crate::generate_traffic_with_phases;
 use forge::{NetworkContext, NetworkTest, Result, Test};
 use tokio::runtime::Runtime;
 
@@ -31,7 +31,14 @@ impl NetworkTest for PerformanceBenchmark {
         let all_nodes = [&all_validators[..], &all_fullnodes[..]].concat();
 
         // Generate some traffic
-        let txn_stat = generate_traffic(ctx, &all_nodes, duration, 1)?;
+        let txn_stat = generate_traffic_with_phases(
+            ctx,
+            &all_nodes,
+            duration,
+            1,
+            duration / 10,
+            duration / 10,
+        )?;
         ctx.report
             .report_txn_stats(self.name().to_string(), &txn_stat, duration);
         // ensure we meet the success criteria
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::generate_traffic;
+use crate::generate_traffic_with_phases;
 use forge::{NetworkContext, NetworkTest, Result, Test};
 
 pub struct PerformanceBenchmarkWithFN;
@@ -23,7 +23,14 @@ impl NetworkTest for PerformanceBenchmarkWithFN {
             .collect::<Vec<_>>();
 
         // Generate some traffic
-        let txn_stat = generate_traffic(ctx, &all_fullnodes, duration, 1)?;
+        let txn_stat = generate_traffic_with_phases(
+            ctx,
+            &all_fullnodes,
+            duration,
+            1,
+            duration / 10,
+            duration / 10,
+        )?;
         ctx.report
             .report_txn_stats(self.name().to_string(), &txn_stat, duration);
         // ensure we meet the success criteria
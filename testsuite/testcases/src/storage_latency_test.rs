@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::{bail, ensure};
+use aptos_rest_client::Client as RestClient;
+use forge::{NetworkContext, NetworkTest, NodeExt, Result, SwarmExt, Test};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+/// Slows down the storage of a minority of the validators by injecting latency into their DB reads
+/// and writes, and checks that the network keeps committing, that the slow validators catch up once
+/// the latency is lifted, and that the API of a node failing to read from its DB returns errors
+/// rather than hangs.
+///
+/// Requires the nodes to be built with the `failpoints` feature and configured with
+/// `api.failpoints_enabled`.
+pub struct StorageLatencyTest;
+
+// Latency
+pub const SLOW_VALIDATORS_PERCENTAGE: usize = 20;
+pub const WRITE_LATENCY_MS: u64 = 200;
+pub const READ_LATENCY_MS: u64 = 20;
+pub const READ_LATENCY_PERCENTAGE: u64 = 10;
+
+const CATCHUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+impl Test for StorageLatencyTest {
+    fn name(&self) -> &'static str {
+        "storage::latency-test"
+    }
+}
+
+impl NetworkTest for StorageLatencyTest {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let runtime = Runtime::new()?;
+        let duration = ctx.global_job.duration;
+        let all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        let num_slow_validators = (all_validators.len() * SLOW_VALIDATORS_PERCENTAGE / 100).max(1);
+        let slow_validator_clients = ctx
+            .swarm()
+            .validators()
+            .take(num_slow_validators)
+            .map(|v| v.rest_client())
+            .collect::<Vec<_>>();
+        let failpoints = [
+            (
+                "schemadb::write_schemas",
+                format!("sleep({})", WRITE_LATENCY_MS),
+            ),
+            (
+                "schemadb::get",
+                format!("{}%sleep({})", READ_LATENCY_PERCENTAGE, READ_LATENCY_MS),
+            ),
+            (
+                "schemadb::iter",
+                format!("{}%sleep({})", READ_LATENCY_PERCENTAGE, READ_LATENCY_MS),
+            ),
+        ];
+
+        // INJECT STORAGE LATENCY AND EMIT TXNS
+        for client in &slow_validator_clients {
+            for (name, actions) in &failpoints {
+                runtime.block_on(client.set_failpoint(name.to_string(), actions.clone()))?;
+            }
+        }
+        let msg = format!(
+            "Injected {}ms write latency and {}ms latency to {}% of reads to {} validators",
+            WRITE_LATENCY_MS, READ_LATENCY_MS, READ_LATENCY_PERCENTAGE, num_slow_validators
+        );
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+        let txn_stat = generate_traffic(ctx, &all_validators, duration, 1)?;
+        ctx.report
+            .report_txn_stats(format!("{}:latency", self.name()), &txn_stat, duration);
+
+        // INJECT READ ERRORS, EXPECTING THE API TO FAIL FAST
+        let client = &slow_validator_clients[0];
+        let root_address = ctx.swarm().chain_info().root_account.address();
+        runtime
+            .block_on(client.set_failpoint("schemadb::get".to_string(), "return".to_string()))?;
+        let result = runtime.block_on(tokio::time::timeout(
+            Duration::from_secs(10),
+            client.get_account(root_address),
+        ));
+        runtime.block_on(client.set_failpoint("schemadb::get".to_string(), "off".to_string()))?;
+        match result {
+            Ok(response) => ensure!(
+                response.is_err(),
+                "Reading an account succeeded despite the injected DB read errors."
+            ),
+            Err(_) => bail!("Reading an account hung on the injected DB read errors."),
+        }
+        ctx.report
+            .report_text("API returned errors on the injected DB read errors".to_string());
+
+        // LIFT THE LATENCY, EXPECTING THE SLOW VALIDATORS TO CATCH UP
+        for client in &slow_validator_clients {
+            remove_failpoints(&runtime, client, &failpoints)?;
+        }
+        runtime.block_on(
+            ctx.swarm()
+                .wait_for_all_nodes_to_catchup(Instant::now() + CATCHUP_TIMEOUT),
+        )?;
+
+        // ensure we meet the success criteria
+        ctx.check_for_success(&txn_stat, &duration)?;
+
+        Ok(())
+    }
+}
+
+fn remove_failpoints(
+    runtime: &Runtime,
+    client: &RestClient,
+    failpoints: &[(&str, String)],
+) -> Result<()> {
+    for (name, _) in failpoints {
+        runtime.block_on(client.set_failpoint(name.to_string(), "off".to_string()))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,28 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use move_deps::move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::Deserialize;
+
+/// The hashes of the scripts of the governance proposals that passed, by proposal id.
+#[derive(Deserialize)]
+pub struct ApprovedExecutionHashesResource {
+    hashes: Vec<(u64, Vec<u8>)>,
+}
+
+impl ApprovedExecutionHashesResource {
+    pub fn contains(&self, script_hash: &[u8]) -> bool {
+        self.hashes.iter().any(|(_, hash)| hash == script_hash)
+    }
+}
+
+impl MoveStructType for ApprovedExecutionHashesResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("aptos_governance");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("ApprovedExecutionHashes");
+}
+
+impl MoveResource for ApprovedExecutionHashesResource {}
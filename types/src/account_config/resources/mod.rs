@@ -1,12 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod approved_execution_hashes;
 pub mod chain_account_info;
 pub mod chain_id;
 pub mod coin_store;
 pub mod core_account;
 pub mod crsn;
 
+pub use approved_execution_hashes::*;
 pub use chain_account_info::*;
 pub use chain_id::*;
 pub use coin_store::*;
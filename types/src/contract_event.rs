@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    account_address::AccountAddress,
     account_config::{DepositEvent, NewBlockEvent, NewEpochEvent, WithdrawEvent},
     event::EventKey,
     transaction::Version,
 };
 use anyhow::{Error, Result};
+use aptos_crypto::HashValue;
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use move_deps::move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
 
@@ -198,3 +200,16 @@ impl EventWithVersion {
         }
     }
 }
+
+/// An event along with the hash, sender and status of the transaction that emitted it.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct EventWithTransactionContext {
+    pub transaction_version: Version,
+    pub transaction_hash: HashValue,
+    /// The sender of the transaction, if it is a user transaction.
+    pub sender: Option<AccountAddress>,
+    /// Whether the transaction was executed successfully, events being kept when it aborted.
+    pub success: bool,
+    pub event: ContractEvent,
+}
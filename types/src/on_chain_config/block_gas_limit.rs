@@ -0,0 +1,17 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use serde::{Deserialize, Serialize};
+
+/// Defines the amount of gas the transactions of a block may use. Once it is reached, the remaining
+/// transactions of the block are retried in a later block. Blocks are unlimited if it is not set.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct BlockGasLimit {
+    pub gas_limit: u64,
+}
+
+impl OnChainConfig for BlockGasLimit {
+    const MODULE_IDENTIFIER: &'static str = "block_gas_limit";
+    const TYPE_IDENTIFIER: &'static str = "BlockGasLimit";
+}
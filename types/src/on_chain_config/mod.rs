@@ -17,18 +17,25 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{collections::HashMap, fmt, sync::Arc};
 
 mod aptos_version;
+mod block_gas_limit;
 mod consensus_config;
 mod gas_schedule;
+mod parallel_execution_config;
+mod script_function_allow_list;
 mod validator_set;
 
 pub use self::{
     aptos_version::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
+        APTOS_VERSION_5,
     },
+    block_gas_limit::BlockGasLimit,
     consensus_config::{
         ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerElectionType,
     },
     gas_schedule::GasSchedule,
+    parallel_execution_config::{ParallelExecutionConfig, ParallelExecutionConfigV1},
+    script_function_allow_list::ScriptFunctionAllowList,
     validator_set::{ConsensusScheme, ValidatorSet},
 };
 
@@ -0,0 +1,106 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+
+/// The on-chain parallel execution config, in order to be able to add fields, we use enum to wrap the actual struct.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ParallelExecutionConfig {
+    V1(ParallelExecutionConfigV1),
+}
+
+/// The public interface that exposes all values with safe fallback.
+impl ParallelExecutionConfig {
+    /// Whether blocks whose transactions conflict beyond the threshold are executed sequentially.
+    pub fn sequential_fallback_enabled(&self) -> bool {
+        match &self {
+            ParallelExecutionConfig::V1(config) => config.sequential_fallback_enabled,
+        }
+    }
+
+    /// Blocks with fewer transactions are always executed in parallel to completion.
+    pub fn min_block_size_for_fallback(&self) -> u64 {
+        match &self {
+            ParallelExecutionConfig::V1(config) => config.min_block_size_for_fallback,
+        }
+    }
+
+    /// The number of validation aborts per hundred transactions of a block beyond which its
+    /// parallel execution is abandoned.
+    pub fn max_aborts_per_hundred_txns(&self) -> u64 {
+        match &self {
+            ParallelExecutionConfig::V1(config) => config.max_aborts_per_hundred_txns,
+        }
+    }
+}
+
+/// This is used when on-chain config is not initialized. Blocks are then executed in parallel to
+/// completion, as before the config existed, until governance enables the fallback.
+impl Default for ParallelExecutionConfig {
+    fn default() -> Self {
+        ParallelExecutionConfig::V1(ParallelExecutionConfigV1::default())
+    }
+}
+
+impl OnChainConfig for ParallelExecutionConfig {
+    const MODULE_IDENTIFIER: &'static str = "parallel_execution_config";
+    const TYPE_IDENTIFIER: &'static str = "ParallelExecutionConfig";
+
+    /// The Move resource is
+    /// ```ignore
+    /// struct ParallelExecutionConfig has key {
+    ///    config: vector<u8>,
+    /// }
+    /// ```
+    /// so we need two rounds of bcs deserilization to turn it back to ParallelExecutionConfig
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        let raw_bytes: Vec<u8> = bcs::from_bytes(bytes)?;
+        bcs::from_bytes(&raw_bytes)
+            .map_err(|e| format_err!("[on-chain config] Failed to deserialize into config: {}", e))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ParallelExecutionConfigV1 {
+    pub sequential_fallback_enabled: bool,
+    pub min_block_size_for_fallback: u64,
+    pub max_aborts_per_hundred_txns: u64,
+}
+
+impl Default for ParallelExecutionConfigV1 {
+    fn default() -> Self {
+        Self {
+            sequential_fallback_enabled: false,
+            min_block_size_for_fallback: 32,
+            // = 3 aborts per transaction
+            max_aborts_per_hundred_txns: 300,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_default_config_disables_fallback() {
+        assert!(!ParallelExecutionConfig::default().sequential_fallback_enabled());
+    }
+
+    #[test]
+    fn test_config_onchain_payload() {
+        let config = ParallelExecutionConfig::V1(ParallelExecutionConfigV1 {
+            sequential_fallback_enabled: true,
+            min_block_size_for_fallback: 10,
+            max_aborts_per_hundred_txns: 50,
+        });
+        let bytes = bcs::to_bytes(&bcs::to_bytes(&config).unwrap()).unwrap();
+
+        assert_eq!(
+            ParallelExecutionConfig::deserialize_into_config(&bytes).unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, on_chain_config::OnChainConfig};
+use serde::{Deserialize, Serialize};
+
+/// Defines the addresses of the modules whose script functions may be executed, for permissioned
+/// deployments. Transactions calling script functions of other modules are rejected at validation,
+/// as are scripts, which may call into any module, unless they are those of governance proposals
+/// whose hashes are approved. Any script function or script may be executed if it is not set.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ScriptFunctionAllowList {
+    pub allowed_module_addresses: Vec<AccountAddress>,
+}
+
+impl ScriptFunctionAllowList {
+    pub fn allows(&self, module_address: &AccountAddress) -> bool {
+        self.allowed_module_addresses.contains(module_address)
+    }
+}
+
+impl OnChainConfig for ScriptFunctionAllowList {
+    const MODULE_IDENTIFIER: &'static str = "script_function_allow_list";
+    const TYPE_IDENTIFIER: &'static str = "ScriptFunctionAllowList";
+}
@@ -24,7 +24,10 @@ use aptos_crypto::{
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
 use serde::{Deserialize, Serialize};
-use std::marker::PhantomData;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    marker::PhantomData,
+};
 
 /// A proof that can be used authenticate an element in an accumulator given trusted root hash. For
 /// example, both `LedgerInfoToTransactionInfoProof` and `TransactionInfoToEventProof` can be
@@ -263,6 +266,99 @@ impl SparseMerkleProof {
         element_key: HashValue,
         element_hash: Option<HashValue>,
     ) -> Result<()> {
+        self.verify_leaf(element_key, element_hash)?;
+
+        let actual_root_hash = self
+            .siblings
+            .iter()
+            .zip(
+                element_key
+                    .iter_bits()
+                    .rev()
+                    .skip(HashValue::LENGTH_IN_BITS - self.siblings.len()),
+            )
+            .fold(self.leaf_hash(), |hash, (sibling_hash, bit)| {
+                parent_hash(hash, *sibling_hash, bit)
+            });
+        ensure!(
+            actual_root_hash == expected_root_hash,
+            "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+            actual_root_hash,
+            expected_root_hash,
+        );
+
+        Ok(())
+    }
+
+    /// Verifies many proofs against the same root hash, each of an element given as its key and
+    /// optional value hash as in `verify_by_hash`. Proofs of keys close in the tree share the
+    /// nodes above them, which are hashed once for the whole batch: the path of a proof is only
+    /// hashed up to the first node already computed by the proofs before it, and checked against
+    /// it, instead of all the way to the root.
+    pub fn batch_verify_by_hash<'a>(
+        expected_root_hash: HashValue,
+        proofs: impl IntoIterator<Item = (&'a SparseMerkleProof, HashValue, Option<HashValue>)>,
+    ) -> Result<()> {
+        // The hashes of the nodes computed so far, by their depth and the path to them, i.e. the
+        // bits of the key down to that depth. Since the batch fails at the first node that doesn't
+        // match, the path from each of them to the root is verified by the time it is looked up.
+        let mut verified_nodes = HashMap::new();
+        verified_nodes.insert((0, HashValue::zero()), expected_root_hash);
+
+        for (index, (proof, element_key, element_hash)) in proofs.into_iter().enumerate() {
+            proof
+                .verify_leaf(element_key, element_hash)
+                .with_context(|| format!("Proof {} of the batch is invalid.", index))?;
+
+            let mut depth = proof.siblings.len();
+            let mut hash = proof.leaf_hash();
+            let mut siblings = proof.siblings.iter();
+            loop {
+                match verified_nodes.entry((depth, key_prefix(element_key, depth))) {
+                    Entry::Occupied(entry) => {
+                        ensure!(
+                            *entry.get() == hash,
+                            "Proof {} of the batch is invalid. Hash at depth {}: {:x}. \
+                             Expected hash: {:x}.",
+                            index,
+                            depth,
+                            hash,
+                            entry.get(),
+                        );
+                        break;
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(hash);
+                    }
+                }
+                let sibling_hash = siblings
+                    .next()
+                    .expect("The root is verified before any other node.");
+                depth -= 1;
+                hash = parent_hash(hash, *sibling_hash, element_key.bit(depth));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies many proofs against the same root hash, each of an element given as its key and
+    /// optional value as in `verify`. See `batch_verify_by_hash`.
+    pub fn batch_verify<'a, V: CryptoHash + 'a>(
+        expected_root_hash: HashValue,
+        proofs: impl IntoIterator<Item = (&'a SparseMerkleProof, HashValue, Option<&'a V>)>,
+    ) -> Result<()> {
+        Self::batch_verify_by_hash(
+            expected_root_hash,
+            proofs
+                .into_iter()
+                .map(|(proof, key, value)| (proof, key, value.map(|v| v.hash()))),
+        )
+    }
+
+    /// Verifies the leaf of the proof is the element's, or, if the element has no hash, that the
+    /// element would be in the subtree of the leaf if it existed.
+    fn verify_leaf(&self, element_key: HashValue, element_hash: Option<HashValue>) -> Result<()> {
         ensure!(
             self.siblings.len() <= HashValue::LENGTH_IN_BITS,
             "Sparse Merkle Tree proof has more than {} ({}) siblings.",
@@ -313,34 +409,34 @@ impl SparseMerkleProof {
             }
         }
 
-        let current_hash = self
-            .leaf
-            .map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash());
-        let actual_root_hash = self
-            .siblings
-            .iter()
-            .zip(
-                element_key
-                    .iter_bits()
-                    .rev()
-                    .skip(HashValue::LENGTH_IN_BITS - self.siblings.len()),
-            )
-            .fold(current_hash, |hash, (sibling_hash, bit)| {
-                if bit {
-                    SparseMerkleInternalNode::new(*sibling_hash, hash).hash()
-                } else {
-                    SparseMerkleInternalNode::new(hash, *sibling_hash).hash()
-                }
-            });
-        ensure!(
-            actual_root_hash == expected_root_hash,
-            "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
-            actual_root_hash,
-            expected_root_hash,
-        );
-
         Ok(())
     }
+
+    /// The hash of the node the siblings of the proof are siblings of the path from.
+    fn leaf_hash(&self) -> HashValue {
+        self.leaf
+            .map_or(*SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| leaf.hash())
+    }
+}
+
+/// The hash of the parent of a node and its sibling, given whether the node is the right child.
+fn parent_hash(hash: HashValue, sibling_hash: HashValue, is_right_child: bool) -> HashValue {
+    if is_right_child {
+        SparseMerkleInternalNode::new(sibling_hash, hash).hash()
+    } else {
+        SparseMerkleInternalNode::new(hash, sibling_hash).hash()
+    }
+}
+
+/// The first `depth` bits of the key, followed by zeros.
+fn key_prefix(key: HashValue, depth: usize) -> HashValue {
+    let mut bytes = *key.as_ref();
+    let (full_bytes, extra_bits) = (depth / 8, depth % 8);
+    if full_bytes < HashValue::LENGTH {
+        bytes[full_bytes] &= !(0xff >> extra_bits);
+        bytes[full_bytes + 1..].fill(0);
+    }
+    HashValue::new(bytes)
 }
 
 /// An in-memory accumulator for storing a summary of the core transaction info
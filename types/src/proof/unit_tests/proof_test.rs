@@ -265,6 +265,96 @@ fn test_verify_three_element_sparse_merkle() {
     }
 }
 
+#[test]
+fn test_batch_verify_sparse_merkle() {
+    //            root
+    //           /    \
+    //          a      default
+    //         / \
+    //     key1   b
+    //           / \
+    //       key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+    let non_existing_key1 = b"abc".test_only_hash();
+    let non_existing_key2 = b"def".test_only_hash();
+
+    let blob1 = StateValue::from(b"1".to_vec());
+    let blob2 = StateValue::from(b"2".to_vec());
+    let blob3 = StateValue::from(b"3".to_vec());
+
+    let leaf1 = SparseMerkleLeafNode::new(key1, blob1.hash());
+    let leaf2 = SparseMerkleLeafNode::new(key2, blob2.hash());
+    let leaf3 = SparseMerkleLeafNode::new(key3, blob3.hash());
+    let internal_b_hash = SparseMerkleInternalNode::new(leaf2.hash(), leaf3.hash()).hash();
+    let internal_a_hash = SparseMerkleInternalNode::new(leaf1.hash(), internal_b_hash).hash();
+    let root_hash =
+        SparseMerkleInternalNode::new(internal_a_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let proof1 = SparseMerkleProof::new(
+        Some(leaf1),
+        vec![internal_b_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH],
+    );
+    let proof2 = SparseMerkleProof::new(
+        Some(leaf2),
+        vec![leaf3.hash(), leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+    );
+    let proof3 = SparseMerkleProof::new(
+        Some(leaf3),
+        vec![leaf2.hash(), leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+    );
+    let default_proof = SparseMerkleProof::new(None, vec![internal_a_hash]);
+
+    // Inclusion and non-inclusion proofs, some of the same node, verify together.
+    assert!(SparseMerkleProof::batch_verify(
+        root_hash,
+        vec![
+            (&proof2, key2, Some(&blob2)),
+            (&proof3, key3, Some(&blob3)),
+            (&proof1, key1, Some(&blob1)),
+            (&proof1, non_existing_key1, None),
+            (&default_proof, non_existing_key2, None),
+        ],
+    )
+    .is_ok());
+    assert!(SparseMerkleProof::batch_verify::<StateValue>(root_hash, vec![]).is_ok());
+
+    // Any invalid proof fails the batch.
+    assert!(SparseMerkleProof::batch_verify(
+        root_hash,
+        vec![(&proof2, key2, Some(&blob2)), (&proof3, key3, Some(&blob2))],
+    )
+    .is_err());
+    assert!(SparseMerkleProof::batch_verify::<StateValue>(
+        root_hash,
+        vec![
+            (&proof1, key1, Some(&blob1)),
+            (&default_proof, non_existing_key1, None)
+        ],
+    )
+    .is_err());
+    assert!(
+        SparseMerkleProof::batch_verify(internal_a_hash, vec![(&proof1, key1, Some(&blob1))],)
+            .is_err()
+    );
+
+    // A proof whose siblings are wrong fails even where its path joins the ones verified before it,
+    // short of the root.
+    let wrong_proof3 = SparseMerkleProof::new(
+        Some(leaf3),
+        vec![leaf1.hash(), leaf1.hash(), *SPARSE_MERKLE_PLACEHOLDER_HASH],
+    );
+    assert!(SparseMerkleProof::batch_verify(
+        root_hash,
+        vec![
+            (&proof2, key2, Some(&blob2)),
+            (&wrong_proof3, key3, Some(&blob3)),
+        ],
+    )
+    .is_err());
+}
+
 #[test]
 fn test_verify_transaction() {
     //            root
@@ -2,6 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{account_address::AccountAddress, event::EventHandle};
+use anyhow::Result;
+use move_deps::move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -27,3 +33,42 @@ pub struct StakePool {
     withdraw_stake_events: EventHandle,
     leave_validator_set_events: EventHandle,
 }
+
+impl StakePool {
+    pub fn distribute_rewards_events(&self) -> &EventHandle {
+        &self.distribute_rewards_events
+    }
+}
+
+impl MoveStructType for StakePool {
+    const MODULE_NAME: &'static IdentStr = ident_str!("stake");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("StakePool");
+}
+
+impl MoveResource for StakePool {}
+
+/// The event emitted when the rewards of an epoch are added to a stake pool.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DistributeRewardsEvent {
+    pool_address: AccountAddress,
+    rewards_amount: u64,
+}
+
+impl DistributeRewardsEvent {
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        bcs::from_bytes(bytes).map_err(Into::into)
+    }
+
+    pub fn pool_address(&self) -> AccountAddress {
+        self.pool_address
+    }
+
+    pub fn rewards_amount(&self) -> u64 {
+        self.rewards_amount
+    }
+}
+
+impl MoveStructType for DistributeRewardsEvent {
+    const MODULE_NAME: &'static IdentStr = ident_str!("stake");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("DistributeRewardsEvent");
+}
@@ -3,13 +3,16 @@
 
 use crate::{
     account_address::AccountAddress,
-    transaction::{RawTransaction, RawTransactionWithData},
+    transaction::{
+        authenticator_registry::AUTHENTICATOR_REGISTRY, RawTransaction, RawTransactionWithData,
+    },
 };
 use anyhow::{ensure, Error, Result};
 use aptos_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    signing_message,
     traits::Signature,
     CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
@@ -104,14 +107,7 @@ impl TransactionAuthenticator {
             return Err(Error::new(AuthenticationError::MaxSignaturesExceeded));
         }
         match self {
-            Self::Ed25519 {
-                public_key,
-                signature,
-            } => signature.verify(raw_txn, public_key),
-            Self::MultiEd25519 {
-                public_key,
-                signature,
-            } => signature.verify(raw_txn, public_key),
+            Self::Ed25519 { .. } | Self::MultiEd25519 { .. } => self.sender().verify(raw_txn),
             Self::MultiAgent {
                 sender,
                 secondary_signer_addresses,
@@ -236,7 +232,7 @@ impl fmt::Display for TransactionAuthenticator {
 /// `AuthenticationKeyPreimage`).
 
 // TODO: in the future, can tie these to the AccountAuthenticator enum directly with https://github.com/rust-lang/rust/issues/60553
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(u8)]
 pub enum Scheme {
     Ed25519 = 0,
@@ -244,6 +240,16 @@ pub enum Scheme {
     // ... add more schemes here
 }
 
+impl Scheme {
+    /// The name of the scheme, as the API reports it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheme::Ed25519 => "ed25519",
+            Scheme::MultiEd25519 => "multi_ed25519",
+        }
+    }
+}
+
 impl fmt::Display for Scheme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let display = match self {
@@ -297,18 +303,10 @@ impl AccountAuthenticator {
         }
     }
 
-    /// Return Ok if the authenticator's public key matches its signature, Err otherwise
+    /// Return Ok if the authenticator's public key matches its signature, Err otherwise. The
+    /// signature is checked by the validator of its scheme in the `AUTHENTICATOR_REGISTRY`.
     pub fn verify<T: Serialize + CryptoHash>(&self, message: &T) -> Result<()> {
-        match self {
-            Self::Ed25519 {
-                public_key,
-                signature,
-            } => signature.verify(message, public_key),
-            Self::MultiEd25519 {
-                public_key,
-                signature,
-            } => signature.verify(message, public_key),
-        }
+        AUTHENTICATOR_REGISTRY.verify(self, &signing_message(message))
     }
 
     /// Return the raw bytes of `self.public_key`
@@ -0,0 +1,246 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The validators of the signatures of each authentication scheme, which transactions are
+//! verified with, looked up by scheme rather than matched on across crates.
+//!
+//! To support a new scheme, e.g. secp256k1 or WebAuthn, add its `AccountAuthenticator` variant and
+//! `Scheme`, implement `AuthenticatorValidator` for it, and register the validator in
+//! `AuthenticatorRegistry::new`, behind a feature gate until the scheme is enabled. The schemes
+//! registered are advertised by the index endpoint of the API.
+
+use crate::transaction::authenticator::{AccountAuthenticator, Scheme};
+use anyhow::{bail, format_err, Result};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    traits::Signature,
+};
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+
+/// The number of bits in a MultiEd25519 signature bitmap.
+const MULTI_ED25519_BITMAP_BITS: usize = 32;
+
+pub static AUTHENTICATOR_REGISTRY: Lazy<AuthenticatorRegistry> =
+    Lazy::new(AuthenticatorRegistry::new);
+
+/// Why the signature of an authenticator is invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignatureFailure {
+    /// The indices of the public keys of the authenticator whose signatures did not verify, which
+    /// is just the one key for single key schemes.
+    pub failed_public_keys: Vec<usize>,
+    pub reason: String,
+}
+
+/// Validates the signatures of the authenticators of one scheme.
+pub trait AuthenticatorValidator: Send + Sync {
+    fn scheme(&self) -> Scheme;
+
+    /// Returns Ok if the signature of the authenticator verifies against the signing message.
+    fn verify(&self, authenticator: &AccountAuthenticator, signing_message: &[u8]) -> Result<()>;
+
+    /// Returns why the signature of the authenticator is invalid, if it is. Unlike `verify`, the
+    /// signatures of all the public keys are checked, rather than stopping at the first failure.
+    fn diagnose(
+        &self,
+        authenticator: &AccountAuthenticator,
+        signing_message: &[u8],
+    ) -> Option<SignatureFailure> {
+        self.verify(authenticator, signing_message)
+            .err()
+            .map(|_| SignatureFailure {
+                failed_public_keys: vec![0],
+                reason: "signature does not verify against the signing message".to_string(),
+            })
+    }
+}
+
+/// The validators of the authentication schemes supported, by scheme.
+pub struct AuthenticatorRegistry {
+    validators: BTreeMap<Scheme, Box<dyn AuthenticatorValidator>>,
+}
+
+impl AuthenticatorRegistry {
+    fn new() -> Self {
+        let validators: Vec<Box<dyn AuthenticatorValidator>> =
+            vec![Box::new(Ed25519Validator), Box::new(MultiEd25519Validator)];
+        Self {
+            validators: validators
+                .into_iter()
+                .map(|validator| (validator.scheme(), validator))
+                .collect(),
+        }
+    }
+
+    /// Returns the validator of the scheme, or an error if the scheme is not supported.
+    pub fn validator(&self, scheme: Scheme) -> Result<&dyn AuthenticatorValidator> {
+        self.validators
+            .get(&scheme)
+            .map(|validator| validator.as_ref())
+            .ok_or_else(|| format_err!("Unsupported authentication scheme {}", scheme.name()))
+    }
+
+    /// Returns Ok if the signature of the authenticator verifies against the signing message.
+    pub fn verify(
+        &self,
+        authenticator: &AccountAuthenticator,
+        signing_message: &[u8],
+    ) -> Result<()> {
+        self.validator(authenticator.scheme())?
+            .verify(authenticator, signing_message)
+    }
+
+    /// Returns why the signature of the authenticator is invalid, if it is.
+    pub fn diagnose(
+        &self,
+        authenticator: &AccountAuthenticator,
+        signing_message: &[u8],
+    ) -> Option<SignatureFailure> {
+        match self.validator(authenticator.scheme()) {
+            Ok(validator) => validator.diagnose(authenticator, signing_message),
+            Err(err) => Some(SignatureFailure {
+                failed_public_keys: vec![],
+                reason: err.to_string(),
+            }),
+        }
+    }
+
+    /// The schemes supported, in the order of their identifiers.
+    pub fn schemes(&self) -> impl Iterator<Item = Scheme> + '_ {
+        self.validators.keys().copied()
+    }
+}
+
+struct Ed25519Validator;
+
+impl Ed25519Validator {
+    fn keys(
+        authenticator: &AccountAuthenticator,
+    ) -> Result<(&Ed25519PublicKey, &Ed25519Signature)> {
+        match authenticator {
+            AccountAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => Ok((public_key, signature)),
+            _ => bail!("Expected an {} authenticator", Scheme::Ed25519.name()),
+        }
+    }
+}
+
+impl AuthenticatorValidator for Ed25519Validator {
+    fn scheme(&self) -> Scheme {
+        Scheme::Ed25519
+    }
+
+    fn verify(&self, authenticator: &AccountAuthenticator, signing_message: &[u8]) -> Result<()> {
+        let (public_key, signature) = Self::keys(authenticator)?;
+        signature.verify_arbitrary_msg(signing_message, public_key)
+    }
+}
+
+struct MultiEd25519Validator;
+
+impl MultiEd25519Validator {
+    fn keys(
+        authenticator: &AccountAuthenticator,
+    ) -> Result<(&MultiEd25519PublicKey, &MultiEd25519Signature)> {
+        match authenticator {
+            AccountAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => Ok((public_key, signature)),
+            _ => bail!("Expected a {} authenticator", Scheme::MultiEd25519.name()),
+        }
+    }
+}
+
+impl AuthenticatorValidator for MultiEd25519Validator {
+    fn scheme(&self) -> Scheme {
+        Scheme::MultiEd25519
+    }
+
+    fn verify(&self, authenticator: &AccountAuthenticator, signing_message: &[u8]) -> Result<()> {
+        let (public_key, signature) = Self::keys(authenticator)?;
+        signature.verify_arbitrary_msg(signing_message, public_key)
+    }
+
+    /// Checks the bitmap and threshold of the signature, then the signature of every key the
+    /// bitmap selects.
+    fn diagnose(
+        &self,
+        authenticator: &AccountAuthenticator,
+        signing_message: &[u8],
+    ) -> Option<SignatureFailure> {
+        let (public_key, signature) = match Self::keys(authenticator) {
+            Ok(keys) => keys,
+            Err(err) => {
+                return Some(SignatureFailure {
+                    failed_public_keys: vec![],
+                    reason: err.to_string(),
+                })
+            }
+        };
+        let public_keys = public_key.public_keys();
+        let bitmap = signature.bitmap();
+        let signed_keys: Vec<usize> = (0..MULTI_ED25519_BITMAP_BITS)
+            .filter(|index| bitmap[index / 8] & (128 >> (index % 8)) != 0)
+            .collect();
+
+        let out_of_range: Vec<usize> = signed_keys
+            .iter()
+            .copied()
+            .filter(|index| *index >= public_keys.len())
+            .collect();
+        if !out_of_range.is_empty() {
+            return Some(SignatureFailure {
+                failed_public_keys: out_of_range,
+                reason: format!(
+                    "bitmap selects public keys that don't exist, there are only {} public keys",
+                    public_keys.len()
+                ),
+            });
+        }
+        if signed_keys.len() != signature.signatures().len() {
+            return Some(SignatureFailure {
+                failed_public_keys: vec![],
+                reason: format!(
+                    "bitmap selects {} public keys but there are {} signatures",
+                    signed_keys.len(),
+                    signature.signatures().len()
+                ),
+            });
+        }
+        if signed_keys.len() < *public_key.threshold() as usize {
+            return Some(SignatureFailure {
+                failed_public_keys: vec![],
+                reason: format!(
+                    "bitmap selects {} public keys, fewer than the threshold of {}",
+                    signed_keys.len(),
+                    public_key.threshold()
+                ),
+            });
+        }
+
+        let failed: Vec<usize> = signed_keys
+            .into_iter()
+            .zip(signature.signatures())
+            .filter(|(index, signature)| {
+                signature
+                    .verify_arbitrary_msg(signing_message, &public_keys[*index])
+                    .is_err()
+            })
+            .map(|(index, _)| index)
+            .collect();
+        if failed.is_empty() {
+            None
+        } else {
+            Some(SignatureFailure {
+                failed_public_keys: failed,
+                reason: "signatures of these public keys do not verify against the signing message"
+                    .to_string(),
+            })
+        }
+    }
+}
@@ -35,6 +35,7 @@ use std::{
 };
 
 pub mod authenticator;
+pub mod authenticator_registry;
 mod change_set;
 mod module;
 mod script;
@@ -5,13 +5,15 @@ use crate::{
     account_address::AccountAddress,
     chain_id::ChainId,
     transaction::{
+        authenticator::{AccountAuthenticator, Scheme},
+        authenticator_registry::AUTHENTICATOR_REGISTRY,
         AccountTransactionsWithProof, RawTransaction, Script, SignedTransaction, Transaction,
         TransactionInfo, TransactionListWithProof, TransactionPayload, TransactionWithProof,
     },
 };
 use aptos_crypto::{
     ed25519::{self, Ed25519PrivateKey, Ed25519Signature},
-    PrivateKey, Uniform,
+    PrivateKey, SigningKey, Uniform,
 };
 use bcs::test_helpers::assert_canonical_encode_decode;
 use proptest::prelude::*;
@@ -36,6 +38,33 @@ fn test_invalid_signature() {
         .expect_err("signature checking should fail");
 }
 
+#[test]
+fn test_authenticator_registry() {
+    assert_eq!(
+        AUTHENTICATOR_REGISTRY.schemes().collect::<Vec<_>>(),
+        vec![Scheme::Ed25519, Scheme::MultiEd25519]
+    );
+
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let message = b"message";
+    let authenticator = AccountAuthenticator::ed25519(
+        private_key.public_key(),
+        private_key.sign_arbitrary_message(message),
+    );
+    AUTHENTICATOR_REGISTRY
+        .verify(&authenticator, message)
+        .unwrap();
+    assert_eq!(
+        AUTHENTICATOR_REGISTRY.diagnose(&authenticator, message),
+        None
+    );
+
+    let failure = AUTHENTICATOR_REGISTRY
+        .diagnose(&authenticator, b"another message")
+        .expect("signature checking should fail");
+    assert_eq!(failure.failed_public_keys, vec![0]);
+}
+
 proptest! {
     #[test]
     fn test_sign_raw_transaction(raw_txn in any::<RawTransaction>(), keypair in ed25519::keypair_strategy()) {